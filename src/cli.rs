@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
@@ -16,10 +18,20 @@ pub struct Cli {
 
 #[derive(Args)]
 pub struct GlobalOpts {
-    /// Output as JSON
+    /// Output as JSON (shorthand for --output json)
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// Output format: table, json, yaml, csv, ndjson; some reports also
+    /// take markdown or html
+    #[arg(long, global = true, default_value = "table")]
+    pub output: String,
+
+    /// Print each JSON record through a template instead, e.g.
+    /// '{{.name}} {{.capacity.used_pct}}' (implies --output json)
+    #[arg(long, global = true, conflicts_with = "output")]
+    pub format: Option<String>,
+
     /// Suppress non-error output
     #[arg(long, global = true)]
     pub quiet: bool,
@@ -31,10 +43,17 @@ pub struct GlobalOpts {
     /// Request timeout in seconds
     #[arg(long, global = true, default_value = "30")]
     pub timeout: u64,
+
+    /// Progress display for long operations: auto (spinners on a terminal),
+    /// json (one event per line on stderr), none
+    #[arg(long, global = true, default_value = "auto")]
+    pub progress: String,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Set up a first profile step by step
+    Init,
     /// Manage connection profiles
     Profile {
         #[command(subcommand)]
@@ -54,11 +73,24 @@ pub enum Commands {
     Snapshot {
         #[command(subcommand)]
         command: SnapshotCommands,
+        /// Print each path argument as normalized and as the encoded file ref
+        /// sent to the API
+        #[arg(long, global = true)]
+        debug_path: bool,
     },
     /// Filesystem browsing commands
     Fs {
         #[command(subcommand)]
         command: FsCommands,
+        /// Print each path argument as normalized and as the encoded file ref
+        /// sent to the API
+        #[arg(long, global = true)]
+        debug_path: bool,
+    },
+    /// Directory quota management
+    Quota {
+        #[command(subcommand)]
+        command: QuotaCommands,
     },
     /// Fleet-wide operations
     Fleet {
@@ -75,20 +107,274 @@ pub enum Commands {
         #[command(subcommand)]
         command: HwCommands,
     },
+    /// Capacity planning
+    Capacity {
+        #[command(subcommand)]
+        command: CapacityCommands,
+    },
+    /// Capacity history charts
+    Analytics {
+        #[command(subcommand)]
+        command: AnalyticsCommands,
+    },
+    /// Fleet-wide reports
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+    /// Alert history recorded by `fleet status`, and acknowledgments
+    #[command(alias = "alerts")]
+    Alert {
+        #[command(subcommand)]
+        command: AlertCommands,
+    },
+    /// One cluster's status; with --detail, also per-node drives, protection,
+    /// tenants, top shares and exports, and replication
+    Status {
+        /// Profile to show (default: --profile or the default profile)
+        profile: Option<String>,
+        /// Add the sections the fleet view leaves out
+        #[arg(long)]
+        detail: bool,
+    },
+    /// Capacity, file count, IOPS, and alert trends recorded by `fleet
+    /// status` and `daemon`
+    History {
+        #[command(subcommand)]
+        command: HistoryCommands,
+    },
+    /// Collect fleet status on a schedule in the background: cache it,
+    /// record alert changes, and send notifications
+    Daemon {
+        /// Seconds between polls
+        #[arg(long, default_value = "60")]
+        interval: u64,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+        /// Re-fetch slow-changing data every N polls; other polls only
+        /// refresh activity, connections, NIC, and restriper status
+        #[arg(long, default_value = "10")]
+        full_refresh_every: u64,
+        /// Maximum concurrent API requests across all clusters (default: 16)
+        #[arg(long, default_value = "16")]
+        jobs: usize,
+        /// Wall-clock budget per cluster, e.g. 20s
+        #[arg(long, value_name = "DURATION")]
+        cluster_budget: Option<String>,
+        /// Also deliver each poll's JSON to a sink, as with `fleet status
+        /// --sink` (repeatable)
+        #[arg(long = "sink", value_name = "SINK")]
+        sinks: Vec<String>,
+        /// Poll once and exit (e.g. from cron)
+        #[arg(long, conflicts_with = "listen")]
+        once: bool,
+        /// Also serve the cached status over HTTP on this address, as
+        /// `qontrol serve` does (e.g. 127.0.0.1:9470)
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<String>,
+    },
+    /// Serve the cached fleet status over HTTP: GET /status,
+    /// /clusters/<profile>, /alerts, /healthz
+    Serve {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:9470")]
+        listen: String,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+        /// Mark clusters cached longer ago than this stale
+        #[arg(long, value_name = "DURATION", default_value = "5m")]
+        max_age: String,
+    },
+    /// Client connection history and idle-client reports
+    Connections {
+        #[command(subcommand)]
+        command: ConnectionsCommands,
+    },
+    /// Planned-work windows that downgrade a cluster's alerts to info
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommands,
+    },
+    /// Access token housekeeping
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+    /// Developer tooling for working on qontrol itself
+    #[command(hide = true)]
+    Dev {
+        #[command(subcommand)]
+        command: DevCommands,
+    },
+    /// Print a shell completion script
+    Completions {
+        /// Shell to complete for: bash or zsh
+        shell: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DevCommands {
+    /// Record test fixtures from the selected profile's cluster, with UUIDs
+    /// and hostnames replaced, into the layout tests/harness reads
+    RecordFixtures {
+        /// Fixture set: status, cdf, fs
+        #[arg(long)]
+        set: String,
+        /// Fixtures directory to write under (default: tests/fixtures)
+        #[arg(long, default_value = "tests/fixtures")]
+        out: PathBuf,
+        /// Directory name for this cluster's fixtures (default: the profile name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Overwrite fixtures already recorded under that name
+        #[arg(long)]
+        force: bool,
+        /// Also replace IPs, usernames, and share names with stable
+        /// pseudonyms, for fixtures from customer clusters
+        #[arg(long)]
+        sanitize: bool,
+    },
 }
 
 #[derive(Subcommand)]
+pub enum AuthCommands {
+    /// Access tokens stored in profiles
+    Tokens {
+        #[command(subcommand)]
+        command: AuthTokensCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuthTokensCommands {
+    /// List profiles whose access tokens have expired or expire soon
+    Expiring {
+        /// Look this far ahead, e.g. 30d, 2w (default: token_warn_days, 14d)
+        #[arg(long)]
+        within: Option<String>,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+        /// Also list tokens that are not expiring
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AlertCommands {
+    /// Show when alerts fired and resolved
+    History {
+        /// Only show alerts for this cluster (profile or cluster name)
+        #[arg(long)]
+        cluster: Option<String>,
+        /// Only show transitions newer than this, e.g. 24h, 7d
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Acknowledge a known issue: its alerts are shown apart and no longer
+    /// count toward `fleet status --check` until the ack expires
+    Ack {
+        /// Profile or cluster name, as shown in the alert
+        cluster: String,
+        /// Alert category, e.g. disk_wear (see `alert categories`)
+        category: String,
+        /// How long the ack lasts, e.g. 4h, 7d
+        #[arg(long, default_value = "24h")]
+        until: String,
+        /// Note recorded with the ack
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Remove an acknowledgment before it expires
+    Unack {
+        /// Profile or cluster name the ack was recorded for
+        cluster: String,
+        /// Alert category
+        category: String,
+    },
+    /// List acknowledgments that haven't expired
+    Acks,
+    /// List every alert category with its default severity, threshold, and
+    /// meaning
+    Categories,
+}
+
+#[derive(Subcommand)]
+pub enum HistoryCommands {
+    /// Show recorded samples of a metric, oldest first
+    Show {
+        /// Only show this cluster (profile or cluster name)
+        #[arg(long)]
+        cluster: Option<String>,
+        /// What to show: capacity, files, iops, or alerts
+        #[arg(long, default_value = "capacity")]
+        metric: String,
+        /// How far back to look, e.g. 24h, 30d
+        #[arg(long, default_value = "7d")]
+        since: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConnectionsCommands {
+    /// Record which clients are connected and which are active; run
+    /// periodically (e.g. from cron) to build the history `idle-report` reads
+    Record,
+    /// List connected clients that have shown no activity for a while
+    IdleReport {
+        /// How long a client must be connected without activity, e.g. 36h, 7d
+        #[arg(long, default_value = "7d")]
+        threshold: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MaintenanceCommands {
+    /// Start a maintenance window now
+    Start {
+        /// Profile name of the cluster under maintenance
+        #[arg(long)]
+        cluster: String,
+        /// How long the window lasts, e.g. 30m, 2h, 1d
+        #[arg(long)]
+        duration: String,
+        /// Note recorded with the window
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// End a maintenance window early
+    End {
+        /// Profile name of the cluster under maintenance
+        #[arg(long)]
+        cluster: String,
+    },
+    /// List recorded maintenance windows
+    #[command(alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // parsed once per run
 pub enum FleetCommands {
     /// Multi-cluster environment status
     #[command(alias = "st")]
     Status {
-        /// Continuously refresh the status view
+        /// Continuously refresh the status view. In a terminal, keys control
+        /// it: r refresh, p pause, 1-9 focus a cluster, 0 show all, q quit
         #[arg(long)]
         watch: bool,
         /// Refresh interval in seconds (used with --watch)
         #[arg(long, default_value = "2")]
         interval: u64,
-        /// Filter to specific profiles (repeatable)
+        /// With --watch, append each poll under a timestamp header instead
+        /// of clearing the screen, keeping every poll in scrollback
+        #[arg(long, requires = "watch")]
+        no_clear: bool,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
         #[arg(long = "cluster", short = 'c', num_args = 1)]
         profiles: Vec<String>,
         /// Skip cache reads and writes
@@ -97,12 +383,112 @@ pub enum FleetCommands {
         /// Show per-API-call timing breakdown (output to stderr)
         #[arg(long)]
         timing: bool,
+        /// Collection level: fast, standard, full (default: each profile's
+        /// collect.level, else standard)
+        #[arg(long)]
+        level: Option<String>,
+        /// Capacity projection model: linear, or smoothing (exponential
+        /// smoothing, which follows recent growth on bursty workloads)
+        /// (default: each profile's collect.projection_model, else linear)
+        #[arg(long, value_name = "MODEL")]
+        projection_model: Option<String>,
+        /// Days of history the capacity projection is fitted to: 7d, 30d,
+        /// 90d (default: each profile's collect.projection_window, else 30d)
+        #[arg(long, value_name = "WINDOW")]
+        projection_window: Option<String>,
+        /// With --watch, re-fetch slow-changing data (settings, nodes,
+        /// capacity, aggregates, hardware) every N polls; other polls only
+        /// refresh activity, connections, NIC, and restriper status
+        #[arg(long, default_value = "10")]
+        full_refresh_every: u64,
+        /// Show the 5 hottest paths and clients per cluster
+        #[arg(long)]
+        top_activity: bool,
+        /// Full-screen dashboard with per-cluster tabs and live refresh
+        /// (every --interval seconds)
+        #[arg(long)]
+        tui: bool,
+        /// Maximum concurrent API requests across all clusters (default: 16)
+        #[arg(long, default_value = "16")]
+        jobs: usize,
+        /// Wall-clock budget per cluster, e.g. 20s; once spent, the
+        /// cluster's remaining optional endpoints are skipped and its data
+        /// marked partial
+        #[arg(long, value_name = "DURATION")]
+        cluster_budget: Option<String>,
+        /// Also deliver each poll's JSON to file:PATH (appended, one line
+        /// per poll) or webhook:URL (POSTed), or record the poll time for
+        /// liveness monitoring in heartbeat:PATH (a Prometheus textfile)
+        /// (repeatable)
+        #[arg(long = "sink", value_name = "SINK")]
+        sinks: Vec<String>,
+        /// Order clusters by: name, capacity, latency, health, score
+        #[arg(long)]
+        sort: Option<String>,
+        /// One line per cluster instead of full sections
+        #[arg(long)]
+        compact: bool,
+        /// Only show clusters that are unreachable, unhealthy, or alerting
+        #[arg(long)]
+        only_degraded: bool,
+        /// Start from a saved view (`[views.<name>]` in config); flags given
+        /// here override it
+        #[arg(long)]
+        view: Option<String>,
+        /// JSON on one line with null fields left out (implies --json)
+        #[arg(long)]
+        json_compact: bool,
+        /// Write the JSON status to this file instead of stdout, replacing
+        /// it on every poll (implies --json)
+        #[arg(long, value_name = "PATH")]
+        json_out: Option<PathBuf>,
+        /// Gzip the --json-out file
+        #[arg(long, requires = "json_out")]
+        gzip: bool,
+        /// Exit with a monitoring-plugin status: 0 healthy, 1 warnings,
+        /// 2 critical alerts, 3 only unreachable clusters
+        #[arg(long, conflicts_with_all = ["watch", "tui"])]
+        check: bool,
+        /// Send a test payload to each `[notify]` webhook and exit
+        #[arg(long, conflicts_with_all = ["watch", "tui", "check"])]
+        notify_test: bool,
+        /// Show the status last collected by `qontrol daemon` (or an earlier
+        /// poll) without contacting any cluster
+        #[arg(long, conflicts_with_all = ["tui", "no_cache", "notify_test"])]
+        cached: bool,
+        /// With --cached, mark clusters cached longer ago than this stale
+        #[arg(
+            long,
+            value_name = "DURATION",
+            default_value = "5m",
+            requires = "cached"
+        )]
+        max_age: String,
     },
     /// Fleet-wide hardware health checks
     Hw {
         #[command(subcommand)]
         command: FleetHwCommands,
     },
+    /// Fleet-wide snapshot policy checks
+    SnapshotPolicy {
+        #[command(subcommand)]
+        command: FleetSnapshotPolicyCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FleetSnapshotPolicyCommands {
+    /// Compare every cluster's snapshot retention against a baseline
+    Check {
+        /// Expected snapshots kept per schedule, e.g. daily=30,weekly=8
+        /// (schedules: hourly, daily, weekly, monthly)
+        #[arg(long)]
+        expect: String,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -118,7 +504,7 @@ pub enum FleetHwCommands {
 pub enum FleetHwPsuCommands {
     /// Check PSU health across all configured clusters
     Check {
-        /// Filter to specific profiles (repeatable)
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
         #[arg(long = "cluster", short = 'c', num_args = 1)]
         profiles: Vec<String>,
         /// Show per-node PSU details for all clusters
@@ -127,6 +513,76 @@ pub enum FleetHwPsuCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// Rank clusters by projected days until full
+    CapacityForecast {
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+    },
+    /// Render fleet status through a user-supplied template
+    Generate {
+        /// Template file (Jinja syntax; sees the `fleet status --json` document)
+        #[arg(long)]
+        template: PathBuf,
+        /// Write the report to a file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+    },
+    /// Per-team capacity usage and cost, for monthly showback
+    Chargeback {
+        /// What each line bills: quota (directory quotas) or toplevel-dir
+        #[arg(long, default_value = "quota")]
+        by: String,
+        /// Price per month, e.g. 0.02-per-GB-month or 20-per-TB-month
+        #[arg(long)]
+        rate: Option<String>,
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
+        #[arg(long = "cluster", short = 'c', num_args = 1)]
+        profiles: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CapacityCommands {
+    /// Recompute days-to-full as if the cluster had more capacity
+    WhatIf {
+        /// Profile of the cluster to size (default: --profile or the default profile)
+        #[arg(long)]
+        cluster: Option<String>,
+        /// Nodes to add, each sized like the cluster's current nodes
+        #[arg(long, required_unless_present = "add_usable")]
+        add_nodes: Option<u32>,
+        /// Model of the added nodes; the cluster must already have some
+        #[arg(long, requires = "add_nodes")]
+        model: Option<String>,
+        /// Usable capacity to add, e.g. 200TB or 50T
+        #[arg(long, conflicts_with = "add_nodes")]
+        add_usable: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AnalyticsCommands {
+    /// Daily capacity used over a window, with the projected fill date;
+    /// --output csv or json for the raw rows
+    Capacity {
+        /// Profile of the cluster (default: --profile or the default profile)
+        #[arg(long)]
+        cluster: Option<String>,
+        /// Days of history to show
+        #[arg(long, default_value = "90", value_parser = clap::value_parser!(u32).range(1..))]
+        days: u32,
+        /// Draw a chart instead of a table
+        #[arg(long)]
+        chart: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum HwCommands {
     /// PSU health checks
@@ -147,7 +603,8 @@ pub enum ProfileCommands {
     /// Add a new profile (interactive login if --token is omitted)
     Add {
         /// Profile name
-        name: String,
+        #[arg(required_unless_present = "from_file")]
+        name: Option<String>,
         /// Cluster hostname or IP
         #[arg(long)]
         host: Option<String>,
@@ -169,9 +626,22 @@ pub enum ProfileCommands {
         /// Password for non-interactive login (visible in process list; prefer interactive login)
         #[arg(long, hide = true)]
         password: Option<String>,
-        /// Access token expiry for non-interactive login: 6months, 1year, never (default: 1year)
-        #[arg(long, hide = true, default_value = "1year")]
-        expiry: String,
+        /// Access token expiry: 6months, 1year or never when logging in (default: 1year);
+        /// with --token, the date the token expires (YYYY-MM-DD)
+        #[arg(long)]
+        expiry: Option<String>,
+        /// PEM client certificate for clusters that require mutual TLS
+        #[arg(long, requires = "client_key")]
+        client_cert: Option<String>,
+        /// PKCS#8 PEM private key for --client-cert
+        #[arg(long, requires = "client_cert")]
+        client_key: Option<String>,
+        /// Add every cluster listed in a TOML, JSON or YAML inventory file
+        /// (YAML: indented mappings and lists of plain or quoted values; no
+        /// anchors, flow lists or multi-line strings); --token, --username
+        /// and --password then apply to each cluster without its own token
+        #[arg(long, conflicts_with_all = ["name", "host", "default", "client_cert"])]
+        from_file: Option<PathBuf>,
     },
     /// List all profiles
     List,
@@ -185,6 +655,12 @@ pub enum ProfileCommands {
         /// Profile name (uses default if omitted)
         name: Option<String>,
     },
+    /// Connect with a profile and report reachability, identity, roles,
+    /// and token expiry
+    Test {
+        /// Profile name (tests every profile if omitted)
+        name: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -198,6 +674,10 @@ pub enum ApiCommands {
         /// Request body as JSON string
         #[arg(long)]
         body: Option<String>,
+        /// Replace hostnames, IPs, usernames, and share names in the
+        /// response with stable pseudonyms, for sharing in bug reports
+        #[arg(long)]
+        sanitize: bool,
     },
 }
 
@@ -205,12 +685,138 @@ pub enum ApiCommands {
 pub enum ClusterCommands {
     /// Show cluster information
     Info,
+    /// Data protection configuration
+    Protection {
+        #[command(subcommand)]
+        command: ClusterProtectionCommands,
+    },
+    /// Show chassis hardware per node: PSUs and any fan or temperature sensors reported
+    Hardware {
+        /// Only show this node
+        #[arg(long)]
+        node: Option<u64>,
+    },
+    /// Node maintenance actions
+    Node {
+        #[command(subcommand)]
+        command: ClusterNodeCommands,
+    },
+    /// TLS settings of the cluster's HTTPS and REST listener
+    Tls {
+        #[command(subcommand)]
+        command: ClusterTlsCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ClusterTlsCommands {
+    /// Show the TLS 1.2 and TLS 1.3 cipher suites and weak-TLS findings
+    Show,
+    /// Replace cipher suites and/or install a certificate
+    Set {
+        /// Comma-separated TLS 1.2 cipher suites to allow, replacing the current list
+        #[arg(long, value_name = "SUITES")]
+        tls12_ciphers: Option<String>,
+        /// Comma-separated TLS 1.3 cipher suites to allow, replacing the current list
+        #[arg(long, value_name = "SUITES")]
+        tls13_ciphers: Option<String>,
+        /// PEM certificate (chain) file to install; needs --private-key
+        #[arg(long, value_name = "FILE", requires = "private_key")]
+        certificate: Option<String>,
+        /// PEM private key file for --certificate
+        #[arg(long, value_name = "FILE", requires = "certificate")]
+        private_key: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Cluster name, for profiles whose confirm policy is typed-name
+        #[arg(long, value_name = "CLUSTER")]
+        confirm_name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ClusterNodeCommands {
+    /// Reboot a single node, then wait for it to rejoin
+    Reboot {
+        /// Node ID
+        id: u64,
+        #[command(flatten)]
+        action: NodeActionArgs,
+    },
+    /// Restart the Qumulo Core services on a node, then wait for it to rejoin
+    RestartServices {
+        /// Node ID
+        id: u64,
+        #[command(flatten)]
+        action: NodeActionArgs,
+    },
+}
+
+/// Confirmation and post-action health check options shared by node actions
+#[derive(Args)]
+pub struct NodeActionArgs {
+    /// Skip the confirmation prompt
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+    /// Cluster name, for profiles whose confirm policy is typed-name
+    #[arg(long, value_name = "CLUSTER")]
+    pub confirm_name: Option<String>,
+    /// Return once the action is accepted instead of waiting for the node to come back
+    #[arg(long)]
+    pub no_wait: bool,
+    /// Give up waiting for the node after this many seconds (default: 900)
+    #[arg(long, default_value = "900")]
+    pub wait_timeout: u64,
+    /// Seconds between health polls (default: 10)
+    #[arg(long, default_value = "10")]
+    pub interval: u64,
+}
+
+/// Schedule flags for snapshot policies
+#[derive(Args)]
+pub struct SnapshotScheduleArgs {
+    /// Take a snapshot every day at this time (HH:MM)
+    #[arg(long, value_name = "HH:MM")]
+    pub every_day_at: Option<String>,
+    /// Only on these days, e.g. MON,THU (default: every day)
+    #[arg(long, value_name = "DAYS")]
+    pub on_days: Option<String>,
+    /// How long snapshots are kept: e.g. 7d, 4w, 6months, or never
+    #[arg(long)]
+    pub keep_for: Option<String>,
+    /// Time zone for the schedule (default: UTC)
+    #[arg(long)]
+    pub timezone: Option<String>,
+}
+
+/// Snapshot selection flags for `snapshot list` and bulk `snapshot delete`
+#[derive(Args)]
+pub struct SnapshotFilterArgs {
+    /// Only snapshots whose name matches this glob, e.g. 'adhoc-*'
+    #[arg(long, value_name = "GLOB")]
+    pub name_glob: Option<String>,
+    /// Only snapshots taken more than this long ago, e.g. 30d
+    #[arg(long, value_name = "DURATION")]
+    pub older_than: Option<String>,
+    /// Only snapshots created by a policy or manually: policy, manual
+    #[arg(long)]
+    pub created_by: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ClusterProtectionCommands {
+    /// Show stripe layout, protection type, failure tolerance, and fault domains
+    Show,
 }
 
 #[derive(Subcommand)]
 pub enum SnapshotCommands {
     /// List all snapshots with capacity usage
-    List,
+    List {
+        #[command(flatten)]
+        filter: SnapshotFilterArgs,
+    },
     /// Show details for a specific snapshot
     Show {
         /// Snapshot ID
@@ -237,6 +843,100 @@ pub enum SnapshotCommands {
         /// Older snapshot ID
         older: u64,
     },
+    /// Estimate how much snapshot space is held by changes under a path
+    Attribution {
+        /// Directory to attribute snapshot space to
+        path: String,
+        /// Changes to sample from each snapshot's diff
+        #[arg(long, default_value = "1000")]
+        sample: usize,
+    },
+    /// Take a snapshot of a directory
+    Create {
+        /// Directory to snapshot
+        path: String,
+        /// Name suffix for the snapshot (the cluster prefixes the ID)
+        #[arg(long)]
+        name: Option<String>,
+        /// When the snapshot expires: a lifetime (e.g. 7d, 12h), an RFC 3339 time, or never
+        #[arg(long)]
+        expiration: Option<String>,
+    },
+    /// Create a snapshot policy
+    PolicyCreate {
+        /// Policy name
+        #[arg(long)]
+        name: String,
+        /// Directory the policy snapshots
+        #[arg(long)]
+        path: String,
+        #[command(flatten)]
+        schedule: SnapshotScheduleArgs,
+        /// Create the policy disabled
+        #[arg(long)]
+        disabled: bool,
+    },
+    /// Change a snapshot policy's name, schedule, or enabled state
+    PolicyUpdate {
+        /// Policy ID
+        id: u64,
+        /// New policy name
+        #[arg(long)]
+        name: Option<String>,
+        #[command(flatten)]
+        schedule: SnapshotScheduleArgs,
+        /// Enable the policy
+        #[arg(long, conflicts_with = "disable")]
+        enable: bool,
+        /// Disable the policy
+        #[arg(long)]
+        disable: bool,
+    },
+    /// Delete a snapshot policy (its snapshots are kept)
+    PolicyDelete {
+        /// Policy ID
+        id: u64,
+        /// Skip the confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Cluster name, for profiles whose confirm policy is typed-name
+        #[arg(long, value_name = "CLUSTER")]
+        confirm_name: Option<String>,
+    },
+    /// Delete a snapshot, or every snapshot matching the filters after a
+    /// preview
+    Delete {
+        /// Snapshot ID
+        #[arg(conflicts_with_all = ["name_glob", "older_than", "created_by", "dry_run"])]
+        id: Option<u64>,
+        #[command(flatten)]
+        filter: SnapshotFilterArgs,
+        /// Preview the snapshots the filters match without deleting them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Cluster name, for profiles whose confirm policy is typed-name
+        #[arg(long, value_name = "CLUSTER")]
+        confirm_name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum QuotaCommands {
+    /// Ensure every directory matching a glob has a quota with the given limit
+    ApplyTemplate {
+        /// Directories to cover, e.g. '/projects/*' ('**' crosses levels)
+        #[arg(long = "path-glob")]
+        path_glob: String,
+        /// Quota limit, e.g. 10T (K/M/G/T/P are powers of 1024; KB/MB/... of 1000)
+        #[arg(long)]
+        limit: String,
+        /// Show what would be created or updated without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -249,12 +949,31 @@ pub enum FsCommands {
         /// Show detailed information (permissions, size, timestamps)
         #[arg(short, long)]
         long: bool,
-        /// Sort by field: name, size, type (default: name)
+        /// Sort by comma-separated keys: name, size, type, mtime, ctime, atime,
+        /// crtime (e.g. 'size,name'; sizes largest and times newest first)
         #[arg(short, long, default_value = "name")]
         sort: String,
+        /// Reverse the sort order
+        #[arg(short, long)]
+        reverse: bool,
         /// Maximum number of entries to return (stop after N entries)
         #[arg(long)]
         limit: Option<u32>,
+        /// Descend into subdirectories, printing each entry with its full path
+        #[arg(short = 'R', long)]
+        recursive: bool,
+        /// Maximum depth to descend with --recursive (default: 3)
+        #[arg(short = 'd', long, default_value = "3", requires = "recursive")]
+        max_depth: u32,
+        /// Descend into symlinks to directories with --recursive
+        #[arg(short = 'L', long, overrides_with = "no_follow")]
+        follow: bool,
+        /// Do not descend into symlinks (default)
+        #[arg(long, overrides_with = "follow")]
+        no_follow: bool,
+        /// Show owners as auth IDs instead of resolving them to names
+        #[arg(short = 'n', long)]
+        numeric_ids: bool,
     },
     /// Show recursive directory tree
     Tree {
@@ -264,11 +983,175 @@ pub enum FsCommands {
         /// Maximum depth to recurse (default: 3)
         #[arg(short = 'd', long, default_value = "3")]
         max_depth: u32,
+        /// Descend into symlinks to directories
+        #[arg(short = 'L', long, overrides_with = "no_follow")]
+        follow: bool,
+        /// Do not descend into symlinks (default)
+        #[arg(long, overrides_with = "follow")]
+        no_follow: bool,
     },
     /// Show detailed file/directory attributes
     Stat {
         /// Path to inspect
         path: String,
+        /// Also list named streams
+        #[arg(long)]
+        streams: bool,
+    },
+    /// List extended metadata (DOS flags, user metadata, named streams) or get one value
+    Xattr {
+        /// Path to inspect
+        path: String,
+        /// User metadata key to print (omit to list everything)
+        key: Option<String>,
+        /// User metadata type for KEY: generic, s3 (default: generic)
+        #[arg(long = "type", default_value = "generic")]
+        metadata_type: String,
+    },
+    /// Create many directories from a file listing one absolute path per line
+    Mkdirs {
+        /// File with one directory path per line ('-' for stdin)
+        #[arg(long = "from-file")]
+        from_file: String,
+        /// Octal mode for created directories (e.g. 0755)
+        #[arg(long)]
+        mode: Option<String>,
+        /// Owner for created directories: UID, SID, or local user name
+        #[arg(long)]
+        owner: Option<String>,
+        /// Maximum concurrent create requests (default: 8)
+        #[arg(long, default_value = "8")]
+        jobs: usize,
+    },
+    /// Download a file, or a directory tree with --recursive
+    Get {
+        /// Remote file or directory
+        remote: String,
+        /// Local destination
+        local: String,
+        /// Download a directory and everything below it
+        #[arg(long, short = 'r')]
+        recursive: bool,
+        /// Parallel download streams (default: 8); large files may also need a longer --timeout
+        #[arg(long, default_value = "8")]
+        jobs: usize,
+        /// Split files at least this big (e.g. 1G) into --jobs ranged reads,
+        /// to fill high-latency links a single stream can't. The API offers
+        /// no content checksum, so the integrity check is the file's ETag:
+        /// every range must carry the one it had before the transfer, or the
+        /// download fails
+        #[arg(long, value_name = "SIZE", default_value = "256M")]
+        multipart_over: String,
+    },
+    /// Search file contents under a path for a fixed string
+    Grep {
+        /// Text to search for (matched literally)
+        pattern: String,
+        /// File or directory to search
+        path: String,
+        /// Only search files whose name matches this glob (e.g. '*.log')
+        #[arg(long)]
+        name: Option<String>,
+        /// Skip files larger than this (e.g. 100M)
+        #[arg(long)]
+        max_size: Option<String>,
+        /// Match case-insensitively
+        #[arg(long, short = 'i')]
+        ignore_case: bool,
+        /// Files searched at once (default: 8)
+        #[arg(long, default_value = "8")]
+        jobs: usize,
+    },
+    /// Report sets of duplicate files under a path and the space they waste
+    DedupeReport {
+        /// Directory to scan
+        path: String,
+        /// Ignore files smaller than this (e.g. 1M; default: skip empty files)
+        #[arg(long)]
+        min_size: Option<String>,
+        /// Compare only size and the first 64 KiB instead of full contents
+        #[arg(long)]
+        quick: bool,
+        /// Files read at once (default: 8)
+        #[arg(long, default_value = "8")]
+        jobs: usize,
+    },
+    /// Show capacity used, file counts, and snapshot-held data per directory
+    Du {
+        /// Directory to report on
+        path: String,
+        /// Levels of subdirectories to list (default: 1; 0 shows only the path)
+        #[arg(short = 'd', long, default_value = "1")]
+        depth: usize,
+    },
+    /// Show the largest directories under a path, with their owners
+    Top {
+        /// Directory to look under (default: /)
+        #[arg(long, default_value = "/")]
+        path: String,
+        /// Rank by: capacity, files (default: capacity)
+        #[arg(long, default_value = "capacity")]
+        by: String,
+        /// Directories to show (default: 20)
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// Levels below the path to consider (default: 3)
+        #[arg(short = 'd', long, default_value = "3")]
+        max_depth: usize,
+        /// Show owners as auth IDs instead of resolving them to names
+        #[arg(short = 'n', long)]
+        numeric_ids: bool,
+    },
+    /// Poll a path until a condition is met (for pipelines waiting on data)
+    Wait {
+        /// Path to watch
+        path: String,
+        /// Condition to wait for: exists, absent, size-stable (default: exists)
+        #[arg(long = "for", default_value = "exists")]
+        condition: String,
+        /// Give up after this many seconds (default: 300; --timeout is the per-request timeout)
+        #[arg(long, default_value = "300")]
+        wait_timeout: u64,
+        /// Seconds between polls (default: 5)
+        #[arg(long, default_value = "5")]
+        interval: u64,
+    },
+    /// Record a tree's files and later check it for changes
+    Manifest {
+        #[command(subcommand)]
+        command: FsManifestCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FsManifestCommands {
+    /// Write a manifest of every file under a path (size, mtime, checksum)
+    Create {
+        /// Directory to record
+        path: String,
+        /// Manifest file to write (default: stdout)
+        #[arg(long)]
+        out: Option<String>,
+        /// Also record a checksum of each file's contents (reads every file)
+        #[arg(long)]
+        checksum: bool,
+        /// Files read at once with --checksum (default: 8)
+        #[arg(long, default_value = "8")]
+        jobs: usize,
+    },
+    /// Report files added, removed, or changed since a manifest was created
+    Verify {
+        /// Manifest file written by `fs manifest create`
+        manifest: String,
+        /// Verify this directory instead of the manifest's root (e.g. a copy)
+        #[arg(long)]
+        path: Option<String>,
+        /// Compare size and mtime only, even if the manifest has checksums
+        #[arg(long)]
+        quick: bool,
+        /// Files read at once for checksums (default: 8)
+        #[arg(long, default_value = "8")]
+        jobs: usize,
     },
 }
 
@@ -283,11 +1166,34 @@ pub enum CdfCommands {
         /// Filter to relationships involving a specific cluster
         #[arg(long)]
         cluster: Option<String>,
-        /// Filter to specific profiles (repeatable)
+        /// Filter to specific profiles (repeatable; globs like prod-* allowed)
         #[arg(long = "from", short = 'f', num_args = 1)]
         profiles: Vec<String>,
+        /// Only show relationships whose root path is at, under, or above
+        /// this path, or matches it as a glob (e.g. '/projects/*')
+        #[arg(long)]
+        path: Option<String>,
         /// Only show relationships with problems (disabled, errored, or unhealthy)
         #[arg(long)]
         problems_only: bool,
+        /// Print only the summary header (counts, problems, worst RPO)
+        #[arg(long)]
+        summary_only: bool,
+        /// Show extra columns (spoke cache free space and eviction threshold)
+        #[arg(long)]
+        wide: bool,
+        /// Exit non-zero when the fabric contains clusters without a profile
+        #[arg(long)]
+        strict_unknown: bool,
+        /// Continuously refresh, e.g. to follow replication catch-up
+        #[arg(long)]
+        watch: bool,
+        /// Refresh interval in seconds (used with --watch)
+        #[arg(long, default_value = "10")]
+        interval: u64,
+        /// With --watch, append each refresh under a timestamp header
+        /// instead of clearing the screen
+        #[arg(long, requires = "watch")]
+        no_clear: bool,
     },
 }
@@ -0,0 +1,536 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::maintenance::parse_duration;
+use crate::commands::status::health;
+use crate::commands::status::types::{Alert, AlertSeverity, EnvironmentStatus};
+use crate::config::data_dir;
+use crate::output::{print_json, print_table};
+
+/// One line of the alert history log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertTransition {
+    /// RFC 3339 UTC time the transition was observed
+    pub timestamp: String,
+    pub transition: TransitionKind,
+    pub severity: AlertSeverity,
+    pub cluster: String,
+    pub category: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransitionKind {
+    Fired,
+    Resolved,
+}
+
+impl AlertTransition {
+    fn new(kind: TransitionKind, alert: &Alert, now: DateTime<Utc>) -> Self {
+        AlertTransition {
+            timestamp: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            transition: kind,
+            severity: alert.severity.clone(),
+            cluster: alert.cluster.clone(),
+            category: alert.category.clone(),
+            message: alert.message.clone(),
+        }
+    }
+
+    fn as_alert(&self) -> Alert {
+        Alert {
+            severity: self.severity.clone(),
+            cluster: self.cluster.clone(),
+            message: self.message.clone(),
+            category: self.category.clone(),
+        }
+    }
+}
+
+/// Path of the append-only alert history (one JSON transition per line).
+fn history_path() -> Result<PathBuf> {
+    Ok(data_dir()?.join("alert-history.jsonl"))
+}
+
+/// Compare a fresh status against the alerts still open in the history log
+/// and append a `fired` or `resolved` line for each change. Returns the
+/// transitions recorded.
+pub fn record_transitions(status: &EnvironmentStatus) -> Result<Vec<AlertTransition>> {
+    record_transitions_at(&history_path()?, status, Utc::now())
+}
+
+fn record_transitions_at(
+    path: &Path,
+    status: &EnvironmentStatus,
+    now: DateTime<Utc>,
+) -> Result<Vec<AlertTransition>> {
+    let history = read_history(path)?;
+    // Only clusters seen with fresh data can resolve their alerts; an
+    // unreachable or filtered-out cluster keeps whatever it had open.
+    let observed: Vec<&str> = status
+        .clusters
+        .iter()
+        .filter(|c| c.reachable && !c.stale)
+        .flat_map(|c| [c.profile.as_str(), c.name.as_str()])
+        .collect();
+    // An acknowledged alert is still firing
+    let current: Vec<Alert> = status
+        .alerts
+        .iter()
+        .chain(&status.acknowledged)
+        .cloned()
+        .collect();
+    let transitions = diff_alerts(&open_alerts(&history), &current, &observed, now);
+    if transitions.is_empty() {
+        return Ok(transitions);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    for t in &transitions {
+        writeln!(file, "{}", serde_json::to_string(t)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(transitions)
+}
+
+/// Read the history log, skipping lines that don't parse. A missing file is empty.
+fn read_history(path: &Path) -> Result<Vec<AlertTransition>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                tracing::warn!(error = %e, "skipping malformed alert history line");
+                None
+            }
+        })
+        .collect())
+}
+
+/// Identity of an alert across polls. Severity is not part of it: a change in
+/// severity is recorded as the old alert resolving and the new one firing.
+fn alert_key(alert: &Alert) -> (String, String, String) {
+    (
+        alert.cluster.clone(),
+        alert.category.clone(),
+        alert.message.clone(),
+    )
+}
+
+/// Alerts whose latest transition in the log is `fired`.
+fn open_alerts(history: &[AlertTransition]) -> Vec<Alert> {
+    let mut latest: BTreeMap<(String, String, String), &AlertTransition> = BTreeMap::new();
+    for t in history {
+        latest.insert(alert_key(&t.as_alert()), t);
+    }
+    latest
+        .into_values()
+        .filter(|t| t.transition == TransitionKind::Fired)
+        .map(AlertTransition::as_alert)
+        .collect()
+}
+
+fn diff_alerts(
+    open: &[Alert],
+    current: &[Alert],
+    observed: &[&str],
+    now: DateTime<Utc>,
+) -> Vec<AlertTransition> {
+    let mut transitions = Vec::new();
+    for old in open {
+        let still_open = current
+            .iter()
+            .any(|a| alert_key(a) == alert_key(old) && a.severity == old.severity);
+        if !still_open && observed.contains(&old.cluster.as_str()) {
+            transitions.push(AlertTransition::new(TransitionKind::Resolved, old, now));
+        }
+    }
+    for alert in current {
+        let already_open = open
+            .iter()
+            .any(|o| alert_key(o) == alert_key(alert) && o.severity == alert.severity);
+        if !already_open {
+            transitions.push(AlertTransition::new(TransitionKind::Fired, alert, now));
+        }
+    }
+    transitions
+}
+
+/// An acknowledged alert: `fleet status` shows it apart from the active
+/// alerts and leaves it out of `--check` until `until` passes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertAck {
+    /// Profile or cluster name, as shown in the alert
+    pub cluster: String,
+    pub category: String,
+    /// RFC 3339 UTC time the ack was recorded
+    pub acked_at: String,
+    /// RFC 3339 UTC time the ack expires
+    pub until: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl AlertAck {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.until).is_ok_and(|until| until > now)
+    }
+}
+
+/// Path of the acknowledgment list (a JSON array of acks).
+fn acks_path() -> Result<PathBuf> {
+    Ok(data_dir()?.join("alert-acks.json"))
+}
+
+/// Read the stored acks, expired ones included. A missing file is empty.
+fn read_acks(path: &Path) -> Result<Vec<AlertAck>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn write_acks(path: &Path, acks: &[AlertAck]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(acks)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// The `(cluster, category)` pairs acknowledged right now. An unreadable ack
+/// file is logged and treated as empty so it can't break a status poll.
+pub fn active_acks(now: DateTime<Utc>) -> Vec<(String, String)> {
+    match acks_path().and_then(|path| read_acks(&path)) {
+        Ok(acks) => acks
+            .into_iter()
+            .filter(|a| a.is_active(now))
+            .map(|a| (a.cluster, a.category))
+            .collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to read alert acknowledgments");
+            Vec::new()
+        }
+    }
+}
+
+/// Add an ack, replacing one for the same alert and dropping expired ones.
+fn upsert_ack(acks: &mut Vec<AlertAck>, ack: AlertAck, now: DateTime<Utc>) {
+    acks.retain(|a| a.is_active(now) && !(a.cluster == ack.cluster && a.category == ack.category));
+    acks.push(ack);
+}
+
+/// Acknowledge a cluster's alerts of one category for `until` (e.g. 24h).
+pub fn ack(
+    cluster: &str,
+    category: &str,
+    until: &str,
+    reason: Option<String>,
+    json_mode: bool,
+) -> Result<()> {
+    let length = parse_duration(until)?;
+    let now = Utc::now();
+    let ack = AlertAck {
+        cluster: cluster.to_string(),
+        category: category.to_string(),
+        acked_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        until: (now + length).to_rfc3339_opts(SecondsFormat::Secs, true),
+        reason,
+    };
+    let path = acks_path()?;
+    let mut acks = read_acks(&path)?;
+    upsert_ack(&mut acks, ack.clone(), now);
+    write_acks(&path, &acks)?;
+
+    if json_mode {
+        print_json(&ack)?;
+        return Ok(());
+    }
+    println!(
+        "Acknowledged {} alerts for '{}' until {} ({}).",
+        category, cluster, ack.until, until
+    );
+    // A typo in the category would otherwise silently acknowledge nothing
+    let open = open_alerts(&read_history(&history_path()?)?);
+    if !open
+        .iter()
+        .any(|a| a.cluster == cluster && a.category == category)
+    {
+        println!("No such alert is open right now; the ack applies if it fires.");
+    }
+    Ok(())
+}
+
+/// Remove the ack for a cluster's alerts of one category.
+pub fn unack(cluster: &str, category: &str, json_mode: bool) -> Result<()> {
+    let path = acks_path()?;
+    let mut acks = read_acks(&path)?;
+    let before = acks.len();
+    acks.retain(|a| !(a.cluster == cluster && a.category == category));
+    let removed = acks.len() < before;
+    if removed {
+        write_acks(&path, &acks)?;
+    }
+
+    if json_mode {
+        let result = serde_json::json!({
+            "cluster": cluster,
+            "category": category,
+            "removed": removed,
+        });
+        print_json(&result)?;
+    } else if removed {
+        println!("Removed the ack for {} alerts on '{}'.", category, cluster);
+    } else {
+        println!("No ack recorded for {} alerts on '{}'.", category, cluster);
+    }
+    Ok(())
+}
+
+/// List the acks that haven't expired yet.
+pub fn acks(json_mode: bool) -> Result<()> {
+    let now = Utc::now();
+    let acks: Vec<AlertAck> = read_acks(&acks_path()?)?
+        .into_iter()
+        .filter(|a| a.is_active(now))
+        .collect();
+
+    if json_mode {
+        print_json(&acks)?;
+    } else if acks.is_empty() {
+        println!("No alerts acknowledged.");
+    } else {
+        print_table(
+            &serde_json::to_value(&acks)?,
+            &["cluster", "category", "until", "reason"],
+        );
+    }
+    Ok(())
+}
+
+/// List every category `fleet status` can alert on, with its default
+/// severity and threshold.
+pub fn categories(json_mode: bool) -> Result<()> {
+    let categories = health::categories();
+    if json_mode {
+        print_json(&categories)?;
+        return Ok(());
+    }
+    print_table(
+        &serde_json::to_value(&categories)?,
+        &["category", "severity", "threshold", "meaning"],
+    );
+    Ok(())
+}
+
+/// Show recorded alert transitions, oldest first.
+pub fn history(cluster: Option<&str>, since: Option<&str>, json_mode: bool) -> Result<()> {
+    let cutoff = since
+        .map(|s| parse_duration(s).map(|d| Utc::now() - d))
+        .transpose()?;
+    let entries = filter_history(read_history(&history_path()?)?, cluster, cutoff);
+
+    if json_mode {
+        print_json(&entries)?;
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No alert history recorded.");
+        return Ok(());
+    }
+    let rows: Vec<Value> = entries
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "time": t.timestamp,
+                "event": t.transition,
+                "severity": t.severity,
+                "cluster": t.cluster,
+                "category": t.category,
+                "message": t.message,
+            })
+        })
+        .collect();
+    print_table(
+        &Value::Array(rows),
+        &[
+            "time", "event", "severity", "cluster", "category", "message",
+        ],
+    );
+    Ok(())
+}
+
+fn filter_history(
+    history: Vec<AlertTransition>,
+    cluster: Option<&str>,
+    cutoff: Option<DateTime<Utc>>,
+) -> Vec<AlertTransition> {
+    history
+        .into_iter()
+        .filter(|t| cluster.is_none_or(|c| t.cluster == c))
+        .filter(|t| {
+            cutoff.is_none_or(|cutoff| {
+                DateTime::parse_from_rfc3339(&t.timestamp).is_ok_and(|ts| ts >= cutoff)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(cluster: &str, category: &str, severity: AlertSeverity) -> Alert {
+        Alert {
+            severity,
+            cluster: cluster.to_string(),
+            message: format!("{} problem", category),
+            category: category.to_string(),
+        }
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_diff_alerts_fires_and_resolves() {
+        let now = at("2026-03-01T10:00:00Z");
+        let open = vec![
+            alert("music", "disk_unhealthy", AlertSeverity::Warning),
+            alert("music", "capacity", AlertSeverity::Warning),
+        ];
+        let current = vec![
+            alert("music", "disk_unhealthy", AlertSeverity::Warning),
+            alert("music", "node_offline", AlertSeverity::Critical),
+        ];
+        let transitions = diff_alerts(&open, &current, &["music"], now);
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].transition, TransitionKind::Resolved);
+        assert_eq!(transitions[0].category, "capacity");
+        assert_eq!(transitions[1].transition, TransitionKind::Fired);
+        assert_eq!(transitions[1].category, "node_offline");
+        assert_eq!(transitions[1].timestamp, "2026-03-01T10:00:00Z");
+    }
+
+    #[test]
+    fn test_diff_alerts_unobserved_cluster_stays_open() {
+        let open = vec![alert("music", "capacity", AlertSeverity::Warning)];
+        let transitions = diff_alerts(&open, &[], &["other"], at("2026-03-01T10:00:00Z"));
+        assert!(transitions.is_empty());
+    }
+
+    #[test]
+    fn test_diff_alerts_severity_change() {
+        let open = vec![alert("music", "capacity", AlertSeverity::Warning)];
+        let current = vec![alert("music", "capacity", AlertSeverity::Critical)];
+        let transitions = diff_alerts(&open, &current, &["music"], at("2026-03-01T10:00:00Z"));
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].severity, AlertSeverity::Warning);
+        assert_eq!(transitions[0].transition, TransitionKind::Resolved);
+        assert_eq!(transitions[1].severity, AlertSeverity::Critical);
+    }
+
+    #[test]
+    fn test_open_alerts_replays_latest_transition() {
+        let a = alert("music", "capacity", AlertSeverity::Warning);
+        let b = alert("music", "node_offline", AlertSeverity::Critical);
+        let t0 = at("2026-03-01T10:00:00Z");
+        let history = vec![
+            AlertTransition::new(TransitionKind::Fired, &a, t0),
+            AlertTransition::new(TransitionKind::Fired, &b, t0),
+            AlertTransition::new(TransitionKind::Resolved, &a, t0),
+        ];
+        let open = open_alerts(&history);
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].category, "node_offline");
+    }
+
+    fn make_ack(cluster: &str, category: &str, until: &str) -> AlertAck {
+        AlertAck {
+            cluster: cluster.to_string(),
+            category: category.to_string(),
+            acked_at: "2026-03-01T00:00:00Z".to_string(),
+            until: until.to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_upsert_ack_replaces_and_prunes() {
+        let now = at("2026-03-01T10:00:00Z");
+        let mut acks = vec![
+            make_ack("music", "capacity", "2026-03-01T12:00:00Z"),
+            make_ack("music", "disk_wear", "2026-03-01T09:00:00Z"),
+            make_ack("other", "capacity", "2026-03-02T00:00:00Z"),
+        ];
+        upsert_ack(
+            &mut acks,
+            make_ack("music", "capacity", "2026-03-03T00:00:00Z"),
+            now,
+        );
+        assert_eq!(acks.len(), 2);
+        assert_eq!(acks[0].cluster, "other");
+        assert_eq!(acks[1].until, "2026-03-03T00:00:00Z");
+    }
+
+    #[test]
+    fn test_acks_round_trip() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("alert-acks.json");
+        assert!(read_acks(&path).unwrap().is_empty());
+        let acks = vec![make_ack("music", "capacity", "2026-03-01T12:00:00Z")];
+        write_acks(&path, &acks).unwrap();
+        assert_eq!(read_acks(&path).unwrap(), acks);
+        assert!(acks[0].is_active(at("2026-03-01T11:59:59Z")));
+        assert!(!acks[0].is_active(at("2026-03-01T12:00:00Z")));
+    }
+
+    #[test]
+    fn test_filter_history_by_cluster_and_since() {
+        let history = vec![
+            AlertTransition::new(
+                TransitionKind::Fired,
+                &alert("music", "capacity", AlertSeverity::Warning),
+                at("2026-02-01T00:00:00Z"),
+            ),
+            AlertTransition::new(
+                TransitionKind::Fired,
+                &alert("music", "node_offline", AlertSeverity::Critical),
+                at("2026-03-01T00:00:00Z"),
+            ),
+            AlertTransition::new(
+                TransitionKind::Fired,
+                &alert("other", "capacity", AlertSeverity::Warning),
+                at("2026-03-01T00:00:00Z"),
+            ),
+        ];
+        let filtered = filter_history(history, Some("music"), Some(at("2026-02-15T00:00:00Z")));
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].category, "node_offline");
+    }
+}
@@ -0,0 +1,292 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::commands::status::capacity;
+use crate::commands::status::renderer::format_bytes;
+use crate::commands::status::types::{CapacityProjection, ProjectionConfidence};
+use crate::output::{emit, print_table, OutputFormat};
+
+/// Rows of the capacity chart
+const CHART_HEIGHT: usize = 12;
+
+/// Columns of the capacity chart; longer histories are bucketed to fit
+const MAX_CHART_WIDTH: usize = 72;
+
+/// Eighth-height blocks, empty to full
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One day of capacity history.
+#[derive(Debug, Clone, Serialize)]
+struct Point {
+    date: String,
+    used_bytes: u64,
+    total_bytes: u64,
+    used_pct: f64,
+}
+
+/// `analytics capacity` result.
+#[derive(Debug, Serialize)]
+struct CapacityReport {
+    profile: String,
+    days: u32,
+    points: Vec<Point>,
+    projection: Option<Projection>,
+}
+
+/// The regression from `compute_projection`, with the day it reaches full.
+#[derive(Debug, Serialize)]
+struct Projection {
+    growth_rate_bytes_per_day: f64,
+    days_until_full: Option<u64>,
+    fill_date: Option<String>,
+    confidence: ProjectionConfidence,
+}
+
+/// Capacity used per day over the last `days` days, as a table, a chart,
+/// or structured rows, with the projected fill date. The projection is the
+/// same regression `fleet status` runs, fitted over the whole window and
+/// measured from the newest day's usage.
+pub fn capacity_history(
+    client: &QumuloClient,
+    profile: &str,
+    days: u32,
+    chart: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let now = Utc::now();
+    let history = client.get_capacity_history(now.timestamp() - days as i64 * 86400)?;
+    let points = parse_points(&history);
+    let projection = points.last().and_then(|last| {
+        capacity::compute_projection(&history, last.used_bytes, last.total_bytes)
+            .map(|p| projection_at(p, now))
+    });
+    let report = CapacityReport {
+        profile: profile.to_string(),
+        days,
+        points,
+        projection,
+    };
+
+    if format.is_structured() {
+        emit(&serde_json::to_value(&report)?, "/points", format);
+        return Ok(());
+    }
+    if report.points.is_empty() {
+        println!(
+            "No capacity history for {} in the last {} days",
+            profile, days
+        );
+        return Ok(());
+    }
+
+    println!("Capacity used on {}, last {} days", profile, days);
+    if chart {
+        println!();
+        let fill_date = report
+            .projection
+            .as_ref()
+            .and_then(|p| p.fill_date.as_deref());
+        for line in render_chart(&report.points, CHART_HEIGHT, fill_date) {
+            println!("{}", line);
+        }
+    } else {
+        let rows: Vec<Value> = report
+            .points
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "date": p.date,
+                    "used": format_bytes(p.used_bytes),
+                    "total": format_bytes(p.total_bytes),
+                    "used_pct": format!("{:.1}%", p.used_pct),
+                })
+            })
+            .collect();
+        print_table(&Value::Array(rows), &["date", "used", "total", "used_pct"]);
+    }
+    println!();
+    println!("{}", describe_projection(report.projection.as_ref()));
+    Ok(())
+}
+
+/// Days of capacity history that report usage, oldest first.
+fn parse_points(history: &Value) -> Vec<Point> {
+    let parse = |v: &Value| match v {
+        Value::String(s) => s.parse::<u64>().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    };
+    history
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let used = parse(&entry["capacity_used"])?;
+            let total = parse(&entry["total_usable"]).unwrap_or(0);
+            let date = entry["period_start_time"]
+                .as_i64()
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            Some(Point {
+                date,
+                used_bytes: used,
+                total_bytes: total,
+                used_pct: if total > 0 {
+                    used as f64 / total as f64 * 100.0
+                } else {
+                    0.0
+                },
+            })
+        })
+        .collect()
+}
+
+fn projection_at(p: CapacityProjection, now: DateTime<Utc>) -> Projection {
+    Projection {
+        growth_rate_bytes_per_day: p.growth_rate_bytes_per_day,
+        fill_date: p.days_until_full.map(|d| {
+            (now + Duration::days(d.min(365 * 100) as i64))
+                .format("%Y-%m-%d")
+                .to_string()
+        }),
+        days_until_full: p.days_until_full,
+        confidence: p.confidence,
+    }
+}
+
+fn describe_projection(projection: Option<&Projection>) -> String {
+    let Some(p) = projection else {
+        return "Projected full: not projected (under 7 days of history, or usage isn't growing)"
+            .to_string();
+    };
+    let confidence = match p.confidence {
+        ProjectionConfidence::High => "high",
+        ProjectionConfidence::Low => "low",
+    };
+    format!(
+        "Projected full: {} (in {} days, +{}/day, {} confidence)",
+        p.fill_date.as_deref().unwrap_or("unknown"),
+        p.days_until_full
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| "?".to_string()),
+        format_bytes(p.growth_rate_bytes_per_day.round() as u64),
+        confidence
+    )
+}
+
+/// Draw used capacity as columns of eighth blocks, `height` rows tall, with
+/// byte labels on the left and the first and last dates below, followed by
+/// the projected fill date when there is one. The scale runs from the
+/// lowest to the highest day so growth on a nearly full cluster still shows.
+fn render_chart(points: &[Point], height: usize, fill_date: Option<&str>) -> Vec<String> {
+    let columns = bucket(points, MAX_CHART_WIDTH);
+    let lo = columns.iter().map(|p| p.used_bytes).min().unwrap_or(0);
+    let hi = columns.iter().map(|p| p.used_bytes).max().unwrap_or(0);
+    let span = (hi - lo).max(1) as f64;
+    // Eighths filled in each column; the lowest day still gets one
+    let levels: Vec<usize> = columns
+        .iter()
+        .map(|p| {
+            let fraction = (p.used_bytes - lo) as f64 / span;
+            ((fraction * (height * 8) as f64).round() as usize).max(1)
+        })
+        .collect();
+
+    let mut lines = Vec::with_capacity(height + 2);
+    for row in (0..height).rev() {
+        let label = if row == height - 1 {
+            format_bytes(hi)
+        } else if row == 0 {
+            format_bytes(lo)
+        } else {
+            String::new()
+        };
+        let bar: String = levels
+            .iter()
+            .map(|level| BLOCKS[level.saturating_sub(row * 8).min(8)])
+            .collect();
+        lines.push(format!("{:>10} │{}", label, bar.trim_end()));
+    }
+    lines.push(format!("{:>10} └{}", "", "─".repeat(columns.len())));
+    let first = &columns[0].date;
+    let last = &columns[columns.len() - 1].date;
+    let gap = columns
+        .len()
+        .saturating_sub(first.len() + last.len())
+        .max(1);
+    let mut axis = format!("{:>10}  {}{}{}", "", first, " ".repeat(gap), last);
+    if let Some(fill_date) = fill_date {
+        axis.push_str(&format!("  → full ~{}", fill_date));
+    }
+    lines.push(axis);
+    lines
+}
+
+/// At most `width` points, each the last day of an even slice of `points`
+fn bucket(points: &[Point], width: usize) -> Vec<Point> {
+    if points.len() <= width {
+        return points.to_vec();
+    }
+    (1..=width)
+        .map(|i| points[i * points.len() / width - 1].clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(day: u32, used: u64) -> Point {
+        Point {
+            date: format!("2026-01-{:02}", day),
+            used_bytes: used,
+            total_bytes: 100,
+            used_pct: used as f64,
+        }
+    }
+
+    #[test]
+    fn test_parse_points() {
+        let history = serde_json::json!([
+            {"capacity_used": "40", "total_usable": "100", "period_start_time": 1768089600},
+            {"capacity_used": null, "total_usable": "100", "period_start_time": 1768176000},
+            {"capacity_used": 50, "total_usable": "100", "period_start_time": 1768262400},
+        ]);
+        let points = parse_points(&history);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].date, "2026-01-11");
+        assert_eq!(points[1].used_bytes, 50);
+        assert_eq!(points[1].used_pct, 50.0);
+    }
+
+    #[test]
+    fn test_render_chart() {
+        let points: Vec<Point> = (1..=20).map(|d| point(d, d as u64 * 10)).collect();
+        let lines = render_chart(&points, 4, None);
+        assert_eq!(lines.len(), 6);
+        // Top row: only the highest day is full
+        assert!(lines[0].ends_with('█'), "{:?}", lines[0]);
+        assert!(lines[0].contains("200 B"));
+        // Bottom row: every day shows
+        let bottom = lines[3].split('│').nth(1).unwrap();
+        assert_eq!(bottom.chars().filter(|c| *c != ' ').count(), 20);
+        assert!(lines[5].contains("2026-01-01") && lines[5].ends_with("2026-01-20"));
+
+        let lines = render_chart(&points, 4, Some("2026-06-01"));
+        assert!(lines[5].ends_with("2026-01-20  → full ~2026-06-01"));
+    }
+
+    #[test]
+    fn test_bucket() {
+        let points: Vec<Point> = (1..=30).map(|d| point(d, d as u64)).collect();
+        let columns = bucket(&points, 10);
+        assert_eq!(columns.len(), 10);
+        assert_eq!(columns[0].used_bytes, 3);
+        assert_eq!(columns[9].used_bytes, 30);
+        assert_eq!(bucket(&points[..5], 10).len(), 5);
+    }
+}
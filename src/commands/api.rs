@@ -2,8 +2,15 @@ use anyhow::{Context, Result};
 use serde_json::Value;
 
 use crate::client::QumuloClient;
+use crate::output::print_json;
 
-pub fn raw(client: &QumuloClient, method: &str, path: &str, body: Option<&str>) -> Result<()> {
+pub fn raw(
+    client: &QumuloClient,
+    method: &str,
+    path: &str,
+    body: Option<&str>,
+    sanitize: bool,
+) -> Result<()> {
     let body_value: Option<Value> = match body {
         Some(b) => {
             let parsed: Value =
@@ -13,12 +20,12 @@ pub fn raw(client: &QumuloClient, method: &str, path: &str, body: Option<&str>)
         None => None,
     };
 
-    let result = client.request(method, path, body_value.as_ref())?;
+    let mut result = client.request(method, path, body_value.as_ref())?;
+    if sanitize {
+        crate::sanitize::sanitize(&mut result);
+    }
 
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
-    );
+    print_json(&result)?;
 
     Ok(())
 }
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+
+use crate::commands::maintenance::parse_duration;
+use crate::config::{Config, ProfileEntry, DEFAULT_TOKEN_WARN_DAYS};
+use crate::output::{print_json, print_table};
+
+/// Where a profile's access token stands relative to the warning window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenState {
+    Expired,
+    Expiring,
+    Valid,
+    /// No expiry recorded: a non-expiring token, or one added before
+    /// expiries were tracked
+    Unknown,
+}
+
+impl TokenState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TokenState::Expired => "expired",
+            TokenState::Expiring => "expiring",
+            TokenState::Valid => "valid",
+            TokenState::Unknown => "unknown",
+        }
+    }
+}
+
+fn token_state(entry: &ProfileEntry, now: DateTime<Utc>, window: Duration) -> TokenState {
+    match entry.token_expires_at() {
+        None => TokenState::Unknown,
+        Some(expires) if expires <= now => TokenState::Expired,
+        Some(expires) if expires <= now + window => TokenState::Expiring,
+        Some(_) => TokenState::Valid,
+    }
+}
+
+fn warn_window(config: &Config) -> Duration {
+    Duration::days(
+        config
+            .token_warn_days
+            .unwrap_or(DEFAULT_TOKEN_WARN_DAYS)
+            .into(),
+    )
+}
+
+/// Warn on stderr about each named profile whose token has expired or
+/// expires within `token_warn_days`. Profiles without a recorded expiry are
+/// skipped.
+pub fn warn_expiring(config: &Config, profiles: &[String]) {
+    let now = Utc::now();
+    let window = warn_window(config);
+    for name in profiles {
+        let Some(entry) = config.profiles.get(name) else {
+            continue;
+        };
+        let Some(expires) = entry.token_expires_at() else {
+            continue;
+        };
+        match token_state(entry, now, window) {
+            TokenState::Expired => eprintln!(
+                "warning: access token for profile '{}' expired on {}; replace it with `qontrol profile add {}`",
+                name,
+                expires.format("%Y-%m-%d"),
+                name
+            ),
+            TokenState::Expiring => eprintln!(
+                "warning: access token for profile '{}' expires {} ({})",
+                name,
+                describe_remaining(expires - now),
+                expires.format("%Y-%m-%d")
+            ),
+            TokenState::Valid | TokenState::Unknown => {}
+        }
+    }
+}
+
+/// List profiles whose tokens have expired or expire within `within`
+/// (default `token_warn_days`). Exits 1 if any are found, so the view can
+/// gate automation.
+pub fn expiring(
+    config: &Config,
+    profile_filters: &[String],
+    within: Option<&str>,
+    all: bool,
+    json_mode: bool,
+) -> Result<()> {
+    let window = match within {
+        Some(within) => parse_duration(within)?,
+        None => warn_window(config),
+    };
+    let profiles = config.select_profiles(profile_filters);
+    if profiles.is_empty() {
+        anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
+    }
+
+    let now = Utc::now();
+    let mut rows: Vec<(String, ProfileEntry, TokenState)> = profiles
+        .into_iter()
+        .map(|(name, entry)| {
+            let state = token_state(&entry, now, window);
+            (name, entry, state)
+        })
+        .filter(|(_, _, state)| all || matches!(state, TokenState::Expired | TokenState::Expiring))
+        .collect();
+    // Soonest first; tokens without an expiry last
+    rows.sort_by_key(|(name, entry, _)| {
+        (
+            entry.token_expires_at().is_none(),
+            entry.token_expires_at(),
+            name.to_string(),
+        )
+    });
+
+    let problems = rows
+        .iter()
+        .filter(|(_, _, state)| matches!(state, TokenState::Expired | TokenState::Expiring))
+        .count();
+    let entries: Vec<Value> = rows
+        .iter()
+        .map(|(name, entry, state)| {
+            let expires = entry.token_expires_at();
+            json!({
+                "profile": name,
+                "host": entry.host,
+                "state": state.as_str(),
+                "expires": entry.token_expires,
+                "remaining": expires.map(|e| describe_remaining(e - now)),
+            })
+        })
+        .collect();
+
+    if json_mode {
+        let result = json!({
+            "within_days": window.num_days(),
+            "tokens": entries,
+        });
+        print_json(&result)?;
+    } else if entries.is_empty() {
+        println!("No access tokens expire within {} days.", window.num_days());
+    } else {
+        print_table(
+            &Value::Array(entries),
+            &["profile", "host", "state", "expires", "remaining"],
+        );
+    }
+
+    if problems > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// "in 3 days", "in 5 hours", "3 days ago"
+pub(crate) fn describe_remaining(remaining: Duration) -> String {
+    let (amount, past) = if remaining < Duration::zero() {
+        (-remaining, true)
+    } else {
+        (remaining, false)
+    };
+    let text = match amount.num_days() {
+        0 => match amount.num_hours() {
+            1 => "1 hour".to_string(),
+            h => format!("{} hours", h),
+        },
+        1 => "1 day".to_string(),
+        d => format!("{} days", d),
+    };
+    if past {
+        format!("{} ago", text)
+    } else {
+        format!("in {}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(token_expires: Option<&str>) -> ProfileEntry {
+        ProfileEntry {
+            host: "10.0.0.1".to_string(),
+            port: 8000,
+            token: "tok".to_string(),
+            token_expires: token_expires.map(|s| s.to_string()),
+            token_id: None,
+            insecure: false,
+            cluster_uuid: None,
+            base_url: None,
+            base_path: None,
+            proxy: None,
+            ssh_tunnel: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            collect: Default::default(),
+            maintenance: None,
+        }
+    }
+
+    #[test]
+    fn test_token_state() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let window = Duration::days(14);
+        let state = |e: Option<&str>| token_state(&entry(e), now, window);
+
+        assert_eq!(state(Some("2026-02-28T00:00:00Z")), TokenState::Expired);
+        assert_eq!(state(Some("2026-03-10T00:00:00Z")), TokenState::Expiring);
+        assert_eq!(state(Some("2026-06-01T00:00:00Z")), TokenState::Valid);
+        assert_eq!(state(None), TokenState::Unknown);
+        assert_eq!(state(Some("garbage")), TokenState::Unknown);
+    }
+
+    #[test]
+    fn test_describe_remaining() {
+        assert_eq!(describe_remaining(Duration::days(3)), "in 3 days");
+        assert_eq!(describe_remaining(Duration::hours(5)), "in 5 hours");
+        assert_eq!(describe_remaining(Duration::hours(-25)), "1 day ago");
+    }
+}
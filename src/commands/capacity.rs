@@ -0,0 +1,208 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::commands::status::capacity;
+use crate::commands::status::collector::{fetch_capacity, Degraded};
+use crate::commands::status::detection::detect_cluster_type;
+use crate::commands::status::renderer::format_bytes;
+use crate::commands::status::types::{ClusterType, ProjectionConfidence};
+use crate::config::Projection;
+use crate::output::print_json;
+
+/// How much capacity a what-if adds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expansion {
+    /// This many more nodes, optionally of a given model
+    Nodes { count: u32, model: Option<String> },
+    /// This much more usable capacity, in bytes
+    Usable(u64),
+}
+
+/// `capacity what-if` result: today's projection next to the one with the
+/// added capacity.
+#[derive(Debug, Serialize)]
+struct WhatIf {
+    profile: String,
+    cluster: String,
+    used_bytes: u64,
+    total_bytes: u64,
+    added_bytes: u64,
+    new_total_bytes: u64,
+    used_pct: f64,
+    new_used_pct: f64,
+    growth_rate_bytes_per_day: Option<f64>,
+    confidence: Option<ProjectionConfidence>,
+    days_until_full: Option<u64>,
+    new_days_until_full: Option<u64>,
+}
+
+/// Recompute days-to-full as if the cluster had more usable capacity,
+/// using the profile's projection model and window, as `fleet status` and
+/// `report capacity-forecast` do.
+pub fn what_if(
+    client: &QumuloClient,
+    profile: &str,
+    projection: Projection,
+    expansion: &Expansion,
+    json_mode: bool,
+) -> Result<()> {
+    let settings = client.get_cluster_settings()?;
+    let nodes = client.get_cluster_nodes()?;
+    let nodes = nodes.as_array().map(|a| a.as_slice()).unwrap_or(&[]);
+    let cap = fetch_capacity(client, &Degraded::default());
+    if cap.total_bytes == 0 {
+        anyhow::bail!("could not read the cluster's capacity");
+    }
+    let added = added_bytes(expansion, nodes, cap.total_bytes)?;
+
+    let days = projection.window.days() as i64;
+    let history = client.get_capacity_history(chrono::Utc::now().timestamp() - days * 86400)?;
+    let project =
+        |total| capacity::compute_projection_with(&history, cap.used_bytes, total, projection);
+    let current = project(cap.total_bytes);
+    let expanded = project(cap.total_bytes + added);
+
+    let new_total = cap.total_bytes + added;
+    let result = WhatIf {
+        profile: profile.to_string(),
+        cluster: settings["cluster_name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        used_bytes: cap.used_bytes,
+        total_bytes: cap.total_bytes,
+        added_bytes: added,
+        new_total_bytes: new_total,
+        used_pct: cap.used_pct,
+        new_used_pct: cap.used_bytes as f64 / new_total as f64 * 100.0,
+        growth_rate_bytes_per_day: current.as_ref().map(|p| p.growth_rate_bytes_per_day),
+        confidence: current.as_ref().map(|p| p.confidence.clone()),
+        days_until_full: current.and_then(|p| p.days_until_full),
+        new_days_until_full: expanded.and_then(|p| p.days_until_full),
+    };
+
+    if json_mode {
+        print_json(&result)?;
+        return Ok(());
+    }
+    print_what_if(&result, expansion);
+    Ok(())
+}
+
+/// Bytes of usable capacity an expansion adds. New nodes are sized from
+/// the cluster's own: its usable capacity spread evenly over its nodes.
+fn added_bytes(expansion: &Expansion, nodes: &[Value], total_bytes: u64) -> Result<u64> {
+    let (count, model) = match expansion {
+        Expansion::Usable(bytes) => return Ok(*bytes),
+        Expansion::Nodes { count, model } => (*count, model.as_deref()),
+    };
+    if let ClusterType::CnqAws | ClusterType::AnqAzure = detect_cluster_type(nodes) {
+        anyhow::bail!(
+            "cloud clusters grow by raising their capacity clamp, not by adding nodes; use --add-usable"
+        );
+    }
+    if nodes.is_empty() {
+        anyhow::bail!("the cluster reports no nodes to size new ones from; use --add-usable");
+    }
+    if let Some(model) = model {
+        if !nodes
+            .iter()
+            .any(|n| n["model_number"].as_str() == Some(model))
+        {
+            anyhow::bail!(
+                "the cluster has no {} nodes to size new ones from; use --add-usable",
+                model
+            );
+        }
+    }
+    Ok(total_bytes / nodes.len() as u64 * count as u64)
+}
+
+fn print_what_if(r: &WhatIf, expansion: &Expansion) {
+    let adding = match expansion {
+        Expansion::Nodes { count, model } => format!(
+            "{} {}node{} ≈ {}",
+            count,
+            model
+                .as_deref()
+                .map(|m| format!("{} ", m))
+                .unwrap_or_default(),
+            if *count == 1 { "" } else { "s" },
+            format_bytes(r.added_bytes)
+        ),
+        Expansion::Usable(_) => format!("{} usable", format_bytes(r.added_bytes)),
+    };
+    let days = |d: Option<u64>| match d {
+        Some(d) => format!("{} days", d),
+        None => "not projected".to_string(),
+    };
+
+    println!("What-if for {} ({})", r.profile, r.cluster);
+    println!(
+        "  Now:      {} of {} ({:.1}%)",
+        format_bytes(r.used_bytes),
+        format_bytes(r.total_bytes),
+        r.used_pct
+    );
+    println!("  Adding:   {}", adding);
+    println!(
+        "  After:    {} of {} ({:.1}%)",
+        format_bytes(r.used_bytes),
+        format_bytes(r.new_total_bytes),
+        r.new_used_pct
+    );
+    match (r.growth_rate_bytes_per_day, &r.confidence) {
+        (Some(rate), Some(confidence)) => {
+            let confidence = match confidence {
+                ProjectionConfidence::High => "high",
+                ProjectionConfidence::Low => "low",
+            };
+            println!(
+                "  Growth:   +{}/day ({} confidence)",
+                format_bytes(rate.round() as u64),
+                confidence
+            );
+            println!(
+                "  Full in:  {} → {}",
+                days(r.days_until_full),
+                days(r.new_days_until_full)
+            );
+        }
+        _ => {
+            println!("  Full in:  not projected (under 7 days of history, or usage isn't growing)")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_added_bytes_sizes_nodes_from_cluster() {
+        let nodes = vec![
+            serde_json::json!({"id": 1, "model_number": "C192T"}),
+            serde_json::json!({"id": 2, "model_number": "C192T"}),
+            serde_json::json!({"id": 3, "model_number": "C192T"}),
+            serde_json::json!({"id": 4, "model_number": "C192T"}),
+        ];
+        let nodes_of = |count, model: Option<&str>| Expansion::Nodes {
+            count,
+            model: model.map(String::from),
+        };
+        assert_eq!(added_bytes(&nodes_of(2, None), &nodes, 400).unwrap(), 200);
+        assert_eq!(
+            added_bytes(&nodes_of(1, Some("C192T")), &nodes, 400).unwrap(),
+            100
+        );
+        let err = added_bytes(&nodes_of(1, Some("C432T")), &nodes, 400).unwrap_err();
+        assert!(err.to_string().contains("no C432T nodes"));
+        assert_eq!(added_bytes(&Expansion::Usable(7), &[], 0).unwrap(), 7);
+
+        let cloud = vec![serde_json::json!({"id": 1, "model_number": "AWS"})];
+        let err = added_bytes(&nodes_of(1, None), &cloud, 400).unwrap_err();
+        assert!(err.to_string().contains("capacity clamp"));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 use anyhow::Result;
@@ -17,6 +18,8 @@ pub struct ClusterCdfData {
     pub address: String,
     pub portal_hubs: Vec<PortalHub>,
     pub portal_spokes: Vec<PortalSpoke>,
+    /// Cache pressure shared by this cluster's spoke portals, if it has any.
+    pub portal_cache: Option<PortalCacheStats>,
     pub replication_sources: Vec<ReplicationSource>,
     pub replication_source_statuses: Vec<ReplicationSourceStatus>,
     pub replication_target_statuses: Vec<ReplicationTargetStatus>,
@@ -44,23 +47,7 @@ pub fn collect_all(
     timeout_secs: u64,
     cluster_filter: Option<&str>,
 ) -> Result<CdfCollectionResult> {
-    let profiles: Vec<(String, ProfileEntry)> = if profile_filters.is_empty() {
-        config
-            .profiles
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect()
-    } else {
-        profile_filters
-            .iter()
-            .filter_map(|name| {
-                config
-                    .profiles
-                    .get(name)
-                    .map(|entry| (name.clone(), entry.clone()))
-            })
-            .collect()
-    };
+    let profiles = config.select_profiles(profile_filters);
 
     if profiles.is_empty() {
         anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
@@ -132,6 +119,7 @@ fn collect_cluster(
     // Query all 7 CDF endpoints — each individually wrapped for error isolation
     let portal_hubs = fetch_portal_hubs(&client);
     let portal_spokes = fetch_portal_spokes(&client);
+    let portal_cache = fetch_portal_cache(&client, &portal_spokes);
     let replication_sources = fetch_replication_sources(&client);
     let replication_source_statuses = fetch_replication_source_statuses(&client);
     let replication_target_statuses = fetch_replication_target_statuses(&client);
@@ -145,6 +133,7 @@ fn collect_cluster(
         address,
         portal_hubs,
         portal_spokes,
+        portal_cache,
         replication_sources,
         replication_source_statuses,
         replication_target_statuses,
@@ -191,6 +180,34 @@ fn fetch_portal_spokes(client: &QumuloClient) -> Vec<PortalSpoke> {
     }
 }
 
+/// Fetch the eviction threshold and capacity that govern this cluster's
+/// spoke caches. Only clusters with spokes are asked, and clusters without
+/// eviction settings have no entry.
+fn fetch_portal_cache(client: &QumuloClient, spokes: &[PortalSpoke]) -> Option<PortalCacheStats> {
+    if spokes.is_empty() {
+        return None;
+    }
+    let settings = match client.get_portal_eviction_settings() {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::debug!(error = %e, "failed to fetch portal eviction settings");
+            return None;
+        }
+    };
+    let mut stats = match client.get_file_system() {
+        Ok(v) => serde_json::from_value::<PortalCacheStats>(v).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to parse file system capacity");
+            PortalCacheStats::default()
+        }),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch file system capacity");
+            PortalCacheStats::default()
+        }
+    };
+    stats.free_threshold = settings["free_threshold"].as_f64();
+    Some(stats)
+}
+
 fn fetch_replication_sources(client: &QumuloClient) -> Vec<ReplicationSource> {
     match client.get_replication_sources() {
         Ok(v) => serde_json::from_value(v).unwrap_or_else(|e| {
@@ -311,7 +328,9 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                 let key = PortalEdgeKey::new(source_node, target, hub.id);
                 if !portal_edges.contains_key(&key) {
                     // Find matching spoke from the other side (if that cluster is profiled)
-                    let spoke_id = find_matching_spoke(clusters, cluster, hub);
+                    let spoke = find_matching_spoke(clusters, cluster, hub);
+                    let spoke_id = spoke.map(|(_, s)| s.id);
+                    let cache = spoke.and_then(|(c, _)| c.portal_cache.clone());
                     graph.add_edge(
                         source_node,
                         target,
@@ -322,6 +341,7 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                             state: hub.state.clone(),
                             status: hub.status.clone(),
                             roots: hub.authorized_roots.clone(),
+                            cache,
                         },
                     );
                     portal_edges.insert(key, true);
@@ -351,11 +371,8 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                     // Hub side didn't add this; try finding the hub's ID
                     let hub_id = find_matching_hub(clusters, cluster, spoke);
                     // Edge direction: hub → spoke
-                    let roots: Vec<String> = spoke
-                        .roots
-                        .iter()
-                        .map(|r| r.local_root.clone())
-                        .collect();
+                    let roots: Vec<String> =
+                        spoke.roots.iter().map(|r| r.local_root.clone()).collect();
                     graph.add_edge(
                         hub_node,
                         source_node,
@@ -366,6 +383,7 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                             state: spoke.state.clone(),
                             status: spoke.status.clone(),
                             roots,
+                            cache: cluster.portal_cache.clone(),
                         },
                     );
                     portal_edges.insert(key, true);
@@ -394,7 +412,7 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                     source_node,
                     target,
                 );
-                if !replication_edges.contains_key(&key) {
+                if let Entry::Vacant(slot) = replication_edges.entry(key) {
                     graph.add_edge(
                         source_node,
                         target,
@@ -410,7 +428,7 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                             replication_job_status: status.replication_job_status.clone(),
                         },
                     );
-                    replication_edges.insert(key, true);
+                    slot.insert(true);
                 }
             }
         }
@@ -432,7 +450,7 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                     src_node,
                     source_node,
                 );
-                if !replication_edges.contains_key(&key) {
+                if let Entry::Vacant(slot) = replication_edges.entry(key) {
                     graph.add_edge(
                         src_node,
                         source_node,
@@ -448,16 +466,14 @@ pub fn build_cdf_graph(clusters: &[ClusterCdfData], cluster_filter: Option<&str>
                             replication_job_status: status.replication_job_status.clone(),
                         },
                     );
-                    replication_edges.insert(key, true);
+                    slot.insert(true);
                 }
             }
         }
 
         // Process object relationships (S3 bucket edges)
         for obj in &cluster.object_relationships {
-            if let (Some(addr), Some(bucket)) =
-                (&obj.object_store_address, &obj.bucket)
-            {
+            if let (Some(addr), Some(bucket)) = (&obj.object_store_address, &obj.bucket) {
                 let s3_node = get_or_create_s3_node(
                     &mut graph,
                     &mut address_to_node,
@@ -571,12 +587,13 @@ fn get_or_create_s3_node(
     node
 }
 
-/// Find the spoke ID on the remote cluster that matches this hub.
-fn find_matching_spoke(
-    clusters: &[ClusterCdfData],
+/// Find the spoke on the remote cluster that matches this hub, along with
+/// the cluster that reported it.
+fn find_matching_spoke<'a>(
+    clusters: &'a [ClusterCdfData],
     hub_cluster: &ClusterCdfData,
     _hub: &PortalHub,
-) -> Option<u64> {
+) -> Option<(&'a ClusterCdfData, &'a PortalSpoke)> {
     // Look through all other clusters' spokes for one that references our hub
     let hub_uuid = &hub_cluster.cluster_uuid;
     for cluster in clusters {
@@ -585,7 +602,7 @@ fn find_matching_spoke(
         }
         for spoke in &cluster.portal_spokes {
             if spoke.hub_cluster_uuid.as_deref() == Some(hub_uuid.as_str()) {
-                return Some(spoke.id);
+                return Some((cluster, spoke));
             }
             // Also match by hub host address
             if spoke
@@ -593,7 +610,7 @@ fn find_matching_spoke(
                 .iter()
                 .any(|h| h.address == hub_cluster.address)
             {
-                return Some(spoke.id);
+                return Some((cluster, spoke));
             }
         }
     }
@@ -635,9 +652,7 @@ fn prune_graph(graph: &mut CdfGraph, cluster_name: &str) {
     let matching: Vec<NodeIndex> = graph
         .node_indices()
         .filter(|&n| match &graph[n] {
-            CdfNode::ProfiledCluster { name, .. } => {
-                name.eq_ignore_ascii_case(cluster_name)
-            }
+            CdfNode::ProfiledCluster { name, .. } => name.eq_ignore_ascii_case(cluster_name),
             _ => false,
         })
         .collect();
@@ -660,10 +675,7 @@ fn prune_graph(graph: &mut CdfGraph, cluster_name: &str) {
     }
 
     // Remove nodes not in keep set
-    let to_remove: Vec<NodeIndex> = graph
-        .node_indices()
-        .filter(|n| !keep.contains(n))
-        .collect();
+    let to_remove: Vec<NodeIndex> = graph.node_indices().filter(|n| !keep.contains(n)).collect();
     // Remove in reverse order to preserve indices
     for node in to_remove.into_iter().rev() {
         graph.remove_node(node);
@@ -756,7 +768,13 @@ pub fn dump_graph_text(graph: &CdfGraph) -> String {
                 name,
                 uuid,
                 address,
-            } => format!("  [{}] {} (uuid={}, addr={})", idx.index(), name, uuid, address),
+            } => format!(
+                "  [{}] {} (uuid={}, addr={})",
+                idx.index(),
+                name,
+                uuid,
+                address
+            ),
             CdfNode::UnknownCluster { address, uuid } => format!(
                 "  [{}] UNKNOWN (addr={}, uuid={})",
                 idx.index(),
@@ -852,15 +870,72 @@ fn node_short_name(node: &CdfNode) -> String {
     }
 }
 
+/// Version of the `cdf status --json` document. Bump when fields are removed
+/// or change meaning; adding fields does not require a bump.
+pub const CDF_JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Stable identifier for a node, derived from its identity rather than its
+/// position in the graph: the cluster UUID when known, otherwise its address.
+pub fn node_id(node: &CdfNode) -> String {
+    match node {
+        CdfNode::ProfiledCluster { name, uuid, .. } if uuid.is_empty() => {
+            format!("cluster:name:{}", name)
+        }
+        CdfNode::ProfiledCluster { uuid, .. } => format!("cluster:{}", uuid),
+        CdfNode::UnknownCluster {
+            uuid: Some(uuid), ..
+        } if !uuid.is_empty() => format!("cluster:{}", uuid),
+        CdfNode::UnknownCluster { address, .. } => format!("cluster:address:{}", address),
+        CdfNode::S3Bucket {
+            address, bucket, ..
+        } => format!("s3:{}/{}", address, bucket),
+    }
+}
+
+/// Stable identifier for an edge: its endpoints' node IDs plus the
+/// relationship's own distinguishing fields.
+fn edge_id(source_id: &str, target_id: &str, edge: &CdfEdge) -> String {
+    let detail = match edge {
+        CdfEdge::Portal {
+            hub_id, spoke_id, ..
+        } => format!("portal:{}:{}", hub_id, spoke_id),
+        CdfEdge::Replication {
+            source_path,
+            target_path,
+            ..
+        } => format!(
+            "replication:{}:{}",
+            source_path.as_deref().unwrap_or(""),
+            target_path.as_deref().unwrap_or("")
+        ),
+        CdfEdge::ObjectReplication { bucket, folder, .. } => format!(
+            "object_replication:{}:{}",
+            bucket.as_deref().unwrap_or(""),
+            folder.as_deref().unwrap_or("")
+        ),
+    };
+    format!("{}->{}/{}", source_id, target_id, detail)
+}
+
 /// Serialize the graph to a JSON value for --json output.
-pub fn graph_to_json(graph: &CdfGraph) -> serde_json::Value {
+///
+/// Nodes and edges carry stable `id`s (edges also `source_id`/`target_id`) so
+/// external tools can track them across runs; the numeric `source`/`target`
+/// indices are kept for existing consumers. Collection errors are included so
+/// a missing cluster is distinguishable from one with no relationships.
+pub fn graph_to_json(graph: &CdfGraph, errors: &[ClusterCdfError]) -> serde_json::Value {
     use petgraph::visit::EdgeRef;
 
+    let ids: Vec<String> = graph
+        .node_indices()
+        .map(|idx| node_id(&graph[idx]))
+        .collect();
+
     let nodes: Vec<serde_json::Value> = graph
         .node_indices()
         .map(|idx| {
             let node = &graph[idx];
-            match node {
+            let mut obj = match node {
                 CdfNode::ProfiledCluster {
                     name,
                     uuid,
@@ -886,16 +961,19 @@ pub fn graph_to_json(graph: &CdfGraph) -> serde_json::Value {
                     "bucket": bucket,
                     "region": region,
                 }),
-            }
+            };
+            obj["id"] = serde_json::json!(ids[idx.index()]);
+            obj
         })
         .collect();
 
+    let mut seen_edge_ids: HashMap<String, usize> = HashMap::new();
     let edges: Vec<serde_json::Value> = graph
         .edge_references()
         .map(|edge| {
             let src = edge.source().index();
             let tgt = edge.target().index();
-            match edge.weight() {
+            let mut obj = match edge.weight() {
                 CdfEdge::Portal {
                     hub_id,
                     spoke_id,
@@ -903,17 +981,26 @@ pub fn graph_to_json(graph: &CdfGraph) -> serde_json::Value {
                     state,
                     status,
                     roots,
-                } => serde_json::json!({
-                    "source": src,
-                    "target": tgt,
-                    "type": "portal",
-                    "hub_id": hub_id,
-                    "spoke_id": spoke_id,
-                    "portal_type": portal_type,
-                    "state": state,
-                    "status": status,
-                    "roots": roots,
-                }),
+                    cache,
+                } => {
+                    let mut obj = serde_json::json!({
+                        "source": src,
+                        "target": tgt,
+                        "type": "portal",
+                        "hub_id": hub_id,
+                        "spoke_id": spoke_id,
+                        "portal_type": portal_type,
+                        "state": state,
+                        "status": status,
+                        "roots": roots,
+                    });
+                    if let Some(cache) = cache {
+                        obj["cache"] = serde_json::to_value(cache).unwrap_or_default();
+                        obj["cache"]["free_pct"] = serde_json::json!(cache.free_pct());
+                        obj["cache"]["evicting"] = serde_json::json!(cache.evicting());
+                    }
+                    obj
+                }
                 CdfEdge::Replication {
                     source_path,
                     target_path,
@@ -939,10 +1026,16 @@ pub fn graph_to_json(graph: &CdfGraph) -> serde_json::Value {
                         "error_from_last_job": error_from_last_job,
                     });
                     if let Some(job) = replication_job_status {
-                        obj["replication_job_status"] = serde_json::to_value(job).unwrap_or_default();
+                        obj["replication_job_status"] =
+                            serde_json::to_value(job).unwrap_or_default();
+                        obj["progress"] = serde_json::json!({
+                            "throughput_bytes_per_sec": job.throughput(),
+                            "bytes_remaining": job.bytes_remaining(),
+                            "eta_seconds": job.eta_seconds(),
+                        });
                     }
                     obj
-                },
+                }
                 CdfEdge::ObjectReplication {
                     direction,
                     bucket,
@@ -957,26 +1050,48 @@ pub fn graph_to_json(graph: &CdfGraph) -> serde_json::Value {
                     "folder": folder,
                     "state": state,
                 }),
-            }
+            };
+            // Parallel relationships with identical fields get a `#n` suffix
+            let base_id = edge_id(&ids[src], &ids[tgt], edge.weight());
+            let count = seen_edge_ids.entry(base_id.clone()).or_insert(0);
+            *count += 1;
+            obj["id"] = if *count == 1 {
+                serde_json::json!(base_id)
+            } else {
+                serde_json::json!(format!("{}#{}", base_id, count))
+            };
+            obj["source_id"] = serde_json::json!(ids[src]);
+            obj["target_id"] = serde_json::json!(ids[tgt]);
+            obj
         })
         .collect();
 
     serde_json::json!({
+        "schema_version": CDF_JSON_SCHEMA_VERSION,
         "nodes": nodes,
         "edges": edges,
+        "errors": errors_to_json(errors),
     })
 }
 
+/// JSON form of collection errors, as used in the `errors` array.
+pub fn errors_to_json(errors: &[ClusterCdfError]) -> Vec<serde_json::Value> {
+    errors
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "profile": e.profile,
+                "error": e.error,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn make_cluster(
-        profile: &str,
-        name: &str,
-        uuid: &str,
-        address: &str,
-    ) -> ClusterCdfData {
+    fn make_cluster(profile: &str, name: &str, uuid: &str, address: &str) -> ClusterCdfData {
         ClusterCdfData {
             profile: profile.to_string(),
             cluster_name: name.to_string(),
@@ -984,6 +1099,7 @@ mod tests {
             address: address.to_string(),
             portal_hubs: Vec::new(),
             portal_spokes: Vec::new(),
+            portal_cache: None,
             replication_sources: Vec::new(),
             replication_source_statuses: Vec::new(),
             replication_target_statuses: Vec::new(),
@@ -1167,9 +1283,22 @@ mod tests {
             }],
         });
 
+        cluster_b.portal_cache = Some(PortalCacheStats {
+            free_threshold: Some(0.15),
+            free_size_bytes: Some("100".into()),
+            total_size_bytes: Some("1000".into()),
+        });
+
         let graph = build_cdf_graph(&[cluster_a, cluster_b], None);
         assert_eq!(graph.node_count(), 2);
         assert_eq!(graph.edge_count(), 1); // Deduplicated!
+
+        // The hub-side edge picks up the spoke cluster's cache pressure
+        let json = graph_to_json(&graph, &[]);
+        assert_eq!(json["edges"][0]["spoke_id"], 5);
+        assert_eq!(json["edges"][0]["cache"]["free_threshold"], 0.15);
+        assert_eq!(json["edges"][0]["cache"]["free_pct"], 10.0);
+        assert_eq!(json["edges"][0]["cache"]["evicting"], true);
     }
 
     #[test]
@@ -1189,11 +1318,72 @@ mod tests {
         });
 
         let graph = build_cdf_graph(&[cluster_a], None);
-        let json = graph_to_json(&graph);
+        let json = graph_to_json(&graph, &[]);
 
         assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
         assert_eq!(json["edges"].as_array().unwrap().len(), 1);
         assert_eq!(json["nodes"][0]["type"], "profiled_cluster");
+        assert_eq!(json["schema_version"], CDF_JSON_SCHEMA_VERSION);
+        assert_eq!(json["nodes"][0]["id"], "cluster:uuid-a");
+        assert_eq!(json["nodes"][1]["id"], "s3:s3.amazonaws.com/test-bucket");
+        assert_eq!(json["edges"][0]["source_id"], "cluster:uuid-a");
+        assert_eq!(
+            json["edges"][0]["id"],
+            "cluster:uuid-a->s3:s3.amazonaws.com/test-bucket/object_replication:test-bucket:"
+        );
+        assert!(json["errors"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_graph_json_includes_errors_and_fallback_ids() {
+        let mut graph = CdfGraph::new();
+        let a = graph.add_node(CdfNode::ProfiledCluster {
+            name: "cluster-a".into(),
+            uuid: String::new(),
+            address: "10.0.0.1".into(),
+        });
+        let b = graph.add_node(CdfNode::UnknownCluster {
+            address: "10.9.9.9".into(),
+            uuid: None,
+        });
+        for _ in 0..2 {
+            graph.add_edge(
+                a,
+                b,
+                CdfEdge::Portal {
+                    hub_id: 1,
+                    spoke_id: 2,
+                    portal_type: "PORTAL_READ_ONLY".into(),
+                    state: "ACCEPTED".into(),
+                    status: "ACTIVE".into(),
+                    roots: Vec::new(),
+                    cache: None,
+                },
+            );
+        }
+        let errors = vec![ClusterCdfError {
+            profile: "c".into(),
+            error: "connection refused".into(),
+        }];
+
+        let json = graph_to_json(&graph, &errors);
+        assert_eq!(json["nodes"][0]["id"], "cluster:name:cluster-a");
+        assert_eq!(json["nodes"][1]["id"], "cluster:address:10.9.9.9");
+        let edge_ids: Vec<&str> = json["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            edge_ids,
+            [
+                "cluster:name:cluster-a->cluster:address:10.9.9.9/portal:1:2",
+                "cluster:name:cluster-a->cluster:address:10.9.9.9/portal:1:2#2",
+            ]
+        );
+        assert_eq!(json["errors"][0]["profile"], "c");
+        assert_eq!(json["errors"][0]["error"], "connection refused");
     }
 
     #[test]
@@ -1224,10 +1414,7 @@ mod tests {
                 replication_job_status: None,
             });
 
-        let graph = build_cdf_graph(
-            &[cluster_a, cluster_b, cluster_c],
-            Some("cluster-a"),
-        );
+        let graph = build_cdf_graph(&[cluster_a, cluster_b, cluster_c], Some("cluster-a"));
 
         // Should have A and B (connected), but not C
         assert_eq!(graph.node_count(), 2);
@@ -1297,10 +1484,7 @@ mod tests {
         // Edge should go from S3 → cluster (COPY_FROM_OBJECT)
         use petgraph::visit::EdgeRef;
         let edge = graph.edge_references().next().unwrap();
-        assert!(matches!(
-            &graph[edge.source()],
-            CdfNode::S3Bucket { .. }
-        ));
+        assert!(matches!(&graph[edge.source()], CdfNode::S3Bucket { .. }));
         assert!(matches!(
             &graph[edge.target()],
             CdfNode::ProfiledCluster { .. }
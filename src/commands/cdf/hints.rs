@@ -0,0 +1,193 @@
+use std::net::{IpAddr, ToSocketAddrs};
+
+use crate::config::Config;
+
+use super::types::*;
+
+/// What we know about a configured profile when matching unknown clusters.
+#[derive(Debug, Clone)]
+struct KnownProfile {
+    name: String,
+    host: String,
+    uuid: Option<String>,
+    addrs: Vec<IpAddr>,
+    collected: bool,
+}
+
+/// How an unknown cluster was tied back to a configured profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchedBy {
+    Uuid,
+    Dns,
+}
+
+impl MatchedBy {
+    fn as_str(self) -> &'static str {
+        match self {
+            MatchedBy::Uuid => "UUID",
+            MatchedBy::Dns => "DNS",
+        }
+    }
+}
+
+/// Build one hint per UnknownCluster node in the graph.
+///
+/// Unknown clusters are matched against configured profiles first by UUID
+/// (as backfilled from each cluster), then by resolving profile hosts and
+/// comparing addresses. `collected` is the set of profiles this run queried.
+pub fn unknown_cluster_hints(
+    graph: &CdfGraph,
+    config: &Config,
+    collected: &[String],
+) -> Vec<String> {
+    let unknowns: Vec<(&str, Option<&str>)> = graph
+        .node_weights()
+        .filter_map(|n| match n {
+            CdfNode::UnknownCluster { address, uuid } => Some((address.as_str(), uuid.as_deref())),
+            _ => None,
+        })
+        .collect();
+    if unknowns.is_empty() {
+        return Vec::new();
+    }
+
+    let profiles: Vec<KnownProfile> = config
+        .profiles
+        .iter()
+        .map(|(name, entry)| KnownProfile {
+            name: name.clone(),
+            host: entry.host.clone(),
+            uuid: entry.cluster_uuid.clone(),
+            addrs: resolve_host(&entry.host, entry.port),
+            collected: collected.is_empty() || collected.contains(name),
+        })
+        .collect();
+
+    unknowns
+        .into_iter()
+        .map(|(address, uuid)| hint_for(address, uuid, &profiles))
+        .collect()
+}
+
+/// Number of UnknownCluster nodes in the graph.
+pub fn unknown_count(graph: &CdfGraph) -> usize {
+    graph
+        .node_weights()
+        .filter(|n| matches!(n, CdfNode::UnknownCluster { .. }))
+        .count()
+}
+
+fn hint_for(address: &str, uuid: Option<&str>, profiles: &[KnownProfile]) -> String {
+    let label = if address.is_empty() {
+        uuid.unwrap_or("unknown cluster")
+    } else {
+        address
+    };
+
+    let Some((profile, by)) = find_profile(address, uuid, profiles) else {
+        return format!(
+            "hint: add a profile for {} to see both sides (qontrol profile add)",
+            label
+        );
+    };
+
+    if profile.collected {
+        format!(
+            "hint: {} matches profile '{}' by {} but was not merged; it may have failed to \
+             collect, or replication targets an address other than {}",
+            label,
+            profile.name,
+            by.as_str(),
+            profile.host
+        )
+    } else {
+        format!(
+            "hint: {} matches profile '{}' by {}; include it with --from {} to see both sides",
+            label,
+            profile.name,
+            by.as_str(),
+            profile.name
+        )
+    }
+}
+
+fn find_profile<'a>(
+    address: &str,
+    uuid: Option<&str>,
+    profiles: &'a [KnownProfile],
+) -> Option<(&'a KnownProfile, MatchedBy)> {
+    if let Some(uuid) = uuid.filter(|u| !u.is_empty()) {
+        if let Some(p) = profiles.iter().find(|p| p.uuid.as_deref() == Some(uuid)) {
+            return Some((p, MatchedBy::Uuid));
+        }
+    }
+    let ip: IpAddr = address.parse().ok()?;
+    profiles
+        .iter()
+        .find(|p| p.addrs.contains(&ip))
+        .map(|p| (p, MatchedBy::Dns))
+}
+
+/// Resolve a profile host to its addresses. Failures resolve to nothing.
+fn resolve_host(host: &str, port: u16) -> Vec<IpAddr> {
+    match (host, port).to_socket_addrs() {
+        Ok(addrs) => addrs.map(|a| a.ip()).collect(),
+        Err(e) => {
+            tracing::debug!(host = %host, error = %e, "failed to resolve profile host");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, uuid: Option<&str>, addr: &str, collected: bool) -> KnownProfile {
+        KnownProfile {
+            name: name.into(),
+            host: format!("{}.example.com", name),
+            uuid: uuid.map(String::from),
+            addrs: vec![addr.parse().unwrap()],
+            collected,
+        }
+    }
+
+    #[test]
+    fn test_hint_matches_by_uuid_before_dns() {
+        let profiles = vec![
+            profile("iss", Some("uuid-iss"), "10.0.0.9", false),
+            profile("dr", None, "10.2.3.4", false),
+        ];
+        let hint = hint_for("10.2.3.4", Some("uuid-iss"), &profiles);
+        assert_eq!(
+            hint,
+            "hint: 10.2.3.4 matches profile 'iss' by UUID; include it with --from iss to see both sides"
+        );
+    }
+
+    #[test]
+    fn test_hint_matches_by_dns() {
+        let profiles = vec![profile("dr", None, "10.2.3.4", true)];
+        let hint = hint_for("10.2.3.4", None, &profiles);
+        assert!(hint.contains("matches profile 'dr' by DNS but was not merged"));
+        assert!(hint.contains("dr.example.com"));
+    }
+
+    #[test]
+    fn test_hint_suggests_adding_profile() {
+        let profiles = vec![profile("dr", None, "10.2.3.4", true)];
+        assert_eq!(
+            hint_for("10.9.9.9", None, &profiles),
+            "hint: add a profile for 10.9.9.9 to see both sides (qontrol profile add)"
+        );
+    }
+
+    #[test]
+    fn test_resolve_host_literal_ip() {
+        assert_eq!(
+            resolve_host("127.0.0.1", 8000),
+            vec!["127.0.0.1".parse::<IpAddr>().unwrap()]
+        );
+    }
+}
@@ -1,16 +1,53 @@
 pub mod collector;
+pub mod hints;
 pub mod renderer;
 pub mod renderer_table;
+pub mod summary;
 pub mod types;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
 use petgraph::visit::EdgeRef;
 
 use crate::config::Config;
-use types::CdfGraph;
+use crate::output::print_json;
+use types::{CdfEdge, CdfGraph};
 
 /// Filter a CdfGraph to only include edges that represent problems.
 fn filter_problems(graph: &CdfGraph) -> CdfGraph {
+    filter_edges(graph, CdfEdge::is_problem)
+}
+
+/// Filter a CdfGraph to relationships whose root paths match `filter`.
+fn filter_path(graph: &CdfGraph, filter: &str) -> CdfGraph {
+    filter_edges(graph, |edge| {
+        edge.root_paths()
+            .iter()
+            .any(|root| path_matches(root, filter))
+    })
+}
+
+/// A glob filter matches root paths directly. A plain path matches roots at
+/// or below it, and roots above it (a relationship on `/projects` also
+/// carries `/projects/foo`).
+fn path_matches(root: &str, filter: &str) -> bool {
+    if crate::glob::is_glob(filter) {
+        return crate::glob::glob_match(filter, root);
+    }
+    let under = |path: &str, dir: &str| {
+        let dir = dir.trim_end_matches('/');
+        path == dir || dir.is_empty() || path.starts_with(&format!("{}/", dir))
+    };
+    under(root, filter) || under(filter, root)
+}
+
+/// Copy a CdfGraph keeping only edges that satisfy `keep`, then drop nodes
+/// left without any edges.
+fn filter_edges(graph: &CdfGraph, keep: impl Fn(&CdfEdge) -> bool) -> CdfGraph {
     let mut filtered = CdfGraph::new();
 
     // Copy all nodes, keeping a mapping from old to new indices
@@ -20,9 +57,9 @@ fn filter_problems(graph: &CdfGraph) -> CdfGraph {
         node_map.insert(idx, new_idx);
     }
 
-    // Copy only problem edges
+    // Copy only matching edges
     for edge in graph.edge_references() {
-        if edge.weight().is_problem() {
+        if keep(edge.weight()) {
             filtered.add_edge(
                 node_map[&edge.source()],
                 node_map[&edge.target()],
@@ -51,15 +88,101 @@ fn filter_problems(graph: &CdfGraph) -> CdfGraph {
 }
 
 /// Run the CDF status command: collect from all clusters and display the graph.
+///
+/// With `watch`, re-collect every `interval` seconds until Ctrl+C, redrawing
+/// the screen each time (running replication jobs show throughput and ETA).
+/// `no_clear` appends each refresh under a timestamp header instead.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     config: &Config,
     profiles: &[String],
     json_mode: bool,
     graph_mode: bool,
     cluster_filter: Option<&str>,
+    path_filter: Option<&str>,
     problems_only: bool,
+    summary_only: bool,
+    wide: bool,
+    strict_unknown: bool,
+    watch: bool,
+    interval: u64,
+    no_clear: bool,
     timeout_secs: u64,
 ) -> Result<()> {
+    let running = Arc::new(AtomicBool::new(true));
+    if watch {
+        let r = running.clone();
+        ctrlc::set_handler(move || {
+            r.store(false, Ordering::SeqCst);
+        })
+        .ok();
+    }
+
+    let mut first = true;
+    loop {
+        if watch && !json_mode {
+            if no_clear {
+                crate::output::print_poll_header();
+            } else if !first {
+                print!("\x1B[2J\x1B[H");
+            }
+        }
+        let unknowns = poll(
+            config,
+            profiles,
+            json_mode,
+            graph_mode,
+            cluster_filter,
+            path_filter,
+            problems_only,
+            summary_only,
+            wide,
+            timeout_secs,
+        )?;
+        if strict_unknown && unknowns > 0 {
+            anyhow::bail!(
+                "{} unknown cluster(s) in the data fabric (--strict-unknown)",
+                unknowns
+            );
+        }
+
+        if !watch {
+            return Ok(());
+        }
+        if !json_mode {
+            println!(
+                "Refreshing every {}s \u{2014} press Ctrl+C to stop",
+                interval
+            );
+        }
+
+        // Sleep in small increments so Ctrl+C is responsive
+        let sleep_end = Instant::now() + Duration::from_secs(interval);
+        while Instant::now() < sleep_end {
+            if !running.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        first = false;
+    }
+}
+
+/// Collect once and print the result in the requested format. Returns the
+/// number of unknown clusters in the (unfiltered) fabric.
+#[allow(clippy::too_many_arguments)]
+fn poll(
+    config: &Config,
+    profiles: &[String],
+    json_mode: bool,
+    graph_mode: bool,
+    cluster_filter: Option<&str>,
+    path_filter: Option<&str>,
+    problems_only: bool,
+    summary_only: bool,
+    wide: bool,
+    timeout_secs: u64,
+) -> Result<usize> {
     let result = collector::collect_all(config, profiles, timeout_secs, cluster_filter)?;
 
     // Report any collection errors
@@ -68,28 +191,119 @@ pub fn run(
             eprintln!("warning: {}: {}", err.profile, err.error);
         }
     }
+    // Glob filters name profiles indirectly; hints need the actual names
+    let collected: Vec<String> = config
+        .select_profiles(profiles)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    for hint in hints::unknown_cluster_hints(&result.graph, config, &collected) {
+        eprintln!("{}", hint);
+    }
+    let unknowns = hints::unknown_count(&result.graph);
+
+    // --path narrows the fabric itself; the headline describes what's left
+    // of it, before --problems-only
+    let fabric = match path_filter {
+        Some(path) => filter_path(&result.graph, path),
+        None => result.graph,
+    };
+    let summary = summary::summarize(&fabric, chrono::Utc::now());
+
+    if summary_only {
+        if json_mode {
+            let json = serde_json::json!({
+                "schema_version": collector::CDF_JSON_SCHEMA_VERSION,
+                "summary": summary,
+                "errors": collector::errors_to_json(&result.errors),
+            });
+            print_json(&json)?;
+        } else {
+            print!("{}", summary::render(&summary));
+        }
+        return Ok(unknowns);
+    }
 
     let graph = if problems_only {
-        let filtered = filter_problems(&result.graph);
+        let filtered = filter_problems(&fabric);
         if filtered.edge_count() == 0 {
             eprintln!("No problems found.");
-            return Ok(());
+            return Ok(unknowns);
         }
         filtered
     } else {
-        result.graph
+        fabric
     };
 
     if json_mode {
-        let json = collector::graph_to_json(&graph);
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        let mut json = collector::graph_to_json(&graph, &result.errors);
+        json["summary"] = serde_json::to_value(&summary)?;
+        print_json(&json)?;
     } else if graph_mode {
         let output = renderer::render(&graph);
-        print!("{}", output);
+        print!("{}\n{}", summary::render(&summary), output);
     } else {
-        let output = renderer_table::render_table(&graph);
-        print!("{}", output);
+        let output = renderer_table::render_table(&graph, wide);
+        print!("{}\n{}", summary::render(&summary), output);
     }
 
-    Ok(())
+    Ok(unknowns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::CdfNode;
+
+    fn replication(source: &str, target: &str) -> CdfEdge {
+        CdfEdge::Replication {
+            source_path: Some(source.into()),
+            target_path: Some(target.into()),
+            mode: None,
+            enabled: true,
+            state: Some("ESTABLISHED".into()),
+            job_state: None,
+            recovery_point: None,
+            error_from_last_job: None,
+            replication_job_status: None,
+        }
+    }
+
+    #[test]
+    fn test_path_matches_prefix_and_ancestor() {
+        assert!(path_matches("/projects/foo", "/projects/foo"));
+        assert!(path_matches("/projects/foo/raw", "/projects/foo/"));
+        assert!(path_matches("/projects", "/projects/foo"));
+        assert!(path_matches("/", "/projects/foo"));
+        assert!(!path_matches("/projects/foobar", "/projects/foo"));
+        assert!(!path_matches("/home", "/projects/foo"));
+    }
+
+    #[test]
+    fn test_path_matches_glob() {
+        assert!(path_matches("/projects/foo", "/projects/*"));
+        assert!(!path_matches("/projects", "/projects/*"));
+    }
+
+    #[test]
+    fn test_filter_path_drops_unrelated_edges_and_nodes() {
+        let mut graph = CdfGraph::new();
+        let cluster = |name: &str| CdfNode::ProfiledCluster {
+            name: name.into(),
+            uuid: format!("uuid-{}", name),
+            address: String::new(),
+        };
+        let a = graph.add_node(cluster("a"));
+        let b = graph.add_node(cluster("b"));
+        let c = graph.add_node(cluster("c"));
+        graph.add_edge(a, b, replication("/projects/foo", "/dr/foo"));
+        graph.add_edge(a, c, replication("/home", "/home"));
+
+        let filtered = filter_path(&graph, "/projects/foo");
+        assert_eq!(filtered.edge_count(), 1);
+        assert_eq!(filtered.node_count(), 2);
+
+        // Either side of the relationship can match
+        assert_eq!(filter_path(&graph, "/dr").edge_count(), 1);
+    }
 }
@@ -364,13 +364,11 @@ fn build_satellite_entries(edges: &[(String, CdfEdge)]) -> Vec<SatelliteEntry> {
             _ => false,
         };
         let dir = match edge {
-            CdfEdge::ObjectReplication { direction, .. } => {
-                match direction.as_deref() {
-                    Some("COPY_TO_OBJECT") => Direction::Forward,
-                    Some("COPY_FROM_OBJECT") => Direction::Backward,
-                    _ => Direction::Forward,
-                }
-            }
+            CdfEdge::ObjectReplication { direction, .. } => match direction.as_deref() {
+                Some("COPY_TO_OBJECT") => Direction::Forward,
+                Some("COPY_FROM_OBJECT") => Direction::Backward,
+                _ => Direction::Forward,
+            },
             _ => Direction::Forward,
         };
         let entry = groups.entry(label.clone()).or_insert((0, 0, dir));
@@ -481,10 +479,7 @@ fn render_cluster_connections(out: &mut String, topo: &Topology) {
         "\n  {}\n",
         style_bold().apply_to("CLUSTER CONNECTIONS")
     ));
-    out.push_str(&format!(
-        "  {}\n",
-        style_dim().apply_to("─".repeat(67))
-    ));
+    out.push_str(&format!("  {}\n", style_dim().apply_to("─".repeat(67))));
 
     for bundle in &topo.cluster_pairs {
         render_pair_line(out, bundle);
@@ -575,15 +570,11 @@ fn format_edge_annotation(groups: &[EdgeSummary]) -> String {
 
         if g.disabled_count > 0 {
             if g.disabled_count == g.count {
-                part.push_str(&format!(
-                    " {}",
-                    style_disabled().apply_to("[ALL DISABLED]")
-                ));
+                part.push_str(&format!(" {}", style_disabled().apply_to("[ALL DISABLED]")));
             } else {
                 part.push_str(&format!(
                     " {}",
-                    style_disabled()
-                        .apply_to(format!("[{} disabled]", g.disabled_count))
+                    style_disabled().apply_to(format!("[{} disabled]", g.disabled_count))
                 ));
             }
         }
@@ -601,10 +592,7 @@ fn render_s3_section(out: &mut String, topo: &Topology) {
         "  {}\n",
         style_bold().apply_to("S3 BUCKETS \u{2601}")
     ));
-    out.push_str(&format!(
-        "  {}\n",
-        style_dim().apply_to("─".repeat(67))
-    ));
+    out.push_str(&format!("  {}\n", style_dim().apply_to("─".repeat(67))));
 
     for group in &topo.s3_groups {
         let cluster = &group.cluster_name;
@@ -694,10 +682,7 @@ fn render_remote_peers_section(out: &mut String, topo: &Topology) {
         "  {}\n",
         style_bold().apply_to("REMOTE PEERS \u{25CB}")
     ));
-    out.push_str(&format!(
-        "  {}\n",
-        style_dim().apply_to("─".repeat(67))
-    ));
+    out.push_str(&format!("  {}\n", style_dim().apply_to("─".repeat(67))));
 
     for group in &topo.remote_peer_groups {
         let cluster = &group.cluster_name;
@@ -775,16 +760,10 @@ fn render_remote_peers_section(out: &mut String, topo: &Topology) {
 fn render_legend(out: &mut String, topo: &Topology) {
     let mut parts = Vec::new();
     if topo.has_replication {
-        parts.push(format!(
-            "{} repl",
-            style_replication().apply_to("══")
-        ));
+        parts.push(format!("{} repl", style_replication().apply_to("══")));
     }
     if topo.has_portal {
-        parts.push(format!(
-            "{} portal",
-            style_portal().apply_to("──")
-        ));
+        parts.push(format!("{} portal", style_portal().apply_to("──")));
     }
     if topo.has_s3 {
         parts.push(format!("{} S3", style_object().apply_to("╌╌")));
@@ -849,6 +828,7 @@ mod tests {
                 state: "ACCEPTED".into(),
                 status: "ACTIVE".into(),
                 roots: vec!["/data".into()],
+                cache: None,
             },
         );
         graph.add_edge(
@@ -917,7 +897,6 @@ mod tests {
         assert!(plain.contains("one-way"));
     }
 
-
     #[test]
     fn test_render_unknown_cluster() {
         let mut graph = CdfGraph::new();
@@ -1026,8 +1005,10 @@ mod tests {
 
         // Verify box-drawing characters
         let plain = strip_ansi(&output);
-        let has_box_chars =
-            plain.contains('═') || plain.contains('─') || plain.contains('╌') || plain.contains('┄');
+        let has_box_chars = plain.contains('═')
+            || plain.contains('─')
+            || plain.contains('╌')
+            || plain.contains('┄');
         assert!(
             has_box_chars,
             "Output should contain box-drawing characters"
@@ -1143,6 +1124,7 @@ mod tests {
                 state: "ACCEPTED".into(),
                 status: "ACTIVE".into(),
                 roots: vec!["/data".into()],
+                cache: None,
             },
         );
 
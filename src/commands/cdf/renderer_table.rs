@@ -2,11 +2,13 @@ use console::Style;
 use petgraph::visit::EdgeRef;
 
 use super::types::*;
+use crate::commands::status::renderer::{format_bytes, format_throughput};
 
 /// Render the CDF graph as a grouped-by-cluster adjacency table with status columns.
 ///
 /// Format: cluster heading, then indented rows showing target, type, mode, status, lag, path.
-pub fn render_table(graph: &CdfGraph) -> String {
+/// `wide` adds a CACHE column with spoke cache pressure for portals.
+pub fn render_table(graph: &CdfGraph, wide: bool) -> String {
     if graph.node_count() == 0 {
         return "(no CDF relationships found)\n".to_string();
     }
@@ -41,11 +43,18 @@ pub fn render_table(graph: &CdfGraph) -> String {
                 Style::new().dim().apply_to("(no outgoing relationships)")
             ));
         } else {
-            render_column_headers(&mut out);
+            render_column_headers(&mut out, wide);
             for edge_ref in &edges {
                 let target_node = &graph[edge_ref.target()];
                 let edge = edge_ref.weight();
-                render_edge_row(&mut out, target_node, edge);
+                render_edge_row(&mut out, target_node, edge, wide);
+                if let Some(progress) = format_job_progress(edge) {
+                    out.push_str(&format!(
+                        "  {:<22} {}\n",
+                        "",
+                        Style::new().dim().apply_to(format!("↳ {}", progress))
+                    ));
+                }
             }
         }
         out.push('\n');
@@ -83,37 +92,124 @@ fn render_cluster_heading(out: &mut String, node: &CdfNode) {
     out.push_str(&format!("{}\n", bold.apply_to(heading)));
 }
 
-fn render_column_headers(out: &mut String) {
+fn render_column_headers(out: &mut String, wide: bool) {
     let dim = Style::new().dim();
+    let cache = if wide {
+        format!(
+            "{} ",
+            pad_styled(&dim.apply_to("CACHE").to_string(), "CACHE", 24)
+        )
+    } else {
+        String::new()
+    };
     out.push_str(&format!(
-        "  {:<22} {:<8} {:<12} {:<12} {:<8} {}\n",
+        "  {:<22} {:<8} {:<12} {:<12} {:<8} {}{}\n",
         dim.apply_to("TARGET"),
         dim.apply_to("TYPE"),
         dim.apply_to("MODE"),
         dim.apply_to("STATUS"),
         dim.apply_to("LAG"),
+        cache,
         dim.apply_to("PATH"),
     ));
 }
 
-fn render_edge_row(out: &mut String, target: &CdfNode, edge: &CdfEdge) {
+fn render_edge_row(out: &mut String, target: &CdfNode, edge: &CdfEdge, wide: bool) {
     let target_name = node_label(target);
     let (edge_type, mode, path, status, lag) = extract_edge_fields(edge);
 
     let style = edge_style(edge);
     let status_style = status_color(&status);
+    let cache = if wide {
+        let text = format_cache(edge);
+        format!("{} ", pad_styled(&text, &text, 24))
+    } else {
+        String::new()
+    };
 
     out.push_str(&format!(
-        "  {:<22} {:<8} {:<12} {} {} {}\n",
+        "  {:<22} {:<8} {:<12} {} {} {}{}\n",
         style.apply_to(truncate(&target_name, 22)),
         style.apply_to(&edge_type),
         mode,
         pad_styled(&status_style.apply_to(&status).to_string(), &status, 12),
         pad_styled(&lag, &lag, 8),
+        cache,
         path,
     ));
 }
 
+/// Progress of a running replication job, e.g.
+/// "50% · 125.0 MB/s · 1.2 GB remaining · ETA 3m".
+fn format_job_progress(edge: &CdfEdge) -> Option<String> {
+    let CdfEdge::Replication {
+        replication_job_status: Some(job),
+        ..
+    } = edge
+    else {
+        return None;
+    };
+
+    let mut parts = Vec::new();
+    if let Some(pct) = job
+        .percent_complete
+        .as_deref()
+        .and_then(|p| p.parse::<f64>().ok())
+    {
+        parts.push(format!("{:.0}%", pct));
+    }
+    if let Some(throughput) = job.throughput() {
+        parts.push(format_throughput(throughput));
+    }
+    if let Some(remaining) = job.bytes_remaining() {
+        parts.push(format!("{} remaining", format_bytes(remaining)));
+    }
+    if let Some(eta) = job.eta_seconds() {
+        parts.push(format!("ETA {}", format_eta(eta)));
+    }
+    (!parts.is_empty()).then(|| parts.join(" \u{00b7} "))
+}
+
+fn format_eta(secs: u64) -> String {
+    if secs >= 86_400 {
+        format!("{}d{}h", secs / 86_400, secs % 86_400 / 3_600)
+    } else if secs >= 3_600 {
+        format!("{}h{:02}m", secs / 3_600, secs % 3_600 / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs.div_ceil(60))
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Spoke cache pressure for portal edges, e.g. "8% free, evicting <15%"
+/// when the spoke cluster is under its eviction threshold, or
+/// "40% free, evicts <15%" when it isn't yet.
+fn format_cache(edge: &CdfEdge) -> String {
+    let CdfEdge::Portal {
+        cache: Some(cache), ..
+    } = edge
+    else {
+        return "-".into();
+    };
+
+    let mut parts = Vec::new();
+    if let Some(pct) = cache.free_pct() {
+        parts.push(format!("{:.0}% free", pct));
+    }
+    match (cache.free_threshold, cache.evicting()) {
+        (Some(t), _) if t <= 0.0 => parts.push("no eviction".into()),
+        (Some(t), Some(true)) => parts.push(format!("evicting <{:.0}%", t * 100.0)),
+        (Some(t), _) => parts.push(format!("evicts <{:.0}%", t * 100.0)),
+        (None, _) => {}
+    }
+    if parts.is_empty() {
+        "-".into()
+    } else {
+        parts.join(", ")
+    }
+}
+
 /// Returns (type, mode, path, status, lag).
 fn extract_edge_fields(edge: &CdfEdge) -> (String, String, String, String, String) {
     match edge {
@@ -179,17 +275,9 @@ fn extract_edge_fields(edge: &CdfEdge) -> (String, String, String, String, Strin
                 "COPY_FROM_OBJECT" => "copy-from",
                 other => other,
             };
-            let status = shorten_status(
-                &state.as_deref().unwrap_or("?").to_lowercase(),
-            );
+            let status = shorten_status(&state.as_deref().unwrap_or("?").to_lowercase());
             let path = folder.as_deref().unwrap_or("-").to_string();
-            (
-                "S3".into(),
-                short_dir.into(),
-                path,
-                status,
-                "-".into(),
-            )
+            ("S3".into(), short_dir.into(), path, status, "-".into())
         }
     }
 }
@@ -234,9 +322,11 @@ fn format_recovery_lag(recovery_point: Option<&str>) -> String {
         return rp.to_string();
     };
 
-    let now = chrono::Utc::now();
-    let delta = now.signed_duration_since(parsed);
+    format_age(chrono::Utc::now().signed_duration_since(parsed))
+}
 
+/// Format an elapsed duration as a coarse "Nd ago" / "Nh ago" style string.
+pub(super) fn format_age(delta: chrono::Duration) -> String {
     if delta.num_seconds() < 0 {
         return "0s".into();
     }
@@ -262,7 +352,7 @@ fn format_replication_path(source: Option<&str>, target: Option<&str>) -> String
     }
 }
 
-fn node_label(node: &CdfNode) -> String {
+pub(super) fn node_label(node: &CdfNode) -> String {
     match node {
         CdfNode::ProfiledCluster { name, .. } => name.clone(),
         CdfNode::UnknownCluster { address, .. } => {
@@ -389,6 +479,7 @@ mod tests {
                 state: "ACCEPTED".into(),
                 status: "ACTIVE".into(),
                 roots: vec!["/data".into()],
+                cache: None,
             },
         );
         graph.add_edge(
@@ -407,22 +498,78 @@ mod tests {
     #[test]
     fn test_render_table_empty() {
         let graph = CdfGraph::new();
-        let output = render_table(&graph);
+        let output = render_table(&graph, false);
         assert_eq!(output, "(no CDF relationships found)\n");
     }
 
     #[test]
     fn test_render_table_header() {
         let graph = make_test_graph();
-        let output = render_table(&graph);
+        let output = render_table(&graph, false);
         assert!(output.contains("Data Fabric Status"));
         assert!(output.contains("3 clusters, 3 relationships"));
     }
 
+    #[test]
+    fn test_render_table_replication_progress() {
+        let graph = make_test_graph();
+        let output = render_table(&graph, false);
+        // The fixture job reports no bytes_remaining, so there is no ETA
+        assert!(output.contains("↳ 50% · 125.0 MB/s"));
+    }
+
+    #[test]
+    fn test_format_job_progress_eta() {
+        let edge = CdfEdge::Replication {
+            source_path: None,
+            target_path: None,
+            mode: None,
+            enabled: true,
+            state: Some("ESTABLISHED".into()),
+            job_state: Some("REPLICATION_RUNNING".into()),
+            recovery_point: None,
+            error_from_last_job: None,
+            replication_job_status: Some(
+                serde_json::from_value(serde_json::json!({
+                    "bytes_remaining": "2147483648",
+                    "throughput_current": "1048576"
+                }))
+                .unwrap(),
+            ),
+        };
+        assert_eq!(
+            format_job_progress(&edge).unwrap(),
+            "1.0 MB/s · 2.0 GB remaining · ETA 35m"
+        );
+        assert_eq!(format_eta(45), "45s");
+        assert_eq!(format_eta(7_260), "2h01m");
+        assert_eq!(format_eta(90_000), "1d1h");
+    }
+
+    #[test]
+    fn test_render_table_wide_shows_portal_cache() {
+        let mut graph = make_test_graph();
+        for edge in graph.edge_weights_mut() {
+            if let CdfEdge::Portal { cache, .. } = edge {
+                *cache = Some(PortalCacheStats {
+                    free_threshold: Some(0.15),
+                    free_size_bytes: Some("80".into()),
+                    total_size_bytes: Some("1000".into()),
+                });
+            }
+        }
+        let narrow = render_table(&graph, false);
+        assert!(!narrow.contains("CACHE"));
+
+        let wide = render_table(&graph, true);
+        assert!(wide.contains("CACHE"));
+        assert!(wide.contains("8% free, evicting <15%"));
+    }
+
     #[test]
     fn test_render_table_cluster_headings() {
         let graph = make_test_graph();
-        let output = render_table(&graph);
+        let output = render_table(&graph, false);
         assert!(output.contains("gravytrain"));
         assert!(output.contains("iss"));
     }
@@ -430,7 +577,7 @@ mod tests {
     #[test]
     fn test_render_table_edge_rows() {
         let graph = make_test_graph();
-        let output = render_table(&graph);
+        let output = render_table(&graph, false);
         // Check replication row
         assert!(output.contains("repl"));
         assert!(output.contains("continuous"));
@@ -447,7 +594,7 @@ mod tests {
     #[test]
     fn test_render_table_path_column() {
         let graph = make_test_graph();
-        let output = render_table(&graph);
+        let output = render_table(&graph, false);
         // Replication edge should show "src → dst" path
         assert!(output.contains("/data"));
         // Portal edge should show roots
@@ -458,8 +605,14 @@ mod tests {
 
     #[test]
     fn test_format_replication_path() {
-        assert_eq!(format_replication_path(Some("/src"), Some("/dst")), "/src \u{2192} /dst");
-        assert_eq!(format_replication_path(Some("/same"), Some("/same")), "/same");
+        assert_eq!(
+            format_replication_path(Some("/src"), Some("/dst")),
+            "/src \u{2192} /dst"
+        );
+        assert_eq!(
+            format_replication_path(Some("/same"), Some("/same")),
+            "/same"
+        );
         assert_eq!(format_replication_path(Some("/src"), None), "/src");
         assert_eq!(format_replication_path(None, Some("/dst")), "/dst");
         assert_eq!(format_replication_path(None, None), "-");
@@ -521,7 +674,7 @@ mod tests {
                 replication_job_status: None,
             },
         );
-        let output = render_table(&graph);
+        let output = render_table(&graph, false);
         assert!(output.contains("disabled"));
     }
 }
@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use console::Style;
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+
+use super::renderer_table::{format_age, node_label};
+use super::types::*;
+
+/// Headline counts for a data fabric graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CdfSummary {
+    pub clusters: usize,
+    pub portal_links: usize,
+    pub replication_relationships: usize,
+    pub object_replication_relationships: usize,
+    pub problems: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worst_rpo: Option<WorstRpo>,
+}
+
+/// The replication relationship whose last recovery point is the oldest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorstRpo {
+    pub source: String,
+    pub target: String,
+    pub recovery_point: String,
+    pub lag_seconds: i64,
+}
+
+/// Summarize a graph. S3 buckets are not counted as clusters.
+pub fn summarize(graph: &CdfGraph, now: DateTime<Utc>) -> CdfSummary {
+    let clusters = graph
+        .node_weights()
+        .filter(|n| !matches!(n, CdfNode::S3Bucket { .. }))
+        .count();
+
+    let mut summary = CdfSummary {
+        clusters,
+        portal_links: 0,
+        replication_relationships: 0,
+        object_replication_relationships: 0,
+        problems: 0,
+        worst_rpo: None,
+    };
+
+    for edge_ref in graph.edge_references() {
+        let edge = edge_ref.weight();
+        if edge.is_problem() {
+            summary.problems += 1;
+        }
+        match edge {
+            CdfEdge::Portal { .. } => summary.portal_links += 1,
+            CdfEdge::ObjectReplication { .. } => summary.object_replication_relationships += 1,
+            CdfEdge::Replication { recovery_point, .. } => {
+                summary.replication_relationships += 1;
+                let Some(rp) = recovery_point else { continue };
+                let Ok(parsed) = DateTime::parse_from_rfc3339(rp) else {
+                    continue;
+                };
+                let lag_seconds = now.signed_duration_since(parsed).num_seconds().max(0);
+                if summary
+                    .worst_rpo
+                    .as_ref()
+                    .is_none_or(|w| lag_seconds > w.lag_seconds)
+                {
+                    summary.worst_rpo = Some(WorstRpo {
+                        source: node_label(&graph[edge_ref.source()]),
+                        target: node_label(&graph[edge_ref.target()]),
+                        recovery_point: rp.clone(),
+                        lag_seconds,
+                    });
+                }
+            }
+        }
+    }
+
+    summary
+}
+
+/// Render the summary as a single headline line.
+pub fn render(summary: &CdfSummary) -> String {
+    let mut parts = vec![
+        plural(summary.clusters, "cluster", "clusters"),
+        plural(summary.portal_links, "portal link", "portal links"),
+        plural(
+            summary.replication_relationships,
+            "replication relationship",
+            "replication relationships",
+        ),
+    ];
+    if summary.object_replication_relationships > 0 {
+        parts.push(plural(
+            summary.object_replication_relationships,
+            "object replication",
+            "object replications",
+        ));
+    }
+
+    let problems = plural(summary.problems, "problem", "problems");
+    parts.push(if summary.problems > 0 {
+        Style::new().red().bold().apply_to(problems).to_string()
+    } else {
+        Style::new().green().apply_to(problems).to_string()
+    });
+
+    if let Some(ref worst) = summary.worst_rpo {
+        parts.push(format!(
+            "worst RPO {} ({} → {})",
+            format_age(chrono::Duration::seconds(worst.lag_seconds)),
+            worst.source,
+            worst.target
+        ));
+    }
+
+    format!("Summary: {}\n", parts.join(" \u{00b7} "))
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::graph::DiGraph;
+
+    fn replication(recovery_point: Option<&str>, enabled: bool) -> CdfEdge {
+        CdfEdge::Replication {
+            source_path: Some("/data".into()),
+            target_path: Some("/data".into()),
+            mode: None,
+            enabled,
+            state: Some("ESTABLISHED".into()),
+            job_state: None,
+            recovery_point: recovery_point.map(String::from),
+            error_from_last_job: None,
+            replication_job_status: None,
+        }
+    }
+
+    fn make_graph() -> CdfGraph {
+        let mut graph = DiGraph::new();
+        let a = graph.add_node(CdfNode::ProfiledCluster {
+            name: "gravytrain".into(),
+            uuid: "uuid-a".into(),
+            address: "10.0.0.1".into(),
+        });
+        let b = graph.add_node(CdfNode::ProfiledCluster {
+            name: "iss".into(),
+            uuid: "uuid-b".into(),
+            address: "10.0.1.1".into(),
+        });
+        let c = graph.add_node(CdfNode::UnknownCluster {
+            address: "10.9.9.9".into(),
+            uuid: None,
+        });
+        let s3 = graph.add_node(CdfNode::S3Bucket {
+            address: "s3.amazonaws.com".into(),
+            bucket: "backups".into(),
+            region: None,
+        });
+        graph.add_edge(a, b, replication(Some("2026-03-01T09:00:00Z"), true));
+        graph.add_edge(a, c, replication(Some("2026-02-28T10:00:00Z"), false));
+        graph.add_edge(b, a, replication(None, true));
+        graph.add_edge(
+            a,
+            b,
+            CdfEdge::Portal {
+                hub_id: 1,
+                spoke_id: 2,
+                portal_type: "PORTAL_READ_WRITE".into(),
+                state: "ACCEPTED".into(),
+                status: "ACTIVE".into(),
+                roots: vec![],
+                cache: None,
+            },
+        );
+        graph.add_edge(
+            b,
+            s3,
+            CdfEdge::ObjectReplication {
+                direction: None,
+                bucket: Some("backups".into()),
+                folder: None,
+                state: Some("ACTIVE".into()),
+            },
+        );
+        graph
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_summarize_counts_and_worst_rpo() {
+        let summary = summarize(&make_graph(), now());
+        assert_eq!(summary.clusters, 3);
+        assert_eq!(summary.portal_links, 1);
+        assert_eq!(summary.replication_relationships, 3);
+        assert_eq!(summary.object_replication_relationships, 1);
+        assert_eq!(summary.problems, 1);
+
+        let worst = summary.worst_rpo.unwrap();
+        assert_eq!(worst.source, "gravytrain");
+        assert_eq!(worst.target, "10.9.9.9 (unknown)");
+        assert_eq!(worst.lag_seconds, 86_400);
+    }
+
+    #[test]
+    fn test_summarize_empty_graph() {
+        let summary = summarize(&CdfGraph::new(), now());
+        assert_eq!(summary.clusters, 0);
+        assert_eq!(summary.problems, 0);
+        assert!(summary.worst_rpo.is_none());
+    }
+
+    #[test]
+    fn test_render_summary_line() {
+        let line = render(&summarize(&make_graph(), now()));
+        assert!(line.starts_with(
+            "Summary: 3 clusters · 1 portal link · 3 replication relationships · 1 object replication · "
+        ));
+        assert!(line.contains("1 problem"));
+        assert!(line.ends_with("worst RPO 1d ago (gravytrain → 10.9.9.9 (unknown))\n"));
+    }
+}
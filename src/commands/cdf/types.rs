@@ -130,6 +130,74 @@ pub struct ReplicationJobStatus {
     pub throughput_current: Option<String>,
 }
 
+impl ReplicationJobStatus {
+    /// Current throughput in bytes/sec, falling back to the job's overall rate.
+    pub fn throughput(&self) -> Option<f64> {
+        let positive = |v: &Option<String>| parse_num(v.as_deref()).filter(|t| *t > 0.0);
+        positive(&self.throughput_current).or_else(|| positive(&self.throughput_overall))
+    }
+
+    pub fn bytes_remaining(&self) -> Option<u64> {
+        parse_num(self.bytes_remaining.as_deref()).map(|b| b as u64)
+    }
+
+    /// Seconds until the job catches up: the API's own estimate when given,
+    /// otherwise bytes remaining divided by throughput.
+    pub fn eta_seconds(&self) -> Option<u64> {
+        if let Some(eta) = parse_num(self.estimated_seconds_remaining.as_deref()) {
+            return Some(eta as u64);
+        }
+        let remaining = self.bytes_remaining()?;
+        let throughput = self.throughput()?;
+        Some((remaining as f64 / throughput).ceil() as u64)
+    }
+}
+
+fn parse_num(value: Option<&str>) -> Option<f64> {
+    value?
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+}
+
+/// Spoke-side portal cache pressure.
+///
+/// Spoke portals cache remote data in the spoke cluster's own capacity, and
+/// the cluster evicts cached data to keep `free_threshold` of it free. A
+/// spoke cluster at or below its threshold is evicting, i.e. its cache is
+/// undersized. The byte counts come from `/v1/file-system` and use the same
+/// string-or-number handling as [`ReplicationJobStatus`].
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct PortalCacheStats {
+    /// Fraction of capacity eviction tries to keep free, 0.0 to 1.0
+    #[serde(default)]
+    pub free_threshold: Option<f64>,
+    #[serde(default, deserialize_with = "deserialize_string_or_number")]
+    pub free_size_bytes: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_number")]
+    pub total_size_bytes: Option<String>,
+}
+
+impl PortalCacheStats {
+    /// Free capacity as a percentage of the spoke cluster's total, when known.
+    pub fn free_pct(&self) -> Option<f64> {
+        let free: f64 = self.free_size_bytes.as_deref()?.parse().ok()?;
+        let total: f64 = self.total_size_bytes.as_deref()?.parse().ok()?;
+        (total > 0.0).then(|| free / total * 100.0)
+    }
+
+    /// Whether free capacity is at or below the eviction threshold, so the
+    /// cluster is evicting cached portal data. A threshold of 0 disables
+    /// eviction.
+    pub fn evicting(&self) -> Option<bool> {
+        let threshold = self.free_threshold?;
+        if threshold <= 0.0 {
+            return Some(false);
+        }
+        Some(self.free_pct()? <= threshold * 100.0)
+    }
+}
+
 /// Deserialize a JSON value that may be either a string or a number into `Option<String>`.
 ///
 /// The Qumulo replication API is inconsistent: some numeric fields are returned as
@@ -326,6 +394,7 @@ pub enum CdfNode {
 
 /// An edge in the CDF relationship graph.
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum CdfEdge {
     Portal {
         hub_id: u64,
@@ -334,6 +403,8 @@ pub enum CdfEdge {
         state: String,
         status: String,
         roots: Vec<String>,
+        /// Cache pressure on the spoke's cluster, if it is a profiled cluster.
+        cache: Option<PortalCacheStats>,
     },
     Replication {
         source_path: Option<String>,
@@ -355,6 +426,24 @@ pub enum CdfEdge {
 }
 
 impl CdfEdge {
+    /// Filesystem root paths this relationship covers on either side.
+    /// Object replication edges have none.
+    pub fn root_paths(&self) -> Vec<&str> {
+        match self {
+            CdfEdge::Portal { roots, .. } => roots.iter().map(String::as_str).collect(),
+            CdfEdge::Replication {
+                source_path,
+                target_path,
+                ..
+            } => source_path
+                .iter()
+                .chain(target_path.iter())
+                .map(String::as_str)
+                .collect(),
+            CdfEdge::ObjectReplication { .. } => Vec::new(),
+        }
+    }
+
     /// Returns true if this edge represents a problematic relationship:
     /// disabled, errored, or in an unhealthy state.
     pub fn is_problem(&self) -> bool {
@@ -369,15 +458,9 @@ impl CdfEdge {
                 state,
                 ..
             } => {
-                !enabled
-                    || error_from_last_job.is_some()
-                    || state
-                        .as_deref()
-                        .map_or(true, |s| s != "ESTABLISHED")
-            }
-            CdfEdge::ObjectReplication { state, .. } => {
-                state.as_deref().map_or(true, |s| s != "ACTIVE")
+                !enabled || error_from_last_job.is_some() || state.as_deref() != Some("ESTABLISHED")
             }
+            CdfEdge::ObjectReplication { state, .. } => state.as_deref() != Some("ACTIVE"),
         }
     }
 }
@@ -412,7 +495,10 @@ mod tests {
         assert_eq!(list.entries[0].state, "ACCEPTED");
         assert_eq!(list.entries[0].status, "ACTIVE");
         assert_eq!(list.entries[0].spoke_hosts[0].address, "10.0.0.1");
-        assert_eq!(list.entries[0].spoke_cluster_uuid.as_deref(), Some("abc-123"));
+        assert_eq!(
+            list.entries[0].spoke_cluster_uuid.as_deref(),
+            Some("abc-123")
+        );
         assert_eq!(list.entries[0].authorized_roots, vec!["/data"]);
     }
 
@@ -474,7 +560,10 @@ mod tests {
         assert_eq!(sources.len(), 1);
         assert_eq!(sources[0].id, "075d8b86-8e28-40f2-921d-8a1b6585475a");
         assert!(sources[0].replication_enabled);
-        assert_eq!(sources[0].replication_mode.as_deref(), Some("REPLICATION_CONTINUOUS"));
+        assert_eq!(
+            sources[0].replication_mode.as_deref(),
+            Some("REPLICATION_CONTINUOUS")
+        );
     }
 
     #[test]
@@ -509,7 +598,10 @@ mod tests {
         }]);
         let statuses: Vec<ReplicationSourceStatus> = serde_json::from_value(data).unwrap();
         assert_eq!(statuses[0].state.as_deref(), Some("ESTABLISHED"));
-        assert_eq!(statuses[0].job_state.as_deref(), Some("REPLICATION_RUNNING"));
+        assert_eq!(
+            statuses[0].job_state.as_deref(),
+            Some("REPLICATION_RUNNING")
+        );
         let job = statuses[0].replication_job_status.as_ref().unwrap();
         assert_eq!(job.percent_complete.as_deref(), Some("75.5"));
     }
@@ -644,6 +736,7 @@ mod tests {
                 state: "ACCEPTED".into(),
                 status: "ACTIVE".into(),
                 roots: vec!["/data".into()],
+                cache: None,
             },
         );
         graph.add_edge(
@@ -760,4 +853,30 @@ mod tests {
         assert_eq!(status.throughput_current.as_deref(), Some("131072000"));
         assert_eq!(status.throughput_overall.as_deref(), Some("98304000"));
     }
+
+    #[test]
+    fn test_job_status_eta() {
+        // API estimate wins when present
+        let status: ReplicationJobStatus = serde_json::from_value(json!({
+            "estimated_seconds_remaining": "120",
+            "bytes_remaining": "1000000",
+            "throughput_current": "1000"
+        }))
+        .unwrap();
+        assert_eq!(status.eta_seconds(), Some(120));
+
+        // Otherwise derived from remaining bytes and throughput
+        let status: ReplicationJobStatus = serde_json::from_value(json!({
+            "bytes_remaining": 10_000_001,
+            "throughput_current": "0",
+            "throughput_overall": 1_000_000
+        }))
+        .unwrap();
+        assert_eq!(status.throughput(), Some(1_000_000.0));
+        assert_eq!(status.eta_seconds(), Some(11));
+
+        let status: ReplicationJobStatus =
+            serde_json::from_value(json!({"bytes_remaining": "500"})).unwrap();
+        assert_eq!(status.eta_seconds(), None);
+    }
 }
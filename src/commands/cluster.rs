@@ -1,24 +1,27 @@
-use anyhow::Result;
-use serde_json::json;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
 
 use crate::client::QumuloClient;
-use crate::output::{print_table, print_value};
+use crate::confirm::Guard;
+use crate::error::QontrolError;
+use crate::output::{emit, format_value, print_json, print_table, print_value, OutputFormat};
 
-pub fn info(client: &QumuloClient, json_mode: bool) -> Result<()> {
+pub fn info(client: &QumuloClient, format: OutputFormat) -> Result<()> {
     let settings = client.get_cluster_settings()?;
     let version = client.get_version()?;
     let nodes = client.get_cluster_nodes()?;
 
-    if json_mode {
+    if format.is_structured() {
         let combined = json!({
             "cluster": settings,
             "version": version,
             "nodes": nodes,
         });
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&combined).unwrap_or_else(|_| combined.to_string())
-        );
+        // Row formats list the nodes, one per line
+        emit(&combined, "/nodes", format);
         return Ok(());
     }
 
@@ -44,3 +47,605 @@ pub fn info(client: &QumuloClient, json_mode: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Show the data protection configuration: stripe layout, protection type,
+/// failure tolerance, any pending reconfiguration, and fault domains.
+pub fn protection_show(client: &QumuloClient, json_mode: bool) -> Result<()> {
+    let status = client.get_cluster_protection_status()?;
+    let nodes = client.get_cluster_nodes()?;
+    // Only object-backed (CNQ) clusters report fault-domain tolerance
+    let fault_tolerance = match client.get_object_backed_fault_tolerance() {
+        Ok(ft) => Some(ft),
+        Err(e) => {
+            tracing::debug!(error = %e, "no object-backed fault tolerance");
+            None
+        }
+    };
+
+    if json_mode {
+        let combined = json!({
+            "protection": status,
+            "fault_tolerance": fault_tolerance,
+            "nodes": nodes,
+        });
+        print_json(&combined)?;
+        return Ok(());
+    }
+
+    let nodes = nodes.as_array().map(|a| a.as_slice()).unwrap_or(&[]);
+    let online = nodes.iter().filter(|n| is_online(n)).count();
+
+    println!(
+        "Protection:      {}",
+        protection_label(status["protection_system_type"].as_str().unwrap_or(""))
+    );
+    println!("Stripe:          {}", stripe_label(&status));
+    println!(
+        "Node failures:   {}",
+        failures_label(
+            &status["remaining_node_failures"],
+            &status["max_node_failures"]
+        )
+    );
+    println!(
+        "Drive failures:  {}",
+        failures_label(
+            &status["remaining_drive_failures"],
+            &status["max_drive_failures"]
+        )
+    );
+    println!(
+        "Pending change:  {}",
+        pending_label(&status["pending_configuration"])
+    );
+    println!();
+
+    println!("Fault domains");
+    match fault_tolerance {
+        Some(ft) => {
+            println!(
+                "  Can lose:      {} fault domain(s), {} node(s), {} disk(s)",
+                format_value(&ft["fault_domains"]),
+                format_value(&ft["nodes"]),
+                format_value(&ft["disks"])
+            );
+        }
+        None => println!("  Each node is a fault domain"),
+    }
+    println!("  Nodes:         {} ({} online)", nodes.len(), online);
+    for node in nodes {
+        println!(
+            "    {:>3}  {:<24} {}",
+            format_value(&node["id"]),
+            node["node_name"].as_str().unwrap_or("-"),
+            node["node_status"].as_str().unwrap_or("unknown")
+        );
+    }
+
+    Ok(())
+}
+
+/// Fragments of cipher suite names (IANA or OpenSSL spelling) that mark a
+/// suite as weak: broken ciphers, no encryption, export grade, anonymous
+/// key exchange, or MD5 MACs
+const WEAK_CIPHER_MARKERS: &[&str] = &[
+    "RC4", "3DES", "DES_CBC", "DES-CBC", "NULL", "EXPORT", "EXP-", "MD5", "ANON", "ADH",
+];
+
+/// The cipher suite lists the cluster reports, by protocol
+const TLS_PROTOCOLS: &[(&str, &str)] = &[("tls_1_2", "TLS 1.2"), ("tls_1_3", "TLS 1.3")];
+
+/// Show the cipher suites the cluster accepts for TLS 1.2 and TLS 1.3
+/// connections, with any weak-TLS findings.
+pub fn tls_show(client: &QumuloClient, json_mode: bool) -> Result<()> {
+    let ciphers = get_ssl_ciphers(client)?;
+    print_tls_settings(&ciphers, json_mode)
+}
+
+/// Replace the TLS 1.2 and/or TLS 1.3 cipher suites, and/or install a new
+/// certificate and private key (PEM files), after confirmation. Cipher
+/// lists not given stay as they are. The API has no minimum-version
+/// setting; the protocols are the ones it lists ciphers for.
+pub fn tls_set(
+    client: &QumuloClient,
+    guard: &Guard,
+    tls12_ciphers: Option<&str>,
+    tls13_ciphers: Option<&str>,
+    certificate: Option<&str>,
+    private_key: Option<&str>,
+    json_mode: bool,
+) -> Result<()> {
+    let certificate_pair = match (certificate, private_key) {
+        (Some(cert), Some(key)) => Some((read_pem(cert)?, read_pem(key)?)),
+        (None, None) => None,
+        _ => anyhow::bail!("--certificate and --private-key must be given together"),
+    };
+    let lists = [
+        ("tls_1_2", "--tls12-ciphers", tls12_ciphers),
+        ("tls_1_3", "--tls13-ciphers", tls13_ciphers),
+    ];
+    if certificate_pair.is_none() && lists.iter().all(|(_, _, l)| l.is_none()) {
+        anyhow::bail!(
+            "nothing to change: pass --tls12-ciphers, --tls13-ciphers, or --certificate with --private-key"
+        );
+    }
+    // The PUT replaces both lists, so start from the current ones. This
+    // also fails on clusters without the endpoint before asking to confirm.
+    let mut ciphers = get_ssl_ciphers(client)?;
+    for (key, flag, list) in lists {
+        let Some(list) = list else { continue };
+        let suites: Vec<Value> = list
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| Value::String(c.to_string()))
+            .collect();
+        if suites.is_empty() {
+            anyhow::bail!("{} needs at least one cipher suite", flag);
+        }
+        ciphers[key] = Value::Array(suites);
+    }
+
+    let confirmed = guard.confirm(
+        client,
+        "change TLS settings",
+        "Change the cluster's TLS settings? Clients that can't negotiate the new settings will be refused.",
+    )?;
+    if !confirmed {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+    if let Some((certificate, private_key)) = certificate_pair {
+        client.set_ssl_certificate(&json!({
+            "certificate": certificate,
+            "private_key": private_key,
+        }))?;
+    }
+    if lists.iter().any(|(_, _, l)| l.is_some()) {
+        client.set_ssl_ciphers(&ciphers)?;
+    }
+    let ciphers = client.get_ssl_ciphers()?;
+    if !json_mode {
+        println!("TLS settings updated.");
+        println!();
+    }
+    print_tls_settings(&ciphers, json_mode)
+}
+
+fn read_pem(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))
+}
+
+fn get_ssl_ciphers(client: &QumuloClient) -> Result<Value> {
+    client
+        .get_ssl_ciphers()
+        .map_err(|e| match e.downcast_ref::<QontrolError>() {
+            Some(QontrolError::ApiError { status: 404, .. }) => anyhow::anyhow!(
+                "this cluster's API does not expose TLS cipher settings (/v2/cluster/settings/ssl/ciphers)"
+            ),
+            _ => e,
+        })
+}
+
+fn print_tls_settings(ciphers: &Value, json_mode: bool) -> Result<()> {
+    let findings = weak_tls_findings(ciphers);
+    if json_mode {
+        let combined = json!({
+            "ciphers": ciphers,
+            "findings": findings,
+        });
+        print_json(&combined)?;
+        return Ok(());
+    }
+
+    for (i, (key, label)) in TLS_PROTOCOLS.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let suites = cipher_suites(ciphers, key);
+        if suites.is_empty() {
+            println!("{} cipher suites: none", label);
+            continue;
+        }
+        println!("{} cipher suites: {}", label, suites.len());
+        for suite in suites {
+            println!("  {}", suite);
+        }
+    }
+    println!();
+    if findings.is_empty() {
+        println!("No weak TLS settings found.");
+    } else {
+        println!("Findings");
+        for finding in &findings {
+            println!("  ⚠ {}", finding);
+        }
+    }
+    Ok(())
+}
+
+/// Weak-TLS findings for the cluster's cipher settings: each enabled
+/// cipher suite known to be weak, by protocol.
+pub fn weak_tls_findings(ciphers: &Value) -> Vec<String> {
+    let mut findings = Vec::new();
+    for (key, label) in TLS_PROTOCOLS {
+        for suite in cipher_suites(ciphers, key) {
+            let upper = suite.to_ascii_uppercase();
+            if WEAK_CIPHER_MARKERS.iter().any(|m| upper.contains(m)) {
+                findings.push(format!("weak {} cipher suite enabled: {}", label, suite));
+            }
+        }
+    }
+    findings
+}
+
+fn cipher_suites<'a>(ciphers: &'a Value, key: &str) -> Vec<&'a str> {
+    ciphers[key]
+        .as_array()
+        .map(|a| a.iter().filter_map(|c| c.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// A disruptive single-node action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeAction {
+    Reboot,
+    RestartServices,
+}
+
+impl NodeAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            NodeAction::Reboot => "reboot",
+            NodeAction::RestartServices => "restart-services",
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            NodeAction::Reboot => "Reboot",
+            NodeAction::RestartServices => "Restart Qumulo Core services on",
+        }
+    }
+
+    fn requested(self) -> &'static str {
+        match self {
+            NodeAction::Reboot => "Reboot",
+            NodeAction::RestartServices => "Service restart",
+        }
+    }
+}
+
+/// Reboot a node or restart its services, after confirmation, then poll the
+/// cluster until the node is back online and report fault tolerance.
+///
+/// Confirmation follows the profile's `[confirm]` policy (see [`Guard`]);
+/// when stdin is not a terminal the action is refused rather than run
+/// unattended.
+#[allow(clippy::too_many_arguments)]
+pub fn node_action(
+    client: &QumuloClient,
+    guard: &Guard,
+    action: NodeAction,
+    node_id: u64,
+    no_wait: bool,
+    wait_timeout_secs: u64,
+    interval_secs: u64,
+    json_mode: bool,
+) -> Result<()> {
+    let nodes = client.get_cluster_nodes()?;
+    let Some(node) = find_node(&nodes, node_id) else {
+        anyhow::bail!("node {} not found in cluster", node_id);
+    };
+    let node_name = node["node_name"].as_str().unwrap_or("unknown").to_string();
+
+    let confirmed = guard.confirm(
+        client,
+        &format!("{} node {}", action.as_str(), node_id),
+        &format!("{} node {} ({})?", action.describe(), node_id, node_name),
+    )?;
+    if !confirmed {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    match action {
+        NodeAction::Reboot => client.reboot_node(node_id)?,
+        NodeAction::RestartServices => client.restart_node_services(node_id)?,
+    };
+    if !json_mode {
+        println!(
+            "{} requested for node {} ({})",
+            action.requested(),
+            node_id,
+            node_name
+        );
+    }
+
+    if no_wait {
+        if json_mode {
+            let result = json!({
+                "node_id": node_id,
+                "action": action.as_str(),
+                "requested": true,
+            });
+            print_json(&result)?;
+        }
+        return Ok(());
+    }
+
+    if !json_mode {
+        println!("Waiting for node {} to come back online...", node_id);
+    }
+    let health = wait_for_node(client, node_id, wait_timeout_secs, interval_secs)?;
+
+    if json_mode {
+        let result = json!({
+            "node_id": node_id,
+            "action": action.as_str(),
+            "requested": true,
+            "health": health,
+        });
+        print_json(&result)?;
+    } else {
+        println!(
+            "Node {} online after {}s",
+            node_id,
+            format_value(&health["elapsed_secs"])
+        );
+        println!(
+            "Nodes online:    {} of {}",
+            format_value(&health["nodes_online"]),
+            format_value(&health["nodes_total"])
+        );
+        println!(
+            "Node failures:   {}",
+            failures_label(
+                &health["remaining_node_failures"],
+                &health["max_node_failures"]
+            )
+        );
+        println!(
+            "Drive failures:  {}",
+            failures_label(
+                &health["remaining_drive_failures"],
+                &health["max_drive_failures"]
+            )
+        );
+        if health["data_at_risk"].as_bool() == Some(true) {
+            println!("Restriper:       DATA AT RISK — restriper active");
+        }
+    }
+    Ok(())
+}
+
+/// Poll until `node_id` has gone down and come back online, then take a
+/// health snapshot.
+///
+/// The node counts as down once it reports anything but online, drops out of
+/// the node list, or the cluster stops answering (as a single-node cluster
+/// does while it reboots). Until then an online node is still waiting to
+/// start its restart, so it doesn't count as back. The first poll happens one
+/// interval after the action; API errors while the node is down are retried.
+fn wait_for_node(
+    client: &QumuloClient,
+    node_id: u64,
+    timeout_secs: u64,
+    interval_secs: u64,
+) -> Result<Value> {
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(timeout_secs);
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let mut went_down = false;
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            if !went_down {
+                anyhow::bail!(
+                    "timed out after {}s waiting for node {} to go offline; \
+                     the restart may not have started",
+                    timeout_secs,
+                    node_id
+                );
+            }
+            anyhow::bail!(
+                "timed out after {}s waiting for node {} to come back online",
+                timeout_secs,
+                node_id
+            );
+        }
+        thread::sleep(interval.min(deadline - now));
+
+        let nodes = match client.get_cluster_nodes() {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                if e.downcast_ref::<QontrolError>().is_none() {
+                    went_down = true;
+                }
+                tracing::warn!("node poll failed, will retry: {:#}", e);
+                continue;
+            }
+        };
+        if !find_node(&nodes, node_id).is_some_and(is_online) {
+            if !went_down {
+                tracing::debug!(node_id, "node went offline");
+            }
+            went_down = true;
+            continue;
+        }
+        if !went_down {
+            tracing::debug!(node_id, "node still online, waiting for it to go down");
+            continue;
+        }
+
+        let all = nodes.as_array().map(|a| a.as_slice()).unwrap_or(&[]);
+        let protection = client
+            .get_cluster_protection_status()
+            .unwrap_or(Value::Null);
+        let data_at_risk = client
+            .get_cluster_restriper_status()
+            .ok()
+            .and_then(|r| r["data_at_risk"].as_bool());
+        return Ok(json!({
+            "elapsed_secs": start.elapsed().as_secs(),
+            "nodes_total": all.len(),
+            "nodes_online": all.iter().filter(|n| is_online(n)).count(),
+            "remaining_node_failures": protection["remaining_node_failures"],
+            "max_node_failures": protection["max_node_failures"],
+            "remaining_drive_failures": protection["remaining_drive_failures"],
+            "max_drive_failures": protection["max_drive_failures"],
+            "data_at_risk": data_at_risk,
+        }));
+    }
+}
+
+fn find_node(nodes: &Value, node_id: u64) -> Option<&Value> {
+    nodes
+        .as_array()?
+        .iter()
+        .find(|n| n["id"].as_u64() == Some(node_id))
+}
+
+fn is_online(node: &Value) -> bool {
+    node["node_status"]
+        .as_str()
+        .is_some_and(|s| s.eq_ignore_ascii_case("online"))
+}
+
+/// Human label for a `protection_system_type` value
+pub(crate) fn protection_label(raw: &str) -> String {
+    match raw.trim_start_matches("PROTECTION_SYSTEM_TYPE_") {
+        "" => "unknown".to_string(),
+        "EC" => "erasure coding".to_string(),
+        "OBJECT_BACKED" => "object-backed".to_string(),
+        other => other.to_lowercase().replace('_', " "),
+    }
+}
+
+/// Describe the stripe as `data+parity` with its storage efficiency, e.g. `6+2 (75% efficient)`
+pub(crate) fn stripe_label(status: &Value) -> String {
+    let (Some(blocks), Some(data)) = (
+        status["blocks_per_stripe"].as_u64(),
+        status["data_blocks_per_stripe"].as_u64(),
+    ) else {
+        return "-".to_string();
+    };
+    if blocks == 0 {
+        return "-".to_string();
+    }
+    format!(
+        "{}+{} ({:.0}% efficient)",
+        data,
+        blocks.saturating_sub(data),
+        data as f64 / blocks as f64 * 100.0
+    )
+}
+
+/// `remaining of max remaining`, flagging exhausted tolerance
+pub(crate) fn failures_label(remaining: &Value, max: &Value) -> String {
+    let label = format!(
+        "{} of {} remaining",
+        format_value(remaining),
+        format_value(max)
+    );
+    if remaining.as_u64() == Some(0) && max.as_u64().is_some_and(|m| m > 0) {
+        format!("{} — degraded", label)
+    } else {
+        label
+    }
+}
+
+fn pending_label(pending: &Value) -> String {
+    if !pending.is_object() {
+        return "none".to_string();
+    }
+    format!(
+        "{} blocks per stripe, tolerating {} node / {} drive failure(s)",
+        format_value(&pending["blocks_per_stripe"]),
+        format_value(&pending["max_node_failures"]),
+        format_value(&pending["max_drive_failures"])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_node_and_online() {
+        let nodes = json!([
+            {"id": 1, "node_status": "online"},
+            {"id": 2, "node_status": "offline"}
+        ]);
+        assert!(find_node(&nodes, 1).is_some_and(is_online));
+        assert!(!find_node(&nodes, 2).is_some_and(is_online));
+        assert!(find_node(&nodes, 3).is_none());
+        assert!(find_node(&json!({}), 1).is_none());
+    }
+
+    #[test]
+    fn test_protection_label() {
+        assert_eq!(
+            protection_label("PROTECTION_SYSTEM_TYPE_EC"),
+            "erasure coding"
+        );
+        assert_eq!(
+            protection_label("PROTECTION_SYSTEM_TYPE_OBJECT_BACKED"),
+            "object-backed"
+        );
+        assert_eq!(protection_label(""), "unknown");
+    }
+
+    #[test]
+    fn test_stripe_label() {
+        let status = json!({"blocks_per_stripe": 8, "data_blocks_per_stripe": 6});
+        assert_eq!(stripe_label(&status), "6+2 (75% efficient)");
+        assert_eq!(stripe_label(&json!({})), "-");
+    }
+
+    #[test]
+    fn test_failures_label_flags_degraded() {
+        assert_eq!(failures_label(&json!(1), &json!(1)), "1 of 1 remaining");
+        assert_eq!(
+            failures_label(&json!(0), &json!(2)),
+            "0 of 2 remaining — degraded"
+        );
+    }
+
+    #[test]
+    fn test_pending_label() {
+        assert_eq!(pending_label(&Value::Null), "none");
+        let pending =
+            json!({"blocks_per_stripe": 10, "max_node_failures": 2, "max_drive_failures": 3});
+        assert_eq!(
+            pending_label(&pending),
+            "10 blocks per stripe, tolerating 2 node / 3 drive failure(s)"
+        );
+    }
+
+    #[test]
+    fn test_weak_tls_findings() {
+        let ciphers = json!({
+            "tls_1_2": [
+                "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+                "TLS_RSA_WITH_3DES_EDE_CBC_SHA",
+                "RC4-SHA",
+                "ECDHE-RSA-CHACHA20-POLY1305"
+            ],
+            "tls_1_3": ["TLS_AES_256_GCM_SHA384"]
+        });
+        assert_eq!(
+            weak_tls_findings(&ciphers),
+            vec![
+                "weak TLS 1.2 cipher suite enabled: TLS_RSA_WITH_3DES_EDE_CBC_SHA",
+                "weak TLS 1.2 cipher suite enabled: RC4-SHA",
+            ]
+        );
+        let strong = json!({
+            "tls_1_2": ["TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256"],
+            "tls_1_3": ["TLS_AES_128_GCM_SHA256"]
+        });
+        assert!(weak_tls_findings(&strong).is_empty());
+    }
+}
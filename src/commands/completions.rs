@@ -0,0 +1,146 @@
+//! Shell completion scripts, generated from the clap command tree so they
+//! can't fall behind the CLI. Completes subcommand names and long flags;
+//! flag values (profile names, paths) are left to the shell's defaults.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+}
+
+impl Shell {
+    pub fn parse(name: &str) -> Result<Shell> {
+        match name {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            _ => anyhow::bail!("unsupported shell '{}' (supported: bash, zsh)", name),
+        }
+    }
+
+    /// The user's login shell, from `$SHELL`, if it is one we support.
+    pub fn detect() -> Option<Shell> {
+        let shell = std::env::var("SHELL").ok()?;
+        let name = Path::new(&shell).file_name()?.to_str()?;
+        Shell::parse(name).ok()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+        }
+    }
+
+    /// Where `qontrol init` installs the script. bash-completion loads
+    /// files from its user directory on its own; zsh has no such directory,
+    /// so that script has to be sourced from `.zshrc`.
+    pub fn install_path(&self, home: &Path, data_home: Option<&Path>) -> PathBuf {
+        let data = data_home
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| home.join(".local").join("share"));
+        match self {
+            Shell::Bash => data
+                .join("bash-completion")
+                .join("completions")
+                .join("qontrol"),
+            Shell::Zsh => data.join("qontrol").join("completions.zsh"),
+        }
+    }
+}
+
+/// Print the completion script for `shell`.
+pub fn run(shell: &str) -> Result<()> {
+    print!("{}", script(Shell::parse(shell)?));
+    Ok(())
+}
+
+pub fn script(shell: Shell) -> String {
+    let mut cmd = Cli::command();
+    cmd.build();
+    let mut levels = Vec::new();
+    collect_levels(&cmd, String::new(), &mut levels);
+    // Deepest paths first, so `fleet status` wins over `fleet`
+    levels.sort_by_key(|(path, _)| std::cmp::Reverse(path.matches(' ').count()));
+
+    let mut out = String::new();
+    if shell == Shell::Zsh {
+        out.push_str("autoload -U +X bashcompinit && bashcompinit\n\n");
+    }
+    out.push_str(
+        "_qontrol() {\n    local cur word path=\"\" opts\n    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n        case \"$word\" in\n            -*) ;;\n            *) path=\"$path $word\" ;;\n        esac\n    done\n    case \"$path\" in\n",
+    );
+    for (path, words) in &levels {
+        let pattern = if path.is_empty() {
+            "*".to_string()
+        } else {
+            format!("\"{}\"|\"{} \"*", path, path)
+        };
+        out.push_str(&format!(
+            "        {}) opts=\"{}\" ;;\n",
+            pattern,
+            words.join(" ")
+        ));
+    }
+    out.push_str(
+        "    esac\n    COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))\n}\n\ncomplete -o default -F _qontrol qontrol\n",
+    );
+    out
+}
+
+/// Every command path (" fleet status") with the words that may follow it:
+/// its subcommands, then its long flags.
+fn collect_levels(cmd: &clap::Command, path: String, levels: &mut Vec<(String, Vec<String>)>) {
+    let visible: Vec<&clap::Command> = cmd.get_subcommands().filter(|s| !s.is_hide_set()).collect();
+    let mut words: Vec<String> = visible.iter().map(|s| s.get_name().to_string()).collect();
+    words.extend(
+        cmd.get_arguments()
+            .filter(|a| !a.is_hide_set())
+            .filter_map(|a| a.get_long())
+            .map(|long| format!("--{}", long)),
+    );
+    levels.push((path.clone(), words));
+    // `help <command>` mirrors the whole tree; its names alone are enough
+    for sub in visible.into_iter().filter(|s| s.get_name() != "help") {
+        collect_levels(sub, format!("{} {}", path, sub.get_name()), levels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_script_covers_command_tree() {
+        let script = script(Shell::Bash);
+        assert!(script.ends_with("complete -o default -F _qontrol qontrol\n"));
+        assert!(script.contains("*) opts=\"init profile api"));
+        assert!(script.contains("\" fleet status\"|\" fleet status \"*) opts=\""));
+        assert!(script.contains("--cluster"));
+        // The top-level catch-all comes last so every other path can match
+        let last_case = script.lines().rfind(|l| l.contains(") opts="));
+        assert!(last_case.unwrap().trim_start().starts_with("*)"));
+        assert!(!script.contains("bashcompinit"));
+        assert!(super::script(Shell::Zsh).starts_with("autoload -U +X bashcompinit"));
+    }
+
+    #[test]
+    fn test_install_paths() {
+        let home = Path::new("/home/ops");
+        assert_eq!(
+            Shell::Bash.install_path(home, None),
+            PathBuf::from("/home/ops/.local/share/bash-completion/completions/qontrol")
+        );
+        assert_eq!(
+            Shell::Zsh.install_path(home, Some(Path::new("/data"))),
+            PathBuf::from("/data/qontrol/completions.zsh")
+        );
+        assert!(Shell::parse("fish").is_err());
+    }
+}
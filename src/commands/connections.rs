@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::commands::maintenance::parse_duration;
+use crate::commands::status::renderer::format_duration_ago;
+use crate::config::data_dir;
+use crate::output::{print_json, print_table};
+
+/// Samples older than this are dropped when a new one is recorded.
+const RETENTION_DAYS: i64 = 90;
+
+/// One line of the client history: who was connected to a cluster and who
+/// was doing I/O at the moment `connections record` ran.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientSample {
+    /// RFC 3339 UTC time the sample was taken
+    pub timestamp: String,
+    /// Profile the sample was taken with
+    pub cluster: String,
+    /// Connection count per client address
+    pub connected: BTreeMap<String, u32>,
+    /// Client addresses with a non-zero activity rate
+    pub active: BTreeSet<String>,
+}
+
+/// A client that has held connections without activity for the threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdleClient {
+    pub address: String,
+    /// Connections in the latest sample
+    pub connections: u32,
+    /// First sample of the current unbroken run of samples with the client
+    /// connected
+    pub connected_since: String,
+    /// Last sample in that run with activity from the client
+    pub last_active: Option<String>,
+}
+
+/// Path of the append-only client history (one JSON sample per line).
+fn history_path() -> Result<PathBuf> {
+    Ok(data_dir()?.join("client-history.jsonl"))
+}
+
+/// Sample the connected and active clients of one cluster and append it to
+/// the history. Meant to run periodically, e.g. from cron.
+pub fn record(client: &QumuloClient, profile: &str, json_mode: bool) -> Result<()> {
+    let sample = take_sample(client, profile, Utc::now())?;
+    append_sample(&history_path()?, &sample, Utc::now())?;
+
+    if json_mode {
+        print_json(&sample)?;
+    } else {
+        println!(
+            "Recorded {} connected client(s), {} active, for {}.",
+            sample.connected.len(),
+            sample.active.len(),
+            profile
+        );
+    }
+    Ok(())
+}
+
+fn take_sample(client: &QumuloClient, profile: &str, now: DateTime<Utc>) -> Result<ClientSample> {
+    let connections = client
+        .get_network_connections()
+        .context("failed to fetch network connections")?;
+    let activity = client
+        .get_activity_current()
+        .context("failed to fetch current activity")?;
+    Ok(ClientSample {
+        timestamp: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        cluster: profile.to_string(),
+        connected: connected_clients(&connections),
+        active: active_clients(&activity),
+    })
+}
+
+/// Connection count per client address, from `/v2/network/connections/`.
+fn connected_clients(connections: &Value) -> BTreeMap<String, u32> {
+    let mut clients = BTreeMap::new();
+    for node in connections.as_array().map(|a| a.as_slice()).unwrap_or(&[]) {
+        for conn in node["connections"]
+            .as_array()
+            .map(|a| a.as_slice())
+            .unwrap_or(&[])
+        {
+            if let Some(address) = conn["network_address"].as_str().filter(|a| !a.is_empty()) {
+                *clients.entry(address.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    clients
+}
+
+/// Client addresses with any non-zero rate in `/v1/analytics/activity/current`.
+fn active_clients(activity: &Value) -> BTreeSet<String> {
+    activity["entries"]
+        .as_array()
+        .map(|a| a.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .filter(|e| e["rate"].as_f64().unwrap_or(0.0) > 0.0)
+        .filter_map(|e| e["ip"].as_str().filter(|ip| !ip.is_empty()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Append a sample, dropping samples past the retention window. The file is
+/// only rewritten when something expired.
+fn append_sample(path: &Path, sample: &ClientSample, now: DateTime<Utc>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let history = read_history(path)?;
+    let cutoff = now - Duration::days(RETENTION_DAYS);
+    let kept: Vec<&ClientSample> = history
+        .iter()
+        .filter(|s| sample_time(s).is_some_and(|ts| ts >= cutoff))
+        .collect();
+
+    let mut file = if kept.len() < history.len() {
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        for s in kept {
+            writeln!(file, "{}", serde_json::to_string(s)?)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        file
+    } else {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?
+    };
+    writeln!(file, "{}", serde_json::to_string(sample)?)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Read the history log, skipping lines that don't parse. A missing file is empty.
+fn read_history(path: &Path) -> Result<Vec<ClientSample>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                tracing::warn!(error = %e, "skipping malformed client history line");
+                None
+            }
+        })
+        .collect())
+}
+
+fn sample_time(sample: &ClientSample) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&sample.timestamp)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+/// List clients that are still connected but have shown no activity for at
+/// least `threshold`, from the samples recorded by `connections record`.
+pub fn idle_report(profile: &str, threshold: &str, json_mode: bool) -> Result<()> {
+    let threshold = parse_duration(threshold)?;
+    let now = Utc::now();
+    let samples: Vec<ClientSample> = read_history(&history_path()?)?
+        .into_iter()
+        .filter(|s| s.cluster == profile)
+        .collect();
+    let Some(oldest) = samples.iter().filter_map(sample_time).min() else {
+        anyhow::bail!(
+            "no connection history for {}: run `qontrol connections record` periodically (e.g. from cron) to build it",
+            profile
+        );
+    };
+    let idle = find_idle(&samples, threshold, now);
+
+    if json_mode {
+        print_json(&idle)?;
+        return Ok(());
+    }
+    if now - oldest < threshold {
+        eprintln!(
+            "Note: the oldest sample for {} is from {}; no client can be idle longer than that yet.",
+            profile,
+            format_duration_ago(oldest)
+        );
+    }
+    if idle.is_empty() {
+        println!("No idle clients.");
+        return Ok(());
+    }
+    let rows: Vec<Value> = idle
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "client": c.address,
+                "connections": c.connections,
+                "connected_since": c.connected_since,
+                "last_active": c.last_active.as_deref().unwrap_or("-"),
+            })
+        })
+        .collect();
+    print_table(
+        &Value::Array(rows),
+        &["client", "connections", "connected_since", "last_active"],
+    );
+    Ok(())
+}
+
+/// Clients connected in the latest sample whose idle time, counted from
+/// their last activity or else from when they connected, is at least
+/// `threshold`. A sample without a client breaks its run of connection:
+/// it disconnected at some point in between. Longest idle first.
+fn find_idle(samples: &[ClientSample], threshold: Duration, now: DateTime<Utc>) -> Vec<IdleClient> {
+    let mut samples: Vec<(DateTime<Utc>, &ClientSample)> = samples
+        .iter()
+        .filter_map(|s| Some((sample_time(s)?, s)))
+        .collect();
+    samples.sort_by_key(|(ts, _)| *ts);
+    let Some((_, latest)) = samples.last() else {
+        return Vec::new();
+    };
+
+    let cutoff = now - threshold;
+    let mut idle: Vec<(DateTime<Utc>, IdleClient)> = Vec::new();
+    for (address, connections) in &latest.connected {
+        let run: Vec<&(DateTime<Utc>, &ClientSample)> = samples
+            .iter()
+            .rev()
+            .take_while(|(_, s)| s.connected.contains_key(address))
+            .collect();
+        let Some((connected_since, _)) = run.last() else {
+            continue;
+        };
+        let last_active = run
+            .iter()
+            .find(|(_, s)| s.active.contains(address))
+            .map(|(ts, _)| *ts);
+        let idle_since = last_active.unwrap_or(*connected_since);
+        if idle_since > cutoff {
+            continue;
+        }
+        let format = |ts: DateTime<Utc>| ts.to_rfc3339_opts(SecondsFormat::Secs, true);
+        idle.push((
+            idle_since,
+            IdleClient {
+                address: address.clone(),
+                connections: *connections,
+                connected_since: format(*connected_since),
+                last_active: last_active.map(format),
+            },
+        ));
+    }
+    idle.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.address.cmp(&b.1.address)));
+    idle.into_iter().map(|(_, c)| c).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn sample(timestamp: &str, connected: &[&str], active: &[&str]) -> ClientSample {
+        ClientSample {
+            timestamp: timestamp.to_string(),
+            cluster: "prod".to_string(),
+            connected: connected.iter().map(|a| (a.to_string(), 1)).collect(),
+            active: active.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_idle() {
+        let samples = vec![
+            sample(
+                "2026-03-01T00:00:00Z",
+                &["10.0.0.1", "10.0.0.2", "10.0.0.3"],
+                &["10.0.0.2"],
+            ),
+            sample(
+                "2026-03-04T00:00:00Z",
+                &["10.0.0.1", "10.0.0.2", "10.0.0.3"],
+                &["10.0.0.3"],
+            ),
+            // 10.0.0.4 only just connected; 10.0.0.5 disconnected
+            sample(
+                "2026-03-09T00:00:00Z",
+                &["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.4"],
+                &[],
+            ),
+        ];
+        let idle = find_idle(&samples, Duration::days(7), at("2026-03-10T00:00:00Z"));
+        let addresses: Vec<&str> = idle.iter().map(|c| c.address.as_str()).collect();
+        assert_eq!(addresses, ["10.0.0.1", "10.0.0.2"]);
+        assert_eq!(idle[0].connected_since, "2026-03-01T00:00:00Z");
+        assert_eq!(idle[0].last_active, None);
+        assert_eq!(idle[1].last_active.as_deref(), Some("2026-03-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_find_idle_reconnect_resets_run() {
+        let samples = vec![
+            sample("2026-03-01T00:00:00Z", &["10.0.0.1"], &[]),
+            sample("2026-03-05T00:00:00Z", &[], &[]),
+            sample("2026-03-09T00:00:00Z", &["10.0.0.1"], &[]),
+        ];
+        assert!(find_idle(&samples, Duration::days(7), at("2026-03-10T00:00:00Z")).is_empty());
+        assert_eq!(
+            find_idle(&samples, Duration::hours(12), at("2026-03-10T00:00:00Z"))[0].connected_since,
+            "2026-03-09T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_sample_parsing() {
+        let connections = serde_json::json!([
+            {"id": 1, "connections": [
+                {"type": "CONNECTION_TYPE_NFS", "network_address": "10.0.0.1"},
+                {"type": "CONNECTION_TYPE_NFS", "network_address": "10.0.0.1"},
+                {"type": "CONNECTION_TYPE_REST", "network_address": ""}
+            ]},
+            {"id": 2, "connections": [
+                {"type": "CONNECTION_TYPE_SMB", "network_address": "10.0.0.2"}
+            ]}
+        ]);
+        let connected = connected_clients(&connections);
+        assert_eq!(connected.len(), 2);
+        assert_eq!(connected["10.0.0.1"], 2);
+
+        let activity = serde_json::json!({"entries": [
+            {"type": "file-iops-read", "ip": "10.0.0.1", "id": "2", "rate": 4.0},
+            {"type": "file-iops-read", "ip": "10.0.0.2", "id": "2", "rate": 0.0}
+        ]});
+        let active = active_clients(&activity);
+        assert_eq!(active.into_iter().collect::<Vec<_>>(), ["10.0.0.1"]);
+    }
+
+    #[test]
+    fn test_append_sample_prunes_expired() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("data").join("client-history.jsonl");
+        let now = at("2026-06-01T00:00:00Z");
+
+        append_sample(
+            &path,
+            &sample("2026-01-01T00:00:00Z", &["10.0.0.1"], &[]),
+            now,
+        )
+        .unwrap();
+        append_sample(
+            &path,
+            &sample("2026-05-30T00:00:00Z", &["10.0.0.2"], &[]),
+            now,
+        )
+        .unwrap();
+        append_sample(
+            &path,
+            &sample("2026-05-31T00:00:00Z", &["10.0.0.3"], &[]),
+            now,
+        )
+        .unwrap();
+        let history = read_history(&path).unwrap();
+
+        let timestamps: Vec<&str> = history.iter().map(|s| s.timestamp.as_str()).collect();
+        assert_eq!(timestamps, ["2026-05-30T00:00:00Z", "2026-05-31T00:00:00Z"]);
+    }
+}
@@ -0,0 +1,351 @@
+//! Developer tooling: recording test fixtures from a live cluster.
+//!
+//! `dev record-fixtures` makes the same calls the status, cdf, and fs tests
+//! mount, and writes each response where `tests/harness` looks for it:
+//! `<out>/status/<name>/` for the status set (the layout of
+//! `mount_full_status_fixtures`), `<out>/<set>/<name>/` for the others (named
+//! like `FIXTURE_ROUTES`, for `mount_fixture_from`). Cluster UUIDs and
+//! hostnames are replaced before anything is written.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::config::ProfileEntry;
+use crate::output::print_json;
+
+/// Days of capacity history recorded for the status set
+const CAPACITY_HISTORY_DAYS: i64 = 30;
+
+/// A group of fixtures recorded together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureSet {
+    Status,
+    Cdf,
+    Fs,
+}
+
+impl FixtureSet {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "status" => Ok(Self::Status),
+            "cdf" => Ok(Self::Cdf),
+            "fs" => Ok(Self::Fs),
+            other => anyhow::bail!("unknown fixture set '{}' (expected status, cdf, fs)", other),
+        }
+    }
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Cdf => "cdf",
+            Self::Fs => "fs",
+        }
+    }
+
+    /// The (fixture name, API path) pairs recorded for this set
+    fn calls(self) -> Vec<(&'static str, String)> {
+        let fixed = |calls: &[(&'static str, &str)]| -> Vec<(&'static str, String)> {
+            calls
+                .iter()
+                .map(|(name, path)| (*name, path.to_string()))
+                .collect()
+        };
+        match self {
+            Self::Status => {
+                let mut calls = fixed(&[
+                    ("cluster_settings", "/v1/cluster/settings"),
+                    ("version", "/v1/version"),
+                    ("node_state", "/v1/node/state"),
+                    ("cluster_nodes", "/v1/cluster/nodes/"),
+                    ("file_system", "/v1/file-system"),
+                    ("aggregates", "/v1/files/%2F/aggregates/?max-entries=0"),
+                    (
+                        "recursive_aggregates",
+                        "/v1/files/%2F/recursive-aggregates/",
+                    ),
+                    ("snapshots_list", "/v2/snapshots/"),
+                    (
+                        "snapshots_total_capacity",
+                        "/v1/snapshots/total-used-capacity",
+                    ),
+                    ("cluster_slots", "/v1/cluster/slots/"),
+                    ("cluster_chassis", "/v1/cluster/nodes/chassis/"),
+                    ("cluster_protection_status", "/v1/cluster/protection/status"),
+                    ("cluster_restriper_status", "/v1/cluster/restriper/status"),
+                    ("network_connections", "/v2/network/connections/"),
+                    ("network_status", "/v3/network/status"),
+                ]);
+                for (name, activity_type) in [
+                    ("activity_iops_read", "file-iops-read"),
+                    ("activity_iops_write", "file-iops-write"),
+                    ("activity_throughput_read", "file-throughput-read"),
+                    ("activity_throughput_write", "file-throughput-write"),
+                ] {
+                    calls.push((
+                        name,
+                        format!("/v1/analytics/activity/current?type={}", activity_type),
+                    ));
+                }
+                let begin = chrono::Utc::now().timestamp() - CAPACITY_HISTORY_DAYS * 86_400;
+                calls.push((
+                    "capacity_history",
+                    format!(
+                        "/v1/analytics/capacity-history/?begin-time={}&interval=DAILY",
+                        begin
+                    ),
+                ));
+                calls
+            }
+            Self::Cdf => fixed(&[
+                ("cluster_settings", "/v1/cluster/settings"),
+                ("portal_hubs", "/v2/portal/hubs/"),
+                ("portal_spokes", "/v2/portal/spokes/"),
+                (
+                    "replication_sources",
+                    "/v2/replication/source-relationships/",
+                ),
+                (
+                    "replication_source_statuses",
+                    "/v2/replication/source-relationships/status/",
+                ),
+                (
+                    "replication_target_statuses",
+                    "/v2/replication/target-relationships/status/",
+                ),
+                (
+                    "object_relationships",
+                    "/v3/replication/object-relationships/",
+                ),
+                (
+                    "object_relationship_statuses",
+                    "/v3/replication/object-relationships/status/",
+                ),
+            ]),
+            Self::Fs => fixed(&[
+                ("fs_entries_root", "/v1/files/%2F/entries/"),
+                ("fs_attributes_root", "/v1/files/%2F/info/attributes"),
+                (
+                    "fs_recursive_aggregates_root",
+                    "/v1/files/%2F/recursive-aggregates/",
+                ),
+                ("quotas_list", "/v1/files/quotas/"),
+            ]),
+        }
+    }
+}
+
+/// Record one fixture set from the profile's cluster into
+/// `<out>/<set>/<name>/`. Calls the cluster rejects (e.g. features it
+/// doesn't have) are skipped with a warning. With `sanitize`, hostnames,
+/// IPs, usernames, and share names are pseudonymized too (see
+/// [`crate::sanitize`]).
+#[allow(clippy::too_many_arguments)]
+pub fn record_fixtures(
+    client: &QumuloClient,
+    profile: &ProfileEntry,
+    set: FixtureSet,
+    out: &Path,
+    name: &str,
+    force: bool,
+    sanitize: bool,
+    json_mode: bool,
+) -> Result<()> {
+    let dir = out.join(set.dir_name()).join(name);
+    if dir.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite its fixtures)",
+            dir.display()
+        );
+    }
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    // Names to scrub come from the cluster itself, whichever set is recorded
+    let settings = client.get_cluster_settings().unwrap_or(Value::Null);
+    let nodes = client.get_cluster_nodes().unwrap_or(Value::Null);
+    let mut scrubber = Scrubber::new(&profile.host, &settings, &nodes);
+
+    let mut fixtures: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    for (fixture, path) in set.calls() {
+        match client.request("GET", &path, None) {
+            Ok(value) => {
+                fixtures.push(fixture);
+                values.push(value);
+            }
+            Err(e) => {
+                eprintln!("Warning: skipped {} ({}): {:#}", fixture, path, e);
+                skipped.push(fixture.to_string());
+            }
+        }
+    }
+    // Sanitized together so a name found in one response is replaced in all
+    if sanitize {
+        crate::sanitize::sanitize_all(&mut values);
+    }
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    for (fixture, mut value) in fixtures.into_iter().zip(values) {
+        scrubber.scrub(&mut value);
+        let file = dir.join(format!("{}.json", fixture));
+        let contents = serde_json::to_string_pretty(&value)? + "\n";
+        std::fs::write(&file, contents)
+            .with_context(|| format!("failed to write {}", file.display()))?;
+        written.push(file);
+    }
+
+    if json_mode {
+        return print_json(&serde_json::json!({
+            "dir": dir,
+            "written": written,
+            "skipped": skipped,
+        }));
+    }
+    println!(
+        "Recorded {} fixtures to {}{}",
+        written.len(),
+        dir.display(),
+        if skipped.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} skipped: {})", skipped.len(), skipped.join(", "))
+        }
+    );
+    Ok(())
+}
+
+/// Replaces UUIDs and hostnames in recorded responses. Each UUID maps to
+/// the same stand-in everywhere it appears, so IDs that tie responses
+/// together still match.
+struct Scrubber {
+    /// (real, stand-in), longest first so a node name is replaced before
+    /// the cluster name it starts with
+    names: Vec<(String, String)>,
+    uuids: HashMap<String, String>,
+}
+
+impl Scrubber {
+    fn new(host: &str, settings: &Value, nodes: &Value) -> Self {
+        let mut names = vec![(host.to_string(), "cluster.example.com".to_string())];
+        if let Some(cluster) = settings.get("cluster_name").and_then(|v| v.as_str()) {
+            names.push((cluster.to_string(), "fixture-cluster".to_string()));
+        }
+        for node in nodes.as_array().into_iter().flatten() {
+            let name = node.get("node_name").and_then(|v| v.as_str());
+            let id = node.get("id").and_then(|v| v.as_u64());
+            if let (Some(name), Some(id)) = (name, id) {
+                names.push((name.to_string(), format!("fixture-cluster-{}", id)));
+            }
+        }
+        names.retain(|(real, _)| !real.is_empty());
+        names.sort_by_key(|(real, _)| std::cmp::Reverse(real.len()));
+        Self {
+            names,
+            uuids: HashMap::new(),
+        }
+    }
+
+    fn scrub(&mut self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.scrub_str(s),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.scrub(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.scrub(v)),
+            _ => {}
+        }
+    }
+
+    fn scrub_str(&mut self, s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(at) = find_uuid(rest) {
+            out.push_str(&rest[..at]);
+            let uuid = rest[at..at + UUID_LEN].to_ascii_lowercase();
+            let next = self.uuids.len() + 1;
+            let stand_in = self
+                .uuids
+                .entry(uuid)
+                .or_insert_with(|| format!("00000000-0000-4000-8000-{:012x}", next));
+            out.push_str(stand_in);
+            rest = &rest[at + UUID_LEN..];
+        }
+        out.push_str(rest);
+
+        for (real, stand_in) in &self.names {
+            if out.contains(real.as_str()) {
+                out = out.replace(real.as_str(), stand_in);
+            }
+        }
+        out
+    }
+}
+
+const UUID_LEN: usize = 36;
+
+/// Byte offset of the first UUID (8-4-4-4-12 hex digits) in `s`
+fn find_uuid(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    (0..bytes.len().saturating_sub(UUID_LEN - 1)).find(|&start| {
+        bytes[start..start + UUID_LEN]
+            .iter()
+            .enumerate()
+            .all(|(i, b)| match i {
+                8 | 13 | 18 | 23 => *b == b'-',
+                _ => b.is_ascii_hexdigit(),
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_uuid() {
+        assert_eq!(find_uuid("ce5899cc-9f50-49bb-98e7-d33628f7ba4a"), Some(0));
+        assert_eq!(
+            find_uuid("id: CE5899CC-9F50-49BB-98E7-D33628F7BA4A!"),
+            Some(4)
+        );
+        assert_eq!(find_uuid("ce5899cc-9f50-49bb-98e7-d33628f7ba4"), None);
+        assert_eq!(find_uuid("0c:42:a1:6a:aa:1c"), None);
+    }
+
+    #[test]
+    fn test_scrub_uuids_and_names() {
+        let settings = json!({"cluster_name": "iss-sg"});
+        let nodes = json!([
+            {"id": 1, "node_name": "iss-sg-1"},
+            {"id": 2, "node_name": "iss-sg-2"},
+        ]);
+        let mut scrubber = Scrubber::new("iss.corp.example", &settings, &nodes);
+        let mut value = json!({
+            "cluster_name": "iss-sg",
+            "nodes": [
+                {"node_name": "iss-sg-2", "uuid": "ce5899cc-9f50-49bb-98e7-d33628f7ba4a"},
+                {"node_name": "iss-sg-1", "uuid": "0b1c2d3e-9f50-49bb-98e7-d33628f7ba4a"},
+            ],
+            "owner": "CE5899CC-9F50-49BB-98E7-D33628F7BA4A",
+            "url": "https://iss.corp.example:8000/",
+            "count": 3,
+        });
+        scrubber.scrub(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "cluster_name": "fixture-cluster",
+                "nodes": [
+                    {"node_name": "fixture-cluster-2", "uuid": "00000000-0000-4000-8000-000000000001"},
+                    {"node_name": "fixture-cluster-1", "uuid": "00000000-0000-4000-8000-000000000002"},
+                ],
+                "owner": "00000000-0000-4000-8000-000000000001",
+                "url": "https://cluster.example.com:8000/",
+                "count": 3,
+            })
+        );
+    }
+}
@@ -1,263 +1,2641 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::io::{self, IsTerminal, Write};
+use std::ops::ControlFlow;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use console::Style;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 
-use crate::client::QumuloClient;
-use crate::output::{format_value, print_value};
+use crate::client::{AggregatesBudget, QumuloClient};
+use crate::commands::status::renderer::format_bytes;
+use crate::error::QontrolError;
+use crate::glob::glob_match;
+use crate::identity::Identities;
+use crate::output::{csv_field, emit, format_value, print_json, print_value, OutputFormat};
+use crate::progress::{self, Tracker};
+
+/// How often `fs ls` updates its loading count, in entries.
+const LS_PROGRESS_EVERY: usize = 1000;
 
 /// List directory contents with auto-pagination
+///
+/// With `recursive` set to a depth, subdirectories are walked down to that depth
+/// and each entry is printed with its full path as soon as its parent is listed.
+#[allow(clippy::too_many_arguments)]
 pub fn ls(
     client: &QumuloClient,
     path: &str,
     long: bool,
     sort: &str,
+    reverse: bool,
     limit: Option<u32>,
-    json_mode: bool,
+    recursive: Option<u32>,
+    follow: bool,
+    identities: Option<&Identities>,
+    format: OutputFormat,
 ) -> Result<()> {
-    if json_mode {
-        return ls_json(client, path, limit);
+    let sort = SortOrder::parse(sort, reverse)?;
+
+    if let Some(max_depth) = recursive {
+        let opts = WalkOptions {
+            max_depth,
+            sort: &sort,
+            follow,
+            // Only long and JSON listings show where links point
+            link_targets: long || (format.is_structured() && format != OutputFormat::Csv),
+        };
+        return ls_recursive(client, path, &opts, long, limit, identities, format);
     }
 
-    let is_tty = io::stderr().is_terminal();
-    let mut total_count: u64 = 0;
-    let mut after: Option<String> = None;
+    // CSV keeps its own columns, streamed like the table; the other
+    // structured formats render the whole listing as one document
+    let csv_mode = format == OutputFormat::Csv;
+    if format.is_structured() && !csv_mode {
+        return ls_document(client, path, limit, format);
+    }
+
+    let is_tty = io::stderr().is_terminal() && progress::interactive();
     let mut all_entries: Vec<Value> = Vec::new();
-    let remaining_limit = limit;
+    let tracker = Tracker::start("list", None, false);
 
-    loop {
-        let page_limit = remaining_limit.map(|l| {
-            let fetched = total_count as u32;
-            l.saturating_sub(fetched)
+    for entry in list_entries(client, path, limit) {
+        all_entries.push(entry?);
+
+        // Show progress on stderr for TTY, about once a page
+        if all_entries.len().is_multiple_of(LS_PROGRESS_EVERY) {
+            tracker.set(all_entries.len() as u64);
+            if is_tty {
+                eprint!("\r\x1b[K(loading... {} entries)", all_entries.len());
+                io::stderr().flush().ok();
+            }
+        }
+    }
+    let total_count = all_entries.len();
+    tracker.set(total_count as u64);
+
+    // Clear progress line
+    if is_tty && total_count > 0 {
+        eprint!("\r\x1b[K");
+        io::stderr().flush().ok();
+    }
+
+    sort_entries(&mut all_entries, &sort);
+
+    if csv_mode {
+        println!("{}", CSV_HEADER);
+        for entry in &all_entries {
+            println!("{}", csv_row(&entry_path(path, entry), entry));
+        }
+        return Ok(());
+    }
+
+    if all_entries.is_empty() {
+        println!("(empty directory)");
+        return Ok(());
+    }
+
+    if long {
+        print_long_listing(client, &all_entries, identities);
+    } else {
+        print_short_listing(&all_entries);
+    }
+
+    // Show summary count on stderr
+    eprintln!("{} entries", total_count);
+
+    Ok(())
+}
+
+/// Stream a directory's entries across pages, stopping after `limit`. With
+/// a limit, pages are requested at that size so a small limit is one call.
+fn list_entries<'a>(
+    client: &'a QumuloClient,
+    path: &'a str,
+    limit: Option<u32>,
+) -> impl Iterator<Item = Result<Value>> + 'a {
+    client
+        .get_file_entries_paged(path, limit)
+        .take(limit.map_or(usize::MAX, |l| l as usize))
+        .map(move |entry| entry.with_context(|| format!("failed to list directory: {}", path)))
+}
+
+/// JSON mode: collect all pages into a single JSON array response
+fn ls_document(
+    client: &QumuloClient,
+    path: &str,
+    limit: Option<u32>,
+    format: OutputFormat,
+) -> Result<()> {
+    let all_files = list_entries(client, path, limit).collect::<Result<Vec<_>>>()?;
+
+    // One combined response; paging is cleared since everything was fetched
+    let result = json!({"files": all_files, "paging": {"next": ""}});
+    emit(&result, "/files", format);
+    Ok(())
+}
+
+/// Recursive listing: stream entries with full paths in table or CSV form, or
+/// collect them into one document for the other structured formats
+fn ls_recursive(
+    client: &QumuloClient,
+    path: &str,
+    opts: &WalkOptions,
+    long: bool,
+    limit: Option<u32>,
+    identities: Option<&Identities>,
+    format: OutputFormat,
+) -> Result<()> {
+    let csv_mode = format == OutputFormat::Csv;
+    let json_mode = format.is_structured() && !csv_mode;
+    let mut count: u64 = 0;
+    let mut collected: Vec<Value> = Vec::new();
+    let dir_style = Style::new().blue().bold();
+    let symlink_style = Style::new().cyan();
+
+    if json_mode {
+        // Collected below and printed as a single document
+    } else if csv_mode {
+        println!("{}", CSV_HEADER);
+    } else if long {
+        println!(
+            "{:<4}  {:>10}  {:<12}  {:<19}  PATH",
+            "TYPE", "SIZE", "OWNER", "MODIFIED"
+        );
+    }
+
+    walk(client, path, opts, &mut |item: &WalkEntry| {
+        if limit.is_some_and(|l| count >= l as u64) {
+            return ControlFlow::Break(());
+        }
+        count += 1;
+
+        if json_mode {
+            let mut node = item.entry.clone();
+            node["path"] = Value::String(item.path.clone());
+            node["depth"] = json!(item.depth);
+            if let Some(ref target) = item.link_target {
+                node["symlink_target"] = Value::String(target.clone());
+            }
+            collected.push(node);
+        } else if csv_mode {
+            println!("{}", csv_row(&item.path, &item.entry));
+        } else {
+            let styled_path = match entry_type(&item.entry) {
+                "FS_FILE_TYPE_DIRECTORY" => dir_style.apply_to(&item.path).to_string(),
+                "FS_FILE_TYPE_SYMLINK" => symlink_style.apply_to(&item.path).to_string(),
+                _ => item.path.clone(),
+            };
+            let styled_path = match item.link_target {
+                Some(ref target) if long => format!("{} -> {}", styled_path, target),
+                _ => styled_path,
+            };
+            if long {
+                println!(
+                    "{}  {:>10}  {:<12}  {:<19}  {}",
+                    type_abbrev(entry_type(&item.entry)),
+                    format_size(entry_size(&item.entry)),
+                    owner_name(client, &item.entry, identities),
+                    item.entry
+                        .get("modification_time")
+                        .and_then(|v| v.as_str())
+                        .map(truncate_timestamp)
+                        .unwrap_or_else(|| "-".to_string()),
+                    styled_path,
+                );
+            } else {
+                println!("{}", styled_path);
+            }
+        }
+        ControlFlow::Continue(())
+    })?;
+
+    if json_mode {
+        let result = json!({
+            "path": path,
+            "max_depth": opts.max_depth,
+            "files": collected,
         });
+        emit(&result, "/files", format);
+    } else if !csv_mode {
+        eprintln!("{} entries", count);
+    }
 
-        // If we've already hit the limit, stop
-        if let Some(0) = page_limit {
-            break;
+    Ok(())
+}
+
+/// Show recursive directory tree
+///
+/// Symlinks are shown with their targets; with `follow`, links to directories
+/// are descended like directories unless they loop back to an ancestor.
+pub fn tree(
+    client: &QumuloClient,
+    path: &str,
+    max_depth: u32,
+    follow: bool,
+    json_mode: bool,
+) -> Result<()> {
+    let mut ancestors = HashSet::new();
+    if json_mode {
+        let mut result = json!({
+            "path": path,
+            "max_depth": max_depth,
+        });
+
+        let tree_data = build_tree_json(client, path, max_depth, 0, follow, &mut ancestors)?;
+        result["tree"] = tree_data;
+
+        // Get aggregates for the root
+        if let Ok(aggregates) = client.get_file_recursive_aggregates(path) {
+            result["aggregates"] = aggregates;
+        }
+
+        print_json(&result)?;
+        return Ok(());
+    }
+
+    // Human-readable tree output
+    let dir_style = Style::new().blue().bold();
+    println!("{}", dir_style.apply_to(path));
+
+    print_tree_recursive(client, path, max_depth, 0, "", follow, &mut ancestors)?;
+
+    // Show aggregated sizes at the bottom
+    if let Ok(aggregates) = client.get_file_recursive_aggregates(path) {
+        println!();
+        print_aggregates_summary(&aggregates);
+    }
+
+    Ok(())
+}
+
+/// Show detailed file/directory attributes
+///
+/// With `streams`, also lists named streams (alternate data streams written by
+/// SMB clients, including macOS resource forks and Finder info).
+pub fn stat(client: &QumuloClient, path: &str, streams: bool, json_mode: bool) -> Result<()> {
+    let raw = client
+        .get_file_attr(path)
+        .with_context(|| format!("failed to get attributes: {}", path))?;
+    let mut attrs = raw.clone();
+
+    if let Some(target) = link_target(client, &attrs) {
+        attrs["symlink_target"] = Value::String(target);
+    }
+
+    if streams {
+        let streams = client
+            .get_file_streams(path)
+            .with_context(|| format!("failed to list named streams: {}", path))?;
+        attrs["named_streams"] = streams;
+    }
+
+    if json_mode {
+        let result = stat_json(path, &attrs, raw);
+        print_json(&result)?;
+    } else {
+        print_stat_human(&attrs, path);
+    }
+
+    Ok(())
+}
+
+/// Attributes `fs stat` shows first, with their display labels
+const STAT_FIELDS: [(&str, &str); 13] = [
+    ("id", "    ID"),
+    ("type", "  Type"),
+    ("symlink_target", "Target"),
+    ("size", "  Size"),
+    ("owner", " Owner"),
+    ("group", " Group"),
+    ("mode", "  Mode"),
+    ("creation_time", "Created"),
+    ("modification_time", "Modified"),
+    ("change_time", "Changed"),
+    ("access_time", "Access"),
+    ("child_count", "Children"),
+    ("num_links", " Links"),
+];
+
+/// JSON form of `fs stat`: the curated fields plus named streams, with the
+/// untouched API response under `raw` so no attribute is ever missing.
+fn stat_json(path: &str, attrs: &Value, raw: Value) -> Value {
+    let mut result = serde_json::Map::new();
+    result.insert("path".into(), json!(path));
+    for (key, _) in &STAT_FIELDS {
+        if let Some(val) = attrs.get(*key) {
+            result.insert(key.to_string(), val.clone());
         }
+    }
+    if let Some(streams) = attrs.get("named_streams") {
+        result.insert("named_streams".into(), streams.clone());
+    }
+    result.insert("raw".into(), raw);
+    Value::Object(result)
+}
 
+/// List a file's extended metadata, or print one user-defined metadata value
+///
+/// The listing covers DOS attribute flags set on the file, user-defined
+/// metadata (GENERIC entries surface as S3 object tags, S3 entries as S3 object
+/// metadata), and named streams. With a `key`, the decoded value is written to
+/// stdout as-is, so binary values can be piped to a file.
+pub fn xattr(
+    client: &QumuloClient,
+    path: &str,
+    key: Option<&str>,
+    metadata_type: &str,
+    json_mode: bool,
+) -> Result<()> {
+    let metadata_type = match metadata_type.to_ascii_uppercase().as_str() {
+        "GENERIC" => "GENERIC",
+        "S3" => "S3",
+        other => anyhow::bail!(
+            "unknown metadata type '{}' (expected generic, s3)",
+            other.to_ascii_lowercase()
+        ),
+    };
+
+    if let Some(key) = key {
         let response = client
-            .get_file_entries(path, after.as_deref(), page_limit)
-            .with_context(|| format!("failed to list directory: {}", path))?;
+            .get_file_user_metadata_value(path, metadata_type, key)
+            .with_context(|| format!("failed to get metadata '{}' on {}", key, path))?;
+        let bytes = decode_metadata_value(response.get("value").unwrap_or(&Value::Null));
+        if json_mode {
+            let result = json!({
+                "path": path,
+                "type": metadata_type,
+                "key": key,
+                "value": BASE64.encode(&bytes),
+                "text": String::from_utf8(bytes).ok(),
+            });
+            print_json(&result)?;
+        } else {
+            let mut stdout = io::stdout();
+            stdout.write_all(&bytes)?;
+            if io::stdout().is_terminal() {
+                println!();
+            }
+        }
+        return Ok(());
+    }
 
-        let entries = response
-            .get("files")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
+    let attrs = client
+        .get_file_attr(path)
+        .with_context(|| format!("failed to get attributes: {}", path))?;
+    let metadata = client
+        .get_file_user_metadata(path)
+        .with_context(|| format!("failed to list user metadata: {}", path))?;
+    let streams = client
+        .get_file_streams(path)
+        .with_context(|| format!("failed to list named streams: {}", path))?;
+
+    let dos_flags = dos_attribute_flags(&attrs);
+    let metadata: Vec<Value> = metadata
+        .iter()
+        .map(|entry| {
+            let bytes = decode_metadata_value(entry.get("value").unwrap_or(&Value::Null));
+            json!({
+                "type": entry.get("type").cloned().unwrap_or(Value::Null),
+                "key": entry.get("key").cloned().unwrap_or(Value::Null),
+                "size": bytes.len(),
+                "value": BASE64.encode(&bytes),
+                "text": String::from_utf8(bytes).ok(),
+            })
+        })
+        .collect();
+
+    let result = json!({
+        "path": path,
+        "dos_attributes": dos_flags,
+        "user_metadata": metadata,
+        "named_streams": streams,
+    });
+
+    print_value(&result, json_mode, |val| {
+        print_xattr_human(val);
+    });
+    Ok(())
+}
 
-        total_count += entries.len() as u64;
-        all_entries.extend(entries);
+/// Names of the DOS attribute flags that are set on a file
+fn dos_attribute_flags(attrs: &Value) -> Vec<String> {
+    attrs
+        .get("extended_attributes")
+        .and_then(|v| v.as_object())
+        .map(|flags| {
+            flags
+                .iter()
+                .filter(|(_, set)| set.as_bool() == Some(true))
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Decode a user metadata value, which the API returns base64-encoded (older
+/// releases return a byte array)
+fn decode_metadata_value(value: &Value) -> Vec<u8> {
+    match value {
+        Value::String(s) => BASE64.decode(s).unwrap_or_else(|_| s.as_bytes().to_vec()),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|b| b.as_u64().map(|b| b as u8))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn print_xattr_human(result: &Value) {
+    let header_style = Style::new().bold();
+    let dim_style = Style::new().dim();
+    let path = result.get("path").and_then(|v| v.as_str()).unwrap_or("?");
+    println!("{}", header_style.apply_to(format!("  File: {}", path)));
 
-        // Show progress on stderr for TTY
-        if is_tty && total_count > 0 {
-            eprint!("\r\x1b[K(loading... {} entries)", total_count);
-            io::stderr().flush().ok();
+    let flags: Vec<&str> = result
+        .get("dos_attributes")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|f| f.as_str()).collect())
+        .unwrap_or_default();
+    println!(
+        "   DOS: {}",
+        if flags.is_empty() {
+            "-".to_string()
+        } else {
+            flags.join(", ")
         }
+    );
 
-        // Check if there's a next page
-        let has_next = response
-            .get("paging")
-            .and_then(|p| p.get("next"))
-            .and_then(|n| n.as_str())
-            .is_some_and(|n| !n.is_empty());
+    let metadata = result
+        .get("user_metadata")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    println!();
+    println!(
+        "{}",
+        header_style.apply_to(format!("User metadata: {}", metadata.len()))
+    );
+    for entry in &metadata {
+        let value = match entry.get("text").and_then(|v| v.as_str()) {
+            Some(text) => text.to_string(),
+            None => dim_style
+                .apply_to(format!(
+                    "<{} bytes binary>",
+                    entry.get("size").and_then(|v| v.as_u64()).unwrap_or(0)
+                ))
+                .to_string(),
+        };
+        println!(
+            "  {:<8} {}  {}",
+            format_value(entry.get("type").unwrap_or(&Value::Null)),
+            format_value(entry.get("key").unwrap_or(&Value::Null)),
+            value
+        );
+    }
 
-        if !has_next {
-            break;
+    let streams = result
+        .get("named_streams")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    println!();
+    println!(
+        "{}",
+        header_style.apply_to(format!("Streams: {}", streams.len()))
+    );
+    for stream in &streams {
+        println!(
+            "  {}  {}",
+            stream.get("name").and_then(|v| v.as_str()).unwrap_or("?"),
+            format_size(entry_size(stream))
+        );
+    }
+}
+
+/// Condition polled for by `fs wait`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WaitCondition {
+    Exists,
+    Absent,
+    SizeStable,
+}
+
+impl WaitCondition {
+    fn parse(s: &str) -> Result<Self> {
+        match s {
+            "exists" => Ok(Self::Exists),
+            "absent" => Ok(Self::Absent),
+            "size-stable" => Ok(Self::SizeStable),
+            other => anyhow::bail!(
+                "unknown wait condition '{}' (expected exists, absent, size-stable)",
+                other
+            ),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Exists => "exists",
+            Self::Absent => "absent",
+            Self::SizeStable => "size-stable",
         }
+    }
+}
+
+/// Outcome of a single poll: the path's size if it exists, `None` if the cluster
+/// reports it missing
+fn poll_path(client: &QumuloClient, path: &str) -> Result<Option<u64>> {
+    match client.get_file_attr(path) {
+        Ok(attrs) => Ok(Some(entry_size(&attrs))),
+        Err(e) => match e.downcast_ref::<QontrolError>() {
+            Some(QontrolError::ApiError { status: 404, .. }) => Ok(None),
+            _ => Err(e),
+        },
+    }
+}
+
+/// Decide whether a condition holds given the current and previous poll results
+fn condition_met(
+    condition: WaitCondition,
+    current: Option<u64>,
+    previous: Option<Option<u64>>,
+) -> bool {
+    match condition {
+        WaitCondition::Exists => current.is_some(),
+        WaitCondition::Absent => current.is_none(),
+        // Stable means present with the same size on two consecutive polls
+        WaitCondition::SizeStable => current.is_some() && previous == Some(current),
+    }
+}
 
-        // Check if we've hit the limit
-        if let Some(l) = remaining_limit {
-            if total_count >= l as u64 {
-                break;
+/// A count or size from an aggregates response, which sends them as strings
+fn aggregate_count(v: &Value) -> u64 {
+    v.as_str()
+        .and_then(|s| s.parse::<u64>().ok())
+        .or_else(|| v.as_u64())
+        .unwrap_or(0)
+}
+
+/// An aggregates directory path (`/home/`) as shown (`/home`)
+fn aggregate_path(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    }
+}
+
+/// One row of `fs du`.
+#[derive(Debug, Serialize)]
+struct DuDir {
+    path: String,
+    used_bytes: u64,
+    files: u64,
+    directories: u64,
+    /// Data held only by snapshots of this directory; None when snapshots
+    /// couldn't be read
+    snapshot_bytes: Option<u64>,
+}
+
+/// Report capacity used, file and directory counts, and snapshot-held data
+/// for `path` and its subdirectories down to `depth` levels, from recursive
+/// aggregates rather than a walk of every file. Snapshot-held data is what
+/// snapshots taken of each directory keep beyond its live data.
+pub fn du(client: &QumuloClient, path: &str, depth: usize, json_mode: bool) -> Result<()> {
+    let budget = AggregatesBudget {
+        max_depth: depth,
+        ..AggregatesBudget::default()
+    };
+    let dirs = client.get_recursive_aggregates_paged(path, budget)?;
+    let held = snapshot_bytes_by_source(client);
+
+    let mut rows: Vec<DuDir> = dirs
+        .iter()
+        .map(|dir| {
+            let dir_path = dir["path"].as_str().unwrap_or_default();
+            let id = dir["id"].as_str().unwrap_or_default();
+            DuDir {
+                path: aggregate_path(dir_path).to_string(),
+                used_bytes: aggregate_count(&dir["total_capacity"]),
+                files: aggregate_count(&dir["total_files"]),
+                directories: aggregate_count(&dir["total_directories"]),
+                snapshot_bytes: held.as_ref().map(|held| held.get(id).copied().unwrap_or(0)),
             }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if json_mode {
+        let result = json!({
+            "path": path,
+            "depth": depth,
+            "directories": rows,
+        });
+        print_json(&result)?;
+        return Ok(());
+    }
+
+    println!("{:>10}  {:>12}  {:>10}  PATH", "USED", "FILES", "SNAPSHOTS");
+    let dir_style = Style::new().blue().bold();
+    for row in &rows {
+        println!(
+            "{:>10}  {:>12}  {:>10}  {}",
+            format_bytes(row.used_bytes),
+            row.files,
+            row.snapshot_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            dir_style.apply_to(&row.path)
+        );
+    }
+    Ok(())
+}
+
+/// Bytes held by snapshots, summed by the file ID each snapshot was taken
+/// of. None when the snapshot list or its capacity can't be read.
+fn snapshot_bytes_by_source(
+    client: &QumuloClient,
+) -> Option<std::collections::HashMap<String, u64>> {
+    let fetched = client.get_snapshots().and_then(|snapshots| {
+        client
+            .get_snapshot_capacity_per_snapshot()
+            .map(|capacity| (snapshots, capacity))
+    });
+    let (snapshots, capacity) = match fetched {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to read snapshot capacity");
+            return None;
         }
+    };
+    let entries = |v: &Value| v["entries"].as_array().cloned().unwrap_or_default();
+    let used: std::collections::HashMap<u64, u64> = entries(&capacity)
+        .iter()
+        .filter_map(|e| {
+            let bytes = e["capacity_used_bytes"].as_str()?.parse().ok()?;
+            Some((e["id"].as_u64()?, bytes))
+        })
+        .collect();
+    let mut held = std::collections::HashMap::new();
+    for snapshot in entries(&snapshots) {
+        let (Some(id), Some(source)) =
+            (snapshot["id"].as_u64(), snapshot["source_file_id"].as_str())
+        else {
+            continue;
+        };
+        *held.entry(source.to_string()).or_insert(0) += used.get(&id).copied().unwrap_or(0);
+    }
+    Some(held)
+}
+
+/// One row of `fs top`.
+#[derive(Debug, Serialize)]
+struct TopDir {
+    path: String,
+    capacity_bytes: u64,
+    files: u64,
+    directories: u64,
+    /// Owner's name, or its auth ID when it can't be resolved
+    owner: String,
+    owner_id: Option<String>,
+    /// Why the owner lookup failed outright
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
-        after = response
-            .get("paging")
-            .and_then(|p| p.get("next"))
-            .and_then(|n| n.as_str())
-            .map(|s| s.to_string());
+impl TopDir {
+    /// A row for one recursive-aggregates entry, before its owner is looked up
+    fn new(dir: &Value) -> Self {
+        TopDir {
+            path: aggregate_path(dir["path"].as_str().unwrap_or_default()).to_string(),
+            capacity_bytes: aggregate_count(&dir["total_capacity"]),
+            files: aggregate_count(&dir["total_files"]),
+            directories: aggregate_count(&dir["total_directories"]),
+            owner: "-".to_string(),
+            owner_id: None,
+            error: None,
+        }
     }
+}
 
-    // Clear progress line
-    if is_tty && total_count > 0 {
-        eprint!("\r\x1b[K");
-        io::stderr().flush().ok();
+/// The recursive-aggregates field `fs top --by` ranks on
+fn top_key(by: &str) -> Result<&'static str> {
+    match by {
+        "capacity" => Ok("total_capacity"),
+        "files" => Ok("total_files"),
+        other => anyhow::bail!("unknown --by '{}' (expected capacity or files)", other),
     }
+}
 
-    if all_entries.is_empty() {
-        println!("(empty directory)");
+/// Show the `limit` largest directories below `path`, by capacity or file
+/// count, looking up to `max_depth` levels down. Sizes come from recursive
+/// aggregates, so nothing is listed file by file; directories nest, so a
+/// subtree and its parent can both appear. Each directory's owner is
+/// resolved to a name through `identities` when given.
+pub fn top(
+    client: &QumuloClient,
+    path: &str,
+    by: &str,
+    limit: usize,
+    max_depth: usize,
+    identities: Option<&Identities>,
+    json_mode: bool,
+) -> Result<()> {
+    let key = top_key(by)?;
+    let budget = AggregatesBudget {
+        max_depth,
+        ..AggregatesBudget::default()
+    };
+    let root = path.trim_end_matches('/');
+    let mut dirs: Vec<Value> = client
+        .get_recursive_aggregates_paged(path, budget)?
+        .into_iter()
+        .filter(|d| {
+            d["path"]
+                .as_str()
+                .is_some_and(|p| p.trim_end_matches('/') != root)
+        })
+        .collect();
+    dirs.sort_by_key(|d| std::cmp::Reverse(aggregate_count(&d[key])));
+    dirs.truncate(limit);
+
+    let rows: Vec<TopDir> = thread::scope(|s| {
+        let handles: Vec<_> = dirs
+            .iter()
+            .map(|dir| {
+                s.spawn(move || {
+                    let mut row = TopDir::new(dir);
+                    row.owner_id = client
+                        .get_file_attr(&row.path)
+                        .ok()
+                        .and_then(|attrs| attrs["owner"].as_str().map(str::to_string));
+                    row.owner = match (&row.owner_id, identities) {
+                        (Some(id), Some(identities)) => identities.display(client, id),
+                        (Some(id), None) => id.clone(),
+                        (None, _) => "-".to_string(),
+                    };
+                    row
+                })
+            })
+            .collect();
+        dirs.iter()
+            .zip(handles)
+            .map(|(dir, h)| {
+                h.join().unwrap_or_else(|_| TopDir {
+                    error: Some("owner lookup panicked".to_string()),
+                    ..TopDir::new(dir)
+                })
+            })
+            .collect()
+    });
+
+    if json_mode {
+        let result = json!({
+            "path": path,
+            "by": by,
+            "max_depth": max_depth,
+            "directories": rows,
+        });
+        print_json(&result)?;
+        return Ok(());
+    }
+    if rows.is_empty() {
+        println!("No directories under {}", path);
         return Ok(());
     }
 
-    sort_entries(&mut all_entries, sort);
+    let owner_w = rows
+        .iter()
+        .map(|r| r.owner.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    println!(
+        "{:>10}  {:>12}  {:<owner_w$}  PATH",
+        "CAPACITY",
+        "FILES",
+        "OWNER",
+        owner_w = owner_w
+    );
+    let dir_style = Style::new().blue().bold();
+    for row in &rows {
+        println!(
+            "{:>10}  {:>12}  {:<owner_w$}  {}",
+            format_size(row.capacity_bytes),
+            row.files,
+            row.owner,
+            dir_style.apply_to(&row.path),
+            owner_w = owner_w
+        );
+    }
+    Ok(())
+}
+
+/// Longest pause between polls while the cluster is answering 429 or 503
+const MAX_WAIT_BACKOFF: Duration = Duration::from_secs(60);
 
-    if long {
-        print_long_listing(&all_entries);
-    } else {
-        print_short_listing(&all_entries);
+/// Poll a path until it exists, disappears, or stops changing size.
+///
+/// Exits with an error once `timeout_secs` elapses so pipeline steps fail
+/// rather than hang. Transport errors are retried on the next poll, and a busy
+/// cluster (HTTP 429 or 503) with a doubling delay; other API errors (e.g.
+/// permission denied) fail immediately.
+pub fn wait(
+    client: &QumuloClient,
+    path: &str,
+    condition: &str,
+    timeout_secs: u64,
+    interval_secs: u64,
+    json_mode: bool,
+) -> Result<()> {
+    let condition = WaitCondition::parse(condition)?;
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(timeout_secs);
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let mut previous: Option<Option<u64>> = None;
+    // Doubles on each consecutive busy response, back to `interval` on success
+    let mut backoff = interval;
+
+    loop {
+        let mut pause = interval;
+        match poll_path(client, path) {
+            Ok(current) => {
+                backoff = interval;
+                if condition_met(condition, current, previous) {
+                    let elapsed = start.elapsed().as_secs();
+                    if json_mode {
+                        let result = json!({
+                            "path": path,
+                            "condition": condition.as_str(),
+                            "met": true,
+                            "elapsed_secs": elapsed,
+                            "size": current.map(|s| s.to_string()),
+                        });
+                        print_json(&result)?;
+                    } else {
+                        println!("{}: {} (after {}s)", path, condition.as_str(), elapsed);
+                    }
+                    return Ok(());
+                }
+                previous = Some(current);
+            }
+            Err(e) => match e.downcast_ref::<QontrolError>() {
+                Some(QontrolError::ApiError {
+                    status: 429 | 503, ..
+                }) => {
+                    tracing::warn!(
+                        "poll of {} failed, retrying in {}s: {:#}",
+                        path,
+                        backoff.as_secs(),
+                        e
+                    );
+                    pause = backoff;
+                    backoff = (backoff * 2).min(MAX_WAIT_BACKOFF);
+                }
+                Some(_) => return Err(e.context(format!("failed to check path: {}", path))),
+                None => tracing::warn!("poll of {} failed, will retry: {:#}", path, e),
+            },
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            anyhow::bail!(
+                "timed out after {}s waiting for {} to be {}",
+                timeout_secs,
+                path,
+                condition.as_str()
+            );
+        }
+        thread::sleep(pause.min(deadline - now));
+    }
+}
+
+/// Outcome of creating one directory with [`mkdirs`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MkdirOutcome {
+    Created,
+    Exists,
+    Failed,
+}
+
+impl MkdirOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            MkdirOutcome::Created => "created",
+            MkdirOutcome::Exists => "exists",
+            MkdirOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// Create every directory listed in `file` (one absolute path per line; blank
+/// lines and `#` comments are skipped, `-` reads stdin).
+///
+/// Paths are created shallowest first, one depth at a time, so a manifest can
+/// list parents and children in any order. Up to `jobs` requests run at once
+/// within a depth. Directories that already exist are reported and left
+/// untouched; `mode` and `owner` are applied only to newly created ones.
+/// Any failure makes the command exit non-zero after the full report.
+pub fn mkdirs(
+    client: &QumuloClient,
+    file: &str,
+    mode: Option<&str>,
+    owner: Option<&str>,
+    jobs: usize,
+    json_mode: bool,
+) -> Result<()> {
+    let contents = if file == "-" {
+        io::read_to_string(io::stdin()).context("failed to read directory list from stdin")?
+    } else {
+        std::fs::read_to_string(file).with_context(|| format!("failed to read {}", file))?
+    };
+    let paths = parse_mkdirs_manifest(&contents)?;
+    let attrs = mkdir_attrs(mode, owner)?;
+
+    let mut by_depth: Vec<&String> = paths.iter().collect();
+    by_depth.sort_by_key(|p| p.matches('/').count());
+
+    let results = std::sync::Mutex::new(Vec::with_capacity(paths.len()));
+    let mut start = 0;
+    while start < by_depth.len() {
+        let depth = by_depth[start].matches('/').count();
+        let end = by_depth[start..]
+            .iter()
+            .position(|p| p.matches('/').count() != depth)
+            .map_or(by_depth.len(), |i| start + i);
+        let level = &by_depth[start..end];
+
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        thread::scope(|s| {
+            for _ in 0..jobs.clamp(1, level.len()) {
+                s.spawn(|| loop {
+                    let i = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let Some(path) = level.get(i) else {
+                        break;
+                    };
+                    let (outcome, error) = create_one(client, path, attrs.as_ref());
+                    if !json_mode {
+                        match &error {
+                            Some(e) => println!("{:<8} {}: {}", outcome.as_str(), path, e),
+                            None => println!("{:<8} {}", outcome.as_str(), path),
+                        }
+                    }
+                    results
+                        .lock()
+                        .unwrap()
+                        .push(((*path).clone(), outcome, error));
+                });
+            }
+        });
+        start = end;
+    }
+
+    let mut results = results.into_inner().unwrap();
+    // Report in manifest order regardless of completion order
+    results.sort_by_key(|(path, _, _)| paths.iter().position(|p| p == path));
+    let count = |o: MkdirOutcome| results.iter().filter(|(_, r, _)| *r == o).count();
+    let (created, existed, failed) = (
+        count(MkdirOutcome::Created),
+        count(MkdirOutcome::Exists),
+        count(MkdirOutcome::Failed),
+    );
+
+    if json_mode {
+        let entries: Vec<Value> = results
+            .iter()
+            .map(|(path, outcome, error)| {
+                json!({"path": path, "status": outcome.as_str(), "error": error})
+            })
+            .collect();
+        let result = json!({
+            "results": entries,
+            "created": created,
+            "existed": existed,
+            "failed": failed,
+        });
+        print_json(&result)?;
+    } else {
+        println!(
+            "{} created, {} already existed, {} failed",
+            created, existed, failed
+        );
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} directories failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// Absolute, de-duplicated directory paths from a manifest, without trailing slashes
+fn parse_mkdirs_manifest(contents: &str) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = Vec::new();
+    for (n, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with('/') {
+            anyhow::bail!("line {}: '{}' is not an absolute path", n + 1, line);
+        }
+        let path = line.trim_end_matches('/');
+        if path.is_empty() {
+            continue;
+        }
+        if !paths.iter().any(|p| p == path) {
+            paths.push(path.to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// Attribute patch for newly created directories, if any was requested
+fn mkdir_attrs(mode: Option<&str>, owner: Option<&str>) -> Result<Option<Value>> {
+    let mut attrs = serde_json::Map::new();
+    if let Some(mode) = mode {
+        let valid = (3..=4).contains(&mode.len()) && mode.chars().all(|c| ('0'..='7').contains(&c));
+        if !valid {
+            anyhow::bail!("invalid mode '{}' (expected octal, e.g. 0755)", mode);
+        }
+        attrs.insert("mode".into(), json!(format!("{:0>4}", mode)));
+    }
+    if let Some(owner) = owner {
+        let (id_type, id_value) = if owner.chars().all(|c| c.is_ascii_digit()) {
+            ("NFS_UID", owner)
+        } else if owner.starts_with("S-1-") {
+            ("SMB_SID", owner)
+        } else {
+            ("LOCAL_USER", owner)
+        };
+        attrs.insert(
+            "owner_details".into(),
+            json!({"id_type": id_type, "id_value": id_value}),
+        );
+    }
+    Ok((!attrs.is_empty()).then_some(Value::Object(attrs)))
+}
+
+fn create_one(
+    client: &QumuloClient,
+    path: &str,
+    attrs: Option<&Value>,
+) -> (MkdirOutcome, Option<String>) {
+    let (parent, name) = match path.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => ("/", path),
+    };
+    if let Err(e) = client.create_directory(parent, name) {
+        return match e.downcast_ref::<QontrolError>() {
+            Some(QontrolError::ApiError { status: 409, .. }) => (MkdirOutcome::Exists, None),
+            _ => (MkdirOutcome::Failed, Some(format!("{:#}", e))),
+        };
+    }
+    if let Some(attrs) = attrs {
+        if let Err(e) = client.set_file_attr(path, attrs) {
+            let error = format!("created, but failed to set attributes: {:#}", e);
+            return (MkdirOutcome::Failed, Some(error));
+        }
+    }
+    (MkdirOutcome::Created, None)
+}
+
+/// A remote file queued for download by [`get`]
+struct Download {
+    remote: String,
+    local: std::path::PathBuf,
+    id: String,
+    size: u64,
+    modified: Option<std::time::SystemTime>,
+}
+
+/// Download `remote` to `local`.
+///
+/// A file is written to `local`, or inside it if `local` is an existing
+/// directory. A directory needs `recursive`: the tree is recreated under
+/// `local` and its files are fetched with up to `jobs` parallel streams.
+/// Files of `multipart_over` bytes or more are instead fetched one at a time,
+/// each split into `jobs` ranged reads (see [`download_ranges`]).
+/// Modification times are preserved. Symlinks and special files are skipped.
+/// Files are written to a `.part` name and renamed once complete, so an
+/// interrupted run leaves no truncated files behind. Any failure makes the
+/// command exit non-zero after the summary.
+pub fn get(
+    client: &QumuloClient,
+    remote: &str,
+    local: &str,
+    recursive: bool,
+    jobs: usize,
+    multipart_over: &str,
+    json_mode: bool,
+) -> Result<()> {
+    let multipart_over = crate::commands::quota::parse_size(multipart_over)?;
+    let started = Instant::now();
+    let attrs = client
+        .get_file_attr(remote)
+        .with_context(|| format!("failed to stat {}", remote))?;
+    let local = std::path::Path::new(local);
+
+    let mut dirs: Vec<(std::path::PathBuf, Option<std::time::SystemTime>)> = Vec::new();
+    let mut files = Vec::new();
+    let mut skipped = 0;
+    match entry_type(&attrs) {
+        "FS_FILE_TYPE_DIRECTORY" => {
+            if !recursive {
+                anyhow::bail!("{} is a directory (use --recursive)", remote);
+            }
+            dirs.push((local.to_path_buf(), modified_time(&attrs)));
+            let opts = WalkOptions {
+                max_depth: u32::MAX,
+                sort: &SortOrder::default(),
+                follow: false,
+                link_targets: false,
+            };
+            walk(client, remote, &opts, &mut |item| {
+                let relative = item
+                    .path
+                    .strip_prefix(remote)
+                    .unwrap_or(&item.path)
+                    .trim_start_matches('/');
+                let target = local.join(relative);
+                match entry_type(&item.entry) {
+                    "FS_FILE_TYPE_DIRECTORY" => dirs.push((target, modified_time(&item.entry))),
+                    "FS_FILE_TYPE_FILE" => match item.entry.get("id").and_then(|v| v.as_str()) {
+                        Some(id) => files.push(Download {
+                            remote: item.path.clone(),
+                            local: target,
+                            id: id.to_string(),
+                            size: entry_size(&item.entry),
+                            modified: modified_time(&item.entry),
+                        }),
+                        None => skipped += 1,
+                    },
+                    _ => skipped += 1,
+                }
+                ControlFlow::Continue(())
+            })?;
+        }
+        "FS_FILE_TYPE_FILE" => {
+            let name = remote
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or(remote);
+            let target = if local.is_dir() {
+                local.join(name)
+            } else {
+                local.to_path_buf()
+            };
+            let id = attrs
+                .get("id")
+                .and_then(|v| v.as_str())
+                .context("unexpected response: missing file id")?;
+            files.push(Download {
+                remote: remote.to_string(),
+                local: target,
+                id: id.to_string(),
+                size: entry_size(&attrs),
+                modified: modified_time(&attrs),
+            });
+        }
+        other => anyhow::bail!("{} is not a file or directory ({})", remote, other),
+    }
+
+    for (dir, _) in &dirs {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let multipart = |file: &Download| jobs > 1 && file.size > 0 && file.size >= multipart_over;
+    let tracker = Tracker::start("download", Some(files.len() as u64), true);
+    let report = |file: &Download, outcome: &Result<u64>| {
+        if let Err(e) = outcome {
+            if !json_mode {
+                eprintln!("failed   {}: {:#}", file.remote, e);
+            }
+        }
+        let bytes = outcome.as_ref().copied().unwrap_or(0);
+        tracker.advance(bytes, Some(&file.remote));
+    };
+    let results = std::sync::Mutex::new(Vec::with_capacity(files.len()));
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, files.len().max(1)) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let Some(file) = files.get(i) else {
+                    break;
+                };
+                if multipart(file) {
+                    continue;
+                }
+                let outcome = download_one(client, file, 1);
+                report(file, &outcome);
+                results.lock().unwrap().push((i, outcome));
+            });
+        }
+    });
+    // Large files already use every stream, so they go one at a time
+    for (i, file) in files.iter().enumerate().filter(|(_, f)| multipart(f)) {
+        let outcome = download_one(client, file, jobs);
+        report(file, &outcome);
+        results.lock().unwrap().push((i, outcome));
+    }
+
+    // Writing files bumps their directories' mtimes; restore deepest first
+    for (dir, modified) in dirs.iter().rev() {
+        if let Some(modified) = modified {
+            set_modified(dir, *modified);
+        }
+    }
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(i, _)| *i);
+    let bytes: u64 = results.iter().filter_map(|(_, r)| r.as_ref().ok()).sum();
+    let failures: Vec<(&str, String)> = results
+        .iter()
+        .filter_map(|(i, r)| {
+            r.as_ref()
+                .err()
+                .map(|e| (files[*i].remote.as_str(), format!("{:#}", e)))
+        })
+        .collect();
+    let downloaded = results.len() - failures.len();
+    let elapsed = started.elapsed().as_secs_f64();
+
+    if json_mode {
+        let result = json!({
+            "remote": remote,
+            "local": local.display().to_string(),
+            "files": downloaded,
+            "bytes": bytes,
+            "failed": failures.len(),
+            "skipped": skipped,
+            "elapsed_secs": (elapsed * 10.0).round() / 10.0,
+            "failures": failures
+                .iter()
+                .map(|(path, error)| json!({"path": path, "error": error}))
+                .collect::<Vec<_>>(),
+        });
+        print_json(&result)?;
+    } else {
+        println!(
+            "Downloaded {} file{} ({}) to {} in {:.1}s: {} failed, {} skipped",
+            downloaded,
+            if downloaded == 1 { "" } else { "s" },
+            format_size(bytes),
+            local.display(),
+            elapsed,
+            failures.len(),
+            skipped
+        );
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!(
+            "{} of {} files failed to download",
+            failures.len(),
+            files.len()
+        );
+    }
+    Ok(())
+}
+
+/// Fetch one file over `streams` connections (a plain read when 1).
+fn download_one(client: &QumuloClient, file: &Download, streams: usize) -> Result<u64> {
+    let mut part_name = file.local.clone().into_os_string();
+    part_name.push(".part");
+    let part = std::path::PathBuf::from(part_name);
+
+    let result = (|| {
+        let bytes = if streams > 1 {
+            download_ranges(client, file, &part, streams)?
+        } else {
+            let mut out = std::fs::File::create(&part)
+                .with_context(|| format!("failed to create {}", part.display()))?;
+            let bytes = client.download_file(&file.id, &mut out)?;
+            out.flush()?;
+            bytes
+        };
+        std::fs::rename(&part, &file.local)
+            .with_context(|| format!("failed to write {}", file.local.display()))?;
+        Ok(bytes)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&part);
+    } else if let Some(modified) = file.modified {
+        set_modified(&file.local, modified);
+    }
+    result
+}
+
+/// Fetch a file as `streams` concurrent ranged reads, each written at its
+/// offset in `part`.
+///
+/// The API has no content checksum to verify the reassembled file against,
+/// so integrity rests on the file's ETag, which names one version of its
+/// whole contents: it is read from the attributes before the transfer, and
+/// every range must come back with the same one. A missing or different
+/// ETag means the pieces may come from different versions, so the download
+/// fails instead of leaving a torn file.
+fn download_ranges(
+    client: &QumuloClient,
+    file: &Download,
+    part: &std::path::Path,
+    streams: usize,
+) -> Result<u64> {
+    let (attrs, etag) = client
+        .get_file_attr_etag(&file.remote)
+        .with_context(|| format!("failed to stat {}", file.remote))?;
+    if entry_size(&attrs) != file.size {
+        anyhow::bail!("{} changed since it was listed", file.remote);
+    }
+    let Some(etag) = etag else {
+        anyhow::bail!(
+            "cluster sent no ETag for {}, so a multi-part download can't be verified; \
+             raise --multipart-over above its size",
+            file.remote
+        );
+    };
+    std::fs::File::create(part)
+        .and_then(|out| out.set_len(file.size))
+        .with_context(|| format!("failed to create {}", part.display()))?;
+    let ranges = split_ranges(file.size, streams);
+
+    thread::scope(|s| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .map(|&(offset, length)| {
+                let etag = etag.as_str();
+                s.spawn(move || fetch_range(client, file, etag, part, offset, length))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("download thread panicked")))
+            })
+            .collect::<Result<Vec<()>>>()
+    })?;
+    Ok(file.size)
+}
+
+/// Split `size` bytes into at most `streams` contiguous `(offset, length)`
+/// ranges of near-equal length.
+fn split_ranges(size: u64, streams: usize) -> Vec<(u64, u64)> {
+    let chunk = size.div_ceil(streams.max(1) as u64).max(1);
+    (0..size)
+        .step_by(chunk as usize)
+        .map(|offset| (offset, chunk.min(size - offset)))
+        .collect()
+}
+
+/// Read one range of a file into `part` at its offset, failing unless it
+/// comes from the file version `etag` names.
+fn fetch_range(
+    client: &QumuloClient,
+    file: &Download,
+    etag: &str,
+    part: &std::path::Path,
+    offset: u64,
+    length: u64,
+) -> Result<()> {
+    let (mut reader, range_etag) = client.open_file_range(&file.id, offset, length)?;
+    if range_etag.as_deref() != Some(etag) {
+        anyhow::bail!(
+            "{} changed during download (ETag {} became {} at offset {}); try again",
+            file.remote,
+            etag,
+            range_etag.as_deref().unwrap_or("none"),
+            offset
+        );
+    }
+    let mut out = std::fs::OpenOptions::new()
+        .write(true)
+        .open(part)
+        .with_context(|| format!("failed to open {}", part.display()))?;
+    io::Seek::seek(&mut out, io::SeekFrom::Start(offset))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut received = 0u64;
+    loop {
+        let n = io::Read::read(&mut reader, &mut buf).context("failed to read file data")?;
+        if n == 0 {
+            break;
+        }
+        out.write_all(&buf[..n])
+            .with_context(|| format!("failed to write {}", part.display()))?;
+        received += n as u64;
+    }
+    if received != length {
+        anyhow::bail!(
+            "short read at offset {}: got {} of {} bytes (file changed during download?)",
+            offset,
+            received,
+            length
+        );
+    }
+    Ok(())
+}
+
+fn modified_time(entry: &Value) -> Option<std::time::SystemTime> {
+    let ts = entry.get("modification_time").and_then(|v| v.as_str())?;
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(Into::into)
+}
+
+/// Best effort: some platforms can't set a directory's mtime
+fn set_modified(path: &std::path::Path, modified: std::time::SystemTime) {
+    let result = std::fs::File::open(path).and_then(|f| f.set_modified(modified));
+    if let Err(e) = result {
+        tracing::debug!("failed to set mtime on {}: {}", path.display(), e);
+    }
+}
+
+/// A line matched by [`grep`]
+struct GrepMatch {
+    line: usize,
+    text: String,
+}
+
+/// Search the contents of files under `path` for a fixed string.
+///
+/// Candidate files come from a full walk of `path` (symlinks are not
+/// followed), narrowed to names matching the `name` glob and to sizes up to
+/// `max_size` bytes. Up to `jobs` files are streamed and searched at once;
+/// matches print as `path:line:text` a file at a time. Like `grep`, files
+/// whose matching lines contain NUL bytes are reported as binary. Exits
+/// non-zero when nothing matches or a file could not be read.
+#[allow(clippy::too_many_arguments)]
+pub fn grep(
+    client: &QumuloClient,
+    pattern: &str,
+    path: &str,
+    name: Option<&str>,
+    max_size: Option<&str>,
+    ignore_case: bool,
+    jobs: usize,
+    json_mode: bool,
+) -> Result<()> {
+    if pattern.is_empty() {
+        anyhow::bail!("pattern must not be empty");
+    }
+    let max_size = max_size
+        .map(crate::commands::quota::parse_size)
+        .transpose()?;
+    let needle = if ignore_case {
+        pattern.to_lowercase()
+    } else {
+        pattern.to_string()
+    };
+
+    let attrs = client
+        .get_file_attr(path)
+        .with_context(|| format!("failed to stat {}", path))?;
+    let mut candidates: Vec<(String, String)> = Vec::new();
+    let mut consider = |file_path: &str, entry: &Value| {
+        if entry_type(entry) != "FS_FILE_TYPE_FILE" {
+            return;
+        }
+        let base = file_path.rsplit('/').next().unwrap_or(file_path);
+        if name.is_some_and(|glob| !glob_match(glob, base)) {
+            return;
+        }
+        if max_size.is_some_and(|max| entry_size(entry) > max) {
+            return;
+        }
+        if let Some(id) = entry.get("id").and_then(|v| v.as_str()) {
+            candidates.push((file_path.to_string(), id.to_string()));
+        }
+    };
+    if entry_type(&attrs) == "FS_FILE_TYPE_DIRECTORY" {
+        let opts = WalkOptions {
+            max_depth: u32::MAX,
+            sort: &SortOrder::default(),
+            follow: false,
+            link_targets: false,
+        };
+        walk(client, path, &opts, &mut |item| {
+            consider(&item.path, &item.entry);
+            ControlFlow::Continue(())
+        })?;
+    } else {
+        consider(path, &attrs);
+    }
+
+    let matched_files = std::sync::atomic::AtomicUsize::new(0);
+    let failures = std::sync::atomic::AtomicUsize::new(0);
+    let json_matches = std::sync::Mutex::new(Vec::new());
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let path_style = Style::new().magenta();
+    let line_style = Style::new().green();
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, candidates.len().max(1)) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let Some((file_path, id)) = candidates.get(i) else {
+                    break;
+                };
+                let (matches, binary) = match search_file(client, id, &needle, ignore_case) {
+                    Ok(found) => found,
+                    Err(e) => {
+                        eprintln!("warning: {}: {:#}", file_path, e);
+                        failures.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        continue;
+                    }
+                };
+                if matches.is_empty() && !binary {
+                    continue;
+                }
+                matched_files.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+                if json_mode {
+                    let mut all = json_matches.lock().unwrap();
+                    if binary {
+                        all.push(json!({"path": file_path, "binary": true}));
+                    }
+                    for m in &matches {
+                        all.push(json!({"path": file_path, "line": m.line, "text": m.text}));
+                    }
+                    continue;
+                }
+                // One file's matches stay together even with parallel searches
+                let stdout = io::stdout();
+                let mut out = stdout.lock();
+                if binary {
+                    let _ = writeln!(out, "Binary file {} matches", file_path);
+                }
+                for m in &matches {
+                    let _ = writeln!(
+                        out,
+                        "{}:{}:{}",
+                        path_style.apply_to(file_path),
+                        line_style.apply_to(m.line),
+                        m.text
+                    );
+                }
+            });
+        }
+    });
+
+    if json_mode {
+        let mut matches = json_matches.into_inner().unwrap();
+        // Deterministic order regardless of which search finished first
+        matches.sort_by(|a, b| {
+            let key = |v: &Value| (v["path"].as_str().map(String::from), v["line"].as_u64());
+            key(a).cmp(&key(b))
+        });
+        let result = Value::Array(matches);
+        print_json(&result)?;
+    }
+
+    let failures = failures.into_inner();
+    if failures > 0 {
+        anyhow::bail!(
+            "{} of {} files could not be searched",
+            failures,
+            candidates.len()
+        );
+    }
+    if matched_files.into_inner() == 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Stream one file and collect its matching lines. A match on a line with a
+/// NUL byte marks the file as binary and stops the search.
+fn search_file(
+    client: &QumuloClient,
+    id: &str,
+    needle: &str,
+    ignore_case: bool,
+) -> Result<(Vec<GrepMatch>, bool)> {
+    let reader = io::BufReader::new(client.open_file(id)?);
+    let mut matches = Vec::new();
+    for (n, line) in io::BufRead::split(reader, b'\n').enumerate() {
+        let line = line.context("failed to read file data")?;
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end_matches('\r');
+        let found = if ignore_case {
+            text.to_lowercase().contains(needle)
+        } else {
+            text.contains(needle)
+        };
+        if !found {
+            continue;
+        }
+        if line.contains(&0) {
+            return Ok((Vec::new(), true));
+        }
+        matches.push(GrepMatch {
+            line: n + 1,
+            text: text.to_string(),
+        });
+    }
+    Ok((matches, false))
+}
+
+/// Bytes hashed per file in the first (sampled) dedupe pass
+const DEDUPE_SAMPLE_BYTES: u64 = 64 * 1024;
+
+/// A regular file found by a walk, for commands that read contents in bulk
+struct RemoteFile {
+    path: String,
+    id: String,
+    size: u64,
+}
+
+/// Find sets of identical files under `path` and what removing the extra
+/// copies would save, as a planning aid ahead of clone-based dedupe.
+///
+/// Files are grouped by size, then by a checksum of their first 64 KiB, then
+/// (unless `quick`) by a checksum of their full contents, so only files that
+/// share a size are ever downloaded, and only candidates that still collide
+/// after sampling are read in full. Hard links to one file are counted once.
+/// Files smaller than `min_size` (default: 1 byte) are ignored. With `quick`
+/// the sets are probable duplicates: equal size and leading bytes.
+pub fn dedupe_report(
+    client: &QumuloClient,
+    path: &str,
+    min_size: Option<&str>,
+    quick: bool,
+    jobs: usize,
+    json_mode: bool,
+) -> Result<()> {
+    let min_size = min_size
+        .map(crate::commands::quota::parse_size)
+        .transpose()?
+        .unwrap_or(1)
+        .max(1);
+
+    let start = Instant::now();
+    let mut files: Vec<RemoteFile> = Vec::new();
+    let mut seen_ids = HashSet::new();
+    let opts = WalkOptions {
+        max_depth: u32::MAX,
+        sort: &SortOrder::default(),
+        follow: false,
+        link_targets: false,
+    };
+    walk(client, path, &opts, &mut |item| {
+        let size = entry_size(&item.entry);
+        if entry_type(&item.entry) == "FS_FILE_TYPE_FILE" && size >= min_size {
+            if let Some(id) = item.entry.get("id").and_then(|v| v.as_str()) {
+                if seen_ids.insert(id.to_string()) {
+                    files.push(RemoteFile {
+                        path: item.path.clone(),
+                        id: id.to_string(),
+                        size,
+                    });
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    })?;
+    let scanned_bytes: u64 = files.iter().map(|f| f.size).sum();
+
+    let failures = std::sync::atomic::AtomicUsize::new(0);
+    let mut groups = group_by(files.iter().collect(), |f| Some(f.size as u128));
+    let sampled: Vec<&RemoteFile> = groups.iter().flatten().copied().collect();
+    let samples = checksum_files(
+        client,
+        &sampled,
+        Some(DEDUPE_SAMPLE_BYTES),
+        jobs,
+        &failures,
+        fnv_digest,
+    );
+    groups = groups
+        .into_iter()
+        .flat_map(|group| group_by(group, |f| samples.get(&f.id).copied()))
+        .collect();
+    if !quick {
+        // Files no larger than the sample were already hashed in full
+        let remaining: Vec<&RemoteFile> = groups
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|f| f.size > DEDUPE_SAMPLE_BYTES)
+            .collect();
+        let full = checksum_files(client, &remaining, None, jobs, &failures, fnv_digest);
+        groups = groups
+            .into_iter()
+            .flat_map(|group| {
+                group_by(group, |f| {
+                    if f.size > DEDUPE_SAMPLE_BYTES {
+                        full.get(&f.id).copied()
+                    } else {
+                        samples.get(&f.id).copied()
+                    }
+                })
+            })
+            .collect();
+    }
+
+    // Biggest savings first, paths sorted within each set
+    for group in &mut groups {
+        group.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    groups.sort_by(|a, b| {
+        let savings = |g: &[&RemoteFile]| g[0].size * (g.len() as u64 - 1);
+        savings(b)
+            .cmp(&savings(a))
+            .then_with(|| a[0].path.cmp(&b[0].path))
+    });
+    let reclaimable: u64 = groups
+        .iter()
+        .map(|g| g[0].size * (g.len() as u64 - 1))
+        .sum();
+    let duplicate_files: usize = groups.iter().map(|g| g.len()).sum();
+
+    if json_mode {
+        let sets: Vec<Value> = groups
+            .iter()
+            .map(|g| {
+                json!({
+                    "size": g[0].size,
+                    "copies": g.len(),
+                    "reclaimable_bytes": g[0].size * (g.len() as u64 - 1),
+                    "paths": g.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let result = json!({
+            "path": path,
+            "verified": !quick,
+            "files_scanned": files.len(),
+            "bytes_scanned": scanned_bytes,
+            "duplicate_sets": sets,
+            "duplicate_files": duplicate_files,
+            "reclaimable_bytes": reclaimable,
+        });
+        print_json(&result)?;
+    } else {
+        let dim = Style::new().dim();
+        for group in &groups {
+            println!(
+                "{} copies of {} ({} reclaimable)",
+                group.len(),
+                format_size(group[0].size),
+                format_size(group[0].size * (group.len() as u64 - 1))
+            );
+            for file in group {
+                println!("  {}", file.path);
+            }
+            println!();
+        }
+        let kind = if quick {
+            "probable duplicate set(s)"
+        } else {
+            "duplicate set(s)"
+        };
+        println!(
+            "{} {} ({} files), {} reclaimable",
+            groups.len(),
+            kind,
+            duplicate_files,
+            format_size(reclaimable)
+        );
+        println!(
+            "{}",
+            dim.apply_to(format!(
+                "Scanned {} file(s) ({}) under {} in {:.1}s",
+                files.len(),
+                format_size(scanned_bytes),
+                path,
+                start.elapsed().as_secs_f64()
+            ))
+        );
+    }
+
+    let failures = failures.into_inner();
+    if failures > 0 {
+        anyhow::bail!(
+            "{} files could not be read; their duplicates may be missing",
+            failures
+        );
+    }
+    Ok(())
+}
+
+/// Split `files` by `key`, keeping only sets of two or more. Files without a
+/// key (e.g. a checksum that failed) are dropped.
+fn group_by(
+    files: Vec<&RemoteFile>,
+    key: impl Fn(&RemoteFile) -> Option<u128>,
+) -> Vec<Vec<&RemoteFile>> {
+    let mut by_key: std::collections::HashMap<u128, Vec<&RemoteFile>> =
+        std::collections::HashMap::new();
+    for file in files {
+        if let Some(k) = key(file) {
+            by_key.entry(k).or_default().push(file);
+        }
+    }
+    by_key.into_values().filter(|g| g.len() > 1).collect()
+}
+
+/// Checksum up to `limit` bytes (or all) of each file with `digest`, `jobs`
+/// at a time, keyed by file ID. Unreadable files are warned about and
+/// counted.
+fn checksum_files<T: Send>(
+    client: &QumuloClient,
+    files: &[&RemoteFile],
+    limit: Option<u64>,
+    jobs: usize,
+    failures: &std::sync::atomic::AtomicUsize,
+    digest: fn(&mut dyn io::Read) -> Result<T>,
+) -> std::collections::HashMap<String, T> {
+    let sums = std::sync::Mutex::new(std::collections::HashMap::new());
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let tracker = Tracker::start("checksum", Some(files.len() as u64), true);
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, files.len().max(1)) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let Some(file) = files.get(i) else {
+                    break;
+                };
+                let read = match checksum_file(client, &file.id, limit, digest) {
+                    Ok(sum) => {
+                        sums.lock().unwrap().insert(file.id.clone(), sum);
+                        limit.map_or(file.size, |limit| file.size.min(limit))
+                    }
+                    Err(e) => {
+                        eprintln!("warning: {}: {:#}", file.path, e);
+                        failures.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        0
+                    }
+                };
+                tracker.advance(read, Some(&file.path));
+            });
+        }
+    });
+    sums.into_inner().unwrap()
+}
+
+/// `digest` over a file's contents, stopping after `limit` bytes
+fn checksum_file<T>(
+    client: &QumuloClient,
+    id: &str,
+    limit: Option<u64>,
+    digest: fn(&mut dyn io::Read) -> Result<T>,
+) -> Result<T> {
+    let reader = client.open_file(id)?;
+    let mut reader: Box<dyn io::Read> = match limit {
+        Some(limit) => Box::new(io::Read::take(reader, limit)),
+        None => Box::new(reader),
+    };
+    digest(&mut reader)
+}
+
+/// FNV-1a (128-bit) of everything `reader` yields, for grouping files
+fn fnv_digest(reader: &mut dyn io::Read) -> Result<u128> {
+    Ok(fnv_read(reader)?.0)
+}
+
+/// SHA-256 of everything `reader` yields, as lowercase hex, for manifests
+fn sha256_digest(reader: &mut dyn io::Read) -> Result<String> {
+    let mut hash = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf).context("failed to read file data")?;
+        if n == 0 {
+            break;
+        }
+        hash.update(&buf[..n]);
+    }
+    Ok(hash
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// FNV-1a (128-bit) of everything `reader` yields, and its length
+fn fnv_read(reader: &mut (impl io::Read + ?Sized)) -> Result<(u128, u64)> {
+    let mut hash = Fnv128::default();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let n = io::Read::read(reader, &mut buf).context("failed to read file data")?;
+        if n == 0 {
+            break;
+        }
+        hash.write(&buf[..n]);
+        total += n as u64;
+    }
+    Ok((hash.0, total))
+}
+
+/// Streaming FNV-1a, 128-bit. Fine for telling files apart within one
+/// report; not a cryptographic digest.
+struct Fnv128(u128);
+
+impl Default for Fnv128 {
+    fn default() -> Self {
+        Fnv128(0x6c62272e07bb014262b821756295c58d)
+    }
+}
+
+impl Fnv128 {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u128::from(b);
+            self.0 = self.0.wrapping_mul(0x0000000001000000000000000000013b);
+        }
+    }
+}
+
+/// Manifest format version written by `fs manifest create`
+const MANIFEST_VERSION: u32 = 1;
+
+/// Checksum algorithm name recorded in manifests. Manifests back integrity
+/// and drift checks, so this is a cryptographic digest.
+const MANIFEST_CHECKSUM: &str = "sha256";
+
+/// A snapshot of a tree's files, written by [`manifest_create`] and checked
+/// by [`manifest_verify`]. Paths are relative to `root`, so a manifest can
+/// also verify a copy of the tree elsewhere.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    root: String,
+    created: String,
+    /// Algorithm of the per-file checksums, when they were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+    files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ManifestEntry {
+    path: String,
+    size: u64,
+    #[serde(default)]
+    mtime: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+}
+
+/// One file that differs between a manifest and the tree
+#[derive(Debug, PartialEq)]
+struct ManifestChange {
+    path: String,
+    reasons: Vec<String>,
+}
+
+/// Record every regular file under `path` with its size and modification
+/// time (and, with `checksum`, a checksum of its contents read `jobs` at a
+/// time). The manifest goes to `out`, or to stdout without one.
+pub fn manifest_create(
+    client: &QumuloClient,
+    path: &str,
+    out: Option<&str>,
+    checksum: bool,
+    jobs: usize,
+    json_mode: bool,
+) -> Result<()> {
+    let (mut entries, files) = manifest_walk(client, path)?;
+    let failures = std::sync::atomic::AtomicUsize::new(0);
+    if checksum {
+        let refs: Vec<&RemoteFile> = files.iter().collect();
+        let sums = checksum_files(client, &refs, None, jobs, &failures, sha256_digest);
+        for (entry, file) in entries.iter_mut().zip(&files) {
+            entry.checksum = sums.get(&file.id).cloned();
+        }
+    }
+    let failures = failures.into_inner();
+    if failures > 0 {
+        anyhow::bail!("{} of {} files could not be read", failures, files.len());
+    }
+
+    let total_bytes: u64 = entries.iter().map(|e| e.size).sum();
+    let manifest = Manifest {
+        version: MANIFEST_VERSION,
+        root: path.to_string(),
+        created: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        checksum: checksum.then(|| MANIFEST_CHECKSUM.to_string()),
+        files: entries,
+    };
+    let contents = serde_json::to_string_pretty(&manifest)?;
+    let Some(out) = out else {
+        println!("{}", contents);
+        return Ok(());
+    };
+    std::fs::write(out, contents + "\n").with_context(|| format!("failed to write {}", out))?;
+
+    if json_mode {
+        let result = json!({
+            "path": path,
+            "out": out,
+            "files": manifest.files.len(),
+            "bytes": total_bytes,
+            "checksum": manifest.checksum,
+        });
+        print_json(&result)?;
+    } else {
+        println!(
+            "Wrote manifest of {} file(s) ({}) under {} to {}",
+            manifest.files.len(),
+            format_size(total_bytes),
+            path,
+            out
+        );
+    }
+    Ok(())
+}
+
+/// Re-walk a manifest's tree (or `path`, e.g. a restored copy) and report
+/// files added, removed, or changed since it was created. Size and mtime are
+/// always compared; checksums are recomputed when the manifest has them,
+/// unless `quick`. Exits 1 when anything differs.
+pub fn manifest_verify(
+    client: &QumuloClient,
+    manifest_path: &str,
+    path: Option<&str>,
+    quick: bool,
+    jobs: usize,
+    json_mode: bool,
+) -> Result<()> {
+    let contents = std::fs::read_to_string(manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path))?;
+    let manifest: Manifest = serde_json::from_str(&contents)
+        .with_context(|| format!("{} is not a qontrol manifest", manifest_path))?;
+    if manifest.version > MANIFEST_VERSION {
+        anyhow::bail!(
+            "{} is manifest version {}; this qontrol reads up to version {}",
+            manifest_path,
+            manifest.version,
+            MANIFEST_VERSION
+        );
+    }
+    let root = path.unwrap_or(&manifest.root);
+    let (mut current, files) = manifest_walk(client, root)?;
+
+    let failures = std::sync::atomic::AtomicUsize::new(0);
+    let check_sums = !quick && manifest.checksum.is_some();
+    if check_sums {
+        if manifest.checksum.as_deref() != Some(MANIFEST_CHECKSUM) {
+            anyhow::bail!(
+                "unsupported manifest checksum '{}' (expected {}); pass --quick to compare size and mtime only",
+                manifest.checksum.as_deref().unwrap_or_default(),
+                MANIFEST_CHECKSUM
+            );
+        }
+        // Only files still in the manifest need reading
+        let recorded: HashSet<&str> = manifest.files.iter().map(|e| e.path.as_str()).collect();
+        let refs: Vec<&RemoteFile> = current
+            .iter()
+            .zip(&files)
+            .filter(|(entry, _)| recorded.contains(entry.path.as_str()))
+            .map(|(_, file)| file)
+            .collect();
+        let sums = checksum_files(client, &refs, None, jobs, &failures, sha256_digest);
+        for (entry, file) in current.iter_mut().zip(&files) {
+            entry.checksum = sums.get(&file.id).cloned();
+        }
+    }
+
+    let (added, removed, changed) = diff_manifest(&manifest.files, &current, check_sums);
+    let unchanged = current.len() - added.len() - changed.len();
+
+    if json_mode {
+        let result = json!({
+            "manifest": manifest_path,
+            "path": root,
+            "created": manifest.created,
+            "checksums_verified": check_sums,
+            "added": added,
+            "removed": removed,
+            "changed": changed
+                .iter()
+                .map(|c| json!({"path": c.path, "reasons": c.reasons}))
+                .collect::<Vec<_>>(),
+            "unchanged": unchanged,
+        });
+        print_json(&result)?;
+    } else {
+        let green = Style::new().green();
+        let red = Style::new().red();
+        let yellow = Style::new().yellow();
+        for p in &added {
+            println!("{} {}", green.apply_to("+"), p);
+        }
+        for p in &removed {
+            println!("{} {}", red.apply_to("-"), p);
+        }
+        for c in &changed {
+            println!(
+                "{} {} ({})",
+                yellow.apply_to("~"),
+                c.path,
+                c.reasons.join(", ")
+            );
+        }
+        println!(
+            "{} added, {} removed, {} changed, {} unchanged since {}{}",
+            added.len(),
+            removed.len(),
+            changed.len(),
+            unchanged,
+            manifest.created,
+            if check_sums {
+                ""
+            } else {
+                " (checksums not compared)"
+            }
+        );
     }
 
-    // Show summary count on stderr
-    eprintln!("{} entries", total_count);
-
+    let failures = failures.into_inner();
+    if failures > 0 {
+        anyhow::bail!("{} files could not be read for checksums", failures);
+    }
+    if !added.is_empty() || !removed.is_empty() || !changed.is_empty() {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
-/// JSON mode: collect all pages into a single JSON array response
-fn ls_json(client: &QumuloClient, path: &str, limit: Option<u32>) -> Result<()> {
-    let mut all_files: Vec<Value> = Vec::new();
-    let mut after: Option<String> = None;
-    let mut last_response: Option<Value> = None;
-
-    loop {
-        let page_limit = limit.map(|l| {
-            let fetched = all_files.len() as u32;
-            l.saturating_sub(fetched)
+/// Walk `root` for regular files, returning manifest entries (paths relative
+/// to `root`, checksums unset) alongside the files they describe.
+fn manifest_walk(
+    client: &QumuloClient,
+    root: &str,
+) -> Result<(Vec<ManifestEntry>, Vec<RemoteFile>)> {
+    let prefix = root.trim_end_matches('/');
+    let mut entries = Vec::new();
+    let mut files = Vec::new();
+    let opts = WalkOptions {
+        max_depth: u32::MAX,
+        sort: &SortOrder::default(),
+        follow: false,
+        link_targets: false,
+    };
+    walk(client, root, &opts, &mut |item| {
+        if entry_type(&item.entry) != "FS_FILE_TYPE_FILE" {
+            return ControlFlow::Continue(());
+        }
+        let Some(id) = item.entry.get("id").and_then(|v| v.as_str()) else {
+            return ControlFlow::Continue(());
+        };
+        let relative = item
+            .path
+            .strip_prefix(prefix)
+            .unwrap_or(&item.path)
+            .trim_start_matches('/');
+        let size = entry_size(&item.entry);
+        entries.push(ManifestEntry {
+            path: relative.to_string(),
+            size,
+            mtime: item.entry["modification_time"].as_str().map(String::from),
+            checksum: None,
+        });
+        files.push(RemoteFile {
+            path: item.path.clone(),
+            id: id.to_string(),
+            size,
         });
+        ControlFlow::Continue(())
+    })?;
+    Ok((entries, files))
+}
 
-        if let Some(0) = page_limit {
-            break;
+/// Compare recorded and current entries by path: (added, removed, changed),
+/// each sorted by path. Checksums are compared only with `check_sums`.
+fn diff_manifest(
+    recorded: &[ManifestEntry],
+    current: &[ManifestEntry],
+    check_sums: bool,
+) -> (Vec<String>, Vec<String>, Vec<ManifestChange>) {
+    let before: std::collections::BTreeMap<&str, &ManifestEntry> =
+        recorded.iter().map(|e| (e.path.as_str(), e)).collect();
+    let after: std::collections::BTreeMap<&str, &ManifestEntry> =
+        current.iter().map(|e| (e.path.as_str(), e)).collect();
+
+    let added = after
+        .keys()
+        .filter(|p| !before.contains_key(*p))
+        .map(|p| p.to_string())
+        .collect();
+    let removed = before
+        .keys()
+        .filter(|p| !after.contains_key(*p))
+        .map(|p| p.to_string())
+        .collect();
+    let mut changed = Vec::new();
+    for (p, old) in &before {
+        let Some(new) = after.get(p) else {
+            continue;
+        };
+        let mut reasons = Vec::new();
+        if old.size != new.size {
+            reasons.push(format!("size {} -> {}", old.size, new.size));
+        }
+        if old.mtime != new.mtime {
+            reasons.push("mtime".to_string());
+        }
+        if check_sums && new.checksum.is_some() && old.checksum != new.checksum {
+            reasons.push("checksum".to_string());
+        }
+        if !reasons.is_empty() {
+            changed.push(ManifestChange {
+                path: p.to_string(),
+                reasons,
+            });
         }
+    }
+    (added, removed, changed)
+}
 
-        let response = client
-            .get_file_entries(path, after.as_deref(), page_limit)
-            .with_context(|| format!("failed to list directory: {}", path))?;
+/// Traversal settings shared by walker-based commands
+pub struct WalkOptions<'a> {
+    /// Number of directory levels below the root to list
+    pub max_depth: u32,
+    /// Per-directory ordering (see `fs ls --sort`)
+    pub sort: &'a SortOrder,
+    /// Descend into symlinks that point at directories
+    pub follow: bool,
+    /// Read each symlink's target into [`WalkEntry::link_target`], one API
+    /// call per link. Targets are always read when following links.
+    pub link_targets: bool,
+}
 
-        if let Some(files) = response.get("files").and_then(|v| v.as_array()) {
-            all_files.extend(files.iter().cloned());
-        }
+/// An entry reached by [`walk`], with its full path and depth below the walk root
+pub struct WalkEntry {
+    pub path: String,
+    /// 1 for direct children of the root, 2 for their children, and so on
+    pub depth: u32,
+    pub entry: Value,
+    /// Target of a symlink entry, as stored in the link
+    pub link_target: Option<String>,
+}
 
-        let has_next = response
-            .get("paging")
-            .and_then(|p| p.get("next"))
-            .and_then(|n| n.as_str())
-            .is_some_and(|n| !n.is_empty());
+/// Walk a directory tree depth-first, visiting each entry as soon as its parent
+/// directory has been listed.
+///
+/// The walk is bounded: directories at `max_depth` are visited but not listed,
+/// so `max_depth = 1` visits only the direct children of `root`. Symlinks are
+/// visited but only descended with `follow`, and never into a directory that is
+/// already being walked, so link cycles terminate. Failing to list `root` is an error; failures
+/// further down are logged and the walk moves on to the next sibling. Returning
+/// `ControlFlow::Break` from `visit` stops the walk.
+pub fn walk<F>(client: &QumuloClient, root: &str, opts: &WalkOptions, visit: &mut F) -> Result<()>
+where
+    F: FnMut(&WalkEntry) -> ControlFlow<()>,
+{
+    let mut entries = client
+        .get_all_file_entries(root)
+        .with_context(|| format!("failed to list directory: {}", root))?;
+    sort_entries(&mut entries, opts.sort);
+    let mut ancestors = HashSet::new();
+    // A `Break` from the visitor is an early stop, not an error
+    let _ = walk_entries(client, root, root, entries, 1, opts, &mut ancestors, visit);
+    Ok(())
+}
 
-        last_response = Some(response);
+#[allow(clippy::too_many_arguments)]
+fn walk_entries<F>(
+    client: &QumuloClient,
+    dir: &str,
+    real_dir: &str,
+    entries: Vec<Value>,
+    depth: u32,
+    opts: &WalkOptions,
+    ancestors: &mut HashSet<String>,
+    visit: &mut F,
+) -> ControlFlow<()>
+where
+    F: FnMut(&WalkEntry) -> ControlFlow<()>,
+{
+    for entry in entries {
+        // Paths under a followed link are reported under the link; `real_dir`
+        // tracks where the entries actually live for resolving nested links
+        let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or("?");
+        let real_path = join_path(real_dir, name);
+        let item = WalkEntry {
+            path: join_path(dir, name),
+            depth,
+            link_target: if opts.link_targets || opts.follow {
+                link_target(client, &entry)
+            } else {
+                None
+            },
+            entry,
+        };
+        visit(&item)?;
 
-        if !has_next {
-            break;
+        if depth >= opts.max_depth {
+            continue;
         }
-
-        if let Some(l) = limit {
-            if all_files.len() >= l as usize {
-                break;
+        let Some((list_path, id)) = descend_path(
+            client,
+            &real_path,
+            &item.entry,
+            item.link_target.as_deref(),
+            opts.follow,
+            ancestors,
+        ) else {
+            continue;
+        };
+        match client.get_all_file_entries(&list_path) {
+            Ok(mut children) => {
+                sort_entries(&mut children, opts.sort);
+                if let Some(ref id) = id {
+                    ancestors.insert(id.clone());
+                }
+                let flow = walk_entries(
+                    client,
+                    &item.path,
+                    &list_path,
+                    children,
+                    depth + 1,
+                    opts,
+                    ancestors,
+                    visit,
+                );
+                if let Some(ref id) = id {
+                    ancestors.remove(id);
+                }
+                flow?;
             }
+            Err(e) => tracing::warn!("failed to list directory {}: {:#}", item.path, e),
         }
-
-        after = last_response
-            .as_ref()
-            .and_then(|r| r.get("paging"))
-            .and_then(|p| p.get("next"))
-            .and_then(|n| n.as_str())
-            .map(|s| s.to_string());
     }
+    ControlFlow::Continue(())
+}
 
-    // Build combined response using structure from last response
-    let mut result = last_response.unwrap_or_else(|| json!({}));
-    result["files"] = Value::Array(all_files);
-    // Clear paging since we've fetched everything
-    result["paging"] = json!({"next": ""});
-
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
-    );
-    Ok(())
+/// Decide whether to descend into an entry, returning the path to list and the
+/// directory's file ID.
+///
+/// Directories are listed at their own path; symlinks to directories are listed
+/// at their resolved target when following links. Returns `None` for anything
+/// else, or for a link back to one of the `ancestors` being walked.
+fn descend_path(
+    client: &QumuloClient,
+    path: &str,
+    entry: &Value,
+    link_target: Option<&str>,
+    follow: bool,
+    ancestors: &HashSet<String>,
+) -> Option<(String, Option<String>)> {
+    match entry_type(entry) {
+        "FS_FILE_TYPE_DIRECTORY" => Some((
+            path.to_string(),
+            entry.get("id").and_then(|v| v.as_str()).map(String::from),
+        )),
+        "FS_FILE_TYPE_SYMLINK"
+            if follow
+                && entry.get("symlink_target_type").and_then(|v| v.as_str())
+                    == Some("FS_FILE_TYPE_DIRECTORY") =>
+        {
+            let parent = path.rsplit_once('/').map(|(p, _)| p).unwrap_or("");
+            let resolved = resolve_link(parent, link_target?);
+            let id = client
+                .get_file_attr(&resolved)
+                .ok()
+                .and_then(|a| a.get("id").and_then(|v| v.as_str()).map(String::from));
+            if id.as_ref().is_some_and(|id| ancestors.contains(id)) {
+                tracing::warn!("not following {}: symlink loop back to {}", path, resolved);
+                return None;
+            }
+            Some((resolved, id))
+        }
+        _ => None,
+    }
 }
 
-/// Show recursive directory tree
-pub fn tree(client: &QumuloClient, path: &str, max_depth: u32, json_mode: bool) -> Result<()> {
-    if json_mode {
-        let mut result = json!({
-            "path": path,
-            "max_depth": max_depth,
-        });
+/// Read a symlink entry's target; `None` for other types or if the read fails
+fn link_target(client: &QumuloClient, entry: &Value) -> Option<String> {
+    if entry_type(entry) != "FS_FILE_TYPE_SYMLINK" {
+        return None;
+    }
+    let id = entry.get("id").and_then(|v| v.as_str())?;
+    match client.read_symlink(id) {
+        Ok(target) => Some(target),
+        Err(e) => {
+            tracing::warn!("failed to read symlink {}: {:#}", id, e);
+            None
+        }
+    }
+}
 
-        let tree_data = build_tree_json(client, path, max_depth, 0)?;
-        result["tree"] = tree_data;
+/// Resolve a symlink target against the directory containing the link,
+/// normalizing `.` and `..` components
+fn resolve_link(parent: &str, target: &str) -> String {
+    let base = if target.starts_with('/') { "" } else { parent };
+    let mut parts: Vec<&str> = Vec::new();
+    for part in base.split('/').chain(target.split('/')) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            other => parts.push(other),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
 
-        // Get aggregates for the root
-        if let Ok(aggregates) = client.get_file_recursive_aggregates(path) {
-            result["aggregates"] = aggregates;
+/// Full path of a directory entry, without the trailing slash the API adds to
+/// directory paths; falls back to joining the parent and name
+fn entry_path(parent: &str, entry: &Value) -> String {
+    if let Some(p) = entry.get("path").and_then(|v| v.as_str()) {
+        let trimmed = p.trim_end_matches('/');
+        if !trimmed.is_empty() {
+            return trimmed.to_string();
         }
+    }
+    join_path(
+        parent,
+        entry.get("name").and_then(|v| v.as_str()).unwrap_or("?"),
+    )
+}
 
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
-        );
-        return Ok(());
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.ends_with('/') {
+        format!("{}{}", parent, name)
+    } else {
+        format!("{}/{}", parent, name)
     }
+}
 
-    // Human-readable tree output
-    let dir_style = Style::new().blue().bold();
-    println!("{}", dir_style.apply_to(path));
+const CSV_HEADER: &str = "path,type,size,owner,group,modified,id";
+
+fn csv_row(path: &str, entry: &Value) -> String {
+    let field = |key: &str| entry.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    [
+        csv_field(path),
+        csv_field(entry_type(entry)),
+        entry_size(entry).to_string(),
+        csv_field(field("owner")),
+        csv_field(field("group")),
+        csv_field(field("modification_time")),
+        csv_field(field("id")),
+    ]
+    .join(",")
+}
+
+// --- Internal helpers ---
 
-    print_tree_recursive(client, path, max_depth, 0, "")?;
+fn entry_type(entry: &Value) -> &str {
+    entry
+        .get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("FS_FILE_TYPE_FILE")
+}
 
-    // Show aggregated sizes at the bottom
-    if let Ok(aggregates) = client.get_file_recursive_aggregates(path) {
-        println!();
-        print_aggregates_summary(&aggregates);
+fn entry_size(entry: &Value) -> u64 {
+    entry
+        .get("size")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+fn type_abbrev(entry_type: &str) -> &'static str {
+    match entry_type {
+        "FS_FILE_TYPE_DIRECTORY" => "DIR ",
+        "FS_FILE_TYPE_SYMLINK" => "LINK",
+        "FS_FILE_TYPE_UNIX_PIPE" => "FIFO",
+        "FS_FILE_TYPE_UNIX_SOCKET" => "SOCK",
+        "FS_FILE_TYPE_UNIX_CHARACTER_DEVICE" => "CHR ",
+        "FS_FILE_TYPE_UNIX_BLOCK_DEVICE" => "BLK ",
+        _ => "FILE",
     }
+}
 
-    Ok(())
+/// A single `fs ls --sort` key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Case-insensitive, A to Z
+    Name,
+    /// Largest first
+    Size,
+    /// Directories first, then files
+    Type,
+    /// Newest first, like `ls -t`
+    Modified,
+    Changed,
+    Accessed,
+    Created,
 }
 
-/// Show detailed file/directory attributes
-pub fn stat(client: &QumuloClient, path: &str, json_mode: bool) -> Result<()> {
-    let attrs = client
-        .get_file_attr(path)
-        .with_context(|| format!("failed to get attributes: {}", path))?;
+impl SortKey {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "name" => SortKey::Name,
+            "size" => SortKey::Size,
+            "type" => SortKey::Type,
+            "mtime" | "modified" => SortKey::Modified,
+            "ctime" | "changed" => SortKey::Changed,
+            "atime" | "accessed" => SortKey::Accessed,
+            "crtime" | "created" => SortKey::Created,
+            _ => return None,
+        })
+    }
 
-    print_value(&attrs, json_mode, |val| {
-        print_stat_human(val, path);
-    });
+    fn compare(self, a: &Value, b: &Value) -> Ordering {
+        let str_field = |v: &Value, key: &str| -> String {
+            v.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let size = |v: &Value| {
+            v.get("size")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        // Missing or unparseable timestamps sort as oldest
+        let time = |v: &Value, key: &str| {
+            v.get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        };
 
-    Ok(())
+        match self {
+            SortKey::Name => str_field(a, "name")
+                .to_lowercase()
+                .cmp(&str_field(b, "name").to_lowercase()),
+            SortKey::Size => size(b).cmp(&size(a)),
+            SortKey::Type => str_field(a, "type").cmp(&str_field(b, "type")),
+            SortKey::Modified => time(b, "modification_time").cmp(&time(a, "modification_time")),
+            SortKey::Changed => time(b, "change_time").cmp(&time(a, "change_time")),
+            SortKey::Accessed => time(b, "access_time").cmp(&time(a, "access_time")),
+            SortKey::Created => time(b, "creation_time").cmp(&time(a, "creation_time")),
+        }
+    }
 }
 
-// --- Internal helpers ---
+/// Entry ordering for listings: keys compared in turn, then by name so ties
+/// are stable, with `reverse` flipping the whole order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOrder {
+    pub keys: Vec<SortKey>,
+    pub reverse: bool,
+}
 
-fn sort_entries(entries: &mut [Value], sort: &str) {
-    match sort {
-        "size" => {
-            entries.sort_by(|a, b| {
-                let size_a = a
-                    .get("size")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .unwrap_or(0);
-                let size_b = b
-                    .get("size")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .unwrap_or(0);
-                size_b.cmp(&size_a) // largest first
-            });
+impl Default for SortOrder {
+    fn default() -> Self {
+        SortOrder {
+            keys: vec![SortKey::Name],
+            reverse: false,
         }
-        "type" => {
-            entries.sort_by(|a, b| {
-                let type_a = a.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                let type_b = b.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                // Directories first, then files
-                type_a.cmp(type_b)
-            });
+    }
+}
+
+impl SortOrder {
+    /// Parse a comma-separated key list such as `size,name`.
+    pub fn parse(spec: &str, reverse: bool) -> Result<Self> {
+        let keys = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(|k| {
+                SortKey::parse(k).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "unknown sort key '{}' (expected name, size, type, mtime, ctime, atime, crtime)",
+                        k
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if keys.is_empty() {
+            anyhow::bail!("--sort needs at least one key");
         }
-        _ => {
-            // Default: sort by name
-            entries.sort_by(|a, b| {
-                let name_a = a.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                let name_b = b.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                name_a.to_lowercase().cmp(&name_b.to_lowercase())
+        Ok(SortOrder { keys, reverse })
+    }
+
+    fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        let ordering = self
+            .keys
+            .iter()
+            .chain(std::iter::once(&SortKey::Name))
+            .fold(Ordering::Equal, |acc, key| {
+                acc.then_with(|| key.compare(a, b))
             });
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
         }
     }
 }
 
+fn sort_entries(entries: &mut [Value], order: &SortOrder) {
+    entries.sort_by(|a, b| order.compare(a, b));
+}
+
 fn print_short_listing(entries: &[Value]) {
     let dir_style = Style::new().blue().bold();
     let file_style = Style::new();
@@ -280,18 +2658,34 @@ fn print_short_listing(entries: &[Value]) {
     }
 }
 
-fn print_long_listing(entries: &[Value]) {
+/// An entry's owner for display: its name when `identities` can resolve it,
+/// otherwise the raw auth ID
+fn owner_name(client: &QumuloClient, entry: &Value, identities: Option<&Identities>) -> String {
+    let owner = entry.get("owner").and_then(|v| v.as_str()).unwrap_or("-");
+    match identities {
+        Some(identities) if owner != "-" => identities.display(client, owner),
+        _ => owner.to_string(),
+    }
+}
+
+fn print_long_listing(client: &QumuloClient, entries: &[Value], identities: Option<&Identities>) {
     let dir_style = Style::new().blue().bold();
     let file_style = Style::new();
     let symlink_style = Style::new().cyan();
     let size_style = Style::new().green();
 
+    // Resolved once per entry up front; the column width depends on them
+    let owners: Vec<String> = entries
+        .iter()
+        .map(|entry| owner_name(client, entry, identities))
+        .collect();
+
     // Calculate column widths
     let mut max_size_len = 4; // "SIZE"
     let mut max_owner_len = 5; // "OWNER"
     let mut max_id_len = 2; // "ID"
 
-    for entry in entries {
+    for (entry, owner) in entries.iter().zip(&owners) {
         let size_str = format_size(
             entry
                 .get("size")
@@ -300,9 +2694,7 @@ fn print_long_listing(entries: &[Value]) {
                 .unwrap_or(0),
         );
         max_size_len = max_size_len.max(size_str.len());
-
-        let owner = entry.get("owner").and_then(|v| v.as_str()).unwrap_or("-");
-        max_owner_len = max_owner_len.max(owner.len());
+        max_owner_len = max_owner_len.max(owner.chars().count());
 
         let id = format_value(entry.get("id").unwrap_or(&Value::Null));
         max_id_len = max_id_len.max(id.len());
@@ -332,7 +2724,7 @@ fn print_long_listing(entries: &[Value]) {
         owner_w = max_owner_len,
     );
 
-    for entry in entries {
+    for (entry, owner) in entries.iter().zip(&owners) {
         let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or("?");
         let entry_type = entry
             .get("type")
@@ -343,7 +2735,6 @@ fn print_long_listing(entries: &[Value]) {
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse::<u64>().ok())
             .unwrap_or(0);
-        let owner = entry.get("owner").and_then(|v| v.as_str()).unwrap_or("-");
         let id = format_value(entry.get("id").unwrap_or(&Value::Null));
         let modified = entry
             .get("modification_time")
@@ -351,12 +2742,6 @@ fn print_long_listing(entries: &[Value]) {
             .map(truncate_timestamp)
             .unwrap_or_else(|| "-".to_string());
 
-        let type_abbrev = match entry_type {
-            "FS_FILE_TYPE_DIRECTORY" => "DIR ",
-            "FS_FILE_TYPE_SYMLINK" => "LINK",
-            _ => "FILE",
-        };
-
         let size_str = format_size(size);
 
         let styled_name = match entry_type {
@@ -364,11 +2749,15 @@ fn print_long_listing(entries: &[Value]) {
             "FS_FILE_TYPE_SYMLINK" => symlink_style.apply_to(name).to_string(),
             _ => file_style.apply_to(name).to_string(),
         };
+        let styled_name = match link_target(client, entry) {
+            Some(target) => format!("{} -> {}", styled_name, target),
+            None => styled_name,
+        };
 
         println!(
             "{:>id_w$}  {}  {}  {:<owner_w$}  {:<19}  {}",
             id,
-            type_abbrev,
+            type_abbrev(entry_type),
             size_style.apply_to(format!("{:>size_w$}", size_str, size_w = max_size_len)),
             owner,
             modified,
@@ -385,6 +2774,8 @@ fn print_tree_recursive(
     max_depth: u32,
     current_depth: u32,
     prefix: &str,
+    follow: bool,
+    ancestors: &mut HashSet<String>,
 ) -> Result<()> {
     if current_depth >= max_depth {
         return Ok(());
@@ -395,7 +2786,7 @@ fn print_tree_recursive(
         .with_context(|| format!("failed to list directory: {}", path))?;
 
     let mut sorted_entries = entries;
-    sort_entries(&mut sorted_entries, "name");
+    sort_entries(&mut sorted_entries, &SortOrder::default());
 
     let dir_style = Style::new().blue().bold();
     let symlink_style = Style::new().cyan();
@@ -415,6 +2806,7 @@ fn print_tree_recursive(
             .get("type")
             .and_then(|v| v.as_str())
             .unwrap_or("FS_FILE_TYPE_FILE");
+        let target = link_target(client, entry);
 
         let styled_name = match entry_type {
             "FS_FILE_TYPE_DIRECTORY" => dir_style.apply_to(name).to_string(),
@@ -422,33 +2814,49 @@ fn print_tree_recursive(
             _ => name.to_string(),
         };
 
-        let size_info = if entry_type != "FS_FILE_TYPE_DIRECTORY" {
-            let size = entry
-                .get("size")
-                .and_then(|v| v.as_str())
-                .and_then(|s| s.parse::<u64>().ok())
-                .unwrap_or(0);
-            format!("  [{}]", format_size(size))
-        } else {
-            String::new()
+        let size_info = match (&target, entry_type) {
+            (Some(t), _) => format!(" -> {}", t),
+            (None, "FS_FILE_TYPE_DIRECTORY") => String::new(),
+            _ => {
+                let size = entry
+                    .get("size")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .unwrap_or(0);
+                format!("  [{}]", format_size(size))
+            }
         };
 
         println!("{}{}{}{}", prefix, connector, styled_name, size_info);
 
-        if entry_type == "FS_FILE_TYPE_DIRECTORY" {
-            let child_path = if path == "/" {
-                format!("/{}", name)
-            } else {
-                format!("{}/{}", path, name)
-            };
+        if current_depth + 1 >= max_depth {
+            continue;
+        }
+        let child_path = join_path(path, name);
+        if let Some((list_path, id)) = descend_path(
+            client,
+            &child_path,
+            entry,
+            target.as_deref(),
+            follow,
+            ancestors,
+        ) {
             let new_prefix = format!("{}{}", prefix, child_prefix);
+            if let Some(ref id) = id {
+                ancestors.insert(id.clone());
+            }
             print_tree_recursive(
                 client,
-                &child_path,
+                &list_path,
                 max_depth,
                 current_depth + 1,
                 &new_prefix,
+                follow,
+                ancestors,
             )?;
+            if let Some(ref id) = id {
+                ancestors.remove(id);
+            }
         }
     }
 
@@ -460,6 +2868,8 @@ fn build_tree_json(
     path: &str,
     max_depth: u32,
     current_depth: u32,
+    follow: bool,
+    ancestors: &mut HashSet<String>,
 ) -> Result<Value> {
     let entries = client
         .get_all_file_entries(path)
@@ -468,21 +2878,38 @@ fn build_tree_json(
     let mut result = Vec::new();
     for entry in &entries {
         let mut node = entry.clone();
-        let entry_type = entry
-            .get("type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("FS_FILE_TYPE_FILE");
         let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or("?");
+        let target = link_target(client, entry);
+        if let Some(ref t) = target {
+            node["symlink_target"] = Value::String(t.clone());
+        }
 
-        if entry_type == "FS_FILE_TYPE_DIRECTORY" && current_depth + 1 < max_depth {
-            let child_path = if path == "/" {
-                format!("/{}", name)
-            } else {
-                format!("{}/{}", path, name)
-            };
-            if let Ok(children) = build_tree_json(client, &child_path, max_depth, current_depth + 1)
-            {
-                node["children"] = children;
+        if current_depth + 1 < max_depth {
+            let child_path = join_path(path, name);
+            if let Some((list_path, id)) = descend_path(
+                client,
+                &child_path,
+                entry,
+                target.as_deref(),
+                follow,
+                ancestors,
+            ) {
+                if let Some(ref id) = id {
+                    ancestors.insert(id.clone());
+                }
+                if let Ok(children) = build_tree_json(
+                    client,
+                    &list_path,
+                    max_depth,
+                    current_depth + 1,
+                    follow,
+                    ancestors,
+                ) {
+                    node["children"] = children;
+                }
+                if let Some(ref id) = id {
+                    ancestors.remove(id);
+                }
             }
         }
         result.push(node);
@@ -497,20 +2924,7 @@ fn print_stat_human(attrs: &Value, path: &str) {
     println!("{}", header_style.apply_to(format!("  File: {}", path)));
 
     if let Some(obj) = attrs.as_object() {
-        let fields = [
-            ("id", "    ID"),
-            ("type", "  Type"),
-            ("size", "  Size"),
-            ("owner", " Owner"),
-            ("group", " Group"),
-            ("mode", "  Mode"),
-            ("creation_time", "Created"),
-            ("modification_time", "Modified"),
-            ("change_time", "Changed"),
-            ("access_time", "Access"),
-            ("child_count", "Children"),
-            ("num_links", " Links"),
-        ];
+        let fields = STAT_FIELDS;
 
         for (key, label) in &fields {
             if let Some(val) = obj.get(*key) {
@@ -531,11 +2945,25 @@ fn print_stat_human(attrs: &Value, path: &str) {
         // Print any remaining fields not in the known list
         let known_keys: Vec<&str> = fields.iter().map(|(k, _)| *k).collect();
         for (key, val) in obj {
-            if !known_keys.contains(&key.as_str()) {
+            if !known_keys.contains(&key.as_str()) && key != "named_streams" {
                 let label = format!("{:>8}", key);
                 println!("{}: {}", label, format_value(val));
             }
         }
+
+        if let Some(streams) = obj.get("named_streams").and_then(|v| v.as_array()) {
+            println!(
+                "{}",
+                header_style.apply_to(format!("Streams: {}", streams.len()))
+            );
+            for stream in streams {
+                println!(
+                    "  {}  {}",
+                    stream.get("name").and_then(|v| v.as_str()).unwrap_or("?"),
+                    format_size(entry_size(stream))
+                );
+            }
+        }
     }
 }
 
@@ -658,7 +3086,7 @@ mod tests {
             json!({"name": "apple", "type": "FS_FILE_TYPE_FILE"}),
             json!({"name": "Mango", "type": "FS_FILE_TYPE_FILE"}),
         ];
-        sort_entries(&mut entries, "name");
+        sort_entries(&mut entries, &SortOrder::default());
         assert_eq!(entries[0]["name"], "apple");
         assert_eq!(entries[1]["name"], "Mango");
         assert_eq!(entries[2]["name"], "zebra");
@@ -671,7 +3099,7 @@ mod tests {
             json!({"name": "big", "size": "9999"}),
             json!({"name": "medium", "size": "500"}),
         ];
-        sort_entries(&mut entries, "size");
+        sort_entries(&mut entries, &SortOrder::parse("size", false).unwrap());
         assert_eq!(entries[0]["name"], "big");
         assert_eq!(entries[1]["name"], "medium");
         assert_eq!(entries[2]["name"], "small");
@@ -684,9 +3112,255 @@ mod tests {
             json!({"name": "dir1", "type": "FS_FILE_TYPE_DIRECTORY"}),
             json!({"name": "link1", "type": "FS_FILE_TYPE_SYMLINK"}),
         ];
-        sort_entries(&mut entries, "type");
+        sort_entries(&mut entries, &SortOrder::parse("type", false).unwrap());
         assert_eq!(entries[0]["name"], "dir1");
         assert_eq!(entries[1]["name"], "file1");
         assert_eq!(entries[2]["name"], "link1");
     }
+
+    #[test]
+    fn test_sort_entries_multiple_keys_and_reverse() {
+        let mut entries = vec![
+            json!({"name": "b", "size": "100"}),
+            json!({"name": "c", "size": "500"}),
+            json!({"name": "a", "size": "100"}),
+        ];
+        sort_entries(&mut entries, &SortOrder::parse("size,name", false).unwrap());
+        let names: Vec<_> = entries.iter().map(|e| e["name"].clone()).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+
+        sort_entries(&mut entries, &SortOrder::parse("size,name", true).unwrap());
+        let names: Vec<_> = entries.iter().map(|e| e["name"].clone()).collect();
+        assert_eq!(names, ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_sort_entries_by_time_newest_first() {
+        let mut entries = vec![
+            json!({"name": "old", "access_time": "2024-01-01T00:00:00Z"}),
+            json!({"name": "missing"}),
+            json!({"name": "new", "access_time": "2024-06-01T00:00:00.123456789Z"}),
+        ];
+        sort_entries(&mut entries, &SortOrder::parse("atime", false).unwrap());
+        assert_eq!(entries[0]["name"], "new");
+        assert_eq!(entries[1]["name"], "old");
+        assert_eq!(entries[2]["name"], "missing");
+    }
+
+    #[test]
+    fn test_sort_order_rejects_unknown_key() {
+        let err = SortOrder::parse("size,colour", false).unwrap_err();
+        assert!(err.to_string().contains("unknown sort key 'colour'"));
+    }
+
+    #[test]
+    fn test_entry_path_trims_directory_slash() {
+        let entry = json!({"name": "dmotles", "path": "/home/dmotles/"});
+        assert_eq!(entry_path("/home", &entry), "/home/dmotles");
+    }
+
+    #[test]
+    fn test_entry_path_falls_back_to_join() {
+        assert_eq!(entry_path("/", &json!({"name": "home"})), "/home");
+        assert_eq!(
+            entry_path("/home", &json!({"name": "a.txt"})),
+            "/home/a.txt"
+        );
+    }
+
+    #[test]
+    fn test_csv_row_quotes_path() {
+        let entry = json!({
+            "type": "FS_FILE_TYPE_FILE",
+            "size": "42",
+            "owner": "500",
+            "group": "513",
+            "modification_time": "2024-01-15T10:30:45Z",
+            "id": "7"
+        });
+        assert_eq!(
+            csv_row("/data/a,b.txt", &entry),
+            "\"/data/a,b.txt\",FS_FILE_TYPE_FILE,42,500,513,2024-01-15T10:30:45Z,7"
+        );
+    }
+
+    #[test]
+    fn test_wait_condition_parse() {
+        assert_eq!(
+            WaitCondition::parse("exists").unwrap(),
+            WaitCondition::Exists
+        );
+        assert_eq!(
+            WaitCondition::parse("size-stable").unwrap(),
+            WaitCondition::SizeStable
+        );
+        assert!(WaitCondition::parse("present").is_err());
+    }
+
+    #[test]
+    fn test_condition_met_exists_and_absent() {
+        assert!(condition_met(WaitCondition::Exists, Some(0), None));
+        assert!(!condition_met(WaitCondition::Exists, None, None));
+        assert!(condition_met(WaitCondition::Absent, None, None));
+        assert!(!condition_met(WaitCondition::Absent, Some(10), None));
+    }
+
+    #[test]
+    fn test_condition_met_size_stable_needs_two_matching_polls() {
+        let c = WaitCondition::SizeStable;
+        assert!(!condition_met(c, Some(100), None));
+        assert!(!condition_met(c, Some(200), Some(Some(100))));
+        assert!(condition_met(c, Some(200), Some(Some(200))));
+        assert!(!condition_met(c, None, Some(None)));
+    }
+
+    #[test]
+    fn test_resolve_link_relative_and_absolute() {
+        assert_eq!(resolve_link("/data", "releases/v2"), "/data/releases/v2");
+        assert_eq!(resolve_link("/data/a", "../b/./c"), "/data/b/c");
+        assert_eq!(resolve_link("/data", "/archive"), "/archive");
+        assert_eq!(resolve_link("/", "../.."), "/");
+    }
+
+    #[test]
+    fn test_type_abbrev_special_files() {
+        assert_eq!(type_abbrev("FS_FILE_TYPE_SYMLINK"), "LINK");
+        assert_eq!(type_abbrev("FS_FILE_TYPE_UNIX_PIPE"), "FIFO");
+        assert_eq!(type_abbrev("FS_FILE_TYPE_UNIX_BLOCK_DEVICE"), "BLK ");
+        assert_eq!(type_abbrev("FS_FILE_TYPE_FILE"), "FILE");
+    }
+
+    #[test]
+    fn test_decode_metadata_value_base64_and_bytes() {
+        assert_eq!(decode_metadata_value(&json!("aGVsbG8=")), b"hello");
+        assert_eq!(decode_metadata_value(&json!([104, 105])), b"hi");
+        assert!(decode_metadata_value(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_dos_attribute_flags_only_set() {
+        let attrs =
+            json!({"extended_attributes": {"hidden": true, "archive": false, "read_only": true}});
+        assert_eq!(dos_attribute_flags(&attrs), vec!["hidden", "read_only"]);
+        assert!(dos_attribute_flags(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_modified_time() {
+        let entry = json!({"modification_time": "1970-01-01T00:01:40.5Z"});
+        let expected = std::time::UNIX_EPOCH + Duration::from_millis(100_500);
+        assert_eq!(modified_time(&entry), Some(expected));
+        assert_eq!(modified_time(&json!({"modification_time": "?"})), None);
+        assert_eq!(modified_time(&json!({})), None);
+    }
+
+    #[test]
+    fn test_parse_mkdirs_manifest() {
+        let paths = parse_mkdirs_manifest("# comment\n/a/b/\n\n  /a  \n/a/b\n").unwrap();
+        assert_eq!(paths, vec!["/a/b", "/a"]);
+        let err = parse_mkdirs_manifest("/ok\nrelative/dir\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: 'relative/dir' is not an absolute path"
+        );
+    }
+
+    #[test]
+    fn test_mkdir_attrs() {
+        assert!(mkdir_attrs(None, None).unwrap().is_none());
+        assert_eq!(
+            mkdir_attrs(Some("755"), Some("1001")).unwrap().unwrap(),
+            json!({"mode": "0755", "owner_details": {"id_type": "NFS_UID", "id_value": "1001"}})
+        );
+        let sid = mkdir_attrs(None, Some("S-1-5-21-1")).unwrap().unwrap();
+        assert_eq!(sid["owner_details"]["id_type"], "SMB_SID");
+        let user = mkdir_attrs(None, Some("alice")).unwrap().unwrap();
+        assert_eq!(user["owner_details"]["id_type"], "LOCAL_USER");
+        assert!(mkdir_attrs(Some("0789"), None).is_err());
+    }
+
+    #[test]
+    fn test_fnv128_is_streaming() {
+        let mut whole = Fnv128::default();
+        whole.write(b"hello world");
+        let mut parts = Fnv128::default();
+        parts.write(b"hello");
+        parts.write(b" world");
+        assert_eq!(whole.0, parts.0);
+
+        let mut other = Fnv128::default();
+        other.write(b"hello worle");
+        assert_ne!(whole.0, other.0);
+    }
+
+    #[test]
+    fn test_split_ranges() {
+        assert_eq!(split_ranges(10, 3), vec![(0, 4), (4, 4), (8, 2)]);
+        assert_eq!(split_ranges(1024, 2), vec![(0, 512), (512, 512)]);
+        // Never more ranges than bytes
+        assert_eq!(split_ranges(2, 8), vec![(0, 1), (1, 1)]);
+        assert!(split_ranges(0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_group_by_keeps_collisions_only() {
+        let file = |path: &str, size: u64| RemoteFile {
+            path: path.to_string(),
+            id: path.to_string(),
+            size,
+        };
+        let files = [file("a", 10), file("b", 10), file("c", 20), file("d", 10)];
+        let groups = group_by(files.iter().collect(), |f| Some(f.size as u128));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 3);
+
+        // Files without a key drop out of their set
+        let groups = group_by(files.iter().collect(), |f| {
+            (f.path != "a" && f.path != "b").then_some(f.size as u128)
+        });
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_diff_manifest() {
+        let entry = |path: &str, size: u64, mtime: &str, checksum: Option<&str>| ManifestEntry {
+            path: path.to_string(),
+            size,
+            mtime: Some(mtime.to_string()),
+            checksum: checksum.map(String::from),
+        };
+        let recorded = [
+            entry("a", 10, "t1", Some("aa")),
+            entry("b", 10, "t1", Some("bb")),
+            entry("c", 10, "t1", Some("cc")),
+            entry("gone", 1, "t1", None),
+        ];
+        let current = [
+            entry("a", 10, "t1", Some("aa")),
+            entry("b", 12, "t2", Some("bb")),
+            entry("c", 10, "t1", Some("cx")),
+            entry("new", 1, "t1", None),
+        ];
+
+        let (added, removed, changed) = diff_manifest(&recorded, &current, true);
+        assert_eq!(added, ["new"]);
+        assert_eq!(removed, ["gone"]);
+        assert_eq!(
+            changed,
+            [
+                ManifestChange {
+                    path: "b".to_string(),
+                    reasons: vec!["size 10 -> 12".to_string(), "mtime".to_string()],
+                },
+                ManifestChange {
+                    path: "c".to_string(),
+                    reasons: vec!["checksum".to_string()],
+                },
+            ]
+        );
+
+        // Without checksums only b differs
+        let (_, _, changed) = diff_manifest(&recorded, &current, false);
+        assert_eq!(changed.len(), 1);
+    }
 }
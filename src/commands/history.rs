@@ -0,0 +1,382 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+
+use crate::commands::maintenance::parse_duration;
+use crate::commands::status::renderer::format_bytes;
+use crate::commands::status::types::EnvironmentStatus;
+use crate::config::data_dir;
+use crate::output::{print_json, print_table};
+
+/// Samples older than this are dropped when new ones are recorded.
+const RETENTION_DAYS: i64 = 400;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS samples (
+    ts INTEGER NOT NULL,
+    profile TEXT NOT NULL,
+    cluster_name TEXT NOT NULL,
+    total_bytes INTEGER NOT NULL,
+    used_bytes INTEGER NOT NULL,
+    free_bytes INTEGER NOT NULL,
+    snapshot_bytes INTEGER NOT NULL,
+    used_pct REAL NOT NULL,
+    total_files INTEGER NOT NULL,
+    total_directories INTEGER NOT NULL,
+    iops_read REAL NOT NULL,
+    iops_write REAL NOT NULL,
+    throughput_read REAL NOT NULL,
+    throughput_write REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS samples_by_time ON samples (profile, ts);
+CREATE TABLE IF NOT EXISTS alerts (
+    ts INTEGER NOT NULL,
+    profile TEXT NOT NULL,
+    cluster_name TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    category TEXT NOT NULL,
+    message TEXT NOT NULL,
+    acknowledged INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS alerts_by_time ON alerts (profile, ts);
+";
+
+/// What `history show` reports on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Capacity,
+    Files,
+    Iops,
+    Alerts,
+}
+
+impl Metric {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "capacity" => Ok(Self::Capacity),
+            "files" => Ok(Self::Files),
+            "iops" => Ok(Self::Iops),
+            "alerts" => Ok(Self::Alerts),
+            _ => anyhow::bail!(
+                "unknown metric '{}' (expected capacity, files, iops, or alerts)",
+                s
+            ),
+        }
+    }
+
+    /// Table columns, after time and cluster
+    fn columns(self) -> &'static [&'static str] {
+        match self {
+            Self::Capacity => &["used", "total", "snapshots", "used_pct"],
+            Self::Files => &["files", "directories"],
+            Self::Iops => &["read", "write"],
+            Self::Alerts => &["severity", "category", "message"],
+        }
+    }
+
+    fn query(self) -> &'static str {
+        match self {
+            Self::Capacity => {
+                "SELECT ts, profile, used_bytes, total_bytes, snapshot_bytes, used_pct
+                 FROM samples WHERE ts >= ?1 AND (?2 IS NULL OR profile = ?2 OR cluster_name = ?2)
+                 ORDER BY ts, profile"
+            }
+            Self::Files => {
+                "SELECT ts, profile, total_files, total_directories
+                 FROM samples WHERE ts >= ?1 AND (?2 IS NULL OR profile = ?2 OR cluster_name = ?2)
+                 ORDER BY ts, profile"
+            }
+            Self::Iops => {
+                "SELECT ts, profile, iops_read, iops_write
+                 FROM samples WHERE ts >= ?1 AND (?2 IS NULL OR profile = ?2 OR cluster_name = ?2)
+                 ORDER BY ts, profile"
+            }
+            Self::Alerts => {
+                "SELECT ts, profile, severity, category, message
+                 FROM alerts WHERE ts >= ?1 AND (?2 IS NULL OR profile = ?2 OR cluster_name = ?2)
+                 ORDER BY ts, profile, category"
+            }
+        }
+    }
+}
+
+/// Path of the history database.
+fn history_path() -> Result<PathBuf> {
+    Ok(data_dir()?.join("history.sqlite"))
+}
+
+fn open(path: &Path) -> Result<Connection> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let conn =
+        Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    // A daemon and an interactive `fleet status` may write at the same time
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    conn.execute_batch(SCHEMA)
+        .with_context(|| format!("failed to initialize {}", path.display()))?;
+    Ok(conn)
+}
+
+/// Append a sample for every cluster collected with fresh data, along with
+/// the alerts it had at that moment.
+pub fn record(status: &EnvironmentStatus) -> Result<()> {
+    let mut conn = open(&history_path()?)?;
+    record_at(&mut conn, status, Utc::now())
+}
+
+fn record_at(conn: &mut Connection, status: &EnvironmentStatus, now: DateTime<Utc>) -> Result<()> {
+    let ts = now.timestamp();
+    let tx = conn.transaction()?;
+    for c in status.clusters.iter().filter(|c| c.reachable && !c.stale) {
+        tx.execute(
+            "INSERT INTO samples VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                ts,
+                c.profile,
+                c.name,
+                c.capacity.total_bytes as i64,
+                c.capacity.used_bytes as i64,
+                c.capacity.free_bytes as i64,
+                c.capacity.snapshot_bytes as i64,
+                c.capacity.used_pct,
+                c.files.total_files as i64,
+                c.files.total_directories as i64,
+                c.activity.iops_read,
+                c.activity.iops_write,
+                c.activity.throughput_read,
+                c.activity.throughput_write,
+            ],
+        )?;
+        let alerts = status
+            .alerts
+            .iter()
+            .map(|a| (a, false))
+            .chain(status.acknowledged.iter().map(|a| (a, true)))
+            .filter(|(a, _)| a.cluster == c.profile || a.cluster == c.name);
+        for (alert, acknowledged) in alerts {
+            let severity = serde_json::to_value(&alert.severity)?;
+            tx.execute(
+                "INSERT INTO alerts VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    ts,
+                    c.profile,
+                    c.name,
+                    severity.as_str().unwrap_or_default(),
+                    alert.category,
+                    alert.message,
+                    acknowledged,
+                ],
+            )?;
+        }
+    }
+    let cutoff = (now - Duration::days(RETENTION_DAYS)).timestamp();
+    tx.execute("DELETE FROM samples WHERE ts < ?1", params![cutoff])?;
+    tx.execute("DELETE FROM alerts WHERE ts < ?1", params![cutoff])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Recorded rows for `metric`, oldest first, as JSON objects keyed by the
+/// metric's columns with raw numbers.
+fn query(
+    conn: &Connection,
+    metric: Metric,
+    cluster: Option<&str>,
+    since: DateTime<Utc>,
+) -> Result<Vec<Value>> {
+    let mut stmt = conn.prepare(metric.query())?;
+    let rows = stmt.query_map(params![since.timestamp(), cluster], |row| {
+        let ts: i64 = row.get(0)?;
+        let time = DateTime::from_timestamp(ts, 0)
+            .unwrap_or_default()
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        let mut entry = json!({ "time": time, "cluster": row.get::<_, String>(1)? });
+        for (i, col) in metric.columns().iter().enumerate() {
+            let value = match row.get_ref(i + 2)? {
+                rusqlite::types::ValueRef::Integer(n) => json!(n),
+                rusqlite::types::ValueRef::Real(f) => json!(f),
+                rusqlite::types::ValueRef::Text(t) => json!(String::from_utf8_lossy(t)),
+                _ => Value::Null,
+            };
+            entry[*col] = value;
+        }
+        Ok(entry)
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Show how a metric changed over the recorded history.
+pub fn show(cluster: Option<&str>, metric: &str, since: &str, json_mode: bool) -> Result<()> {
+    let metric = Metric::parse(metric)?;
+    let since = Utc::now() - parse_duration(since)?;
+    let path = history_path()?;
+    let entries = if path.exists() {
+        query(&open(&path)?, metric, cluster, since)?
+    } else {
+        Vec::new()
+    };
+
+    if json_mode {
+        print_json(&entries)?;
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No history recorded (run `fleet status` or `qontrol daemon` to collect it).");
+        return Ok(());
+    }
+    let rows: Vec<Value> = entries.into_iter().map(|e| humanize(metric, e)).collect();
+    let mut columns = vec!["time", "cluster"];
+    columns.extend(metric.columns());
+    print_table(&Value::Array(rows), &columns);
+    Ok(())
+}
+
+/// Format a row's numbers for the table
+fn humanize(metric: Metric, mut entry: Value) -> Value {
+    let bytes = |v: &Value| json!(format_bytes(v.as_u64().unwrap_or(0)));
+    match metric {
+        Metric::Capacity => {
+            for col in ["used", "total", "snapshots"] {
+                entry[col] = bytes(&entry[col]);
+            }
+            entry["used_pct"] = json!(format!("{:.1}%", entry["used_pct"].as_f64().unwrap_or(0.0)));
+        }
+        Metric::Iops => {
+            for col in ["read", "write"] {
+                entry[col] = json!(format!("{:.0}", entry[col].as_f64().unwrap_or(0.0)));
+            }
+        }
+        Metric::Files | Metric::Alerts => {}
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::status::collector::build_aggregates;
+    use crate::commands::status::types::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn make_status(used_bytes: u64, reachable: bool) -> EnvironmentStatus {
+        let cluster = ClusterStatus {
+            profile: "prod".to_string(),
+            name: "prod-cluster".to_string(),
+            uuid: "uuid".to_string(),
+            version: "7.0".to_string(),
+            cluster_type: ClusterType::OnPrem(vec![]),
+            reachable,
+            stale: false,
+            latency_ms: 5,
+            degraded: Vec::new(),
+            partial: false,
+            nodes: NodeStatus {
+                total: 1,
+                online: 1,
+                offline_nodes: vec![],
+                details: vec![],
+            },
+            capacity: CapacityStatus {
+                total_bytes: 1000,
+                used_bytes,
+                free_bytes: 1000 - used_bytes,
+                used_pct: used_bytes as f64 / 10.0,
+                ..Default::default()
+            },
+            activity: ActivityStatus {
+                iops_read: 100.0,
+                iops_write: 50.0,
+                ..Default::default()
+            },
+            files: FileStats {
+                total_files: 10,
+                total_directories: 2,
+                ..Default::default()
+            },
+            health: HealthStatus {
+                status: HealthLevel::Healthy,
+                issues: vec![],
+                disks_unhealthy: 0,
+                psus_unhealthy: 0,
+                data_at_risk: false,
+                remaining_node_failures: None,
+                remaining_drive_failures: None,
+                protection_type: None,
+                unhealthy_disk_details: vec![],
+                unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
+            },
+        };
+        EnvironmentStatus {
+            aggregates: build_aggregates(std::slice::from_ref(&cluster)),
+            alerts: vec![Alert {
+                severity: AlertSeverity::Warning,
+                cluster: "prod".to_string(),
+                message: "capacity high".to_string(),
+                category: "capacity".to_string(),
+            }],
+            acknowledged: vec![],
+            clusters: vec![cluster],
+        }
+    }
+
+    fn record_sample(conn: &mut Connection, used_bytes: u64, reachable: bool, time: &str) {
+        record_at(conn, &make_status(used_bytes, reachable), at(time)).unwrap();
+    }
+
+    #[test]
+    fn test_record_and_query() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        record_sample(&mut conn, 500, true, "2026-01-01T00:00:00Z");
+        record_sample(&mut conn, 600, true, "2026-01-02T00:00:00Z");
+        // Unreachable clusters have nothing fresh to record
+        record_sample(&mut conn, 0, false, "2026-01-03T00:00:00Z");
+
+        let since = at("2025-12-01T00:00:00Z");
+        let rows = query(&conn, Metric::Capacity, Some("prod-cluster"), since).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["time"], "2026-01-01T00:00:00Z");
+        assert_eq!(rows[0]["cluster"], "prod");
+        assert_eq!(rows[1]["used"], 600);
+        assert_eq!(rows[1]["used_pct"], 60.0);
+
+        let rows = query(&conn, Metric::Files, None, at("2026-01-02T00:00:00Z")).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["files"], 10);
+
+        let rows = query(&conn, Metric::Alerts, Some("prod"), since).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["severity"], "warning");
+        assert_eq!(rows[0]["category"], "capacity");
+
+        let rows = query(&conn, Metric::Iops, Some("dev"), since).unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_record_prunes_expired() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        record_sample(&mut conn, 500, true, "2025-01-01T00:00:00Z");
+        record_sample(&mut conn, 600, true, "2026-06-01T00:00:00Z");
+        let rows = query(&conn, Metric::Capacity, None, DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["used"], 600);
+    }
+
+    #[test]
+    fn test_metric_parse() {
+        assert_eq!(Metric::parse("iops").unwrap(), Metric::Iops);
+        assert!(Metric::parse("latency").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use console::Style;
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::output::{format_value, print_json};
+
+/// Show chassis hardware per node: the identify light, PSUs, and any other
+/// sensor groups the chassis API exposes (`*_statuses` arrays such as fans or
+/// temperature sensors on platforms that report them).
+pub fn show(client: &QumuloClient, node: Option<u64>, json_mode: bool) -> Result<()> {
+    let chassis = match node {
+        Some(id) => Value::Array(vec![client.get_node_chassis(id)?]),
+        None => client.get_cluster_chassis()?,
+    };
+
+    if json_mode {
+        print_json(&chassis)?;
+        return Ok(());
+    }
+
+    let nodes = chassis.as_array().map(|a| a.as_slice()).unwrap_or(&[]);
+    if nodes.is_empty() {
+        println!("No chassis data reported");
+        return Ok(());
+    }
+
+    let bold = Style::new().bold();
+    let dim = Style::new().dim();
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let light = match node["light_visible"].as_bool() {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "unknown",
+        };
+        println!(
+            "{}  {}",
+            bold.apply_to(format!("Node {}", format_value(&node["id"]))),
+            dim.apply_to(format!("identify light: {}", light))
+        );
+
+        let groups = sensor_groups(node);
+        if groups.is_empty() {
+            println!("  no sensors reported");
+        }
+        for (label, sensors) in groups {
+            println!("  {}", label);
+            for sensor in sensors {
+                print_sensor(sensor);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sensor groups in a chassis node object as (label, entries), PSUs first.
+fn sensor_groups(node: &Value) -> Vec<(String, &[Value])> {
+    let Some(obj) = node.as_object() else {
+        return Vec::new();
+    };
+    let mut groups: Vec<(String, &[Value])> = obj
+        .iter()
+        .filter_map(|(key, val)| {
+            let group = key.strip_suffix("_statuses")?;
+            Some((group_label(group), val.as_array()?.as_slice()))
+        })
+        .collect();
+    groups.sort_by_key(|(label, _)| (label != "PSUs", label.clone()));
+    groups
+}
+
+/// `psu` → `PSUs`, `fan` → `Fans`, `temperature_sensor` → `Temperature sensors`
+fn group_label(group: &str) -> String {
+    if group.eq_ignore_ascii_case("psu") {
+        return "PSUs".to_string();
+    }
+    let spaced = group.replace('_', " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}s", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+fn print_sensor(sensor: &Value) {
+    let green = Style::new().green();
+    let red = Style::new().red();
+
+    let state = sensor["state"].as_str().unwrap_or("unknown");
+    let state_styled = if state.eq_ignore_ascii_case("GOOD") {
+        green.apply_to(state)
+    } else {
+        red.apply_to(state)
+    };
+    // Readings and other scalar fields the platform reports, e.g. `rpm=9000`
+    let extras: Vec<String> = sensor
+        .as_object()
+        .map(|o| {
+            o.iter()
+                .filter(|(k, v)| {
+                    !matches!(k.as_str(), "name" | "location" | "state")
+                        && !v.is_object()
+                        && !v.is_array()
+                })
+                .map(|(k, v)| format!("{}={}", k, format_value(v)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    println!(
+        "    {:<10} {:<10} {}{}",
+        sensor["name"].as_str().unwrap_or("unknown"),
+        sensor["location"].as_str().unwrap_or("-"),
+        state_styled,
+        if extras.is_empty() {
+            String::new()
+        } else {
+            format!("  {}", extras.join(" "))
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sensor_groups_psus_first() {
+        let node = json!({
+            "id": 1,
+            "light_visible": false,
+            "fan_statuses": [{"name": "FAN1", "state": "GOOD"}],
+            "psu_statuses": [
+                {"name": "PSU1", "location": "left", "state": "GOOD"},
+                {"name": "PSU2", "location": "right", "state": "FAILED"}
+            ],
+            "temperature_sensor_statuses": []
+        });
+        let groups = sensor_groups(&node);
+        let labels: Vec<&str> = groups.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(labels, vec!["PSUs", "Fans", "Temperature sensors"]);
+        assert_eq!(groups[0].1.len(), 2);
+    }
+
+    #[test]
+    fn test_sensor_groups_none() {
+        assert!(sensor_groups(&json!({"id": 1})).is_empty());
+        assert!(sensor_groups(&json!(null)).is_empty());
+    }
+
+    #[test]
+    fn test_group_label() {
+        assert_eq!(group_label("psu"), "PSUs");
+        assert_eq!(group_label("fan"), "Fans");
+        assert_eq!(group_label("temperature_sensor"), "Temperature sensors");
+    }
+}
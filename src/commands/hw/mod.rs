@@ -1 +1,2 @@
+pub mod chassis;
 pub mod psu;
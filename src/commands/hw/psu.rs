@@ -4,7 +4,8 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::client::QumuloClient;
-use crate::config::{Config, ProfileEntry};
+use crate::config::Config;
+use crate::output::print_json;
 
 /// A single PSU entry with its health status.
 #[derive(Debug, Clone, Serialize)]
@@ -55,7 +56,10 @@ fn parse_all_psus(chassis: &Value) -> (usize, Vec<PsuEntry>) {
 pub fn check(client: &QumuloClient, json_mode: bool) -> Result<()> {
     let chassis = client.get_cluster_chassis()?;
     let (node_count, psus) = parse_all_psus(&chassis);
-    let unhealthy_count = psus.iter().filter(|p| !p.state.eq_ignore_ascii_case("GOOD")).count();
+    let unhealthy_count = psus
+        .iter()
+        .filter(|p| !p.state.eq_ignore_ascii_case("GOOD"))
+        .count();
     let healthy_count = psus.len() - unhealthy_count;
 
     if json_mode {
@@ -68,7 +72,7 @@ pub fn check(client: &QumuloClient, json_mode: bool) -> Result<()> {
             psus,
             error: None,
         };
-        println!("{}", serde_json::to_string_pretty(&result)?);
+        print_json(&result)?;
     } else {
         print_psu_table(&psus);
         println!();
@@ -95,23 +99,7 @@ pub fn fleet_check(
     json_mode: bool,
     verbose: bool,
 ) -> Result<()> {
-    let profiles: Vec<(String, ProfileEntry)> = if profile_filters.is_empty() {
-        config
-            .profiles
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect()
-    } else {
-        profile_filters
-            .iter()
-            .filter_map(|name| {
-                config
-                    .profiles
-                    .get(name)
-                    .map(|entry| (name.clone(), entry.clone()))
-            })
-            .collect()
-    };
+    let profiles = config.select_profiles(profile_filters);
 
     if profiles.is_empty() {
         anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
@@ -124,8 +112,8 @@ pub fn fleet_check(
             .map(|(name, entry)| {
                 let name = name.clone();
                 let entry = entry.clone();
-                s.spawn(move || {
-                    match QumuloClient::new(&entry, timeout_secs, None) {
+                s.spawn(
+                    move || match QumuloClient::new(&entry, timeout_secs, None) {
                         Ok(client) => match client.get_cluster_chassis() {
                             Ok(chassis) => {
                                 let (node_count, psus) = parse_all_psus(&chassis);
@@ -162,8 +150,8 @@ pub fn fleet_check(
                             psus: Vec::new(),
                             error: Some(format!("{:#}", e)),
                         },
-                    }
-                })
+                    },
+                )
             })
             .collect();
 
@@ -187,7 +175,7 @@ pub fn fleet_check(
     let any_errors = results.iter().any(|r| r.error.is_some());
 
     if json_mode {
-        println!("{}", serde_json::to_string_pretty(&results)?);
+        print_json(&results)?;
     } else {
         let green = Style::new().green();
         let red = Style::new().red();
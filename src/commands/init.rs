@@ -0,0 +1,220 @@
+//! `qontrol init`: a guided first run. Asks for a cluster and how to sign
+//! in, saves the profile through the same paths as `profile add`, checks
+//! that it works, and offers to install shell completions.
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::client::TlsOptions;
+use crate::commands::completions::{self, Shell};
+use crate::commands::profile;
+use crate::config::{load_config, Config};
+
+const AUTH_METHODS: [&str; 2] = [
+    "Username and password (qontrol creates a long-lived access token)",
+    "An existing access token",
+];
+
+pub fn run(timeout: u64) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "`qontrol init` is interactive; in scripts use `qontrol profile add` \
+             with --host and --token, or --username and --password"
+        );
+    }
+    let config = load_config()?;
+
+    println!("This sets up a qontrol profile: how to reach a Qumulo cluster and sign in.");
+    if !config.profiles.is_empty() {
+        let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        println!(
+            "You already have {} profile(s) ({}); this adds another.",
+            names.len(),
+            names.join(", ")
+        );
+    }
+    println!();
+
+    let host: String = dialoguer::Input::new()
+        .with_prompt("Cluster hostname or IP")
+        .interact_text()?;
+    let host = host.trim().to_string();
+    let port: u16 = dialoguer::Input::new()
+        .with_prompt("Port")
+        .default(8000u16)
+        .interact_text()?;
+    let name: String = dialoguer::Input::new()
+        .with_prompt("Profile name")
+        .default(suggest_name(&host, &config))
+        .validate_with(|name: &String| match name.trim() {
+            "" => Err("a profile needs a name".to_string()),
+            n if config.profiles.contains_key(n) => Err(format!(
+                "'{}' already exists; pick another name or edit it with `qontrol profile add`",
+                n
+            )),
+            _ => Ok(()),
+        })
+        .interact_text()?;
+    let name = name.trim().to_string();
+
+    println!();
+    println!("Clusters use a self-signed TLS certificate unless an administrator has");
+    println!("installed one from a trusted CA. A self-signed certificate can't be verified,");
+    println!("so connecting to one needs verification turned off. Traffic is still");
+    println!("encrypted, but qontrol can no longer tell your cluster from an impostor.");
+    let insecure = dialoguer::Confirm::new()
+        .with_prompt("Skip TLS certificate verification for this cluster?")
+        .default(false)
+        .interact()?;
+
+    let method = dialoguer::Select::new()
+        .with_prompt("How do you want to sign in?")
+        .items(&AUTH_METHODS)
+        .default(0)
+        .interact()?;
+
+    let default = match &config.default_profile {
+        None => true,
+        Some(current) => dialoguer::Confirm::new()
+            .with_prompt(format!(
+                "Make '{}' the default profile instead of '{}'?",
+                name, current
+            ))
+            .default(false)
+            .interact()?,
+    };
+
+    println!();
+    if method == 0 {
+        profile::add_interactive(
+            name.clone(),
+            Some(host),
+            port,
+            insecure,
+            None,
+            None,
+            default,
+            timeout,
+            None,
+            None,
+            None,
+        )?;
+    } else {
+        let token = rpassword::prompt_password("Access token: ")?;
+        let tls = TlsOptions {
+            insecure,
+            client_cert: None,
+            client_key: None,
+        };
+        profile::add(
+            name.clone(),
+            host,
+            port,
+            token.trim().to_string(),
+            None,
+            tls,
+            default,
+        )?;
+    }
+
+    println!();
+    println!("Checking the connection...");
+    let config = load_config()?;
+    let entry = config
+        .profiles
+        .get(&name)
+        .with_context(|| format!("profile '{}' was not saved", name))?;
+    if !profile::verify(&name, entry, timeout) {
+        println!();
+        println!(
+            "The profile was saved, but qontrol couldn't use it. Re-run `qontrol init`, or fix it \
+             with `qontrol profile add {}` and check again with `qontrol profile test {}`.",
+            name, name
+        );
+    }
+
+    println!();
+    offer_completions()?;
+    println!();
+    println!("All set. Try `qontrol cluster info` or `qontrol fleet status`.");
+    Ok(())
+}
+
+/// A profile name from the host's first label ("prod" for
+/// prod.example.com), made unique among existing profiles.
+fn suggest_name(host: &str, config: &Config) -> String {
+    let base = if host.parse::<std::net::IpAddr>().is_ok() {
+        "cluster"
+    } else {
+        match host.split('.').next() {
+            Some(label) if !label.is_empty() => label,
+            _ => "cluster",
+        }
+    };
+    let mut name = base.to_string();
+    let mut n = 2;
+    while config.profiles.contains_key(&name) {
+        name = format!("{}-{}", base, n);
+        n += 1;
+    }
+    name
+}
+
+/// Offer to install completions for the login shell, if it is one we
+/// generate them for.
+fn offer_completions() -> Result<()> {
+    let Some(shell) = Shell::detect() else {
+        return Ok(());
+    };
+    let Ok(home) = std::env::var("HOME") else {
+        return Ok(());
+    };
+    let data_home = std::env::var_os("XDG_DATA_HOME").map(PathBuf::from);
+    let path = shell.install_path(home.as_ref(), data_home.as_deref());
+    let install = dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "Install {} completions to {}?",
+            shell.name(),
+            path.display()
+        ))
+        .default(true)
+        .interact()?;
+    if !install {
+        return Ok(());
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&path, completions::script(shell))
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    match shell {
+        Shell::Bash => println!("Installed; new bash sessions will complete qontrol commands."),
+        Shell::Zsh => println!(
+            "Installed. Add this line to ~/.zshrc to load it:\n  source {}",
+            path.display()
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_name() {
+        let config: Config = toml::from_str(
+            "[profiles.prod]\nhost = \"h\"\nport = 8000\ntoken = \"t\"\n\
+             [profiles.prod-2]\nhost = \"h\"\nport = 8000\ntoken = \"t\"\n",
+        )
+        .unwrap();
+        assert_eq!(suggest_name("prod.example.com", &config), "prod-3");
+        assert_eq!(suggest_name("dr-qumulo", &config), "dr-qumulo");
+        assert_eq!(suggest_name("10.1.2.3", &config), "cluster");
+        assert_eq!(suggest_name("", &Config::default()), "cluster");
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use crate::config::{load_config, save_config, Config, MaintenanceWindow};
+use crate::error::QontrolError;
+use crate::output::{print_json, print_table};
+
+/// Record a maintenance window for a profile, starting now.
+pub fn start(cluster: &str, duration: &str, reason: Option<String>, json_mode: bool) -> Result<()> {
+    let length = parse_duration(duration)?;
+    let mut config = load_config()?;
+    let entry = config
+        .profiles
+        .get_mut(cluster)
+        .ok_or_else(|| QontrolError::ProfileNotFound(cluster.to_string()))?;
+
+    let now = Utc::now();
+    let window = MaintenanceWindow {
+        start: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        end: (now + length).to_rfc3339_opts(SecondsFormat::Secs, true),
+        reason,
+    };
+    entry.maintenance = Some(window.clone());
+    save_config(&config)?;
+
+    if json_mode {
+        let result = window_json(cluster, &window, now);
+        print_json(&result)?;
+    } else {
+        println!(
+            "Maintenance window started for '{}' until {} ({}).",
+            cluster, window.end, duration
+        );
+        println!("Alerts for this cluster are downgraded to info until then.");
+    }
+    Ok(())
+}
+
+/// Close a profile's maintenance window early.
+pub fn end(cluster: &str, json_mode: bool) -> Result<()> {
+    let mut config = load_config()?;
+    let entry = config
+        .profiles
+        .get_mut(cluster)
+        .ok_or_else(|| QontrolError::ProfileNotFound(cluster.to_string()))?;
+    let ended = entry.maintenance.take();
+    if ended.is_some() {
+        save_config(&config)?;
+    }
+
+    if json_mode {
+        let result = json!({"profile": cluster, "ended": ended.is_some()});
+        print_json(&result)?;
+    } else if ended.is_some() {
+        println!("Maintenance window ended for '{}'.", cluster);
+    } else {
+        println!("No maintenance window recorded for '{}'.", cluster);
+    }
+    Ok(())
+}
+
+/// List recorded maintenance windows, including ones that have expired.
+pub fn list(config: &Config, json_mode: bool) -> Result<()> {
+    let now = Utc::now();
+    let windows: Vec<Value> = config
+        .profiles
+        .iter()
+        .filter_map(|(name, entry)| {
+            entry
+                .maintenance
+                .as_ref()
+                .map(|w| window_json(name, w, now))
+        })
+        .collect();
+    let windows = Value::Array(windows);
+
+    if json_mode {
+        print_json(&windows)?;
+    } else if windows.as_array().is_some_and(|w| w.is_empty()) {
+        println!("No maintenance windows recorded.");
+    } else {
+        print_table(&windows, &["profile", "state", "start", "end", "reason"]);
+    }
+    Ok(())
+}
+
+fn window_json(profile: &str, window: &MaintenanceWindow, now: DateTime<Utc>) -> Value {
+    json!({
+        "profile": profile,
+        "state": window_state(window, now),
+        "start": window.start,
+        "end": window.end,
+        "reason": window.reason,
+    })
+}
+
+fn window_state(window: &MaintenanceWindow, now: DateTime<Utc>) -> &'static str {
+    if window.is_active(now) {
+        "active"
+    } else if DateTime::parse_from_rfc3339(&window.start).is_ok_and(|s| s > now) {
+        "scheduled"
+    } else {
+        "expired"
+    }
+}
+
+/// Parse a duration such as `90s`, `30m`, `2h`, `1d`, `1w`, or `1h30m`.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let invalid = || anyhow::anyhow!("invalid duration '{}' (expected e.g. 30m, 2h, 7d)", s);
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits.parse().map_err(|_| invalid())?;
+        digits.clear();
+        total += match c {
+            's' => Duration::seconds(n),
+            'm' => Duration::minutes(n),
+            'h' => Duration::hours(n),
+            'd' => Duration::days(n),
+            'w' => Duration::weeks(n),
+            _ => return Err(invalid()),
+        };
+    }
+    if !digits.is_empty() || total <= Duration::zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::hours(2));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_bad_input() {
+        for bad in ["", "2", "h", "2x", "0m", "-1h"] {
+            assert!(parse_duration(bad).is_err(), "{} should fail", bad);
+        }
+    }
+
+    #[test]
+    fn test_window_state() {
+        let window = MaintenanceWindow {
+            start: "2026-03-01T10:00:00Z".to_string(),
+            end: "2026-03-01T12:00:00Z".to_string(),
+            reason: None,
+        };
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        assert_eq!(
+            window_state(&window, at("2026-03-01T09:00:00Z")),
+            "scheduled"
+        );
+        assert_eq!(window_state(&window, at("2026-03-01T11:00:00Z")), "active");
+        assert_eq!(window_state(&window, at("2026-03-01T13:00:00Z")), "expired");
+    }
+}
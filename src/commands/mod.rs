@@ -1,8 +1,20 @@
+pub mod alert;
+pub mod analytics;
 pub mod api;
+pub mod auth;
+pub mod capacity;
 pub mod cdf;
 pub mod cluster;
+pub mod completions;
+pub mod connections;
+pub mod dev;
 pub mod fs;
+pub mod history;
 pub mod hw;
+pub mod init;
+pub mod maintenance;
 pub mod profile;
+pub mod quota;
+pub mod report;
 pub mod snapshot;
 pub mod status;
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use console::Style;
+use serde_json::{json, Value};
+
+use crate::client::QumuloClient;
+use crate::commands::status::renderer::format_bytes;
+use crate::glob::{glob_match, is_glob};
+use crate::output::print_json;
+
+/// What `apply-template` does (or would do) to one directory's quota
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum QuotaAction {
+    Create,
+    Update { from: u64 },
+    Unchanged,
+}
+
+impl QuotaAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QuotaAction::Create => "create",
+            QuotaAction::Update { .. } => "update",
+            QuotaAction::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// A directory matched by the template, keyed by its file ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QuotaTarget {
+    path: String,
+    id: String,
+}
+
+/// Ensure every directory matching `path_glob` has a quota of `limit`.
+///
+/// Directories without a quota get one; directories whose quota differs are
+/// updated; matching quotas are left alone. With `dry_run` the plan is printed
+/// as a diff and nothing is changed.
+pub fn apply_template(
+    client: &QumuloClient,
+    path_glob: &str,
+    limit: &str,
+    dry_run: bool,
+    json_mode: bool,
+) -> Result<()> {
+    let limit = parse_size(limit)?;
+    let targets = discover(client, path_glob)?;
+
+    let existing: HashMap<String, u64> = client
+        .get_all_quotas()
+        .context("failed to list quotas")?
+        .iter()
+        .filter_map(|q| {
+            let id = q.get("id").and_then(|v| v.as_str())?;
+            let limit = q.get("limit").and_then(|v| v.as_str())?.parse().ok()?;
+            Some((id.to_string(), limit))
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(targets.len());
+    for target in &targets {
+        let action = plan(existing.get(&target.id).copied(), limit);
+        let outcome = if dry_run {
+            Ok(())
+        } else {
+            match action {
+                QuotaAction::Create => client.create_quota(&target.id, limit).map(drop),
+                QuotaAction::Update { .. } => client.update_quota(&target.id, limit).map(drop),
+                QuotaAction::Unchanged => Ok(()),
+            }
+        };
+        let error = outcome.err().map(|e| format!("{:#}", e));
+        if !json_mode {
+            print_diff_line(&target.path, &action, limit, error.as_deref());
+        }
+        results.push((target, action, error));
+    }
+
+    let count = |name: &str| {
+        results
+            .iter()
+            .filter(|(_, a, e)| e.is_none() && a.as_str() == name)
+            .count()
+    };
+    let failed = results.iter().filter(|(_, _, e)| e.is_some()).count();
+
+    if json_mode {
+        let entries: Vec<Value> = results
+            .iter()
+            .map(|(target, action, error)| {
+                let current = match action {
+                    QuotaAction::Create => None,
+                    QuotaAction::Update { from } => Some(*from),
+                    QuotaAction::Unchanged => Some(limit),
+                };
+                json!({
+                    "path": target.path,
+                    "id": target.id,
+                    "action": action.as_str(),
+                    "current_limit": current.map(|b| b.to_string()),
+                    "limit": limit.to_string(),
+                    "error": error,
+                })
+            })
+            .collect();
+        let result = json!({
+            "path_glob": path_glob,
+            "limit": limit.to_string(),
+            "dry_run": dry_run,
+            "results": entries,
+        });
+        print_json(&result)?;
+    } else if targets.is_empty() {
+        println!("No directories match '{}'.", path_glob);
+    } else {
+        let (create, update, unchanged) = (count("create"), count("update"), count("unchanged"));
+        if dry_run {
+            println!(
+                "{} matched: {} to create, {} to update, {} unchanged (dry run)",
+                plural(targets.len()),
+                create,
+                update,
+                unchanged
+            );
+        } else {
+            println!(
+                "{} matched: {} created, {} updated, {} unchanged, {} failed",
+                plural(targets.len()),
+                create,
+                update,
+                unchanged,
+                failed
+            );
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} quotas failed to apply", failed, targets.len());
+    }
+    Ok(())
+}
+
+fn plan(current: Option<u64>, limit: u64) -> QuotaAction {
+    match current {
+        None => QuotaAction::Create,
+        Some(from) if from != limit => QuotaAction::Update { from },
+        Some(_) => QuotaAction::Unchanged,
+    }
+}
+
+fn print_diff_line(path: &str, action: &QuotaAction, limit: u64, error: Option<&str>) {
+    let line = match action {
+        QuotaAction::Create => {
+            Style::new()
+                .green()
+                .apply_to(format!("+ {}  {}", path, format_bytes(limit)))
+        }
+        QuotaAction::Update { from } => Style::new().yellow().apply_to(format!(
+            "~ {}  {} → {}",
+            path,
+            format_bytes(*from),
+            format_bytes(limit)
+        )),
+        QuotaAction::Unchanged => {
+            Style::new()
+                .dim()
+                .apply_to(format!("= {}  {}", path, format_bytes(limit)))
+        }
+    };
+    match error {
+        Some(e) => println!(
+            "{}  {}",
+            line,
+            Style::new().red().apply_to(format!("failed: {}", e))
+        ),
+        None => println!("{}", line),
+    }
+}
+
+fn plural(n: usize) -> String {
+    format!("{} {}", n, if n == 1 { "directory" } else { "directories" })
+}
+
+/// Find the directories matching an absolute glob, walking only the levels
+/// the pattern needs. Symlinks are not followed.
+fn discover(client: &QumuloClient, pattern: &str) -> Result<Vec<QuotaTarget>> {
+    if !pattern.starts_with('/') {
+        anyhow::bail!("--path-glob must be an absolute path (got '{}')", pattern);
+    }
+    let pattern = match pattern.trim_end_matches('/') {
+        "" => "/",
+        p => p,
+    };
+    let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let literal = segments.iter().take_while(|s| !is_glob(s)).count();
+    let base = format!("/{}", segments[..literal].join("/"));
+
+    let mut found = Vec::new();
+    if literal == segments.len() {
+        // No wildcards: the path itself, if it is a directory
+        let attrs = client
+            .get_file_attr(&base)
+            .with_context(|| format!("failed to stat {}", base))?;
+        if is_directory(&attrs) {
+            if let Some(id) = attrs.get("id").and_then(|v| v.as_str()) {
+                found.push(QuotaTarget {
+                    path: base,
+                    id: id.to_string(),
+                });
+            }
+        }
+    } else {
+        descend(client, &base, pattern, &segments[literal..], &mut found)?;
+    }
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(found)
+}
+
+fn descend(
+    client: &QumuloClient,
+    dir: &str,
+    pattern: &str,
+    remaining: &[&str],
+    found: &mut Vec<QuotaTarget>,
+) -> Result<()> {
+    let Some((segment, rest)) = remaining.split_first() else {
+        return Ok(());
+    };
+    let recursive = remaining.iter().any(|s| s.contains("**"));
+    let entries = client
+        .get_all_file_entries(dir)
+        .with_context(|| format!("failed to list {}", dir))?;
+
+    for entry in entries.iter().filter(|e| is_directory(e)) {
+        let (Some(name), Some(id)) = (
+            entry.get("name").and_then(|v| v.as_str()),
+            entry.get("id").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        let path = join_path(dir, name);
+
+        if recursive {
+            // '**' can match any depth; test the full path and keep walking
+            if glob_match(pattern, &path) {
+                found.push(QuotaTarget {
+                    path: path.clone(),
+                    id: id.to_string(),
+                });
+            }
+            descend(client, &path, pattern, remaining, found)?;
+        } else if glob_match(segment, name) {
+            if rest.is_empty() {
+                found.push(QuotaTarget {
+                    path,
+                    id: id.to_string(),
+                });
+            } else {
+                descend(client, &path, pattern, rest, found)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_directory(entry: &Value) -> bool {
+    entry.get("type").and_then(|v| v.as_str()) == Some("FS_FILE_TYPE_DIRECTORY")
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.ends_with('/') {
+        format!("{}{}", parent, name)
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+/// Parse a size such as `10T`, `500GiB`, `1.5TB` or `1048576` into bytes.
+///
+/// Single-letter and `iB` suffixes are binary (10T = 10 TiB); `KB`, `MB`, ...
+/// are decimal.
+pub fn parse_size(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+    let multiplier: u64 = match suffix.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KI" | "KIB" => 1 << 10,
+        "M" | "MI" | "MIB" => 1 << 20,
+        "G" | "GI" | "GIB" => 1 << 30,
+        "T" | "TI" | "TIB" => 1 << 40,
+        "P" | "PI" | "PIB" => 1 << 50,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "PB" => 1_000_000_000_000_000,
+        _ => anyhow::bail!("invalid size '{}' (expected e.g. 500G or 10T)", s),
+    };
+    let value: f64 = number
+        .parse()
+        .ok()
+        .filter(|v: &f64| v.is_finite() && *v > 0.0)
+        .with_context(|| format!("invalid size '{}' (expected e.g. 500G or 10T)", s))?;
+    Ok((value * multiplier as f64).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("10T").unwrap(), 10 * (1 << 40));
+        assert_eq!(parse_size("500GiB").unwrap(), 500 * (1 << 30));
+        assert_eq!(parse_size("1.5t").unwrap(), 3 * (1 << 39));
+        assert_eq!(parse_size("2TB").unwrap(), 2_000_000_000_000);
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("T").is_err());
+        assert!(parse_size("0G").is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(plan(None, 10), QuotaAction::Create);
+        assert_eq!(plan(Some(5), 10), QuotaAction::Update { from: 5 });
+        assert_eq!(plan(Some(10), 10), QuotaAction::Unchanged);
+    }
+}
@@ -0,0 +1,389 @@
+use anyhow::Result;
+use console::Style;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::commands::status::renderer::format_bytes;
+use crate::config::{Config, ProfileEntry};
+use crate::output::{csv_field, emit, print_json, OutputFormat};
+
+/// What a chargeback line is billed for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChargebackBy {
+    /// Each directory quota, at the capacity its tree uses
+    Quota,
+    /// Each directory under the root, at its aggregate capacity
+    ToplevelDir,
+}
+
+impl ChargebackBy {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "quota" => Ok(ChargebackBy::Quota),
+            "toplevel-dir" => Ok(ChargebackBy::ToplevelDir),
+            other => anyhow::bail!("unknown --by '{}' (expected quota, toplevel-dir)", other),
+        }
+    }
+}
+
+/// A price per unit of capacity per month, e.g. `0.02-per-GB-month`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rate {
+    pub amount: f64,
+    pub unit: &'static str,
+    unit_bytes: f64,
+}
+
+impl Rate {
+    /// Parse `AMOUNT-per-UNIT-month` (or `AMOUNT/UNIT-month`, or a bare
+    /// amount per GB-month). Units are GB and TB (decimal, as storage is
+    /// usually priced) or GiB and TiB.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || {
+            anyhow::anyhow!(
+                "invalid --rate '{}' (expected e.g. 0.02-per-GB-month or 20-per-TB-month)",
+                spec
+            )
+        };
+        let (amount, unit) = match spec.split_once("-per-").or_else(|| spec.split_once('/')) {
+            Some((amount, unit)) => (amount, unit.strip_suffix("-month").ok_or_else(invalid)?),
+            None => (spec, "GB"),
+        };
+        let amount: f64 = amount
+            .trim()
+            .parse()
+            .ok()
+            .filter(|a: &f64| a.is_finite() && *a >= 0.0)
+            .ok_or_else(invalid)?;
+        let (unit, unit_bytes) = match unit.to_ascii_lowercase().as_str() {
+            "gb" => ("GB", 1e9),
+            "tb" => ("TB", 1e12),
+            "gib" => ("GiB", 1_073_741_824.0),
+            "tib" => ("TiB", 1_099_511_627_776.0),
+            _ => return Err(invalid()),
+        };
+        Ok(Rate {
+            amount,
+            unit,
+            unit_bytes,
+        })
+    }
+
+    /// Monthly cost of holding `bytes`.
+    pub fn cost(&self, bytes: u64) -> f64 {
+        bytes as f64 / self.unit_bytes * self.amount
+    }
+}
+
+/// One team's line in the chargeback report.
+#[derive(Debug, Clone, Serialize)]
+struct ChargebackLine {
+    profile: String,
+    cluster: String,
+    path: String,
+    used_bytes: u64,
+    /// The quota's limit; None when billing by top-level directory
+    limit_bytes: Option<u64>,
+    /// Cost per month at --rate; None without one
+    cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ChargebackLine {
+    fn unreachable(profile: &str, error: String) -> Self {
+        Self {
+            profile: profile.to_string(),
+            cluster: String::new(),
+            path: String::new(),
+            used_bytes: 0,
+            limit_bytes: None,
+            cost: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Turn capacity usage on every selected cluster into per-team cost lines.
+pub fn chargeback(
+    config: &Config,
+    profile_filters: &[String],
+    timeout_secs: u64,
+    by: &str,
+    rate: Option<&str>,
+    json_mode: bool,
+    output: &str,
+) -> Result<()> {
+    let by = ChargebackBy::parse(by)?;
+    let rate = rate.map(Rate::parse).transpose()?;
+    let format = OutputFormat::parse(output)?;
+    let profiles = config.select_profiles(profile_filters);
+
+    if profiles.is_empty() {
+        anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
+    }
+
+    let mut lines: Vec<ChargebackLine> = std::thread::scope(|s| {
+        let handles: Vec<_> = profiles
+            .iter()
+            .map(|(name, entry)| s.spawn(move || collect_cluster(name, entry, timeout_secs, by)))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|h| {
+                h.join().unwrap_or_else(|_| {
+                    vec![ChargebackLine::unreachable(
+                        "unknown",
+                        "thread panicked".into(),
+                    )]
+                })
+            })
+            .collect()
+    });
+    if let Some(ref rate) = rate {
+        for line in lines.iter_mut().filter(|l| l.error.is_none()) {
+            line.cost = Some(rate.cost(line.used_bytes));
+        }
+    }
+    sort_lines(&mut lines);
+
+    if json_mode {
+        print_json(&lines)?;
+        return Ok(());
+    }
+    match format {
+        OutputFormat::Table => print_table(&lines, rate.as_ref()),
+        OutputFormat::Csv => print!("{}", render_csv(&lines)),
+        other => emit(&serde_json::to_value(&lines)?, "", other),
+    }
+    Ok(())
+}
+
+fn collect_cluster(
+    profile: &str,
+    entry: &ProfileEntry,
+    timeout_secs: u64,
+    by: ChargebackBy,
+) -> Vec<ChargebackLine> {
+    let fetch = || -> Result<Vec<ChargebackLine>> {
+        let client = QumuloClient::new(entry, timeout_secs, None)?;
+        let settings = client.get_cluster_settings()?;
+        let cluster = settings["cluster_name"].as_str().unwrap_or("unknown");
+        let line = |path: &str, used: &Value, limit: Option<u64>| ChargebackLine {
+            profile: profile.to_string(),
+            cluster: cluster.to_string(),
+            path: path.to_string(),
+            used_bytes: parse_u64(used).unwrap_or(0),
+            limit_bytes: limit,
+            cost: None,
+            error: None,
+        };
+
+        Ok(match by {
+            ChargebackBy::Quota => client
+                .get_all_quota_statuses()?
+                .iter()
+                .map(|q| {
+                    line(
+                        q["path"].as_str().unwrap_or("?"),
+                        &q["capacity_usage"],
+                        parse_u64(&q["limit"]),
+                    )
+                })
+                .collect(),
+            ChargebackBy::ToplevelDir => {
+                let root = client.get_child_aggregates("/")?;
+                root["files"]
+                    .as_array()
+                    .map(|a| a.as_slice())
+                    .unwrap_or(&[])
+                    .iter()
+                    .filter(|f| f["type"] == "FS_FILE_TYPE_DIRECTORY")
+                    .map(|f| {
+                        let path = format!("/{}/", f["name"].as_str().unwrap_or("?"));
+                        line(&path, &f["capacity_usage"], None)
+                    })
+                    .collect()
+            }
+        })
+    };
+    fetch().unwrap_or_else(|e| vec![ChargebackLine::unreachable(profile, format!("{:#}", e))])
+}
+
+/// The API reports sizes as decimal strings.
+fn parse_u64(v: &Value) -> Option<u64> {
+    v.as_str()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| v.as_u64())
+}
+
+/// Grouped by cluster, biggest consumer first; unreachable clusters last.
+fn sort_lines(lines: &mut [ChargebackLine]) {
+    lines.sort_by(|a, b| {
+        a.error
+            .is_some()
+            .cmp(&b.error.is_some())
+            .then_with(|| a.profile.cmp(&b.profile))
+            .then_with(|| b.used_bytes.cmp(&a.used_bytes))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+fn format_limit(limit: Option<u64>) -> String {
+    limit.map(format_bytes).unwrap_or_else(|| "-".to_string())
+}
+
+fn print_table(lines: &[ChargebackLine], rate: Option<&Rate>) {
+    let bold = Style::new().bold();
+    let yellow = Style::new().yellow();
+
+    println!(
+        "{:<20} {:<32} {:>10} {:>10} {:>12}",
+        bold.apply_to("CLUSTER"),
+        bold.apply_to("PATH"),
+        bold.apply_to("USED"),
+        bold.apply_to("LIMIT"),
+        bold.apply_to("COST/MONTH"),
+    );
+    println!("{}", "-".repeat(88));
+
+    for l in lines {
+        if let Some(ref err) = l.error {
+            println!(
+                "{:<20} {}",
+                l.profile,
+                yellow.apply_to(format!("error: {}", err))
+            );
+            continue;
+        }
+        println!(
+            "{:<20} {:<32} {:>10} {:>10} {:>12}",
+            l.profile,
+            l.path,
+            format_bytes(l.used_bytes),
+            format_limit(l.limit_bytes),
+            l.cost
+                .map(|c| format!("{:.2}", c))
+                .unwrap_or_else(|| "-".to_string()),
+        );
+    }
+
+    let billed: Vec<&ChargebackLine> = lines.iter().filter(|l| l.error.is_none()).collect();
+    let used: u64 = billed.iter().map(|l| l.used_bytes).sum();
+    println!();
+    match rate {
+        Some(rate) => println!(
+            "{} lines, {} used, {:.2} per month at {} per {}-month",
+            billed.len(),
+            format_bytes(used),
+            billed.iter().filter_map(|l| l.cost).sum::<f64>(),
+            rate.amount,
+            rate.unit
+        ),
+        None => println!(
+            "{} lines, {} used (pass --rate to price them)",
+            billed.len(),
+            format_bytes(used)
+        ),
+    }
+}
+
+fn render_csv(lines: &[ChargebackLine]) -> String {
+    let mut out = String::from("profile,cluster,path,used_bytes,limit_bytes,cost,error\n");
+    for l in lines {
+        let fields = [
+            csv_field(&l.profile),
+            csv_field(&l.cluster),
+            csv_field(&l.path),
+            l.used_bytes.to_string(),
+            l.limit_bytes.map(|b| b.to_string()).unwrap_or_default(),
+            l.cost.map(|c| format!("{:.2}", c)).unwrap_or_default(),
+            csv_field(l.error.as_deref().unwrap_or("")),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_line(profile: &str, path: &str, used_bytes: u64) -> ChargebackLine {
+        ChargebackLine {
+            profile: profile.to_string(),
+            cluster: profile.to_string(),
+            path: path.to_string(),
+            used_bytes,
+            limit_bytes: Some(5_000_000_000_000),
+            cost: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        let rate = Rate::parse("0.02-per-GB-month").unwrap();
+        assert_eq!((rate.amount, rate.unit), (0.02, "GB"));
+        assert!((rate.cost(1_500_000_000_000) - 30.0).abs() < 1e-9);
+
+        assert_eq!(Rate::parse("20-per-TB-month").unwrap().unit, "TB");
+        assert_eq!(Rate::parse("20/tib-month").unwrap().unit, "TiB");
+        assert_eq!(
+            Rate::parse("0.02").unwrap(),
+            Rate::parse("0.02-per-GB-month").unwrap()
+        );
+        let gib = Rate::parse("1-per-GiB-month").unwrap();
+        assert_eq!(gib.cost(2 * 1_073_741_824), 2.0);
+
+        for bad in ["cheap", "0.02-per-GB", "0.02-per-PB-month", "-1", "NaN"] {
+            let err = Rate::parse(bad).unwrap_err().to_string();
+            assert!(err.contains("expected e.g. 0.02-per-GB-month"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_parse_by() {
+        assert_eq!(ChargebackBy::parse("quota").unwrap(), ChargebackBy::Quota);
+        assert_eq!(
+            ChargebackBy::parse("toplevel-dir").unwrap(),
+            ChargebackBy::ToplevelDir
+        );
+        let err = ChargebackBy::parse("user").unwrap_err().to_string();
+        assert!(err.contains("expected quota, toplevel-dir"));
+    }
+
+    #[test]
+    fn test_sort_and_render_csv() {
+        let mut small = make_line("prod", "/home/", 1_000);
+        small.cost = Some(0.5);
+        let mut lines = vec![
+            ChargebackLine::unreachable("down", "refused, retry".into()),
+            small,
+            make_line("prod", "/projects/beta, old/", 2_000),
+            make_line("dr", "/projects/", 10),
+        ];
+        sort_lines(&mut lines);
+        let order: Vec<&str> = lines.iter().map(|l| l.path.as_str()).collect();
+        assert_eq!(
+            order,
+            vec!["/projects/", "/projects/beta, old/", "/home/", ""]
+        );
+
+        let csv = render_csv(&lines);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            rows[0],
+            "profile,cluster,path,used_bytes,limit_bytes,cost,error"
+        );
+        assert_eq!(
+            rows[2],
+            "prod,prod,\"/projects/beta, old/\",2000,5000000000000,,"
+        );
+        assert_eq!(rows[3], "prod,prod,/home/,1000,5000000000000,0.50,");
+        assert_eq!(rows[4], "down,,,0,,,\"refused, retry\"");
+    }
+}
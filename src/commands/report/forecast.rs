@@ -0,0 +1,447 @@
+use anyhow::Result;
+use console::Style;
+use serde::Serialize;
+
+use crate::client::QumuloClient;
+use crate::commands::status::capacity;
+use crate::commands::status::collector::{fetch_capacity, fetch_capacity_projection, Degraded};
+use crate::commands::status::detection::detect_cluster_type;
+use crate::commands::status::renderer::format_bytes;
+use crate::commands::status::types::{ClusterType, ProjectionConfidence};
+use crate::config::{Config, ProfileEntry};
+use crate::error::QontrolError;
+use crate::output::{csv_field, emit, html_bar, html_escape, html_page, print_json, OutputFormat};
+
+/// One cluster's line in the capacity forecast report.
+#[derive(Debug, Clone, Serialize)]
+struct ForecastRow {
+    profile: String,
+    cluster: String,
+    #[serde(rename = "type")]
+    cluster_type: String,
+    total_bytes: u64,
+    used_bytes: u64,
+    used_pct: f64,
+    days_until_full: Option<u64>,
+    growth_rate_bytes_per_day: Option<f64>,
+    confidence: Option<ProjectionConfidence>,
+    /// True when days-to-full is inside the warning threshold for the cluster type.
+    at_risk: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ForecastRow {
+    fn unreachable(profile: &str, error: String) -> Self {
+        Self {
+            profile: profile.to_string(),
+            cluster: String::new(),
+            cluster_type: String::new(),
+            total_bytes: 0,
+            used_bytes: 0,
+            used_pct: 0.0,
+            days_until_full: None,
+            growth_rate_bytes_per_day: None,
+            confidence: None,
+            at_risk: false,
+            error: Some(error),
+        }
+    }
+}
+
+/// Run the projection model for every selected cluster and print a ranked report.
+pub fn capacity_forecast(
+    config: &Config,
+    profile_filters: &[String],
+    timeout_secs: u64,
+    json_mode: bool,
+    output: &str,
+) -> Result<()> {
+    // A misspelled --cluster would otherwise just shrink the report
+    if let Some(name) = profile_filters
+        .iter()
+        .find(|f| !crate::glob::is_glob(f) && !config.profiles.contains_key(*f))
+    {
+        return Err(QontrolError::ProfileNotFound(name.clone()).into());
+    }
+    let profiles = config.select_profiles(profile_filters);
+
+    if profiles.is_empty() {
+        anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
+    }
+
+    let mut rows: Vec<ForecastRow> = std::thread::scope(|s| {
+        let handles: Vec<_> = profiles
+            .iter()
+            .map(|(name, entry)| s.spawn(move || forecast_cluster(name, entry, timeout_secs)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| {
+                h.join().unwrap_or_else(|_| {
+                    ForecastRow::unreachable("unknown", "thread panicked".into())
+                })
+            })
+            .collect()
+    });
+    rank_rows(&mut rows);
+
+    if json_mode {
+        print_json(&rows)?;
+        return Ok(());
+    }
+
+    match output {
+        "csv" => print!("{}", render_csv(&rows)),
+        "markdown" | "md" => print!("{}", render_markdown(&rows)),
+        "html" => print!("{}", render_html(&rows)),
+        "table" => print_table(&rows),
+        "yaml" => emit(&serde_json::to_value(&rows)?, "", OutputFormat::Yaml),
+        "ndjson" => emit(&serde_json::to_value(&rows)?, "", OutputFormat::Ndjson),
+        other => anyhow::bail!(
+            "unknown output format '{}' (expected table, json, yaml, csv, ndjson, markdown, html)",
+            other
+        ),
+    }
+    Ok(())
+}
+
+fn forecast_cluster(profile: &str, entry: &ProfileEntry, timeout_secs: u64) -> ForecastRow {
+    let client = match QumuloClient::new(entry, timeout_secs, None) {
+        Ok(c) => c,
+        Err(e) => return ForecastRow::unreachable(profile, format!("{:#}", e)),
+    };
+    let settings = match client.get_cluster_settings() {
+        Ok(v) => v,
+        Err(e) => return ForecastRow::unreachable(profile, format!("{:#}", e)),
+    };
+    let cluster_type = match client.get_cluster_nodes() {
+        Ok(nodes) => detect_cluster_type(nodes.as_array().map(|a| a.as_slice()).unwrap_or(&[])),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch nodes");
+            ClusterType::OnPrem(Vec::new())
+        }
+    };
+
+    let degraded = Degraded::default();
+    let cap = fetch_capacity(&client, &degraded);
+    let projection = fetch_capacity_projection(
+        &client,
+        cap.used_bytes,
+        cap.total_bytes,
+        entry.collect.projection(None, None),
+        &degraded,
+    );
+    let at_risk = projection
+        .as_ref()
+        .map(|p| capacity::should_warn(p, &cluster_type))
+        .unwrap_or(false);
+
+    ForecastRow {
+        profile: profile.to_string(),
+        cluster: settings["cluster_name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        cluster_type: cluster_type.to_string(),
+        total_bytes: cap.total_bytes,
+        used_bytes: cap.used_bytes,
+        used_pct: cap.used_pct,
+        days_until_full: projection.as_ref().and_then(|p| p.days_until_full),
+        growth_rate_bytes_per_day: projection.as_ref().map(|p| p.growth_rate_bytes_per_day),
+        confidence: projection.map(|p| p.confidence),
+        at_risk,
+        error: None,
+    }
+}
+
+/// Soonest-to-fill first; clusters without a projection follow, unreachable ones last.
+fn rank_rows(rows: &mut [ForecastRow]) {
+    rows.sort_by(|a, b| {
+        let key = |r: &ForecastRow| (r.error.is_some(), r.days_until_full.unwrap_or(u64::MAX));
+        key(a)
+            .cmp(&key(b))
+            .then_with(|| b.used_pct.total_cmp(&a.used_pct))
+            .then_with(|| a.profile.cmp(&b.profile))
+    });
+}
+
+fn format_days(days: Option<u64>) -> String {
+    match days {
+        Some(d) => format!("{}", d),
+        None => "-".to_string(),
+    }
+}
+
+fn format_growth(rate: Option<f64>) -> String {
+    match rate {
+        Some(r) => format!("+{}/day", format_bytes(r.max(0.0) as u64)),
+        None => "-".to_string(),
+    }
+}
+
+fn format_confidence(confidence: &Option<ProjectionConfidence>) -> &'static str {
+    match confidence {
+        Some(ProjectionConfidence::High) => "high",
+        Some(ProjectionConfidence::Low) => "low",
+        None => "-",
+    }
+}
+
+fn print_table(rows: &[ForecastRow]) {
+    let bold = Style::new().bold();
+    let red = Style::new().red();
+    let yellow = Style::new().yellow();
+
+    println!(
+        "{:<20} {:>10} {:>10} {:>6} {:>12} {:>14} {:>10}",
+        bold.apply_to("CLUSTER"),
+        bold.apply_to("USED"),
+        bold.apply_to("TOTAL"),
+        bold.apply_to("USED%"),
+        bold.apply_to("DAYS TO FULL"),
+        bold.apply_to("GROWTH"),
+        bold.apply_to("CONFIDENCE"),
+    );
+    println!("{}", "-".repeat(88));
+
+    for r in rows {
+        if let Some(ref err) = r.error {
+            println!(
+                "{:<20} {}",
+                r.profile,
+                yellow.apply_to(format!("error: {}", err))
+            );
+            continue;
+        }
+        let days = format!("{:>12}", format_days(r.days_until_full));
+        println!(
+            "{:<20} {:>10} {:>10} {:>5.1}% {} {:>14} {:>10}",
+            r.profile,
+            format_bytes(r.used_bytes),
+            format_bytes(r.total_bytes),
+            r.used_pct,
+            if r.at_risk {
+                red.apply_to(days).to_string()
+            } else {
+                days
+            },
+            format_growth(r.growth_rate_bytes_per_day),
+            format_confidence(&r.confidence),
+        );
+    }
+
+    let at_risk = rows.iter().filter(|r| r.at_risk).count();
+    println!();
+    println!(
+        "{} clusters, {} projected to fill within the warning threshold",
+        rows.len(),
+        at_risk
+    );
+}
+
+fn render_csv(rows: &[ForecastRow]) -> String {
+    let mut out = String::from(
+        "profile,cluster,type,total_bytes,used_bytes,used_pct,days_until_full,growth_bytes_per_day,confidence,at_risk,error\n",
+    );
+    for r in rows {
+        let fields = [
+            csv_field(&r.profile),
+            csv_field(&r.cluster),
+            csv_field(&r.cluster_type),
+            r.total_bytes.to_string(),
+            r.used_bytes.to_string(),
+            format!("{:.2}", r.used_pct),
+            r.days_until_full.map(|d| d.to_string()).unwrap_or_default(),
+            r.growth_rate_bytes_per_day
+                .map(|g| format!("{:.0}", g))
+                .unwrap_or_default(),
+            r.confidence
+                .as_ref()
+                .map(|_| format_confidence(&r.confidence).to_string())
+                .unwrap_or_default(),
+            r.at_risk.to_string(),
+            csv_field(r.error.as_deref().unwrap_or("")),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn render_markdown(rows: &[ForecastRow]) -> String {
+    let mut out = String::new();
+    let reachable = rows.iter().filter(|r| r.error.is_none()).count();
+    let at_risk: Vec<&ForecastRow> = rows.iter().filter(|r| r.at_risk).collect();
+
+    out.push_str(&format!(
+        "## Capacity forecast — {}\n\n",
+        chrono::Utc::now().format("%Y-%m-%d")
+    ));
+    out.push_str(&format!(
+        "- **{}** clusters reporting ({} unreachable)\n",
+        reachable,
+        rows.len() - reachable
+    ));
+    out.push_str(&format!(
+        "- **{}** projected to fill within the warning threshold\n",
+        at_risk.len()
+    ));
+    if let Some(first) = at_risk.first() {
+        out.push_str(&format!(
+            "- Soonest: **{}** in ~{} days\n",
+            first.profile,
+            format_days(first.days_until_full)
+        ));
+    }
+    out.push('\n');
+
+    out.push_str("| Cluster | Used | Total | Used % | Days to full | Growth | Confidence |\n");
+    out.push_str("|---|---:|---:|---:|---:|---:|---|\n");
+    for r in rows {
+        if let Some(ref err) = r.error {
+            out.push_str(&format!(
+                "| {} | - | - | - | - | - | error: {} |\n",
+                r.profile,
+                err.replace('|', "\\|")
+            ));
+            continue;
+        }
+        let days = format_days(r.days_until_full);
+        out.push_str(&format!(
+            "| {} | {} | {} | {:.1}% | {} | {} | {} |\n",
+            r.profile,
+            format_bytes(r.used_bytes),
+            format_bytes(r.total_bytes),
+            r.used_pct,
+            if r.at_risk {
+                format!("**{}**", days)
+            } else {
+                days
+            },
+            format_growth(r.growth_rate_bytes_per_day),
+            format_confidence(&r.confidence),
+        ));
+    }
+    out
+}
+
+fn render_html(rows: &[ForecastRow]) -> String {
+    let mut body = String::new();
+    let at_risk = rows.iter().filter(|r| r.at_risk).count();
+
+    body.push_str("<h1>Capacity Forecast</h1>\n");
+    body.push_str(&format!(
+        "<p class=\"muted\">Generated {} · {} clusters, {} projected to fill within the warning threshold</p>\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M %Z"),
+        rows.len(),
+        at_risk
+    ));
+    body.push_str("<table>\n<tr><th>Cluster</th><th>Capacity</th><th>Used</th><th>Total</th><th>Days to full</th><th>Growth</th><th>Confidence</th></tr>\n");
+    for r in rows {
+        if let Some(ref err) = r.error {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td colspan=\"6\" class=\"warning\">error: {}</td></tr>\n",
+                html_escape(&r.profile),
+                html_escape(err)
+            ));
+            continue;
+        }
+        body.push_str(&format!(
+            "<tr><td>{}</td><td>{} {:.1}%</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num{}\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+            html_escape(&r.profile),
+            html_bar(r.used_pct),
+            r.used_pct,
+            format_bytes(r.used_bytes),
+            format_bytes(r.total_bytes),
+            if r.at_risk { " critical" } else { "" },
+            format_days(r.days_until_full),
+            format_growth(r.growth_rate_bytes_per_day),
+            format_confidence(&r.confidence),
+        ));
+    }
+    body.push_str("</table>\n");
+    html_page("Capacity Forecast", &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_row(profile: &str, days: Option<u64>, used_pct: f64) -> ForecastRow {
+        ForecastRow {
+            profile: profile.to_string(),
+            cluster: profile.to_string(),
+            cluster_type: "CNQ-AWS".to_string(),
+            total_bytes: 1_000_000,
+            used_bytes: (used_pct * 10_000.0) as u64,
+            used_pct,
+            days_until_full: days,
+            growth_rate_bytes_per_day: days.map(|_| 1024.0),
+            confidence: days.map(|_| ProjectionConfidence::High),
+            at_risk: days.map(|d| d < 7).unwrap_or(false),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_rank_rows_soonest_first() {
+        let mut rows = vec![
+            make_row("stable", None, 50.0),
+            make_row("slow", Some(400), 40.0),
+            ForecastRow::unreachable("down", "connection refused".into()),
+            make_row("fast", Some(3), 90.0),
+        ];
+        rank_rows(&mut rows);
+        let order: Vec<&str> = rows.iter().map(|r| r.profile.as_str()).collect();
+        assert_eq!(order, vec!["fast", "slow", "stable", "down"]);
+    }
+
+    #[test]
+    fn test_rank_rows_ties_broken_by_usage() {
+        let mut rows = vec![make_row("a", None, 10.0), make_row("b", None, 80.0)];
+        rank_rows(&mut rows);
+        assert_eq!(rows[0].profile, "b");
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let rows = vec![
+            make_row("fast", Some(3), 90.0),
+            ForecastRow::unreachable("down", "refused, retry".into()),
+        ];
+        let csv = render_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("profile,cluster,type,"));
+        assert!(lines[1].starts_with("fast,fast,CNQ-AWS,1000000,900000,90.00,3,1024,high,true,"));
+        assert!(lines[2].ends_with(",false,\"refused, retry\""));
+    }
+
+    #[test]
+    fn test_render_markdown_summary() {
+        let rows = vec![
+            make_row("fast", Some(3), 90.0),
+            make_row("slow", Some(400), 40.0),
+        ];
+        let md = render_markdown(&rows);
+        assert!(md.contains("**2** clusters reporting (0 unreachable)"));
+        assert!(md.contains("**1** projected to fill"));
+        assert!(md.contains("Soonest: **fast** in ~3 days"));
+        assert!(md.contains("| fast | "));
+        assert!(md.contains("| **3** |"));
+    }
+
+    #[test]
+    fn test_render_html() {
+        let rows = vec![
+            make_row("fast", Some(3), 90.0),
+            ForecastRow::unreachable("down", "refused <tls>".into()),
+        ];
+        let html = render_html(&rows);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<td class=\"num critical\">3</td>"));
+        assert!(html.contains("error: refused &lt;tls&gt;"));
+    }
+}
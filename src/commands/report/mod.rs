@@ -0,0 +1,3 @@
+pub mod chargeback;
+pub mod forecast;
+pub mod template;
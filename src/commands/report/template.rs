@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::Value;
+
+use crate::commands::status::collector;
+use crate::commands::status::json::JsonOutput;
+use crate::commands::status::renderer::{format_bytes, format_number};
+use crate::config::Config;
+
+/// Collect fleet status and render it through a user-supplied template.
+///
+/// The template sees the same document as `fleet status --json` (`timestamp`,
+/// `aggregates`, `alerts`, `clusters`) plus `generated_at`, and can use the
+/// `bytes` and `number` filters for human-readable values.
+pub fn generate(
+    config: &Config,
+    profile_filters: &[String],
+    timeout_secs: u64,
+    template_path: &Path,
+    out_path: Option<&Path>,
+) -> Result<()> {
+    let source = std::fs::read_to_string(template_path)
+        .with_context(|| format!("failed to read template {}", template_path.display()))?;
+
+    let (status, _) = collector::collect_all(
+        config,
+        profile_filters,
+        collector::CollectOptions {
+            suppress_progress: out_path.is_none(),
+            ..collector::CollectOptions::new(timeout_secs)
+        },
+    )?;
+    let mut context = serde_json::to_value(JsonOutput::from_status(&status))?;
+    context["generated_at"] =
+        Value::String(chrono::Local::now().format("%Y-%m-%d %H:%M %Z").to_string());
+
+    let rendered = render_template(&source, &context)
+        .with_context(|| format!("failed to render template {}", template_path.display()))?;
+
+    match out_path {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("Report written to {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Render a template string against a JSON context.
+fn render_template(source: &str, context: &Value) -> Result<String> {
+    let mut env = Environment::new();
+    // Typos in field names should fail loudly rather than render as blanks.
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.add_filter("bytes", |v: u64| format_bytes(v));
+    env.add_filter("number", |v: u64| format_number(v));
+    env.add_template("report", source)?;
+    let tmpl = env.get_template("report")?;
+    Ok(tmpl.render(context)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_context() -> Value {
+        json!({
+            "generated_at": "2026-01-05 09:00 UTC",
+            "aggregates": {"cluster_count": 2, "used_capacity_bytes": 1_099_511_627_776u64, "total_files": 1234567},
+            "alerts": [{"severity": "warning", "cluster": "alpha", "message": "disk unhealthy", "category": "disk_unhealthy"}],
+            "clusters": [
+                {"profile": "alpha", "capacity": {"used_pct": 91.5}},
+                {"profile": "beta", "capacity": {"used_pct": 12.0}}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_render_template_fields_and_filters() {
+        let out = render_template(
+            "{{ generated_at }}: {{ aggregates.cluster_count }} clusters, {{ aggregates.used_capacity_bytes | bytes }}, {{ aggregates.total_files | number }} files",
+            &sample_context(),
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "2026-01-05 09:00 UTC: 2 clusters, 1.0 TB, 1,234,567 files"
+        );
+    }
+
+    #[test]
+    fn test_render_template_loops() {
+        let out = render_template(
+            "{% for c in clusters %}- {{ c.profile }} {{ c.capacity.used_pct }}%\n{% endfor %}{% for a in alerts %}[{{ a.severity }}] {{ a.message }}{% endfor %}",
+            &sample_context(),
+        )
+        .unwrap();
+        assert_eq!(out, "- alpha 91.5%\n- beta 12.0%\n[warning] disk unhealthy");
+    }
+
+    #[test]
+    fn test_render_template_unknown_field_errors() {
+        let result = render_template("{{ aggregates.no_such_field }}", &sample_context());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_render_template_syntax_error() {
+        let result = render_template("{% for c in clusters %}", &sample_context());
+        assert!(result.is_err());
+    }
+}
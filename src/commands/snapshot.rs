@@ -2,10 +2,15 @@ use std::collections::HashMap;
 
 use anyhow::{Context, Result};
 use chrono::{Datelike, NaiveDate};
+use console::Style;
+use serde::Serialize;
 use serde_json::Value;
 
 use crate::client::QumuloClient;
-use crate::output::{print_table, print_value};
+use crate::commands::maintenance::parse_duration;
+use crate::config::Config;
+use crate::confirm::Guard;
+use crate::output::{emit, print_json, print_table, print_value, OutputFormat};
 
 /// Format bytes into human-readable size
 fn format_bytes(bytes_str: &str) -> String {
@@ -27,8 +32,75 @@ fn format_bytes(bytes_str: &str) -> String {
     }
 }
 
-pub fn list(client: &QumuloClient, json_mode: bool) -> Result<()> {
-    let status = client.get_snapshots()?;
+/// Which snapshots `list` shows and a bulk `delete` removes. An empty filter
+/// matches every snapshot.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotFilter {
+    name_glob: Option<String>,
+    older_than: Option<chrono::Duration>,
+    /// `Some(true)` for policy-created snapshots, `Some(false)` for manual ones
+    by_policy: Option<bool>,
+}
+
+impl SnapshotFilter {
+    pub fn parse(
+        name_glob: Option<&str>,
+        older_than: Option<&str>,
+        created_by: Option<&str>,
+    ) -> Result<Self> {
+        let by_policy = match created_by {
+            None => None,
+            Some("policy") => Some(true),
+            Some("manual") => Some(false),
+            Some(other) => anyhow::bail!(
+                "invalid --created-by '{}' (expected policy or manual)",
+                other
+            ),
+        };
+        Ok(SnapshotFilter {
+            name_glob: name_glob.map(str::to_string),
+            older_than: older_than.map(parse_duration).transpose()?,
+            by_policy,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name_glob.is_none() && self.older_than.is_none() && self.by_policy.is_none()
+    }
+
+    /// A snapshot without a parseable timestamp never matches `older_than`.
+    fn matches(&self, entry: &Value, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        if self
+            .name_glob
+            .as_deref()
+            .is_some_and(|g| !crate::glob::glob_match(g, name))
+        {
+            return false;
+        }
+        if let Some(age) = self.older_than {
+            let taken = entry
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok());
+            if taken.is_none_or(|t| t >= now - age) {
+                return false;
+            }
+        }
+        let policy = entry
+            .get("created_by_policy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        self.by_policy.is_none_or(|p| p == policy)
+    }
+}
+
+pub fn list(client: &QumuloClient, filter: &SnapshotFilter, format: OutputFormat) -> Result<()> {
+    let mut status = client.get_snapshots()?;
+    if let Some(entries) = status.get_mut("entries").and_then(|v| v.as_array_mut()) {
+        let now = chrono::Utc::now();
+        entries.retain(|e| filter.matches(e, now));
+    }
     let capacity = client.get_snapshot_capacity_per_snapshot()?;
 
     // Build capacity lookup: id -> capacity_used_bytes
@@ -44,7 +116,7 @@ pub fn list(client: &QumuloClient, json_mode: bool) -> Result<()> {
         }
     }
 
-    if json_mode {
+    if format.is_structured() {
         // Enrich entries with capacity info
         let mut result = status.clone();
         if let Some(entries) = result.get_mut("entries").and_then(|v| v.as_array_mut()) {
@@ -59,10 +131,7 @@ pub fn list(client: &QumuloClient, json_mode: bool) -> Result<()> {
                 }
             }
         }
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
-        );
+        emit(&result, "/entries", format);
         return Ok(());
     }
 
@@ -105,10 +174,7 @@ pub fn show(client: &QumuloClient, id: u64, json_mode: bool) -> Result<()> {
     let snap = client.get_snapshot(id)?;
 
     if json_mode {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&snap).unwrap_or_else(|_| snap.to_string())
-        );
+        print_json(&snap)?;
         return Ok(());
     }
 
@@ -167,10 +233,7 @@ pub fn policies(client: &QumuloClient, json_mode: bool) -> Result<()> {
     let policies = client.get_snapshot_policies()?;
 
     if json_mode {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&policies).unwrap_or_else(|_| policies.to_string())
-        );
+        print_json(&policies)?;
         return Ok(());
     }
 
@@ -270,7 +333,7 @@ pub fn recommend_delete(
         }
     }
 
-    snapshots.sort_by(|a, b| b.2.cmp(&a.2));
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.2));
 
     // GFS retention: keep the most recent snapshot per day/week/month
     let mut keep_set: std::collections::HashSet<u64> = std::collections::HashSet::new();
@@ -335,10 +398,7 @@ pub fn recommend_delete(
             "estimated_savings_bytes": savings.get("bytes").and_then(|v| v.as_str()).unwrap_or("0"),
         });
 
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
-        );
+        print_json(&result)?;
         return Ok(());
     }
 
@@ -390,10 +450,7 @@ pub fn diff(client: &QumuloClient, newer: u64, older: u64, json_mode: bool) -> R
     let changes = client.get_snapshot_diff(newer, older)?;
 
     if json_mode {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&changes).unwrap_or_else(|_| changes.to_string())
-        );
+        print_json(&changes)?;
         return Ok(());
     }
 
@@ -420,6 +477,1070 @@ pub fn diff(client: &QumuloClient, newer: u64, older: u64, json_mode: bool) -> R
     Ok(())
 }
 
+/// One snapshot's share of the space attributed to a path.
+#[derive(Debug, Serialize)]
+struct Attribution {
+    id: u64,
+    name: String,
+    capacity_used_bytes: u64,
+    /// The next snapshot of the same directory, which the changes were
+    /// sampled against
+    compared_with: Option<u64>,
+    /// Modified and deleted entries read from the diff
+    sampled_changes: usize,
+    changes_under_path: usize,
+    /// None when nothing could be sampled: the newest snapshot of a
+    /// directory, or a diff with no modifies or deletes
+    attributed_bytes: Option<u64>,
+}
+
+/// Estimate how much snapshot space is attributable to changes under `path`.
+///
+/// The space a snapshot uses is data only it still holds: data modified or
+/// deleted before the next snapshot of the same directory was taken. So
+/// each snapshot is diffed against that next one, and the share of modified
+/// and deleted entries under `path` (creates hold nothing in the older
+/// snapshot) becomes the share of its space attributed to the path. Up to
+/// `sample` changes are read per snapshot. The newest snapshot of each
+/// directory holds data changed since in the live tree, which can't be
+/// diffed, so its space is reported as not sampled.
+pub fn attribution(
+    client: &QumuloClient,
+    path: &str,
+    sample: usize,
+    json_mode: bool,
+) -> Result<()> {
+    let status = client.get_snapshots()?;
+    let capacity = client.get_snapshot_capacity_per_snapshot()?;
+    let entries = status
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .context("unexpected response: missing entries")?;
+    let held: HashMap<u64, u64> = capacity
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .map(|a| a.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|e| {
+            let id = e.get("id")?.as_u64()?;
+            let bytes = e.get("capacity_used_bytes")?.as_str()?.parse().ok()?;
+            Some((id, bytes))
+        })
+        .collect();
+
+    let prefix = path.trim_end_matches('/');
+    let mut rows = Vec::new();
+    for (snap, next) in successors(entries) {
+        let id = snap.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+        let capacity_used_bytes = held.get(&id).copied().unwrap_or(0);
+        let (mut sampled, mut under) = (0, 0);
+        if let Some(next) = next.filter(|_| capacity_used_bytes > 0) {
+            for change in client.snapshot_changes(next, id).take(sample) {
+                let change = change?;
+                if change.get("op").and_then(|v| v.as_str()) == Some("CREATE") {
+                    continue;
+                }
+                sampled += 1;
+                let changed = change.get("path").and_then(|v| v.as_str()).unwrap_or("");
+                if is_under(changed, prefix) {
+                    under += 1;
+                }
+            }
+        }
+        let attributed_bytes = match next {
+            Some(_) if capacity_used_bytes == 0 => Some(0),
+            Some(_) if sampled > 0 => {
+                Some((capacity_used_bytes as f64 * under as f64 / sampled as f64).round() as u64)
+            }
+            _ => None,
+        };
+        rows.push(Attribution {
+            id,
+            name: snap
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("-")
+                .to_string(),
+            capacity_used_bytes,
+            compared_with: next,
+            sampled_changes: sampled,
+            changes_under_path: under,
+            attributed_bytes,
+        });
+    }
+
+    let attributed: u64 = rows.iter().filter_map(|r| r.attributed_bytes).sum();
+    let sampled_bytes: u64 = rows
+        .iter()
+        .filter(|r| r.attributed_bytes.is_some())
+        .map(|r| r.capacity_used_bytes)
+        .sum();
+    let unsampled_bytes = rows.iter().map(|r| r.capacity_used_bytes).sum::<u64>() - sampled_bytes;
+
+    if json_mode {
+        let result = serde_json::json!({
+            "path": path,
+            "sample": sample,
+            "attributed_bytes": attributed,
+            "sampled_capacity_bytes": sampled_bytes,
+            "unsampled_capacity_bytes": unsampled_bytes,
+            "snapshots": rows,
+        });
+        print_json(&result)?;
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("No snapshots found.");
+        return Ok(());
+    }
+    println!(
+        "Snapshot space attributable to {} (up to {} changes sampled per snapshot)",
+        path, sample
+    );
+    println!();
+    let table: Vec<Value> = rows
+        .iter()
+        .map(|r| {
+            let under = match (r.compared_with, r.sampled_changes) {
+                (None, _) => "newest; not sampled".to_string(),
+                (Some(_), 0) => "-".to_string(),
+                (Some(_), n) => format!(
+                    "{}/{} ({:.0}%)",
+                    r.changes_under_path,
+                    n,
+                    r.changes_under_path as f64 / n as f64 * 100.0
+                ),
+            };
+            serde_json::json!({
+                "id": r.id,
+                "name": r.name,
+                "held": format_bytes(&r.capacity_used_bytes.to_string()),
+                "changes_under_path": under,
+                "attributed": r
+                    .attributed_bytes
+                    .map(|b| format_bytes(&b.to_string()))
+                    .unwrap_or_else(|| "-".to_string()),
+            })
+        })
+        .collect();
+    print_table(
+        &Value::Array(table),
+        &["id", "name", "held", "changes_under_path", "attributed"],
+    );
+    println!();
+    println!(
+        "Attributed: {} of the {} held by sampled snapshots",
+        format_bytes(&attributed.to_string()),
+        format_bytes(&sampled_bytes.to_string())
+    );
+    if unsampled_bytes > 0 {
+        println!(
+            "Not sampled: {} held by snapshots with nothing to diff against",
+            format_bytes(&unsampled_bytes.to_string())
+        );
+    }
+    Ok(())
+}
+
+/// Each live snapshot, oldest first, with the id of the next snapshot of
+/// the same directory (None for the newest).
+fn successors(entries: &[Value]) -> Vec<(&Value, Option<u64>)> {
+    let mut live: Vec<&Value> = entries
+        .iter()
+        .filter(|e| {
+            !e.get("in_delete")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+        .filter(|e| e.get("id").and_then(|v| v.as_u64()).is_some())
+        .collect();
+    live.sort_by_key(|e| e.get("id").and_then(|v| v.as_u64()));
+    live.iter()
+        .enumerate()
+        .map(|(i, snap)| {
+            let source = snap.get("source_file_id");
+            let next = live[i + 1..]
+                .iter()
+                .find(|later| later.get("source_file_id") == source)
+                .and_then(|later| later.get("id").and_then(|v| v.as_u64()));
+            (*snap, next)
+        })
+        .collect()
+}
+
+/// Whether a changed path is `prefix` or inside it ("" is the root).
+fn is_under(path: &str, prefix: &str) -> bool {
+    prefix.is_empty()
+        || path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Snapshot the directory at `path`. `expiration` is a lifetime such as `7d`,
+/// an RFC 3339 time, or `never` (the default).
+pub fn create(
+    client: &QumuloClient,
+    path: &str,
+    name: Option<&str>,
+    expiration: Option<&str>,
+    json_mode: bool,
+) -> Result<()> {
+    let expiration = expiration.map(parse_expiration).transpose()?.flatten();
+    let attrs = client
+        .get_file_attr(path)
+        .with_context(|| format!("failed to stat {}", path))?;
+    let source_id = attrs
+        .get("id")
+        .and_then(|v| v.as_str())
+        .context("unexpected response: missing file id")?;
+
+    let snap = client.create_snapshot(source_id, name, expiration.as_deref())?;
+
+    if json_mode {
+        print_json(&snap)?;
+        return Ok(());
+    }
+
+    let id = snap.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+    let snap_name = snap.get("name").and_then(|v| v.as_str()).unwrap_or("-");
+    println!("Created snapshot {} ({}) of {}", id, snap_name, path);
+    println!(
+        "  Expiration:  {}",
+        expiration.as_deref().unwrap_or("never")
+    );
+    Ok(())
+}
+
+/// Delete a snapshot after confirmation, reporting the capacity the delete
+/// is expected to reclaim.
+pub fn delete(client: &QumuloClient, guard: &Guard, id: u64, json_mode: bool) -> Result<()> {
+    let snap = client.get_snapshot(id)?;
+    let name = snap.get("name").and_then(|v| v.as_str()).unwrap_or("-");
+    let capacity = client.get_snapshot_capacity_per_snapshot()?;
+    let reclaimable = capacity
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .and_then(|entries| {
+            entries
+                .iter()
+                .find(|e| e.get("id").and_then(|v| v.as_u64()) == Some(id))
+        })
+        .and_then(|e| e.get("capacity_used_bytes").and_then(|v| v.as_str()))
+        .unwrap_or("0")
+        .to_string();
+
+    let confirmed = guard.confirm(
+        client,
+        &format!("delete snapshot {}", id),
+        &format!(
+            "Delete snapshot {} ({})? About {} will be reclaimed.",
+            id,
+            name,
+            format_bytes(&reclaimable)
+        ),
+    )?;
+    if !confirmed {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    client.delete_snapshot(id)?;
+
+    if json_mode {
+        let result = serde_json::json!({
+            "id": id,
+            "name": name,
+            "deleted": true,
+            "reclaimable_bytes": reclaimable,
+        });
+        print_json(&result)?;
+    } else {
+        println!("Deleted snapshot {} ({})", id, name);
+        println!(
+            "  Reclaimed:   ~{} (freed as the background delete completes)",
+            format_bytes(&reclaimable)
+        );
+    }
+    Ok(())
+}
+
+/// Delete every snapshot matching `filter` after previewing them and asking
+/// once. With `dry_run` only the preview is shown.
+pub fn delete_matching(
+    client: &QumuloClient,
+    guard: &Guard,
+    filter: &SnapshotFilter,
+    dry_run: bool,
+    json_mode: bool,
+) -> Result<()> {
+    if filter.is_empty() {
+        anyhow::bail!(
+            "pass a snapshot ID, or at least one of --name-glob, --older-than, --created-by"
+        );
+    }
+    let status = client.get_snapshots()?;
+    let capacity = client.get_snapshot_capacity_per_snapshot()?;
+    let cap_map: HashMap<u64, u64> = capacity
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| {
+            let id = e.get("id").and_then(|v| v.as_u64())?;
+            let bytes = e.get("capacity_used_bytes").and_then(|v| v.as_str())?;
+            Some((id, bytes.parse().unwrap_or(0)))
+        })
+        .collect();
+
+    let now = chrono::Utc::now();
+    let targets: Vec<Value> = status
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .context("unexpected response: missing entries")?
+        .iter()
+        .filter(|e| {
+            !e.get("in_delete")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+        .filter(|e| filter.matches(e, now))
+        .map(|e| {
+            let id = e.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+            serde_json::json!({
+                "id": id,
+                "name": e.get("name").and_then(|v| v.as_str()).unwrap_or("-"),
+                "timestamp": e.get("timestamp").and_then(|v| v.as_str()).unwrap_or("-"),
+                "reclaimable_bytes": cap_map.get(&id).copied().unwrap_or(0).to_string(),
+            })
+        })
+        .collect();
+    // Blocks shared only among the deleted snapshots are freed too, so this is
+    // a lower bound
+    let reclaimable: u64 = targets
+        .iter()
+        .filter_map(|t| t["reclaimable_bytes"].as_str()?.parse::<u64>().ok())
+        .sum();
+
+    if !json_mode {
+        if targets.is_empty() {
+            println!("No snapshots match.");
+            return Ok(());
+        }
+        let rows: Vec<Value> = targets
+            .iter()
+            .map(|t| {
+                let mut row = t.clone();
+                row["capacity"] =
+                    Value::String(format_bytes(t["reclaimable_bytes"].as_str().unwrap_or("0")));
+                row
+            })
+            .collect();
+        print_table(
+            &Value::Array(rows),
+            &["id", "name", "timestamp", "capacity"],
+        );
+        println!();
+        println!(
+            "{} snapshot(s) match; at least {} would be reclaimed.",
+            targets.len(),
+            format_bytes(&reclaimable.to_string())
+        );
+    }
+    if dry_run || targets.is_empty() {
+        if json_mode {
+            let result = serde_json::json!({
+                "dry_run": dry_run,
+                "reclaimable_bytes": reclaimable.to_string(),
+                "snapshots": targets,
+            });
+            print_json(&result)?;
+        } else {
+            println!("Dry run: nothing was deleted.");
+        }
+        return Ok(());
+    }
+
+    let confirmed = guard.confirm(
+        client,
+        &format!("delete {} snapshots", targets.len()),
+        &format!(
+            "Delete these {} snapshots? At least {} will be reclaimed.",
+            targets.len(),
+            format_bytes(&reclaimable.to_string())
+        ),
+    )?;
+    if !confirmed {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    let mut results = Vec::with_capacity(targets.len());
+    for target in targets {
+        let id = target["id"].as_u64().unwrap_or(0);
+        let error = client.delete_snapshot(id).err().map(|e| format!("{:#}", e));
+        if let Some(err) = &error {
+            if !json_mode {
+                eprintln!("Failed to delete snapshot {}: {}", id, err);
+            }
+        }
+        let mut result = target;
+        result["deleted"] = Value::Bool(error.is_none());
+        result["error"] = error.map_or(Value::Null, Value::String);
+        results.push(result);
+    }
+    let failed = results.iter().filter(|r| r["deleted"] == false).count();
+
+    if json_mode {
+        let result = serde_json::json!({
+            "dry_run": false,
+            "reclaimable_bytes": reclaimable.to_string(),
+            "snapshots": results,
+        });
+        print_json(&result)?;
+    } else {
+        println!(
+            "Deleted {} of {} snapshots (space is freed as the background delete completes).",
+            results.len() - failed,
+            results.len()
+        );
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} snapshot deletes failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// `never` means no expiration; durations are relative to now.
+fn parse_expiration(s: &str) -> Result<Option<String>> {
+    if s == "never" {
+        return Ok(None);
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        let dt = dt.with_timezone(&chrono::Utc);
+        return Ok(Some(dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)));
+    }
+    let lifetime = parse_duration(s).with_context(|| {
+        format!(
+            "invalid --expiration '{}' (expected e.g. 7d, an RFC 3339 time, or never)",
+            s
+        )
+    })?;
+    Ok(Some(
+        (chrono::Utc::now() + lifetime).to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    ))
+}
+
+/// Schedule flags shared by `policy-create` and `policy-update`
+#[derive(Debug, Default)]
+pub struct ScheduleArgs<'a> {
+    /// Time of day to take the snapshot, `HH:MM`
+    pub every_day_at: Option<&'a str>,
+    /// Restrict to these days (e.g. `MON,THU`); every day if unset
+    pub on_days: Option<&'a str>,
+    /// Snapshot lifetime (e.g. `7d`, `4w`, `6months`) or `never`
+    pub keep_for: Option<&'a str>,
+    pub timezone: Option<&'a str>,
+}
+
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Create a snapshot policy on the directory at `path`.
+pub fn policy_create(
+    client: &QumuloClient,
+    name: &str,
+    path: &str,
+    schedule: &ScheduleArgs,
+    disabled: bool,
+    json_mode: bool,
+) -> Result<()> {
+    if schedule.every_day_at.is_none() {
+        anyhow::bail!("a schedule is required: pass --every-day-at HH:MM");
+    }
+    let schedule = build_schedule(None, schedule)?;
+    let attrs = client
+        .get_file_attr(path)
+        .with_context(|| format!("failed to stat {}", path))?;
+    let source_id = attrs
+        .get("id")
+        .and_then(|v| v.as_str())
+        .context("unexpected response: missing file id")?;
+
+    let body = serde_json::json!({
+        "policy_name": name,
+        "source_file_id": source_id,
+        "enabled": !disabled,
+        "schedule": schedule,
+    });
+    let policy = client.create_snapshot_policy(&body)?;
+    print_policy_result("Created", &policy, json_mode);
+    Ok(())
+}
+
+/// Change a policy's name, schedule, or enabled state. Schedule flags are
+/// merged into the existing schedule, so `--keep-for` alone keeps the times.
+pub fn policy_update(
+    client: &QumuloClient,
+    id: u64,
+    name: Option<&str>,
+    schedule: &ScheduleArgs,
+    enabled: Option<bool>,
+    json_mode: bool,
+) -> Result<()> {
+    let current = client.get_snapshot_policy(id)?;
+    let mut patch = serde_json::Map::new();
+    if let Some(name) = name {
+        patch.insert("policy_name".into(), Value::String(name.to_string()));
+    }
+    if let Some(enabled) = enabled {
+        patch.insert("enabled".into(), Value::Bool(enabled));
+    }
+    let changes_schedule = schedule.every_day_at.is_some()
+        || schedule.on_days.is_some()
+        || schedule.keep_for.is_some()
+        || schedule.timezone.is_some();
+    if changes_schedule {
+        let merged = build_schedule(current.get("schedule"), schedule)?;
+        patch.insert("schedule".into(), merged);
+    }
+    if patch.is_empty() {
+        anyhow::bail!("nothing to update: pass --name, a schedule flag, --enable, or --disable");
+    }
+
+    let policy = client.update_snapshot_policy(id, &Value::Object(patch))?;
+    print_policy_result("Updated", &policy, json_mode);
+    Ok(())
+}
+
+/// Delete a snapshot policy after confirmation. Snapshots it already took
+/// are kept.
+pub fn policy_delete(client: &QumuloClient, guard: &Guard, id: u64, json_mode: bool) -> Result<()> {
+    let policy = client.get_snapshot_policy(id)?;
+    let name = policy
+        .get("policy_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("-");
+
+    let confirmed = guard.confirm(
+        client,
+        &format!("delete snapshot policy {}", id),
+        &format!(
+            "Delete snapshot policy {} ({})? Existing snapshots are kept.",
+            id, name
+        ),
+    )?;
+    if !confirmed {
+        eprintln!("Aborted.");
+        return Ok(());
+    }
+
+    client.delete_snapshot_policy(id)?;
+    if json_mode {
+        let result = serde_json::json!({"id": id, "policy_name": name, "deleted": true});
+        print_json(&result)?;
+    } else {
+        println!("Deleted snapshot policy {} ({})", id, name);
+    }
+    Ok(())
+}
+
+fn print_policy_result(verb: &str, policy: &Value, json_mode: bool) {
+    if json_mode {
+        print_value(policy, true, |_| {});
+        return;
+    }
+    let id = policy.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+    let name = policy
+        .get("policy_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("-");
+    let enabled = policy
+        .get("enabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    println!("{} snapshot policy {} ({})", verb, id, name);
+    if let Some(schedule) = policy.get("schedule") {
+        println!("  Schedule:    {}", describe_schedule(schedule));
+        let ttl = schedule
+            .get("expiration_time_to_live")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("never");
+        println!("  Keep for:    {}", ttl);
+    }
+    println!(
+        "  Status:      {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
+
+/// "every day at 02:00 UTC", "MON,THU at 02:00 UTC"
+fn describe_schedule(schedule: &Value) -> String {
+    let creation = &schedule["creation_schedule"];
+    let days: Vec<&str> = creation["on_days"]
+        .as_array()
+        .map(|d| d.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let when = if days.len() == 7 {
+        "every day".to_string()
+    } else {
+        days.join(",")
+    };
+    format!(
+        "{} at {:02}:{:02} {}",
+        when,
+        creation["hour"].as_u64().unwrap_or(0),
+        creation["minute"].as_u64().unwrap_or(0),
+        creation["timezone"].as_str().unwrap_or("UTC")
+    )
+}
+
+/// Build a daily-or-weekly schedule from the flags, on top of `current` if
+/// given. Everything is validated before anything is sent to the cluster.
+fn build_schedule(current: Option<&Value>, args: &ScheduleArgs) -> Result<Value> {
+    let mut schedule = current.cloned().unwrap_or_else(|| serde_json::json!({}));
+    let creation = &schedule["creation_schedule"];
+    let frequency = creation["frequency"].as_str();
+    if frequency.is_some_and(|f| f != "SCHEDULE_DAILY_OR_WEEKLY") && args.every_day_at.is_none() {
+        anyhow::bail!(
+            "policy has a {} schedule; pass --every-day-at to replace it",
+            frequency.unwrap_or_default()
+        );
+    }
+
+    let (hour, minute) = match args.every_day_at {
+        Some(at) => parse_time_of_day(at)?,
+        None => (
+            creation["hour"].as_u64().unwrap_or(0) as u32,
+            creation["minute"].as_u64().unwrap_or(0) as u32,
+        ),
+    };
+    let on_days: Vec<String> = match args.on_days {
+        Some(days) => parse_on_days(days)?,
+        None => match creation["on_days"].as_array() {
+            Some(days) if args.every_day_at.is_none() => days
+                .iter()
+                .filter_map(|d| d.as_str().map(String::from))
+                .collect(),
+            _ => WEEKDAYS.iter().map(|d| d.to_string()).collect(),
+        },
+    };
+    let timezone = match args.timezone {
+        Some(tz) if tz.trim().is_empty() => anyhow::bail!("--timezone must not be empty"),
+        Some(tz) => tz.to_string(),
+        None => creation["timezone"].as_str().unwrap_or("UTC").to_string(),
+    };
+
+    schedule["creation_schedule"] = serde_json::json!({
+        "frequency": "SCHEDULE_DAILY_OR_WEEKLY",
+        "hour": hour,
+        "minute": minute,
+        "on_days": on_days,
+        "timezone": timezone,
+    });
+    if let Some(keep) = args.keep_for {
+        schedule["expiration_time_to_live"] = Value::String(parse_keep_for(keep)?);
+    }
+    Ok(schedule)
+}
+
+fn parse_time_of_day(s: &str) -> Result<(u32, u32)> {
+    let invalid = || anyhow::anyhow!("invalid time '{}' (expected HH:MM, e.g. 02:30)", s);
+    let (h, m) = s.trim().split_once(':').ok_or_else(invalid)?;
+    let hour: u32 = h.parse().map_err(|_| invalid())?;
+    let minute: u32 = m.parse().map_err(|_| invalid())?;
+    if hour > 23 || minute > 59 || m.len() != 2 {
+        return Err(invalid());
+    }
+    Ok((hour, minute))
+}
+
+/// `MON,THU` → `["MON", "THU"]`, in week order
+fn parse_on_days(s: &str) -> Result<Vec<String>> {
+    let mut days = Vec::new();
+    for day in s.split(',').map(|d| d.trim().to_ascii_uppercase()) {
+        let Some(pos) = WEEKDAYS.iter().position(|w| *w == day) else {
+            anyhow::bail!("invalid day '{}' (expected e.g. MON,THU)", day);
+        };
+        if !days.contains(&pos) {
+            days.push(pos);
+        }
+    }
+    days.sort();
+    Ok(days.into_iter().map(|d| WEEKDAYS[d].to_string()).collect())
+}
+
+/// `7d` → `7days`; also accepts the API's own `7days` form. `never` (no
+/// expiration) is an empty TTL.
+fn parse_keep_for(s: &str) -> Result<String> {
+    let s = s.trim();
+    if s == "never" {
+        return Ok(String::new());
+    }
+    let invalid = || {
+        anyhow::anyhow!(
+            "invalid --keep-for '{}' (expected e.g. 7d, 4w, 6months, never)",
+            s
+        )
+    };
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (n, unit) = s.split_at(split);
+    let n: u64 = n.parse().map_err(|_| invalid())?;
+    let unit = match unit {
+        "h" | "hour" | "hours" => "hours",
+        "d" | "day" | "days" => "days",
+        "w" | "week" | "weeks" => "weeks",
+        "mo" | "month" | "months" => "months",
+        "y" | "year" | "years" => "years",
+        _ => return Err(invalid()),
+    };
+    if n == 0 {
+        return Err(invalid());
+    }
+    Ok(format!("{}{}", n, unit))
+}
+
+/// How often a snapshot schedule fires, as far as retention baselines care.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleClass {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl ScheduleClass {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hourly" => Some(ScheduleClass::Hourly),
+            "daily" => Some(ScheduleClass::Daily),
+            "weekly" => Some(ScheduleClass::Weekly),
+            "monthly" => Some(ScheduleClass::Monthly),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ScheduleClass::Hourly => "hourly",
+            ScheduleClass::Daily => "daily",
+            ScheduleClass::Weekly => "weekly",
+            ScheduleClass::Monthly => "monthly",
+        }
+    }
+
+    /// Interval between snapshots, in hours
+    fn period_hours(self) -> u64 {
+        match self {
+            ScheduleClass::Hourly => 1,
+            ScheduleClass::Daily => 24,
+            ScheduleClass::Weekly => 24 * 7,
+            ScheduleClass::Monthly => 24 * 30,
+        }
+    }
+}
+
+/// Parse a baseline such as `daily=30,weekly=8` (snapshots kept per schedule).
+pub fn parse_expectations(spec: &str) -> Result<Vec<(ScheduleClass, u64)>> {
+    let mut expect: Vec<(ScheduleClass, u64)> = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (class, keep) = part
+            .split_once('=')
+            .with_context(|| format!("invalid expectation '{}' (expected e.g. daily=30)", part))?;
+        let class = ScheduleClass::parse(class).with_context(|| {
+            format!(
+                "unknown schedule '{}' (expected hourly, daily, weekly, monthly)",
+                class.trim()
+            )
+        })?;
+        let keep: u64 = keep
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid snapshot count '{}'", keep.trim()))?;
+        expect.retain(|(c, _)| *c != class);
+        expect.push((class, keep));
+    }
+    if expect.is_empty() {
+        anyhow::bail!("--expect needs at least one schedule, e.g. daily=30");
+    }
+    Ok(expect)
+}
+
+/// Classify a policy's creation schedule. Daily-or-weekly schedules firing on
+/// every day are daily; on a single day, weekly.
+fn schedule_class(creation: &Value) -> Option<ScheduleClass> {
+    match creation.get("frequency").and_then(|v| v.as_str())? {
+        "SCHEDULE_HOURLY_OR_LESS" => Some(ScheduleClass::Hourly),
+        "SCHEDULE_MONTHLY" => Some(ScheduleClass::Monthly),
+        "SCHEDULE_DAILY_OR_WEEKLY" => {
+            let days = creation
+                .get("on_days")
+                .and_then(|v| v.as_array())
+                .map_or(0, |d| d.len());
+            match days {
+                7 => Some(ScheduleClass::Daily),
+                1 => Some(ScheduleClass::Weekly),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Parse an expiration TTL such as `7days` or `8weeks` into hours.
+/// Empty or missing TTLs mean the snapshots never expire.
+fn ttl_hours(ttl: &str) -> Option<u64> {
+    let ttl = ttl.trim();
+    let split = ttl.find(|c: char| !c.is_ascii_digit())?;
+    let (n, unit) = ttl.split_at(split);
+    let n: u64 = n.parse().ok()?;
+    let per = match unit.trim_end_matches('s') {
+        "hour" => 1,
+        "day" => 24,
+        "week" => 24 * 7,
+        "month" => 24 * 30,
+        "year" => 24 * 365,
+        _ => return None,
+    };
+    Some(n * per)
+}
+
+/// One way a cluster's snapshot policies differ from the baseline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyDrift {
+    pub schedule: ScheduleClass,
+    pub expected: u64,
+    /// Snapshots the best matching policy keeps (None: missing or never expires)
+    pub actual: Option<u64>,
+    pub policy: Option<String>,
+    pub problem: DriftProblem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftProblem {
+    Missing,
+    Disabled,
+    Retention,
+    NeverExpires,
+}
+
+impl PolicyDrift {
+    fn describe(&self) -> String {
+        let schedule = self.schedule.as_str();
+        let policy = self.policy.as_deref().unwrap_or("-");
+        match self.problem {
+            DriftProblem::Missing => {
+                format!(
+                    "{}: no {} policy, expected {}",
+                    schedule, schedule, self.expected
+                )
+            }
+            DriftProblem::Disabled => format!(
+                "{}: policy '{}' is disabled, expected {}",
+                schedule, policy, self.expected
+            ),
+            DriftProblem::Retention => format!(
+                "{}: keeps {} (policy '{}'), expected {}",
+                schedule,
+                self.actual.unwrap_or(0),
+                policy,
+                self.expected
+            ),
+            DriftProblem::NeverExpires => format!(
+                "{}: policy '{}' never expires, expected {}",
+                schedule, policy, self.expected
+            ),
+        }
+    }
+}
+
+/// Compare a cluster's snapshot policies against the expected retention per
+/// schedule. For each schedule the enabled policy closest to the baseline is
+/// judged, so an extra policy with other retention is not drift by itself.
+pub fn policy_drift(policies: &Value, expect: &[(ScheduleClass, u64)]) -> Vec<PolicyDrift> {
+    // (class, name, enabled, snapshots kept; None = never expires)
+    let mut found: Vec<(ScheduleClass, String, bool, Option<u64>)> = Vec::new();
+    let entries = policies
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for entry in entries {
+        let name = entry
+            .get("policy_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("-")
+            .to_string();
+        let enabled = entry
+            .get("enabled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let schedules = match entry.get("schedules").and_then(|v| v.as_array()) {
+            Some(list) => list.iter().collect(),
+            None => entry.get("schedule").into_iter().collect::<Vec<_>>(),
+        };
+        for schedule in schedules {
+            let Some(class) = schedule.get("creation_schedule").and_then(schedule_class) else {
+                continue;
+            };
+            let kept = schedule
+                .get("expiration_time_to_live")
+                .and_then(|v| v.as_str())
+                .and_then(ttl_hours)
+                .map(|h| (h + class.period_hours() / 2) / class.period_hours());
+            found.push((class, name.clone(), enabled, kept));
+        }
+    }
+
+    let mut drift = Vec::new();
+    for &(class, expected) in expect {
+        let candidates: Vec<_> = found.iter().filter(|(c, ..)| *c == class).collect();
+        let enabled: Vec<_> = candidates.iter().filter(|(_, _, e, _)| *e).collect();
+        let make = |policy: Option<&String>, actual, problem| PolicyDrift {
+            schedule: class,
+            expected,
+            actual,
+            policy: policy.cloned(),
+            problem,
+        };
+
+        if enabled.is_empty() {
+            drift.push(match candidates.first() {
+                Some((_, name, _, kept)) => make(Some(name), *kept, DriftProblem::Disabled),
+                None => make(None, None, DriftProblem::Missing),
+            });
+            continue;
+        }
+        if enabled.iter().any(|(.., kept)| *kept == Some(expected)) {
+            continue;
+        }
+        let closest = enabled
+            .iter()
+            .min_by_key(|(.., kept)| kept.map_or(u64::MAX, |k| k.abs_diff(expected)))
+            .unwrap();
+        drift.push(match closest.3 {
+            Some(kept) => make(Some(&closest.1), Some(kept), DriftProblem::Retention),
+            None => make(Some(&closest.1), None, DriftProblem::NeverExpires),
+        });
+    }
+    drift
+}
+
+#[derive(Debug, Serialize)]
+struct ClusterPolicyResult {
+    cluster: String,
+    policy_count: usize,
+    drift: Vec<PolicyDrift>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Check snapshot policies across the fleet against an expected retention
+/// baseline, e.g. `daily=30,weekly=8`. Exits non-zero on drift or errors.
+pub fn fleet_policy_check(
+    config: &Config,
+    profile_filters: &[String],
+    expect: &str,
+    timeout_secs: u64,
+    json_mode: bool,
+) -> Result<()> {
+    let expect = parse_expectations(expect)?;
+    let profiles = config.select_profiles(profile_filters);
+
+    if profiles.is_empty() {
+        anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
+    }
+
+    let results: Vec<ClusterPolicyResult> = std::thread::scope(|s| {
+        let handles: Vec<_> = profiles
+            .iter()
+            .map(|(name, entry)| {
+                let expect = &expect;
+                s.spawn(move || {
+                    let policies = QumuloClient::new(entry, timeout_secs, None)
+                        .and_then(|client| client.get_snapshot_policies());
+                    match policies {
+                        Ok(policies) => ClusterPolicyResult {
+                            cluster: name.clone(),
+                            policy_count: policies
+                                .get("entries")
+                                .and_then(|v| v.as_array())
+                                .map_or(0, |e| e.len()),
+                            drift: policy_drift(&policies, expect),
+                            error: None,
+                        },
+                        Err(e) => ClusterPolicyResult {
+                            cluster: name.clone(),
+                            policy_count: 0,
+                            drift: Vec::new(),
+                            error: Some(format!("{:#}", e)),
+                        },
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| {
+                h.join().unwrap_or_else(|_| ClusterPolicyResult {
+                    cluster: "unknown".to_string(),
+                    policy_count: 0,
+                    drift: Vec::new(),
+                    error: Some("thread panicked".to_string()),
+                })
+            })
+            .collect()
+    });
+
+    let any_drift = results.iter().any(|r| !r.drift.is_empty());
+    let any_errors = results.iter().any(|r| r.error.is_some());
+
+    if json_mode {
+        print_json(&results)?;
+    } else {
+        let green = Style::new().green();
+        let red = Style::new().red();
+        let yellow = Style::new().yellow();
+        let bold = Style::new().bold();
+
+        println!(
+            "{:<20} {:>8}  {}",
+            bold.apply_to("CLUSTER"),
+            bold.apply_to("POLICIES"),
+            bold.apply_to("STATUS"),
+        );
+        println!("{}", "-".repeat(60));
+        for r in &results {
+            let status = match (&r.error, r.drift.len()) {
+                (Some(err), _) => yellow.apply_to(format!("error: {}", err)),
+                (None, 0) => green.apply_to("✓ matches baseline".to_string()),
+                (None, n) => red.apply_to(format!("✗ {} drift", n)),
+            };
+            println!("{:<20} {:>8}  {}", r.cluster, r.policy_count, status);
+        }
+
+        if any_drift {
+            println!();
+            println!("{}", bold.apply_to("Drift:"));
+            for r in &results {
+                for d in &r.drift {
+                    println!("  {}  {}", r.cluster, red.apply_to(d.describe()));
+                }
+            }
+        }
+    }
+
+    if any_drift || any_errors {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -454,4 +1575,199 @@ mod tests {
     fn test_format_bytes_invalid() {
         assert_eq!(format_bytes("not_a_number"), "0 B");
     }
+
+    fn policy(name: &str, enabled: bool, days: &[&str], ttl: &str) -> Value {
+        serde_json::json!({
+            "policy_name": name,
+            "enabled": enabled,
+            "schedule": {
+                "creation_schedule": {
+                    "frequency": "SCHEDULE_DAILY_OR_WEEKLY",
+                    "on_days": days,
+                },
+                "expiration_time_to_live": ttl,
+            },
+        })
+    }
+
+    const EVERY_DAY: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+    #[test]
+    fn test_schedule_flag_parsing() {
+        assert_eq!(parse_time_of_day("02:30").unwrap(), (2, 30));
+        assert!(parse_time_of_day("24:00").is_err());
+        assert!(parse_time_of_day("2:3").is_err());
+        assert!(parse_time_of_day("0230").is_err());
+
+        assert_eq!(parse_on_days("thu, MON,mon").unwrap(), ["MON", "THU"]);
+        assert!(parse_on_days("MONDAY").is_err());
+
+        assert_eq!(parse_keep_for("7d").unwrap(), "7days");
+        assert_eq!(parse_keep_for("6months").unwrap(), "6months");
+        assert_eq!(parse_keep_for("4weeks").unwrap(), "4weeks");
+        assert_eq!(parse_keep_for("never").unwrap(), "");
+        assert!(parse_keep_for("0d").is_err());
+        assert!(parse_keep_for("7").is_err());
+        assert!(parse_keep_for("7x").is_err());
+    }
+
+    #[test]
+    fn test_build_schedule_merges_with_current() {
+        let current = serde_json::json!({
+            "creation_schedule": {
+                "frequency": "SCHEDULE_DAILY_OR_WEEKLY",
+                "hour": 8,
+                "minute": 0,
+                "on_days": ["MON"],
+                "timezone": "America/Denver"
+            },
+            "expiration_time_to_live": "7days",
+            "id": 1
+        });
+        let args = ScheduleArgs {
+            keep_for: Some("4w"),
+            ..Default::default()
+        };
+        let merged = build_schedule(Some(&current), &args).unwrap();
+        assert_eq!(merged["creation_schedule"], current["creation_schedule"]);
+        assert_eq!(merged["expiration_time_to_live"], "4weeks");
+        assert_eq!(merged["id"], 1);
+
+        // A new time without --on-days means every day
+        let args = ScheduleArgs {
+            every_day_at: Some("23:15"),
+            ..Default::default()
+        };
+        let merged = build_schedule(Some(&current), &args).unwrap();
+        assert_eq!(merged["creation_schedule"]["hour"], 23);
+        assert_eq!(
+            merged["creation_schedule"]["on_days"]
+                .as_array()
+                .unwrap()
+                .len(),
+            7
+        );
+        assert_eq!(merged["creation_schedule"]["timezone"], "America/Denver");
+
+        let hourly =
+            serde_json::json!({"creation_schedule": {"frequency": "SCHEDULE_HOURLY_OR_LESS"}});
+        let args = ScheduleArgs {
+            keep_for: Some("1d"),
+            ..Default::default()
+        };
+        assert!(build_schedule(Some(&hourly), &args).is_err());
+    }
+
+    #[test]
+    fn test_parse_expectations() {
+        assert_eq!(
+            parse_expectations("daily=30, weekly=8").unwrap(),
+            vec![(ScheduleClass::Daily, 30), (ScheduleClass::Weekly, 8)]
+        );
+        assert!(parse_expectations("yearly=1").is_err());
+        assert!(parse_expectations("daily").is_err());
+        assert!(parse_expectations("").is_err());
+    }
+
+    #[test]
+    fn test_ttl_hours() {
+        assert_eq!(ttl_hours("7days"), Some(168));
+        assert_eq!(ttl_hours("8weeks"), Some(8 * 168));
+        assert_eq!(ttl_hours("1month"), Some(720));
+        assert_eq!(ttl_hours(""), None);
+    }
+
+    #[test]
+    fn test_policy_drift_matches_baseline() {
+        let policies = serde_json::json!({"entries": [
+            policy("daily", true, &EVERY_DAY, "30days"),
+            policy("weekly", true, &["SUN"], "8weeks"),
+        ]});
+        let expect = parse_expectations("daily=30,weekly=8").unwrap();
+        assert!(policy_drift(&policies, &expect).is_empty());
+    }
+
+    #[test]
+    fn test_policy_drift_reports_each_problem() {
+        let policies = serde_json::json!({"entries": [
+            policy("daily-root", true, &EVERY_DAY, "7days"),
+            policy("weekly", false, &["SUN"], "8weeks"),
+            policy("weekdays", true, &["MON", "WED"], ""),
+        ]});
+        let expect = parse_expectations("daily=30,weekly=8,hourly=24").unwrap();
+        let drift = policy_drift(&policies, &expect);
+        assert_eq!(drift.len(), 3);
+        assert_eq!(drift[0].problem, DriftProblem::Retention);
+        assert_eq!(drift[0].actual, Some(7));
+        assert_eq!(
+            drift[0].describe(),
+            "daily: keeps 7 (policy 'daily-root'), expected 30"
+        );
+        assert_eq!(drift[1].problem, DriftProblem::Disabled);
+        assert_eq!(drift[2].problem, DriftProblem::Missing);
+    }
+
+    #[test]
+    fn test_successors_follow_each_directory() {
+        let entries = vec![
+            serde_json::json!({"id": 7, "source_file_id": "2"}),
+            serde_json::json!({"id": 3, "source_file_id": "2"}),
+            serde_json::json!({"id": 5, "source_file_id": "9"}),
+            serde_json::json!({"id": 6, "source_file_id": "2", "in_delete": true}),
+            serde_json::json!({"id": 8, "source_file_id": "9"}),
+        ];
+        let pairs: Vec<(u64, Option<u64>)> = successors(&entries)
+            .into_iter()
+            .map(|(snap, next)| (snap["id"].as_u64().unwrap(), next))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![(3, Some(7)), (5, Some(8)), (7, None), (8, None)]
+        );
+    }
+
+    #[test]
+    fn test_is_under() {
+        assert!(is_under("/projects/beta/a.txt", "/projects"));
+        assert!(is_under("/projects", "/projects"));
+        assert!(!is_under("/projects-old/a.txt", "/projects"));
+        assert!(is_under("/anything", ""));
+    }
+
+    #[test]
+    fn test_snapshot_filter_matches() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let adhoc_old = serde_json::json!({
+            "name": "adhoc-1", "timestamp": "2026-01-01T00:00:00Z", "created_by_policy": false
+        });
+        let adhoc_new = serde_json::json!({
+            "name": "adhoc-2", "timestamp": "2026-02-28T00:00:00Z", "created_by_policy": false
+        });
+        let daily = serde_json::json!({
+            "name": "daily", "timestamp": "2026-01-01T00:00:00Z", "created_by_policy": true
+        });
+        let all = [&adhoc_old, &adhoc_new, &daily];
+        let matching = |filter: &SnapshotFilter| {
+            all.iter()
+                .filter(|e| filter.matches(e, now))
+                .map(|e| e["name"].as_str().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let empty = SnapshotFilter::default();
+        assert!(empty.is_empty());
+        assert_eq!(matching(&empty).len(), 3);
+        let f = SnapshotFilter::parse(Some("adhoc-*"), Some("30d"), None).unwrap();
+        assert_eq!(matching(&f), ["adhoc-1"]);
+        let f = SnapshotFilter::parse(None, None, Some("policy")).unwrap();
+        assert_eq!(matching(&f), ["daily"]);
+        let f = SnapshotFilter::parse(None, None, Some("manual")).unwrap();
+        assert_eq!(matching(&f), ["adhoc-1", "adhoc-2"]);
+
+        let err = SnapshotFilter::parse(None, None, Some("cron")).unwrap_err();
+        assert!(err.to_string().contains("expected policy or manual"));
+        assert!(SnapshotFilter::parse(None, Some("old"), None).is_err());
+    }
 }
@@ -98,6 +98,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 50,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 1,
                 online: 1,
@@ -118,6 +120,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         }
     }
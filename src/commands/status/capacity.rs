@@ -1,6 +1,7 @@
 use serde_json::Value;
 
 use super::types::{CapacityProjection, ClusterType, ProjectionConfidence};
+use crate::config::{Projection, ProjectionModel};
 
 /// Minimum number of data points required to compute a projection.
 const MIN_DATA_POINTS: usize = 7;
@@ -8,11 +9,17 @@ const MIN_DATA_POINTS: usize = 7;
 /// R-squared threshold below which confidence is marked as "low".
 const LOW_CONFIDENCE_R_SQUARED: f64 = 0.5;
 
+/// Smoothing weight of the newest day in the level estimate.
+const SMOOTHING_LEVEL: f64 = 0.5;
+
+/// Smoothing weight of the newest day-over-day change in the trend.
+const SMOOTHING_TREND: f64 = 0.3;
+
 /// On-prem: warn if days-to-full < 90.
-const ONPREM_WARN_DAYS: u64 = 90;
+pub(crate) const ONPREM_WARN_DAYS: u64 = 90;
 
 /// Cloud: warn if days-to-full < 7.
-const CLOUD_WARN_DAYS: u64 = 7;
+pub(crate) const CLOUD_WARN_DAYS: u64 = 7;
 
 /// Result of a linear regression: y = slope * x + intercept.
 #[derive(Debug, Clone)]
@@ -86,6 +93,14 @@ pub fn parse_capacity_history(history: &Value) -> Vec<(f64, f64)> {
         .collect()
 }
 
+/// Used bytes for each entry of capacity history, oldest first.
+pub fn daily_used(history: &Value) -> Vec<u64> {
+    parse_capacity_history(history)
+        .into_iter()
+        .map(|(_, used)| used as u64)
+        .collect()
+}
+
 /// Extract total_usable from the first entry of capacity history.
 #[allow(dead_code)] // Public utility, used in tests
 pub fn parse_total_usable(history: &Value) -> Option<u64> {
@@ -95,7 +110,7 @@ pub fn parse_total_usable(history: &Value) -> Option<u64> {
         .and_then(|entry| parse_byte_str(&entry["total_usable"]))
 }
 
-/// Compute a capacity projection from history data.
+/// Compute a linear capacity projection from all of `history`.
 ///
 /// Returns None if:
 /// - Fewer than MIN_DATA_POINTS history entries
@@ -104,16 +119,50 @@ pub fn compute_projection(
     history: &Value,
     current_used: u64,
     total_capacity: u64,
+) -> Option<CapacityProjection> {
+    project(
+        &parse_capacity_history(history),
+        current_used,
+        total_capacity,
+        Projection::default(),
+    )
+}
+
+/// Compute a capacity projection with the configured model, fitted to the
+/// newest `projection.window` days of `history`. None under the same
+/// conditions as [`compute_projection`].
+pub fn compute_projection_with(
+    history: &Value,
+    current_used: u64,
+    total_capacity: u64,
+    projection: Projection,
 ) -> Option<CapacityProjection> {
     let points = parse_capacity_history(history);
+    let start = points
+        .len()
+        .saturating_sub(projection.window.days() as usize);
+    project(&points[start..], current_used, total_capacity, projection)
+}
+
+fn project(
+    points: &[(f64, f64)],
+    current_used: u64,
+    total_capacity: u64,
+    projection: Projection,
+) -> Option<CapacityProjection> {
     if points.len() < MIN_DATA_POINTS {
         return None;
     }
 
-    let regression = linear_regression(&points)?;
-
-    // Slope is in bytes per day_index (each index = 1 day)
-    let daily_growth = regression.slope;
+    // Growth in bytes per day_index (each index = 1 day), and how well the
+    // model fits the history
+    let (daily_growth, fit) = match projection.model {
+        ProjectionModel::Linear => {
+            let regression = linear_regression(points)?;
+            (regression.slope, regression.r_squared)
+        }
+        ProjectionModel::Smoothing => smoothed_trend(points)?,
+    };
 
     if daily_growth <= 0.0 {
         return None;
@@ -122,7 +171,7 @@ pub fn compute_projection(
     let remaining = total_capacity as f64 - current_used as f64;
     let days_to_full = (remaining / daily_growth).ceil() as u64;
 
-    let confidence = if regression.r_squared < LOW_CONFIDENCE_R_SQUARED {
+    let confidence = if fit < LOW_CONFIDENCE_R_SQUARED {
         ProjectionConfidence::Low
     } else {
         ProjectionConfidence::High
@@ -132,9 +181,39 @@ pub fn compute_projection(
         days_until_full: Some(days_to_full),
         growth_rate_bytes_per_day: daily_growth,
         confidence,
+        model: projection.model,
+        window_days: projection.window.days(),
     })
 }
 
+/// Holt's double exponential smoothing over (day_index, used) pairs.
+/// Returns the final trend (bytes per day) and the R-squared of its
+/// one-day-ahead forecasts, comparable to the linear fit's. None with
+/// fewer than 2 points.
+fn smoothed_trend(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let (first, rest) = points.split_first()?;
+    let second = rest.first()?;
+    let mut level = first.1;
+    let mut trend = second.1 - first.1;
+    let mut ss_res = 0.0;
+    for &(_, y) in rest {
+        let forecast = level + trend;
+        ss_res += (y - forecast).powi(2);
+        let next_level = SMOOTHING_LEVEL * y + (1.0 - SMOOTHING_LEVEL) * forecast;
+        trend = SMOOTHING_TREND * (next_level - level) + (1.0 - SMOOTHING_TREND) * trend;
+        level = next_level;
+    }
+
+    let mean_y = rest.iter().map(|(_, y)| y).sum::<f64>() / rest.len() as f64;
+    let ss_tot: f64 = rest.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let fit = if ss_tot.abs() < f64::EPSILON {
+        1.0
+    } else {
+        1.0 - ss_res / ss_tot
+    };
+    Some((trend, fit))
+}
+
 /// Check whether a projection should trigger a warning alert.
 pub fn should_warn(projection: &CapacityProjection, cluster_type: &ClusterType) -> bool {
     match projection.days_until_full {
@@ -181,6 +260,7 @@ fn parse_byte_str(val: &Value) -> Option<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ProjectionWindow;
 
     // ─── Linear regression tests ───────────────────────────────────
 
@@ -363,6 +443,8 @@ mod tests {
             days_until_full: Some(80),
             growth_rate_bytes_per_day: 1e12,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         assert!(should_warn(
             &proj,
@@ -376,6 +458,8 @@ mod tests {
             days_until_full: Some(100),
             growth_rate_bytes_per_day: 1e12,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         assert!(!should_warn(
             &proj,
@@ -389,6 +473,8 @@ mod tests {
             days_until_full: Some(5),
             growth_rate_bytes_per_day: 1e12,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         assert!(should_warn(&proj, &ClusterType::CnqAws));
         assert!(should_warn(&proj, &ClusterType::AnqAzure));
@@ -400,6 +486,8 @@ mod tests {
             days_until_full: Some(10),
             growth_rate_bytes_per_day: 1e12,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         assert!(!should_warn(&proj, &ClusterType::CnqAws));
         assert!(!should_warn(&proj, &ClusterType::AnqAzure));
@@ -411,6 +499,8 @@ mod tests {
             days_until_full: None,
             growth_rate_bytes_per_day: 0.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         assert!(!should_warn(&proj, &ClusterType::OnPrem(vec![])));
     }
@@ -440,6 +530,89 @@ mod tests {
         assert!(days > 950 && days < 1050, "days_to_full={}", days);
     }
 
+    // ─── Model and window tests ────────────────────────────────────
+
+    fn history_of(used_tb: &[u64]) -> Value {
+        let tb = 1_099_511_627_776_u64;
+        Value::Array(
+            used_tb
+                .iter()
+                .enumerate()
+                .map(|(i, &used)| {
+                    serde_json::json!({
+                        "capacity_used": (used * tb).to_string(),
+                        "total_usable": (1000 * tb).to_string(),
+                        "period_start_time": 1768089600 + i as i64 * 86400,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_smoothing_follows_recent_growth() {
+        // Flat for 20 days, then 2 TB/day
+        let tb = 1_099_511_627_776_f64;
+        let used: Vec<u64> = (0..30).map(|i| 100 + 2 * (i.max(20) - 20)).collect();
+        let history = history_of(&used);
+        let linear = compute_projection(&history, 118 << 40, 1000 << 40).unwrap();
+        let smoothed = compute_projection_with(
+            &history,
+            118 << 40,
+            1000 << 40,
+            Projection {
+                model: ProjectionModel::Smoothing,
+                window: ProjectionWindow::Month,
+            },
+        )
+        .unwrap();
+
+        assert!(linear.growth_rate_bytes_per_day < 1.5 * tb);
+        let rate = smoothed.growth_rate_bytes_per_day / tb;
+        assert!((1.8..=2.2).contains(&rate), "smoothed rate {}", rate);
+        assert!(smoothed.days_until_full.unwrap() < linear.days_until_full.unwrap());
+        assert_eq!(smoothed.model, ProjectionModel::Smoothing);
+        assert_eq!(smoothed.window_days, 30);
+    }
+
+    #[test]
+    fn test_smoothed_trend_steady_growth() {
+        let points: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, 5.0 * i as f64)).collect();
+        let (trend, fit) = smoothed_trend(&points).unwrap();
+        assert!((trend - 5.0).abs() < 1e-9);
+        assert!((fit - 1.0).abs() < 1e-9);
+        assert!(smoothed_trend(&points[..1]).is_none());
+    }
+
+    #[test]
+    fn test_projection_window_uses_newest_days() {
+        // 5 TB/day for 60 days, then 1 TB/day for 30
+        let used: Vec<u64> = (0..90)
+            .map(|i| if i < 60 { 5 * i } else { 300 + (i - 60) })
+            .collect();
+        let history = history_of(&used);
+        let with = |window| {
+            compute_projection_with(
+                &history,
+                329 << 40,
+                1000 << 40,
+                Projection {
+                    model: ProjectionModel::Linear,
+                    window,
+                },
+            )
+            .unwrap()
+        };
+        let tb = 1_099_511_627_776_f64;
+        let month = with(ProjectionWindow::Month);
+        assert!((month.growth_rate_bytes_per_day / tb - 1.0).abs() < 0.01);
+        let quarter = with(ProjectionWindow::Quarter);
+        assert!(quarter.growth_rate_bytes_per_day / tb > 3.0);
+        assert_eq!(quarter.window_days, 90);
+        // A week is still enough history to project
+        assert!(with(ProjectionWindow::Week).days_until_full.is_some());
+    }
+
     // ─── Parse tests ───────────────────────────────────────────────
 
     #[test]
@@ -486,6 +659,8 @@ mod tests {
             days_until_full: Some(62),
             growth_rate_bytes_per_day: 1.2 * 1_099_511_627_776.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         let msg = format_warning(&proj, &ClusterType::OnPrem(vec!["C192T".into()]));
         assert!(msg.contains("62 days"));
@@ -498,6 +673,8 @@ mod tests {
             days_until_full: Some(5),
             growth_rate_bytes_per_day: 1e12,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         };
         let msg = format_warning(&proj, &ClusterType::CnqAws);
         assert!(msg.contains("5 days"));
@@ -1,33 +1,89 @@
+use std::collections::BTreeSet;
 use std::io::IsTerminal;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use serde_json::Value;
 
 use crate::cache::DiskCache;
-use crate::client::QumuloClient;
-use crate::config::{Config, ProfileEntry};
+use crate::client::{AggregatesBudget, QumuloClient};
+use crate::config::{
+    CollectConfig, CollectLevel, Config, ProfileEntry, Projection, ProjectionModel,
+    ProjectionWindow,
+};
+use crate::error::QontrolError;
+use crate::progress::{self, Tracker};
+use crate::subnet::{SubnetLabels, UNLABELED};
 
 use super::cache;
 use super::capacity;
 use super::detection::detect_cluster_type;
 use super::health;
+use super::rules;
 use super::timing::{ApiCallTiming, TimingReport};
 use super::types::*;
 
+/// Per-node connection counts, keyed by node id
+type ConnectionsMap = std::collections::HashMap<u64, NodeConnections>;
+
+/// Connection counts for one node
+#[derive(Debug, Clone, Default)]
+struct NodeConnections {
+    total: u32,
+    by_protocol: std::collections::HashMap<String, u32>,
+    by_version: std::collections::HashMap<String, u32>,
+    by_subnet: std::collections::HashMap<String, u32>,
+}
+
 /// Per-node NIC stats: (throughput_bps, link_speed_bps, utilization_pct, raw_bytes_total)
 type NicStatsMap =
     std::collections::HashMap<u64, (Option<u64>, Option<u64>, Option<f64>, Option<u64>)>;
 
+/// Endpoint groups a profile can exclude with `collect.skip`. Settings, version,
+/// and nodes are always fetched since reachability and health depend on them.
+pub const SKIPPABLE_ENDPOINTS: &[&str] = &[
+    "activity",
+    "recursive_aggregates",
+    "snapshots",
+    "network",
+    "capacity_history",
+    "disks",
+    "psus",
+    "protection",
+    "restriper",
+    "ssd_endurance",
+    "directory_services",
+];
+
+/// How many of the largest paths to keep from the capacity analytics.
+const LARGEST_PATHS_LIMIT: usize = 5;
+
+/// How many of the largest directories to keep from the
+/// `collect.aggregates_max_depth` walk.
+const LARGEST_DIRECTORIES_LIMIT: usize = 5;
+
+/// How many paths and clients to keep for `--top-activity`.
+const TOP_ACTIVITY_LIMIT: usize = 5;
+
+/// Days of daily usage kept for the capacity sparkline, whatever window the
+/// projection is fitted to.
+pub const TREND_DAYS: u32 = 30;
+
+/// Default bound on concurrent API call groups across the fleet (`--jobs`).
+pub const DEFAULT_JOBS: usize = 16;
+
 /// Create a MultiProgress with one spinner per cluster for progress display.
-/// Returns None if progress display should be skipped (non-TTY, json mode).
+/// Returns None if progress display should be skipped (non-TTY, json mode,
+/// `--progress json|none`).
 fn create_progress_spinners(
     profile_names: &[(String, ProfileEntry)],
     json_mode: bool,
     suppress: bool,
 ) -> Option<(MultiProgress, Vec<ProgressBar>)> {
-    if json_mode || suppress || !std::io::stderr().is_terminal() {
+    if json_mode || suppress || !progress::interactive() || !std::io::stderr().is_terminal() {
         return None;
     }
 
@@ -50,40 +106,85 @@ fn create_progress_spinners(
     Some((mp, spinners))
 }
 
-/// Collect status from all configured clusters (or a filtered subset) in parallel.
-/// When `watch_mode` is true, NIC stats use a single call (no 1-second sleep)
-/// and return raw byte counters for inter-poll delta computation.
-/// When `json_mode` is true (or stdout is not a TTY), progress spinners are suppressed.
-/// When `record_timing` is true, returns a TimingReport with per-API-call durations.
-#[allow(clippy::too_many_arguments)]
+/// How [`collect_all`] polls the fleet. Start from [`CollectOptions::new`]
+/// and override fields with struct update syntax.
+#[derive(Debug, Clone, Copy)]
+pub struct CollectOptions<'a> {
+    /// Overrides each profile's `collect.level` (default: standard)
+    pub level: Option<CollectLevel>,
+    /// Override each profile's `collect.projection_*`
+    pub projection_model: Option<ProjectionModel>,
+    pub projection_window: Option<ProjectionWindow>,
+    /// Fetch each cluster's hottest paths and clients
+    pub top_activity: bool,
+    pub timeout_secs: u64,
+    /// Don't read or write the API response cache or the status cache
+    pub no_cache: bool,
+    /// NIC stats use a single call (no 1-second sleep) and return raw byte
+    /// counters for inter-poll delta computation
+    pub watch_mode: bool,
+    /// The last poll, for incremental watch polls: clusters that were
+    /// reachable on it only re-fetch volatile endpoints; see `refresh_cluster`
+    pub previous: Option<&'a EnvironmentStatus>,
+    /// Suppresses progress spinners (as does stdout not being a TTY)
+    pub json_mode: bool,
+    /// Return a TimingReport with per-API-call durations
+    pub record_timing: bool,
+    pub suppress_progress: bool,
+    /// At most this many endpoint groups are in flight at once across all
+    /// clusters
+    pub jobs: usize,
+    /// A cluster still collecting once this runs out skips its remaining
+    /// optional endpoints and is marked partial
+    pub cluster_budget: Option<Duration>,
+}
+
+impl CollectOptions<'_> {
+    /// A one-off poll at the profiles' own levels, with progress spinners
+    /// and [`DEFAULT_JOBS`]
+    pub fn new(timeout_secs: u64) -> Self {
+        CollectOptions {
+            level: None,
+            projection_model: None,
+            projection_window: None,
+            top_activity: false,
+            timeout_secs,
+            no_cache: false,
+            watch_mode: false,
+            previous: None,
+            json_mode: false,
+            record_timing: false,
+            suppress_progress: false,
+            jobs: DEFAULT_JOBS,
+            cluster_budget: None,
+        }
+    }
+}
+
+/// Collect status from all configured clusters (or a filtered subset) in
+/// parallel, as `opts` describes.
 pub fn collect_all(
     config: &Config,
     profile_filters: &[String],
-    timeout_secs: u64,
-    no_cache: bool,
-    watch_mode: bool,
-    json_mode: bool,
-    record_timing: bool,
-    suppress_progress: bool,
+    opts: CollectOptions,
 ) -> Result<(EnvironmentStatus, Option<TimingReport>)> {
+    let CollectOptions {
+        level,
+        projection_model,
+        projection_window,
+        top_activity,
+        timeout_secs,
+        no_cache,
+        watch_mode,
+        previous,
+        json_mode,
+        record_timing,
+        suppress_progress,
+        jobs,
+        cluster_budget,
+    } = opts;
     // Determine which profiles to query
-    let profiles: Vec<(String, ProfileEntry)> = if profile_filters.is_empty() {
-        config
-            .profiles
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect()
-    } else {
-        profile_filters
-            .iter()
-            .filter_map(|name| {
-                config
-                    .profiles
-                    .get(name)
-                    .map(|entry| (name.clone(), entry.clone()))
-            })
-            .collect()
-    };
+    let profiles = config.select_profiles(profile_filters);
 
     if profiles.is_empty() {
         anyhow::bail!("no matching profiles found — add profiles with `qontrol profile add`");
@@ -91,15 +192,30 @@ pub fn collect_all(
 
     // Set up progress spinners (skipped for non-TTY / json mode / subsequent watch polls)
     let progress = create_progress_spinners(&profiles, json_mode, suppress_progress);
-
-    // Spawn one thread per cluster for parallel collection
+    let tracker = Tracker::start("collect", Some(profiles.len() as u64), false);
+    let tracker = &tracker;
+
+    // Spawn one thread per cluster for parallel collection; each fans its
+    // endpoint groups out further, sharing one fleet-wide request limit
+    let labels = SubnetLabels::new(&config.subnets)?;
+    let labels = &labels;
+    let limiter = Limiter::new(jobs);
+    let limiter = &limiter;
     let results: Vec<(ClusterResult, Vec<ApiCallTiming>, u64)> = std::thread::scope(|s| {
         let handles: Vec<_> = profiles
             .iter()
             .enumerate()
             .map(|(idx, (name, entry))| {
+                let previous = previous
+                    .and_then(|p| p.clusters.iter().find(|c| &c.profile == name))
+                    .filter(|c| c.reachable && !c.stale)
+                    .cloned();
                 let name = name.clone();
                 let entry = entry.clone();
+                let level = level.or(entry.collect.level).unwrap_or_default();
+                let projection = entry
+                    .collect
+                    .projection(projection_model, projection_window);
                 let spinner = progress.as_ref().map(|(_, spinners)| spinners[idx].clone());
                 s.spawn(move || {
                     let on_progress = |msg: &str| {
@@ -108,16 +224,40 @@ pub fn collect_all(
                         }
                     };
                     let wall_start = Instant::now();
-                    let (result, call_timings) = collect_cluster(
-                        &name,
-                        &entry,
-                        timeout_secs,
-                        watch_mode,
-                        no_cache,
-                        &on_progress,
-                        record_timing,
-                    );
+                    let deadline = cluster_budget.map(|budget| wall_start + budget);
+                    let (result, call_timings) = match previous {
+                        Some(ref prev) => refresh_cluster(
+                            &name,
+                            &entry,
+                            prev,
+                            level,
+                            top_activity,
+                            timeout_secs,
+                            no_cache,
+                            &on_progress,
+                            record_timing,
+                            limiter,
+                            labels,
+                            deadline,
+                        ),
+                        None => collect_cluster(
+                            &name,
+                            &entry,
+                            level,
+                            projection,
+                            top_activity,
+                            timeout_secs,
+                            watch_mode,
+                            no_cache,
+                            &on_progress,
+                            record_timing,
+                            limiter,
+                            labels,
+                            deadline,
+                        ),
+                    };
                     let wall_ms = wall_start.elapsed().as_millis() as u64;
+                    tracker.advance(0, Some(name.as_str()));
                     // Finish spinner based on result
                     if let Some(ref pb) = spinner {
                         match &result {
@@ -243,13 +383,28 @@ pub fn collect_all(
     // Build aggregates
     let aggregates = build_aggregates(&clusters);
 
-    // Generate prioritized, sorted alerts via the alerts engine
-    let alerts = health::generate_alerts(&clusters, connectivity_alerts);
+    // Generate prioritized, sorted alerts via the alerts engine, alongside
+    // any site-defined rules
+    let mut prebuilt = connectivity_alerts;
+    prebuilt.extend(rules::evaluate(
+        &rules::from_config(&config.alert_rules)?,
+        &clusters,
+    ));
+    let mut alerts = health::generate_alerts(&clusters, prebuilt);
+    let now = chrono::Utc::now();
+    let in_maintenance = config.profiles_in_maintenance(now);
+    health::apply_maintenance(&mut alerts, &clusters, &in_maintenance);
+    let acknowledged = health::take_acknowledged(
+        &mut alerts,
+        &clusters,
+        &crate::commands::alert::active_acks(now),
+    );
 
     Ok((
         EnvironmentStatus {
             aggregates,
             alerts,
+            acknowledged,
             clusters,
         },
         timing_report,
@@ -259,111 +414,82 @@ pub fn collect_all(
 /// Collect status from a single cluster. Returns a ClusterResult and timing entries.
 /// The `on_progress` callback is invoked with a message describing the current API call.
 /// When `record_timing` is true, each API call group is timed and returned.
+///
+/// Settings, version, and nodes are fetched in order since everything else
+/// depends on them; the optional endpoint groups then run concurrently, each
+/// holding a `limiter` permit while its requests are in flight. Once
+/// `deadline` passes, optional requests not yet sent are abandoned.
+#[allow(clippy::too_many_arguments)]
 fn collect_cluster(
     profile: &str,
     entry: &ProfileEntry,
+    level: CollectLevel,
+    projection: Projection,
+    top_activity: bool,
     timeout_secs: u64,
     watch_mode: bool,
     no_cache: bool,
     on_progress: &dyn Fn(&str),
     record_timing: bool,
+    limiter: &Limiter,
+    labels: &SubnetLabels,
+    deadline: Option<Instant>,
 ) -> (ClusterResult, Vec<ApiCallTiming>) {
-    let mut timings: Vec<ApiCallTiming> = Vec::new();
-    let profile_str = profile.to_string();
-
-    macro_rules! timed {
-        ($name:expr, $body:expr) => {{
-            let __start = Instant::now();
-            let __result = $body;
-            if record_timing {
-                timings.push(ApiCallTiming {
-                    cluster: profile_str.clone(),
-                    api_call: $name.to_string(),
-                    duration_ms: __start.elapsed().as_millis() as u64,
-                });
-            }
-            __result
-        }};
-    }
+    let calls = Calls::new(profile, limiter, record_timing, deadline);
+    let unreachable = |error: String| ClusterResult::Unreachable {
+        profile: profile.to_string(),
+        error,
+    };
 
     on_progress("connecting...");
 
-    // Build disk cache if caching is enabled and we have a UUID to key by.
-    // ensure_cluster_uuids() runs before collect_all(), so most profiles have UUIDs.
-    let disk_cache: Option<DiskCache> = if !no_cache {
-        entry
-            .cluster_uuid
-            .as_deref()
-            .filter(|uuid| !uuid.is_empty())
-            .and_then(|uuid| DiskCache::new(uuid).ok())
-    } else {
-        None
-    };
+    for name in &entry.collect.skip {
+        if !SKIPPABLE_ENDPOINTS.contains(&name.as_str()) {
+            tracing::warn!(
+                %profile,
+                endpoint = %name,
+                "unknown collect.skip entry (expected one of: {})",
+                SKIPPABLE_ENDPOINTS.join(", ")
+            );
+        }
+    }
+    let skip = |endpoint: &str| skips_endpoint(entry, level, endpoint);
 
-    let client = match QumuloClient::new(entry, timeout_secs, disk_cache) {
+    let client = match build_client(entry, timeout_secs, no_cache) {
         Ok(c) => c,
         Err(e) => {
             return (
-                ClusterResult::Unreachable {
-                    profile: profile.to_string(),
-                    error: format!("failed to create client: {}", e),
-                },
-                timings,
+                unreachable(format!("failed to create client: {}", e)),
+                calls.into_timings(),
             );
         }
     };
 
     // Fetch basic cluster data
     on_progress("fetching cluster settings...");
-    let settings = match timed!("get_cluster_settings", client.get_cluster_settings()) {
+    let settings = match calls.run("get_cluster_settings", || client.get_cluster_settings()) {
         Ok(v) => v,
-        Err(e) => {
-            return (
-                ClusterResult::Unreachable {
-                    profile: profile.to_string(),
-                    error: format!("{}", e),
-                },
-                timings,
-            );
-        }
+        Err(e) => return (unreachable(format!("{}", e)), calls.into_timings()),
     };
 
     // Measure latency from just the /v1/version call (lightweight, near-zero server work)
     on_progress("fetching version...");
-    let start = Instant::now();
-    let version = match client.get_version() {
+    let (version, latency_ms) = {
+        let _permit = limiter.acquire();
+        let start = Instant::now();
+        let version = client.get_version();
+        (version, start.elapsed().as_millis() as u64)
+    };
+    let version = match version {
         Ok(v) => v,
-        Err(e) => {
-            return (
-                ClusterResult::Unreachable {
-                    profile: profile.to_string(),
-                    error: format!("{}", e),
-                },
-                timings,
-            );
-        }
+        Err(e) => return (unreachable(format!("{}", e)), calls.into_timings()),
     };
-    let latency_ms = start.elapsed().as_millis() as u64;
-    if record_timing {
-        timings.push(ApiCallTiming {
-            cluster: profile_str.clone(),
-            api_call: "get_version".to_string(),
-            duration_ms: latency_ms,
-        });
-    }
+    calls.record("get_version", latency_ms);
 
     on_progress("fetching nodes...");
-    let nodes_data = match timed!("get_cluster_nodes", client.get_cluster_nodes()) {
+    let nodes_data = match calls.run("get_cluster_nodes", || client.get_cluster_nodes()) {
         Ok(v) => v,
-        Err(e) => {
-            return (
-                ClusterResult::Unreachable {
-                    profile: profile.to_string(),
-                    error: format!("{}", e),
-                },
-                timings,
-            );
-        }
+        Err(e) => return (unreachable(format!("{}", e)), calls.into_timings()),
     };
 
     // Parse node data
@@ -398,103 +524,167 @@ fn collect_cluster(
         .unwrap_or("unknown")
         .to_string();
     // Prefer UUID from saved profile config; fall back to fetching from /v1/node/state
-    let cluster_uuid =
-        entry
-            .cluster_uuid
-            .clone()
-            .unwrap_or_else(|| match client.get_node_state() {
-                Ok(state) => state["cluster_id"].as_str().unwrap_or("").to_string(),
-                Err(_) => String::new(),
-            });
+    let cluster_uuid = entry.cluster_uuid.clone().unwrap_or_else(|| {
+        let _permit = limiter.acquire();
+        match client.get_node_state() {
+            Ok(state) => state["cluster_id"].as_str().unwrap_or("").to_string(),
+            Err(_) => String::new(),
+        }
+    });
     let version_str = version["revision_id"]
         .as_str()
         .unwrap_or("unknown")
         .to_string();
 
-    // Fetch optional data — don't fail if these are unavailable
-    on_progress("fetching capacity...");
-    let mut capacity = timed!("get_file_system", fetch_capacity(&client));
-    on_progress("fetching activity...");
-    let activity = timed!("get_activity", fetch_activity(&client));
-    on_progress("fetching file stats...");
-    let files = timed!("get_file_stats", fetch_file_stats(&client));
-    on_progress("fetching network stats...");
-    let node_details = timed!(
-        "get_network_details",
-        fetch_node_network_details(&client, &cluster_type, watch_mode)
-    );
-
-    // Fetch capacity history and compute projection
-    on_progress("fetching capacity history...");
-    capacity.projection = timed!(
-        "get_capacity_history",
-        fetch_capacity_projection(
-            &client,
-            capacity.used_bytes,
-            capacity.total_bytes,
-            &cluster_type
+    // Fetch optional data — don't fail if these are unavailable. Skipped
+    // groups (per-profile `collect.skip`) fall back to empty defaults.
+    on_progress("fetching capacity, activity, and health data...");
+    let client = &client;
+    let (
+        capacity,
+        activity,
+        files,
+        node_details,
+        disks,
+        psus,
+        protection,
+        data_at_risk,
+        worn_disks,
+        directory,
+    ) = std::thread::scope(|s| {
+        let capacity = s.spawn(|| {
+            let mut capacity = calls.run("get_file_system", || {
+                fetch_capacity(client, &calls.degraded)
+            });
+            // The projection needs current usage, so history follows capacity
+            if !skip("capacity_history") {
+                let days = projection.window.days().max(TREND_DAYS);
+                let history = calls.run("get_capacity_history", || {
+                    fetch_capacity_history(client, days, &calls.degraded)
+                });
+                if let Some(ref history) = history {
+                    let used = capacity::daily_used(history);
+                    capacity.trend =
+                        used[used.len().saturating_sub(TREND_DAYS as usize)..].to_vec();
+                    if capacity.total_bytes > 0 {
+                        capacity.projection = capacity::compute_projection_with(
+                            history,
+                            capacity.used_bytes,
+                            capacity.total_bytes,
+                            projection,
+                        );
+                    }
+                }
+            }
+            if level == CollectLevel::Full {
+                capacity.largest_paths = calls.run("get_largest_paths", || {
+                    fetch_largest_paths(client, &calls.degraded)
+                });
+            }
+            capacity
+        });
+        let activity = s.spawn(|| {
+            if skip("activity") {
+                return ActivityStatus::default();
+            }
+            let mut activity =
+                calls.run("get_activity", || fetch_activity(client, &calls.degraded));
+            if top_activity {
+                activity.top = calls.run("get_top_activity", || {
+                    fetch_top_activity(client, labels, &calls.degraded)
+                });
+            }
+            activity
+        });
+        let files = s.spawn(|| {
+            calls.run("get_file_stats", || {
+                fetch_file_stats(
+                    client,
+                    &calls.degraded,
+                    (!skip("recursive_aggregates")).then(|| aggregates_budget(&entry.collect)),
+                    !skip("snapshots"),
+                )
+            })
+        });
+        let node_details = s.spawn(|| {
+            if skip("network") {
+                return Vec::new();
+            }
+            calls.run("get_network_details", || {
+                fetch_node_network_details(
+                    client,
+                    &cluster_type,
+                    watch_mode,
+                    labels,
+                    &calls.degraded,
+                )
+            })
+        });
+        // Health data — each individually wrapped for error isolation
+        let disks = s.spawn(|| {
+            if skip("disks") {
+                return (0, Vec::new());
+            }
+            calls.run("get_cluster_slots", || {
+                fetch_disk_health(client, &calls.degraded)
+            })
+        });
+        let psus = s.spawn(|| {
+            if skip("psus") {
+                return (0, Vec::new());
+            }
+            calls.run("get_cluster_chassis", || {
+                fetch_psu_health(client, &calls.degraded)
+            })
+        });
+        let protection = s.spawn(|| {
+            if skip("protection") {
+                return (None, None, None);
+            }
+            calls.run("get_protection_status", || {
+                fetch_protection_status(client, &calls.degraded)
+            })
+        });
+        let data_at_risk = s.spawn(|| {
+            !skip("restriper")
+                && calls.run("get_restriper_status", || {
+                    fetch_restriper_status(client, &calls.degraded)
+                })
+        });
+        let worn_disks = s.spawn(|| {
+            if skip("ssd_endurance") {
+                return Vec::new();
+            }
+            calls.run("get_ssd_endurance", || {
+                fetch_worn_disks(client, &calls.degraded, entry.collect.disk_wear_threshold())
+            })
+        });
+        let directory = s.spawn(|| {
+            if skip("directory_services") {
+                return None;
+            }
+            calls.run("get_ad_status", || {
+                fetch_directory_status(client, &calls.degraded)
+            })
+        });
+        (
+            join(capacity),
+            join(activity),
+            join(files),
+            join(node_details),
+            join(disks),
+            join(psus),
+            join(protection),
+            join(data_at_risk),
+            join(worn_disks),
+            join(directory),
         )
-    );
-
-    // Fetch health data — each individually wrapped for error isolation
-    on_progress("fetching health data...");
-    let (unhealthy_disks, disk_details) = timed!("get_cluster_slots", fetch_disk_health(&client));
-    let (unhealthy_psus, psu_details) = timed!("get_cluster_chassis", fetch_psu_health(&client));
-    let (remaining_node_failures, remaining_drive_failures, protection_type) =
-        timed!("get_protection_status", fetch_protection_status(&client));
-    let data_at_risk = timed!("get_restriper_status", fetch_restriper_status(&client));
-
-    // Build health status
-    let mut issues = Vec::new();
-    if online_nodes < total_nodes {
-        issues.push(format!(
-            "{} of {} nodes offline",
-            total_nodes - online_nodes,
-            total_nodes
-        ));
-    }
-    if capacity.used_pct >= 90.0 {
-        issues.push(format!("capacity at {:.0}%", capacity.used_pct));
-    }
-    if unhealthy_disks > 0 {
-        for d in &disk_details {
-            issues.push(format!(
-                "disk unhealthy: node {}, bay {}, {}",
-                d.node_id, d.bay, d.disk_type
-            ));
-        }
-    }
-    if unhealthy_psus > 0 {
-        for p in &psu_details {
-            issues.push(format!(
-                "PSU issue: node {}, {} ({})",
-                p.node_id, p.location, p.state
-            ));
-        }
-    }
-    if data_at_risk {
-        issues.push("DATA AT RISK — restriper active".to_string());
-    }
-    if let Some(remaining) = remaining_node_failures {
-        if remaining == 0 {
-            issues.push("fault tolerance degraded (0 node failures remaining)".to_string());
-        }
-    }
-    if let Some(remaining) = remaining_drive_failures {
-        if remaining == 0 {
-            issues.push("fault tolerance degraded (0 drive failures remaining)".to_string());
-        }
-    }
-
-    let health_level = if data_at_risk || (online_nodes == 0 && total_nodes > 0) {
-        HealthLevel::Critical
-    } else if !issues.is_empty() {
-        HealthLevel::Degraded
-    } else {
-        HealthLevel::Healthy
-    };
+    });
+    let (unhealthy_disks, disk_details) = disks;
+    let (unhealthy_psus, psu_details) = psus;
+    let (remaining_node_failures, remaining_drive_failures, protection_type) = protection;
 
-    let data = ClusterStatus {
+    let mut data = ClusterStatus {
         profile: profile.to_string(),
         name: cluster_name,
         uuid: cluster_uuid,
@@ -503,6 +693,8 @@ fn collect_cluster(
         reachable: true,
         stale: false,
         latency_ms,
+        degraded: calls.degraded.groups(),
+        partial: calls.degraded.over_budget(),
         nodes: NodeStatus {
             total: total_nodes,
             online: online_nodes,
@@ -513,8 +705,8 @@ fn collect_cluster(
         activity,
         files,
         health: HealthStatus {
-            status: health_level,
-            issues,
+            status: HealthLevel::Healthy,
+            issues: Vec::new(),
             disks_unhealthy: unhealthy_disks,
             psus_unhealthy: unhealthy_psus,
             data_at_risk,
@@ -523,32 +715,428 @@ fn collect_cluster(
             protection_type,
             unhealthy_disk_details: disk_details,
             unhealthy_psu_details: psu_details,
+            worn_disks,
+            directory,
+        },
+    };
+    assess_health(&data.nodes, &data.capacity, &mut data.health);
+
+    (
+        ClusterResult::Success {
+            data: Box::new(data),
+            latency_ms,
         },
+        calls.into_timings(),
+    )
+}
+
+/// Re-collect only the volatile endpoints (activity, connections, NIC, restriper)
+/// for a cluster seen on the previous watch poll, carrying settings, nodes,
+/// capacity, file stats, and hardware health forward from `previous`. The
+/// version call is kept as the reachability probe and latency measurement.
+#[allow(clippy::too_many_arguments)]
+fn refresh_cluster(
+    profile: &str,
+    entry: &ProfileEntry,
+    previous: &ClusterStatus,
+    level: CollectLevel,
+    top_activity: bool,
+    timeout_secs: u64,
+    no_cache: bool,
+    on_progress: &dyn Fn(&str),
+    record_timing: bool,
+    limiter: &Limiter,
+    labels: &SubnetLabels,
+    deadline: Option<Instant>,
+) -> (ClusterResult, Vec<ApiCallTiming>) {
+    let calls = Calls::new(profile, limiter, record_timing, deadline);
+    let skip = |endpoint: &str| skips_endpoint(entry, level, endpoint);
+    let unreachable = |error: String| ClusterResult::Unreachable {
+        profile: profile.to_string(),
+        error,
+    };
+
+    let client = match build_client(entry, timeout_secs, no_cache) {
+        Ok(c) => c,
+        Err(e) => {
+            return (
+                unreachable(format!("failed to create client: {}", e)),
+                calls.into_timings(),
+            )
+        }
+    };
+
+    on_progress("fetching version...");
+    let (version, latency_ms) = {
+        let _permit = limiter.acquire();
+        let start = Instant::now();
+        let version = client.get_version();
+        (version, start.elapsed().as_millis() as u64)
     };
+    if let Err(e) = version {
+        return (unreachable(format!("{}", e)), calls.into_timings());
+    }
+    calls.record("get_version", latency_ms);
+
+    let mut data = previous.clone();
+    data.latency_ms = latency_ms;
+    on_progress("fetching activity and health data...");
+    let client = &client;
+    let cluster_type = &previous.cluster_type;
+    std::thread::scope(|s| {
+        let activity = s.spawn(|| {
+            if skip("activity") {
+                return None;
+            }
+            let mut activity =
+                calls.run("get_activity", || fetch_activity(client, &calls.degraded));
+            if top_activity {
+                activity.top = calls.run("get_top_activity", || {
+                    fetch_top_activity(client, labels, &calls.degraded)
+                });
+            }
+            Some(activity)
+        });
+        let node_details = s.spawn(|| {
+            if skip("network") {
+                return None;
+            }
+            Some(calls.run("get_network_details", || {
+                fetch_node_network_details(client, cluster_type, true, labels, &calls.degraded)
+            }))
+        });
+        let data_at_risk = s.spawn(|| {
+            if skip("restriper") {
+                return None;
+            }
+            Some(calls.run("get_restriper_status", || {
+                fetch_restriper_status(client, &calls.degraded)
+            }))
+        });
+        if let Some(activity) = join(activity) {
+            data.activity = activity;
+        }
+        if let Some(details) = join(node_details) {
+            data.nodes.details = details;
+        }
+        if let Some(data_at_risk) = join(data_at_risk) {
+            data.health.data_at_risk = data_at_risk;
+        }
+    });
+    // Carried-forward groups keep their state; refreshed ones are re-judged
+    data.degraded
+        .retain(|group| !VOLATILE_GROUPS.contains(&group.as_str()));
+    data.degraded.extend(calls.degraded.groups());
+    data.degraded.sort();
+    data.partial = calls.degraded.over_budget();
+    assess_health(&data.nodes, &data.capacity, &mut data.health);
 
     (
         ClusterResult::Success {
             data: Box::new(data),
             latency_ms,
         },
-        timings,
+        calls.into_timings(),
     )
 }
 
-fn fetch_capacity_projection(
+/// Wait for a scoped fetch thread, re-raising its panic on the cluster's
+/// thread (where collect_all reports it as an unreachable cluster).
+fn join<T>(handle: std::thread::ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Counting semaphore bounding how many API call groups are in flight at
+/// once across every cluster being collected (`fleet status --jobs`).
+pub struct Limiter {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Limiter {
+    pub fn new(jobs: usize) -> Self {
+        Limiter {
+            available: Mutex::new(jobs.max(1)),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until a slot is free. The slot is returned when the permit drops.
+    pub fn acquire(&self) -> Permit<'_> {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+        Permit { limiter: self }
+    }
+}
+
+pub struct Permit<'a> {
+    limiter: &'a Limiter,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let mut available = self
+            .limiter
+            .available
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *available += 1;
+        self.limiter.released.notify_one();
+    }
+}
+
+/// Runs one cluster's API call groups under the shared `Limiter`, recording
+/// how long each took when timing is on, and which groups ended up degraded.
+/// Usable from several fetch threads.
+struct Calls<'a> {
+    cluster: &'a str,
+    limiter: &'a Limiter,
+    record_timing: bool,
+    timings: Mutex<Vec<ApiCallTiming>>,
+    degraded: Degraded,
+}
+
+impl<'a> Calls<'a> {
+    fn new(
+        cluster: &'a str,
+        limiter: &'a Limiter,
+        record_timing: bool,
+        deadline: Option<Instant>,
+    ) -> Self {
+        Calls {
+            cluster,
+            limiter,
+            record_timing,
+            timings: Mutex::new(Vec::new()),
+            degraded: Degraded::with_deadline(deadline),
+        }
+    }
+
+    /// Run `f` while holding a permit. Time spent waiting for the permit is
+    /// not counted in the recorded duration.
+    fn run<T>(&self, api_call: &str, f: impl FnOnce() -> T) -> T {
+        let _permit = self.limiter.acquire();
+        let start = Instant::now();
+        let result = f();
+        self.record(api_call, start.elapsed().as_millis() as u64);
+        result
+    }
+
+    fn record(&self, api_call: &str, duration_ms: u64) {
+        if !self.record_timing {
+            return;
+        }
+        self.timings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ApiCallTiming {
+                cluster: self.cluster.to_string(),
+                api_call: api_call.to_string(),
+                duration_ms,
+            });
+    }
+
+    fn into_timings(self) -> Vec<ApiCallTiming> {
+        self.timings.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Endpoint groups `refresh_cluster` re-fetches on every watch poll.
+const VOLATILE_GROUPS: &[&str] = &["activity", "top_activity", "network", "restriper"];
+
+/// Pause before retrying an endpoint that failed transiently.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Endpoint groups of one cluster whose data could not be fetched even after
+/// a retry. Their fields fall back to defaults, so renderers and alerts use
+/// this to tell "unavailable" apart from a genuine zero.
+#[derive(Default)]
+pub(crate) struct Degraded {
+    groups: Mutex<BTreeSet<String>>,
+    /// End of the cluster's collection budget (`--cluster-budget`)
+    deadline: Option<Instant>,
+    /// Set once a call was skipped because the deadline had passed
+    abandoned: AtomicBool,
+}
+
+impl Degraded {
+    pub(crate) fn with_deadline(deadline: Option<Instant>) -> Self {
+        Degraded {
+            deadline,
+            ..Degraded::default()
+        }
+    }
+
+    /// Run one API call, retrying it once after `RETRY_DELAY` when the
+    /// failure looks transient (transport error, HTTP 429 or 5xx). A final
+    /// failure marks `group` degraded, except for 404s: the endpoint doesn't
+    /// exist on this cluster's version, so there was no data to miss.
+    ///
+    /// Past the deadline the call is not made at all and `group` is marked
+    /// degraded straight away; a request already in flight is left to finish.
+    pub(crate) fn retry<T>(&self, group: &str, call: impl Fn() -> Result<T>) -> Result<T> {
+        if self.past_deadline() {
+            self.abandon(group);
+            anyhow::bail!("cluster collection budget exceeded");
+        }
+        let result = match call() {
+            Err(e) if is_transient(&e) && !self.past_deadline() => {
+                tracing::debug!(error = %e, %group, "retrying after transient failure");
+                std::thread::sleep(RETRY_DELAY);
+                call()
+            }
+            result => result,
+        };
+        if let Err(e) = &result {
+            if api_status(e) != Some(404) {
+                self.mark(group);
+            }
+        }
+        result
+    }
+
+    fn past_deadline(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    fn abandon(&self, group: &str) {
+        if !self.abandoned.swap(true, Ordering::Relaxed) {
+            tracing::warn!(%group, "cluster collection budget exceeded, skipping remaining endpoints");
+        }
+        self.mark(group);
+    }
+
+    /// Whether any call was skipped for running over the budget
+    pub(crate) fn over_budget(&self) -> bool {
+        self.abandoned.load(Ordering::Relaxed)
+    }
+
+    fn mark(&self, group: &str) {
+        self.groups
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(group.to_string());
+    }
+
+    /// The degraded groups, sorted
+    pub(crate) fn groups(&self) -> Vec<String> {
+        let groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+        groups.iter().cloned().collect()
+    }
+}
+
+fn api_status(e: &anyhow::Error) -> Option<u16> {
+    match e.downcast_ref::<QontrolError>() {
+        Some(QontrolError::ApiError { status, .. }) => Some(*status),
+        _ => None,
+    }
+}
+
+/// Whether an error is worth one retry: the request never completed, or the
+/// cluster said it was busy or failed internally.
+fn is_transient(e: &anyhow::Error) -> bool {
+    match api_status(e) {
+        Some(status) => status == 429 || status >= 500,
+        None => e.downcast_ref::<reqwest::Error>().is_some(),
+    }
+}
+
+/// Whether an endpoint group is left out at this level or by the profile's
+/// `collect.skip`. The fast level skips every optional group, leaving
+/// settings, version, nodes, and file system capacity.
+fn skips_endpoint(entry: &ProfileEntry, level: CollectLevel, endpoint: &str) -> bool {
+    level == CollectLevel::Fast || entry.collect.skips(endpoint)
+}
+
+/// Build a client for a profile, with a disk cache when caching is enabled and
+/// the profile has a UUID to key by. ensure_cluster_uuids() runs before
+/// collect_all(), so most profiles have UUIDs.
+fn build_client(entry: &ProfileEntry, timeout_secs: u64, no_cache: bool) -> Result<QumuloClient> {
+    let disk_cache: Option<DiskCache> = if !no_cache {
+        entry
+            .cluster_uuid
+            .as_deref()
+            .filter(|uuid| !uuid.is_empty())
+            .and_then(|uuid| DiskCache::new(uuid).ok())
+    } else {
+        None
+    };
+    QumuloClient::new(entry, timeout_secs, disk_cache)
+}
+
+/// Derive the health issue list and overall level from collected node,
+/// capacity, and health fields.
+fn assess_health(nodes: &NodeStatus, capacity: &CapacityStatus, health: &mut HealthStatus) {
+    let mut issues = Vec::new();
+    if nodes.online < nodes.total {
+        issues.push(format!(
+            "{} of {} nodes offline",
+            nodes.total - nodes.online,
+            nodes.total
+        ));
+    }
+    if capacity.used_pct >= 90.0 {
+        issues.push(format!("capacity at {:.0}%", capacity.used_pct));
+    }
+    for d in &health.unhealthy_disk_details {
+        issues.push(format!(
+            "disk unhealthy: node {}, bay {}, {}",
+            d.node_id, d.bay, d.disk_type
+        ));
+    }
+    for p in &health.unhealthy_psu_details {
+        issues.push(format!(
+            "PSU issue: node {}, {} ({})",
+            p.node_id, p.location, p.state
+        ));
+    }
+    if health.data_at_risk {
+        issues.push("DATA AT RISK — restriper active".to_string());
+    }
+    if health.remaining_node_failures == Some(0) {
+        issues.push("fault tolerance degraded (0 node failures remaining)".to_string());
+    }
+    if health.remaining_drive_failures == Some(0) {
+        issues.push("fault tolerance degraded (0 drive failures remaining)".to_string());
+    }
+
+    health.status = if health.data_at_risk || (nodes.online == 0 && nodes.total > 0) {
+        HealthLevel::Critical
+    } else if !issues.is_empty() {
+        HealthLevel::Degraded
+    } else {
+        HealthLevel::Healthy
+    };
+    health.issues = issues;
+}
+
+pub(crate) fn fetch_capacity_projection(
     client: &QumuloClient,
     current_used: u64,
     total_capacity: u64,
-    _cluster_type: &ClusterType,
+    projection: Projection,
+    degraded: &Degraded,
 ) -> Option<CapacityProjection> {
     if total_capacity == 0 {
         return None;
     }
-    // Fetch 30 days of history
-    let now = chrono::Utc::now().timestamp();
-    let thirty_days_ago = now - 30 * 86400;
-    match client.get_capacity_history(thirty_days_ago) {
-        Ok(history) => capacity::compute_projection(&history, current_used, total_capacity),
+    let history = fetch_capacity_history(client, projection.window.days(), degraded)?;
+    capacity::compute_projection_with(&history, current_used, total_capacity, projection)
+}
+
+/// Fetch `days` days of daily capacity history, or None if it can't be fetched.
+fn fetch_capacity_history(client: &QumuloClient, days: u32, degraded: &Degraded) -> Option<Value> {
+    let begin = chrono::Utc::now().timestamp() - days as i64 * 86400;
+    match degraded.retry("capacity_history", || client.get_capacity_history(begin)) {
+        Ok(history) => Some(history),
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch capacity history");
             None
@@ -556,8 +1144,8 @@ fn fetch_capacity_projection(
     }
 }
 
-fn fetch_capacity(client: &QumuloClient) -> CapacityStatus {
-    match client.get_file_system() {
+pub(crate) fn fetch_capacity(client: &QumuloClient, degraded: &Degraded) -> CapacityStatus {
+    match degraded.retry("capacity", || client.get_file_system()) {
         Ok(fs) => {
             let total = parse_byte_value(&fs["total_size_bytes"]);
             let free = parse_byte_value(&fs["free_size_bytes"]);
@@ -575,6 +1163,8 @@ fn fetch_capacity(client: &QumuloClient) -> CapacityStatus {
                 snapshot_bytes: snapshot,
                 used_pct: pct,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             }
         }
         Err(e) => {
@@ -584,11 +1174,50 @@ fn fetch_capacity(client: &QumuloClient) -> CapacityStatus {
     }
 }
 
-fn fetch_activity(client: &QumuloClient) -> ActivityStatus {
-    let iops_read = fetch_activity_sum(client, "file-iops-read");
-    let iops_write = fetch_activity_sum(client, "file-iops-write");
-    let throughput_read = fetch_activity_sum(client, "file-throughput-read");
-    let throughput_write = fetch_activity_sum(client, "file-throughput-write");
+/// Fetch the largest paths by capacity as of now from the capacity analytics.
+fn fetch_largest_paths(client: &QumuloClient, degraded: &Degraded) -> Vec<PathCapacity> {
+    match degraded.retry("largest_paths", || {
+        client.get_capacity_by_path(chrono::Utc::now().timestamp())
+    }) {
+        Ok(details) => parse_largest_paths(&details, LARGEST_PATHS_LIMIT),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch largest paths");
+            Vec::new()
+        }
+    }
+}
+
+/// Parse `largest_paths` from a capacity-history detail response, biggest first.
+/// Returns nothing while the analytics are still pending or unavailable.
+fn parse_largest_paths(details: &Value, limit: usize) -> Vec<PathCapacity> {
+    if details["status"]
+        .as_str()
+        .is_some_and(|s| !s.eq_ignore_ascii_case("available"))
+    {
+        return Vec::new();
+    }
+    let mut paths: Vec<PathCapacity> = details["largest_paths"]
+        .as_array()
+        .map(|a| a.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|p| {
+            Some(PathCapacity {
+                path: p["path"].as_str()?.to_string(),
+                used_bytes: parse_byte_value(&p["capacity_used"]),
+            })
+        })
+        .collect();
+    paths.sort_by_key(|p| std::cmp::Reverse(p.used_bytes));
+    paths.truncate(limit);
+    paths
+}
+
+fn fetch_activity(client: &QumuloClient, degraded: &Degraded) -> ActivityStatus {
+    let iops_read = fetch_activity_sum(client, degraded, "file-iops-read");
+    let iops_write = fetch_activity_sum(client, degraded, "file-iops-write");
+    let throughput_read = fetch_activity_sum(client, degraded, "file-throughput-read");
+    let throughput_write = fetch_activity_sum(client, degraded, "file-throughput-write");
 
     let is_idle =
         iops_read == 0.0 && iops_write == 0.0 && throughput_read == 0.0 && throughput_write == 0.0;
@@ -600,12 +1229,120 @@ fn fetch_activity(client: &QumuloClient) -> ActivityStatus {
         throughput_write,
         connections: 0,
         is_idle,
+        top: None,
+    }
+}
+
+/// Fetch the hottest paths and clients, resolving file IDs to paths. Paths that
+/// fail to resolve are shown by file ID.
+fn fetch_top_activity(
+    client: &QumuloClient,
+    labels: &SubnetLabels,
+    degraded: &Degraded,
+) -> Option<TopActivity> {
+    let current = match degraded.retry("top_activity", || client.get_activity_current()) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch current activity");
+            return None;
+        }
+    };
+    let mut top = parse_top_activity(&current, TOP_ACTIVITY_LIMIT);
+    for talker in &mut top.clients {
+        talker.label = labels.label(&talker.name).map(str::to_string);
+    }
+    if top.paths.is_empty() {
+        return Some(top);
+    }
+
+    let ids: Vec<String> = top.paths.iter().map(|p| p.name.clone()).collect();
+    let resolved: std::collections::HashMap<String, String> =
+        match degraded.retry("top_activity", || client.resolve_file_ids(&ids)) {
+            Ok(v) => v
+                .as_array()
+                .map(|a| a.as_slice())
+                .unwrap_or(&[])
+                .iter()
+                .filter_map(|e| {
+                    Some((
+                        e["id"].as_str()?.to_string(),
+                        e["path"].as_str()?.to_string(),
+                    ))
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to resolve activity file IDs");
+                Default::default()
+            }
+        };
+    for talker in &mut top.paths {
+        talker.name = match resolved.get(&talker.name) {
+            Some(path) if !path.is_empty() => path.clone(),
+            _ => format!("file id {}", talker.name),
+        };
+    }
+    Some(top)
+}
+
+/// Rank the entries of an activity response by combined IOPS (file and
+/// metadata, read and write), grouped once by file ID and once by client IP.
+/// Path names are left as file IDs for the caller to resolve.
+fn parse_top_activity(current: &Value, limit: usize) -> TopActivity {
+    let mut by_id: std::collections::HashMap<String, (f64, f64)> = Default::default();
+    let mut by_ip: std::collections::HashMap<String, (f64, f64)> = Default::default();
+
+    for entry in current["entries"]
+        .as_array()
+        .map(|a| a.as_slice())
+        .unwrap_or(&[])
+    {
+        let rate = entry["rate"].as_f64().unwrap_or(0.0);
+        let kind = entry["type"].as_str().unwrap_or("");
+        let (iops, throughput) = if kind.contains("throughput") {
+            (0.0, rate)
+        } else if kind.contains("iops") {
+            (rate, 0.0)
+        } else {
+            continue;
+        };
+        for (map, key) in [(&mut by_id, &entry["id"]), (&mut by_ip, &entry["ip"])] {
+            if let Some(key) = key.as_str().filter(|k| !k.is_empty()) {
+                let slot = map.entry(key.to_string()).or_default();
+                slot.0 += iops;
+                slot.1 += throughput;
+            }
+        }
+    }
+
+    let rank = |map: std::collections::HashMap<String, (f64, f64)>| {
+        let mut talkers: Vec<ActivityTalker> = map
+            .into_iter()
+            .map(|(name, (iops, throughput))| ActivityTalker {
+                name,
+                label: None,
+                iops,
+                throughput,
+            })
+            .collect();
+        talkers.sort_by(|a, b| {
+            b.iops
+                .total_cmp(&a.iops)
+                .then(b.throughput.total_cmp(&a.throughput))
+                .then(a.name.cmp(&b.name))
+        });
+        talkers.truncate(limit);
+        talkers
+    };
+
+    TopActivity {
+        paths: rank(by_id),
+        clients: rank(by_ip),
     }
 }
 
 /// Fetch a single activity type and sum all entry rates.
-fn fetch_activity_sum(client: &QumuloClient, activity_type: &str) -> f64 {
-    match client.get_activity_by_type(activity_type) {
+fn fetch_activity_sum(client: &QumuloClient, degraded: &Degraded, activity_type: &str) -> f64 {
+    match degraded.retry("activity", || client.get_activity_by_type(activity_type)) {
         Ok(resp) => resp["entries"]
             .as_array()
             .map(|entries| {
@@ -628,22 +1365,74 @@ fn fetch_activity_sum(client: &QumuloClient, activity_type: &str) -> f64 {
     }
 }
 
-fn fetch_file_stats(client: &QumuloClient) -> FileStats {
+/// How far a profile's status poll walks recursive aggregates: nowhere
+/// (`None`) unless it sets `collect.aggregates_max_depth`.
+fn aggregates_budget(collect: &CollectConfig) -> Option<AggregatesBudget> {
+    let max_depth = collect.aggregates_max_depth.filter(|&depth| depth > 0)?;
+    let default = AggregatesBudget::default();
+    Some(AggregatesBudget {
+        max_depth,
+        max_entries: collect
+            .aggregates_max_entries
+            .unwrap_or(default.max_entries),
+        ..default
+    })
+}
+
+/// File and directory counts, and snapshot totals. `aggregates` is `None`
+/// when the profile skips them; `Some(None)` reads the root inode's
+/// aggregates with `max-entries=0`, one small request whatever the size of
+/// the tree. With a budget the counts come from the root of a paged
+/// recursive-aggregates walk (see
+/// `QumuloClient::get_recursive_aggregates_paged`), which also yields the
+/// largest directories.
+fn fetch_file_stats(
+    client: &QumuloClient,
+    degraded: &Degraded,
+    aggregates: Option<Option<AggregatesBudget>>,
+    snapshots: bool,
+) -> FileStats {
     let mut stats = FileStats::default();
 
-    // File/directory counts from root inode aggregates (no tree walk)
-    match client.get_file_aggregates("/") {
-        Ok(agg) => {
-            stats.total_files = parse_string_u64(&agg["total_files"]);
-            stats.total_directories = parse_string_u64(&agg["total_directories"]);
+    match aggregates {
+        None => {}
+        // File/directory counts from root inode aggregates (no tree walk)
+        Some(None) => {
+            match degraded.retry("recursive_aggregates", || client.get_file_aggregates("/")) {
+                Ok(agg) => {
+                    stats.total_files = parse_string_u64(&agg["total_files"]);
+                    stats.total_directories = parse_string_u64(&agg["total_directories"]);
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to fetch file aggregates");
+                }
+            }
         }
-        Err(e) => {
-            tracing::warn!(error = %e, "failed to fetch file aggregates");
+        Some(Some(budget)) => {
+            match degraded.retry("recursive_aggregates", || {
+                client.get_recursive_aggregates_paged("/", budget)
+            }) {
+                Ok(dirs) => {
+                    if let Some(root) = dirs.first() {
+                        stats.total_files = parse_string_u64(&root["total_files"]);
+                        stats.total_directories = parse_string_u64(&root["total_directories"]);
+                    }
+                    stats.largest_directories =
+                        largest_directories(&dirs, LARGEST_DIRECTORIES_LIMIT);
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to walk recursive aggregates");
+                }
+            }
         }
     }
 
+    if !snapshots {
+        return stats;
+    }
+
     // Snapshot count from /v2/snapshots/
-    match client.get_snapshots() {
+    match degraded.retry("snapshots", || client.get_snapshots()) {
         Ok(snap) => {
             if let Some(entries) = snap["entries"].as_array() {
                 stats.total_snapshots = entries.len() as u64;
@@ -655,7 +1444,7 @@ fn fetch_file_stats(client: &QumuloClient) -> FileStats {
     }
 
     // Snapshot total capacity from /v1/snapshots/total-used-capacity
-    match client.get_snapshots_total_capacity() {
+    match degraded.retry("snapshots", || client.get_snapshots_total_capacity()) {
         Ok(cap) => {
             stats.snapshot_bytes = parse_byte_value(&cap["bytes"]);
         }
@@ -667,6 +1456,24 @@ fn fetch_file_stats(client: &QumuloClient) -> FileStats {
     stats
 }
 
+/// The largest directories below the root of a recursive-aggregates walk,
+/// biggest first.
+fn largest_directories(dirs: &[Value], limit: usize) -> Vec<PathCapacity> {
+    let mut paths: Vec<PathCapacity> = dirs
+        .iter()
+        .skip(1)
+        .filter_map(|d| {
+            Some(PathCapacity {
+                path: d["path"].as_str()?.to_string(),
+                used_bytes: parse_string_u64(&d["total_capacity"]),
+            })
+        })
+        .collect();
+    paths.sort_by_key(|p| std::cmp::Reverse(p.used_bytes));
+    paths.truncate(limit);
+    paths
+}
+
 fn parse_string_u64(val: &Value) -> u64 {
     match val {
         Value::String(s) => s.parse::<u64>().unwrap_or(0),
@@ -677,8 +1484,8 @@ fn parse_string_u64(val: &Value) -> u64 {
 
 /// Fetch disk health from /v1/cluster/slots/.
 /// Returns (unhealthy_count, details).
-fn fetch_disk_health(client: &QumuloClient) -> (usize, Vec<UnhealthyDisk>) {
-    match client.get_cluster_slots() {
+fn fetch_disk_health(client: &QumuloClient, degraded: &Degraded) -> (usize, Vec<UnhealthyDisk>) {
+    match degraded.retry("disks", || client.get_cluster_slots()) {
         Ok(slots) => parse_disk_health(&slots),
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch disk health");
@@ -687,12 +1494,73 @@ fn fetch_disk_health(client: &QumuloClient) -> (usize, Vec<UnhealthyDisk>) {
     }
 }
 
+/// Parse the AD join from /v1/ad/status.
+///
+/// Domain controllers come from `dcs` (name, else address). Per-node LDAP
+/// state comes from `ldap_connection_states`: a node counts as disconnected
+/// when it lists no servers, or when it reports states and none of them is
+/// connected. Nodes reporting no state at all are left out.
+fn parse_ad_status(resp: &Value) -> Option<DirectoryStatus> {
+    let status = resp["status"].as_str().unwrap_or("");
+    if status.is_empty() || status.eq_ignore_ascii_case("NOT_IN_DOMAIN") {
+        return None;
+    }
+    let domain_controllers = resp["dcs"]
+        .as_array()
+        .map(|dcs| {
+            dcs.iter()
+                .filter_map(|dc| dc["name"].as_str().or_else(|| dc["address"].as_str()))
+                .filter(|dc| !dc.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let connected = |state: &str| {
+        let state = state.to_ascii_uppercase();
+        state.contains("CONNECTED")
+            && !state.contains("DISCONNECTED")
+            && !state.contains("NOT_CONNECTED")
+    };
+    let states = |v: &Value| -> Vec<String> {
+        ["status", "state", "connection_state"]
+            .iter()
+            .filter_map(|k| v[*k].as_str().map(String::from))
+            .collect()
+    };
+    let mut disconnected_nodes: Vec<u64> = resp["ldap_connection_states"]
+        .as_array()
+        .map(|a| a.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|node| {
+            let id = node["node_id"].as_u64()?;
+            let servers = node["servers"].as_array();
+            let mut found = states(node);
+            for server in servers.into_iter().flatten() {
+                found.extend(states(server));
+            }
+            let down = servers.is_some_and(|s| s.is_empty())
+                || (!found.is_empty() && !found.iter().any(|s| connected(s)));
+            down.then_some(id)
+        })
+        .collect();
+    disconnected_nodes.sort_unstable();
+
+    Some(DirectoryStatus {
+        domain: resp["domain"].as_str().unwrap_or("unknown").to_string(),
+        status: status.to_string(),
+        domain_controllers,
+        disconnected_nodes,
+    })
+}
+
 #[allow(dead_code)] // Preserved for dedicated PSU commands (separate bead)
 /// Fetch PSU health from /v1/cluster/nodes/chassis/.
 /// Cloud clusters return empty psu_statuses arrays — handled gracefully.
 /// Returns (unhealthy_count, details).
-fn fetch_psu_health(client: &QumuloClient) -> (usize, Vec<UnhealthyPsu>) {
-    match client.get_cluster_chassis() {
+fn fetch_psu_health(client: &QumuloClient, degraded: &Degraded) -> (usize, Vec<UnhealthyPsu>) {
+    match degraded.retry("psus", || client.get_cluster_chassis()) {
         Ok(chassis) => parse_psu_health(&chassis),
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch PSU health");
@@ -701,10 +1569,41 @@ fn fetch_psu_health(client: &QumuloClient) -> (usize, Vec<UnhealthyPsu>) {
     }
 }
 
+/// Fetch SSD endurance from /v1/health/ssd-endurance and keep the drives at
+/// or past `threshold` percent of rated endurance used.
+/// HDD-only and cloud clusters report no SSDs — handled gracefully.
+fn fetch_worn_disks(client: &QumuloClient, degraded: &Degraded, threshold: f64) -> Vec<WornDisk> {
+    match degraded.retry("ssd_endurance", || client.get_ssd_endurance()) {
+        Ok(resp) => parse_ssd_endurance(&resp)
+            .into_iter()
+            .filter(|d| d.wear_pct >= threshold)
+            .collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch SSD endurance");
+            Vec::new()
+        }
+    }
+}
+
+/// Fetch the Active Directory join from /v1/ad/status. Clusters not joined
+/// to a domain, and versions without the endpoint, yield None.
+fn fetch_directory_status(client: &QumuloClient, degraded: &Degraded) -> Option<DirectoryStatus> {
+    match degraded.retry("directory_services", || client.get_ad_status()) {
+        Ok(resp) => parse_ad_status(&resp),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch Active Directory status");
+            None
+        }
+    }
+}
+
 /// Fetch protection status from /v1/cluster/protection/status.
 /// Returns (remaining_node_failures, remaining_drive_failures, protection_system_type).
-fn fetch_protection_status(client: &QumuloClient) -> (Option<u64>, Option<u64>, Option<String>) {
-    match client.get_cluster_protection_status() {
+fn fetch_protection_status(
+    client: &QumuloClient,
+    degraded: &Degraded,
+) -> (Option<u64>, Option<u64>, Option<String>) {
+    match degraded.retry("protection", || client.get_cluster_protection_status()) {
         Ok(prot) => parse_protection_status(&prot),
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch protection status");
@@ -715,8 +1614,8 @@ fn fetch_protection_status(client: &QumuloClient) -> (Option<u64>, Option<u64>,
 
 /// Fetch restriper status from /v1/cluster/restriper/status.
 /// Returns true if data_at_risk is true.
-fn fetch_restriper_status(client: &QumuloClient) -> bool {
-    match client.get_cluster_restriper_status() {
+fn fetch_restriper_status(client: &QumuloClient, degraded: &Degraded) -> bool {
+    match degraded.retry("restriper", || client.get_cluster_restriper_status()) {
         Ok(restriper) => parse_restriper_status(&restriper),
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch restriper status");
@@ -739,9 +1638,11 @@ fn fetch_node_network_details(
     client: &QumuloClient,
     cluster_type: &ClusterType,
     watch_mode: bool,
+    labels: &SubnetLabels,
+    degraded: &Degraded,
 ) -> Vec<NodeNetworkInfo> {
-    let connections_by_node = fetch_connections_per_node(client);
-    let nic_stats_by_node = fetch_nic_stats_per_node(client, cluster_type, watch_mode);
+    let connections_by_node = fetch_connections_per_node(client, labels, degraded);
+    let nic_stats_by_node = fetch_nic_stats_per_node(client, cluster_type, watch_mode, degraded);
 
     // Merge connection data and NIC data by node_id
     let mut node_ids: std::collections::BTreeSet<u64> = std::collections::BTreeSet::new();
@@ -755,7 +1656,7 @@ fn fetch_node_network_details(
     node_ids
         .into_iter()
         .map(|node_id| {
-            let (connections, breakdown) = connections_by_node
+            let conns = connections_by_node
                 .get(&node_id)
                 .cloned()
                 .unwrap_or_default();
@@ -767,8 +1668,10 @@ fn fetch_node_network_details(
 
             NodeNetworkInfo {
                 node_id,
-                connections,
-                connection_breakdown: breakdown,
+                connections: conns.total,
+                connection_breakdown: conns.by_protocol,
+                subnet_breakdown: conns.by_subnet,
+                version_breakdown: conns.by_version,
                 nic_throughput_bps: throughput,
                 nic_link_speed_bps: link_speed,
                 nic_utilization_pct: utilization,
@@ -778,20 +1681,28 @@ fn fetch_node_network_details(
         .collect()
 }
 
-/// Parse connections response: array of {id, connections: [{type, ...}]}
-/// Returns map of node_id → (total_connections, breakdown_by_protocol)
+/// Fetch connections per node; see `parse_connections_per_node`.
 fn fetch_connections_per_node(
     client: &QumuloClient,
-) -> std::collections::HashMap<u64, (u32, std::collections::HashMap<String, u32>)> {
-    let mut result = std::collections::HashMap::new();
-
-    let data = match client.get_network_connections() {
-        Ok(v) => v,
+    labels: &SubnetLabels,
+    degraded: &Degraded,
+) -> ConnectionsMap {
+    match degraded.retry("network", || client.get_network_connections()) {
+        Ok(data) => parse_connections_per_node(&data, labels),
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch network connections");
-            return result;
+            ConnectionsMap::new()
         }
-    };
+    }
+}
+
+/// Parse connections response: array of {id, connections: [{type, network_address, ...}]}
+/// Returns map of node_id → counts by protocol, by protocol version and by
+/// subnet label. The version breakdown only covers connections that report a
+/// version, and the subnet breakdown is left empty when no `[subnets]` are
+/// configured.
+fn parse_connections_per_node(data: &Value, labels: &SubnetLabels) -> ConnectionsMap {
+    let mut result = ConnectionsMap::new();
 
     let nodes = match data.as_array() {
         Some(a) => a,
@@ -807,23 +1718,32 @@ fn fetch_connections_per_node(
         let conns = match node["connections"].as_array() {
             Some(c) => c,
             None => {
-                result.insert(node_id, (0, std::collections::HashMap::new()));
+                result.insert(node_id, Default::default());
                 continue;
             }
         };
 
-        let total = conns.len() as u32;
-        let mut breakdown: std::collections::HashMap<String, u32> =
-            std::collections::HashMap::new();
+        let mut counts = NodeConnections {
+            total: conns.len() as u32,
+            ..Default::default()
+        };
 
         for conn in conns {
             if let Some(conn_type) = conn["type"].as_str() {
                 let protocol = normalize_connection_type(conn_type);
-                *breakdown.entry(protocol).or_insert(0) += 1;
+                if let Some(version) = connection_version(conn, &protocol) {
+                    *counts.by_version.entry(version).or_insert(0) += 1;
+                }
+                *counts.by_protocol.entry(protocol).or_insert(0) += 1;
+            }
+            if !labels.is_empty() {
+                let address = conn["network_address"].as_str().unwrap_or("");
+                let label = labels.label(address).unwrap_or(UNLABELED);
+                *counts.by_subnet.entry(label.to_string()).or_insert(0) += 1;
             }
         }
 
-        result.insert(node_id, (total, breakdown));
+        result.insert(node_id, counts);
     }
 
     result
@@ -836,6 +1756,43 @@ fn normalize_connection_type(raw: &str) -> String {
         .to_string()
 }
 
+/// Fields a connection's protocol version may be reported under, depending
+/// on the cluster version
+const VERSION_FIELDS: &[&str] = &["protocol_version", "version", "nfs_version", "dialect"];
+
+/// Protocol plus version for a connection, e.g. "NFSv3", "NFSv4.1" or
+/// "SMB3". NFS keeps the minor version (4.0 and 4.1 differ a lot); SMB
+/// dialects are grouped by major version. None when the cluster doesn't
+/// report a version.
+fn connection_version(conn: &Value, protocol: &str) -> Option<String> {
+    let raw = VERSION_FIELDS
+        .iter()
+        .find_map(|field| match &conn[*field] {
+            Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })?;
+    // Accept bare numbers ("4.1", "3") as well as prefixed forms like
+    // "NFSV4_1", "NFSv3" or "SMB3_1_1"
+    let upper = raw.to_ascii_uppercase();
+    let number = upper
+        .trim_start_matches(protocol)
+        .trim_start_matches("_V")
+        .trim_start_matches('V')
+        .trim_start_matches('_')
+        .replace('_', ".");
+    let mut parts = number.split('.').filter(|p| !p.is_empty());
+    let major: u32 = parts.next()?.parse().ok()?;
+    match protocol {
+        "NFS" => match parts.next().and_then(|m| m.parse::<u32>().ok()) {
+            Some(minor) if major >= 4 => Some(format!("NFSv{}.{}", major, minor)),
+            _ => Some(format!("NFSv{}", major)),
+        },
+        "SMB" => Some(format!("SMB{}", major)),
+        _ => Some(format!("{}v{}", protocol, major)),
+    }
+}
+
 /// Fetch NIC stats for each node. For on-prem, also extracts link speed and computes utilization.
 /// Uses the bond0 device (primary frontend/backend interface).
 /// Returns map of node_id → (throughput_bps, link_speed_bps, utilization_pct, raw_bytes_total)
@@ -846,12 +1803,13 @@ fn fetch_nic_stats_per_node(
     client: &QumuloClient,
     cluster_type: &ClusterType,
     watch_mode: bool,
+    degraded: &Degraded,
 ) -> NicStatsMap {
     let mut result = std::collections::HashMap::new();
     let is_cloud = matches!(cluster_type, ClusterType::CnqAws | ClusterType::AnqAzure);
 
     // First call to get baseline byte counters
-    let data1 = match client.get_network_status() {
+    let data1 = match degraded.retry("network", || client.get_network_status()) {
         Ok(v) => v,
         Err(e) => {
             tracing::warn!(error = %e, "failed to fetch network status");
@@ -1026,7 +1984,7 @@ fn sum_activity_rates(resp: &Value, activity_type: &str) -> f64 {
         .unwrap_or(0.0)
 }
 
-pub(super) fn build_aggregates(clusters: &[ClusterStatus]) -> Aggregates {
+pub(crate) fn build_aggregates(clusters: &[ClusterStatus]) -> Aggregates {
     let reachable_count = clusters.iter().filter(|c| c.reachable).count();
     let total_nodes: usize = clusters.iter().map(|c| c.nodes.total).sum();
     let online_nodes: usize = clusters.iter().map(|c| c.nodes.online).sum();
@@ -1080,6 +2038,36 @@ fn parse_disk_health(slots: &Value) -> (usize, Vec<UnhealthyDisk>) {
     (count, unhealthy)
 }
 
+/// Parse per-SSD wear from the ssd-endurance response.
+///
+/// The endpoint's response schema isn't published, so this accepts either a
+/// bare array or an `entries` wrapper, and reads wear as a used percentage
+/// (`percent_used`, `wear_level`) or derives it from a remaining percentage
+/// (`percent_remaining`, `endurance_remaining`). Entries with neither are dropped.
+fn parse_ssd_endurance(resp: &Value) -> Vec<WornDisk> {
+    let entries = resp
+        .as_array()
+        .or_else(|| resp["entries"].as_array())
+        .map(|a| a.as_slice())
+        .unwrap_or(&[]);
+    let pct = |entry: &Value, keys: &[&str]| keys.iter().find_map(|k| entry[*k].as_f64());
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let wear_pct = pct(entry, &["percent_used", "wear_level"]).or_else(|| {
+                pct(entry, &["percent_remaining", "endurance_remaining"]).map(|r| 100.0 - r)
+            })?;
+            let id = match &entry["id"] {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                _ => "unknown".to_string(),
+            };
+            Some(WornDisk { id, wear_pct })
+        })
+        .collect()
+}
+
 #[allow(dead_code)] // Preserved for dedicated PSU commands (separate bead)
 /// Parse PSU health from a chassis JSON array.
 fn parse_psu_health(chassis: &Value) -> (usize, Vec<UnhealthyPsu>) {
@@ -1126,6 +2114,195 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    fn empty_health() -> HealthStatus {
+        HealthStatus {
+            status: HealthLevel::Healthy,
+            issues: vec![],
+            disks_unhealthy: 0,
+            psus_unhealthy: 0,
+            data_at_risk: false,
+            remaining_node_failures: None,
+            remaining_drive_failures: None,
+            protection_type: None,
+            unhealthy_disk_details: vec![],
+            unhealthy_psu_details: vec![],
+            worn_disks: vec![],
+            directory: None,
+        }
+    }
+
+    #[test]
+    fn test_assess_health_recomputes_issues() {
+        let nodes = NodeStatus {
+            total: 4,
+            online: 3,
+            offline_nodes: vec![4],
+            details: vec![],
+        };
+        let capacity = CapacityStatus {
+            used_pct: 50.0,
+            ..Default::default()
+        };
+        let mut health = empty_health();
+        health.issues.push("stale issue".to_string());
+        assess_health(&nodes, &capacity, &mut health);
+        assert_eq!(health.issues, vec!["1 of 4 nodes offline"]);
+        assert!(matches!(health.status, HealthLevel::Degraded));
+
+        health.data_at_risk = true;
+        assess_health(&nodes, &capacity, &mut health);
+        assert_eq!(health.issues.len(), 2);
+        assert!(matches!(health.status, HealthLevel::Critical));
+    }
+
+    #[test]
+    fn test_assess_health_healthy() {
+        let nodes = NodeStatus {
+            total: 4,
+            online: 4,
+            offline_nodes: vec![],
+            details: vec![],
+        };
+        let mut health = empty_health();
+        assess_health(&nodes, &CapacityStatus::default(), &mut health);
+        assert!(health.issues.is_empty());
+        assert!(matches!(health.status, HealthLevel::Healthy));
+    }
+
+    #[test]
+    fn test_parse_top_activity_groups_and_ranks() {
+        let current = json!({
+            "entries": [
+                {"id": "100", "ip": "10.0.0.5", "rate": 50.0, "type": "file-iops-read"},
+                {"id": "100", "ip": "10.0.0.6", "rate": 30.0, "type": "metadata-iops-write"},
+                {"id": "100", "ip": "10.0.0.5", "rate": 4096.0, "type": "file-throughput-read"},
+                {"id": "200", "ip": "10.0.0.6", "rate": 200.0, "type": "file-iops-write"},
+                {"id": "300", "ip": "10.0.0.7", "rate": 1.0, "type": "file-iops-read"}
+            ]
+        });
+        let top = parse_top_activity(&current, 2);
+        assert_eq!(top.paths.len(), 2);
+        assert_eq!(top.paths[0].name, "200");
+        assert_eq!(top.paths[0].iops, 200.0);
+        assert_eq!(top.paths[1].name, "100");
+        assert_eq!(top.paths[1].iops, 80.0);
+        assert_eq!(top.paths[1].throughput, 4096.0);
+
+        assert_eq!(top.clients[0].name, "10.0.0.6");
+        assert_eq!(top.clients[0].iops, 230.0);
+        assert_eq!(top.clients[1].name, "10.0.0.5");
+    }
+
+    #[test]
+    fn test_parse_top_activity_empty() {
+        let top = parse_top_activity(&json!({"entries": []}), 5);
+        assert!(top.paths.is_empty());
+        assert!(top.clients.is_empty());
+    }
+
+    #[test]
+    fn test_parse_largest_paths_sorted_and_limited() {
+        let details = json!({
+            "status": "AVAILABLE",
+            "largest_paths": [
+                {"path": "/scratch/", "capacity_used": "1000"},
+                {"path": "/home/", "capacity_used": "5000"},
+                {"path": "/media/", "capacity_used": "3000"}
+            ]
+        });
+        let paths = parse_largest_paths(&details, 2);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].path, "/home/");
+        assert_eq!(paths[0].used_bytes, 5000);
+        assert_eq!(paths[1].path, "/media/");
+    }
+
+    #[test]
+    fn test_parse_largest_paths_pending() {
+        let details = json!({
+            "status": "PENDING",
+            "largest_paths": [{"path": "/home/", "capacity_used": "5000"}]
+        });
+        assert!(parse_largest_paths(&details, 5).is_empty());
+    }
+
+    #[test]
+    fn test_largest_directories_skip_root() {
+        let dirs = vec![
+            json!({"path": "/", "total_capacity": "9000"}),
+            json!({"path": "/home/", "total_capacity": "1000"}),
+            json!({"path": "/data/", "total_capacity": "8000"}),
+            json!({"path": "/tmp/", "total_capacity": "10"}),
+        ];
+        let paths = largest_directories(&dirs, 2);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].path, "/data/");
+        assert_eq!(paths[1].path, "/home/");
+    }
+
+    #[test]
+    fn test_aggregates_budget_from_config() {
+        let mut collect = CollectConfig::default();
+        assert!(aggregates_budget(&collect).is_none());
+        collect.aggregates_max_depth = Some(0);
+        assert!(aggregates_budget(&collect).is_none());
+        collect.aggregates_max_depth = Some(2);
+        collect.aggregates_max_entries = Some(50);
+        let budget = aggregates_budget(&collect).unwrap();
+        assert_eq!(budget.max_depth, 2);
+        assert_eq!(budget.max_entries, 50);
+    }
+
+    #[test]
+    fn test_parse_ssd_endurance_used_and_remaining() {
+        let resp = json!([
+            {"id": "1.3", "percent_used": 91.0},
+            {"id": 7, "percent_remaining": 40.0},
+            {"id": "2.1", "model": "no wear field"}
+        ]);
+        let disks = parse_ssd_endurance(&resp);
+        assert_eq!(disks.len(), 2);
+        assert_eq!(disks[0].id, "1.3");
+        assert_eq!(disks[0].wear_pct, 91.0);
+        assert_eq!(disks[1].id, "7");
+        assert_eq!(disks[1].wear_pct, 60.0);
+    }
+
+    #[test]
+    fn test_parse_ad_status_joined() {
+        let resp = json!({
+            "status": "JOINED",
+            "domain": "ad.example.com",
+            "dcs": [{"name": "DC1", "address": "10.0.0.5"}, {"address": "10.0.0.6"}],
+            "ldap_connection_states": [
+                {"node_id": 2, "servers": [{"bind_uri": "ldap://10.0.0.5", "status": "DISCONNECTED"}]},
+                {"node_id": 1, "servers": [{"bind_uri": "ldap://10.0.0.5", "status": "CONNECTED"}]},
+                {"node_id": 3, "servers": []},
+                {"node_id": 4, "servers": [{"bind_uri": "ldap://10.0.0.6"}]}
+            ]
+        });
+        let dir = parse_ad_status(&resp).unwrap();
+        assert_eq!(dir.domain, "ad.example.com");
+        assert_eq!(dir.status, "JOINED");
+        assert_eq!(dir.domain_controllers, vec!["DC1", "10.0.0.6"]);
+        assert_eq!(dir.disconnected_nodes, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_parse_ad_status_not_joined() {
+        assert!(parse_ad_status(&json!({"status": "NOT_IN_DOMAIN"})).is_none());
+        assert!(parse_ad_status(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_parse_ssd_endurance_entries_wrapper() {
+        let resp = json!({"entries": [{"id": "1.1", "wear_level": 12.5}]});
+        let disks = parse_ssd_endurance(&resp);
+        assert_eq!(disks.len(), 1);
+        assert_eq!(disks[0].wear_pct, 12.5);
+        assert!(parse_ssd_endurance(&json!({})).is_empty());
+    }
+
     #[test]
     fn test_parse_disk_health_all_healthy() {
         let slots = json!([
@@ -1348,6 +2525,66 @@ mod tests {
         assert_eq!(*count, 0);
     }
 
+    #[test]
+    fn test_parse_connections_by_subnet_label() {
+        let data = json!([
+            {
+                "id": 1,
+                "connections": [
+                    {"type": "CONNECTION_TYPE_NFS", "network_address": "10.20.0.1"},
+                    {"type": "CONNECTION_TYPE_NFS", "network_address": "10.20.3.9"},
+                    {"type": "CONNECTION_TYPE_SMB", "network_address": "192.168.1.4"}
+                ]
+            }
+        ]);
+
+        let unlabeled = parse_connections_per_node(&data, &SubnetLabels::default());
+        let counts = &unlabeled[&1];
+        assert_eq!(counts.total, 3);
+        assert_eq!(counts.by_protocol["NFS"], 2);
+        assert!(
+            counts.by_subnet.is_empty(),
+            "no [subnets], no subnet breakdown"
+        );
+        assert!(counts.by_version.is_empty(), "no versions reported");
+
+        let table = std::collections::BTreeMap::from([(
+            "10.20.0.0/16".to_string(),
+            "render-farm".to_string(),
+        )]);
+        let labels = SubnetLabels::new(&table).unwrap();
+        let subnets = &parse_connections_per_node(&data, &labels)[&1].by_subnet;
+        assert_eq!(subnets["render-farm"], 2);
+        assert_eq!(subnets[UNLABELED], 1);
+    }
+
+    #[test]
+    fn test_parse_connections_by_protocol_version() {
+        let data = json!([
+            {
+                "id": 1,
+                "connections": [
+                    {"type": "CONNECTION_TYPE_NFS", "protocol_version": "NFSV3"},
+                    {"type": "CONNECTION_TYPE_NFS", "version": "3"},
+                    {"type": "CONNECTION_TYPE_NFS", "nfs_version": "NFSV4_1"},
+                    {"type": "CONNECTION_TYPE_NFS", "version": 4.1},
+                    {"type": "CONNECTION_TYPE_SMB", "dialect": "SMB2_1"},
+                    {"type": "CONNECTION_TYPE_SMB", "dialect": "SMB3_1_1"},
+                    {"type": "CONNECTION_TYPE_SMB", "dialect": "3.0.2"},
+                    {"type": "CONNECTION_TYPE_REST"}
+                ]
+            }
+        ]);
+
+        let counts = &parse_connections_per_node(&data, &SubnetLabels::default())[&1];
+        assert_eq!(counts.total, 8);
+        assert_eq!(counts.by_version["NFSv3"], 2);
+        assert_eq!(counts.by_version["NFSv4.1"], 2);
+        assert_eq!(counts.by_version["SMB2"], 1);
+        assert_eq!(counts.by_version["SMB3"], 2);
+        assert_eq!(counts.by_version.len(), 4, "REST has no version");
+    }
+
     #[test]
     fn test_extract_bond0_stats_on_prem() {
         let node = json!({
@@ -1677,6 +2914,7 @@ mod tests {
             throughput_write: 0.0,
             connections: 0,
             is_idle: true,
+            top: None,
         };
         assert!(activity.is_idle);
     }
@@ -1690,6 +2928,7 @@ mod tests {
             throughput_write: 0.0,
             connections: 0,
             is_idle: false,
+            top: None,
         };
         assert!(!activity.is_idle);
     }
@@ -1753,6 +2992,8 @@ mod tests {
                 reachable: true,
                 stale: false,
                 latency_ms: 0,
+                degraded: Vec::new(),
+                partial: false,
                 nodes: NodeStatus {
                     total: 3,
                     online: 3,
@@ -1766,6 +3007,7 @@ mod tests {
                     total_directories: 10,
                     total_snapshots: 5,
                     snapshot_bytes: 1000,
+                    largest_directories: Vec::new(),
                 },
                 health: HealthStatus {
                     status: HealthLevel::Healthy,
@@ -1778,6 +3020,8 @@ mod tests {
                     protection_type: None,
                     unhealthy_disk_details: vec![],
                     unhealthy_psu_details: vec![],
+                    worn_disks: vec![],
+                    directory: None,
                 },
             },
             ClusterStatus {
@@ -1789,6 +3033,8 @@ mod tests {
                 reachable: true,
                 stale: false,
                 latency_ms: 0,
+                degraded: Vec::new(),
+                partial: false,
                 nodes: NodeStatus {
                     total: 5,
                     online: 5,
@@ -1802,6 +3048,7 @@ mod tests {
                     total_directories: 20,
                     total_snapshots: 10,
                     snapshot_bytes: 2000,
+                    largest_directories: Vec::new(),
                 },
                 health: HealthStatus {
                     status: HealthLevel::Healthy,
@@ -1814,6 +3061,8 @@ mod tests {
                     protection_type: None,
                     unhealthy_disk_details: vec![],
                     unhealthy_psu_details: vec![],
+                    worn_disks: vec![],
+                    directory: None,
                 },
             },
         ];
@@ -1826,4 +3075,123 @@ mod tests {
         assert_eq!(agg.online_nodes, 8);
         assert_eq!(agg.reachable_count, 2);
     }
+
+    #[test]
+    fn test_limiter_bounds_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let limiter = Limiter::new(2);
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    let _permit = limiter.acquire();
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*limiter.available.lock().unwrap(), 2);
+
+        // Zero is treated as one rather than blocking forever
+        let serial = Limiter::new(0);
+        drop(serial.acquire());
+        drop(serial.acquire());
+    }
+
+    #[test]
+    fn test_calls_records_timings_only_when_enabled() {
+        let limiter = Limiter::new(1);
+        let calls = Calls::new("prod", &limiter, true, None);
+        assert_eq!(calls.run("get_activity", || 7), 7);
+        calls.record("get_version", 12);
+        let timings = calls.into_timings();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].cluster, "prod");
+        assert_eq!(timings[1].duration_ms, 12);
+
+        let calls = Calls::new("prod", &limiter, false, None);
+        calls.run("get_activity", || ());
+        assert!(calls.into_timings().is_empty());
+    }
+
+    fn api_error(status: u16) -> anyhow::Error {
+        QontrolError::ApiError {
+            status,
+            method: "GET".to_string(),
+            path: "/v1/cluster/slots/".to_string(),
+            body: String::new(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_degraded_retries_transient_failures_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let degraded = Degraded::default();
+        let attempts = AtomicUsize::new(0);
+
+        // A 503 then success: retried, and not degraded
+        let result = degraded.retry("disks", || match attempts.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(api_error(503)),
+            n => Ok(n),
+        });
+        assert_eq!(result.unwrap(), 1);
+        assert!(degraded.groups().is_empty());
+
+        // A persistent 500: two attempts, then degraded
+        attempts.store(0, Ordering::SeqCst);
+        let result: Result<()> = degraded.retry("psus", || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(api_error(500))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(degraded.groups(), vec!["psus"]);
+    }
+
+    #[test]
+    fn test_degraded_skips_retry_for_client_errors() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let degraded = Degraded::default();
+        let attempts = AtomicUsize::new(0);
+        for status in [403, 404] {
+            let result: Result<()> = degraded.retry(&status.to_string(), || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(api_error(status))
+            });
+            assert!(result.is_err());
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        // A missing endpoint isn't missing data
+        assert_eq!(degraded.groups(), vec!["403"]);
+    }
+
+    #[test]
+    fn test_degraded_abandons_calls_past_deadline() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let attempts = AtomicUsize::new(0);
+        let call = || -> Result<()> {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        };
+
+        let degraded = Degraded::with_deadline(Some(Instant::now() + Duration::from_secs(60)));
+        assert!(degraded.retry("disks", call).is_ok());
+        assert!(!degraded.over_budget());
+
+        let degraded = Degraded::with_deadline(Some(Instant::now()));
+        assert!(degraded.retry("disks", call).is_err());
+        assert!(degraded.retry("psus", call).is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert!(degraded.over_budget());
+        assert_eq!(degraded.groups(), vec!["disks", "psus"]);
+    }
 }
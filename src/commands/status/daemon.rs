@@ -0,0 +1,147 @@
+//! `qontrol daemon`: collect fleet status on a schedule with no terminal.
+//!
+//! Each poll is collected like `fleet status --watch` (incremental polls
+//! between full refreshes, NIC throughput from the previous poll's
+//! counters), written to the status cache and the history database,
+//! checked for alert transitions, and handed to `[notify]` webhooks and any
+//! `--sink`s. `fleet status --cached` then reads what the daemon last wrote
+//! without contacting any cluster, `--listen` serves it over HTTP (see
+//! [`super::serve`]), and `history show` charts what it recorded.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::config::Config;
+
+use super::types::EnvironmentStatus;
+use super::{cache, collector, json, sink, track_nic_deltas, WatchState};
+
+/// Run the collection loop until interrupted (Ctrl+C or SIGTERM), or for a
+/// single poll with `once`.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    config: &Config,
+    profiles: &[String],
+    interval: u64,
+    timeout_secs: u64,
+    full_refresh_every: u64,
+    jobs: usize,
+    cluster_budget: Option<&str>,
+    sinks: &[String],
+    once: bool,
+    quiet: bool,
+) -> Result<()> {
+    if interval == 0 {
+        anyhow::bail!("--interval must be at least 1 second");
+    }
+    let cluster_budget = cluster_budget
+        .map(crate::commands::maintenance::parse_duration)
+        .transpose()?
+        .map(|budget| budget.to_std().unwrap_or_default());
+    let sinks = sinks
+        .iter()
+        .map(|spec| sink::Sink::parse(spec))
+        .collect::<Result<Vec<_>>>()?;
+    crate::notify::validate(&config.notify)?;
+
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || {
+        r.store(false, Ordering::SeqCst);
+    })
+    .ok();
+
+    let mut previous: Option<EnvironmentStatus> = None;
+    let mut watch_state: Option<WatchState> = None;
+    let mut poll: u64 = 0;
+
+    loop {
+        let started = Instant::now();
+        let full_refresh = poll.is_multiple_of(full_refresh_every.max(1));
+        let collected = collector::collect_all(
+            config,
+            profiles,
+            collector::CollectOptions {
+                watch_mode: true,
+                previous: previous.as_ref().filter(|_| !full_refresh),
+                json_mode: true,
+                suppress_progress: true,
+                jobs,
+                cluster_budget,
+                ..collector::CollectOptions::new(timeout_secs)
+            },
+        );
+        match collected {
+            Ok((mut status, _)) => {
+                track_nic_deltas(&mut watch_state, &mut status);
+                // The collector cached each cluster before throughput was
+                // known; rewrite it so cached reads show it too
+                let fresh = status.clusters.iter().filter(|c| c.reachable && !c.stale);
+                for cluster in fresh.filter(|_| poll > 0) {
+                    if let Err(e) = cache::write_cache(&cluster.profile, cluster) {
+                        tracing::warn!(profile = %cluster.profile, error = %e, "failed to write cache");
+                    }
+                }
+
+                let changes = match crate::commands::alert::record_transitions(&status) {
+                    Ok(transitions) => {
+                        crate::notify::notify(&config.notify, &transitions);
+                        transitions.len()
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "failed to update alert history");
+                        0
+                    }
+                };
+                if let Err(e) = crate::commands::history::record(&status) {
+                    tracing::warn!(error = %e, "failed to record status history");
+                }
+                sink::deliver_all(&sinks, &json::JsonOutput::from_status(&status))?;
+
+                if !quiet {
+                    eprintln!("{}", poll_line(&status, changes, started.elapsed()));
+                }
+                previous = Some(status);
+            }
+            // A bad config is fatal; later failures are logged and retried
+            Err(e) if poll == 0 => return Err(e),
+            Err(e) => eprintln!("{} poll failed: {:#}", timestamp(), e),
+        }
+        poll += 1;
+
+        if once {
+            return Ok(());
+        }
+        let next = started + Duration::from_secs(interval);
+        while Instant::now() < next {
+            if !running.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        if !running.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+    }
+}
+
+fn timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// One stderr line per poll, for the journal or a log file
+fn poll_line(status: &EnvironmentStatus, changes: usize, elapsed: Duration) -> String {
+    format!(
+        "{} polled {}/{} clusters, {} alerts, {} alert changes ({}ms)",
+        timestamp(),
+        status.aggregates.reachable_count,
+        status.aggregates.cluster_count,
+        status.alerts.len(),
+        changes,
+        elapsed.as_millis()
+    )
+}
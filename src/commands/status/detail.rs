@@ -0,0 +1,499 @@
+//! `qontrol status <profile>`: one cluster's status, and with `--detail`
+//! what the fleet view leaves out — per-node drives and raw capacity, the
+//! protection layout, tenants, the largest shares and exports, and
+//! replication relationships — on one page for incident triage.
+//!
+//! Detail sections are fetched after the status. A section whose endpoints
+//! fail is reported as unavailable (null in JSON) rather than failing the
+//! command.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use console::Style;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::client::QumuloClient;
+use crate::commands::cluster::{failures_label, protection_label, stripe_label};
+use crate::config::Config;
+use crate::output::print_json;
+
+use super::renderer::{self, format_bytes, HEADER_WIDTH};
+use super::{collector, json::JsonOutput};
+
+/// Shares and exports shown, biggest first
+const TOP_SHARES: usize = 10;
+/// Distinct share and export paths whose capacity is looked up
+const MAX_SHARE_LOOKUPS: usize = 50;
+
+/// Everything `--detail` adds for one cluster.
+#[derive(Debug, Serialize)]
+pub struct ClusterDetail {
+    pub nodes: Option<Vec<NodeDetail>>,
+    pub protection: Option<Value>,
+    pub tenants: Option<Vec<Tenant>>,
+    pub shares: Option<Vec<ShareUsage>>,
+    pub replication: Option<Vec<ReplicationEdge>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeDetail {
+    pub id: u64,
+    pub name: String,
+    pub status: String,
+    pub drives: usize,
+    pub unhealthy_drives: usize,
+    /// Sum of the node's drive capacities
+    pub raw_capacity_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Tenant {
+    pub id: u64,
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// An SMB share or NFS export and the capacity used under its path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShareUsage {
+    /// `smb` or `nfs`
+    pub protocol: String,
+    pub name: String,
+    pub path: String,
+    /// None when the path's aggregates couldn't be read
+    pub capacity_bytes: Option<u64>,
+}
+
+/// A replication relationship from this cluster's point of view.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplicationEdge {
+    /// `outbound` when this cluster is the source, `inbound` when the target
+    pub direction: String,
+    pub peer: String,
+    pub local_path: String,
+    pub peer_path: String,
+    pub state: String,
+    pub recovery_point: Option<String>,
+}
+
+/// Show one cluster's status, with the detail sections when `detail` is set.
+pub fn run(
+    config: &Config,
+    profile: &str,
+    client: &QumuloClient,
+    detail: bool,
+    timeout_secs: u64,
+    json_mode: bool,
+) -> Result<()> {
+    let (status, _) = collector::collect_all(
+        config,
+        &[profile.to_string()],
+        collector::CollectOptions {
+            json_mode,
+            jobs: collector::DEFAULT_JOBS,
+            ..collector::CollectOptions::new(timeout_secs)
+        },
+    )?;
+    let Some(cluster) = status.clusters.first() else {
+        anyhow::bail!("no status collected for profile '{}'", profile);
+    };
+    let detail = (detail && cluster.reachable).then(|| fetch_detail(client));
+
+    if json_mode {
+        let mut output = serde_json::to_value(JsonOutput::from_status(&status))?;
+        if let Some(ref detail) = detail {
+            output["detail"] = serde_json::to_value(detail)?;
+        }
+        print_json(&output)?;
+        return Ok(());
+    }
+
+    let mut out = String::new();
+    renderer::render_cluster(&mut out, cluster, &status, None);
+    out.push('\n');
+    renderer::render_alerts(&mut out, &status);
+    if let Some(ref detail) = detail {
+        render_detail(&mut out, detail);
+    }
+    print!("{}", out);
+    Ok(())
+}
+
+fn fetch_detail(client: &QumuloClient) -> ClusterDetail {
+    let section = |name: &str, result: Result<Value>| match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!(section = name, error = %e, "failed to fetch status detail");
+            None
+        }
+    };
+    let nodes = section("nodes", client.get_cluster_nodes())
+        .zip(section("slots", client.get_cluster_slots()))
+        .map(|(nodes, slots)| node_details(&nodes, &slots));
+    let protection = section("protection", client.get_cluster_protection_status());
+    let tenants = section("tenants", client.get_tenants()).map(|t| parse_tenants(&t));
+    let shares = section("smb_shares", client.get_smb_shares())
+        .zip(section("nfs_exports", client.get_nfs_exports()))
+        .map(|(smb, nfs)| top_shares(client, &smb, &nfs));
+    let replication = section(
+        "replication_sources",
+        client.get_replication_source_statuses(),
+    )
+    .zip(section(
+        "replication_targets",
+        client.get_replication_target_statuses(),
+    ))
+    .map(|(sources, targets)| replication_edges(&sources, &targets));
+
+    ClusterDetail {
+        nodes,
+        protection,
+        tenants,
+        shares,
+        replication,
+    }
+}
+
+/// The API reports sizes and IDs as decimal strings.
+fn parse_u64(v: &Value) -> Option<u64> {
+    v.as_str()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| v.as_u64())
+}
+
+fn str_field(v: &Value, key: &str) -> String {
+    v[key].as_str().unwrap_or("-").to_string()
+}
+
+/// Drive counts and raw capacity per node, from the node list and slots.
+fn node_details(nodes: &Value, slots: &Value) -> Vec<NodeDetail> {
+    let mut by_node: BTreeMap<u64, (usize, usize, u64)> = BTreeMap::new();
+    for slot in slots.as_array().into_iter().flatten() {
+        let Some(node_id) = parse_u64(&slot["node_id"]) else {
+            continue;
+        };
+        // Empty bays are listed too
+        if slot["state"].as_str() == Some("empty") {
+            continue;
+        }
+        let entry = by_node.entry(node_id).or_default();
+        entry.0 += 1;
+        if slot["state"].as_str() != Some("healthy") {
+            entry.1 += 1;
+        }
+        entry.2 += parse_u64(&slot["capacity"]).unwrap_or(0);
+    }
+    nodes
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| {
+            let id = parse_u64(&node["id"])?;
+            let (drives, unhealthy_drives, raw_capacity_bytes) =
+                by_node.get(&id).copied().unwrap_or_default();
+            Some(NodeDetail {
+                id,
+                name: str_field(node, "node_name"),
+                status: str_field(node, "node_status"),
+                drives,
+                unhealthy_drives,
+                raw_capacity_bytes,
+            })
+        })
+        .collect()
+}
+
+fn parse_tenants(tenants: &Value) -> Vec<Tenant> {
+    // Listed bare or under `entries`, depending on the API version
+    let list = tenants.get("entries").unwrap_or(tenants);
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| {
+            Some(Tenant {
+                id: parse_u64(&t["id"])?,
+                name: str_field(t, "name"),
+                enabled: t["enabled"].as_bool().unwrap_or(true),
+            })
+        })
+        .collect()
+}
+
+/// SMB shares and NFS exports as (protocol, name, path)
+fn list_shares(smb: &Value, nfs: &Value) -> Vec<(&'static str, String, String)> {
+    let smb = smb.as_array().into_iter().flatten().map(|s| {
+        let name = str_field(s, "share_name");
+        ("smb", name, str_field(s, "fs_path"))
+    });
+    let nfs = nfs.as_array().into_iter().flatten().map(|e| {
+        let name = str_field(e, "export_path");
+        ("nfs", name, str_field(e, "fs_path"))
+    });
+    smb.chain(nfs).collect()
+}
+
+/// The biggest shares and exports by capacity used under their paths.
+fn top_shares(client: &QumuloClient, smb: &Value, nfs: &Value) -> Vec<ShareUsage> {
+    let shares = list_shares(smb, nfs);
+    let mut capacity: BTreeMap<&str, Option<u64>> = BTreeMap::new();
+    for (_, _, path) in &shares {
+        if capacity.len() >= MAX_SHARE_LOOKUPS || capacity.contains_key(path.as_str()) {
+            continue;
+        }
+        let used = match client.get_file_aggregates(path) {
+            Ok(aggregates) => parse_u64(&aggregates["total_capacity"]),
+            Err(e) => {
+                tracing::debug!(%path, error = %e, "failed to read share aggregates");
+                None
+            }
+        };
+        capacity.insert(path, used);
+    }
+    let mut usage: Vec<ShareUsage> = shares
+        .iter()
+        .map(|(protocol, name, path)| ShareUsage {
+            protocol: protocol.to_string(),
+            name: name.clone(),
+            path: path.clone(),
+            capacity_bytes: capacity.get(path.as_str()).copied().flatten(),
+        })
+        .collect();
+    sort_shares(&mut usage);
+    usage.truncate(TOP_SHARES);
+    usage
+}
+
+/// Biggest first; unknown sizes last, by name
+fn sort_shares(shares: &mut [ShareUsage]) {
+    shares.sort_by(|a, b| {
+        b.capacity_bytes
+            .cmp(&a.capacity_bytes)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+fn replication_edges(sources: &Value, targets: &Value) -> Vec<ReplicationEdge> {
+    let edge =
+        |direction: &str, r: &Value, peer: &str, local: &str, remote: &str| ReplicationEdge {
+            direction: direction.to_string(),
+            peer: str_field(r, peer),
+            local_path: str_field(r, local),
+            peer_path: str_field(r, remote),
+            state: r["job_state"]
+                .as_str()
+                .or_else(|| r["state"].as_str())
+                .unwrap_or("-")
+                .trim_start_matches("REPLICATION_")
+                .to_lowercase(),
+            recovery_point: r["recovery_point"].as_str().map(str::to_string),
+        };
+    let outbound = sources.as_array().into_iter().flatten().map(|r| {
+        edge(
+            "outbound",
+            r,
+            "target_cluster_name",
+            "source_root_path",
+            "target_root_path",
+        )
+    });
+    let inbound = targets.as_array().into_iter().flatten().map(|r| {
+        edge(
+            "inbound",
+            r,
+            "source_cluster_name",
+            "target_root_path",
+            "source_root_path",
+        )
+    });
+    outbound.chain(inbound).collect()
+}
+
+fn section_header(out: &mut String, title: &str) {
+    let bold = Style::new().bold();
+    let title = format!("═══ {} ", title);
+    let padding = HEADER_WIDTH.saturating_sub(title.len());
+    out.push_str(&format!(
+        "\n{}\n",
+        bold.apply_to(format!("{}{}", title, "═".repeat(padding)))
+    ));
+}
+
+fn render_detail(out: &mut String, detail: &ClusterDetail) {
+    let dim = Style::new().dim();
+    let red = Style::new().red();
+    let unavailable = format!("  {}\n", dim.apply_to("unavailable"));
+
+    section_header(out, "Nodes");
+    match detail.nodes {
+        Some(ref nodes) => {
+            for node in nodes {
+                let unhealthy = if node.unhealthy_drives > 0 {
+                    format!(
+                        " ({})",
+                        red.apply_to(format!("{} unhealthy", node.unhealthy_drives))
+                    )
+                } else {
+                    String::new()
+                };
+                out.push_str(&format!(
+                    "  {:>3}  {:<24} {:<8} {:>3} drives{}  {} raw\n",
+                    node.id,
+                    node.name,
+                    node.status,
+                    node.drives,
+                    unhealthy,
+                    format_bytes(node.raw_capacity_bytes),
+                ));
+            }
+        }
+        None => out.push_str(&unavailable),
+    }
+
+    section_header(out, "Protection");
+    match detail.protection {
+        Some(ref p) => {
+            out.push_str(&format!(
+                "  {}, {}\n",
+                protection_label(p["protection_system_type"].as_str().unwrap_or("")),
+                stripe_label(p)
+            ));
+            out.push_str(&format!(
+                "  Node failures:  {}\n  Drive failures: {}\n",
+                failures_label(&p["remaining_node_failures"], &p["max_node_failures"]),
+                failures_label(&p["remaining_drive_failures"], &p["max_drive_failures"]),
+            ));
+        }
+        None => out.push_str(&unavailable),
+    }
+
+    section_header(out, "Tenants");
+    match detail.tenants {
+        Some(ref tenants) if tenants.is_empty() => out.push_str("  none\n"),
+        Some(ref tenants) => {
+            for t in tenants {
+                let state = if t.enabled { "" } else { " (disabled)" };
+                out.push_str(&format!("  {:>3}  {}{}\n", t.id, t.name, state));
+            }
+        }
+        None => out.push_str(&unavailable),
+    }
+
+    section_header(out, "Top Shares and Exports");
+    match detail.shares {
+        Some(ref shares) if shares.is_empty() => out.push_str("  none\n"),
+        Some(ref shares) => {
+            for s in shares {
+                let size = s.capacity_bytes.map(format_bytes).unwrap_or("-".into());
+                out.push_str(&format!(
+                    "  {:>10}  {}  {:<24} {}\n",
+                    size, s.protocol, s.name, s.path
+                ));
+            }
+        }
+        None => out.push_str(&unavailable),
+    }
+
+    section_header(out, "Replication");
+    match detail.replication {
+        Some(ref edges) if edges.is_empty() => out.push_str("  none\n"),
+        Some(ref edges) => {
+            for e in edges {
+                let arrow = if e.direction == "outbound" {
+                    format!("{} → {}:{}", e.local_path, e.peer, e.peer_path)
+                } else {
+                    format!("{} ← {}:{}", e.local_path, e.peer, e.peer_path)
+                };
+                let recovery = e
+                    .recovery_point
+                    .as_deref()
+                    .map(|rp| format!("  recovery point {}", rp))
+                    .unwrap_or_default();
+                out.push_str(&format!("  {}  {}{}\n", arrow, e.state, recovery));
+            }
+        }
+        None => out.push_str(&unavailable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_node_details() {
+        let nodes = json!([
+            {"id": 1, "node_name": "n1", "node_status": "online"},
+            {"id": 2, "node_name": "n2", "node_status": "offline"}
+        ]);
+        let slots = json!([
+            {"node_id": 1, "capacity": "1000", "state": "healthy"},
+            {"node_id": 1, "capacity": "1000", "state": "missing"},
+            {"node_id": 1, "capacity": "0", "state": "empty"},
+            {"node_id": 2, "capacity": "500", "state": "healthy"}
+        ]);
+        let details = node_details(&nodes, &slots);
+        assert_eq!(details.len(), 2);
+        assert_eq!(details[0].drives, 2);
+        assert_eq!(details[0].unhealthy_drives, 1);
+        assert_eq!(details[0].raw_capacity_bytes, 2000);
+        assert_eq!(details[1].status, "offline");
+        assert_eq!(details[1].raw_capacity_bytes, 500);
+    }
+
+    #[test]
+    fn test_parse_tenants() {
+        let bare = json!([{"id": 1, "name": "Default", "enabled": true}]);
+        let paged = json!({"entries": [{"id": "2", "name": "lab", "enabled": false}]});
+        assert_eq!(parse_tenants(&bare)[0].name, "Default");
+        assert_eq!(
+            parse_tenants(&paged),
+            vec![Tenant {
+                id: 2,
+                name: "lab".to_string(),
+                enabled: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_sort_shares() {
+        let share = |name: &str, capacity_bytes: Option<u64>| ShareUsage {
+            protocol: "smb".to_string(),
+            name: name.to_string(),
+            path: format!("/{}", name),
+            capacity_bytes,
+        };
+        let mut shares = vec![share("b", None), share("a", Some(10)), share("c", Some(20))];
+        sort_shares(&mut shares);
+        let names: Vec<&str> = shares.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_replication_edges() {
+        let sources = json!([{
+            "target_cluster_name": "dr",
+            "source_root_path": "/data",
+            "target_root_path": "/replica",
+            "job_state": "REPLICATION_RUNNING",
+            "recovery_point": "2026-02-23T11:30:00Z"
+        }]);
+        let targets = json!([{
+            "source_cluster_name": "hq",
+            "source_root_path": "/home",
+            "target_root_path": "/hq-home",
+            "state": "ESTABLISHED"
+        }]);
+        let edges = replication_edges(&sources, &targets);
+        assert_eq!(edges[0].direction, "outbound");
+        assert_eq!(edges[0].peer, "dr");
+        assert_eq!(edges[0].state, "running");
+        assert_eq!(edges[1].direction, "inbound");
+        assert_eq!(edges[1].local_path, "/hq-home");
+        assert_eq!(edges[1].state, "established");
+        assert_eq!(edges[1].recovery_point, None);
+    }
+}
@@ -1,10 +1,110 @@
+use serde::Serialize;
+
 use super::capacity;
 use super::types::*;
+use crate::config::DEFAULT_DISK_WEAR_THRESHOLD;
+
+/// An alert category the engine can emit, for `alert categories`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertCategory {
+    pub category: &'static str,
+    /// Severity before maintenance windows downgrade it
+    pub severity: &'static str,
+    /// What has to be true for the alert to fire
+    pub threshold: String,
+    pub meaning: &'static str,
+}
+
+/// Every category `fleet status` can report, in check order. Kept next to
+/// the checks so a new one is documented where it is added.
+pub fn categories() -> Vec<AlertCategory> {
+    let category = |category, severity, threshold: String, meaning| AlertCategory {
+        category,
+        severity,
+        threshold,
+        meaning,
+    };
+    vec![
+        category(
+            "connectivity",
+            "critical",
+            "API unreachable; warning when cached data is shown".to_string(),
+            "qontrol could not reach the cluster's API",
+        ),
+        category(
+            "node_offline",
+            "critical",
+            "any node not online".to_string(),
+            "a node is down; one alert per node",
+        ),
+        category(
+            "data_at_risk",
+            "critical",
+            "restriper reports data at risk".to_string(),
+            "more failures than the protection tolerates; data may be lost",
+        ),
+        category(
+            "disk_unhealthy",
+            "warning",
+            "any drive not healthy".to_string(),
+            "drives need replacing",
+        ),
+        category(
+            "psu_unhealthy",
+            "warning",
+            "any PSU not good".to_string(),
+            "a power supply has failed or is missing",
+        ),
+        category(
+            "disk_wear",
+            "warning",
+            format!(
+                "SSD at {:.0}% of rated endurance (collect.disk_wear_threshold)",
+                DEFAULT_DISK_WEAR_THRESHOLD
+            ),
+            "plan a replacement before the drive fails; one alert per drive",
+        ),
+        category(
+            "directory_services",
+            "critical",
+            "AD not joined, no DC, or LDAP down on all nodes; warning on some".to_string(),
+            "SMB logins fail",
+        ),
+        category(
+            "protection_degraded",
+            "warning",
+            "0 node or drive failures remaining".to_string(),
+            "one more failure puts data at risk",
+        ),
+        category(
+            "capacity_projection",
+            "warning",
+            format!(
+                "projected full within {} days on-prem, {} days in the cloud",
+                capacity::ONPREM_WARN_DAYS,
+                capacity::CLOUD_WARN_DAYS
+            ),
+            "add capacity, or raise the capacity clamp in the cloud",
+        ),
+        category(
+            "degraded_collection",
+            "warning",
+            "an endpoint failed after retry, or the collection budget ran out".to_string(),
+            "some fields show defaults rather than readings",
+        ),
+        category(
+            "rule:<name>",
+            "per rule (warning by default)",
+            "the rule's `when` expression".to_string(),
+            "a custom rule from [[alert_rules]] matched",
+        ),
+    ]
+}
 
 /// Generate all alerts from collected cluster data and connectivity failures.
 ///
-/// Takes the successfully collected clusters and any pre-built connectivity alerts
-/// (for unreachable clusters). Scans each cluster's health, node status, capacity
+/// Takes the successfully collected clusters and any pre-built alerts
+/// (connectivity failures for unreachable clusters, custom rule matches). Scans each cluster's health, node status, capacity
 /// projection, and protection data to produce a prioritized, sorted alert list.
 ///
 /// Returns alerts sorted by severity: Critical first, then Warning, then Info.
@@ -25,8 +125,11 @@ fn generate_cluster_alerts(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
     check_data_at_risk(cluster, alerts);
     check_disk_health(cluster, alerts);
     check_psu_health(cluster, alerts);
+    check_disk_wear(cluster, alerts);
+    check_directory_services(cluster, alerts);
     check_protection_degraded(cluster, alerts);
     check_capacity_projection(cluster, alerts);
+    check_degraded_collection(cluster, alerts);
 }
 
 /// Offline nodes: one alert per offline node with the node ID.
@@ -125,6 +228,71 @@ fn check_psu_health(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
     }
 }
 
+/// Worn SSDs: one warning per drive past the wear threshold, so replacements
+/// can be planned before the drive fails.
+fn check_disk_wear(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
+    for disk in &cluster.health.worn_disks {
+        alerts.push(Alert {
+            severity: AlertSeverity::Warning,
+            cluster: cluster.name.clone(),
+            message: format!(
+                "SSD {} at {:.0}% of rated endurance",
+                disk.id, disk.wear_pct
+            ),
+            category: "disk_wear".to_string(),
+        });
+    }
+}
+
+/// Active Directory: a join that isn't healthy, no domain controller in use,
+/// or nodes that lost their LDAP connection. SMB logins fail through these,
+/// so a cluster-wide loss is critical and a partial one a warning.
+fn check_directory_services(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
+    let Some(ref dir) = cluster.health.directory else {
+        return;
+    };
+    let mut alert = |severity, message| {
+        alerts.push(Alert {
+            severity,
+            cluster: cluster.name.clone(),
+            message,
+            category: "directory_services".to_string(),
+        })
+    };
+    if !dir.status.eq_ignore_ascii_case("JOINED") {
+        alert(
+            AlertSeverity::Critical,
+            format!(
+                "Active Directory join to {} is {}",
+                dir.domain,
+                dir.status.to_lowercase()
+            ),
+        );
+    } else if dir.domain_controllers.is_empty() {
+        alert(
+            AlertSeverity::Critical,
+            format!("no domain controller reachable for {}", dir.domain),
+        );
+    } else if !dir.disconnected_nodes.is_empty() {
+        let all_down =
+            cluster.nodes.total > 0 && dir.disconnected_nodes.len() >= cluster.nodes.total;
+        let nodes: Vec<String> = dir
+            .disconnected_nodes
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        let (severity, scope) = if all_down {
+            (AlertSeverity::Critical, "all nodes".to_string())
+        } else {
+            (AlertSeverity::Warning, format!("node {}", nodes.join(", ")))
+        };
+        alert(
+            severity,
+            format!("LDAP connection to {} down on {}", dir.domain, scope),
+        );
+    }
+}
+
 /// Protection degraded: 0 remaining node or drive failures.
 fn check_protection_degraded(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
     if let Some(remaining) = cluster.health.remaining_node_failures {
@@ -163,6 +331,165 @@ fn check_capacity_projection(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
     }
 }
 
+/// Incomplete collection: endpoints that failed even after a retry, or were
+/// abandoned when the cluster ran out of collection budget, so the fields
+/// they feed show defaults rather than readings.
+fn check_degraded_collection(cluster: &ClusterStatus, alerts: &mut Vec<Alert>) {
+    if cluster.degraded.is_empty() {
+        return;
+    }
+    alerts.push(Alert {
+        severity: AlertSeverity::Warning,
+        cluster: cluster.name.clone(),
+        message: format!("incomplete data: {}", missing_data(cluster)),
+        category: "degraded_collection".to_string(),
+    });
+}
+
+/// What is missing from a cluster with `degraded` groups, and why.
+pub fn missing_data(cluster: &ClusterStatus) -> String {
+    if cluster.partial {
+        format!(
+            "collection budget exceeded, missing {}",
+            cluster.degraded.join(", ")
+        )
+    } else {
+        format!(
+            "could not fetch {} after retry",
+            cluster.degraded.join(", ")
+        )
+    }
+}
+
+/// A 0–100 health score for comparing clusters at a glance, from four parts:
+///
+/// - capacity (30): full marks up to 70% used, none from 95%
+/// - protection (30): none with data at risk, half when one more node or
+///   drive failure would put it at risk
+/// - alerts (30): less 10 per critical and 4 per warning
+/// - freshness (10): none for stale data, half for incomplete data
+///
+/// Unreachable clusters score 0.
+pub fn score(cluster: &ClusterStatus, alerts: &[Alert]) -> u8 {
+    if !cluster.reachable {
+        return 0;
+    }
+    let used = cluster.capacity.used_pct;
+    let capacity = 30.0 * ((95.0 - used) / 25.0).clamp(0.0, 1.0);
+
+    let health = &cluster.health;
+    let protection = if health.data_at_risk {
+        0.0
+    } else if health.remaining_node_failures == Some(0)
+        || health.remaining_drive_failures == Some(0)
+    {
+        15.0
+    } else {
+        30.0
+    };
+
+    let penalty: u32 = alerts
+        .iter()
+        .filter(|a| a.cluster == cluster.name || a.cluster == cluster.profile)
+        .map(|a| match a.severity {
+            AlertSeverity::Critical => 10,
+            AlertSeverity::Warning => 4,
+            AlertSeverity::Info => 0,
+        })
+        .sum();
+    let alert_points = 30u32.saturating_sub(penalty) as f64;
+
+    let freshness = if cluster.stale {
+        0.0
+    } else if !cluster.degraded.is_empty() {
+        5.0
+    } else {
+        10.0
+    };
+
+    (capacity + protection + alert_points + freshness).round() as u8
+}
+
+/// Downgrade alerts for clusters inside a maintenance window to info so that
+/// planned work doesn't page anyone.
+///
+/// `in_maintenance` holds profile names. Connectivity alerts carry the profile
+/// name; the rest carry the cluster name, which is looked up in `clusters`.
+pub fn apply_maintenance(
+    alerts: &mut [Alert],
+    clusters: &[ClusterStatus],
+    in_maintenance: &[&str],
+) {
+    if in_maintenance.is_empty() {
+        return;
+    }
+    let names: Vec<&str> = clusters
+        .iter()
+        .filter(|c| in_maintenance.contains(&c.profile.as_str()))
+        .map(|c| c.name.as_str())
+        .chain(in_maintenance.iter().copied())
+        .collect();
+
+    for alert in alerts.iter_mut() {
+        if names.contains(&alert.cluster.as_str()) && alert.severity != AlertSeverity::Info {
+            alert.severity = AlertSeverity::Info;
+            alert.message.push_str(" (maintenance)");
+        }
+    }
+    sort_alerts(alerts);
+}
+
+/// Move acknowledged alerts out of `alerts` and return them.
+///
+/// `acknowledged` holds `(cluster, category)` pairs where the cluster is a
+/// profile or cluster name; a profile also covers the alerts carrying its
+/// cluster's name.
+pub fn take_acknowledged(
+    alerts: &mut Vec<Alert>,
+    clusters: &[ClusterStatus],
+    acknowledged: &[(String, String)],
+) -> Vec<Alert> {
+    if acknowledged.is_empty() {
+        return Vec::new();
+    }
+    let is_acked = |alert: &Alert| {
+        acknowledged.iter().any(|(cluster, category)| {
+            *category == alert.category
+                && (*cluster == alert.cluster
+                    || clusters
+                        .iter()
+                        .any(|c| c.profile == *cluster && c.name == alert.cluster))
+        })
+    };
+    let (acked, active) = std::mem::take(alerts).into_iter().partition(is_acked);
+    *alerts = active;
+    acked
+}
+
+/// Map alerts to a monitoring-plugin exit code for `fleet status --check`:
+/// 2 for any critical alert, 1 for any warning, 3 when the only problems are
+/// unreachable clusters, 0 otherwise. Info alerts (including ones downgraded
+/// by a maintenance window) don't count.
+pub fn check_exit_code(alerts: &[Alert]) -> i32 {
+    let (connectivity, cluster): (Vec<&Alert>, Vec<&Alert>) =
+        alerts.iter().partition(|a| a.category == "connectivity");
+    if cluster
+        .iter()
+        .any(|a| a.severity == AlertSeverity::Critical)
+    {
+        2
+    } else if cluster.iter().any(|a| a.severity == AlertSeverity::Warning) {
+        1
+    } else if connectivity
+        .iter()
+        .any(|a| a.severity != AlertSeverity::Info)
+    {
+        3
+    } else {
+        0
+    }
+}
+
 /// Sort alerts by severity: Critical (0) > Warning (1) > Info (2).
 fn sort_alerts(alerts: &mut [Alert]) {
     alerts.sort_by_key(|a| match a.severity {
@@ -175,6 +502,7 @@ fn sort_alerts(alerts: &mut [Alert]) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ProjectionModel;
 
     /// Helper to build a minimal healthy ClusterStatus for testing.
     fn make_cluster(name: &str) -> ClusterStatus {
@@ -187,6 +515,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 42,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 5,
                 online: 5,
@@ -207,6 +537,8 @@ mod tests {
                 protection_type: Some("PROTECTION_SYSTEM_TYPE_EC".to_string()),
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         }
     }
@@ -223,6 +555,35 @@ mod tests {
         );
     }
 
+    // ── Maintenance windows ─────────────────────────────────────────
+
+    #[test]
+    fn test_maintenance_downgrades_to_info() {
+        let mut music = make_cluster("music");
+        music.profile = "music-prod".to_string();
+        music.health.data_at_risk = true;
+        let mut other = make_cluster("other");
+        other.health.data_at_risk = true;
+        let connectivity = vec![Alert {
+            severity: AlertSeverity::Critical,
+            cluster: "music-prod".to_string(),
+            message: "unreachable: timeout".to_string(),
+            category: "connectivity".to_string(),
+        }];
+
+        let clusters = [music, other];
+        let mut alerts = generate_alerts(&clusters, connectivity);
+        apply_maintenance(&mut alerts, &clusters, &["music-prod"]);
+
+        assert_eq!(alerts[0].cluster, "other");
+        assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+        let downgraded: Vec<_> = alerts.iter().filter(|a| a.cluster != "other").collect();
+        assert_eq!(downgraded.len(), 2);
+        assert!(downgraded
+            .iter()
+            .all(|a| a.severity == AlertSeverity::Info && a.message.ends_with("(maintenance)")));
+    }
+
     // ── Node offline alerts ─────────────────────────────────────────
 
     #[test]
@@ -365,6 +726,127 @@ mod tests {
         assert!(!alerts.iter().any(|a| a.category == "disk_unhealthy"));
     }
 
+    // ── Disk wear alerts ────────────────────────────────────────────
+
+    #[test]
+    fn test_disk_wear_one_alert_per_drive() {
+        let mut cluster = make_cluster("music");
+        cluster.health.worn_disks = vec![
+            WornDisk {
+                id: "1.3".to_string(),
+                wear_pct: 91.4,
+            },
+            WornDisk {
+                id: "2.3".to_string(),
+                wear_pct: 85.0,
+            },
+        ];
+
+        let alerts = generate_alerts(&[cluster], vec![]);
+        let wear: Vec<_> = alerts
+            .iter()
+            .filter(|a| a.category == "disk_wear")
+            .collect();
+        assert_eq!(wear.len(), 2);
+        assert_eq!(wear[0].severity, AlertSeverity::Warning);
+        assert_eq!(wear[0].message, "SSD 1.3 at 91% of rated endurance");
+    }
+
+    // ── Degraded collection alerts ──────────────────────────────────
+
+    #[test]
+    fn test_degraded_collection_alert() {
+        let mut cluster = make_cluster("music");
+        cluster.degraded = vec!["activity".to_string(), "disks".to_string()];
+
+        let alerts = generate_alerts(&[cluster], vec![]);
+        let alert = alerts
+            .iter()
+            .find(|a| a.category == "degraded_collection")
+            .unwrap();
+        assert_eq!(alert.severity, AlertSeverity::Warning);
+        assert_eq!(
+            alert.message,
+            "incomplete data: could not fetch activity, disks after retry"
+        );
+    }
+
+    #[test]
+    fn test_partial_collection_alert() {
+        let mut cluster = make_cluster("music");
+        cluster.degraded = vec!["capacity_history".to_string()];
+        cluster.partial = true;
+
+        let alerts = generate_alerts(&[cluster], vec![]);
+        let alert = alerts
+            .iter()
+            .find(|a| a.category == "degraded_collection")
+            .unwrap();
+        assert_eq!(
+            alert.message,
+            "incomplete data: collection budget exceeded, missing capacity_history"
+        );
+    }
+
+    // ── Directory services alerts ───────────────────────────────────
+
+    fn joined(status: &str, dcs: &[&str], disconnected: &[u64]) -> DirectoryStatus {
+        DirectoryStatus {
+            domain: "ad.example.com".to_string(),
+            status: status.to_string(),
+            domain_controllers: dcs.iter().map(|d| d.to_string()).collect(),
+            disconnected_nodes: disconnected.to_vec(),
+        }
+    }
+
+    fn directory_alerts(dir: DirectoryStatus) -> Vec<Alert> {
+        let mut cluster = make_cluster("music");
+        cluster.health.directory = Some(dir);
+        generate_alerts(&[cluster], vec![])
+            .into_iter()
+            .filter(|a| a.category == "directory_services")
+            .collect()
+    }
+
+    #[test]
+    fn test_directory_services_healthy_join() {
+        assert!(directory_alerts(joined("JOINED", &["DC1"], &[])).is_empty());
+        assert!(generate_alerts(&[make_cluster("music")], vec![])
+            .iter()
+            .all(|a| a.category != "directory_services"));
+    }
+
+    #[test]
+    fn test_directory_services_broken_bind() {
+        let alerts = directory_alerts(joined("JOINED", &["DC1"], &[2]));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+        assert_eq!(
+            alerts[0].message,
+            "LDAP connection to ad.example.com down on node 2"
+        );
+
+        let alerts = directory_alerts(joined("JOINED", &["DC1"], &[1, 2, 3, 4, 5]));
+        assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+        assert_eq!(
+            alerts[0].message,
+            "LDAP connection to ad.example.com down on all nodes"
+        );
+
+        let alerts = directory_alerts(joined("JOINED", &[], &[]));
+        assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+        assert_eq!(
+            alerts[0].message,
+            "no domain controller reachable for ad.example.com"
+        );
+
+        let alerts = directory_alerts(joined("JOIN_FAILED", &["DC1"], &[]));
+        assert_eq!(
+            alerts[0].message,
+            "Active Directory join to ad.example.com is join_failed"
+        );
+    }
+
     // ── PSU health alerts ───────────────────────────────────────────
 
     #[test]
@@ -491,6 +973,8 @@ mod tests {
             days_until_full: Some(62),
             growth_rate_bytes_per_day: 1_200_000_000_000.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         });
 
         let alerts = generate_alerts(&[cluster], vec![]);
@@ -506,6 +990,8 @@ mod tests {
             days_until_full: Some(200),
             growth_rate_bytes_per_day: 100_000_000_000.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         });
 
         let alerts = generate_alerts(&[cluster], vec![]);
@@ -520,6 +1006,8 @@ mod tests {
             days_until_full: Some(5),
             growth_rate_bytes_per_day: 500_000_000_000.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         });
 
         let alerts = generate_alerts(&[cluster], vec![]);
@@ -535,6 +1023,8 @@ mod tests {
             days_until_full: Some(30),
             growth_rate_bytes_per_day: 100_000_000_000.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         });
 
         let alerts = generate_alerts(&[cluster], vec![]);
@@ -700,6 +1190,8 @@ mod tests {
             days_until_full: Some(30),
             growth_rate_bytes_per_day: 2_000_000_000_000.0,
             confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
         });
 
         let alerts = generate_alerts(&[cluster], vec![]);
@@ -725,6 +1217,65 @@ mod tests {
         assert!(last_critical < first_warning);
     }
 
+    #[test]
+    fn test_categories_match_checks() {
+        let mut cluster = make_cluster("bad-cluster");
+        cluster.nodes.online = 4;
+        cluster.health.data_at_risk = true;
+        cluster.health.disks_unhealthy = 1;
+        cluster.health.psus_unhealthy = 1;
+        cluster.health.worn_disks = vec![WornDisk {
+            id: "1.3".to_string(),
+            wear_pct: 91.0,
+        }];
+        cluster.health.directory = Some(DirectoryStatus {
+            domain: "corp.example.com".to_string(),
+            status: "NOT_JOINED".to_string(),
+            domain_controllers: vec![],
+            disconnected_nodes: vec![],
+        });
+        cluster.health.remaining_drive_failures = Some(0);
+        cluster.capacity.projection = Some(CapacityProjection {
+            days_until_full: Some(1),
+            growth_rate_bytes_per_day: 1.0,
+            confidence: ProjectionConfidence::High,
+            model: ProjectionModel::Linear,
+            window_days: 30,
+        });
+        cluster.degraded = vec!["network".to_string()];
+        let prebuilt = vec![
+            Alert {
+                severity: AlertSeverity::Critical,
+                cluster: "gone".to_string(),
+                message: "unreachable".to_string(),
+                category: "connectivity".to_string(),
+            },
+            Alert {
+                severity: AlertSeverity::Warning,
+                cluster: "bad-cluster".to_string(),
+                message: "rule matched".to_string(),
+                category: "rule:hot".to_string(),
+            },
+        ];
+
+        let mut emitted: Vec<&str> = Vec::new();
+        let alerts = generate_alerts(&[cluster], prebuilt);
+        for alert in &alerts {
+            let category = if alert.category.starts_with("rule:") {
+                "rule:<name>"
+            } else {
+                alert.category.as_str()
+            };
+            if !emitted.contains(&category) {
+                emitted.push(category);
+            }
+        }
+        let mut listed: Vec<&str> = categories().iter().map(|c| c.category).collect();
+        emitted.sort();
+        listed.sort();
+        assert_eq!(emitted, listed);
+    }
+
     // ── Edge cases ──────────────────────────────────────────────────
 
     #[test]
@@ -747,6 +1298,44 @@ mod tests {
         assert!(alerts.is_empty());
     }
 
+    // ── Health score ────────────────────────────────────────────────
+
+    #[test]
+    fn test_score_weights() {
+        let healthy = make_cluster("a");
+        assert_eq!(score(&healthy, &[]), 100);
+
+        let mut full = make_cluster("a");
+        full.capacity.used_pct = 82.5;
+        full.health.remaining_node_failures = Some(0);
+        full.degraded = vec!["activity".to_string()];
+        let alerts = vec![
+            Alert {
+                severity: AlertSeverity::Critical,
+                cluster: "a".to_string(),
+                message: "node offline".to_string(),
+                category: "node_offline".to_string(),
+            },
+            Alert {
+                severity: AlertSeverity::Warning,
+                cluster: "b".to_string(),
+                message: "other cluster".to_string(),
+                category: "capacity".to_string(),
+            },
+        ];
+        // 15 capacity + 15 protection + 20 alerts + 5 freshness
+        assert_eq!(score(&full, &alerts), 55);
+
+        let mut at_risk = make_cluster("a");
+        at_risk.health.data_at_risk = true;
+        at_risk.stale = true;
+        assert_eq!(score(&at_risk, &[]), 60);
+
+        let mut unreachable = make_cluster("a");
+        unreachable.reachable = false;
+        assert_eq!(score(&unreachable, &[]), 0);
+    }
+
     #[test]
     fn test_stale_cluster_still_generates_alerts() {
         let mut cluster = make_cluster("stale-cluster");
@@ -763,4 +1352,54 @@ mod tests {
         let alerts = generate_alerts(&[cluster], vec![]);
         assert!(alerts.iter().any(|a| a.category == "disk_unhealthy"));
     }
+
+    #[test]
+    fn test_take_acknowledged() {
+        let mut music = make_cluster("music");
+        music.profile = "music-prod".to_string();
+        let alert = |cluster: &str, category: &str| Alert {
+            severity: AlertSeverity::Warning,
+            cluster: cluster.to_string(),
+            message: String::new(),
+            category: category.to_string(),
+        };
+        let mut alerts = vec![
+            alert("music", "disk_wear"),
+            alert("music", "capacity_projection"),
+            alert("other", "disk_wear"),
+        ];
+        let acked = take_acknowledged(
+            &mut alerts,
+            &[music],
+            &[("music-prod".to_string(), "disk_wear".to_string())],
+        );
+        assert_eq!(acked.len(), 1);
+        assert_eq!(acked[0].category, "disk_wear");
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts
+            .iter()
+            .all(|a| a.category != "disk_wear" || a.cluster == "other"));
+    }
+
+    #[test]
+    fn test_check_exit_code() {
+        let alert = |severity, category: &str| Alert {
+            severity,
+            cluster: "a".to_string(),
+            message: String::new(),
+            category: category.to_string(),
+        };
+        let unreachable = alert(AlertSeverity::Critical, "connectivity");
+        let warning = alert(AlertSeverity::Warning, "disk_wear");
+        let critical = alert(AlertSeverity::Critical, "data_at_risk");
+
+        assert_eq!(check_exit_code(&[]), 0);
+        assert_eq!(
+            check_exit_code(&[alert(AlertSeverity::Info, "node_offline")]),
+            0
+        );
+        assert_eq!(check_exit_code(std::slice::from_ref(&unreachable)), 3);
+        assert_eq!(check_exit_code(&[unreachable.clone(), warning.clone()]), 1);
+        assert_eq!(check_exit_code(&[warning, critical, unreachable]), 2);
+    }
 }
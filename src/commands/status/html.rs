@@ -0,0 +1,300 @@
+use crate::output::{html_bar, html_escape, html_page};
+
+use super::renderer::{format_bytes, format_number, format_throughput};
+use super::types::*;
+
+/// Render the environment status as a standalone HTML page.
+///
+/// The page carries its own stylesheet so it can be attached to an email or
+/// served as a static file without any other assets.
+pub fn render(status: &EnvironmentStatus) -> String {
+    let mut body = String::new();
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M %Z");
+
+    body.push_str("<h1>Qumulo Environment Status</h1>\n");
+    body.push_str(&format!(
+        "<p class=\"muted\">Generated {}</p>\n",
+        html_escape(&generated.to_string())
+    ));
+
+    render_overview(&mut body, status);
+    render_alerts(&mut body, status);
+    for cluster in &status.clusters {
+        render_cluster(&mut body, cluster);
+    }
+
+    html_page("Qumulo Environment Status", &body)
+}
+
+fn render_overview(out: &mut String, status: &EnvironmentStatus) {
+    let agg = &status.aggregates;
+    let unreachable = agg.cluster_count.saturating_sub(agg.reachable_count);
+
+    out.push_str("<h2>Overview</h2>\n<table>\n");
+    out.push_str(&format!(
+        "<tr><th>Clusters</th><td>{} ({} reachable, {} unreachable)</td></tr>\n",
+        agg.cluster_count, agg.reachable_count, unreachable
+    ));
+    out.push_str(&format!(
+        "<tr><th>Nodes</th><td>{} total ({} online)</td></tr>\n",
+        agg.total_nodes, agg.online_nodes
+    ));
+    out.push_str(&format!(
+        "<tr><th>Capacity</th><td>{} {} / {} ({:.1}%)</td></tr>\n",
+        html_bar(agg.capacity.used_pct),
+        format_bytes(agg.capacity.used_bytes),
+        format_bytes(agg.capacity.total_bytes),
+        agg.capacity.used_pct
+    ));
+    out.push_str(&format!(
+        "<tr><th>Files</th><td>{} files, {} directories, {} snapshots ({})</td></tr>\n",
+        format_number(agg.files.total_files),
+        format_number(agg.files.total_directories),
+        format_number(agg.files.total_snapshots),
+        format_bytes(agg.files.snapshot_bytes)
+    ));
+    out.push_str("</table>\n");
+}
+
+fn render_alerts(out: &mut String, status: &EnvironmentStatus) {
+    out.push_str("<h2>Alerts</h2>\n");
+    if status.alerts.is_empty() {
+        out.push_str("<p class=\"ok\">No issues detected.</p>\n");
+        return;
+    }
+
+    out.push_str(
+        "<table>\n<tr><th>Severity</th><th>Cluster</th><th>Category</th><th>Message</th></tr>\n",
+    );
+    for alert in &status.alerts {
+        let class = severity_class(&alert.severity);
+        out.push_str(&format!(
+            "<tr><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            class,
+            class,
+            html_escape(&alert.cluster),
+            html_escape(&alert.category),
+            html_escape(&alert.message)
+        ));
+    }
+    out.push_str("</table>\n");
+}
+
+fn render_cluster(out: &mut String, cluster: &ClusterStatus) {
+    let label = if cluster.profile != cluster.name && !cluster.name.is_empty() {
+        format!("{} ({})", cluster.profile, cluster.name)
+    } else {
+        cluster.profile.clone()
+    };
+
+    out.push_str("<section class=\"cluster\">\n");
+    out.push_str(&format!(
+        "<h2>{} <span class=\"{}\">{}</span></h2>\n",
+        html_escape(&label),
+        health_class(cluster),
+        health_label(cluster)
+    ));
+    out.push_str(&format!(
+        "<p class=\"muted\">{} · {} · {}ms{}</p>\n",
+        html_escape(&cluster.cluster_type.to_string()),
+        html_escape(&cluster.version),
+        cluster.latency_ms,
+        if cluster.stale { " · cached data" } else { "" }
+    ));
+
+    if !cluster.reachable {
+        out.push_str("<p class=\"critical\">Cluster unreachable.</p>\n</section>\n");
+        return;
+    }
+
+    let cap = &cluster.capacity;
+    out.push_str("<table>\n");
+    out.push_str(&format!(
+        "<tr><th>Capacity</th><td>{} {} / {} ({:.1}%)</td></tr>\n",
+        html_bar(cap.used_pct),
+        format_bytes(cap.used_bytes),
+        format_bytes(cap.total_bytes),
+        cap.used_pct
+    ));
+    if let Some(ref projection) = cap.projection {
+        if let Some(days) = projection.days_until_full {
+            out.push_str(&format!(
+                "<tr><th>Projection</th><td>full in ~{} days (+{}/day, {} confidence)</td></tr>\n",
+                days,
+                format_bytes(projection.growth_rate_bytes_per_day.max(0.0) as u64),
+                match projection.confidence {
+                    ProjectionConfidence::High => "high",
+                    ProjectionConfidence::Low => "low",
+                }
+            ));
+        }
+    }
+    out.push_str(&format!(
+        "<tr><th>Nodes</th><td>{} / {} online</td></tr>\n",
+        cluster.nodes.online, cluster.nodes.total
+    ));
+    out.push_str(&format!(
+        "<tr><th>Activity</th><td>{} read / {} write IOPS · {} read / {} write</td></tr>\n",
+        format_number(cluster.activity.iops_read as u64),
+        format_number(cluster.activity.iops_write as u64),
+        format_throughput(cluster.activity.throughput_read),
+        format_throughput(cluster.activity.throughput_write)
+    ));
+    out.push_str(&format!(
+        "<tr><th>Files</th><td>{} files, {} directories, {} snapshots</td></tr>\n",
+        format_number(cluster.files.total_files),
+        format_number(cluster.files.total_directories),
+        format_number(cluster.files.total_snapshots)
+    ));
+    out.push_str("</table>\n");
+
+    if !cluster.health.issues.is_empty() {
+        out.push_str("<ul>\n");
+        for issue in &cluster.health.issues {
+            out.push_str(&format!("<li>{}</li>\n", html_escape(issue)));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</section>\n");
+}
+
+fn severity_class(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Critical => "critical",
+        AlertSeverity::Warning => "warning",
+        AlertSeverity::Info => "info",
+    }
+}
+
+fn health_class(cluster: &ClusterStatus) -> &'static str {
+    if !cluster.reachable {
+        return "critical";
+    }
+    match cluster.health.status {
+        HealthLevel::Healthy => "ok",
+        HealthLevel::Degraded => "warning",
+        HealthLevel::Critical => "critical",
+    }
+}
+
+fn health_label(cluster: &ClusterStatus) -> &'static str {
+    if !cluster.reachable {
+        return "unreachable";
+    }
+    match cluster.health.status {
+        HealthLevel::Healthy => "healthy",
+        HealthLevel::Degraded => "degraded",
+        HealthLevel::Critical => "critical",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_cluster(name: &str) -> ClusterStatus {
+        ClusterStatus {
+            profile: name.to_string(),
+            name: name.to_string(),
+            uuid: "uuid".to_string(),
+            version: "7.2.3".to_string(),
+            cluster_type: ClusterType::CnqAws,
+            reachable: true,
+            stale: false,
+            latency_ms: 12,
+            degraded: Vec::new(),
+            partial: false,
+            nodes: NodeStatus {
+                total: 4,
+                online: 4,
+                offline_nodes: vec![],
+                details: vec![],
+            },
+            capacity: CapacityStatus {
+                total_bytes: 1_099_511_627_776,
+                used_bytes: 989_560_464_998,
+                free_bytes: 109_951_162_778,
+                snapshot_bytes: 0,
+                used_pct: 90.0,
+                projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
+            },
+            activity: ActivityStatus::default(),
+            files: FileStats::default(),
+            health: HealthStatus {
+                status: HealthLevel::Healthy,
+                issues: vec![],
+                disks_unhealthy: 0,
+                psus_unhealthy: 0,
+                data_at_risk: false,
+                remaining_node_failures: None,
+                remaining_drive_failures: None,
+                protection_type: None,
+                unhealthy_disk_details: vec![],
+                unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
+            },
+        }
+    }
+
+    fn make_status(clusters: Vec<ClusterStatus>, alerts: Vec<Alert>) -> EnvironmentStatus {
+        EnvironmentStatus {
+            aggregates: Aggregates {
+                cluster_count: clusters.len(),
+                reachable_count: clusters.iter().filter(|c| c.reachable).count(),
+                total_nodes: 4,
+                online_nodes: 4,
+                capacity: CapacityStatus::default(),
+                files: FileStats::default(),
+            },
+            alerts,
+            acknowledged: vec![],
+            clusters,
+        }
+    }
+
+    #[test]
+    fn test_render_html_sections() {
+        let status = make_status(
+            vec![make_cluster("alpha")],
+            vec![Alert {
+                severity: AlertSeverity::Warning,
+                cluster: "alpha".into(),
+                message: "capacity at 90%".into(),
+                category: "capacity".into(),
+            }],
+        );
+        let html = render(&status);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>Overview</h2>"));
+        assert!(html.contains("<td class=\"warning\">warning</td><td>alpha</td>"));
+        assert!(html.contains("<section class=\"cluster\">"));
+        assert!(html.contains("<h2>alpha <span class=\"ok\">healthy</span></h2>"));
+        assert!(html.contains("class=\"critical\" style=\"width: 90.0%\""));
+    }
+
+    #[test]
+    fn test_render_html_no_alerts() {
+        let html = render(&make_status(vec![make_cluster("alpha")], vec![]));
+        assert!(html.contains("No issues detected."));
+    }
+
+    #[test]
+    fn test_render_html_unreachable_cluster() {
+        let mut cluster = make_cluster("beta");
+        cluster.reachable = false;
+        let html = render(&make_status(vec![cluster], vec![]));
+        assert!(html.contains("<span class=\"critical\">unreachable</span>"));
+        assert!(html.contains("Cluster unreachable."));
+    }
+
+    #[test]
+    fn test_render_html_escapes_issue_text() {
+        let mut cluster = make_cluster("alpha");
+        cluster.health.issues.push("disk <bay 3> & more".into());
+        let html = render(&make_status(vec![cluster], vec![]));
+        assert!(html.contains("<li>disk &lt;bay 3&gt; &amp; more</li>"));
+    }
+}
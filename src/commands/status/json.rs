@@ -1,8 +1,15 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
+use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::Serialize;
+use serde_json::Value;
 
+use crate::config::ProjectionModel;
+use crate::output::OutputFormat;
+
+use super::health;
 use super::types::*;
 
 /// Top-level JSON output matching the design spec Section 8 schema.
@@ -11,6 +18,8 @@ pub struct JsonOutput {
     pub timestamp: String,
     pub aggregates: JsonAggregates,
     pub alerts: Vec<JsonAlert>,
+    /// Alerts silenced by `alert ack`; not counted by `--check`
+    pub acknowledged_alerts: Vec<JsonAlert>,
     pub clusters: Vec<JsonCluster>,
 }
 
@@ -55,6 +64,14 @@ pub struct JsonCluster {
     pub reachable: bool,
     pub stale: bool,
     pub latency_ms: u64,
+    /// 0–100 health score, higher is better
+    pub score: u8,
+    /// Endpoint groups that failed after a retry (omitted when none)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<String>,
+    /// Collection hit the cluster budget (omitted when it did not)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
     pub nodes: JsonNodes,
     pub capacity: JsonCapacity,
     pub activity: JsonActivity,
@@ -77,6 +94,10 @@ pub struct JsonNodeDetail {
     pub node_id: u64,
     pub connections: u32,
     pub connection_breakdown: HashMap<String, u32>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub subnet_breakdown: HashMap<String, u32>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub version_breakdown: HashMap<String, u32>,
     pub nic_throughput_bps: Option<u64>,
     pub nic_link_speed_bps: Option<u64>,
     pub nic_utilization_pct: Option<f64>,
@@ -91,14 +112,26 @@ pub struct JsonCapacity {
     pub snapshot_bytes: u64,
     pub used_pct: f64,
     pub projection: Option<JsonProjection>,
+    /// Largest paths by used capacity; null unless collected with `--level full`
+    pub largest_paths: Option<Vec<JsonPathCapacity>>,
 }
 
-/// Capacity projection from linear regression.
+/// A path and the capacity it uses.
+#[derive(Debug, Serialize)]
+pub struct JsonPathCapacity {
+    pub path: String,
+    pub used_bytes: u64,
+}
+
+/// Capacity projection and the model and window it was fitted with.
 #[derive(Debug, Serialize)]
 pub struct JsonProjection {
     pub growth_rate_bytes_per_day: f64,
     pub days_to_full: Option<u64>,
     pub confidence: String,
+    /// linear or smoothing
+    pub model: ProjectionModel,
+    pub window_days: u32,
 }
 
 /// Activity metrics (IOPS and throughput).
@@ -108,6 +141,26 @@ pub struct JsonActivity {
     pub write_iops: f64,
     pub read_throughput_bps: f64,
     pub write_throughput_bps: f64,
+    /// Hottest paths and clients; null unless collected with `--top-activity`
+    pub top: Option<JsonTopActivity>,
+}
+
+/// Busiest paths and clients by combined IOPS.
+#[derive(Debug, Serialize)]
+pub struct JsonTopActivity {
+    pub paths: Vec<JsonTalker>,
+    pub clients: Vec<JsonTalker>,
+}
+
+/// A path or client with its IOPS and throughput.
+#[derive(Debug, Serialize)]
+pub struct JsonTalker {
+    pub name: String,
+    /// `[subnets]` label of a client IP (omitted when unmatched)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub iops: f64,
+    pub throughput_bps: f64,
 }
 
 /// File/directory/snapshot counts.
@@ -116,6 +169,9 @@ pub struct JsonFiles {
     pub total_files: u64,
     pub total_directories: u64,
     pub total_snapshots: u64,
+    /// Largest directories by used capacity; null unless the profile sets
+    /// `collect.aggregates_max_depth`
+    pub largest_directories: Option<Vec<JsonPathCapacity>>,
 }
 
 /// Health status indicators.
@@ -127,6 +183,26 @@ pub struct JsonHealth {
     pub remaining_node_failures: Option<u64>,
     pub remaining_drive_failures: Option<u64>,
     pub protection_type: Option<String>,
+    pub worn_disks: Vec<JsonWornDisk>,
+    /// Active Directory join (omitted when the cluster isn't joined)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory_services: Option<JsonDirectoryStatus>,
+}
+
+/// A cluster's Active Directory join.
+#[derive(Debug, Serialize)]
+pub struct JsonDirectoryStatus {
+    pub domain: String,
+    pub status: String,
+    pub domain_controllers: Vec<String>,
+    pub disconnected_nodes: Vec<u64>,
+}
+
+/// An SSD past the configured wear threshold.
+#[derive(Debug, Serialize)]
+pub struct JsonWornDisk {
+    pub id: String,
+    pub wear_pct: f64,
 }
 
 impl JsonOutput {
@@ -159,26 +235,101 @@ impl JsonOutput {
                 latency_min_ms: latencies.iter().copied().min(),
                 latency_max_ms: latencies.iter().copied().max(),
             },
-            alerts: status
-                .alerts
+            alerts: status.alerts.iter().map(convert_alert).collect(),
+            acknowledged_alerts: status.acknowledged.iter().map(convert_alert).collect(),
+            clusters: status
+                .clusters
                 .iter()
-                .map(|a| JsonAlert {
-                    severity: match a.severity {
-                        AlertSeverity::Critical => "critical".to_string(),
-                        AlertSeverity::Warning => "warning".to_string(),
-                        AlertSeverity::Info => "info".to_string(),
-                    },
-                    cluster: a.cluster.clone(),
-                    message: a.message.clone(),
-                    category: a.category.clone(),
-                })
+                .map(|c| convert_cluster(c, &status.alerts))
                 .collect(),
-            clusters: status.clusters.iter().map(convert_cluster).collect(),
         }
     }
 }
 
-fn convert_cluster(c: &ClusterStatus) -> JsonCluster {
+/// How `status` writes its JSON: pretty-printed or compact, to stdout or to
+/// a file, optionally gzipped.
+#[derive(Debug, Clone, Default)]
+pub struct JsonOptions {
+    /// One line with null fields left out
+    pub compact: bool,
+    /// Write here instead of stdout, replacing the file on every poll
+    pub out: Option<PathBuf>,
+    pub gzip: bool,
+    /// yaml, csv and ndjson go through the shared serializer, one row per
+    /// cluster; anything else is JSON
+    pub format: OutputFormat,
+}
+
+impl JsonOptions {
+    pub fn write(&self, output: &JsonOutput) -> Result<()> {
+        let mut value = serde_json::to_value(output).context("failed to serialize status")?;
+        let text = match (
+            crate::output::render_template(&value, "/clusters"),
+            self.format,
+        ) {
+            (Some(lines), _) => lines,
+            (None, OutputFormat::Yaml | OutputFormat::Csv | OutputFormat::Ndjson) => {
+                crate::output::render(&value, "/clusters", self.format)
+            }
+            (None, _) => {
+                let mut text = if self.compact {
+                    strip_nulls(&mut value);
+                    serde_json::to_string(&value)
+                } else {
+                    serde_json::to_string_pretty(output)
+                }
+                .context("failed to serialize status")?;
+                text.push('\n');
+                text
+            }
+        };
+
+        let Some(path) = &self.out else {
+            print!("{}", text);
+            return Ok(());
+        };
+        let bytes = if self.gzip {
+            crate::gzip::compress(text.as_bytes())
+        } else {
+            text.into_bytes()
+        };
+        // Write beside the target and rename, so an archiver or a reader
+        // polling the file never sees a partial write
+        let tmp = path.with_extension("part");
+        std::fs::write(&tmp, bytes)
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Drop null object fields, recursively.
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+fn convert_alert(a: &Alert) -> JsonAlert {
+    JsonAlert {
+        severity: match a.severity {
+            AlertSeverity::Critical => "critical".to_string(),
+            AlertSeverity::Warning => "warning".to_string(),
+            AlertSeverity::Info => "info".to_string(),
+        },
+        cluster: a.cluster.clone(),
+        message: a.message.clone(),
+        category: a.category.clone(),
+    }
+}
+
+fn convert_cluster(c: &ClusterStatus, alerts: &[Alert]) -> JsonCluster {
     let (cluster_type, hardware_skus) = match &c.cluster_type {
         ClusterType::OnPrem(models) => ("on-prem".to_string(), models.clone()),
         ClusterType::CnqAws => ("cnq-aws".to_string(), vec![]),
@@ -195,6 +346,9 @@ fn convert_cluster(c: &ClusterStatus) -> JsonCluster {
         reachable: c.reachable,
         stale: c.stale,
         latency_ms: c.latency_ms,
+        score: health::score(c, alerts),
+        degraded: c.degraded.clone(),
+        partial: c.partial,
         nodes: JsonNodes {
             total: c.nodes.total,
             online: c.nodes.online,
@@ -207,6 +361,8 @@ fn convert_cluster(c: &ClusterStatus) -> JsonCluster {
                     node_id: n.node_id,
                     connections: n.connections,
                     connection_breakdown: n.connection_breakdown.clone(),
+                    subnet_breakdown: n.subnet_breakdown.clone(),
+                    version_breakdown: n.version_breakdown.clone(),
                     nic_throughput_bps: n.nic_throughput_bps,
                     nic_link_speed_bps: n.nic_link_speed_bps,
                     nic_utilization_pct: n.nic_utilization_pct,
@@ -226,6 +382,18 @@ fn convert_cluster(c: &ClusterStatus) -> JsonCluster {
                     ProjectionConfidence::High => "high".to_string(),
                     ProjectionConfidence::Low => "low".to_string(),
                 },
+                model: p.model,
+                window_days: p.window_days,
+            }),
+            largest_paths: (!c.capacity.largest_paths.is_empty()).then(|| {
+                c.capacity
+                    .largest_paths
+                    .iter()
+                    .map(|p| JsonPathCapacity {
+                        path: p.path.clone(),
+                        used_bytes: p.used_bytes,
+                    })
+                    .collect()
             }),
         },
         activity: JsonActivity {
@@ -233,11 +401,38 @@ fn convert_cluster(c: &ClusterStatus) -> JsonCluster {
             write_iops: c.activity.iops_write,
             read_throughput_bps: c.activity.throughput_read,
             write_throughput_bps: c.activity.throughput_write,
+            top: c.activity.top.as_ref().map(|t| {
+                let convert = |talkers: &[ActivityTalker]| {
+                    talkers
+                        .iter()
+                        .map(|t| JsonTalker {
+                            name: t.name.clone(),
+                            label: t.label.clone(),
+                            iops: t.iops,
+                            throughput_bps: t.throughput,
+                        })
+                        .collect()
+                };
+                JsonTopActivity {
+                    paths: convert(&t.paths),
+                    clients: convert(&t.clients),
+                }
+            }),
         },
         files: JsonFiles {
             total_files: c.files.total_files,
             total_directories: c.files.total_directories,
             total_snapshots: c.files.total_snapshots,
+            largest_directories: (!c.files.largest_directories.is_empty()).then(|| {
+                c.files
+                    .largest_directories
+                    .iter()
+                    .map(|p| JsonPathCapacity {
+                        path: p.path.clone(),
+                        used_bytes: p.used_bytes,
+                    })
+                    .collect()
+            }),
         },
         health: JsonHealth {
             disks_unhealthy: c.health.disks_unhealthy,
@@ -246,6 +441,21 @@ fn convert_cluster(c: &ClusterStatus) -> JsonCluster {
             remaining_node_failures: c.health.remaining_node_failures,
             remaining_drive_failures: c.health.remaining_drive_failures,
             protection_type: c.health.protection_type.clone(),
+            worn_disks: c
+                .health
+                .worn_disks
+                .iter()
+                .map(|d| JsonWornDisk {
+                    id: d.id.clone(),
+                    wear_pct: d.wear_pct,
+                })
+                .collect(),
+            directory_services: c.health.directory.as_ref().map(|d| JsonDirectoryStatus {
+                domain: d.domain.clone(),
+                status: d.status.clone(),
+                domain_controllers: d.domain_controllers.clone(),
+                disconnected_nodes: d.disconnected_nodes.clone(),
+            }),
         },
     }
 }
@@ -269,12 +479,15 @@ mod tests {
                     snapshot_bytes: 7_700_000_000_000,
                     used_pct: 85.7,
                     projection: None,
+                    largest_paths: Vec::new(),
+                    trend: Vec::new(),
                 },
                 files: FileStats {
                     total_files: 698_412_061,
                     total_directories: 48_231_004,
                     total_snapshots: 12_847,
                     snapshot_bytes: 7_700_000_000_000,
+                    largest_directories: Vec::new(),
                 },
             },
             alerts: vec![
@@ -291,6 +504,7 @@ mod tests {
                     category: "disk_unhealthy".to_string(),
                 },
             ],
+            acknowledged: vec![],
             clusters: vec![
                 ClusterStatus {
                     profile: "gravytrain".to_string(),
@@ -304,6 +518,8 @@ mod tests {
                     reachable: true,
                     stale: false,
                     latency_ms: 42,
+                    degraded: Vec::new(),
+                    partial: false,
                     nodes: NodeStatus {
                         total: 5,
                         online: 5,
@@ -318,6 +534,8 @@ mod tests {
                                 m.insert("REST".to_string(), 2);
                                 m
                             },
+                            subnet_breakdown: HashMap::new(),
+                            version_breakdown: HashMap::new(),
                             nic_throughput_bps: Some(12_400_000_000),
                             nic_link_speed_bps: Some(200_000_000_000),
                             nic_utilization_pct: Some(6.2),
@@ -334,7 +552,14 @@ mod tests {
                             days_until_full: Some(62),
                             growth_rate_bytes_per_day: 1_200_000_000_000.0,
                             confidence: ProjectionConfidence::High,
+                            model: ProjectionModel::Linear,
+                            window_days: 30,
                         }),
+                        largest_paths: vec![PathCapacity {
+                            path: "/home/".to_string(),
+                            used_bytes: 412_000_000_000_000,
+                        }],
+                        trend: Vec::new(),
                     },
                     activity: ActivityStatus {
                         iops_read: 140.0,
@@ -343,12 +568,14 @@ mod tests {
                         throughput_write: 1_600_000.0,
                         connections: 20,
                         is_idle: false,
+                        top: None,
                     },
                     files: FileStats {
                         total_files: 501_204_881,
                         total_directories: 32_401_221,
                         total_snapshots: 8_201,
                         snapshot_bytes: 6_700_000_000_000,
+                        largest_directories: Vec::new(),
                     },
                     health: HealthStatus {
                         status: HealthLevel::Healthy,
@@ -361,6 +588,8 @@ mod tests {
                         protection_type: Some("PROTECTION_SYSTEM_TYPE_EC".to_string()),
                         unhealthy_disk_details: vec![],
                         unhealthy_psu_details: vec![],
+                        worn_disks: vec![],
+                        directory: None,
                     },
                 },
                 ClusterStatus {
@@ -372,6 +601,8 @@ mod tests {
                     reachable: false,
                     stale: true,
                     latency_ms: 0,
+                    degraded: Vec::new(),
+                    partial: false,
                     nodes: NodeStatus {
                         total: 3,
                         online: 2,
@@ -380,6 +611,8 @@ mod tests {
                             node_id: 1,
                             connections: 5,
                             connection_breakdown: HashMap::new(),
+                            subnet_breakdown: HashMap::new(),
+                            version_breakdown: HashMap::new(),
                             nic_throughput_bps: Some(1_000_000),
                             nic_link_speed_bps: None,
                             nic_utilization_pct: None,
@@ -393,6 +626,8 @@ mod tests {
                         snapshot_bytes: 0,
                         used_pct: 17.0,
                         projection: None,
+                        largest_paths: Vec::new(),
+                        trend: Vec::new(),
                     },
                     activity: ActivityStatus::default(),
                     files: FileStats {
@@ -400,6 +635,7 @@ mod tests {
                         total_directories: 1_452,
                         total_snapshots: 0,
                         snapshot_bytes: 0,
+                        largest_directories: Vec::new(),
                     },
                     health: HealthStatus {
                         status: HealthLevel::Healthy,
@@ -412,6 +648,8 @@ mod tests {
                         protection_type: None,
                         unhealthy_disk_details: vec![],
                         unhealthy_psu_details: vec![],
+                        worn_disks: vec![],
+                        directory: None,
                     },
                 },
             ],
@@ -428,6 +666,17 @@ mod tests {
             .expect("timestamp should be valid RFC3339");
     }
 
+    #[test]
+    fn test_strip_nulls() {
+        let mut value = serde_json::json!({
+            "a": null,
+            "b": [{"c": null, "d": 1}, null],
+            "e": {"f": null}
+        });
+        strip_nulls(&mut value);
+        assert_eq!(value, serde_json::json!({"b": [{"d": 1}, null], "e": {}}));
+    }
+
     #[test]
     fn test_json_output_valid_json() {
         let status = make_test_status();
@@ -609,6 +858,8 @@ mod tests {
             1_200_000_000_000.0
         );
         assert_eq!(projection["confidence"], "high");
+        assert_eq!(projection["model"], "linear");
+        assert_eq!(projection["window_days"], 30);
 
         // Should NOT have internal field name
         assert!(projection.get("days_until_full").is_none());
@@ -629,6 +880,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_json_largest_paths() {
+        let status = make_test_status();
+        let val = serde_json::to_value(JsonOutput::from_status(&status)).unwrap();
+
+        let paths = &val["clusters"][0]["capacity"]["largest_paths"];
+        assert_eq!(paths[0]["path"], "/home/");
+        assert_eq!(paths[0]["used_bytes"], 412_000_000_000_000u64);
+        // Not collected below --level full: present but null
+        let cap = &val["clusters"][1]["capacity"];
+        assert!(cap.get("largest_paths").is_some());
+        assert!(cap["largest_paths"].is_null());
+    }
+
     #[test]
     fn test_json_large_numbers_as_numbers() {
         let status = make_test_status();
@@ -780,6 +1045,7 @@ mod tests {
                 files: FileStats::default(),
             },
             alerts: vec![],
+            acknowledged: vec![],
             clusters: vec![],
         };
 
@@ -0,0 +1,83 @@
+//! Single-key controls for `fleet status --watch` in a terminal.
+//!
+//! Keys are read raw on a background thread, so they take effect without
+//! Enter, and handed to the watch loop over a channel it polls while
+//! waiting for the next refresh. Output processing is left on, so the
+//! table renders normally while a read is pending.
+
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use console::{Key, Term};
+
+/// What a key press asks the watch loop to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKey {
+    /// Poll now instead of waiting for the interval
+    Refresh,
+    /// Stop or resume polling on the interval
+    Pause,
+    Quit,
+    /// Show only the Nth cluster (0-based), or every cluster
+    Focus(Option<usize>),
+}
+
+impl WatchKey {
+    fn from_key(key: &Key) -> Option<Self> {
+        match key {
+            Key::Char('r') => Some(Self::Refresh),
+            Key::Char('p') | Key::Char(' ') => Some(Self::Pause),
+            // The terminal is raw during a read, so Ctrl+C arrives as a key
+            Key::Char('q') | Key::Escape | Key::CtrlC => Some(Self::Quit),
+            Key::Char('0') => Some(Self::Focus(None)),
+            Key::Char(c @ '1'..='9') => Some(Self::Focus(Some(*c as usize - '1' as usize))),
+            _ => None,
+        }
+    }
+}
+
+/// Help shown in the watch footer
+pub const HELP: &str = "r refresh, p pause, 1-9 focus, 0 all, q quit";
+
+/// Start reading keys. The reader stops after a quit key, leaving the
+/// terminal in cooked mode for whatever runs next.
+pub fn spawn() -> Receiver<WatchKey> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let term = Term::stdout();
+        while let Ok(key) = term.read_key() {
+            let Some(key) = WatchKey::from_key(&key) else {
+                continue;
+            };
+            if tx.send(key).is_err() || key == WatchKey::Quit {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_key() {
+        assert_eq!(WatchKey::from_key(&Key::Char('r')), Some(WatchKey::Refresh));
+        assert_eq!(WatchKey::from_key(&Key::Char('p')), Some(WatchKey::Pause));
+        assert_eq!(WatchKey::from_key(&Key::CtrlC), Some(WatchKey::Quit));
+        assert_eq!(
+            WatchKey::from_key(&Key::Char('1')),
+            Some(WatchKey::Focus(Some(0)))
+        );
+        assert_eq!(
+            WatchKey::from_key(&Key::Char('9')),
+            Some(WatchKey::Focus(Some(8)))
+        );
+        assert_eq!(
+            WatchKey::from_key(&Key::Char('0')),
+            Some(WatchKey::Focus(None))
+        );
+        assert_eq!(WatchKey::from_key(&Key::Char('x')), None);
+    }
+}
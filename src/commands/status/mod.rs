@@ -1,15 +1,25 @@
 pub mod cache;
 pub mod capacity;
 pub mod collector;
+pub mod daemon;
+pub mod detail;
 pub mod detection;
 pub mod health;
+pub mod html;
 pub mod json;
+pub mod keys;
 pub mod renderer;
+pub mod rules;
+pub mod serve;
+pub mod sink;
 pub mod timing;
+pub mod tui;
 pub mod types;
+pub mod view;
 
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -17,8 +27,11 @@ use std::time::{Duration, Instant};
 use anyhow::Result;
 
 use crate::config::Config;
+use crate::output::OutputFormat;
 
+use self::keys::WatchKey;
 use self::types::{CachedClusterData, EnvironmentStatus};
+use self::view::DisplayOptions;
 
 /// State maintained between watch mode polls for NIC throughput delta computation.
 struct WatchState {
@@ -68,6 +81,20 @@ fn apply_nic_deltas(
     new_counters
 }
 
+/// Patch NIC throughput into `status` from the counters of the previous
+/// poll, then remember this poll's counters. The first poll only records
+/// counters, so its throughput stays None.
+fn track_nic_deltas(state: &mut Option<WatchState>, status: &mut EnvironmentStatus) {
+    let counters = match state {
+        Some(ref prev) => apply_nic_deltas(status, prev),
+        None => extract_nic_counters(status),
+    };
+    *state = Some(WatchState {
+        previous_nic_counters: counters,
+        previous_timestamp: Instant::now(),
+    });
+}
+
 /// Extract current NIC byte counters from an EnvironmentStatus.
 fn extract_nic_counters(status: &EnvironmentStatus) -> HashMap<(String, u64), u64> {
     let mut counters = HashMap::new();
@@ -82,16 +109,18 @@ fn extract_nic_counters(status: &EnvironmentStatus) -> HashMap<(String, u64), u6
 }
 
 /// Build an EnvironmentStatus from cached status data for the given profiles.
-/// Returns None if no cached data is available for any profile.
+/// Returns None if no cached data is available for any profile. Clusters are
+/// marked stale unless cached within `fresh_within`.
 fn build_cached_status(
     config: &Config,
     profile_filters: &[String],
+    fresh_within: Option<Duration>,
 ) -> Option<EnvironmentStatus> {
-    let profile_names: Vec<String> = if profile_filters.is_empty() {
-        config.profiles.keys().cloned().collect()
-    } else {
-        profile_filters.to_vec()
-    };
+    let profile_names: Vec<String> = config
+        .select_profiles(profile_filters)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
 
     let cached_entries: Vec<CachedClusterData> = cache::read_all_cache(&profile_names);
     if cached_entries.is_empty() {
@@ -101,18 +130,38 @@ fn build_cached_status(
     let clusters: Vec<_> = cached_entries
         .into_iter()
         .map(|entry| {
+            let age = chrono::DateTime::parse_from_rfc3339(&entry.cached_at)
+                .ok()
+                .and_then(|at| {
+                    (chrono::Utc::now() - at.with_timezone(&chrono::Utc))
+                        .to_std()
+                        .ok()
+                });
             let mut data = entry.data;
-            data.stale = true;
+            data.stale = match (fresh_within, age) {
+                (Some(max_age), Some(age)) => age > max_age,
+                _ => true,
+            };
             data
         })
         .collect();
 
     let aggregates = collector::build_aggregates(&clusters);
-    let alerts = health::generate_alerts(&clusters, vec![]);
+    let rules = rules::from_config(&config.alert_rules).unwrap_or_default();
+    let mut alerts = health::generate_alerts(&clusters, rules::evaluate(&rules, &clusters));
+    let now = chrono::Utc::now();
+    let in_maintenance = config.profiles_in_maintenance(now);
+    health::apply_maintenance(&mut alerts, &clusters, &in_maintenance);
+    let acknowledged = health::take_acknowledged(
+        &mut alerts,
+        &clusters,
+        &crate::commands::alert::active_acks(now),
+    );
 
     Some(EnvironmentStatus {
         aggregates,
         alerts,
+        acknowledged,
         clusters,
     })
 }
@@ -121,14 +170,79 @@ fn build_cached_status(
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     config: &Config,
-    profiles: &[String],
+    display: &DisplayOptions,
     json_mode: bool,
     watch: bool,
     interval: u64,
+    no_clear: bool,
     no_cache: bool,
     timeout_secs: u64,
     show_timing: bool,
+    output: &str,
+    full_refresh_every: u64,
+    tui_mode: bool,
+    jobs: usize,
+    cluster_budget: Option<&str>,
+    sinks: &[String],
+    json_options: &json::JsonOptions,
+    check: bool,
+    cached: Option<&str>,
 ) -> Result<()> {
+    let (html_mode, format) = match output {
+        "html" => (true, OutputFormat::Table),
+        other => (
+            false,
+            OutputFormat::parse(other).map_err(|_| {
+                anyhow::anyhow!(
+                    "unknown output format '{}' (expected table, json, yaml, csv, ndjson, html)",
+                    other
+                )
+            })?,
+        ),
+    };
+    let json_mode = json_mode || format.is_structured();
+    if json_options.compact && !matches!(format, OutputFormat::Table | OutputFormat::Json) {
+        anyhow::bail!("--json-compact cannot be combined with --output {}", output);
+    }
+    let json_options = &json::JsonOptions {
+        format,
+        ..json_options.clone()
+    };
+    if html_mode && watch {
+        anyhow::bail!("--output html cannot be combined with --watch");
+    }
+    let cluster_budget = cluster_budget
+        .map(crate::commands::maintenance::parse_duration)
+        .transpose()?
+        .map(|budget| budget.to_std().unwrap_or_default());
+    let sinks = sinks
+        .iter()
+        .map(|spec| sink::Sink::parse(spec))
+        .collect::<Result<Vec<_>>>()?;
+    crate::notify::validate(&config.notify)?;
+    let cached = cached
+        .map(crate::commands::maintenance::parse_duration)
+        .transpose()?
+        .map(|max_age| max_age.to_std().unwrap_or_default());
+    if tui_mode {
+        if !sinks.is_empty() {
+            anyhow::bail!("--sink cannot be combined with --tui");
+        }
+        if json_mode || html_mode {
+            anyhow::bail!("--tui cannot be combined with --json or --output html");
+        }
+        return tui::run(
+            config,
+            display,
+            interval,
+            no_cache,
+            timeout_secs,
+            full_refresh_every,
+            jobs,
+            cluster_budget,
+        );
+    }
+
     // Set up Ctrl+C handler for graceful exit in watch mode
     let running = Arc::new(AtomicBool::new(true));
     if watch {
@@ -138,16 +252,34 @@ pub fn run(
         })
         .ok(); // Ignore if handler can't be set (e.g., already set)
     }
+    // Single-key controls when someone is watching the table
+    let keys =
+        (watch && !json_mode && !html_mode && console::Term::stdout().is_term()).then(keys::spawn);
+    let mut paused = false;
+    let mut focus: Option<usize> = None;
 
     let mut watch_state: Option<WatchState> = None;
     let mut is_first_poll = true;
+    // In watch mode, polls between full refreshes only re-fetch volatile
+    // endpoints and carry the rest forward from the previous status, which
+    // also serves as the baseline for rendered deltas.
+    let mut previous: Option<EnvironmentStatus> = None;
+    let mut poll: u64 = 0;
 
     loop {
         // On first poll (non-JSON, caching enabled), show cached data immediately
-        // so the user sees something while fresh data is being collected.
-        let showed_cached = if is_first_poll && !json_mode && !no_cache {
-            if let Some(cached_status) = build_cached_status(config, profiles) {
-                print!("{}", renderer::render(&cached_status));
+        // so the user sees something while fresh data is being collected. With
+        // --no-clear it couldn't be replaced, so the scrollback only gets fresh polls.
+        let showed_cached = if is_first_poll
+            && !json_mode
+            && !html_mode
+            && !no_cache
+            && !no_clear
+            && cached.is_none()
+        {
+            if let Some(cached_status) = build_cached_status(config, &display.profiles, None) {
+                let shown = display.shape(&cached_status);
+                print!("{}", render_table(&shown, None, display));
                 let dim = console::Style::new().dim();
                 println!("{}", dim.apply_to("Refreshing..."));
                 true
@@ -158,76 +290,148 @@ pub fn run(
             false
         };
 
-        let (mut status, timing_report) = collector::collect_all(
-            config,
-            profiles,
-            timeout_secs,
-            no_cache,
-            watch,
-            json_mode,
-            show_timing,
-            // Suppress progress spinners when cached data is shown (user already
-            // has data on screen) or on subsequent watch polls.
-            showed_cached || (watch && !is_first_poll),
-        )?;
+        let full_refresh = poll.is_multiple_of(full_refresh_every.max(1));
+        // --cached reads what the daemon (or an earlier poll) last collected
+        let (mut status, timing_report) = if let Some(max_age) = cached {
+            let status = build_cached_status(config, &display.profiles, Some(max_age))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "no cached status yet — start `qontrol daemon` or run `qontrol fleet status` once"
+                    )
+                })?;
+            (status, None)
+        } else {
+            collector::collect_all(
+                config,
+                &display.profiles,
+                collector::CollectOptions {
+                    level: display.level,
+                    projection_model: display.projection_model,
+                    projection_window: display.projection_window,
+                    top_activity: display.top_activity,
+                    timeout_secs,
+                    no_cache,
+                    watch_mode: watch,
+                    previous: previous.as_ref().filter(|_| !full_refresh),
+                    json_mode,
+                    record_timing: show_timing,
+                    // Suppress progress spinners when cached data is shown (user already
+                    // has data on screen) or on subsequent watch polls.
+                    suppress_progress: showed_cached || (watch && !is_first_poll),
+                    jobs,
+                    cluster_budget,
+                },
+            )?
+        };
 
         // In watch mode, compute NIC throughput from deltas between polls
-        if watch {
-            if let Some(ref prev) = watch_state {
-                let new_counters = apply_nic_deltas(&mut status, prev);
-                watch_state = Some(WatchState {
-                    previous_nic_counters: new_counters,
-                    previous_timestamp: Instant::now(),
-                });
-            } else {
-                // First poll: extract counters for next poll, throughput stays None
-                let counters = extract_nic_counters(&status);
-                watch_state = Some(WatchState {
-                    previous_nic_counters: counters,
-                    previous_timestamp: Instant::now(),
-                });
+        if watch && cached.is_none() {
+            track_nic_deltas(&mut watch_state, &mut status);
+        }
+
+        if cached.is_none() {
+            match crate::commands::alert::record_transitions(&status) {
+                Ok(transitions) if watch => crate::notify::notify(&config.notify, &transitions),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "failed to update alert history"),
+            }
+            if let Err(e) = crate::commands::history::record(&status) {
+                tracing::warn!(error = %e, "failed to record status history");
             }
         }
 
+        // Sorting and --only-degraded only affect what is shown; the full
+        // status is kept as the next poll's baseline.
+        let shown = display.shape(&status);
         if json_mode {
-            let json_output = json::JsonOutput::from_status(&status);
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json_output).unwrap_or_else(|_| "{}".to_string())
-            );
+            json_options.write(&json::JsonOutput::from_status(&shown))?;
+        } else if html_mode {
+            print!("{}", html::render(&shown));
         } else {
             // Clear terminal before re-rendering when cached data was shown
             // or on subsequent watch polls (keep previous output visible during collection).
-            if showed_cached || (watch && !is_first_poll) {
+            if no_clear {
+                crate::output::print_poll_header();
+            } else if showed_cached || (watch && !is_first_poll) {
                 print!("\x1B[2J\x1B[H");
             }
-            print!("{}", renderer::render(&status));
+            print!(
+                "{}",
+                render_table(&focused(&shown, focus), previous.as_ref(), display)
+            );
         }
+        sink::deliver_all(&sinks, &json::JsonOutput::from_status(&shown))?;
 
         // Timing output goes to stderr so it doesn't interfere with --json stdout
         if let Some(ref report) = timing_report {
             timing::render_timing_report(report);
         }
 
+        if check {
+            let code = health::check_exit_code(&status.alerts);
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
         if !watch {
             break;
         }
+        // What `status` was compared against when it was rendered, so a
+        // redraw after a key press shows the same deltas
+        let baseline = previous.replace(status);
+        poll += 1;
 
-        // Print watch footer
         if !json_mode {
-            println!(
-                "Refreshing every {}s \u{2014} press Ctrl+C to stop",
-                interval
-            );
+            println!("{}", watch_footer(interval, keys.is_some(), paused));
         }
 
-        // Sleep in small increments so Ctrl+C is responsive
+        // Wait in small increments so Ctrl+C and keys are responsive
         let sleep_end = Instant::now() + Duration::from_secs(interval);
-        while Instant::now() < sleep_end {
+        loop {
             if !running.load(Ordering::SeqCst) {
                 return Ok(());
             }
-            thread::sleep(Duration::from_millis(100));
+            if !paused && Instant::now() >= sleep_end {
+                break;
+            }
+            let Some(ref keys) = keys else {
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            };
+            let key = match keys.recv_timeout(Duration::from_millis(100)) {
+                Ok(key) => key,
+                Err(RecvTimeoutError::Timeout) => continue,
+                // The reader stopped (stdin closed); keep polling on the interval
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(Duration::from_millis(100));
+                    continue;
+                }
+            };
+            let Some(ref status) = previous else { break };
+            match key {
+                WatchKey::Quit => return Ok(()),
+                WatchKey::Refresh => break,
+                WatchKey::Pause => paused = !paused,
+                WatchKey::Focus(Some(n)) if view::focus(&display.shape(status), n).is_none() => {
+                    continue
+                }
+                WatchKey::Focus(n) => focus = n,
+            }
+            // With --no-clear a pause only needs the new footer, not another
+            // copy of the table
+            if !(no_clear && key == WatchKey::Pause) {
+                if no_clear {
+                    crate::output::print_poll_header();
+                } else {
+                    print!("\x1B[2J\x1B[H");
+                }
+                let shown = display.shape(status);
+                print!(
+                    "{}",
+                    render_table(&focused(&shown, focus), baseline.as_ref(), display)
+                );
+            }
+            println!("{}", watch_footer(interval, true, paused));
         }
 
         if !running.load(Ordering::SeqCst) {
@@ -240,6 +444,36 @@ pub fn run(
     Ok(())
 }
 
+/// The cluster `focus` selects, or every cluster when it selects none
+fn focused(status: &EnvironmentStatus, focus: Option<usize>) -> EnvironmentStatus {
+    focus
+        .and_then(|n| view::focus(status, n))
+        .unwrap_or_else(|| status.clone())
+}
+
+fn watch_footer(interval: u64, interactive: bool, paused: bool) -> String {
+    match (interactive, paused) {
+        (false, _) => format!(
+            "Refreshing every {}s \u{2014} press Ctrl+C to stop",
+            interval
+        ),
+        (true, false) => format!("Refreshing every {}s \u{2014} {}", interval, keys::HELP),
+        (true, true) => format!("Paused \u{2014} {}", keys::HELP),
+    }
+}
+
+fn render_table(
+    status: &EnvironmentStatus,
+    previous: Option<&EnvironmentStatus>,
+    display: &DisplayOptions,
+) -> String {
+    if display.compact {
+        renderer::render_compact(status)
+    } else {
+        renderer::render_with_deltas(status, previous)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +489,8 @@ mod tests {
             node_id,
             connections: 10,
             connection_breakdown: HashMap::new(),
+            subnet_breakdown: HashMap::new(),
+            version_breakdown: HashMap::new(),
             nic_throughput_bps: None,
             nic_link_speed_bps: link_speed,
             nic_utilization_pct: None,
@@ -272,6 +508,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 10,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: nodes.len(),
                 online: nodes.len(),
@@ -292,6 +530,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         }
     }
@@ -307,6 +547,7 @@ mod tests {
                 files: FileStats::default(),
             },
             alerts: vec![],
+            acknowledged: vec![],
             clusters,
         }
     }
@@ -453,6 +694,7 @@ mod tests {
         let status = EnvironmentStatus {
             aggregates,
             alerts: vec![],
+            acknowledged: vec![],
             clusters: vec![stale_cluster],
         };
 
@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use console::Style;
 
 use super::capacity;
+use super::collector;
+use super::health;
 use super::types::*;
 
-const HEADER_WIDTH: usize = 80;
+pub(super) const HEADER_WIDTH: usize = 80;
 const CAPACITY_BAR_WIDTH: usize = 20;
 const NIC_BAR_WIDTH: usize = 10;
 
@@ -14,18 +18,39 @@ const NIC_BAR_WIDTH: usize = 10;
 /// Returns a styled string ready for printing. All ANSI escape codes for color
 /// are embedded in the returned string via the `console` crate.
 pub fn render(status: &EnvironmentStatus) -> String {
+    render_with_deltas(status, None)
+}
+
+/// Render like [`render`], annotating capacity, IOPS, and connection counts with
+/// their change since `previous` (the prior watch poll) when one is given.
+pub fn render_with_deltas(
+    status: &EnvironmentStatus,
+    previous: Option<&EnvironmentStatus>,
+) -> String {
     let mut out = String::new();
 
     render_overview(&mut out, status);
     render_alerts(&mut out, status);
-    render_clusters(&mut out, status);
+    render_clusters(&mut out, status, previous);
+
+    out
+}
+
+/// Render the overview and alerts followed by one line per cluster
+/// (`--compact`).
+pub fn render_compact(status: &EnvironmentStatus) -> String {
+    let mut out = String::new();
+
+    render_overview(&mut out, status);
+    render_alerts(&mut out, status);
+    render_compact_clusters(&mut out, status);
 
     out
 }
 
 // ── Section renderers ───────────────────────────────────────────────────────
 
-fn render_overview(out: &mut String, status: &EnvironmentStatus) {
+pub(super) fn render_overview(out: &mut String, status: &EnvironmentStatus) {
     let bold = Style::new().bold();
     let agg = &status.aggregates;
 
@@ -92,11 +117,9 @@ fn render_overview(out: &mut String, status: &EnvironmentStatus) {
     ));
 }
 
-fn render_alerts(out: &mut String, status: &EnvironmentStatus) {
+pub(super) fn render_alerts(out: &mut String, status: &EnvironmentStatus) {
     let bold = Style::new().bold();
     let green = Style::new().green();
-    let yellow = Style::new().yellow();
-    let red = Style::new().red();
 
     let title = "═══ Alerts ";
     let padding = HEADER_WIDTH.saturating_sub(title.len());
@@ -105,40 +128,120 @@ fn render_alerts(out: &mut String, status: &EnvironmentStatus) {
         bold.apply_to(format!("{}{}", title, "═".repeat(padding)))
     ));
 
-    if status.alerts.is_empty() {
+    if status.alerts.is_empty() && status.acknowledged.is_empty() {
         out.push_str(&format!("  {}\n", green.apply_to("No issues detected.")));
+    } else if status.alerts.is_empty() {
+        out.push_str(&format!(
+            "  {}\n",
+            green.apply_to("No unacknowledged issues.")
+        ));
     } else {
-        for alert in &status.alerts {
-            let (icon, style) = match alert.severity {
-                AlertSeverity::Critical => ("✗", &red),
-                AlertSeverity::Warning => ("⚠", &yellow),
-                AlertSeverity::Info => ("ℹ", &green),
-            };
+        render_alert_list(out, &status.alerts);
+    }
+    if !status.acknowledged.is_empty() {
+        let dim = Style::new().dim();
+        out.push_str(&format!("  {}\n", dim.apply_to("Acknowledged:")));
+        for alert in &status.acknowledged {
             out.push_str(&format!(
-                "  {} {}: {}\n",
-                style.apply_to(icon),
-                alert.cluster,
-                alert.message,
+                "  {}\n",
+                dim.apply_to(format!("  {}: {}", alert.cluster, alert.message))
             ));
         }
     }
 }
 
-fn render_clusters(out: &mut String, status: &EnvironmentStatus) {
+/// One line per alert with a severity icon.
+pub(super) fn render_alert_list(out: &mut String, alerts: &[Alert]) {
+    let green = Style::new().green();
+    let yellow = Style::new().yellow();
+    let red = Style::new().red();
+
+    for alert in alerts {
+        let (icon, style) = match alert.severity {
+            AlertSeverity::Critical => ("✗", &red),
+            AlertSeverity::Warning => ("⚠", &yellow),
+            AlertSeverity::Info => ("ℹ", &green),
+        };
+        out.push_str(&format!(
+            "  {} {}: {}\n",
+            style.apply_to(icon),
+            alert.cluster,
+            alert.message,
+        ));
+    }
+}
+
+fn render_clusters(
+    out: &mut String,
+    status: &EnvironmentStatus,
+    previous: Option<&EnvironmentStatus>,
+) {
     for cluster in &status.clusters {
         out.push('\n');
-        render_cluster_header(out, cluster);
-        render_cluster_separator(out);
+        render_cluster(out, cluster, status, previous);
+    }
+}
 
-        if !cluster.reachable {
-            render_unreachable_cluster(out, cluster, &status.alerts);
-        } else {
-            render_reachable_cluster(out, cluster);
-        }
+/// Header, separator, and body for one cluster of `status`.
+pub(super) fn render_cluster(
+    out: &mut String,
+    cluster: &ClusterStatus,
+    status: &EnvironmentStatus,
+    previous: Option<&EnvironmentStatus>,
+) {
+    render_cluster_header(out, cluster, health::score(cluster, &status.alerts));
+    render_cluster_separator(out);
+
+    if !cluster.reachable {
+        render_unreachable_cluster(out, cluster, &status.alerts);
+    } else {
+        let prev = previous.and_then(|p| {
+            p.clusters
+                .iter()
+                .find(|c| c.profile == cluster.profile && c.reachable)
+        });
+        render_reachable_cluster(out, cluster, prev);
     }
 }
 
-fn render_cluster_header(out: &mut String, cluster: &ClusterStatus) {
+fn render_compact_clusters(out: &mut String, status: &EnvironmentStatus) {
+    let bold = Style::new().bold();
+    let green = Style::new().green();
+    let yellow = Style::new().yellow();
+    let red = Style::new().red();
+
+    let title = "═══ Clusters ";
+    let padding = HEADER_WIDTH.saturating_sub(title.len());
+    out.push_str(&format!(
+        "{}\n",
+        bold.apply_to(format!("{}{}", title, "═".repeat(padding)))
+    ));
+
+    // Pad before styling so ANSI codes don't throw off the columns
+    for cluster in &status.clusters {
+        let (health, style) = match (cluster.reachable, &cluster.health.status) {
+            (false, _) => ("✗ unreachable", &red),
+            (true, HealthLevel::Critical) => ("✗ critical", &red),
+            (true, HealthLevel::Degraded) => ("⚠ degraded", &yellow),
+            (true, HealthLevel::Healthy) => ("✓ healthy", &green),
+        };
+        let activity = &cluster.activity;
+        let iops = activity.iops_read + activity.iops_write;
+        out.push_str(&format!(
+            "  {:<20} {}  score {:>3}  {:>5} nodes  {:>5.1}% of {:<9}  {:>8} IOPS  {:>5}ms\n",
+            cluster.profile,
+            style.apply_to(format!("{:<13}", health)),
+            health::score(cluster, &status.alerts),
+            format!("{}/{}", cluster.nodes.online, cluster.nodes.total),
+            cluster.capacity.used_pct,
+            format_bytes(cluster.capacity.total_bytes),
+            format_number(iops.round() as u64),
+            cluster.latency_ms,
+        ));
+    }
+}
+
+fn render_cluster_header(out: &mut String, cluster: &ClusterStatus, score: u8) {
     let bold = Style::new().bold();
 
     let type_info = match &cluster.cluster_type {
@@ -155,8 +258,8 @@ fn render_cluster_header(out: &mut String, cluster: &ClusterStatus) {
 
     let right_side = if cluster.reachable {
         format!(
-            "{} · {} · {}ms",
-            type_info, cluster.version, cluster.latency_ms
+            "{} · {} · {}ms · score {}",
+            type_info, cluster.version, cluster.latency_ms, score
         )
     } else {
         format!("{} · {}", type_info, cluster.version)
@@ -215,20 +318,30 @@ fn render_unreachable_cluster(out: &mut String, cluster: &ClusterStatus, alerts:
     ));
 }
 
-fn render_reachable_cluster(out: &mut String, cluster: &ClusterStatus) {
+fn render_reachable_cluster(
+    out: &mut String,
+    cluster: &ClusterStatus,
+    prev: Option<&ClusterStatus>,
+) {
     let yellow = Style::new().yellow();
 
     // Nodes line
     render_nodes_line(out, cluster);
 
     // Capacity with bar
-    render_capacity_bar(out, cluster);
+    render_capacity_bar(out, cluster, prev);
+
+    // Largest paths (only collected with --level full)
+    render_largest_paths_line(out, cluster);
 
     // Files
     render_files_line(out, cluster);
 
     // Activity
-    render_activity_line(out, cluster);
+    render_activity_line(out, cluster, prev);
+    if let Some(ref top) = cluster.activity.top {
+        render_top_activity(out, top);
+    }
 
     // Capacity projection warning (inline in cluster section)
     if let Some(ref projection) = cluster.capacity.projection {
@@ -238,9 +351,19 @@ fn render_reachable_cluster(out: &mut String, cluster: &ClusterStatus) {
         }
     }
 
+    // Endpoint groups that failed after a retry or ran out of budget; their
+    // fields read as defaults
+    if !cluster.degraded.is_empty() {
+        out.push_str(&format!(
+            "  {} incomplete data: {}\n",
+            yellow.apply_to("⚠"),
+            health::missing_data(cluster)
+        ));
+    }
+
     // Connections + NIC throughput table
     if !cluster.nodes.details.is_empty() {
-        render_network_table(out, cluster);
+        render_network_table(out, cluster, prev);
     }
 }
 
@@ -278,19 +401,45 @@ fn render_capacity_line(out: &mut String, cluster: &ClusterStatus) {
     ));
 }
 
-fn render_capacity_bar(out: &mut String, cluster: &ClusterStatus) {
+fn render_capacity_bar(out: &mut String, cluster: &ClusterStatus, prev: Option<&ClusterStatus>) {
+    let dim = Style::new().dim();
     let cap = &cluster.capacity;
     let bar = progress_bar(cap.used_pct, CAPACITY_BAR_WIDTH);
+    let delta = prev
+        .and_then(|p| format_bytes_delta(cap.used_bytes, p.capacity.used_bytes))
+        .map(|d| format!("  {}", dim.apply_to(d)))
+        .unwrap_or_default();
+    // Recent growth; the projection may be fitted to a different window
+    let trend = if cap.trend.len() >= 2 {
+        let label = format!("{}d", collector::TREND_DAYS);
+        format!("  {} {}", dim.apply_to(label), sparkline(&cap.trend))
+    } else {
+        String::new()
+    };
     out.push_str(&format!(
-        "  Capacity: {} / {} ({:.1}%) {}  snaps: {}\n",
+        "  Capacity: {} / {} ({:.1}%) {}  snaps: {}{}{}\n",
         format_bytes(cap.used_bytes),
         format_bytes(cap.total_bytes),
         cap.used_pct,
         bar,
         format_bytes(cap.snapshot_bytes),
+        trend,
+        delta,
     ));
 }
 
+fn render_largest_paths_line(out: &mut String, cluster: &ClusterStatus) {
+    let paths = &cluster.capacity.largest_paths;
+    if paths.is_empty() {
+        return;
+    }
+    let entries: Vec<String> = paths
+        .iter()
+        .map(|p| format!("{} {}", p.path, format_bytes(p.used_bytes)))
+        .collect();
+    out.push_str(&format!("  Largest:  {}\n", entries.join("    ")));
+}
+
 fn render_files_line(out: &mut String, cluster: &ClusterStatus) {
     let f = &cluster.files;
     out.push_str(&format!(
@@ -301,23 +450,66 @@ fn render_files_line(out: &mut String, cluster: &ClusterStatus) {
     ));
 }
 
-fn render_activity_line(out: &mut String, cluster: &ClusterStatus) {
+fn render_activity_line(out: &mut String, cluster: &ClusterStatus, prev: Option<&ClusterStatus>) {
     let activity = &cluster.activity;
-    if activity.is_idle {
+    if cluster.degraded.iter().any(|g| g == "activity") {
+        let yellow = Style::new().yellow();
+        out.push_str(&format!("  Activity: {}\n", yellow.apply_to("unavailable")));
+    } else if activity.is_idle {
         out.push_str("  Activity: idle\n");
     } else {
+        let arrow = |current: f64, previous: Option<f64>| {
+            previous
+                .and_then(|p| trend_arrow(current, p))
+                .map(|a| format!(" {}", a))
+                .unwrap_or_default()
+        };
+        let prev_activity = prev.map(|p| &p.activity);
         out.push_str(&format!(
-            "  Activity: R: {:.0} IOPS / {}    W: {:.0} IOPS / {}\n",
+            "  Activity: R: {:.0} IOPS{} / {}    W: {:.0} IOPS{} / {}\n",
             activity.iops_read,
+            arrow(activity.iops_read, prev_activity.map(|a| a.iops_read)),
             format_throughput(activity.throughput_read),
             activity.iops_write,
+            arrow(activity.iops_write, prev_activity.map(|a| a.iops_write)),
             format_throughput(activity.throughput_write),
         ));
     }
 }
 
-fn render_network_table(out: &mut String, cluster: &ClusterStatus) {
+fn render_top_activity(out: &mut String, top: &TopActivity) {
+    let dim = Style::new().dim();
+    if top.paths.is_empty() && top.clients.is_empty() {
+        out.push_str(&format!("  {}\n", dim.apply_to("Top activity: none")));
+        return;
+    }
+    for (label, talkers) in [("Top paths", &top.paths), ("Top clients", &top.clients)] {
+        if talkers.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "  {:<46}{}\n",
+            label,
+            dim.apply_to(format!("{:>8}  {:>11}", "IOPS", "Throughput"))
+        ));
+        for t in talkers {
+            let name = match &t.label {
+                Some(subnet) => format!("{} ({})", t.name, subnet),
+                None => t.name.clone(),
+            };
+            out.push_str(&format!(
+                "    {:<44}{:>8.0}  {:>11}\n",
+                name,
+                t.iops,
+                format_throughput(t.throughput)
+            ));
+        }
+    }
+}
+
+fn render_network_table(out: &mut String, cluster: &ClusterStatus, prev: Option<&ClusterStatus>) {
     let red = Style::new().red();
+    let dim = Style::new().dim();
     let details = &cluster.nodes.details;
     let offline = &cluster.nodes.offline_nodes;
 
@@ -345,7 +537,12 @@ fn render_network_table(out: &mut String, cluster: &ClusterStatus) {
             ));
         } else {
             // Connections side
-            let conn_str = format!("{:>3}", node.connections);
+            let conn_delta = prev
+                .and_then(|p| p.nodes.details.iter().find(|n| n.node_id == node.node_id))
+                .and_then(|p| format_count_delta(node.connections, p.connections))
+                .map(|d| format!(" {}", dim.apply_to(d)))
+                .unwrap_or_default();
+            let conn_str = format!("{:>3}{}", node.connections, conn_delta);
             let conn_bar = if node.connections > 0 {
                 format!("  {}", connection_bar(node.connections, max_conns))
             } else {
@@ -363,6 +560,37 @@ fn render_network_table(out: &mut String, cluster: &ClusterStatus) {
             out.push_str(&format!("{}node{}: {}\n", padded_left, node.node_id, right,));
         }
     }
+
+    render_breakdown_line(out, "Clients: ", details, |n| &n.subnet_breakdown);
+    render_breakdown_line(out, "Protocols:", details, |n| &n.version_breakdown);
+}
+
+/// Connection counts summed across all nodes, busiest first, e.g.
+/// "Clients:  render-farm: 234, office: 12, unlabeled: 3" for `[subnets]`
+/// labels or "Protocols: NFSv3: 120, NFSv4.1: 40" for protocol versions.
+/// Nothing is printed when no node has a breakdown.
+fn render_breakdown_line(
+    out: &mut String,
+    heading: &str,
+    details: &[NodeNetworkInfo],
+    breakdown: impl Fn(&NodeNetworkInfo) -> &HashMap<String, u32>,
+) {
+    let mut totals: HashMap<&str, u32> = Default::default();
+    for node in details {
+        for (label, count) in breakdown(node) {
+            *totals.entry(label.as_str()).or_insert(0) += count;
+        }
+    }
+    if totals.is_empty() {
+        return;
+    }
+    let mut totals: Vec<(&str, u32)> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let parts: Vec<String> = totals
+        .iter()
+        .map(|(label, count)| format!("{}: {}", label, count))
+        .collect();
+    out.push_str(&format!("  {} {}\n", heading, parts.join(", ")));
 }
 
 fn format_nic_column(node: &NodeNetworkInfo, cluster_type: &ClusterType) -> String {
@@ -530,6 +758,24 @@ pub fn progress_bar(pct: f64, width: usize) -> String {
     format!("{}{}", "█".repeat(filled), "░".repeat(empty))
 }
 
+/// Render values as a one-line chart of ▁..█ blocks scaled between their
+/// minimum and maximum. A flat series is a flat line.
+pub fn sparkline(values: &[u64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if max == min {
+                return BLOCKS[0];
+            }
+            let level = (v - min) as f64 / (max - min) as f64 * (BLOCKS.len() - 1) as f64;
+            BLOCKS[level.round() as usize]
+        })
+        .collect()
+}
+
 /// Render a proportional connection bar using █ characters.
 pub fn connection_bar(count: u32, max: u32) -> String {
     if max == 0 || count == 0 {
@@ -548,6 +794,37 @@ pub fn nic_bar(utilization_pct: f64) -> String {
     format!("{}{}", "▸".repeat(filled), "░".repeat(empty))
 }
 
+/// Format the change in a byte count as `↑0.3 TB` / `↓1.2 GB`; None when unchanged.
+pub fn format_bytes_delta(current: u64, previous: u64) -> Option<String> {
+    match current.cmp(&previous) {
+        std::cmp::Ordering::Greater => Some(format!("↑{}", format_bytes(current - previous))),
+        std::cmp::Ordering::Less => Some(format!("↓{}", format_bytes(previous - current))),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Format the change in a count as `+3` / `-2`; None when unchanged.
+pub fn format_count_delta(current: u32, previous: u32) -> Option<String> {
+    match current.cmp(&previous) {
+        std::cmp::Ordering::Greater => Some(format!("+{}", current - previous)),
+        std::cmp::Ordering::Less => Some(format!("-{}", previous - current)),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Arrow for a rate that moved by more than 5% (and at least 1) since the
+/// previous poll; None for noise-level changes.
+pub fn trend_arrow(current: f64, previous: f64) -> Option<&'static str> {
+    let threshold = (previous.abs() * 0.05).max(1.0);
+    if current - previous > threshold {
+        Some("↑")
+    } else if previous - current > threshold {
+        Some("↓")
+    } else {
+        None
+    }
+}
+
 /// Compute latency range string (e.g., "8-142ms") from reachable clusters.
 fn format_latency_range(clusters: &[ClusterStatus]) -> String {
     let latencies: Vec<u64> = clusters
@@ -656,6 +933,14 @@ mod tests {
         assert_eq!(connection_bar(1, 14), "█");
     }
 
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[5, 5, 5]), "▁▁▁");
+        assert_eq!(sparkline(&[0, 1, 2, 3, 4, 5, 6, 7]), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&[100, 150, 200]), "▁▅█");
+    }
+
     #[test]
     fn test_nic_bar() {
         assert_eq!(nic_bar(0.0), "░░░░░░░░░░");
@@ -777,6 +1062,18 @@ mod tests {
         assert!(plain.contains("Activity: idle"));
     }
 
+    #[test]
+    fn test_render_degraded_activity_is_unavailable() {
+        let mut status = make_full_status_healthy_cloud();
+        status.clusters[0].degraded = vec!["activity".to_string()];
+        let output = render(&status);
+        let plain = strip_ansi(&output);
+
+        assert!(plain.contains("Activity: unavailable"));
+        assert!(!plain.contains("Activity: idle"));
+        assert!(plain.contains("⚠ incomplete data: could not fetch activity"));
+    }
+
     #[test]
     fn test_render_capacity_bar_in_cluster() {
         let status = make_full_status_healthy_onprem();
@@ -788,6 +1085,105 @@ mod tests {
         assert!(plain.contains("snaps:"));
     }
 
+    #[test]
+    fn test_format_bytes_delta() {
+        assert_eq!(
+            format_bytes_delta(1_421_634_174_976, 1_099_511_627_776).as_deref(),
+            Some("↑300.0 GB")
+        );
+        assert_eq!(
+            format_bytes_delta(0, 1_099_511_627_776).as_deref(),
+            Some("↓1.0 TB")
+        );
+        assert_eq!(format_bytes_delta(42, 42), None);
+    }
+
+    #[test]
+    fn test_format_count_delta() {
+        assert_eq!(format_count_delta(45, 42).as_deref(), Some("+3"));
+        assert_eq!(format_count_delta(40, 42).as_deref(), Some("-2"));
+        assert_eq!(format_count_delta(42, 42), None);
+    }
+
+    #[test]
+    fn test_trend_arrow_ignores_noise() {
+        assert_eq!(trend_arrow(150.0, 100.0), Some("↑"));
+        assert_eq!(trend_arrow(50.0, 100.0), Some("↓"));
+        assert_eq!(trend_arrow(103.0, 100.0), None);
+        assert_eq!(trend_arrow(0.5, 0.0), None);
+    }
+
+    #[test]
+    fn test_render_with_deltas() {
+        let previous = make_full_status_healthy_onprem();
+        let mut status = make_full_status_healthy_onprem();
+        status.clusters[0].capacity.used_bytes += 322_122_547_200;
+        status.clusters[0].activity.iops_read *= 2.0;
+        status.clusters[0].nodes.details[0].connections += 3;
+
+        let plain = strip_ansi(&render_with_deltas(&status, Some(&previous)));
+        assert!(plain.contains("↑300.0 GB"), "got: {}", plain);
+        assert!(plain.contains("R: 280 IOPS ↑ /"), "got: {}", plain);
+        assert!(plain.contains(" +3"), "got: {}", plain);
+
+        // No previous poll: plain render, no indicators
+        let plain = strip_ansi(&render_with_deltas(&status, None));
+        assert!(!plain.contains('↑'));
+        assert_eq!(render_with_deltas(&status, None), render(&status));
+    }
+
+    #[test]
+    fn test_render_top_activity() {
+        let mut status = make_full_status_healthy_onprem();
+        let plain = strip_ansi(&render(&status));
+        assert!(!plain.contains("Top paths"));
+
+        status.clusters[0].activity.top = Some(TopActivity {
+            paths: vec![ActivityTalker {
+                name: "/data/render/".to_string(),
+                label: None,
+                iops: 812.0,
+                throughput: 57_800_000.0,
+            }],
+            clients: vec![ActivityTalker {
+                name: "10.0.0.5".to_string(),
+                label: Some("render-farm".to_string()),
+                iops: 812.0,
+                throughput: 0.0,
+            }],
+        });
+        let plain = strip_ansi(&render(&status));
+        assert!(plain.contains("Top paths"), "got: {}", plain);
+        assert!(plain.contains("    /data/render/"));
+        assert!(plain.contains("812    55.1 MB/s"), "got: {}", plain);
+        assert!(plain.contains("Top clients"));
+        assert!(plain.contains("    10.0.0.5 (render-farm)"));
+
+        status.clusters[0].activity.top = Some(TopActivity::default());
+        let plain = strip_ansi(&render(&status));
+        assert!(plain.contains("Top activity: none"));
+    }
+
+    #[test]
+    fn test_render_largest_paths_line() {
+        let mut status = make_full_status_healthy_onprem();
+        let plain = strip_ansi(&render(&status));
+        assert!(!plain.contains("Largest:"));
+
+        status.clusters[0].capacity.largest_paths = vec![
+            PathCapacity {
+                path: "/home/".to_string(),
+                used_bytes: 2_199_023_255_552,
+            },
+            PathCapacity {
+                path: "/media/".to_string(),
+                used_bytes: 1_099_511_627_776,
+            },
+        ];
+        let plain = strip_ansi(&render(&status));
+        assert!(plain.contains("Largest:  /home/ 2.0 TB    /media/ 1.0 TB"));
+    }
+
     // ── Test helpers ────────────────────────────────────────────────────
 
     fn make_cluster(name: &str, reachable: bool, latency_ms: u64) -> ClusterStatus {
@@ -800,6 +1196,8 @@ mod tests {
             reachable,
             stale: !reachable,
             latency_ms,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 3,
                 online: 3,
@@ -820,6 +1218,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         }
     }
@@ -834,6 +1234,8 @@ mod tests {
                     ("SMB".to_string(), 4),
                     ("REST".to_string(), 2),
                 ]),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(12_400_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(6.0),
@@ -843,6 +1245,8 @@ mod tests {
                 node_id: 2,
                 connections: 3,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(1_100_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(1.0),
@@ -852,6 +1256,8 @@ mod tests {
                 node_id: 3,
                 connections: 3,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(800_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(0.4),
@@ -861,6 +1267,8 @@ mod tests {
                 node_id: 4,
                 connections: 0,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(200_000_000),
                 nic_link_speed_bps: Some(100_000_000_000),
                 nic_utilization_pct: Some(0.2),
@@ -870,6 +1278,8 @@ mod tests {
                 node_id: 5,
                 connections: 1,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(400_000_000),
                 nic_link_speed_bps: Some(100_000_000_000),
                 nic_utilization_pct: Some(0.4),
@@ -886,6 +1296,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 42,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 5,
                 online: 5,
@@ -899,6 +1311,8 @@ mod tests {
                 snapshot_bytes: 6_700_000_000_000,
                 used_pct: 98.2,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus {
                 iops_read: 140.0,
@@ -907,12 +1321,14 @@ mod tests {
                 throughput_write: 1_600_000.0,
                 connections: 21,
                 is_idle: false,
+                top: None,
             },
             files: FileStats {
                 total_files: 501_204_881,
                 total_directories: 32_401_221,
                 total_snapshots: 8_201,
                 snapshot_bytes: 6_700_000_000_000,
+                largest_directories: Vec::new(),
             },
             health: HealthStatus {
                 status: HealthLevel::Healthy,
@@ -925,6 +1341,8 @@ mod tests {
                 protection_type: Some("PROTECTION_SYSTEM_TYPE_EC".to_string()),
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -938,6 +1356,7 @@ mod tests {
                 files: cluster.files.clone(),
             },
             alerts: vec![],
+            acknowledged: vec![],
             clusters: vec![cluster],
         }
     }
@@ -948,6 +1367,8 @@ mod tests {
                 node_id: 1,
                 connections: 0,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(0),
                 nic_link_speed_bps: None,
                 nic_utilization_pct: None,
@@ -957,6 +1378,8 @@ mod tests {
                 node_id: 2,
                 connections: 1,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(0),
                 nic_link_speed_bps: None,
                 nic_utilization_pct: None,
@@ -966,6 +1389,8 @@ mod tests {
                 node_id: 3,
                 connections: 0,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(0),
                 nic_link_speed_bps: None,
                 nic_utilization_pct: None,
@@ -982,6 +1407,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 142,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 3,
                 online: 3,
@@ -995,6 +1422,8 @@ mod tests {
                 snapshot_bytes: 0,
                 used_pct: 17.0,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus {
                 iops_read: 0.0,
@@ -1003,12 +1432,14 @@ mod tests {
                 throughput_write: 0.0,
                 connections: 1,
                 is_idle: true,
+                top: None,
             },
             files: FileStats {
                 total_files: 35_679,
                 total_directories: 1_452,
                 total_snapshots: 0,
                 snapshot_bytes: 0,
+                largest_directories: Vec::new(),
             },
             health: HealthStatus {
                 status: HealthLevel::Healthy,
@@ -1021,6 +1452,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -1034,6 +1467,7 @@ mod tests {
                 files: cluster.files.clone(),
             },
             alerts: vec![],
+            acknowledged: vec![],
             clusters: vec![cluster],
         }
     }
@@ -1044,6 +1478,8 @@ mod tests {
                 node_id: 1,
                 connections: 10,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(5_000_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(2.5),
@@ -1053,6 +1489,8 @@ mod tests {
                 node_id: 2,
                 connections: 8,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(3_000_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(1.5),
@@ -1062,6 +1500,8 @@ mod tests {
                 node_id: 3,
                 connections: 5,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(1_000_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(0.5),
@@ -1071,6 +1511,8 @@ mod tests {
                 node_id: 4,
                 connections: 0,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: None,
                 nic_link_speed_bps: None,
                 nic_utilization_pct: None,
@@ -1080,6 +1522,8 @@ mod tests {
                 node_id: 5,
                 connections: 3,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(500_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(0.3),
@@ -1089,6 +1533,8 @@ mod tests {
                 node_id: 6,
                 connections: 2,
                 connection_breakdown: HashMap::new(),
+                subnet_breakdown: HashMap::new(),
+                version_breakdown: HashMap::new(),
                 nic_throughput_bps: Some(200_000_000),
                 nic_link_speed_bps: Some(200_000_000_000),
                 nic_utilization_pct: Some(0.1),
@@ -1105,6 +1551,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 38,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 6,
                 online: 5,
@@ -1118,6 +1566,8 @@ mod tests {
                 snapshot_bytes: 980_000_000_000,
                 used_pct: 96.1,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus {
                 iops_read: 200.0,
@@ -1126,12 +1576,14 @@ mod tests {
                 throughput_write: 5_000_000.0,
                 connections: 28,
                 is_idle: false,
+                top: None,
             },
             files: FileStats {
                 total_files: 197_207_180,
                 total_directories: 15_829_783,
                 total_snapshots: 4_646,
                 snapshot_bytes: 980_000_000_000,
+                largest_directories: Vec::new(),
             },
             health: HealthStatus {
                 status: HealthLevel::Degraded,
@@ -1144,6 +1596,8 @@ mod tests {
                 protection_type: Some("PROTECTION_SYSTEM_TYPE_EC".to_string()),
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -1162,6 +1616,7 @@ mod tests {
                 message: "node 4: OFFLINE".to_string(),
                 category: "node_offline".to_string(),
             }],
+            acknowledged: vec![],
             clusters: vec![cluster],
         }
     }
@@ -1176,6 +1631,8 @@ mod tests {
             reachable: false,
             stale: true,
             latency_ms: 0,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 3,
                 online: 3,
@@ -1189,6 +1646,8 @@ mod tests {
                 snapshot_bytes: 0,
                 used_pct: 4.9,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus::default(),
             files: FileStats {
@@ -1196,6 +1655,7 @@ mod tests {
                 total_directories: 84,
                 total_snapshots: 0,
                 snapshot_bytes: 0,
+                largest_directories: Vec::new(),
             },
             health: HealthStatus {
                 status: HealthLevel::Healthy,
@@ -1208,6 +1668,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -1226,6 +1688,7 @@ mod tests {
                 message: "UNREACHABLE (last seen 2h ago)".to_string(),
                 category: "connectivity".to_string(),
             }],
+            acknowledged: vec![],
             clusters: vec![cluster],
         }
     }
@@ -1244,12 +1707,15 @@ mod tests {
                     snapshot_bytes: 7_700_000_000_000,
                     used_pct: 84.6,
                     projection: None,
+                    largest_paths: Vec::new(),
+                    trend: Vec::new(),
                 },
                 files: FileStats {
                     total_files: 698_412_061,
                     total_directories: 48_231_004,
                     total_snapshots: 12_847,
                     snapshot_bytes: 7_700_000_000_000,
+                    largest_directories: Vec::new(),
                 },
             },
             alerts: vec![
@@ -1266,6 +1732,7 @@ mod tests {
                     category: "capacity_projection".to_string(),
                 },
             ],
+            acknowledged: vec![],
             clusters: vec![],
         }
     }
@@ -1301,6 +1768,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 42,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 5,
                 online: 5,
@@ -1309,6 +1778,8 @@ mod tests {
                     node_id: 1,
                     connections: 14,
                     connection_breakdown: HashMap::new(),
+                    subnet_breakdown: HashMap::new(),
+                    version_breakdown: HashMap::new(),
                     nic_throughput_bps: Some(12_400_000_000),
                     nic_link_speed_bps: Some(200_000_000_000),
                     nic_utilization_pct: Some(6.0),
@@ -1322,6 +1793,8 @@ mod tests {
                 snapshot_bytes: 6_700_000_000_000,
                 used_pct: 98.2,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus {
                 iops_read: 140.0,
@@ -1330,12 +1803,14 @@ mod tests {
                 throughput_write: 1_600_000.0,
                 connections: 21,
                 is_idle: false,
+                top: None,
             },
             files: FileStats {
                 total_files: 501_204_881,
                 total_directories: 32_401_221,
                 total_snapshots: 8_201,
                 snapshot_bytes: 6_700_000_000_000,
+                largest_directories: Vec::new(),
             },
             health: HealthStatus {
                 status: HealthLevel::Healthy,
@@ -1348,6 +1823,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -1360,6 +1837,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 142,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 3,
                 online: 3,
@@ -1373,6 +1852,8 @@ mod tests {
                 snapshot_bytes: 0,
                 used_pct: 17.0,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus {
                 iops_read: 0.0,
@@ -1381,12 +1862,14 @@ mod tests {
                 throughput_write: 0.0,
                 connections: 0,
                 is_idle: true,
+                top: None,
             },
             files: FileStats {
                 total_files: 35_679,
                 total_directories: 1_452,
                 total_snapshots: 0,
                 snapshot_bytes: 0,
+                largest_directories: Vec::new(),
             },
             health: HealthStatus {
                 status: HealthLevel::Healthy,
@@ -1399,6 +1882,8 @@ mod tests {
                 protection_type: None,
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -1415,15 +1900,19 @@ mod tests {
                     snapshot_bytes: 6_700_000_000_000,
                     used_pct: 63.3,
                     projection: None,
+                    largest_paths: Vec::new(),
+                    trend: Vec::new(),
                 },
                 files: FileStats {
                     total_files: 501_240_560,
                     total_directories: 32_402_673,
                     total_snapshots: 8_201,
                     snapshot_bytes: 6_700_000_000_000,
+                    largest_directories: Vec::new(),
                 },
             },
             alerts: vec![],
+            acknowledged: vec![],
             clusters: vec![gravytrain, aws],
         };
 
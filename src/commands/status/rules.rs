@@ -0,0 +1,306 @@
+//! User-defined alert rules (`[[alert_rules]]` in config), evaluated against
+//! each collected cluster alongside the built-in checks:
+//!
+//! ```toml
+//! [[alert_rules]]
+//! name = "snapshots too many"
+//! when = "files.total_snapshots > 5000"
+//! severity = "warning"
+//! ```
+//!
+//! `when` compares a dotted path into the collected [`ClusterStatus`] (e.g.
+//! `capacity.used_pct`, `health.disks_unhealthy`) with a number, `true` /
+//! `false`, or a word (quoted if it has spaces), using `>`, `>=`, `<`, `<=`,
+//! `==` or `!=`. Conditions can be joined with `and`. A list compares by its
+//! length, so `nodes.offline_nodes > 1` counts offline nodes. A path the
+//! cluster doesn't report never matches.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use super::types::{Alert, AlertSeverity, ClusterStatus};
+use crate::config::AlertRuleConfig;
+
+/// A parsed alert rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub severity: AlertSeverity,
+    conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    path: String,
+    op: Op,
+    value: Operand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Op {
+    fn as_str(self) -> &'static str {
+        match self {
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Eq => "==",
+            Op::Ne => "!=",
+        }
+    }
+}
+
+/// Parse every configured rule, naming the rule that fails.
+pub fn from_config(rules: &[AlertRuleConfig]) -> Result<Vec<Rule>> {
+    rules
+        .iter()
+        .map(|r| {
+            Rule::parse(&r.name, &r.when, r.severity.as_deref())
+                .with_context(|| format!("invalid alert rule '{}'", r.name))
+        })
+        .collect()
+}
+
+impl Rule {
+    pub fn parse(name: &str, when: &str, severity: Option<&str>) -> Result<Rule> {
+        let severity = match severity.unwrap_or("warning") {
+            "critical" => AlertSeverity::Critical,
+            "warning" => AlertSeverity::Warning,
+            "info" => AlertSeverity::Info,
+            other => anyhow::bail!(
+                "unknown severity '{}' (expected critical, warning, info)",
+                other
+            ),
+        };
+        let conditions = when
+            .split(" and ")
+            .map(Condition::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Rule {
+            name: name.to_string(),
+            severity,
+            conditions,
+        })
+    }
+
+    /// When every condition holds for `cluster` (its status as JSON), the
+    /// alert message describing the readings that matched.
+    fn evaluate(&self, cluster: &Value) -> Option<String> {
+        let readings = self
+            .conditions
+            .iter()
+            .map(|c| c.evaluate(cluster))
+            .collect::<Option<Vec<_>>>()?;
+        Some(format!("{}: {}", self.name, readings.join(", ")))
+    }
+}
+
+impl Condition {
+    fn parse(text: &str) -> Result<Condition> {
+        let text = text.trim();
+        // Two-character operators first so ">=" isn't read as ">"
+        let (at, op) = [
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+        ]
+        .iter()
+        .find_map(|(token, op)| text.find(token).map(|at| ((at, token.len()), *op)))
+        .with_context(|| {
+            format!(
+                "'{}' has no comparison (expected e.g. files.total_snapshots > 5000)",
+                text
+            )
+        })?;
+        let path = text[..at.0].trim();
+        let raw = text[at.0 + at.1..].trim();
+        if path.is_empty() || path.contains(char::is_whitespace) {
+            anyhow::bail!("'{}' should start with a field path", text);
+        }
+        if raw.is_empty() {
+            anyhow::bail!("'{}' has nothing to compare with", text);
+        }
+
+        let value = if let Ok(n) = raw.parse::<f64>() {
+            Operand::Number(n)
+        } else if let Ok(b) = raw.parse::<bool>() {
+            Operand::Bool(b)
+        } else {
+            Operand::Text(raw.trim_matches('"').to_string())
+        };
+        if !matches!(value, Operand::Number(_)) && !matches!(op, Op::Eq | Op::Ne) {
+            anyhow::bail!("'{}': {} needs a number", text, op.as_str());
+        }
+        Ok(Condition {
+            path: path.to_string(),
+            op,
+            value,
+        })
+    }
+
+    /// `path is <reading> (<op> <value>)` if the condition holds.
+    fn evaluate(&self, cluster: &Value) -> Option<String> {
+        let pointer = format!("/{}", self.path.replace('.', "/"));
+        let actual = cluster.pointer(&pointer)?;
+        let holds = match (&self.value, actual) {
+            (Operand::Number(want), _) => {
+                let got = match actual {
+                    Value::Array(items) => items.len() as f64,
+                    Value::String(s) => s.parse().ok()?,
+                    other => other.as_f64()?,
+                };
+                compare(self.op, got.total_cmp(want))
+            }
+            (Operand::Bool(want), Value::Bool(got)) => compare(self.op, got.cmp(want)),
+            (Operand::Text(want), Value::String(got)) => {
+                compare(self.op, got.to_lowercase().cmp(&want.to_lowercase()))
+            }
+            _ => false,
+        };
+        if !holds {
+            return None;
+        }
+        let reading = match actual {
+            Value::Array(items) => items.len().to_string(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let wanted = match &self.value {
+            Operand::Number(n) => n.to_string(),
+            Operand::Bool(b) => b.to_string(),
+            Operand::Text(s) => s.clone(),
+        };
+        Some(format!(
+            "{} is {} ({} {})",
+            self.path,
+            reading,
+            self.op.as_str(),
+            wanted
+        ))
+    }
+}
+
+fn compare(op: Op, ordering: std::cmp::Ordering) -> bool {
+    use std::cmp::Ordering::*;
+    match op {
+        Op::Gt => ordering == Greater,
+        Op::Ge => ordering != Less,
+        Op::Lt => ordering == Less,
+        Op::Le => ordering != Greater,
+        Op::Eq => ordering == Equal,
+        Op::Ne => ordering != Equal,
+    }
+}
+
+/// One alert per rule that matches a cluster. The category is `rule:<name>`
+/// so a single rule can be acknowledged.
+pub fn evaluate(rules: &[Rule], clusters: &[ClusterStatus]) -> Vec<Alert> {
+    if rules.is_empty() {
+        return Vec::new();
+    }
+    let mut alerts = Vec::new();
+    for cluster in clusters {
+        let Ok(value) = serde_json::to_value(cluster) else {
+            continue;
+        };
+        for rule in rules {
+            if let Some(message) = rule.evaluate(&value) {
+                alerts.push(Alert {
+                    severity: rule.severity.clone(),
+                    cluster: cluster.name.clone(),
+                    message,
+                    category: format!("rule:{}", rule.name),
+                });
+            }
+        }
+    }
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cluster() -> Value {
+        json!({
+            "name": "music",
+            "version": "7.8.0",
+            "reachable": true,
+            "capacity": {"used_pct": 91.5, "used_bytes": 900},
+            "files": {"total_snapshots": 6000},
+            "nodes": {"offline_nodes": [2, 4]},
+            "health": {"status": "degraded"},
+        })
+    }
+
+    fn matches(when: &str) -> Option<String> {
+        Rule::parse("r", when, None).unwrap().evaluate(&cluster())
+    }
+
+    #[test]
+    fn test_numeric_comparisons() {
+        assert_eq!(
+            matches("files.total_snapshots > 5000").unwrap(),
+            "r: files.total_snapshots is 6000 (> 5000)"
+        );
+        assert!(matches("files.total_snapshots >= 6000").is_some());
+        assert!(matches("files.total_snapshots < 5000").is_none());
+        assert!(matches("capacity.used_pct <= 91.5").is_some());
+        assert!(matches("capacity.used_pct != 91.5").is_none());
+    }
+
+    #[test]
+    fn test_lists_bools_text_and_conjunctions() {
+        assert!(matches("nodes.offline_nodes > 1").is_some());
+        assert!(matches("reachable == true").is_some());
+        assert!(matches("health.status == Degraded").is_some());
+        assert!(matches("version != \"7.8.0\"").is_none());
+        assert_eq!(
+            matches("capacity.used_pct > 90 and nodes.offline_nodes >= 2").unwrap(),
+            "r: capacity.used_pct is 91.5 (> 90), nodes.offline_nodes is 2 (>= 2)"
+        );
+        assert!(matches("capacity.used_pct > 90 and reachable == false").is_none());
+        // A field the cluster doesn't report never matches
+        assert!(matches("capacity.projection.days_until_full < 30").is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |when: &str, severity: Option<&str>| {
+            Rule::parse("r", when, severity).unwrap_err().to_string()
+        };
+        assert!(err("files.total_snapshots", None).contains("has no comparison"));
+        assert!(err("> 5", None).contains("should start with a field path"));
+        assert!(err("files.total_snapshots >", None).contains("nothing to compare"));
+        assert!(err("version > abc", None).contains("> needs a number"));
+        assert!(err("x > 1", Some("page")).contains("unknown severity 'page'"));
+
+        let rules = vec![AlertRuleConfig {
+            name: "broken".to_string(),
+            when: "nope".to_string(),
+            severity: None,
+        }];
+        let err = from_config(&rules).unwrap_err();
+        assert_eq!(err.to_string(), "invalid alert rule 'broken'");
+    }
+}
@@ -0,0 +1,331 @@
+//! A small read-only HTTP API over the status cache.
+//!
+//! `qontrol serve` (or `qontrol daemon --listen`) answers from whatever the
+//! daemon last cached, so other tooling can ask qontrol for fleet status
+//! instead of each program polling every cluster:
+//!
+//! - `GET /status` — the same document as `fleet status --json`
+//! - `GET /clusters/<profile>` — one cluster from it
+//! - `GET /alerts` — its `alerts` and `acknowledged_alerts`
+//! - `GET /healthz` — when the daemon last completed a poll
+//!   (`qontrol_last_successful_poll`) and how long ago; 503 once that is
+//!   older than `--max-age`, so a stalled daemon gets noticed
+//!
+//! Responses are JSON; errors are `{"error": "..."}`.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+
+use super::build_cached_status;
+use super::cache;
+use super::json::JsonOutput;
+use super::types::EnvironmentStatus;
+
+/// How long a client gets to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serve cached status on `listen` until the process exits. Clusters cached
+/// longer than `max_age` ago are reported stale.
+pub fn run(config: &Config, profiles: &[String], listen: &str, max_age: Duration) -> Result<()> {
+    let listener = bind(listen)?;
+    eprintln!("Serving cached status on http://{}", listener.local_addr()?);
+    serve(listener, config, profiles, max_age);
+    Ok(())
+}
+
+/// Serve in a background thread, for `daemon --listen`. Binding happens
+/// before returning, so a bad address fails the caller.
+pub fn spawn(config: &Config, profiles: &[String], listen: &str, max_age: Duration) -> Result<()> {
+    let listener = bind(listen)?;
+    let config = config.clone();
+    let profiles = profiles.to_vec();
+    thread::spawn(move || serve(listener, &config, &profiles, max_age));
+    Ok(())
+}
+
+fn bind(listen: &str) -> Result<TcpListener> {
+    TcpListener::bind(listen).with_context(|| format!("failed to listen on {}", listen))
+}
+
+fn serve(listener: TcpListener, config: &Config, profiles: &[String], max_age: Duration) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to accept connection");
+                continue;
+            }
+        };
+        if let Err(e) = handle(stream, config, profiles, max_age) {
+            tracing::debug!(error = %e, "failed to answer request");
+        }
+    }
+}
+
+fn handle(
+    stream: TcpStream,
+    config: &Config,
+    profiles: &[String],
+    max_age: Duration,
+) -> Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers and any body are ignored
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+    let (code, body) = if method == "GET" {
+        route(
+            path,
+            build_cached_status(config, profiles, Some(max_age)),
+            last_poll(config, profiles),
+            max_age,
+        )
+    } else {
+        (
+            405,
+            json!({"error": format!("{} is not supported; use GET", method)}),
+        )
+    };
+    tracing::debug!(%method, %path, code, "served request");
+
+    let body = serde_json::to_string_pretty(&body)? + "\n";
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason(code),
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// When the newest cached cluster status was written, i.e. the daemon's
+/// last successful poll of any of `profiles`
+fn last_poll(config: &Config, profiles: &[String]) -> Option<DateTime<Utc>> {
+    let names: Vec<String> = config
+        .select_profiles(profiles)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    cache::read_all_cache(&names)
+        .iter()
+        .filter_map(|entry| DateTime::parse_from_rfc3339(&entry.cached_at).ok())
+        .map(|at| at.with_timezone(&Utc))
+        .max()
+}
+
+/// The status code and JSON body for a GET of `path`
+fn route(
+    path: &str,
+    status: Option<EnvironmentStatus>,
+    last_poll: Option<DateTime<Utc>>,
+    max_age: Duration,
+) -> (u16, Value) {
+    let path = path.trim_end_matches('/');
+    if path == "/healthz" {
+        return healthz(last_poll, max_age, Utc::now());
+    }
+    let known = path == "/status" || path == "/alerts" || path.starts_with("/clusters/");
+    if !known {
+        let error = format!(
+            "no such endpoint '{}' (try /status, /clusters/<profile>, /alerts, /healthz)",
+            path
+        );
+        return (404, json!({ "error": error }));
+    }
+    let Some(status) = status else {
+        return (
+            503,
+            json!({"error": "no cached status yet — is `qontrol daemon` running?"}),
+        );
+    };
+    let output = JsonOutput::from_status(&status);
+
+    if let Some(profile) = path.strip_prefix("/clusters/") {
+        let profile = urlencoding::decode(profile)
+            .map(|p| p.into_owned())
+            .unwrap_or_else(|_| profile.to_string());
+        return match output.clusters.iter().find(|c| c.profile == profile) {
+            Some(cluster) => (200, json!(cluster)),
+            None => (
+                404,
+                json!({"error": format!("no cached status for profile '{}'", profile)}),
+            ),
+        };
+    }
+    if path == "/alerts" {
+        return (
+            200,
+            json!({
+                "timestamp": output.timestamp,
+                "alerts": output.alerts,
+                "acknowledged_alerts": output.acknowledged_alerts,
+            }),
+        );
+    }
+    (200, json!(output))
+}
+
+/// Liveness of the daemon behind the cache: 200 while the last successful
+/// poll is within `max_age` of `now`, 503 when it is older or there is none.
+fn healthz(
+    last_poll: Option<DateTime<Utc>>,
+    max_age: Duration,
+    now: DateTime<Utc>,
+) -> (u16, Value) {
+    let Some(last_poll) = last_poll else {
+        return (
+            503,
+            json!({
+                "status": "no_data",
+                "stale": true,
+                "qontrol_last_successful_poll": null,
+                "max_age_secs": max_age.as_secs(),
+            }),
+        );
+    };
+    let age = (now - last_poll).num_seconds().max(0) as u64;
+    let stale = age > max_age.as_secs();
+    (
+        if stale { 503 } else { 200 },
+        json!({
+            "status": if stale { "stale" } else { "ok" },
+            "stale": stale,
+            "qontrol_last_successful_poll": last_poll.timestamp(),
+            "last_successful_poll": last_poll.to_rfc3339(),
+            "age_secs": age,
+            "max_age_secs": max_age.as_secs(),
+        }),
+    )
+}
+
+fn reason(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::status::collector::build_aggregates;
+    use crate::commands::status::types::*;
+
+    fn make_status() -> EnvironmentStatus {
+        let cluster = ClusterStatus {
+            profile: "prod a".to_string(),
+            name: "prod-a".to_string(),
+            uuid: "uuid".to_string(),
+            version: "7.0".to_string(),
+            cluster_type: ClusterType::OnPrem(vec![]),
+            reachable: true,
+            stale: false,
+            latency_ms: 10,
+            degraded: Vec::new(),
+            partial: false,
+            nodes: NodeStatus {
+                total: 1,
+                online: 1,
+                offline_nodes: vec![],
+                details: vec![],
+            },
+            capacity: CapacityStatus::default(),
+            activity: ActivityStatus::default(),
+            files: FileStats::default(),
+            health: HealthStatus {
+                status: HealthLevel::Healthy,
+                issues: vec![],
+                disks_unhealthy: 0,
+                psus_unhealthy: 0,
+                data_at_risk: false,
+                remaining_node_failures: None,
+                remaining_drive_failures: None,
+                protection_type: None,
+                unhealthy_disk_details: vec![],
+                unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
+            },
+        };
+        EnvironmentStatus {
+            aggregates: build_aggregates(std::slice::from_ref(&cluster)),
+            alerts: vec![Alert {
+                severity: AlertSeverity::Warning,
+                cluster: "prod a".to_string(),
+                message: "capacity 85%".to_string(),
+                category: "capacity".to_string(),
+            }],
+            acknowledged: vec![],
+            clusters: vec![cluster],
+        }
+    }
+
+    #[test]
+    fn test_route() {
+        let max_age = Duration::from_secs(300);
+        let recent = Utc::now() - chrono::Duration::seconds(30);
+        let route = |path: &str, status: Option<EnvironmentStatus>| {
+            route(path, status, Some(recent), max_age)
+        };
+
+        let (code, body) = route("/status", Some(make_status()));
+        assert_eq!(code, 200);
+        assert_eq!(body["aggregates"]["cluster_count"], 1);
+
+        let (code, body) = route("/clusters/prod%20a/", Some(make_status()));
+        assert_eq!(code, 200);
+        assert_eq!(body["cluster_name"], "prod-a");
+
+        let (code, _) = route("/clusters/prod-b", Some(make_status()));
+        assert_eq!(code, 404);
+
+        let (code, body) = route("/alerts", Some(make_status()));
+        assert_eq!(code, 200);
+        assert_eq!(body["alerts"][0]["message"], "capacity 85%");
+
+        assert_eq!(route("/status", None).0, 503);
+        assert_eq!(route("/metrics", Some(make_status())).0, 404);
+
+        let (code, body) = route("/healthz", Some(make_status()));
+        assert_eq!(code, 200);
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["qontrol_last_successful_poll"], recent.timestamp());
+        assert!(body["age_secs"].as_u64().unwrap() >= 30);
+
+        let old = Some(Utc::now() - chrono::Duration::seconds(600));
+        let (code, body) = super::route("/healthz", None, old, max_age);
+        assert_eq!(code, 503);
+        assert_eq!(body["status"], "stale");
+        assert_eq!(body["stale"], true);
+
+        let (code, body) = super::route("/healthz", None, None, max_age);
+        assert_eq!(code, 503);
+        assert_eq!(body["status"], "no_data");
+    }
+}
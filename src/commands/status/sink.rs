@@ -0,0 +1,174 @@
+//! Extra destinations for each poll's JSON (`fleet status --sink`), delivered
+//! alongside whatever is rendered to the terminal:
+//!
+//! - `file:PATH` appends one JSON line per poll
+//! - `webhook:URL` POSTs the JSON to the URL
+//! - `heartbeat:PATH` rewrites a Prometheus textfile holding
+//!   `qontrol_last_successful_poll`, so a stalled watch can itself be alerted on
+//!
+//! A failed delivery is logged and the poll goes on; one unreachable
+//! webhook shouldn't stop a watch.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use super::json::JsonOutput;
+
+/// How long a webhook gets to accept a delivery
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sink {
+    File(PathBuf),
+    Webhook(String),
+    Heartbeat(PathBuf),
+}
+
+impl Sink {
+    pub fn parse(spec: &str) -> Result<Sink> {
+        let invalid = || {
+            anyhow::anyhow!(
+                "invalid --sink '{}' (expected file:PATH, webhook:URL or heartbeat:PATH)",
+                spec
+            )
+        };
+        let (kind, target) = spec.split_once(':').ok_or_else(invalid)?;
+        if target.is_empty() {
+            return Err(invalid());
+        }
+        match kind {
+            "file" => Ok(Sink::File(PathBuf::from(target))),
+            "heartbeat" => Ok(Sink::Heartbeat(PathBuf::from(target))),
+            "webhook" if target.starts_with("http://") || target.starts_with("https://") => {
+                Ok(Sink::Webhook(target.to_string()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    fn deliver(&self, body: &str) -> Result<()> {
+        match self {
+            Sink::File(path) => {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("failed to open {}", path.display()))?;
+                writeln!(file, "{}", body)
+                    .with_context(|| format!("failed to write {}", path.display()))
+            }
+            Sink::Webhook(url) => {
+                let response = reqwest::blocking::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()?
+                    .post(url)
+                    .header("Content-Type", "application/json")
+                    .body(body.to_string())
+                    .send()
+                    .with_context(|| format!("failed to reach {}", url))?;
+                if !response.status().is_success() {
+                    anyhow::bail!("{} answered HTTP {}", url, response.status().as_u16());
+                }
+                Ok(())
+            }
+            Sink::Heartbeat(path) => {
+                // Replace via rename so the textfile collector never scrapes
+                // a half-written file
+                let tmp = path.with_extension("part");
+                std::fs::write(&tmp, heartbeat_metric(chrono::Utc::now().timestamp()))
+                    .with_context(|| format!("failed to write {}", tmp.display()))?;
+                std::fs::rename(&tmp, path)
+                    .with_context(|| format!("failed to write {}", path.display()))
+            }
+        }
+    }
+}
+
+/// The heartbeat file's contents in Prometheus text exposition format.
+fn heartbeat_metric(unix_secs: i64) -> String {
+    format!(
+        "# HELP qontrol_last_successful_poll Unix time of the last completed status poll.\n\
+         # TYPE qontrol_last_successful_poll gauge\n\
+         qontrol_last_successful_poll {}\n",
+        unix_secs
+    )
+}
+
+/// Deliver one poll's status to every sink.
+pub fn deliver_all(sinks: &[Sink], output: &JsonOutput) -> Result<()> {
+    if sinks.is_empty() {
+        return Ok(());
+    }
+    let body = serde_json::to_string(output).context("failed to serialize status")?;
+    for sink in sinks {
+        if let Err(e) = sink.deliver(&body) {
+            tracing::warn!(sink = ?sink, error = %e, "failed to deliver status to sink");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sinks() {
+        assert_eq!(
+            Sink::parse("file:/var/log/qontrol.jsonl").unwrap(),
+            Sink::File(PathBuf::from("/var/log/qontrol.jsonl"))
+        );
+        assert_eq!(
+            Sink::parse("webhook:https://hooks.example.com/q?x=1").unwrap(),
+            Sink::Webhook("https://hooks.example.com/q?x=1".to_string())
+        );
+        assert_eq!(
+            Sink::parse("heartbeat:/var/lib/node_exporter/qontrol.prom").unwrap(),
+            Sink::Heartbeat(PathBuf::from("/var/lib/node_exporter/qontrol.prom"))
+        );
+        for bad in ["file:", "webhook:ftp://x", "syslog:local0", "status.jsonl"] {
+            let err = Sink::parse(bad).unwrap_err().to_string();
+            assert!(
+                err.contains("expected file:PATH, webhook:URL or heartbeat:PATH"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_file_sink_appends_lines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("status.jsonl");
+        let sink = Sink::File(path.clone());
+        sink.deliver(r#"{"poll":1}"#).unwrap();
+        sink.deliver(r#"{"poll":2}"#).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "{\"poll\":1}\n{\"poll\":2}\n"
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_sink_replaces_metric() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("qontrol.prom");
+        std::fs::write(&path, "qontrol_last_successful_poll 1\n").unwrap();
+        let before = chrono::Utc::now().timestamp();
+        Sink::Heartbeat(path.clone()).deliver("{}").unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("# TYPE qontrol_last_successful_poll gauge\n"));
+        let value: i64 = text
+            .lines()
+            .find_map(|l| l.strip_prefix("qontrol_last_successful_poll "))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(value >= before);
+        assert!(!tmp.path().join("qontrol.part").exists());
+    }
+}
@@ -23,7 +23,7 @@ pub fn render_timing_report(report: &TimingReport) {
 
     // Sort API calls by duration descending (slowest first)
     let mut sorted = report.api_calls.clone();
-    sorted.sort_by(|a, b| b.duration_ms.cmp(&a.duration_ms));
+    sorted.sort_by_key(|e| std::cmp::Reverse(e.duration_ms));
 
     let max_cluster = sorted.iter().map(|e| e.cluster.len()).max().unwrap_or(0);
     let max_api = sorted.iter().map(|e| e.api_call.len()).max().unwrap_or(0);
@@ -44,7 +44,7 @@ pub fn render_timing_report(report: &TimingReport) {
     // Cluster wall-clock totals
     if !report.cluster_wall_clock.is_empty() {
         let mut totals = report.cluster_wall_clock.clone();
-        totals.sort_by(|a, b| b.1.cmp(&a.1));
+        totals.sort_by_key(|t| std::cmp::Reverse(t.1));
 
         let max_name = totals.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
 
@@ -130,7 +130,7 @@ mod tests {
         };
 
         let mut sorted = report.api_calls.clone();
-        sorted.sort_by(|a, b| b.duration_ms.cmp(&a.duration_ms));
+        sorted.sort_by_key(|e| std::cmp::Reverse(e.duration_ms));
         assert_eq!(sorted[0].duration_ms, 5000);
         assert_eq!(sorted[1].duration_ms, 200);
         assert_eq!(sorted[2].duration_ms, 10);
@@ -148,7 +148,7 @@ mod tests {
         };
 
         let mut totals = report.cluster_wall_clock.clone();
-        totals.sort_by(|a, b| b.1.cmp(&a.1));
+        totals.sort_by_key(|t| std::cmp::Reverse(t.1));
         assert_eq!(totals[0].0, "slow_cluster");
         assert_eq!(totals[1].0, "medium_cluster");
         assert_eq!(totals[2].0, "fast_cluster");
@@ -190,23 +190,23 @@ mod tests {
     #[test]
     fn test_api_call_timing_accumulator() {
         // Verify the accumulator pattern works correctly
-        let mut timings: Vec<ApiCallTiming> = Vec::new();
-
-        timings.push(ApiCallTiming {
-            cluster: "c1".into(),
-            api_call: "get_version".into(),
-            duration_ms: 100,
-        });
-        timings.push(ApiCallTiming {
-            cluster: "c1".into(),
-            api_call: "get_nodes".into(),
-            duration_ms: 200,
-        });
-        timings.push(ApiCallTiming {
-            cluster: "c2".into(),
-            api_call: "get_version".into(),
-            duration_ms: 50,
-        });
+        let timings: Vec<ApiCallTiming> = vec![
+            ApiCallTiming {
+                cluster: "c1".into(),
+                api_call: "get_version".into(),
+                duration_ms: 100,
+            },
+            ApiCallTiming {
+                cluster: "c1".into(),
+                api_call: "get_nodes".into(),
+                duration_ms: 200,
+            },
+            ApiCallTiming {
+                cluster: "c2".into(),
+                api_call: "get_version".into(),
+                duration_ms: 50,
+            },
+        ];
 
         assert_eq!(timings.len(), 3);
 
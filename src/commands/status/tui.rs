@@ -0,0 +1,565 @@
+//! Full-screen `fleet status --tui` dashboard.
+//!
+//! Frames are drawn on the alternate screen by overwriting lines in place
+//! (cursor home, clear-to-end-of-line) rather than clearing the terminal, so
+//! refreshes don't flicker. Collection runs on a background thread; key
+//! presses are read on another and both feed one event channel.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use console::{measure_text_width, truncate_str, Key, Style, Term};
+
+use crate::config::Config;
+
+use super::types::{AlertSeverity, EnvironmentStatus};
+use super::view::DisplayOptions;
+use super::{collector, renderer, track_nic_deltas, WatchState};
+
+/// Something the dashboard loop reacts to.
+enum Event {
+    Key(Key),
+    Status(Box<EnvironmentStatus>),
+    CollectError(String),
+}
+
+/// What a key press asks the loop to do besides updating the view.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    None,
+    Refresh,
+    Quit,
+}
+
+/// View state: which tab is showing, how far it is scrolled, and whether
+/// the alerts pane is expanded.
+#[derive(Debug, Default)]
+struct View {
+    /// 0 is the overview; 1.. are clusters in status order
+    tab: usize,
+    scroll: usize,
+    alerts_expanded: bool,
+}
+
+impl View {
+    fn handle_key(&mut self, key: &Key, tab_count: usize) -> Action {
+        let tab_count = tab_count.max(1);
+        match key {
+            Key::Char('q') | Key::Escape | Key::CtrlC => return Action::Quit,
+            Key::Char('r') => return Action::Refresh,
+            Key::Char('a') => self.alerts_expanded = !self.alerts_expanded,
+            Key::Tab | Key::ArrowRight | Key::Char('l') => self.set_tab((self.tab + 1) % tab_count),
+            Key::BackTab | Key::ArrowLeft | Key::Char('h') => {
+                self.set_tab((self.tab + tab_count - 1) % tab_count)
+            }
+            Key::Char(c @ '0'..='9') => {
+                let tab = c.to_digit(10).unwrap_or(0) as usize;
+                if tab < tab_count {
+                    self.set_tab(tab);
+                }
+            }
+            Key::ArrowDown | Key::Char('j') => self.scroll += 1,
+            Key::ArrowUp | Key::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            Key::PageDown | Key::Char(' ') => self.scroll += 10,
+            Key::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            Key::Home | Key::Char('g') => self.scroll = 0,
+            Key::End | Key::Char('G') => self.scroll = usize::MAX,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn set_tab(&mut self, tab: usize) {
+        if tab != self.tab {
+            self.tab = tab;
+            self.scroll = 0;
+        }
+    }
+}
+
+/// Run the dashboard until the user quits. `--sort` and `--only-degraded`
+/// apply to the tabs; `--compact` is ignored.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    config: &Config,
+    display: &DisplayOptions,
+    interval: u64,
+    no_cache: bool,
+    timeout_secs: u64,
+    full_refresh_every: u64,
+    jobs: usize,
+    cluster_budget: Option<Duration>,
+) -> Result<()> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        anyhow::bail!("--tui needs an interactive terminal");
+    }
+
+    let (events, rx) = mpsc::channel();
+    let refresh = spawn_collector(
+        config.clone(),
+        display.clone(),
+        interval,
+        no_cache,
+        timeout_secs,
+        full_refresh_every,
+        jobs,
+        cluster_budget,
+        events.clone(),
+    );
+    spawn_key_reader(events);
+
+    // Ctrl+C outside a key read arrives as a signal; restore the screen first
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = interrupted.clone();
+    ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst)).ok();
+
+    print!("\x1B[?1049h\x1B[?25l");
+    let result = event_loop(&term, &rx, &refresh, &interrupted, interval);
+    print!("\x1B[?25h\x1B[?1049l");
+    std::io::stdout().flush().ok();
+    result
+}
+
+fn event_loop(
+    term: &Term,
+    rx: &Receiver<Event>,
+    refresh: &Sender<()>,
+    interrupted: &AtomicBool,
+    interval: u64,
+) -> Result<()> {
+    let mut view = View::default();
+    let mut status: Option<EnvironmentStatus> = None;
+    let mut previous: Option<EnvironmentStatus> = None;
+    let mut updated: Option<chrono::DateTime<chrono::Local>> = None;
+    let mut error: Option<String> = None;
+
+    loop {
+        let (rows, cols) = term.size();
+        let footer = footer_line(updated, error.as_deref(), interval);
+        let frame = render_frame(
+            status.as_ref(),
+            previous.as_ref(),
+            &mut view,
+            rows as usize,
+            cols as usize,
+            &footer,
+        );
+        draw(&frame)?;
+
+        let event = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        if interrupted.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        match event {
+            Some(Event::Key(key)) => {
+                let tabs = status.as_ref().map_or(1, |s| s.clusters.len() + 1);
+                match view.handle_key(&key, tabs) {
+                    Action::Quit => return Ok(()),
+                    Action::Refresh => {
+                        refresh.send(()).ok();
+                    }
+                    Action::None => {}
+                }
+            }
+            Some(Event::Status(new)) => {
+                previous = status.take();
+                status = Some(*new);
+                updated = Some(chrono::Local::now());
+                error = None;
+            }
+            Some(Event::CollectError(e)) => error = Some(e),
+            None => {}
+        }
+    }
+}
+
+/// Poll `collect_all` the way `--watch` does and send each status to the
+/// dashboard. A message on the returned sender triggers an immediate poll.
+#[allow(clippy::too_many_arguments)]
+fn spawn_collector(
+    config: Config,
+    display: DisplayOptions,
+    interval: u64,
+    no_cache: bool,
+    timeout_secs: u64,
+    full_refresh_every: u64,
+    jobs: usize,
+    cluster_budget: Option<Duration>,
+    events: Sender<Event>,
+) -> Sender<()> {
+    let (refresh, wake) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        let mut previous: Option<EnvironmentStatus> = None;
+        let mut watch_state: Option<WatchState> = None;
+        let mut poll: u64 = 0;
+        loop {
+            let full_refresh = poll.is_multiple_of(full_refresh_every.max(1));
+            let collected = collector::collect_all(
+                &config,
+                &display.profiles,
+                collector::CollectOptions {
+                    level: display.level,
+                    projection_model: display.projection_model,
+                    projection_window: display.projection_window,
+                    top_activity: display.top_activity,
+                    timeout_secs,
+                    no_cache,
+                    watch_mode: true,
+                    previous: previous.as_ref().filter(|_| !full_refresh),
+                    json_mode: false,
+                    record_timing: false,
+                    suppress_progress: true,
+                    jobs,
+                    cluster_budget,
+                },
+            );
+            let event = match collected {
+                Ok((mut status, _)) => {
+                    track_nic_deltas(&mut watch_state, &mut status);
+                    match crate::commands::alert::record_transitions(&status) {
+                        Ok(transitions) => crate::notify::notify(&config.notify, &transitions),
+                        Err(e) => tracing::warn!(error = %e, "failed to update alert history"),
+                    }
+                    let shown = display.shape(&status);
+                    previous = Some(status);
+                    poll += 1;
+                    Event::Status(Box::new(shown))
+                }
+                Err(e) => Event::CollectError(format!("{:#}", e)),
+            };
+            if events.send(event).is_err() {
+                return;
+            }
+            match wake.recv_timeout(Duration::from_secs(interval)) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+    refresh
+}
+
+/// Read keys until a quit key, so the terminal is back in cooked mode when
+/// the dashboard exits.
+fn spawn_key_reader(events: Sender<Event>) {
+    thread::spawn(move || {
+        let term = Term::stdout();
+        while let Ok(key) = term.read_key() {
+            let quit = matches!(key, Key::Char('q') | Key::Escape | Key::CtrlC);
+            if events.send(Event::Key(key)).is_err() || quit {
+                return;
+            }
+        }
+    });
+}
+
+fn draw(frame: &[String]) -> Result<()> {
+    let mut out = String::from("\x1B[H");
+    for (i, line) in frame.iter().enumerate() {
+        if i > 0 {
+            out.push_str("\r\n");
+        }
+        out.push_str(line);
+        out.push_str("\x1B[K");
+    }
+    out.push_str("\x1B[J");
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(out.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Lay out one screen: tab bar, scrollable body, alerts pane, footer. Every
+/// line is cut to `width` and exactly `height` lines are returned. Clamps
+/// the view's scroll offset to the body.
+fn render_frame(
+    status: Option<&EnvironmentStatus>,
+    previous: Option<&EnvironmentStatus>,
+    view: &mut View,
+    height: usize,
+    width: usize,
+    footer: &str,
+) -> Vec<String> {
+    let height = height.max(4);
+    let mut lines = Vec::with_capacity(height);
+
+    let Some(status) = status else {
+        lines.push(Style::new().bold().apply_to("qontrol").to_string());
+        lines.push(String::new());
+        lines.push("  Collecting status\u{2026}".to_string());
+        lines.resize(height - 1, String::new());
+        lines.push(footer.to_string());
+        return fit(lines, width);
+    };
+    view.tab = view.tab.min(status.clusters.len());
+
+    lines.push(tab_bar(status, view.tab));
+    lines.push(Style::new().dim().apply_to("─".repeat(width)).to_string());
+
+    let alerts = alerts_pane(status, view, height / 3);
+    let body_height = height.saturating_sub(lines.len() + alerts.len() + 1);
+
+    let mut body = String::new();
+    match view.tab.checked_sub(1).map(|i| &status.clusters[i]) {
+        None => renderer::render_overview(&mut body, status),
+        Some(cluster) => renderer::render_cluster(&mut body, cluster, status, previous),
+    }
+    let body: Vec<&str> = body.lines().collect();
+    view.scroll = view.scroll.min(body.len().saturating_sub(body_height));
+
+    let visible = body.iter().skip(view.scroll).take(body_height);
+    lines.extend(visible.map(|l| l.to_string()));
+    lines.resize(height - alerts.len() - 1, String::new());
+    lines.extend(alerts);
+    lines.push(footer.to_string());
+    fit(lines, width)
+}
+
+fn tab_bar(status: &EnvironmentStatus, selected: usize) -> String {
+    let names = std::iter::once("Overview".to_string()).chain(status.clusters.iter().map(|c| {
+        let critical = status
+            .alerts
+            .iter()
+            .any(|a| a.cluster == c.name && a.severity == AlertSeverity::Critical);
+        match (c.reachable, critical) {
+            (false, _) => format!("{} ✗", c.profile),
+            (true, true) => format!("{} !", c.profile),
+            _ => c.profile.clone(),
+        }
+    }));
+    names
+        .enumerate()
+        .map(|(i, name)| {
+            let label = format!(" {}:{} ", i, name);
+            if i == selected {
+                Style::new().reverse().bold().apply_to(label).to_string()
+            } else {
+                label
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("│")
+}
+
+/// Alerts for the current tab: one summary line collapsed, or up to
+/// `max_lines` of alerts expanded.
+fn alerts_pane(status: &EnvironmentStatus, view: &View, max_lines: usize) -> Vec<String> {
+    let cluster = view.tab.checked_sub(1).map(|i| &status.clusters[i]);
+    let alerts: Vec<_> = status
+        .alerts
+        .iter()
+        .filter(|a| cluster.is_none_or(|c| a.cluster == c.name || a.cluster == c.profile))
+        .cloned()
+        .collect();
+    let bold = Style::new().bold();
+
+    if alerts.is_empty() {
+        return vec![format!(
+            "{} {}",
+            bold.apply_to("Alerts:"),
+            Style::new().green().apply_to("none")
+        )];
+    }
+    let count = |sev: AlertSeverity| alerts.iter().filter(|a| a.severity == sev).count();
+    let summary = format!(
+        "{} {} critical, {} warning, {} info ({} to {})",
+        bold.apply_to("Alerts:"),
+        count(AlertSeverity::Critical),
+        count(AlertSeverity::Warning),
+        count(AlertSeverity::Info),
+        bold.apply_to("a"),
+        if view.alerts_expanded {
+            "collapse"
+        } else {
+            "expand"
+        },
+    );
+    if !view.alerts_expanded {
+        return vec![summary];
+    }
+
+    let mut list = String::new();
+    renderer::render_alert_list(&mut list, &alerts);
+    let max_lines = max_lines.max(2);
+    let mut pane = vec![summary];
+    let lines: Vec<&str> = list.lines().collect();
+    if lines.len() < max_lines {
+        pane.extend(lines.iter().map(|l| l.to_string()));
+    } else {
+        pane.extend(lines.iter().take(max_lines - 2).map(|l| l.to_string()));
+        pane.push(format!("  \u{2026} {} more", lines.len() - (max_lines - 2)));
+    }
+    pane
+}
+
+fn footer_line(
+    updated: Option<chrono::DateTime<chrono::Local>>,
+    error: Option<&str>,
+    interval: u64,
+) -> String {
+    let dim = Style::new().dim();
+    let keys = "←/→ tab · ↑/↓ scroll · a alerts · r refresh · q quit";
+    let state = match (error, updated) {
+        (Some(e), _) => Style::new()
+            .red()
+            .apply_to(format!("error: {}", e))
+            .to_string(),
+        (None, Some(t)) => format!("updated {} · every {}s", t.format("%H:%M:%S"), interval),
+        (None, None) => "collecting\u{2026}".to_string(),
+    };
+    format!("{}  {}", dim.apply_to(keys), state)
+}
+
+fn fit(lines: Vec<String>, width: usize) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|l| {
+            if measure_text_width(&l) > width {
+                truncate_str(&l, width, "").into_owned()
+            } else {
+                l
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::status::types::*;
+
+    fn make_status(nodes: usize) -> EnvironmentStatus {
+        let cluster = |profile: &str| ClusterStatus {
+            profile: profile.into(),
+            name: profile.into(),
+            uuid: String::new(),
+            version: "7.2.0".into(),
+            cluster_type: ClusterType::OnPrem(vec![]),
+            reachable: true,
+            stale: false,
+            latency_ms: 5,
+            degraded: Vec::new(),
+            partial: false,
+            nodes: NodeStatus {
+                total: nodes,
+                online: nodes,
+                offline_nodes: vec![],
+                details: (1..=nodes as u64)
+                    .map(|node_id| NodeNetworkInfo {
+                        node_id,
+                        connections: 3,
+                        connection_breakdown: Default::default(),
+                        subnet_breakdown: Default::default(),
+                        version_breakdown: Default::default(),
+                        nic_throughput_bps: None,
+                        nic_link_speed_bps: None,
+                        nic_utilization_pct: None,
+                        nic_bytes_total: None,
+                    })
+                    .collect(),
+            },
+            capacity: CapacityStatus::default(),
+            activity: ActivityStatus::default(),
+            files: FileStats::default(),
+            health: HealthStatus {
+                status: HealthLevel::Healthy,
+                issues: vec![],
+                disks_unhealthy: 0,
+                psus_unhealthy: 0,
+                data_at_risk: false,
+                remaining_node_failures: None,
+                remaining_drive_failures: None,
+                protection_type: None,
+                unhealthy_disk_details: vec![],
+                unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
+            },
+        };
+        let clusters = vec![cluster("music"), cluster("archive")];
+        EnvironmentStatus {
+            aggregates: collector::build_aggregates(&clusters),
+            alerts: vec![Alert {
+                severity: AlertSeverity::Critical,
+                cluster: "archive".into(),
+                message: "node 2 offline".into(),
+                category: "node_offline".into(),
+            }],
+            acknowledged: vec![],
+            clusters,
+        }
+    }
+
+    #[test]
+    fn test_handle_key_switches_tabs_and_resets_scroll() {
+        let mut view = View {
+            scroll: 5,
+            ..Default::default()
+        };
+        assert_eq!(view.handle_key(&Key::Tab, 3), Action::None);
+        assert_eq!((view.tab, view.scroll), (1, 0));
+        view.handle_key(&Key::ArrowLeft, 3);
+        view.handle_key(&Key::ArrowLeft, 3);
+        assert_eq!(view.tab, 2);
+        view.handle_key(&Key::Char('0'), 3);
+        assert_eq!(view.tab, 0);
+        view.handle_key(&Key::Char('7'), 3);
+        assert_eq!(view.tab, 0);
+        assert_eq!(view.handle_key(&Key::Char('r'), 3), Action::Refresh);
+        assert_eq!(view.handle_key(&Key::Char('q'), 3), Action::Quit);
+    }
+
+    #[test]
+    fn test_render_frame_fills_screen_and_clamps_scroll() {
+        let status = make_status(40);
+        let mut view = View {
+            tab: 1,
+            scroll: usize::MAX,
+            ..Default::default()
+        };
+        let frame = render_frame(Some(&status), None, &mut view, 20, 60, "footer");
+        assert_eq!(frame.len(), 20);
+        assert!(frame.iter().all(|l| measure_text_width(l) <= 60));
+        assert!(frame[0].contains("1:music"));
+        assert_eq!(frame[19], "footer");
+        assert!(view.scroll > 0 && view.scroll < usize::MAX);
+        // The last node is visible once scrolled to the end
+        assert!(frame.iter().any(|l| l.contains("node40:")));
+    }
+
+    #[test]
+    fn test_alerts_pane_expands_per_cluster() {
+        let status = make_status(1);
+        let mut view = View::default();
+        let collapsed = alerts_pane(&status, &view, 10);
+        assert_eq!(collapsed.len(), 1);
+        assert!(collapsed[0].contains("1 critical"));
+
+        view.alerts_expanded = true;
+        let expanded = alerts_pane(&status, &view, 10);
+        assert_eq!(expanded.len(), 2);
+        assert!(expanded[1].contains("archive: node 2 offline"));
+
+        // The music tab has no alerts of its own
+        view.tab = 1;
+        assert!(alerts_pane(&status, &view, 10)[0].contains("none"));
+    }
+
+    #[test]
+    fn test_render_frame_before_first_poll() {
+        let mut view = View::default();
+        let frame = render_frame(None, None, &mut view, 10, 80, "footer");
+        assert_eq!(frame.len(), 10);
+        assert!(frame[2].contains("Collecting status"));
+    }
+}
@@ -2,11 +2,16 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::{ProjectionModel, ProjectionWindow};
+
 /// Top-level environment status aggregating all clusters.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvironmentStatus {
     pub aggregates: Aggregates,
     pub alerts: Vec<Alert>,
+    /// Alerts silenced by `alert ack`, kept apart from `alerts`
+    #[serde(default)]
+    pub acknowledged: Vec<Alert>,
     pub clusters: Vec<ClusterStatus>,
 }
 
@@ -51,6 +56,14 @@ pub struct ClusterStatus {
     pub reachable: bool,
     pub stale: bool,
     pub latency_ms: u64,
+    /// Endpoint groups that still failed after a retry; their fields hold
+    /// defaults rather than real readings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<String>,
+    /// Collection ran out of its `--cluster-budget` and abandoned the
+    /// endpoints still pending; they are listed in `degraded`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
     pub nodes: NodeStatus,
     pub capacity: CapacityStatus,
     pub activity: ActivityStatus,
@@ -76,6 +89,14 @@ pub struct NodeNetworkInfo {
     pub connections: u32,
     #[serde(default)]
     pub connection_breakdown: HashMap<String, u32>,
+    /// Connections by `[subnets]` label, with unmatched addresses under
+    /// "unlabeled" (empty when no subnets are configured)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub subnet_breakdown: HashMap<String, u32>,
+    /// Connections by protocol and version ("NFSv3", "NFSv4.1", "SMB3"),
+    /// for connections whose version the cluster reports
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub version_breakdown: HashMap<String, u32>,
     /// Current NIC throughput in bits per second (None if unavailable)
     pub nic_throughput_bps: Option<u64>,
     /// Link speed in bits per second (None for cloud clusters)
@@ -98,14 +119,39 @@ pub struct CapacityStatus {
     pub used_pct: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub projection: Option<CapacityProjection>,
+    /// Largest paths by used capacity (only collected at `--level full`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub largest_paths: Vec<PathCapacity>,
+    /// Daily used bytes over the projection's 30-day history, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trend: Vec<u64>,
+}
+
+/// A path and the capacity it uses, from the capacity analytics or
+/// recursive aggregates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathCapacity {
+    pub path: String,
+    pub used_bytes: u64,
 }
 
-/// Capacity projection from linear regression on historical usage data.
+/// Capacity projection fitted to historical usage data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapacityProjection {
     pub days_until_full: Option<u64>,
     pub growth_rate_bytes_per_day: f64,
     pub confidence: ProjectionConfidence,
+    /// Model the growth rate came from (caches written before models were
+    /// configurable are linear)
+    #[serde(default)]
+    pub model: ProjectionModel,
+    /// Days of history the model was fitted to
+    #[serde(default = "default_window_days")]
+    pub window_days: u32,
+}
+
+fn default_window_days() -> u32 {
+    ProjectionWindow::default().days()
 }
 
 /// Confidence level for capacity projections.
@@ -125,6 +171,27 @@ pub struct ActivityStatus {
     pub throughput_write: f64,
     pub connections: usize,
     pub is_idle: bool,
+    /// Hottest paths and clients (only collected with `--top-activity`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<TopActivity>,
+}
+
+/// The busiest paths and clients from the current activity analytics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopActivity {
+    pub paths: Vec<ActivityTalker>,
+    pub clients: Vec<ActivityTalker>,
+}
+
+/// A path or client IP with its combined file + metadata IOPS and throughput.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityTalker {
+    pub name: String,
+    /// `[subnets]` label of a client IP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub iops: f64,
+    pub throughput: f64,
 }
 
 /// File system statistics.
@@ -134,6 +201,10 @@ pub struct FileStats {
     pub total_directories: u64,
     pub total_snapshots: u64,
     pub snapshot_bytes: u64,
+    /// Largest directories by capacity from the recursive-aggregates walk
+    /// (only collected when the profile sets `collect.aggregates_max_depth`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub largest_directories: Vec<PathCapacity>,
 }
 
 /// Overall health status of a cluster.
@@ -157,6 +228,25 @@ pub struct HealthStatus {
     pub unhealthy_disk_details: Vec<UnhealthyDisk>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub unhealthy_psu_details: Vec<UnhealthyPsu>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worn_disks: Vec<WornDisk>,
+    /// Active Directory join; None when the cluster isn't joined to a domain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<DirectoryStatus>,
+}
+
+/// A cluster's Active Directory join and its connections to the domain
+/// controllers, from /v1/ad/status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryStatus {
+    pub domain: String,
+    /// Join state as reported, e.g. JOINED
+    pub status: String,
+    /// Domain controllers the cluster currently uses
+    pub domain_controllers: Vec<String>,
+    /// Nodes without a working LDAP connection to any DC
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disconnected_nodes: Vec<u64>,
 }
 
 /// Details of an unhealthy disk.
@@ -168,6 +258,13 @@ pub struct UnhealthyDisk {
     pub state: String,
 }
 
+/// An SSD past the configured share of its rated write endurance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WornDisk {
+    pub id: String,
+    pub wear_pct: f64,
+}
+
 /// Details of an unhealthy PSU.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnhealthyPsu {
@@ -278,6 +375,8 @@ mod tests {
             reachable: true,
             stale: false,
             latency_ms: 42,
+            degraded: Vec::new(),
+            partial: false,
             nodes: NodeStatus {
                 total: 4,
                 online: 4,
@@ -291,6 +390,8 @@ mod tests {
                 snapshot_bytes: 0,
                 used_pct: 50.0,
                 projection: None,
+                largest_paths: Vec::new(),
+                trend: Vec::new(),
             },
             activity: ActivityStatus::default(),
             files: FileStats::default(),
@@ -305,6 +406,8 @@ mod tests {
                 protection_type: Some("PROTECTION_SYSTEM_TYPE_EC".to_string()),
                 unhealthy_disk_details: vec![],
                 unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
             },
         };
 
@@ -328,6 +431,8 @@ mod tests {
                 m.insert("REST".to_string(), 12);
                 m
             },
+            subnet_breakdown: HashMap::new(),
+            version_breakdown: HashMap::new(),
             nic_throughput_bps: Some(12_400_000_000),
             nic_link_speed_bps: Some(200_000_000_000),
             nic_utilization_pct: Some(6.2),
@@ -349,6 +454,8 @@ mod tests {
             node_id: 1,
             connections: 5,
             connection_breakdown: HashMap::new(),
+            subnet_breakdown: HashMap::new(),
+            version_breakdown: HashMap::new(),
             nic_throughput_bps: Some(1_000_000),
             nic_link_speed_bps: None,
             nic_utilization_pct: None,
@@ -378,6 +485,7 @@ mod tests {
                 message: "node offline".to_string(),
                 category: "nodes".to_string(),
             }],
+            acknowledged: vec![],
             clusters: vec![],
         };
 
@@ -400,6 +508,8 @@ mod tests {
                 reachable: true,
                 stale: false,
                 latency_ms: 10,
+                degraded: Vec::new(),
+                partial: false,
                 nodes: NodeStatus {
                     total: 1,
                     online: 1,
@@ -420,6 +530,8 @@ mod tests {
                     protection_type: None,
                     unhealthy_disk_details: vec![],
                     unhealthy_psu_details: vec![],
+                    worn_disks: vec![],
+                    directory: None,
                 },
             }),
             latency_ms: 10,
@@ -0,0 +1,343 @@
+use anyhow::Result;
+
+use crate::config::{CollectLevel, Config, ProjectionModel, ProjectionWindow};
+
+use super::health;
+use super::types::{Alert, AlertSeverity, ClusterStatus, EnvironmentStatus, HealthLevel};
+
+/// Cluster ordering for `fleet status --sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterSort {
+    Name,
+    /// Fullest first
+    Capacity,
+    /// Slowest first
+    Latency,
+    /// Worst first: unreachable, critical, degraded, healthy
+    Health,
+    /// Lowest health score first
+    Score,
+}
+
+impl ClusterSort {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "name" => Ok(Self::Name),
+            "capacity" => Ok(Self::Capacity),
+            "latency" => Ok(Self::Latency),
+            "health" => Ok(Self::Health),
+            "score" => Ok(Self::Score),
+            other => anyhow::bail!(
+                "unknown sort '{}' (expected name, capacity, latency, health, score)",
+                other
+            ),
+        }
+    }
+}
+
+/// What `fleet status` collects and how it is shown, after merging command
+/// line flags with a saved `[views.<name>]` entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DisplayOptions {
+    pub profiles: Vec<String>,
+    pub sort: Option<ClusterSort>,
+    pub compact: bool,
+    pub only_degraded: bool,
+    pub level: Option<CollectLevel>,
+    /// Override each profile's `collect.projection_model`
+    pub projection_model: Option<ProjectionModel>,
+    /// Override each profile's `collect.projection_window`
+    pub projection_window: Option<ProjectionWindow>,
+    pub top_activity: bool,
+}
+
+impl DisplayOptions {
+    /// Merge flags with the named view. Flags win: explicit profiles, sort and
+    /// level replace the view's, and switches are on if either turns them on.
+    /// The projection flags have no view setting.
+    #[allow(clippy::too_many_arguments)]
+    pub fn resolve(
+        config: &Config,
+        view: Option<&str>,
+        profiles: &[String],
+        sort: Option<&str>,
+        compact: bool,
+        only_degraded: bool,
+        level: Option<&str>,
+        projection_model: Option<&str>,
+        projection_window: Option<&str>,
+        top_activity: bool,
+    ) -> Result<Self> {
+        let saved = view.map(|name| config.view(name)).transpose()?;
+        let sort = sort.or_else(|| saved.and_then(|v| v.sort.as_deref()));
+        let level = match level {
+            Some(level) => Some(CollectLevel::parse(level)?),
+            None => saved.and_then(|v| v.level),
+        };
+
+        Ok(DisplayOptions {
+            profiles: match saved {
+                Some(v) if profiles.is_empty() => v.profiles.clone(),
+                _ => profiles.to_vec(),
+            },
+            sort: sort.map(ClusterSort::parse).transpose()?,
+            compact: compact || saved.is_some_and(|v| v.compact),
+            only_degraded: only_degraded || saved.is_some_and(|v| v.only_degraded),
+            level,
+            projection_model: projection_model.map(ProjectionModel::parse).transpose()?,
+            projection_window: projection_window.map(ProjectionWindow::parse).transpose()?,
+            top_activity: top_activity || saved.is_some_and(|v| v.top_activity),
+        })
+    }
+
+    /// The status as it should be displayed: filtered and sorted clusters.
+    /// Fleet aggregates and alerts are left as collected.
+    pub fn shape(&self, status: &EnvironmentStatus) -> EnvironmentStatus {
+        let mut shaped = status.clone();
+        if self.only_degraded {
+            shaped.clusters.retain(|c| is_degraded(c, status));
+        }
+        if let Some(sort) = self.sort {
+            sort_clusters(&mut shaped.clusters, &status.alerts, sort);
+        }
+        shaped
+    }
+}
+
+/// Narrow a shaped status to its `index`th cluster (0-based) and that
+/// cluster's alerts, for the watch view's focus keys. None when there is no
+/// such cluster.
+pub fn focus(status: &EnvironmentStatus, index: usize) -> Option<EnvironmentStatus> {
+    let cluster = status.clusters.get(index)?;
+    let own = |a: &Alert| a.cluster == cluster.name || a.cluster == cluster.profile;
+    Some(EnvironmentStatus {
+        aggregates: super::collector::build_aggregates(std::slice::from_ref(cluster)),
+        alerts: status.alerts.iter().filter(|a| own(a)).cloned().collect(),
+        acknowledged: status
+            .acknowledged
+            .iter()
+            .filter(|a| own(a))
+            .cloned()
+            .collect(),
+        clusters: vec![cluster.clone()],
+    })
+}
+
+/// Whether a cluster needs attention: unreachable or stale, not healthy, or
+/// carrying a warning or critical alert.
+fn is_degraded(cluster: &ClusterStatus, status: &EnvironmentStatus) -> bool {
+    !cluster.reachable
+        || cluster.stale
+        || cluster.health.status != HealthLevel::Healthy
+        || status.alerts.iter().any(|a| {
+            a.severity != AlertSeverity::Info
+                && (a.cluster == cluster.name || a.cluster == cluster.profile)
+        })
+}
+
+fn sort_clusters(clusters: &mut [ClusterStatus], alerts: &[Alert], sort: ClusterSort) {
+    match sort {
+        ClusterSort::Name => clusters.sort_by(|a, b| a.profile.cmp(&b.profile)),
+        ClusterSort::Capacity => {
+            clusters.sort_by(|a, b| b.capacity.used_pct.total_cmp(&a.capacity.used_pct))
+        }
+        ClusterSort::Latency => clusters.sort_by_key(|c| std::cmp::Reverse(c.latency_ms)),
+        ClusterSort::Health => clusters.sort_by_key(|c| {
+            let rank = match (c.reachable, &c.health.status) {
+                (false, _) => 0,
+                (true, HealthLevel::Critical) => 1,
+                (true, HealthLevel::Degraded) => 2,
+                (true, HealthLevel::Healthy) => 3,
+            };
+            (rank, c.profile.clone())
+        }),
+        ClusterSort::Score => {
+            clusters.sort_by_key(|c| (health::score(c, alerts), c.profile.clone()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::status::collector::build_aggregates;
+    use crate::commands::status::types::*;
+    use crate::config::StatusView;
+
+    fn make_cluster(name: &str, used_pct: f64, health: HealthLevel) -> ClusterStatus {
+        ClusterStatus {
+            profile: name.to_string(),
+            name: name.to_string(),
+            uuid: "uuid".to_string(),
+            version: "7.2.3".to_string(),
+            cluster_type: ClusterType::CnqAws,
+            reachable: true,
+            stale: false,
+            latency_ms: 12,
+            degraded: Vec::new(),
+            partial: false,
+            nodes: NodeStatus {
+                total: 4,
+                online: 4,
+                offline_nodes: vec![],
+                details: vec![],
+            },
+            capacity: CapacityStatus {
+                used_pct,
+                ..Default::default()
+            },
+            activity: ActivityStatus::default(),
+            files: FileStats::default(),
+            health: HealthStatus {
+                status: health,
+                issues: vec![],
+                disks_unhealthy: 0,
+                psus_unhealthy: 0,
+                data_at_risk: false,
+                remaining_node_failures: None,
+                remaining_drive_failures: None,
+                protection_type: None,
+                unhealthy_disk_details: vec![],
+                unhealthy_psu_details: vec![],
+                worn_disks: vec![],
+                directory: None,
+            },
+        }
+    }
+
+    fn make_status() -> EnvironmentStatus {
+        let clusters = vec![
+            make_cluster("alpha", 40.0, HealthLevel::Healthy),
+            make_cluster("bravo", 95.0, HealthLevel::Healthy),
+            make_cluster("charlie", 60.0, HealthLevel::Degraded),
+        ];
+        EnvironmentStatus {
+            aggregates: build_aggregates(&clusters),
+            alerts: vec![Alert {
+                severity: AlertSeverity::Warning,
+                cluster: "bravo".to_string(),
+                message: "capacity at 95%".to_string(),
+                category: "capacity".to_string(),
+            }],
+            acknowledged: vec![],
+            clusters,
+        }
+    }
+
+    fn names(status: &EnvironmentStatus) -> Vec<&str> {
+        status.clusters.iter().map(|c| c.profile.as_str()).collect()
+    }
+
+    #[test]
+    fn test_shape_sorts_and_filters() {
+        let status = make_status();
+        let by_capacity = DisplayOptions {
+            sort: Some(ClusterSort::Capacity),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&by_capacity.shape(&status)),
+            ["bravo", "charlie", "alpha"]
+        );
+
+        let degraded = DisplayOptions {
+            only_degraded: true,
+            sort: Some(ClusterSort::Health),
+            ..Default::default()
+        };
+        let shaped = degraded.shape(&status);
+        assert_eq!(names(&shaped), ["charlie", "bravo"]);
+        assert_eq!(shaped.aggregates.cluster_count, 3);
+
+        let by_score = DisplayOptions {
+            sort: Some(ClusterSort::Score),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&by_score.shape(&status)),
+            ["bravo", "alpha", "charlie"]
+        );
+    }
+
+    #[test]
+    fn test_focus() {
+        let status = make_status();
+        let focused = focus(&status, 1).unwrap();
+        assert_eq!(names(&focused), ["bravo"]);
+        assert_eq!(focused.aggregates.cluster_count, 1);
+        assert_eq!(focused.alerts.len(), 1);
+
+        let focused = focus(&status, 0).unwrap();
+        assert_eq!(names(&focused), ["alpha"]);
+        assert!(focused.alerts.is_empty());
+
+        assert!(focus(&status, 3).is_none());
+    }
+
+    #[test]
+    fn test_resolve_merges_view_with_flags() {
+        let mut config = Config::default();
+        config.views.insert(
+            "morning".to_string(),
+            StatusView {
+                profiles: vec!["prod".to_string()],
+                sort: Some("capacity".to_string()),
+                compact: true,
+                level: Some(CollectLevel::Fast),
+                ..Default::default()
+            },
+        );
+
+        let opts = DisplayOptions::resolve(
+            &config,
+            Some("morning"),
+            &[],
+            None,
+            false,
+            true,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(opts.profiles, ["prod"]);
+        assert_eq!(opts.sort, Some(ClusterSort::Capacity));
+        assert!(opts.compact && opts.only_degraded);
+        assert_eq!(opts.level, Some(CollectLevel::Fast));
+
+        // Explicit flags replace the view's values
+        let opts = DisplayOptions::resolve(
+            &config,
+            Some("morning"),
+            &["dr".to_string()],
+            Some("name"),
+            false,
+            false,
+            Some("full"),
+            Some("smoothing"),
+            Some("7d"),
+            false,
+        )
+        .unwrap();
+        assert_eq!(opts.profiles, ["dr"]);
+        assert_eq!(opts.sort, Some(ClusterSort::Name));
+        assert_eq!(opts.level, Some(CollectLevel::Full));
+        assert_eq!(opts.projection_model, Some(ProjectionModel::Smoothing));
+        assert_eq!(opts.projection_window, Some(ProjectionWindow::Week));
+
+        assert!(DisplayOptions::resolve(
+            &config,
+            Some("nope"),
+            &[],
+            None,
+            false,
+            false,
+            None,
+            None,
+            None,
+            false
+        )
+        .is_err());
+    }
+}
@@ -1,23 +1,194 @@
+pub mod secrets;
+
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::error::QontrolError;
+use secrets::CredentialStore;
+
+/// Current config file schema version. Bump it and append a step to
+/// [`MIGRATIONS`] whenever a change needs existing files rewritten.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Days of notice before a token expiry when `token_warn_days` is unset
+pub const DEFAULT_TOKEN_WARN_DAYS: u32 = 14;
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version of the file; files written before versioning are 1
+    #[serde(default = "unversioned")]
+    pub version: u32,
     pub default_profile: Option<String>,
+    /// Warn when a profile's access token expires within this many days
+    /// (default [`DEFAULT_TOKEN_WARN_DAYS`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_warn_days: Option<u32>,
+    /// Rotate a profile's access token once it is within this many days of
+    /// expiring, writing the new token back here (unset: never rotate)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_refresh_days: Option<u32>,
+    /// Where `profile add` stores access tokens: "config" (this file, the
+    /// default) or "keyring" (the OS credential store)
+    #[serde(default, skip_serializing_if = "CredentialStore::is_config")]
+    pub credential_store: CredentialStore,
+    /// CA bundle for profiles that don't set their own `ca_cert`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
     #[serde(default)]
     pub profiles: BTreeMap<String, ProfileEntry>,
+    /// Saved `fleet status` views (`[views.<name>]`), run with `--view <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub views: BTreeMap<String, StatusView>,
+    /// How destructive operations are confirmed (`[confirm]`)
+    #[serde(default, skip_serializing_if = "ConfirmConfig::is_empty")]
+    pub confirm: ConfirmConfig,
+    /// Client address labels by CIDR range (`[subnets]`, e.g.
+    /// `"10.20.0.0/16" = "render-farm"`); see [`crate::subnet`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub subnets: BTreeMap<String, String>,
+    /// Site-specific alerts for `fleet status` (`[[alert_rules]]`); see
+    /// [`crate::commands::status::rules`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alert_rules: Vec<AlertRuleConfig>,
+    /// Where `fleet status --watch` reports alert changes (`[notify]`); see
+    /// [`crate::notify`]
+    #[serde(default, skip_serializing_if = "NotifyConfig::is_empty")]
+    pub notify: NotifyConfig,
+}
+
+/// One `[[alert_rules]]` entry: raise an alert on any cluster where `when`
+/// holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRuleConfig {
+    pub name: String,
+    /// Condition such as `files.total_snapshots > 5000`
+    pub when: String,
+    /// critical, warning (the default), or info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+/// Alert change notifications:
+///
+/// ```toml
+/// [notify]
+/// webhooks = ["https://hooks.example.com/qontrol"]
+/// slack = ["https://hooks.slack.com/services/T000/B000/XXXX"]
+/// retries = 3
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// URLs each alert change is POSTed to as JSON
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<String>,
+    /// Slack incoming-webhook URLs, sent the changes as Block Kit messages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slack: Vec<String>,
+    /// Extra attempts after a failed delivery (default
+    /// [`DEFAULT_NOTIFY_RETRIES`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+/// Default `notify.retries` when the config doesn't set it
+pub const DEFAULT_NOTIFY_RETRIES: u32 = 2;
+
+impl NotifyConfig {
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty() && self.slack.is_empty() && self.retries.is_none()
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(DEFAULT_NOTIFY_RETRIES)
+    }
+}
+
+/// Confirmation rules for destructive operations:
+///
+/// ```toml
+/// [confirm]
+/// destructive = "always"
+///
+/// [confirm.profile]
+/// prod = "typed-name"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmConfig {
+    /// Level for every profile without its own entry (default: always)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive: Option<ConfirmLevel>,
+    /// Per-profile overrides
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, ConfirmLevel>,
+}
+
+/// How much confirmation a destructive operation needs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfirmLevel {
+    /// Run without asking
+    Never,
+    /// Ask yes/no; `--yes` skips the prompt
+    #[default]
+    Always,
+    /// Require retyping the cluster name; `--yes` alone is not enough
+    TypedName,
+}
+
+impl ConfirmConfig {
+    pub fn is_empty(&self) -> bool {
+        self.destructive.is_none() && self.profile.is_empty()
+    }
+
+    /// The confirmation level that applies to `profile`
+    pub fn level_for(&self, profile: &str) -> ConfirmLevel {
+        self.profile
+            .get(profile)
+            .copied()
+            .or(self.destructive)
+            .unwrap_or_default()
+    }
+}
+
+/// A named set of `fleet status` display flags. Flags given on the command
+/// line take precedence over the view.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusView {
+    /// Profiles to include (empty: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
+    /// Cluster order: name, capacity, latency, health, score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// One line per cluster instead of the full sections
+    #[serde(default)]
+    pub compact: bool,
+    /// Only show clusters that are unreachable, unhealthy, or alerting
+    #[serde(default)]
+    pub only_degraded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<CollectLevel>,
+    #[serde(default)]
+    pub top_activity: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileEntry {
     pub host: String,
     pub port: u16,
+    /// Access token, or a `keyring:<profile>` reference to one kept in the
+    /// OS keyring (see [`secrets`])
     pub token: String,
+    /// When the access token expires (RFC 3339, UTC), if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_expires: Option<String>,
+    /// The access token's ID on the cluster, used to revoke it once rotated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
     #[serde(default)]
     pub insecure: bool,
     /// Cluster UUID fetched from GET /v1/node/state → cluster_id. Persisted for cache keying.
@@ -26,6 +197,330 @@ pub struct ProfileEntry {
     /// Override the base URL for this profile (e.g. "http://proxy:8080"). Used by test harness.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub base_url: Option<String>,
+    /// Path the API is served under when a reverse proxy rewrites paths
+    /// (e.g. "/qumulo-api"); requests go to `<base>/qumulo-api/v1/...`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+    /// HTTP(S) proxy to reach this cluster through (e.g. "http://bastion:3128").
+    /// Unset uses HTTPS_PROXY / HTTP_PROXY / NO_PROXY; "none" ignores them too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// SSH destination to reach this cluster through (e.g. "user@bastion" or
+    /// "ssh://user@bastion:2222"); each connection opens an `ssh -L` forward
+    /// to host:port. Ignored when `base_url` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_tunnel: Option<String>,
+    /// PEM bundle of CA certificates to verify this cluster's TLS certificate
+    /// with, on top of the system roots (default: the top-level `ca_cert`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+    /// PEM client certificate presented to clusters that require mutual TLS;
+    /// set together with `client_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    /// PKCS#8 PEM private key for `client_cert`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+    /// Status collection settings (`[profiles.<name>.collect]`)
+    #[serde(default, skip_serializing_if = "CollectConfig::is_empty")]
+    pub collect: CollectConfig,
+    /// Planned-work window during which this cluster's alerts are downgraded to info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<MaintenanceWindow>,
+}
+
+/// A maintenance window recorded by `qontrol maintenance start`.
+/// Times are RFC 3339 in UTC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start: String,
+    pub end: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ProfileEntry {
+    /// The recorded token expiry, if any. Unparseable values count as unknown.
+    pub fn token_expires_at(&self) -> Option<DateTime<Utc>> {
+        let expires = self.token_expires.as_deref()?;
+        DateTime::parse_from_rfc3339(expires)
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+}
+
+impl MaintenanceWindow {
+    /// Whether `now` falls inside the window. Unparseable times count as inactive.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        let parse = |s: &str| DateTime::parse_from_rfc3339(s).ok();
+        match (parse(&self.start), parse(&self.end)) {
+            (Some(start), Some(end)) => start <= now && now < end,
+            _ => false,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            version: CONFIG_VERSION,
+            default_profile: None,
+            token_warn_days: None,
+            token_refresh_days: None,
+            credential_store: CredentialStore::Config,
+            ca_cert: None,
+            profiles: BTreeMap::new(),
+            views: BTreeMap::new(),
+            confirm: ConfirmConfig::default(),
+            subnets: BTreeMap::new(),
+            alert_rules: Vec::new(),
+            notify: NotifyConfig::default(),
+        }
+    }
+}
+
+fn unversioned() -> u32 {
+    1
+}
+
+impl Config {
+    /// The profiles picked by `--cluster` filters: every profile when there
+    /// are none, else each named profile plus those matching glob filters
+    /// such as `prod-*`. Names that match nothing are ignored.
+    pub fn select_profiles(&self, filters: &[String]) -> Vec<(String, ProfileEntry)> {
+        if filters.is_empty() {
+            return self
+                .profiles
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+        }
+        let mut selected: Vec<(String, ProfileEntry)> = Vec::new();
+        for filter in filters {
+            let matches: Vec<(&String, &ProfileEntry)> = if crate::glob::is_glob(filter) {
+                self.profiles
+                    .iter()
+                    .filter(|(name, _)| crate::glob::glob_match(filter, name))
+                    .collect()
+            } else {
+                self.profiles.get_key_value(filter).into_iter().collect()
+            };
+            for (name, entry) in matches {
+                if !selected.iter().any(|(n, _)| n == name) {
+                    selected.push((name.clone(), entry.clone()));
+                }
+            }
+        }
+        selected
+    }
+
+    /// Fill profile settings that fall back to a top-level default, so
+    /// anything holding a `ProfileEntry` sees the effective value.
+    fn inherit_defaults(&mut self) {
+        if let Some(ca_cert) = &self.ca_cert {
+            for entry in self.profiles.values_mut() {
+                entry.ca_cert.get_or_insert_with(|| ca_cert.clone());
+            }
+        }
+    }
+
+    /// The inverse of `inherit_defaults`, for saving: values equal to the
+    /// top-level default are left to inherit it.
+    fn without_inherited(&self) -> Config {
+        let mut config = self.clone();
+        if let Some(ca_cert) = &self.ca_cert {
+            for entry in config.profiles.values_mut() {
+                if entry.ca_cert.as_ref() == Some(ca_cert) {
+                    entry.ca_cert = None;
+                }
+            }
+        }
+        config
+    }
+
+    /// Look up a saved status view by name
+    pub fn view(&self, name: &str) -> Result<&StatusView> {
+        self.views.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.views.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                anyhow::anyhow!("unknown view '{}' (no [views] defined in config)", name)
+            } else {
+                anyhow::anyhow!("unknown view '{}' (available: {})", name, known.join(", "))
+            }
+        })
+    }
+
+    /// Profiles whose maintenance window covers `now`
+    pub fn profiles_in_maintenance(&self, now: DateTime<Utc>) -> Vec<&str> {
+        self.profiles
+            .iter()
+            .filter(|(_, entry)| entry.maintenance.as_ref().is_some_and(|m| m.is_active(now)))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// Per-profile tuning for `fleet status` collection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectConfig {
+    /// Endpoint groups to leave out of collection, e.g. `["recursive_aggregates", "capacity_history"]`.
+    /// See `collector::SKIPPABLE_ENDPOINTS` for the accepted names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<String>,
+    /// Default collection level when `--level` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<CollectLevel>,
+    /// Percent of rated SSD endurance used before a drive is reported as `disk_wear`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_wear_threshold: Option<f64>,
+    /// How days-to-full is projected when `--projection-model` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projection_model: Option<ProjectionModel>,
+    /// Days of history the projection is fitted to when
+    /// `--projection-window` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projection_window: Option<ProjectionWindow>,
+    /// Directory levels below `/` to walk recursive aggregates for, in
+    /// pages, reporting the largest directories. Unset or 0 reads only the
+    /// root's totals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregates_max_depth: Option<usize>,
+    /// Most directories one `aggregates_max_depth` walk collects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregates_max_entries: Option<usize>,
+}
+
+/// Default `collect.disk_wear_threshold` when the profile doesn't set one
+pub const DEFAULT_DISK_WEAR_THRESHOLD: f64 = 80.0;
+
+impl CollectConfig {
+    pub fn is_empty(&self) -> bool {
+        self.skip.is_empty()
+            && self.level.is_none()
+            && self.disk_wear_threshold.is_none()
+            && self.projection_model.is_none()
+            && self.projection_window.is_none()
+            && self.aggregates_max_depth.is_none()
+            && self.aggregates_max_entries.is_none()
+    }
+
+    /// Effective capacity projection: the given overrides (from the command
+    /// line), else this profile's settings, else linear over 30 days
+    pub fn projection(
+        &self,
+        model: Option<ProjectionModel>,
+        window: Option<ProjectionWindow>,
+    ) -> Projection {
+        Projection {
+            model: model.or(self.projection_model).unwrap_or_default(),
+            window: window.or(self.projection_window).unwrap_or_default(),
+        }
+    }
+
+    /// Effective SSD wear threshold in percent of rated endurance used
+    pub fn disk_wear_threshold(&self) -> f64 {
+        self.disk_wear_threshold
+            .unwrap_or(DEFAULT_DISK_WEAR_THRESHOLD)
+    }
+
+    /// Whether the named endpoint group is excluded for this profile
+    pub fn skips(&self, endpoint: &str) -> bool {
+        self.skip.iter().any(|s| s == endpoint)
+    }
+}
+
+/// How much data `fleet status` gathers per cluster.
+///
+/// `fast` stops after settings, version, nodes, and file system capacity;
+/// `standard` is the full dashboard; `full` adds expensive analytics such as
+/// the largest paths by capacity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectLevel {
+    Fast,
+    #[default]
+    Standard,
+    Full,
+}
+
+impl CollectLevel {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "fast" => Ok(Self::Fast),
+            "standard" => Ok(Self::Standard),
+            "full" => Ok(Self::Full),
+            other => anyhow::bail!(
+                "unknown collection level '{}' (expected fast, standard, full)",
+                other
+            ),
+        }
+    }
+}
+
+/// How days-to-full is projected from daily capacity history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectionModel {
+    /// Least-squares line through every day in the window
+    #[default]
+    Linear,
+    /// Exponential smoothing of level and trend (Holt), weighting recent
+    /// days most, so a burst early in the window fades out
+    Smoothing,
+}
+
+impl ProjectionModel {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "smoothing" => Ok(Self::Smoothing),
+            other => anyhow::bail!(
+                "unknown projection model '{}' (expected linear, smoothing)",
+                other
+            ),
+        }
+    }
+}
+
+/// Days of capacity history a projection is fitted to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectionWindow {
+    #[serde(rename = "7d")]
+    Week,
+    #[default]
+    #[serde(rename = "30d")]
+    Month,
+    #[serde(rename = "90d")]
+    Quarter,
+}
+
+impl ProjectionWindow {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "7d" => Ok(Self::Week),
+            "30d" => Ok(Self::Month),
+            "90d" => Ok(Self::Quarter),
+            other => anyhow::bail!(
+                "unknown projection window '{}' (expected 7d, 30d, 90d)",
+                other
+            ),
+        }
+    }
+
+    pub fn days(self) -> u32 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Quarter => 90,
+        }
+    }
+}
+
+/// The model and window a capacity projection uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Projection {
+    pub model: ProjectionModel,
+    pub window: ProjectionWindow,
 }
 
 /// Returns the config directory: ~/.config/qontrol/ on Linux, %APPDATA%\qontrol\ on Windows.
@@ -39,24 +534,124 @@ pub fn config_dir() -> Result<PathBuf> {
     Ok(proj.config_dir().to_path_buf())
 }
 
+/// Returns the data directory for local state such as the alert history.
+/// Prefers QONTROL_DATA_DIR env var, then XDG_DATA_HOME/qontrol, then ~/.local/share/qontrol.
+pub fn data_dir() -> Result<PathBuf> {
+    if let Ok(dir) = std::env::var("QONTROL_DATA_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
+        return Ok(PathBuf::from(xdg).join("qontrol"));
+    }
+    let home = std::env::var("HOME").context("HOME not set")?;
+    Ok(PathBuf::from(home)
+        .join(".local")
+        .join("share")
+        .join("qontrol"))
+}
+
 /// Returns the full path to config.toml
 pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir()?.join("config.toml"))
 }
 
-/// Load config from disk, returning a default Config if the file doesn't exist
+/// Load config from disk, returning a default Config if the file doesn't exist.
+/// Files from an older schema version are migrated and rewritten in place,
+/// keeping the original as `config.toml.v<N>.bak`.
 pub fn load_config() -> Result<Config> {
-    let path = config_path()?;
+    load_config_from(&config_path()?)
+}
+
+fn load_config_from(path: &Path) -> Result<Config> {
     if !path.exists() {
         return Ok(Config::default());
     }
-    let contents = std::fs::read_to_string(&path)
+    let contents = std::fs::read_to_string(path)
         .with_context(|| format!("failed to read {}", path.display()))?;
-    let config: Config =
+    let mut table: toml::Table =
         toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let from = file_version(&table)?;
+    if from < CONFIG_VERSION {
+        let applied = migrate(&mut table, from, MIGRATIONS)
+            .with_context(|| format!("failed to migrate {}", path.display()))?;
+        let backup = path.with_extension(format!("toml.v{}.bak", from));
+        std::fs::write(&backup, &contents)
+            .with_context(|| format!("failed to write {}", backup.display()))?;
+        let migrated = toml::to_string_pretty(&table).context("failed to serialize config")?;
+        std::fs::write(path, migrated)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        eprintln!(
+            "Migrated {} from config version {} to {} ({}); backup at {}",
+            path.display(),
+            from,
+            CONFIG_VERSION,
+            applied.join("; "),
+            backup.display()
+        );
+    }
+
+    let mut config: Config = table
+        .try_into()
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    config.inherit_defaults();
     Ok(config)
 }
 
+/// One config schema change, upgrading a file from version `from` to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut toml::Table) -> Result<()>,
+}
+
+/// Every schema change since versioning began, oldest first.
+pub const MIGRATIONS: &[Migration] = &[];
+
+fn file_version(table: &toml::Table) -> Result<u32> {
+    let version = match table.get("version") {
+        None => return Ok(unversioned()),
+        Some(v) => v
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| QontrolError::ConfigError(format!("invalid version {}", v)))?,
+    };
+    if version > CONFIG_VERSION {
+        return Err(QontrolError::ConfigError(format!(
+            "config file is version {} but this qontrol supports up to {}; upgrade qontrol",
+            version, CONFIG_VERSION
+        ))
+        .into());
+    }
+    Ok(version)
+}
+
+/// Apply the migrations that take a file from version `from` to the latest
+/// version in `migrations`, stamping the new version. Returns the
+/// description of each step applied.
+fn migrate(
+    table: &mut toml::Table,
+    from: u32,
+    migrations: &[Migration],
+) -> Result<Vec<&'static str>> {
+    let mut version = from;
+    let mut applied = Vec::new();
+    for step in migrations.iter().filter(|m| m.from >= from) {
+        if step.from != version {
+            return Err(QontrolError::ConfigError(format!(
+                "no migration from config version {}",
+                version
+            ))
+            .into());
+        }
+        (step.apply)(table).with_context(|| format!("migration '{}'", step.description))?;
+        applied.push(step.description);
+        version += 1;
+    }
+    table.insert("version".into(), toml::Value::Integer(version.into()));
+    Ok(applied)
+}
+
 /// Save config to disk, creating the directory if needed
 pub fn save_config(config: &Config) -> Result<()> {
     let path = config_path()?;
@@ -64,7 +659,8 @@ pub fn save_config(config: &Config) -> Result<()> {
         std::fs::create_dir_all(parent)
             .with_context(|| format!("failed to create {}", parent.display()))?;
     }
-    let contents = toml::to_string_pretty(config).context("failed to serialize config")?;
+    let contents = toml::to_string_pretty(&config.without_inherited())
+        .context("failed to serialize config")?;
     std::fs::write(&path, contents)
         .with_context(|| format!("failed to write {}", path.display()))?;
     Ok(())
@@ -106,6 +702,98 @@ pub fn ensure_cluster_uuids(config: &mut Config, timeout_secs: u64) {
     }
 }
 
+/// Lifetime requested for a rotated access token, matching `profile add`'s
+/// default
+const ROTATED_TOKEN_LIFETIME_DAYS: i64 = 365;
+
+/// Rotate the access tokens of the named profiles that expire within
+/// `token_refresh_days`: create a new token with the current one, save it,
+/// then revoke the old one (when its ID is known). Expired tokens can't be
+/// rotated this way and are left for `warn_expiring` to report. Returns the
+/// profiles rotated, with their new expiry.
+pub fn ensure_fresh_tokens(
+    config: &mut Config,
+    profiles: &[String],
+    timeout_secs: u64,
+) -> Vec<(String, Option<DateTime<Utc>>)> {
+    let Some(days) = config.token_refresh_days else {
+        return Vec::new();
+    };
+    let now = Utc::now();
+    let mut rotated = Vec::new();
+    for name in profiles {
+        let Some(entry) = config.profiles.get_mut(name) else {
+            continue;
+        };
+        let due = entry
+            .token_expires_at()
+            .is_some_and(|e| e > now && e <= now + chrono::Duration::days(days.into()));
+        if !due {
+            continue;
+        }
+        let old_token_id = entry.token_id.clone();
+        match rotate_token(entry, timeout_secs, now) {
+            Ok(()) => {
+                tracing::info!(profile = %name, "rotated access token");
+                rotated.push((name.clone(), old_token_id));
+            }
+            Err(e) => tracing::warn!(profile = %name, error = %e, "could not rotate access token"),
+        }
+    }
+    if rotated.is_empty() {
+        return Vec::new();
+    }
+    // Save before revoking, so a failure below can't strand the profile
+    // with a token that no longer exists
+    if let Err(e) = save_config(config) {
+        tracing::warn!(error = %e, "failed to save config after token rotation");
+        return Vec::new();
+    }
+    rotated
+        .into_iter()
+        .map(|(name, old_token_id)| {
+            let entry = &config.profiles[&name];
+            if let Some(id) = old_token_id {
+                let path = format!("/v1/auth/access-tokens/{}", id);
+                let revoked = crate::client::QumuloClient::new(entry, timeout_secs, None)
+                    .and_then(|c| c.request("DELETE", &path, None));
+                if let Err(e) = revoked {
+                    tracing::warn!(profile = %name, error = %e, "could not revoke the old access token");
+                }
+            }
+            let expires = entry.token_expires_at();
+            (name, expires)
+        })
+        .collect()
+}
+
+/// Replace `entry`'s token with a new one for the same user, created with
+/// the current token.
+fn rotate_token(entry: &mut ProfileEntry, timeout_secs: u64, now: DateTime<Utc>) -> Result<()> {
+    let client = crate::client::QumuloClient::new(entry, timeout_secs, None)?;
+    let who = client.request("GET", "/v1/session/who-am-i", None)?;
+    let auth_id = who["id"].as_str().context("who-am-i response missing id")?;
+    let requested = now + chrono::Duration::days(ROTATED_TOKEN_LIFETIME_DAYS);
+    let body = serde_json::json!({
+        "user": {"auth_id": auth_id},
+        "expiration_time": requested.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+    });
+    let created = client.request("POST", "/v1/auth/access-tokens/", Some(&body))?;
+    let token = created["bearer_token"]
+        .as_str()
+        .context("access token response missing bearer_token")?;
+    entry.token = secrets::replace_token(&entry.token, token)?;
+    entry.token_id = created["id"].as_str().map(str::to_string);
+    // The cluster may clamp the requested expiry; prefer what it granted
+    entry.token_expires = Some(
+        created["expiration_time"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| requested.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+    );
+    Ok(())
+}
+
 /// Resolve which profile to use: --profile flag > QONTROL_PROFILE env (via clap) > default_profile > error
 pub fn resolve_profile(
     config: &Config,
@@ -132,17 +820,29 @@ mod tests {
 
     #[test]
     fn test_toml_roundtrip() {
-        let mut config = Config::default();
-        config.default_profile = Some("test".to_string());
+        let mut config = Config {
+            default_profile: Some("test".to_string()),
+            ..Default::default()
+        };
         config.profiles.insert(
             "test".to_string(),
             ProfileEntry {
                 host: "10.0.0.1".to_string(),
                 port: 8000,
                 token: "access-v1:abc123".to_string(),
+                token_expires: None,
+                token_id: None,
                 insecure: true,
                 cluster_uuid: None,
                 base_url: None,
+                base_path: None,
+                proxy: None,
+                ssh_tunnel: None,
+                ca_cert: None,
+                client_cert: None,
+                client_key: None,
+                collect: Default::default(),
+                maintenance: None,
             },
         );
 
@@ -167,9 +867,19 @@ mod tests {
                 host: "10.0.0.1".to_string(),
                 port: 8000,
                 token: "tok".to_string(),
+                token_expires: None,
+                token_id: None,
                 insecure: false,
                 cluster_uuid: None,
                 base_url: None,
+                base_path: None,
+                proxy: None,
+                ssh_tunnel: None,
+                ca_cert: None,
+                client_cert: None,
+                client_key: None,
+                collect: Default::default(),
+                maintenance: None,
             },
         );
 
@@ -181,17 +891,29 @@ mod tests {
 
     #[test]
     fn test_resolve_profile_with_default() {
-        let mut config = Config::default();
-        config.default_profile = Some("dev".to_string());
+        let mut config = Config {
+            default_profile: Some("dev".to_string()),
+            ..Default::default()
+        };
         config.profiles.insert(
             "dev".to_string(),
             ProfileEntry {
                 host: "192.168.1.1".to_string(),
                 port: 8000,
                 token: "tok".to_string(),
+                token_expires: None,
+                token_id: None,
                 insecure: false,
                 cluster_uuid: None,
                 base_url: None,
+                base_path: None,
+                proxy: None,
+                ssh_tunnel: None,
+                ca_cert: None,
+                client_cert: None,
+                client_key: None,
+                collect: Default::default(),
+                maintenance: None,
             },
         );
 
@@ -225,9 +947,19 @@ mod tests {
                 host: "10.0.0.1".to_string(),
                 port: 8000,
                 token: "tok".to_string(),
+                token_expires: None,
+                token_id: None,
                 insecure: false,
                 cluster_uuid: Some("a1b2c3d4-e5f6-7890-abcd-ef1234567890".to_string()),
                 base_url: None,
+                base_path: None,
+                proxy: None,
+                ssh_tunnel: None,
+                ca_cert: None,
+                client_cert: None,
+                client_key: None,
+                collect: Default::default(),
+                maintenance: None,
             },
         );
 
@@ -256,6 +988,137 @@ insecure = false
         assert_eq!(entry.cluster_uuid, None);
     }
 
+    #[test]
+    fn test_collect_skip_parses_dotted_key() {
+        let toml_str = r#"
+[profiles.slow]
+host = "10.0.0.1"
+port = 8000
+token = "tok"
+collect.skip = ["recursive_aggregates", "capacity_history"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let collect = &config.profiles["slow"].collect;
+        assert!(collect.skips("capacity_history"));
+        assert!(!collect.skips("activity"));
+        assert_eq!(collect.level, None);
+    }
+
+    #[test]
+    fn test_collect_level_parses() {
+        let toml_str = r#"
+[profiles.remote]
+host = "10.0.0.1"
+port = 8000
+token = "tok"
+
+[profiles.remote.collect]
+level = "fast"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.profiles["remote"].collect.level,
+            Some(CollectLevel::Fast)
+        );
+        assert_eq!(CollectLevel::parse("full").unwrap(), CollectLevel::Full);
+        assert!(CollectLevel::parse("slow").is_err());
+    }
+
+    #[test]
+    fn test_collect_projection_parses() {
+        let toml_str = r#"
+[profiles.bursty]
+host = "10.0.0.1"
+port = 8000
+token = "tok"
+
+[profiles.bursty.collect]
+projection_model = "smoothing"
+projection_window = "90d"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let collect = &config.profiles["bursty"].collect;
+        assert_eq!(
+            collect.projection(None, None),
+            Projection {
+                model: ProjectionModel::Smoothing,
+                window: ProjectionWindow::Quarter,
+            }
+        );
+        // Flags win over the profile
+        let flagged = collect.projection(Some(ProjectionModel::Linear), None);
+        assert_eq!(flagged.model, ProjectionModel::Linear);
+        assert_eq!(flagged.window.days(), 90);
+        assert_eq!(
+            CollectConfig::default().projection(None, None),
+            Projection::default()
+        );
+        assert_eq!(ProjectionWindow::default().days(), 30);
+        assert!(ProjectionWindow::parse("14d").is_err());
+        assert!(ProjectionModel::parse("holt").is_err());
+    }
+
+    #[test]
+    fn test_maintenance_window_active() {
+        let toml_str = r#"
+[profiles.music]
+host = "10.0.0.1"
+port = 8000
+token = "tok"
+
+[profiles.music.maintenance]
+start = "2026-03-01T10:00:00Z"
+end = "2026-03-01T12:00:00Z"
+reason = "drive swap"
+
+[profiles.other]
+host = "10.0.0.2"
+port = 8000
+token = "tok"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        assert_eq!(
+            config.profiles_in_maintenance(at("2026-03-01T11:00:00Z")),
+            vec!["music"]
+        );
+        assert!(config
+            .profiles_in_maintenance(at("2026-03-01T12:00:00Z"))
+            .is_empty());
+        assert!(config
+            .profiles_in_maintenance(at("2026-03-01T09:59:59Z"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_collect_empty_not_serialized() {
+        let mut config = Config::default();
+        config.profiles.insert(
+            "test".to_string(),
+            ProfileEntry {
+                host: "10.0.0.1".to_string(),
+                port: 8000,
+                token: "tok".to_string(),
+                token_expires: None,
+                token_id: None,
+                insecure: false,
+                cluster_uuid: None,
+                base_url: None,
+                base_path: None,
+                proxy: None,
+                ssh_tunnel: None,
+                ca_cert: None,
+                client_cert: None,
+                client_key: None,
+                collect: Default::default(),
+                maintenance: None,
+            },
+        );
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        assert!(!serialized.contains("collect"));
+    }
+
     #[test]
     fn test_cluster_uuid_none_not_serialized() {
         let mut config = Config::default();
@@ -265,13 +1128,205 @@ insecure = false
                 host: "10.0.0.1".to_string(),
                 port: 8000,
                 token: "tok".to_string(),
+                token_expires: None,
+                token_id: None,
                 insecure: false,
                 cluster_uuid: None,
                 base_url: None,
+                base_path: None,
+                proxy: None,
+                ssh_tunnel: None,
+                ca_cert: None,
+                client_cert: None,
+                client_key: None,
+                collect: Default::default(),
+                maintenance: None,
             },
         );
 
         let serialized = toml::to_string_pretty(&config).unwrap();
         assert!(!serialized.contains("cluster_uuid"));
     }
+
+    #[test]
+    fn test_views_parse_and_lookup() {
+        let config: Config = toml::from_str(
+            "[views.morning]\nprofiles = [\"prod\", \"dr\"]\nsort = \"capacity\"\ncompact = true\n",
+        )
+        .unwrap();
+        let view = config.view("morning").unwrap();
+        assert_eq!(view.profiles, vec!["prod", "dr"]);
+        assert_eq!(view.sort.as_deref(), Some("capacity"));
+        assert!(view.compact);
+        assert!(!view.only_degraded);
+
+        let err = config.view("evening").unwrap_err().to_string();
+        assert_eq!(err, "unknown view 'evening' (available: morning)");
+        assert!(!toml::to_string_pretty(&Config::default())
+            .unwrap()
+            .contains("views"));
+    }
+
+    #[test]
+    fn test_select_profiles_matches_globs() {
+        let mut toml_str = String::new();
+        for name in ["dev", "prod-a", "prod-b", "staging"] {
+            toml_str.push_str(&format!(
+                "[profiles.{}]\nhost = \"h\"\nport = 8000\ntoken = \"t\"\n",
+                name
+            ));
+        }
+        let config: Config = toml::from_str(&toml_str).unwrap();
+        let names = |filters: &[&str]| -> Vec<String> {
+            let filters: Vec<String> = filters.iter().map(|f| f.to_string()).collect();
+            config
+                .select_profiles(&filters)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        assert_eq!(names(&[]).len(), 4);
+        assert_eq!(names(&["prod-*"]), vec!["prod-a", "prod-b"]);
+        assert_eq!(
+            names(&["staging", "prod-*", "prod-a"]),
+            vec!["staging", "prod-a", "prod-b"]
+        );
+        assert!(names(&["qa-*", "missing"]).is_empty());
+    }
+
+    fn rename_token_field(table: &mut toml::Table) -> Result<()> {
+        if let Some(toml::Value::Table(profiles)) = table.get_mut("profiles") {
+            for profile in profiles.iter_mut().filter_map(|(_, p)| p.as_table_mut()) {
+                if let Some(token) = profile.remove("access_token") {
+                    profile.insert("token".into(), token);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[Migration {
+        from: 1,
+        description: "rename access_token to token",
+        apply: rename_token_field,
+    }];
+
+    #[test]
+    fn test_migrate_applies_steps_and_stamps_version() {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+[profiles.old]
+host = "10.0.0.1"
+access_token = "tok"
+"#,
+        )
+        .unwrap();
+        let applied = migrate(&mut table, 1, TEST_MIGRATIONS).unwrap();
+        assert_eq!(applied, ["rename access_token to token"]);
+        assert_eq!(table["version"].as_integer(), Some(2));
+        assert_eq!(table["profiles"]["old"]["token"].as_str(), Some("tok"));
+        assert!(table["profiles"]["old"].get("access_token").is_none());
+
+        // Already current: nothing to apply
+        let applied = migrate(&mut table, 2, TEST_MIGRATIONS).unwrap();
+        assert!(applied.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_rejects_gap() {
+        let mut table = toml::Table::new();
+        let gapped = [Migration {
+            from: 2,
+            description: "needs v2",
+            apply: |_| Ok(()),
+        }];
+        assert!(migrate(&mut table, 1, &gapped).is_err());
+    }
+
+    #[test]
+    fn test_file_version() {
+        let unversioned: toml::Table = toml::from_str("default_profile = \"a\"").unwrap();
+        assert_eq!(file_version(&unversioned).unwrap(), 1);
+
+        let newer: toml::Table =
+            toml::from_str(&format!("version = {}", CONFIG_VERSION + 1)).unwrap();
+        let err = file_version(&newer).unwrap_err().to_string();
+        assert!(err.contains("upgrade qontrol"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_current_config_leaves_file_alone() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let contents = "default_profile = \"dev\"\n";
+        std::fs::write(&path, contents).unwrap();
+
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.default_profile.as_deref(), Some("dev"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_ca_cert_inherits_top_level_default() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"ca_cert = "/etc/qontrol/corp-ca.pem"
+
+[profiles.prod]
+host = "prod"
+port = 8000
+token = "t"
+
+[profiles.lab]
+host = "lab"
+port = 8000
+token = "t"
+ca_cert = "/etc/qontrol/lab-ca.pem"
+"#,
+        )
+        .unwrap();
+
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(
+            config.profiles["prod"].ca_cert.as_deref(),
+            Some("/etc/qontrol/corp-ca.pem")
+        );
+        assert_eq!(
+            config.profiles["lab"].ca_cert.as_deref(),
+            Some("/etc/qontrol/lab-ca.pem")
+        );
+
+        // Saving keeps prod inheriting rather than pinning the default
+        let saved = config.without_inherited();
+        assert_eq!(saved.profiles["prod"].ca_cert, None);
+        assert_eq!(
+            saved.profiles["lab"].ca_cert.as_deref(),
+            Some("/etc/qontrol/lab-ca.pem")
+        );
+    }
+
+    #[test]
+    fn test_confirm_level_for() {
+        let config: Config = toml::from_str(
+            r#"
+[confirm]
+destructive = "never"
+
+[confirm.profile]
+prod = "typed-name"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.confirm.level_for("prod"), ConfirmLevel::TypedName);
+        assert_eq!(config.confirm.level_for("dev"), ConfirmLevel::Never);
+        assert_eq!(
+            Config::default().confirm.level_for("prod"),
+            ConfirmLevel::Always
+        );
+        assert!(toml::from_str::<Config>("[confirm]\ndestructive = \"maybe\"").is_err());
+    }
 }
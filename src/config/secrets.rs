@@ -0,0 +1,460 @@
+//! Where profile access tokens are kept. By default a token sits in
+//! `config.toml` as-is. With `credential_store = "keyring"`, `profile add`
+//! puts it in the OS credential store and leaves a reference in its place:
+//!
+//! ```toml
+//! [profiles.prod]
+//! token = "keyring:prod"
+//! ```
+//!
+//! The keyring is reached through the platform's own tool: `security` for
+//! the macOS Keychain, `secret-tool` (libsecret) for the Secret Service on
+//! Linux and the BSDs, and PowerShell for Windows Credential Manager, whose
+//! `cmdkey` can't read a secret back. Secrets go to the tools over stdin,
+//! never on the command line.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Service name entries are filed under in the keyring
+const SERVICE: &str = "qontrol";
+
+/// Prefix of a `token` value that refers to a keyring entry
+const KEYRING_PREFIX: &str = "keyring:";
+
+/// Where `profile add` stores new access tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStore {
+    /// In `config.toml`, in plain text
+    #[default]
+    Config,
+    /// In the OS keyring, with a `keyring:<profile>` reference in config
+    Keyring,
+}
+
+impl CredentialStore {
+    pub fn is_config(&self) -> bool {
+        *self == CredentialStore::Config
+    }
+}
+
+/// Longest line `security -i` reads, which bounds the secret it can store
+const SECURITY_MAX_LINE: usize = 4096;
+
+/// The keyring account a `token` value refers to, if it is a reference.
+pub fn keyring_account(token: &str) -> Option<&str> {
+    token.strip_prefix(KEYRING_PREFIX)
+}
+
+/// The access token a profile's `token` value stands for: the value itself,
+/// or the keyring entry it refers to.
+pub fn resolve_token(token: &str) -> Result<String> {
+    let Some(account) = keyring_account(token) else {
+        return Ok(token.to_string());
+    };
+    let secret = keyring().get(checked_account(account)?)?;
+    secret.with_context(|| {
+        format!(
+            "no keyring entry for '{}' (service '{}'): add the profile again with `qontrol profile add`",
+            account, SERVICE
+        )
+    })
+}
+
+/// Keep `token` for `profile` in `store`, returning what the profile's
+/// `token` field should hold.
+pub fn store_token(store: CredentialStore, profile: &str, token: &str) -> Result<String> {
+    match store {
+        CredentialStore::Config => Ok(token.to_string()),
+        CredentialStore::Keyring => {
+            keyring().set(checked_account(profile)?, token)?;
+            Ok(format!("{}{}", KEYRING_PREFIX, profile))
+        }
+    }
+}
+
+/// Replace the secret behind a profile's `token` field, keeping it wherever
+/// it is now. Returns the new field value.
+pub fn replace_token(current: &str, token: &str) -> Result<String> {
+    match keyring_account(current) {
+        Some(account) => {
+            keyring().set(checked_account(account)?, token)?;
+            Ok(current.to_string())
+        }
+        None => Ok(token.to_string()),
+    }
+}
+
+/// Remove the keyring entry a `token` value refers to. Plain tokens go away
+/// with the profile, so there is nothing to do for them.
+pub fn delete_token(token: &str) -> Result<()> {
+    match keyring_account(token) {
+        Some(account) => keyring().delete(checked_account(account)?),
+        None => Ok(()),
+    }
+}
+
+/// `account` if it is safe to hand to a keyring tool as one argument:
+/// letters, digits, `-`, `_` and `.`, not starting with `-` or `.`.
+fn checked_account(account: &str) -> Result<&str> {
+    let valid = !account.is_empty()
+        && account
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !account.starts_with(['-', '.']);
+    if !valid {
+        anyhow::bail!(
+            "invalid keyring account '{}': use letters, digits, '-', '_' and '.'",
+            account
+        );
+    }
+    Ok(account)
+}
+
+/// A backend for secrets keyed by account name.
+trait Keyring {
+    fn get(&self, account: &str) -> Result<Option<String>>;
+    fn set(&self, account: &str, secret: &str) -> Result<()>;
+    fn delete(&self, account: &str) -> Result<()>;
+}
+
+/// The keyring for this platform
+fn keyring() -> Box<dyn Keyring> {
+    if cfg!(target_os = "macos") {
+        Box::new(MacKeychain)
+    } else if cfg!(windows) {
+        Box::new(CredentialManager)
+    } else {
+        Box::new(SecretService)
+    }
+}
+
+/// Run a keyring tool, writing `input` to its stdin. Returns (success, stdout).
+fn run_tool(tool: &str, args: &[&str], input: Option<&str>) -> Result<(bool, String)> {
+    let mut child = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| {
+            format!(
+                "credential_store = \"keyring\" needs `{}`, which could not be run",
+                tool
+            )
+        })?;
+    if let Some(input) = input {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(input.as_bytes())
+                .with_context(|| format!("failed to write to {}", tool))?;
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("failed to run {}", tool))?;
+    let stdout = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches('\n')
+        .to_string();
+    if !output.status.success() {
+        tracing::debug!(
+            %tool,
+            stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+            "keyring tool failed"
+        );
+    }
+    Ok((output.status.success(), stdout))
+}
+
+/// The macOS Keychain, through `security`
+struct MacKeychain;
+
+impl Keyring for MacKeychain {
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        let args = ["find-generic-password", "-s", SERVICE, "-a", account, "-w"];
+        let (found, secret) = run_tool("security", &args, None)?;
+        Ok(found.then_some(secret))
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<()> {
+        // `security` only takes the secret as an argument, so run it in
+        // interactive mode and send the command over stdin, keeping the
+        // secret out of `ps`. -X takes it hex-encoded, which needs no
+        // quoting; -U updates an existing item instead of failing.
+        let hex: String = secret.bytes().map(|b| format!("{:02x}", b)).collect();
+        let command = format!(
+            "add-generic-password -U -s {} -a {} -X {}\n",
+            SERVICE, account, hex
+        );
+        if command.len() > SECURITY_MAX_LINE {
+            anyhow::bail!("the token for '{}' is too long for the Keychain", account);
+        }
+        let (ok, _) = run_tool("security", &["-i"], Some(&command))?;
+        // Interactive mode doesn't always fail on a failed command, so
+        // check the item is really there
+        if !ok || self.get(account)?.as_deref() != Some(secret) {
+            anyhow::bail!(
+                "failed to store the token for '{}' in the Keychain",
+                account
+            );
+        }
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<()> {
+        let args = ["delete-generic-password", "-s", SERVICE, "-a", account];
+        run_tool("security", &args, None)?;
+        Ok(())
+    }
+}
+
+/// The freedesktop Secret Service (GNOME Keyring, KWallet), through
+/// `secret-tool`
+struct SecretService;
+
+impl Keyring for SecretService {
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        let args = ["lookup", "service", SERVICE, "account", account];
+        let (found, secret) = run_tool("secret-tool", &args, None)?;
+        Ok((found && !secret.is_empty()).then_some(secret))
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<()> {
+        let label = format!("qontrol profile {}", account);
+        let args = [
+            "store", "--label", &label, "service", SERVICE, "account", account,
+        ];
+        // The secret goes over stdin, not the command line
+        let (ok, _) = run_tool("secret-tool", &args, Some(secret))?;
+        if !ok {
+            anyhow::bail!(
+                "failed to store the token for '{}' with secret-tool: is a keyring unlocked?",
+                account
+            );
+        }
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<()> {
+        let args = ["clear", "service", SERVICE, "account", account];
+        run_tool("secret-tool", &args, None)?;
+        Ok(())
+    }
+}
+
+/// Runs the script PowerShell reads from stdin and writes out what it
+/// returns, failing when it returns nothing
+const POWERSHELL_RUN: &str = "$ErrorActionPreference = 'Stop'; \
+    $r = & ([scriptblock]::Create([Console]::In.ReadToEnd())); \
+    if ($null -eq $r) { exit 1 }; [Console]::Out.Write($r)";
+
+/// Generic credentials through the Win32 Cred* API, as PowerShell has no
+/// cmdlets for them out of the box
+const CRED_API: &str = r#"Add-Type -TypeDefinition @'
+using System;
+using System.Runtime.InteropServices;
+using FILETIME = System.Runtime.InteropServices.ComTypes.FILETIME;
+
+public static class QontrolCred {
+    [StructLayout(LayoutKind.Sequential, CharSet = CharSet.Unicode)]
+    struct Credential {
+        public int Flags;
+        public int Type;
+        public string TargetName;
+        public string Comment;
+        public FILETIME LastWritten;
+        public int CredentialBlobSize;
+        public IntPtr CredentialBlob;
+        public int Persist;
+        public int AttributeCount;
+        public IntPtr Attributes;
+        public string TargetAlias;
+        public string UserName;
+    }
+
+    const int Generic = 1;
+    const int PersistLocalMachine = 2;
+
+    [DllImport("advapi32.dll", CharSet = CharSet.Unicode, SetLastError = true)]
+    static extern bool CredReadW(string target, int type, int flags, out IntPtr credential);
+    [DllImport("advapi32.dll", CharSet = CharSet.Unicode, SetLastError = true)]
+    static extern bool CredWriteW(ref Credential credential, int flags);
+    [DllImport("advapi32.dll", CharSet = CharSet.Unicode, SetLastError = true)]
+    static extern bool CredDeleteW(string target, int type, int flags);
+    [DllImport("advapi32.dll")]
+    static extern void CredFree(IntPtr buffer);
+
+    public static string Read(string target) {
+        IntPtr p;
+        if (!CredReadW(target, Generic, 0, out p)) return null;
+        try {
+            var c = (Credential)Marshal.PtrToStructure(p, typeof(Credential));
+            return Marshal.PtrToStringUni(c.CredentialBlob, c.CredentialBlobSize / 2);
+        } finally {
+            CredFree(p);
+        }
+    }
+
+    public static string Write(string target, string user, string secret) {
+        var c = new Credential {
+            Type = Generic,
+            TargetName = target,
+            UserName = user,
+            CredentialBlobSize = secret.Length * 2,
+            CredentialBlob = Marshal.StringToCoTaskMemUni(secret),
+            Persist = PersistLocalMachine,
+        };
+        try {
+            return CredWriteW(ref c, 0) ? "ok" : null;
+        } finally {
+            Marshal.FreeCoTaskMem(c.CredentialBlob);
+        }
+    }
+
+    public static string Delete(string target) {
+        return CredDeleteW(target, Generic, 0) ? "ok" : null;
+    }
+}
+'@
+"#;
+
+/// Windows Credential Manager, through PowerShell and the Win32 Cred* API.
+/// Entries are generic credentials named `qontrol:<account>`.
+struct CredentialManager;
+
+impl CredentialManager {
+    /// Run `call` (a `[QontrolCred]::` expression) after loading the API;
+    /// returns (success, what it returned)
+    fn run(&self, call: &str) -> Result<(bool, String)> {
+        let script = format!("{}{}\n", CRED_API, call);
+        let args = ["-NoProfile", "-NonInteractive", "-Command", POWERSHELL_RUN];
+        run_tool("powershell.exe", &args, Some(&script))
+    }
+}
+
+impl Keyring for CredentialManager {
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        let call = format!("[QontrolCred]::Read('{}:{}')", SERVICE, account);
+        let (found, secret) = self.run(&call)?;
+        Ok(found.then_some(secret))
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<()> {
+        // Hex keeps the secret clear of PowerShell's quoting rules
+        let hex: String = secret.bytes().map(|b| format!("{:02x}", b)).collect();
+        let call = format!(
+            "$h = '{}'; $b = [byte[]]::new($h.Length / 2); \
+             for ($i = 0; $i -lt $b.Length; $i++) {{ $b[$i] = [Convert]::ToByte($h.Substring(2 * $i, 2), 16) }}; \
+             [QontrolCred]::Write('{}:{}', '{}', [Text.Encoding]::UTF8.GetString($b))",
+            hex, SERVICE, account, account
+        );
+        let (ok, _) = self.run(&call)?;
+        if !ok {
+            anyhow::bail!(
+                "failed to store the token for '{}' in Credential Manager",
+                account
+            );
+        }
+        Ok(())
+    }
+
+    fn delete(&self, account: &str) -> Result<()> {
+        self.run(&format!("[QontrolCred]::Delete('{}:{}')", SERVICE, account))?;
+        Ok(())
+    }
+}
+
+/// One file per account in a directory, for tests
+#[cfg(test)]
+struct DirKeyring(std::path::PathBuf);
+
+#[cfg(test)]
+impl Keyring for DirKeyring {
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        match std::fs::read_to_string(self.0.join(account)) {
+            Ok(secret) => Ok(Some(secret)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).context("failed to read keyring entry"),
+        }
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.0).context("failed to create keyring directory")?;
+        std::fs::write(self.0.join(account), secret).context("failed to write keyring entry")
+    }
+
+    fn delete(&self, account: &str) -> Result<()> {
+        match std::fs::remove_file(self.0.join(account)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).context("failed to delete keyring entry")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_tokens_pass_through() {
+        assert_eq!(resolve_token("access-v1:abc").unwrap(), "access-v1:abc");
+        assert_eq!(
+            store_token(CredentialStore::Config, "prod", "access-v1:abc").unwrap(),
+            "access-v1:abc"
+        );
+        assert_eq!(
+            replace_token("access-v1:old", "access-v1:new").unwrap(),
+            "access-v1:new"
+        );
+        assert_eq!(keyring_account("access-v1:abc"), None);
+        assert_eq!(keyring_account("keyring:prod"), Some("prod"));
+        delete_token("access-v1:abc").unwrap();
+    }
+
+    #[test]
+    fn test_dir_keyring_round_trip() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let keyring = DirKeyring(tmp.path().join("keyring"));
+        assert_eq!(keyring.get("prod").unwrap(), None);
+        keyring.set("prod", "access-v1:abc").unwrap();
+        assert_eq!(
+            keyring.get("prod").unwrap().as_deref(),
+            Some("access-v1:abc")
+        );
+        keyring.delete("prod").unwrap();
+        keyring.delete("prod").unwrap();
+        assert_eq!(keyring.get("prod").unwrap(), None);
+    }
+
+    #[test]
+    fn test_checked_account() {
+        assert_eq!(
+            checked_account("prod-east_1.lab").unwrap(),
+            "prod-east_1.lab"
+        );
+        for bad in [
+            "", "..", "../prod", "a/b", "-oProxy", ".hidden", "a b", "a\"b",
+        ] {
+            assert!(checked_account(bad).is_err(), "{}", bad);
+        }
+        assert!(resolve_token("keyring:../../etc/passwd").is_err());
+        assert!(delete_token("keyring:a/b").is_err());
+    }
+
+    #[test]
+    fn test_credential_store_parses() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            store: CredentialStore,
+        }
+        let w: Wrapper = toml::from_str(r#"store = "keyring""#).unwrap();
+        assert_eq!(w.store, CredentialStore::Keyring);
+        assert!(toml::from_str::<Wrapper>(r#"store = "vault""#).is_err());
+    }
+}
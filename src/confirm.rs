@@ -0,0 +1,93 @@
+use std::io::IsTerminal;
+
+use anyhow::Result;
+
+use crate::client::QumuloClient;
+use crate::config::{Config, ConfirmLevel};
+
+/// Confirmation for a destructive operation against one profile, combining
+/// the `[confirm]` policy with the `--yes` / `--confirm-name` flags.
+#[derive(Debug, Clone)]
+pub struct Guard {
+    pub profile: String,
+    pub level: ConfirmLevel,
+    pub yes: bool,
+    pub confirm_name: Option<String>,
+}
+
+impl Guard {
+    pub fn new(config: &Config, profile: &str, yes: bool, confirm_name: Option<String>) -> Self {
+        Guard {
+            profile: profile.to_string(),
+            level: config.confirm.level_for(profile),
+            yes,
+            confirm_name,
+        }
+    }
+
+    /// Ask before running `action` (e.g. "reboot node 1"). `prompt` is the
+    /// yes/no question shown at the `always` level.
+    ///
+    /// Returns false if the user declined. When stdin is not a terminal and
+    /// the flags don't satisfy the policy, the action is refused with an error
+    /// rather than run unattended.
+    pub fn confirm(&self, client: &QumuloClient, action: &str, prompt: &str) -> Result<bool> {
+        match self.level {
+            ConfirmLevel::Never => Ok(true),
+            ConfirmLevel::Always => {
+                if self.yes {
+                    return Ok(true);
+                }
+                if !std::io::stdin().is_terminal() {
+                    anyhow::bail!(
+                        "refusing to {} without confirmation; pass --yes to proceed",
+                        action
+                    );
+                }
+                Ok(dialoguer::Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()?)
+            }
+            ConfirmLevel::TypedName => {
+                let cluster_name = self.cluster_name(client)?;
+                if let Some(name) = &self.confirm_name {
+                    if name.trim() != cluster_name {
+                        anyhow::bail!(
+                            "refusing to {}: --confirm-name '{}' does not match cluster '{}'",
+                            action,
+                            name,
+                            cluster_name
+                        );
+                    }
+                    return Ok(true);
+                }
+                if !std::io::stdin().is_terminal() {
+                    anyhow::bail!(
+                        "refusing to {}: profile '{}' requires typing the cluster name; pass --confirm-name {} to proceed",
+                        action,
+                        self.profile,
+                        cluster_name
+                    );
+                }
+                eprintln!("{}", prompt);
+                let typed: String = dialoguer::Input::new()
+                    .with_prompt(format!(
+                        "Type the cluster name '{}' to confirm",
+                        cluster_name
+                    ))
+                    .allow_empty(true)
+                    .interact_text()?;
+                Ok(typed.trim() == cluster_name)
+            }
+        }
+    }
+
+    fn cluster_name(&self, client: &QumuloClient) -> Result<String> {
+        let settings = client.get_cluster_settings()?;
+        Ok(settings["cluster_name"]
+            .as_str()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| self.profile.clone()))
+    }
+}
@@ -9,9 +9,222 @@ pub enum QontrolError {
     #[error("no default profile configured — use `qontrol profile add <name> --default` or `--profile <name>`")]
     NoDefaultProfile,
 
-    #[error("API error ({status}): {body}")]
-    ApiError { status: u16, body: String },
+    /// A non-2xx response. Displays as an actionable message; the raw
+    /// response stays in `body` for callers that match on error classes.
+    #[error("{}", describe_api_error(.method, .path, *.status, .body))]
+    ApiError {
+        status: u16,
+        method: String,
+        path: String,
+        body: String,
+    },
 
     #[error("config error: {0}")]
     ConfigError(String),
 }
+
+/// Role privilege areas by API path prefix; reads need `PRIVILEGE_<AREA>_READ`
+/// and changes `PRIVILEGE_<AREA>_WRITE`
+const PRIVILEGES: &[(&str, &str)] = &[
+    ("/v1/cluster/", "CLUSTER"),
+    ("/v1/node/", "CLUSTER"),
+    ("/v1/analytics/", "ANALYTICS"),
+    ("/v1/metrics/", "METRICS"),
+    ("/v2/snapshots/", "SNAPSHOT"),
+    ("/v1/files/quotas/", "QUOTA"),
+    ("/v2/network/", "NETWORK"),
+    ("/v3/network/", "NETWORK"),
+    ("/v1/auth/access-tokens/", "ACCESS_TOKENS"),
+    ("/v1/ad/", "AD"),
+    ("/v2/smb/", "SMB_SHARE"),
+    ("/v3/smb/", "SMB_SHARE"),
+    ("/v2/nfs/", "NFS_EXPORT"),
+    ("/v3/nfs/", "NFS_EXPORT"),
+];
+
+/// Plain-language meanings of common Qumulo `error_class` values
+const ERROR_CLASSES: &[(&str, &str)] = &[
+    ("fs_no_such_entry_error", "no such file or directory"),
+    ("fs_no_such_path_error", "no such file or directory"),
+    ("fs_entry_exists_error", "already exists"),
+    ("fs_not_a_directory_error", "not a directory"),
+    ("fs_not_a_file_error", "not a regular file"),
+    ("fs_directory_not_empty_error", "directory not empty"),
+    (
+        "fs_access_denied_error",
+        "permission denied by the file's ACL or mode",
+    ),
+    (
+        "fs_access_perm_not_owner_error",
+        "permission denied: only the owner can do this",
+    ),
+    ("fs_no_space_error", "the cluster is out of space"),
+    (
+        "too_many_access_tokens_error",
+        "the user already has the maximum number of access tokens",
+    ),
+];
+
+/// Turn an API error response into a message that says what went wrong and
+/// what to do about it, instead of echoing the raw JSON. Qumulo error bodies
+/// carry `error_class` and `description`; other bodies are shown trimmed.
+pub fn describe_api_error(method: &str, path: &str, status: u16, body: &str) -> String {
+    let parsed: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    let error_class = parsed
+        .as_ref()
+        .and_then(|v| v["error_class"].as_str())
+        .unwrap_or("");
+    let description = parsed
+        .as_ref()
+        .and_then(|v| v["description"].as_str())
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| truncate_body(body));
+    let endpoint = format!("{} {}", method, path);
+    let known_class = ERROR_CLASSES
+        .iter()
+        .find(|(class, _)| *class == error_class)
+        .map(|(_, meaning)| *meaning);
+
+    if let Some(meaning) = known_class {
+        return match file_path_of(path) {
+            Some(file) => format!("{}: {} (HTTP {})", file, meaning, status),
+            None => format!("{} (HTTP {}): {}", meaning, status, endpoint),
+        };
+    }
+    match status {
+        401 => "access token rejected (HTTP 401): it may have expired or been revoked; replace it with `qontrol profile add`".to_string(),
+        403 => match required_privilege(method, path, &description) {
+            Some(privilege) => format!(
+                "permission denied (HTTP 403): token lacks {} (needed for {})",
+                privilege, endpoint
+            ),
+            None => format!("permission denied (HTTP 403) for {}: {}", endpoint, description),
+        },
+        404 if parsed.is_none() || error_class == "http_not_found_error" => format!(
+            "not found (HTTP 404): {}; the cluster may not support this endpoint (check its version)",
+            endpoint
+        ),
+        429 | 503 => format!(
+            "cluster busy (HTTP {}) for {}: {}; retry later",
+            status, endpoint, description
+        ),
+        500..=599 => format!(
+            "cluster error (HTTP {}) for {}: {}",
+            status, endpoint, description
+        ),
+        _ => format!("API error (HTTP {}) for {}: {}", status, endpoint, description),
+    }
+}
+
+/// The privilege a 403 points at: one named in the description, else the
+/// one the endpoint's area requires for this method.
+fn required_privilege(method: &str, path: &str, description: &str) -> Option<String> {
+    if let Some(start) = description.find("PRIVILEGE_") {
+        let name: String = description[start..]
+            .chars()
+            .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_')
+            .collect();
+        return Some(name);
+    }
+    let (_, area) = PRIVILEGES
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))?;
+    let access = if method.eq_ignore_ascii_case("GET") {
+        "READ"
+    } else {
+        "WRITE"
+    };
+    Some(format!("PRIVILEGE_{}_{}", area, access))
+}
+
+/// The file system path in a `/v1/files/<encoded path>/...` API path
+fn file_path_of(path: &str) -> Option<String> {
+    let encoded = path.strip_prefix("/v1/files/")?.split('/').next()?;
+    if !encoded.starts_with("%2F") {
+        return None;
+    }
+    urlencoding::decode(encoded).ok().map(|p| p.into_owned())
+}
+
+fn truncate_body(body: &str) -> String {
+    const MAX: usize = 200;
+    let body = body.trim();
+    if body.is_empty() {
+        return "(empty response)".to_string();
+    }
+    match body.char_indices().nth(MAX) {
+        Some((cut, _)) => format!("{}...", &body[..cut]),
+        None => body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qumulo_error(class: &str, description: &str) -> String {
+        serde_json::json!({
+            "module": "qfsd",
+            "error_class": class,
+            "description": description,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_forbidden_names_privilege() {
+        let body = qumulo_error("http_forbidden_error", "Forbidden");
+        assert_eq!(
+            describe_api_error("GET", "/v1/cluster/slots/", 403, &body),
+            "permission denied (HTTP 403): token lacks PRIVILEGE_CLUSTER_READ (needed for GET /v1/cluster/slots/)"
+        );
+        assert!(describe_api_error("DELETE", "/v2/snapshots/5", 403, &body)
+            .contains("PRIVILEGE_SNAPSHOT_WRITE"));
+
+        // A privilege named by the cluster wins over the table
+        let body = qumulo_error(
+            "http_forbidden_error",
+            "User lacks privilege PRIVILEGE_FS_DELETE_TREE_WRITE.",
+        );
+        assert!(
+            describe_api_error("POST", "/v1/tree-delete/jobs/", 403, &body)
+                .contains("token lacks PRIVILEGE_FS_DELETE_TREE_WRITE")
+        );
+    }
+
+    #[test]
+    fn test_known_error_classes() {
+        let body = qumulo_error("fs_no_such_entry_error", "{ path: \"/data/missing\" }");
+        assert_eq!(
+            describe_api_error(
+                "GET",
+                "/v1/files/%2Fdata%2Fmissing/info/attributes",
+                404,
+                &body
+            ),
+            "/data/missing: no such file or directory (HTTP 404)"
+        );
+        let body = qumulo_error("too_many_access_tokens_error", "limit reached");
+        assert!(
+            describe_api_error("POST", "/v1/auth/access-tokens/", 400, &body)
+                .starts_with("the user already has the maximum number of access tokens")
+        );
+    }
+
+    #[test]
+    fn test_status_fallbacks() {
+        assert!(describe_api_error("GET", "/v1/cluster/settings", 401, "")
+            .contains("expired or been revoked"));
+        assert_eq!(
+            describe_api_error("GET", "/v9/nope", 404, "<html>Not Found</html>"),
+            "not found (HTTP 404): GET /v9/nope; the cluster may not support this endpoint (check its version)"
+        );
+        assert_eq!(
+            describe_api_error("GET", "/v1/version", 500, &qumulo_error("x", "boom")),
+            "cluster error (HTTP 500) for GET /v1/version: boom"
+        );
+        let long = "x".repeat(500);
+        assert!(describe_api_error("PUT", "/v1/a", 400, &long).ends_with("..."));
+    }
+}
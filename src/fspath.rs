@@ -0,0 +1,61 @@
+//! Cluster filesystem paths: normalization and encoding as file refs.
+//!
+//! Every fs and snapshot command goes through here so that `/a/b/`,
+//! `/a//b`, and `/a/c/../b` all reach the API as the same ref. Names are
+//! encoded literally, so a file called `x%2Fy` is sent as `x%252Fy` and is
+//! never confused with the path `x/y`.
+
+/// Normalize a cluster path: make it absolute, drop empty and `.`
+/// segments and any trailing slash, and resolve `..` lexically (`..` at
+/// the root stays at the root).
+pub fn normalize(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            name => segments.push(name),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+/// Encode a path as a file ref for `/v1/files/{ref}/...` URLs
+pub fn file_ref(path: &str) -> String {
+    urlencoding::encode(&normalize(path)).into_owned()
+}
+
+/// The `--debug-path` line for a user-supplied path
+pub fn debug_line(path: &str) -> String {
+    format!(
+        "debug-path: '{}' -> {} -> /v1/files/{}/",
+        path,
+        normalize(path),
+        file_ref(path)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("/"), "/");
+        assert_eq!(normalize(""), "/");
+        assert_eq!(normalize("/home/"), "/home");
+        assert_eq!(normalize("home//alice/./docs"), "/home/alice/docs");
+        assert_eq!(normalize("/home/alice/../bob"), "/home/bob");
+        assert_eq!(normalize("/../.."), "/");
+    }
+
+    #[test]
+    fn test_file_ref() {
+        assert_eq!(file_ref("/"), "%2F");
+        assert_eq!(file_ref("/home/a b"), "%2Fhome%2Fa%20b");
+        assert_eq!(file_ref("/home/a b/"), "%2Fhome%2Fa%20b");
+        assert_eq!(file_ref("//home/../x%2Fy"), "%2Fx%252Fy");
+    }
+}
@@ -0,0 +1,120 @@
+//! Minimal shell-style glob matching for filesystem paths and names.
+//!
+//! `*` matches any run of characters except `/`, `**` also crosses `/`,
+//! `?` matches one non-`/` character, and `[abc]` / `[a-z]` / `[!a]` match
+//! a character class.
+
+/// Returns true if `s` contains glob metacharacters.
+pub fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+/// Match `text` against `pattern` in full.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    match_from(&pattern, &text)
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return text.is_empty();
+    };
+
+    match first {
+        '*' => {
+            let crosses_slash = rest.first() == Some(&'*');
+            let rest = if crosses_slash { &rest[1..] } else { rest };
+            for i in 0..=text.len() {
+                if match_from(rest, &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' && !crosses_slash {
+                    return false;
+                }
+            }
+            false
+        }
+        '?' => match text.split_first() {
+            Some((&c, text_rest)) if c != '/' => match_from(rest, text_rest),
+            _ => false,
+        },
+        '[' => {
+            let Some((&c, text_rest)) = text.split_first() else {
+                return false;
+            };
+            match match_class(rest, c) {
+                Some((true, after)) => match_from(after, text_rest),
+                Some((false, _)) => false,
+                // Unterminated class: treat '[' literally
+                None => c == '[' && match_from(rest, text_rest),
+            }
+        }
+        literal => match text.split_first() {
+            Some((&c, text_rest)) if c == literal => match_from(rest, text_rest),
+            _ => false,
+        },
+    }
+}
+
+/// Match `c` against the class body following '['. Returns whether it
+/// matched and the pattern after the closing ']'.
+fn match_class(body: &[char], c: char) -> Option<(bool, &[char])> {
+    let (negate, body) = match body.first() {
+        Some('!') | Some('^') => (true, &body[1..]),
+        _ => (false, body),
+    };
+
+    let mut matched = false;
+    let mut i = 0;
+    while i < body.len() {
+        // A ']' first in the class is a literal member
+        if body[i] == ']' && i > 0 {
+            return Some((matched != negate, &body[i + 1..]));
+        }
+        if i + 2 < body.len() && body[i + 1] == '-' && body[i + 2] != ']' {
+            matched |= body[i] <= c && c <= body[i + 2];
+            i += 3;
+        } else {
+            matched |= body[i] == c;
+            i += 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_star_stays_within_segment() {
+        assert!(glob_match("/projects/*", "/projects/foo"));
+        assert!(!glob_match("/projects/*", "/projects/foo/bar"));
+        assert!(glob_match("/projects/*/data", "/projects/foo/data"));
+        assert!(glob_match("*.log", "app.log"));
+    }
+
+    #[test]
+    fn test_glob_double_star_crosses_segments() {
+        assert!(glob_match("/projects/**", "/projects/foo/bar"));
+        assert!(glob_match("/**/data", "/a/b/data"));
+        assert!(!glob_match("/**/data", "/a/b/data2"));
+    }
+
+    #[test]
+    fn test_glob_question_and_classes() {
+        assert!(glob_match("snap-?", "snap-1"));
+        assert!(!glob_match("snap-?", "snap-12"));
+        assert!(glob_match("snap-[0-9]", "snap-7"));
+        assert!(!glob_match("snap-[!0-9]", "snap-7"));
+        assert!(glob_match("snap-[ab]", "snap-b"));
+        assert!(glob_match("a[", "a["));
+    }
+
+    #[test]
+    fn test_is_glob() {
+        assert!(is_glob("/projects/*"));
+        assert!(!is_glob("/projects/foo"));
+    }
+}
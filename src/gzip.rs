@@ -0,0 +1,295 @@
+//! A small gzip encoder for archiving JSON output: LZ77 matching with the
+//! fixed Huffman codes of deflate (RFC 1951), wrapped in a gzip member
+//! (RFC 1952). It trades some ratio against a real compressor for not
+//! pulling one in; repetitive JSON still shrinks several times over.
+
+/// Longest and shortest matches deflate can encode
+const MAX_MATCH: usize = 258;
+const MIN_MATCH: usize = 3;
+/// How far back a match may start
+const WINDOW: usize = 32 * 1024;
+/// Candidates tried per position; more finds longer matches, slower
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Compress `data` into a complete gzip file.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    // Magic, deflate, no flags, no mtime, no extra flags, unknown OS
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    deflate(data, &mut out);
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Append `data` as a single final deflate block with fixed Huffman codes.
+fn deflate(data: &[u8], out: &mut Vec<u8>) {
+    let mut bits = BitWriter {
+        out,
+        acc: 0,
+        len: 0,
+    };
+    bits.write(1, 1); // BFINAL
+    bits.write(1, 2); // BTYPE = fixed Huffman
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; WINDOW];
+    let insert = |head: &mut Vec<usize>, prev: &mut Vec<usize>, pos: usize| {
+        if pos + MIN_MATCH <= data.len() {
+            let h = hash(&data[pos..pos + MIN_MATCH]);
+            prev[pos % WINDOW] = head[h];
+            head[h] = pos;
+        }
+    };
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let (len, dist) = longest_match(data, pos, &head, &prev);
+        if len >= MIN_MATCH {
+            bits.length(len);
+            bits.distance(dist);
+            for p in pos..pos + len {
+                insert(&mut head, &mut prev, p);
+            }
+            pos += len;
+        } else {
+            bits.literal(data[pos] as u16);
+            insert(&mut head, &mut prev, pos);
+            pos += 1;
+        }
+    }
+    bits.literal(256); // end of block
+    bits.flush();
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let v = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (v.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+/// The longest earlier match for the bytes at `pos` within the window, as
+/// (length, distance); length 0 when there is none.
+fn longest_match(data: &[u8], pos: usize, head: &[usize], prev: &[usize]) -> (usize, usize) {
+    if pos + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+    let max = (data.len() - pos).min(MAX_MATCH);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(&data[pos..pos + MIN_MATCH])];
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || pos - candidate > WINDOW {
+            break;
+        }
+        let len = data[candidate..]
+            .iter()
+            .zip(&data[pos..pos + max])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if len > best.0 {
+            best = (len, pos - candidate);
+            if len == max {
+                break;
+            }
+        }
+        let next = prev[candidate % WINDOW];
+        // A slot reused by a newer position ends the chain
+        if next == usize::MAX || next >= candidate {
+            break;
+        }
+        candidate = next;
+    }
+    best
+}
+
+/// Packs bits least-significant first, as deflate stores them.
+struct BitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    acc: u32,
+    len: u32,
+}
+
+impl BitWriter<'_> {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.acc |= value << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.out.push(self.acc as u8);
+            self.acc >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Huffman codes are defined most-significant bit first.
+    fn code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn literal(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, len: usize) {
+        let i = LENGTH_BASE
+            .iter()
+            .rposition(|&b| b as usize <= len)
+            .unwrap();
+        self.literal(257 + i as u16);
+        self.write(
+            (len - LENGTH_BASE[i] as usize) as u32,
+            LENGTH_EXTRA[i] as u32,
+        );
+    }
+
+    fn distance(&mut self, dist: usize) {
+        let i = DIST_BASE.iter().rposition(|&b| b as usize <= dist).unwrap();
+        self.code(i as u32, 5);
+        self.write((dist - DIST_BASE[i] as usize) as u32, DIST_EXTRA[i] as u32);
+    }
+
+    fn flush(&mut self) {
+        if self.len > 0 {
+            self.out.push(self.acc as u8);
+            self.acc = 0;
+            self.len = 0;
+        }
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+        }
+        *entry = c;
+    }
+    !data.iter().fold(!0u32, |crc, &b| {
+        table[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inflate a single fixed-Huffman block, enough to check `deflate`.
+    fn inflate_fixed(data: &[u8]) -> Vec<u8> {
+        let mut pos = 0usize;
+        let mut bit = |n: u32| {
+            let mut v = 0;
+            for i in 0..n {
+                v |= ((data[pos / 8] >> (pos % 8)) as u32 & 1) << i;
+                pos += 1;
+            }
+            v
+        };
+        assert_eq!(bit(1), 1, "final block");
+        assert_eq!(bit(2), 1, "fixed Huffman");
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            let mut code = 0;
+            for _ in 0..7 {
+                code = code << 1 | bit(1);
+            }
+            let symbol = if code <= 0x17 {
+                code + 256
+            } else {
+                code = code << 1 | bit(1);
+                match code {
+                    0x30..=0xbf => code - 0x30,
+                    0xc0..=0xc7 => code - 0xc0 + 280,
+                    _ => (code << 1 | bit(1)) - 0x190 + 144,
+                }
+            };
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => return out,
+                _ => {
+                    let i = (symbol - 257) as usize;
+                    let len = LENGTH_BASE[i] as usize + bit(LENGTH_EXTRA[i] as u32) as usize;
+                    let mut d = 0;
+                    for _ in 0..5 {
+                        d = d << 1 | bit(1);
+                    }
+                    let d = d as usize;
+                    let dist = DIST_BASE[d] as usize + bit(DIST_EXTRA[d] as u32) as usize;
+                    for _ in 0..len {
+                        out.push(out[out.len() - dist]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        let gz = compress(data);
+        assert_eq!(&gz[..3], &[0x1f, 0x8b, 8]);
+        let n = gz.len();
+        assert_eq!(gz[n - 8..n - 4], crc32(data).to_le_bytes());
+        assert_eq!(gz[n - 4..], (data.len() as u32).to_le_bytes());
+        inflate_fixed(&gz[10..n - 8])
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for data in [
+            &b""[..],
+            b"a",
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "héllo wörld \u{2014} ÿ".as_bytes(),
+        ] {
+            assert_eq!(round_trip(data), data);
+        }
+        let bytes: Vec<u8> = (0..100_000u32).map(|i| (i * 7 % 251) as u8).collect();
+        assert_eq!(round_trip(&bytes), bytes);
+    }
+
+    #[test]
+    fn test_json_shrinks() {
+        let json: String = (0..2000)
+            .map(|i| {
+                format!(
+                    r#"{{"node_id":{},"connections":{},"status":"online"}},"#,
+                    i,
+                    i % 40
+                )
+            })
+            .collect();
+        let gz = compress(json.as_bytes());
+        assert!(gz.len() * 4 < json.len(), "{} -> {}", json.len(), gz.len());
+        assert_eq!(round_trip(json.as_bytes()), json.as_bytes());
+    }
+}
@@ -0,0 +1,113 @@
+//! Resolving auth IDs to names, cached across runs.
+//!
+//! A long listing shows one owner per entry, and a directory of a million
+//! files usually has a handful of owners. Lookups are memoized for the life
+//! of the process and kept in the disk cache (`~/.cache/qontrol/api/`) for
+//! [`IDENTITY_TTL`], so repeated listings of the same tree make no identity
+//! calls at all.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::cache::DiskCache;
+use crate::client::QumuloClient;
+use crate::config::ProfileEntry;
+
+/// How long a resolved identity stays in the disk cache. Names change rarely
+/// and a stale one only affects display.
+pub const IDENTITY_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Identity lookups for one cluster
+pub struct Identities {
+    disk: Option<DiskCache>,
+    ttl: Duration,
+    memo: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl Identities {
+    /// Identities for the profile's cluster, cached under its UUID (or its
+    /// host and port until the UUID is known)
+    pub fn for_profile(profile: &ProfileEntry) -> Self {
+        let scope = profile
+            .cluster_uuid
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", profile.host, profile.port));
+        Self::new(DiskCache::new(&scope).ok(), IDENTITY_TTL)
+    }
+
+    pub fn new(disk: Option<DiskCache>, ttl: Duration) -> Self {
+        Self {
+            disk,
+            ttl,
+            memo: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The display name for an auth ID, or None when the cluster can't
+    /// resolve it. Lookup failures are not cached on disk, so they are
+    /// retried on the next run.
+    pub fn name(&self, client: &QumuloClient, auth_id: &str) -> Option<String> {
+        if let Some(name) = self.memo.lock().unwrap().get(auth_id) {
+            return name.clone();
+        }
+
+        let key = format!("/v1/identity/find#{}", auth_id);
+        let cached = self.disk.as_ref().and_then(|d| d.get(&key, self.ttl));
+        let identity = match cached {
+            Some(identity) => Some(identity),
+            None => match client.find_identity(auth_id) {
+                Ok(identity) => {
+                    if let Some(ref disk) = self.disk {
+                        disk.put(&key, self.ttl, &identity);
+                    }
+                    Some(identity)
+                }
+                Err(e) => {
+                    tracing::debug!(%auth_id, error = %e, "identity lookup failed");
+                    None
+                }
+            },
+        };
+
+        let name = identity.as_ref().and_then(identity_name);
+        self.memo
+            .lock()
+            .unwrap()
+            .insert(auth_id.to_string(), name.clone());
+        name
+    }
+
+    /// The name for an auth ID, falling back to the ID itself
+    pub fn display(&self, client: &QumuloClient, auth_id: &str) -> String {
+        self.name(client, auth_id)
+            .unwrap_or_else(|| auth_id.to_string())
+    }
+}
+
+/// The name of a `/v1/identity/find` response, if it has a non-empty one
+fn identity_name(identity: &Value) -> Option<String> {
+    identity
+        .get("name")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identity_name() {
+        assert_eq!(
+            identity_name(&json!({"auth_id": "500", "name": "alice"})),
+            Some("alice".to_string())
+        );
+        assert_eq!(identity_name(&json!({"auth_id": "500", "name": ""})), None);
+        assert_eq!(identity_name(&json!({"auth_id": "500"})), None);
+    }
+}
@@ -3,5 +3,16 @@ pub mod cli;
 pub mod client;
 pub mod commands;
 pub mod config;
+pub mod confirm;
 pub mod error;
+pub mod fspath;
+pub mod glob;
+pub mod gzip;
+pub mod identity;
+pub mod notify;
 pub mod output;
+pub mod progress;
+pub mod sanitize;
+pub mod subnet;
+pub mod tunnel;
+pub mod yaml;
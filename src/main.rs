@@ -3,12 +3,21 @@ use clap::Parser;
 use tracing_subscriber::EnvFilter;
 
 use qontrol::cli::{
-    ApiCommands, CdfCommands, Cli, ClusterCommands, Commands, FleetCommands, FleetHwCommands,
-    FleetHwPsuCommands, FsCommands, HwCommands, HwPsuCommands, ProfileCommands, SnapshotCommands,
+    AlertCommands, AnalyticsCommands, ApiCommands, AuthCommands, AuthTokensCommands,
+    CapacityCommands, CdfCommands, Cli, ClusterCommands, ClusterNodeCommands,
+    ClusterProtectionCommands, ClusterTlsCommands, Commands, ConnectionsCommands, DevCommands,
+    FleetCommands, FleetHwCommands, FleetHwPsuCommands, FleetSnapshotPolicyCommands, FsCommands,
+    FsManifestCommands, HistoryCommands, HwCommands, HwPsuCommands, MaintenanceCommands,
+    ProfileCommands, QuotaCommands, ReportCommands, SnapshotCommands, SnapshotFilterArgs,
+    SnapshotScheduleArgs,
 };
-use qontrol::client::QumuloClient;
+use qontrol::client::{QumuloClient, TlsOptions};
 use qontrol::commands;
-use qontrol::config::{ensure_cluster_uuids, load_config, resolve_profile};
+use qontrol::config::{ensure_cluster_uuids, ensure_fresh_tokens, load_config, resolve_profile};
+use qontrol::confirm::Guard;
+use qontrol::identity::Identities;
+use qontrol::output::{OutputFormat, Template};
+use qontrol::progress::ProgressMode;
 
 fn main() {
     let cli = Cli::parse();
@@ -38,8 +47,139 @@ fn main() {
     }
 }
 
-fn run(cli: Cli) -> Result<()> {
+fn schedule_args(args: &SnapshotScheduleArgs) -> commands::snapshot::ScheduleArgs<'_> {
+    commands::snapshot::ScheduleArgs {
+        every_day_at: args.every_day_at.as_deref(),
+        on_days: args.on_days.as_deref(),
+        keep_for: args.keep_for.as_deref(),
+        timezone: args.timezone.as_deref(),
+    }
+}
+
+/// Normalize a path argument for fs and snapshot commands, echoing its
+/// encoded file ref to stderr with --debug-path.
+fn fs_path(path: &str, debug: bool) -> String {
+    if debug {
+        eprintln!("{}", qontrol::fspath::debug_line(path));
+    }
+    qontrol::fspath::normalize(path)
+}
+
+fn snapshot_filter(args: &SnapshotFilterArgs) -> Result<commands::snapshot::SnapshotFilter> {
+    commands::snapshot::SnapshotFilter::parse(
+        args.name_glob.as_deref(),
+        args.older_than.as_deref(),
+        args.created_by.as_deref(),
+    )
+}
+
+/// Rotate, then warn about, expired or soon-to-expire access tokens before
+/// running a command: the selected profile's, or every profile's for
+/// fleet-wide commands.
+fn check_tokens(cli: &Cli) {
+    let fleet_wide = match cli.command {
+        Commands::Init
+        | Commands::Completions { .. }
+        | Commands::Profile { .. }
+        | Commands::Auth { .. }
+        | Commands::Alert { .. }
+        | Commands::History { .. }
+        | Commands::Maintenance { .. }
+        | Commands::Serve { .. } => return,
+        Commands::Fleet { .. }
+        | Commands::Cdf { .. }
+        | Commands::Report { .. }
+        | Commands::Daemon { .. } => true,
+        _ => false,
+    };
+    // Config problems are reported by the command itself
+    let Ok(mut config) = load_config() else {
+        return;
+    };
+    let profiles = if fleet_wide {
+        config.profiles.keys().cloned().collect()
+    } else {
+        let profile = match cli.command {
+            Commands::Status {
+                profile: Some(ref profile),
+                ..
+            } => Some(profile.clone()),
+            _ => cli.profile.clone(),
+        };
+        match resolve_profile(&config, &profile) {
+            Ok((name, _)) => vec![name],
+            Err(_) => return,
+        }
+    };
+    // Rotation runs even with --quiet: unattended runs need it most
+    let rotated = ensure_fresh_tokens(&mut config, &profiles, cli.global_opts.timeout);
+    if cli.global_opts.quiet {
+        return;
+    }
+    for (name, expires) in rotated {
+        let until = expires
+            .map(|e| format!("; the new one expires {}", e.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        eprintln!(
+            "note: rotated the access token for profile '{}'{}",
+            name, until
+        );
+    }
+    commands::auth::warn_expiring(&config, &profiles);
+}
+
+/// Formats other than table and json are rendered only by the commands
+/// migrated to the shared serializer (and the reports' own renderers);
+/// refuse them elsewhere rather than quietly printing a table.
+fn check_output_format(cli: &Cli) -> Result<()> {
+    if matches!(cli.global_opts.output.as_str(), "table" | "json") {
+        return Ok(());
+    }
+    let supported = matches!(
+        cli.command,
+        Commands::Cluster {
+            command: ClusterCommands::Info
+        } | Commands::Snapshot {
+            command: SnapshotCommands::List { .. },
+            ..
+        } | Commands::Fs {
+            command: FsCommands::Ls { .. },
+            ..
+        } | Commands::Fleet {
+            command: FleetCommands::Status { .. }
+        } | Commands::Analytics {
+            command: AnalyticsCommands::Capacity { .. }
+        } | Commands::Report {
+            command: ReportCommands::CapacityForecast { .. } | ReportCommands::Chargeback { .. }
+        }
+    );
+    if !supported {
+        anyhow::bail!(
+            "--output {} is not supported by this command (use table or json); \
+             cluster info, snapshot list, fs ls, fleet status, analytics capacity and reports \
+             take other formats",
+            cli.global_opts.output
+        );
+    }
+    Ok(())
+}
+
+fn run(mut cli: Cli) -> Result<()> {
+    // --json is shorthand for --output json; commands still read the flag
+    if let Some(ref format) = cli.global_opts.format {
+        qontrol::output::set_template(Template::parse(format)?);
+        cli.global_opts.json = true;
+    }
+    if cli.global_opts.json {
+        cli.global_opts.output = "json".to_string();
+    }
+    cli.global_opts.json = cli.global_opts.output == "json";
+    check_output_format(&cli)?;
+    qontrol::progress::set_mode(ProgressMode::parse(&cli.global_opts.progress)?);
+    check_tokens(&cli);
     match cli.command {
+        Commands::Init => commands::init::run(cli.global_opts.timeout),
+        Commands::Completions { shell } => commands::completions::run(&shell),
         Commands::Profile { command } => match command {
             ProfileCommands::Add {
                 name,
@@ -51,22 +191,52 @@ fn run(cli: Cli) -> Result<()> {
                 username,
                 password,
                 expiry,
+                client_cert,
+                client_key,
+                from_file,
             } => {
+                if let Some(path) = from_file {
+                    return commands::profile::add_from_file(
+                        &path,
+                        token,
+                        username,
+                        password,
+                        expiry.as_deref(),
+                        cli.global_opts.timeout,
+                    );
+                }
+                let Some(name) = name else {
+                    anyhow::bail!("a profile name is required");
+                };
                 if let Some(token) = token {
                     let host = host
                         .ok_or_else(|| anyhow::anyhow!("--host is required when using --token"))?;
-                    commands::profile::add(name, host, port, token, insecure, default)
+                    commands::profile::add(
+                        name,
+                        host,
+                        port,
+                        token,
+                        expiry.as_deref(),
+                        TlsOptions {
+                            insecure,
+                            client_cert,
+                            client_key,
+                        },
+                        default,
+                    )
                 } else {
                     commands::profile::add_interactive(
                         name,
                         host,
                         port,
                         insecure,
+                        client_cert,
+                        client_key,
                         default,
                         cli.global_opts.timeout,
                         username,
                         password,
-                        &expiry,
+                        expiry.as_deref(),
                     )
                 }
             }
@@ -76,31 +246,105 @@ fn run(cli: Cli) -> Result<()> {
                 let config = load_config()?;
                 commands::profile::show(name, &config, cli.global_opts.json)
             }
+            ProfileCommands::Test { name } => {
+                let config = load_config()?;
+                commands::profile::test(
+                    name,
+                    &config,
+                    cli.global_opts.timeout,
+                    cli.global_opts.json,
+                )
+            }
         },
         Commands::Api { command } => {
             let config = load_config()?;
             let (_, profile) = resolve_profile(&config, &cli.profile)?;
             let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
             match command {
-                ApiCommands::Raw { method, path, body } => {
-                    commands::api::raw(&client, &method, &path, body.as_deref())
-                }
+                ApiCommands::Raw {
+                    method,
+                    path,
+                    body,
+                    sanitize,
+                } => commands::api::raw(&client, &method, &path, body.as_deref(), sanitize),
             }
         }
         Commands::Cluster { command } => {
             let config = load_config()?;
-            let (_, profile) = resolve_profile(&config, &cli.profile)?;
+            let (profile_name, profile) = resolve_profile(&config, &cli.profile)?;
             let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
             match command {
-                ClusterCommands::Info => commands::cluster::info(&client, cli.global_opts.json),
+                ClusterCommands::Info => {
+                    commands::cluster::info(&client, OutputFormat::parse(&cli.global_opts.output)?)
+                }
+                ClusterCommands::Protection { command } => match command {
+                    ClusterProtectionCommands::Show => {
+                        commands::cluster::protection_show(&client, cli.global_opts.json)
+                    }
+                },
+                ClusterCommands::Tls { command } => match command {
+                    ClusterTlsCommands::Show => {
+                        commands::cluster::tls_show(&client, cli.global_opts.json)
+                    }
+                    ClusterTlsCommands::Set {
+                        tls12_ciphers,
+                        tls13_ciphers,
+                        certificate,
+                        private_key,
+                        yes,
+                        confirm_name,
+                    } => {
+                        let guard = Guard::new(&config, &profile_name, yes, confirm_name);
+                        commands::cluster::tls_set(
+                            &client,
+                            &guard,
+                            tls12_ciphers.as_deref(),
+                            tls13_ciphers.as_deref(),
+                            certificate.as_deref(),
+                            private_key.as_deref(),
+                            cli.global_opts.json,
+                        )
+                    }
+                },
+                ClusterCommands::Hardware { node } => {
+                    commands::hw::chassis::show(&client, node, cli.global_opts.json)
+                }
+                ClusterCommands::Node { command } => {
+                    let (action, id, args) = match command {
+                        ClusterNodeCommands::Reboot { id, action } => {
+                            (commands::cluster::NodeAction::Reboot, id, action)
+                        }
+                        ClusterNodeCommands::RestartServices { id, action } => {
+                            (commands::cluster::NodeAction::RestartServices, id, action)
+                        }
+                    };
+                    let guard = Guard::new(&config, &profile_name, args.yes, args.confirm_name);
+                    commands::cluster::node_action(
+                        &client,
+                        &guard,
+                        action,
+                        id,
+                        args.no_wait,
+                        args.wait_timeout,
+                        args.interval,
+                        cli.global_opts.json,
+                    )
+                }
             }
         }
-        Commands::Snapshot { command } => {
+        Commands::Snapshot {
+            command,
+            debug_path,
+        } => {
             let config = load_config()?;
-            let (_, profile) = resolve_profile(&config, &cli.profile)?;
+            let (profile_name, profile) = resolve_profile(&config, &cli.profile)?;
             let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
             match command {
-                SnapshotCommands::List => commands::snapshot::list(&client, cli.global_opts.json),
+                SnapshotCommands::List { filter } => commands::snapshot::list(
+                    &client,
+                    &snapshot_filter(&filter)?,
+                    OutputFormat::parse(&cli.global_opts.output)?,
+                ),
                 SnapshotCommands::Show { id } => {
                     commands::snapshot::show(&client, id, cli.global_opts.json)
                 }
@@ -121,27 +365,152 @@ fn run(cli: Cli) -> Result<()> {
                 SnapshotCommands::Diff { newer, older } => {
                     commands::snapshot::diff(&client, newer, older, cli.global_opts.json)
                 }
+                SnapshotCommands::Attribution { path, sample } => {
+                    let path = fs_path(&path, debug_path);
+                    commands::snapshot::attribution(&client, &path, sample, cli.global_opts.json)
+                }
+                SnapshotCommands::Create {
+                    path,
+                    name,
+                    expiration,
+                } => commands::snapshot::create(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    name.as_deref(),
+                    expiration.as_deref(),
+                    cli.global_opts.json,
+                ),
+                SnapshotCommands::PolicyCreate {
+                    name,
+                    path,
+                    schedule,
+                    disabled,
+                } => commands::snapshot::policy_create(
+                    &client,
+                    &name,
+                    &fs_path(&path, debug_path),
+                    &schedule_args(&schedule),
+                    disabled,
+                    cli.global_opts.json,
+                ),
+                SnapshotCommands::PolicyUpdate {
+                    id,
+                    name,
+                    schedule,
+                    enable,
+                    disable,
+                } => commands::snapshot::policy_update(
+                    &client,
+                    id,
+                    name.as_deref(),
+                    &schedule_args(&schedule),
+                    (enable || disable).then_some(enable),
+                    cli.global_opts.json,
+                ),
+                SnapshotCommands::PolicyDelete {
+                    id,
+                    yes,
+                    confirm_name,
+                } => {
+                    let guard = Guard::new(&config, &profile_name, yes, confirm_name);
+                    commands::snapshot::policy_delete(&client, &guard, id, cli.global_opts.json)
+                }
+                SnapshotCommands::Delete {
+                    id,
+                    filter,
+                    dry_run,
+                    yes,
+                    confirm_name,
+                } => {
+                    let guard = Guard::new(&config, &profile_name, yes, confirm_name);
+                    match id {
+                        Some(id) => {
+                            commands::snapshot::delete(&client, &guard, id, cli.global_opts.json)
+                        }
+                        None => commands::snapshot::delete_matching(
+                            &client,
+                            &guard,
+                            &snapshot_filter(&filter)?,
+                            dry_run,
+                            cli.global_opts.json,
+                        ),
+                    }
+                }
             }
         }
         Commands::Fleet { command } => match command {
             FleetCommands::Status {
                 watch,
                 interval,
+                no_clear,
                 profiles,
                 no_cache,
                 timing,
+                level,
+                projection_model,
+                projection_window,
+                full_refresh_every,
+                top_activity,
+                tui,
+                jobs,
+                cluster_budget,
+                sinks,
+                sort,
+                compact,
+                only_degraded,
+                view,
+                json_compact,
+                json_out,
+                gzip,
+                check,
+                notify_test,
+                cached,
+                max_age,
             } => {
                 let mut config = load_config()?;
-                ensure_cluster_uuids(&mut config, cli.global_opts.timeout);
-                commands::status::run(
+                if notify_test {
+                    return qontrol::notify::send_test(&config.notify, cli.global_opts.json);
+                }
+                if !cached {
+                    ensure_cluster_uuids(&mut config, cli.global_opts.timeout);
+                }
+                let display = commands::status::view::DisplayOptions::resolve(
                     &config,
+                    view.as_deref(),
                     &profiles,
-                    cli.global_opts.json,
+                    sort.as_deref(),
+                    compact,
+                    only_degraded,
+                    level.as_deref(),
+                    projection_model.as_deref(),
+                    projection_window.as_deref(),
+                    top_activity,
+                )?;
+                let json_mode = cli.global_opts.json || json_compact || json_out.is_some();
+                commands::status::run(
+                    &config,
+                    &display,
+                    json_mode,
                     watch,
                     interval,
+                    no_clear,
                     no_cache,
                     cli.global_opts.timeout,
                     timing,
+                    &cli.global_opts.output,
+                    full_refresh_every,
+                    tui,
+                    jobs,
+                    cluster_budget.as_deref(),
+                    &sinks,
+                    &commands::status::json::JsonOptions {
+                        compact: json_compact,
+                        out: json_out,
+                        gzip,
+                        ..Default::default()
+                    },
+                    check,
+                    cached.then_some(max_age.as_str()),
                 )
             }
             FleetCommands::Hw { command } => match command {
@@ -158,6 +527,18 @@ fn run(cli: Cli) -> Result<()> {
                     }
                 },
             },
+            FleetCommands::SnapshotPolicy { command } => match command {
+                FleetSnapshotPolicyCommands::Check { expect, profiles } => {
+                    let config = load_config()?;
+                    commands::snapshot::fleet_policy_check(
+                        &config,
+                        &profiles,
+                        &expect,
+                        cli.global_opts.timeout,
+                        cli.global_opts.json,
+                    )
+                }
+            },
         },
         Commands::Hw { command } => {
             let config = load_config()?;
@@ -165,32 +546,279 @@ fn run(cli: Cli) -> Result<()> {
             let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
             match command {
                 HwCommands::Psu { command } => match command {
-                    HwPsuCommands::Check => {
-                        commands::hw::psu::check(&client, cli.global_opts.json)
-                    }
+                    HwPsuCommands::Check => commands::hw::psu::check(&client, cli.global_opts.json),
                 },
             }
         }
+        Commands::Capacity { command } => match command {
+            CapacityCommands::WhatIf {
+                cluster,
+                add_nodes,
+                model,
+                add_usable,
+            } => {
+                let config = load_config()?;
+                let (profile_name, profile) =
+                    resolve_profile(&config, &cluster.or(cli.profile.clone()))?;
+                let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
+                let expansion = match (add_nodes, add_usable) {
+                    (Some(count), _) => commands::capacity::Expansion::Nodes { count, model },
+                    (None, Some(size)) => {
+                        commands::capacity::Expansion::Usable(commands::quota::parse_size(&size)?)
+                    }
+                    (None, None) => anyhow::bail!("give --add-nodes or --add-usable"),
+                };
+                commands::capacity::what_if(
+                    &client,
+                    &profile_name,
+                    profile.collect.projection(None, None),
+                    &expansion,
+                    cli.global_opts.json,
+                )
+            }
+        },
+        Commands::Analytics { command } => match command {
+            AnalyticsCommands::Capacity {
+                cluster,
+                days,
+                chart,
+            } => {
+                let config = load_config()?;
+                let (profile_name, profile) =
+                    resolve_profile(&config, &cluster.or(cli.profile.clone()))?;
+                let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
+                commands::analytics::capacity_history(
+                    &client,
+                    &profile_name,
+                    days,
+                    chart,
+                    OutputFormat::parse(&cli.global_opts.output)?,
+                )
+            }
+        },
+        Commands::Report { command } => match command {
+            ReportCommands::CapacityForecast { profiles } => {
+                let config = load_config()?;
+                commands::report::forecast::capacity_forecast(
+                    &config,
+                    &profiles,
+                    cli.global_opts.timeout,
+                    cli.global_opts.json,
+                    &cli.global_opts.output,
+                )
+            }
+            ReportCommands::Generate {
+                template,
+                out,
+                profiles,
+            } => {
+                let mut config = load_config()?;
+                ensure_cluster_uuids(&mut config, cli.global_opts.timeout);
+                commands::report::template::generate(
+                    &config,
+                    &profiles,
+                    cli.global_opts.timeout,
+                    &template,
+                    out.as_deref(),
+                )
+            }
+            ReportCommands::Chargeback { by, rate, profiles } => {
+                let config = load_config()?;
+                commands::report::chargeback::chargeback(
+                    &config,
+                    &profiles,
+                    cli.global_opts.timeout,
+                    &by,
+                    rate.as_deref(),
+                    cli.global_opts.json,
+                    &cli.global_opts.output,
+                )
+            }
+        },
+        Commands::Alert { command } => match command {
+            AlertCommands::History { cluster, since } => {
+                commands::alert::history(cluster.as_deref(), since.as_deref(), cli.global_opts.json)
+            }
+            AlertCommands::Ack {
+                cluster,
+                category,
+                until,
+                reason,
+            } => commands::alert::ack(&cluster, &category, &until, reason, cli.global_opts.json),
+            AlertCommands::Unack { cluster, category } => {
+                commands::alert::unack(&cluster, &category, cli.global_opts.json)
+            }
+            AlertCommands::Acks => commands::alert::acks(cli.global_opts.json),
+            AlertCommands::Categories => commands::alert::categories(cli.global_opts.json),
+        },
+        Commands::Status { profile, detail } => {
+            let config = load_config()?;
+            let (profile_name, entry) = resolve_profile(&config, &profile.or(cli.profile))?;
+            let client = QumuloClient::new(&entry, cli.global_opts.timeout, None)?;
+            commands::status::detail::run(
+                &config,
+                &profile_name,
+                &client,
+                detail,
+                cli.global_opts.timeout,
+                cli.global_opts.json,
+            )
+        }
+        Commands::History { command } => match command {
+            HistoryCommands::Show {
+                cluster,
+                metric,
+                since,
+            } => commands::history::show(cluster.as_deref(), &metric, &since, cli.global_opts.json),
+        },
+        Commands::Daemon {
+            interval,
+            profiles,
+            full_refresh_every,
+            jobs,
+            cluster_budget,
+            sinks,
+            once,
+            listen,
+        } => {
+            let mut config = load_config()?;
+            ensure_cluster_uuids(&mut config, cli.global_opts.timeout);
+            if let Some(ref listen) = listen {
+                // Clusters count as fresh for a few missed polls
+                let max_age = std::time::Duration::from_secs(interval.saturating_mul(3));
+                commands::status::serve::spawn(&config, &profiles, listen, max_age)?;
+            }
+            commands::status::daemon::run(
+                &config,
+                &profiles,
+                interval,
+                cli.global_opts.timeout,
+                full_refresh_every,
+                jobs,
+                cluster_budget.as_deref(),
+                &sinks,
+                once,
+                cli.global_opts.quiet,
+            )
+        }
+        Commands::Serve {
+            listen,
+            profiles,
+            max_age,
+        } => {
+            let config = load_config()?;
+            let max_age = commands::maintenance::parse_duration(&max_age)?
+                .to_std()
+                .unwrap_or_default();
+            commands::status::serve::run(&config, &profiles, &listen, max_age)
+        }
+        Commands::Dev { command } => match command {
+            DevCommands::RecordFixtures {
+                set,
+                out,
+                name,
+                force,
+                sanitize,
+            } => {
+                let config = load_config()?;
+                let (profile_name, profile) = resolve_profile(&config, &cli.profile)?;
+                let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
+                commands::dev::record_fixtures(
+                    &client,
+                    &profile,
+                    commands::dev::FixtureSet::parse(&set)?,
+                    &out,
+                    name.as_deref().unwrap_or(&profile_name),
+                    force,
+                    sanitize,
+                    cli.global_opts.json,
+                )
+            }
+        },
+        Commands::Connections { command } => {
+            let config = load_config()?;
+            let (name, profile) = resolve_profile(&config, &cli.profile)?;
+            match command {
+                ConnectionsCommands::Record => {
+                    let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
+                    commands::connections::record(&client, &name, cli.global_opts.json)
+                }
+                ConnectionsCommands::IdleReport { threshold } => {
+                    commands::connections::idle_report(&name, &threshold, cli.global_opts.json)
+                }
+            }
+        }
+        Commands::Maintenance { command } => match command {
+            MaintenanceCommands::Start {
+                cluster,
+                duration,
+                reason,
+            } => commands::maintenance::start(&cluster, &duration, reason, cli.global_opts.json),
+            MaintenanceCommands::End { cluster } => {
+                commands::maintenance::end(&cluster, cli.global_opts.json)
+            }
+            MaintenanceCommands::List => {
+                let config = load_config()?;
+                commands::maintenance::list(&config, cli.global_opts.json)
+            }
+        },
+        Commands::Auth { command } => match command {
+            AuthCommands::Tokens { command } => match command {
+                AuthTokensCommands::Expiring {
+                    within,
+                    profiles,
+                    all,
+                } => {
+                    let config = load_config()?;
+                    commands::auth::expiring(
+                        &config,
+                        &profiles,
+                        within.as_deref(),
+                        all,
+                        cli.global_opts.json,
+                    )
+                }
+            },
+        },
         Commands::Cdf { command } => match command {
             CdfCommands::Status {
                 graph,
                 cluster,
                 profiles,
+                path,
                 problems_only,
+                summary_only,
+                wide,
+                strict_unknown,
+                watch,
+                interval,
+                no_clear,
             } => {
-                let config = load_config()?;
+                let mut config = load_config()?;
+                // UUIDs let unknown peers be matched back to profiles
+                ensure_cluster_uuids(&mut config, cli.global_opts.timeout);
                 commands::cdf::run(
                     &config,
                     &profiles,
                     cli.global_opts.json,
                     graph,
                     cluster.as_deref(),
+                    path.as_deref(),
                     problems_only,
+                    summary_only,
+                    wide,
+                    strict_unknown,
+                    watch,
+                    interval,
+                    no_clear,
                     cli.global_opts.timeout,
                 )
             }
         },
-        Commands::Fs { command } => {
+        Commands::Fs {
+            command,
+            debug_path,
+        } => {
             let config = load_config()?;
             let (_, profile) = resolve_profile(&config, &cli.profile)?;
             let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
@@ -199,14 +827,196 @@ fn run(cli: Cli) -> Result<()> {
                     path,
                     long,
                     sort,
+                    reverse,
                     limit,
-                } => commands::fs::ls(&client, &path, long, &sort, limit, cli.global_opts.json),
-                FsCommands::Tree { path, max_depth } => {
-                    commands::fs::tree(&client, &path, max_depth, cli.global_opts.json)
-                }
-                FsCommands::Stat { path } => {
-                    commands::fs::stat(&client, &path, cli.global_opts.json)
-                }
+                    recursive,
+                    max_depth,
+                    follow,
+                    no_follow: _,
+                    numeric_ids,
+                } => commands::fs::ls(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    long,
+                    &sort,
+                    reverse,
+                    limit,
+                    recursive.then_some(max_depth),
+                    follow,
+                    (!numeric_ids)
+                        .then(|| Identities::for_profile(&profile))
+                        .as_ref(),
+                    OutputFormat::parse(&cli.global_opts.output)?,
+                ),
+                FsCommands::Tree {
+                    path,
+                    max_depth,
+                    follow,
+                    no_follow: _,
+                } => commands::fs::tree(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    max_depth,
+                    follow,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Stat { path, streams } => commands::fs::stat(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    streams,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Xattr {
+                    path,
+                    key,
+                    metadata_type,
+                } => commands::fs::xattr(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    key.as_deref(),
+                    &metadata_type,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Mkdirs {
+                    from_file,
+                    mode,
+                    owner,
+                    jobs,
+                } => commands::fs::mkdirs(
+                    &client,
+                    &from_file,
+                    mode.as_deref(),
+                    owner.as_deref(),
+                    jobs,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Get {
+                    remote,
+                    local,
+                    recursive,
+                    jobs,
+                    multipart_over,
+                } => commands::fs::get(
+                    &client,
+                    &fs_path(&remote, debug_path),
+                    &local,
+                    recursive,
+                    jobs,
+                    &multipart_over,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Grep {
+                    pattern,
+                    path,
+                    name,
+                    max_size,
+                    ignore_case,
+                    jobs,
+                } => commands::fs::grep(
+                    &client,
+                    &pattern,
+                    &fs_path(&path, debug_path),
+                    name.as_deref(),
+                    max_size.as_deref(),
+                    ignore_case,
+                    jobs,
+                    cli.global_opts.json,
+                ),
+                FsCommands::DedupeReport {
+                    path,
+                    min_size,
+                    quick,
+                    jobs,
+                } => commands::fs::dedupe_report(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    min_size.as_deref(),
+                    quick,
+                    jobs,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Du { path, depth } => commands::fs::du(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    depth,
+                    cli.global_opts.json,
+                ),
+                FsCommands::Top {
+                    path,
+                    by,
+                    limit,
+                    max_depth,
+                    numeric_ids,
+                } => commands::fs::top(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    &by,
+                    limit,
+                    max_depth,
+                    (!numeric_ids)
+                        .then(|| Identities::for_profile(&profile))
+                        .as_ref(),
+                    cli.global_opts.json,
+                ),
+                FsCommands::Manifest { command } => match command {
+                    FsManifestCommands::Create {
+                        path,
+                        out,
+                        checksum,
+                        jobs,
+                    } => commands::fs::manifest_create(
+                        &client,
+                        &fs_path(&path, debug_path),
+                        out.as_deref(),
+                        checksum,
+                        jobs,
+                        cli.global_opts.json,
+                    ),
+                    FsManifestCommands::Verify {
+                        manifest,
+                        path,
+                        quick,
+                        jobs,
+                    } => commands::fs::manifest_verify(
+                        &client,
+                        &manifest,
+                        path.map(|path| fs_path(&path, debug_path)).as_deref(),
+                        quick,
+                        jobs,
+                        cli.global_opts.json,
+                    ),
+                },
+                FsCommands::Wait {
+                    path,
+                    condition,
+                    wait_timeout,
+                    interval,
+                } => commands::fs::wait(
+                    &client,
+                    &fs_path(&path, debug_path),
+                    &condition,
+                    wait_timeout,
+                    interval,
+                    cli.global_opts.json,
+                ),
+            }
+        }
+        Commands::Quota { command } => {
+            let config = load_config()?;
+            let (_, profile) = resolve_profile(&config, &cli.profile)?;
+            let client = QumuloClient::new(&profile, cli.global_opts.timeout, None)?;
+            match command {
+                QuotaCommands::ApplyTemplate {
+                    path_glob,
+                    limit,
+                    dry_run,
+                } => commands::quota::apply_template(
+                    &client,
+                    &path_glob,
+                    &limit,
+                    dry_run,
+                    cli.global_opts.json,
+                ),
             }
         }
     }
@@ -0,0 +1,466 @@
+//! Alert change notifications for `fleet status --watch` and `--tui`.
+//!
+//! Each poll's alert transitions (see [`crate::commands::alert`]) become
+//! events: a new alert, a severity escalation or downgrade, or a resolution.
+//! A poll with any events sends one JSON payload to every `[notify]`
+//! webhook, and a Block Kit message to every `[notify]` Slack webhook:
+//!
+//! ```json
+//! {"source": "qontrol", "timestamp": "2026-03-01T10:00:00Z",
+//!  "events": [{"event": "escalated", "severity": "critical",
+//!              "previous_severity": "warning", "cluster": "prod",
+//!              "category": "capacity", "message": "..."}]}
+//! ```
+//!
+//! Deliveries that fail on the network or with a 5xx/429 are retried with
+//! backoff, then logged; a notification problem never stops a watch.
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::commands::alert::{AlertTransition, TransitionKind};
+use crate::commands::status::types::AlertSeverity;
+use crate::config::NotifyConfig;
+use crate::output::print_json;
+
+/// How long a webhook gets to accept a delivery
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first retry; doubled for each one after
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Most events listed in one Slack message (Slack allows 50 blocks)
+const SLACK_MAX_EVENTS: usize = 40;
+
+/// How a target wants alert changes rendered
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Slack,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Fired,
+    Escalated,
+    Downgraded,
+    Resolved,
+}
+
+/// One change to the alert set between polls.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertEvent {
+    pub event: EventKind,
+    pub severity: AlertSeverity,
+    /// The severity before an escalation or downgrade
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_severity: Option<AlertSeverity>,
+    pub cluster: String,
+    pub category: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Payload<'a> {
+    source: &'static str,
+    timestamp: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    test: bool,
+    events: &'a [AlertEvent],
+}
+
+impl<'a> Payload<'a> {
+    fn new(events: &'a [AlertEvent], test: bool) -> Self {
+        Payload {
+            source: "qontrol",
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            test,
+            events,
+        }
+    }
+}
+
+/// Every configured target with the format it is sent
+fn targets(config: &NotifyConfig) -> Vec<(&str, Format)> {
+    let webhooks = config.webhooks.iter().map(|u| (u.as_str(), Format::Json));
+    let slack = config.slack.iter().map(|u| (u.as_str(), Format::Slack));
+    webhooks.chain(slack).collect()
+}
+
+/// Reject webhook URLs that aren't http(s) before a watch starts.
+pub fn validate(config: &NotifyConfig) -> Result<()> {
+    for (url, _) in targets(config) {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            anyhow::bail!(
+                "invalid [notify] webhook '{}' (expected an http:// or https:// URL)",
+                url
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Turn a poll's alert transitions into events. The history records a
+/// severity change as the old alert resolving and the new one firing; such
+/// pairs become a single escalation or downgrade.
+pub fn events(transitions: &[AlertTransition]) -> Vec<AlertEvent> {
+    let same_alert = |a: &AlertTransition, b: &AlertTransition| {
+        a.cluster == b.cluster && a.category == b.category && a.message == b.message
+    };
+    let mut resolved: Vec<Option<&AlertTransition>> = transitions
+        .iter()
+        .filter(|t| t.transition == TransitionKind::Resolved)
+        .map(Some)
+        .collect();
+
+    let mut events = Vec::new();
+    for fired in transitions
+        .iter()
+        .filter(|t| t.transition == TransitionKind::Fired)
+    {
+        let previous = resolved
+            .iter_mut()
+            .find(|r| r.is_some_and(|r| same_alert(r, fired)))
+            .and_then(Option::take);
+        let event = match previous {
+            None => EventKind::Fired,
+            Some(old) if rank(&fired.severity) > rank(&old.severity) => EventKind::Escalated,
+            Some(_) => EventKind::Downgraded,
+        };
+        events.push(AlertEvent {
+            event,
+            severity: fired.severity.clone(),
+            previous_severity: previous.map(|old| old.severity.clone()),
+            cluster: fired.cluster.clone(),
+            category: fired.category.clone(),
+            message: fired.message.clone(),
+        });
+    }
+    events.extend(resolved.into_iter().flatten().map(|r| AlertEvent {
+        event: EventKind::Resolved,
+        severity: r.severity.clone(),
+        previous_severity: None,
+        cluster: r.cluster.clone(),
+        category: r.category.clone(),
+        message: r.message.clone(),
+    }));
+    events
+}
+
+fn rank(severity: &AlertSeverity) -> u8 {
+    match severity {
+        AlertSeverity::Critical => 2,
+        AlertSeverity::Warning => 1,
+        AlertSeverity::Info => 0,
+    }
+}
+
+/// Send a poll's alert changes to every configured webhook, logging
+/// deliveries that still fail after retrying.
+pub fn notify(config: &NotifyConfig, transitions: &[AlertTransition]) {
+    let targets = targets(config);
+    if targets.is_empty() {
+        return;
+    }
+    let events = events(transitions);
+    if events.is_empty() {
+        return;
+    }
+    for (url, format) in targets {
+        let delivered = render(format, &events, false)
+            .and_then(|body| post(url, &body, config.retries(), RETRY_BACKOFF));
+        if let Err(e) = delivered {
+            tracing::warn!(%url, error = %e, "failed to deliver alert notification");
+        }
+    }
+}
+
+fn render(format: Format, events: &[AlertEvent], test: bool) -> Result<String> {
+    match format {
+        Format::Json => serde_json::to_string(&Payload::new(events, test))
+            .context("failed to serialize alert notification"),
+        Format::Slack => Ok(slack_message(events, test).to_string()),
+    }
+}
+
+/// A Block Kit message: a header counting the changes, then one section
+/// per event with its severity, cluster, category and message.
+fn slack_message(events: &[AlertEvent], test: bool) -> Value {
+    if test {
+        let text = "qontrol test notification";
+        return json!({
+            "text": text,
+            "blocks": [{
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        ":white_check_mark: *{}*: alert changes from `fleet status --watch` will be posted here.",
+                        text
+                    ),
+                },
+            }],
+        });
+    }
+    let summary = format!(
+        "qontrol: {} alert change{}",
+        events.len(),
+        if events.len() == 1 { "" } else { "s" }
+    );
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": {"type": "plain_text", "text": summary},
+    })];
+    for event in events.iter().take(SLACK_MAX_EVENTS) {
+        blocks.push(json!({
+            "type": "section",
+            "text": {"type": "mrkdwn", "text": slack_line(event)},
+        }));
+    }
+    if events.len() > SLACK_MAX_EVENTS {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{
+                "type": "mrkdwn",
+                "text": format!("…and {} more", events.len() - SLACK_MAX_EVENTS),
+            }],
+        }));
+    }
+    json!({"text": summary, "blocks": blocks})
+}
+
+fn slack_line(event: &AlertEvent) -> String {
+    let icon = match (event.event, &event.severity) {
+        (EventKind::Resolved, _) => ":large_green_circle:",
+        (_, AlertSeverity::Critical) => ":red_circle:",
+        (_, AlertSeverity::Warning) => ":large_orange_circle:",
+        (_, AlertSeverity::Info) => ":large_blue_circle:",
+    };
+    let change = match event.event {
+        EventKind::Fired => "New",
+        EventKind::Escalated => "Escalated",
+        EventKind::Downgraded => "Downgraded",
+        EventKind::Resolved => "Resolved",
+    };
+    let severity = match &event.previous_severity {
+        Some(previous) => format!("{} → {}", label(previous), label(&event.severity)),
+        None => label(&event.severity).to_string(),
+    };
+    format!(
+        "{} *{} {}* on *{}* ({})\n{}",
+        icon,
+        change,
+        severity,
+        slack_escape(&event.cluster),
+        slack_escape(&event.category),
+        slack_escape(&event.message)
+    )
+}
+
+fn label(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Critical => "CRITICAL",
+        AlertSeverity::Warning => "WARNING",
+        AlertSeverity::Info => "INFO",
+    }
+}
+
+/// Escape the characters Slack mrkdwn treats as control sequences
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// POST `body` to `url`, retrying network errors, 5xx and 429 up to
+/// `retries` more times.
+fn post(url: &str, body: &str, retries: u32, backoff: Duration) -> Result<()> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?;
+    let mut attempt = 0;
+    loop {
+        let result = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .with_context(|| format!("failed to reach {}", url));
+        let retryable = match &result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                response.status().is_server_error()
+                    || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(_) => true,
+        };
+        if !retryable || attempt >= retries {
+            return match result {
+                Ok(response) => Err(anyhow::anyhow!(
+                    "{} answered HTTP {}",
+                    url,
+                    response.status().as_u16()
+                )),
+                Err(e) => Err(e),
+            };
+        }
+        thread::sleep(backoff * 2u32.pow(attempt));
+        attempt += 1;
+    }
+}
+
+/// `fleet status --notify-test`: send a payload marked `"test": true` (a
+/// test message on Slack) to every configured webhook and report which
+/// accepted it.
+pub fn send_test(config: &NotifyConfig, json_mode: bool) -> Result<()> {
+    validate(config)?;
+    let targets = targets(config);
+    if targets.is_empty() {
+        anyhow::bail!(
+            "no webhooks configured (add `webhooks = [...]` or `slack = [...]` under [notify])"
+        );
+    }
+    let results: Vec<(&str, Option<String>)> = targets
+        .into_iter()
+        .map(|(url, format)| {
+            let error = render(format, &[], true)
+                .and_then(|body| post(url, &body, config.retries(), RETRY_BACKOFF))
+                .err()
+                .map(|e| format!("{:#}", e));
+            (url, error)
+        })
+        .collect();
+
+    if json_mode {
+        let value: Vec<_> = results
+            .iter()
+            .map(|(url, error)| {
+                serde_json::json!({"url": url, "delivered": error.is_none(), "error": error})
+            })
+            .collect();
+        print_json(&value)?;
+    } else {
+        for (url, error) in &results {
+            match error {
+                None => println!("Delivered a test notification to {}", url),
+                Some(e) => println!("Failed to notify {}: {}", url, e),
+            }
+        }
+    }
+    let failed = results.iter().filter(|(_, e)| e.is_some()).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} webhooks failed", failed, results.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(kind: TransitionKind, severity: AlertSeverity, message: &str) -> AlertTransition {
+        AlertTransition {
+            timestamp: "2026-03-01T10:00:00Z".to_string(),
+            transition: kind,
+            severity,
+            cluster: "music".to_string(),
+            category: "capacity".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_events_pair_severity_changes() {
+        let transitions = vec![
+            transition(TransitionKind::Resolved, AlertSeverity::Warning, "85% full"),
+            transition(
+                TransitionKind::Resolved,
+                AlertSeverity::Critical,
+                "node 2 offline",
+            ),
+            transition(TransitionKind::Fired, AlertSeverity::Critical, "85% full"),
+            transition(
+                TransitionKind::Fired,
+                AlertSeverity::Warning,
+                "disk 4 failed",
+            ),
+        ];
+        let changes = events(&transitions);
+        let kinds: Vec<(EventKind, &str)> = changes
+            .iter()
+            .map(|e| (e.event, e.message.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (EventKind::Escalated, "85% full"),
+                (EventKind::Fired, "disk 4 failed"),
+                (EventKind::Resolved, "node 2 offline"),
+            ]
+        );
+        assert_eq!(changes[0].previous_severity, Some(AlertSeverity::Warning));
+        assert_eq!(changes[1].previous_severity, None);
+
+        let downgrade = events(&[
+            transition(
+                TransitionKind::Resolved,
+                AlertSeverity::Critical,
+                "95% full",
+            ),
+            transition(TransitionKind::Fired, AlertSeverity::Info, "95% full"),
+        ]);
+        assert_eq!(downgrade.len(), 1);
+        assert_eq!(downgrade[0].event, EventKind::Downgraded);
+    }
+
+    #[test]
+    fn test_slack_message() {
+        let changes = events(&[
+            transition(TransitionKind::Resolved, AlertSeverity::Warning, "85% full"),
+            transition(TransitionKind::Fired, AlertSeverity::Critical, "85% full"),
+            transition(
+                TransitionKind::Resolved,
+                AlertSeverity::Info,
+                "<ad> unbound",
+            ),
+        ]);
+        let message = slack_message(&changes, false);
+        assert_eq!(message["text"], "qontrol: 2 alert changes");
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(
+            blocks[1]["text"]["text"],
+            ":red_circle: *Escalated WARNING → CRITICAL* on *music* (capacity)\n85% full"
+        );
+        assert_eq!(
+            blocks[2]["text"]["text"],
+            ":large_green_circle: *Resolved INFO* on *music* (capacity)\n&lt;ad&gt; unbound"
+        );
+
+        let many: Vec<AlertEvent> = (0..SLACK_MAX_EVENTS + 5)
+            .map(|_| changes[0].clone())
+            .collect();
+        let blocks = slack_message(&many, false)["blocks"].clone();
+        let blocks = blocks.as_array().unwrap();
+        assert_eq!(blocks.len(), SLACK_MAX_EVENTS + 2);
+        assert_eq!(blocks.last().unwrap()["elements"][0]["text"], "…and 5 more");
+    }
+
+    #[test]
+    fn test_validate_rejects_non_http_webhooks() {
+        let config = NotifyConfig {
+            slack: vec!["ftp://hooks.example.com".to_string()],
+            ..Default::default()
+        };
+        let err = validate(&config).unwrap_err().to_string();
+        assert!(err.contains("invalid [notify] webhook 'ftp://hooks.example.com'"));
+        assert!(validate(&NotifyConfig::default()).is_ok());
+    }
+}
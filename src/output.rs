@@ -1,15 +1,350 @@
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use serde::Serialize;
 use serde_json::Value;
 
+/// The global `--format` template, set once at startup. JSON output goes
+/// through it instead of being printed as a document.
+static TEMPLATE: OnceLock<Template> = OnceLock::new();
+
+/// Render JSON output through `template` for the rest of the process.
+pub fn set_template(template: Template) {
+    let _ = TEMPLATE.set(template);
+}
+
+/// A `--format` template such as `{{.name}} {{.capacity.used_pct}}`: text
+/// with `{{.path}}` fields, where a path walks object keys and list
+/// indexes (`{{.nodes.0.id}}`) and `{{.}}` is the whole value. `\t` and
+/// `\n` are tabs and newlines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template(Vec<Piece>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(Vec<String>),
+}
+
+impl Template {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let unescape = |s: &str| s.replace("\\t", "\t").replace("\\n", "\n");
+        let mut pieces = Vec::new();
+        let mut rest = spec;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                pieces.push(Piece::Text(unescape(&rest[..start])));
+            }
+            let end = rest[start..].find("}}").ok_or_else(|| {
+                anyhow::anyhow!("invalid --format: unclosed '{{{{' in '{}'", spec)
+            })?;
+            let field = rest[start + 2..start + end].trim();
+            let Some(path) = field.strip_prefix('.') else {
+                anyhow::bail!(
+                    "invalid --format: '{{{{{}}}}}' is not a field (expected e.g. {{{{.name}}}})",
+                    field
+                );
+            };
+            pieces.push(Piece::Field(
+                path.split('.')
+                    .filter(|k| !k.is_empty())
+                    .map(String::from)
+                    .collect(),
+            ));
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            pieces.push(Piece::Text(unescape(rest)));
+        }
+        Ok(Template(pieces))
+    }
+
+    /// One line for `value`. Missing fields and nulls render empty;
+    /// objects and lists render as compact JSON.
+    pub fn render(&self, value: &Value) -> String {
+        let mut out = String::new();
+        for piece in &self.0 {
+            match piece {
+                Piece::Text(text) => out.push_str(text),
+                Piece::Field(path) => {
+                    let field = path.iter().try_fold(value, |v, key| match v {
+                        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                        _ => v.get(key),
+                    });
+                    match field {
+                        None | Some(Value::Null) => {}
+                        Some(Value::String(s)) => out.push_str(s),
+                        Some(other) => out.push_str(&other.to_string()),
+                    }
+                }
+            }
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Print a serializable value as pretty JSON, or through the `--format`
+/// template when one is set (once per element when it is a list).
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    if TEMPLATE.get().is_some() {
+        let value = serde_json::to_value(value)?;
+        print!("{}", render_template(&value, "").unwrap_or_default());
+    } else {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    }
+    Ok(())
+}
+
+/// The rows at the JSON pointer `rows` through the `--format` template,
+/// when one is set.
+pub fn render_template(doc: &Value, rows: &str) -> Option<String> {
+    let template = TEMPLATE.get()?;
+    Some(
+        rows_at(doc, rows)
+            .into_iter()
+            .map(|row| template.render(row))
+            .collect(),
+    )
+}
+
+/// The elements of the list at the JSON pointer `rows`; a value that isn't
+/// a list is a single row.
+fn rows_at<'a>(doc: &'a Value, rows: &str) -> Vec<&'a Value> {
+    match doc.pointer(rows).unwrap_or(&Value::Null) {
+        Value::Array(items) => items.iter().collect(),
+        Value::Null => Vec::new(),
+        single => vec![single],
+    }
+}
+
+/// The global `--output` format. `Table` is each command's own
+/// human-readable rendering; the rest are structured and shared by every
+/// command that supports them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+    Ndjson,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "csv" => Ok(OutputFormat::Csv),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            other => anyhow::bail!(
+                "unknown output format '{}' (expected table, json, yaml, csv, ndjson)",
+                other
+            ),
+        }
+    }
+
+    pub fn is_structured(self) -> bool {
+        self != OutputFormat::Table
+    }
+}
+
+/// Print `doc` in a structured format; see [`render`].
+pub fn emit(doc: &Value, rows: &str, format: OutputFormat) {
+    print!("{}", render(doc, rows, format));
+}
+
+/// Render `doc` in a structured format. json and yaml render the whole
+/// document; csv and ndjson render one line per element of the list at the
+/// JSON pointer `rows` (such as "/entries"; "" is the document itself), and
+/// a document that isn't a list is a single row. `Table` has no shared
+/// rendering and falls back to JSON. With a `--format` template set, JSON
+/// is one template line per row instead.
+pub fn render(doc: &Value, rows: &str, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Table | OutputFormat::Json => {
+            render_template(doc, rows).unwrap_or_else(|| {
+                let mut out = serde_json::to_string_pretty(doc).unwrap_or_else(|_| doc.to_string());
+                out.push('\n');
+                out
+            })
+        }
+        OutputFormat::Yaml => {
+            let mut out = String::new();
+            yaml_block(&mut out, doc, 0);
+            out
+        }
+        OutputFormat::Ndjson => rows_at(doc, rows)
+            .iter()
+            .map(|row| format!("{}\n", row))
+            .collect(),
+        OutputFormat::Csv => render_csv(&rows_at(doc, rows)),
+    }
+}
+
+/// Write a value as indented YAML lines, each prefixed with `indent` spaces.
+fn yaml_block(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, v) in map {
+                out.push_str(&pad);
+                out.push_str(&yaml_string(key));
+                out.push(':');
+                yaml_child(out, v, indent + 2);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                out.push_str(&pad);
+                out.push('-');
+                if is_yaml_container(item) {
+                    // The item's first line goes beside its dash
+                    let mut inner = String::new();
+                    yaml_block(&mut inner, item, indent + 2);
+                    out.push(' ');
+                    out.push_str(&inner[indent + 2..]);
+                } else {
+                    yaml_child(out, item, indent + 2);
+                }
+            }
+        }
+        scalar => {
+            out.push_str(&pad);
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+}
+
+/// Finish a `key:` or `-` line: a scalar on the same line, or a nested
+/// block on the lines below.
+fn yaml_child(out: &mut String, value: &Value, indent: usize) {
+    if is_yaml_container(value) {
+        out.push('\n');
+        yaml_block(out, value, indent);
+    } else {
+        out.push(' ');
+        out.push_str(&yaml_scalar(value));
+        out.push('\n');
+    }
+}
+
+fn is_yaml_container(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// A string left plain when YAML would read it back as the same string,
+/// quoted otherwise. JSON's double-quoted form is also valid YAML.
+fn yaml_string(s: &str) -> String {
+    let plain = s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./ ".contains(c))
+        && !s.ends_with(' ')
+        && !matches!(
+            s.to_ascii_lowercase().as_str(),
+            "y" | "n" | "yes" | "no" | "on" | "off" | "true" | "false" | "null"
+        );
+    if plain {
+        s.to_string()
+    } else {
+        serde_json::to_string(s).unwrap_or_default()
+    }
+}
+
+/// One CSV line per row, with nested objects flattened into dotted columns
+/// ("capacity.used_pct") and lists kept as JSON text. Columns are the
+/// union of every row's, in first-seen order.
+fn render_csv(rows: &[&Value]) -> String {
+    let flat: Vec<Vec<(String, String)>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = Vec::new();
+            flatten(row, String::new(), &mut cells);
+            cells
+        })
+        .collect();
+    let mut columns: Vec<&str> = Vec::new();
+    for (column, _) in flat.iter().flatten() {
+        if !columns.contains(&column.as_str()) {
+            columns.push(column);
+        }
+    }
+
+    let mut out = columns
+        .iter()
+        .map(|c| csv_field(c))
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push('\n');
+    for cells in &flat {
+        let line: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                cells
+                    .iter()
+                    .find(|(c, _)| c == column)
+                    .map(|(_, v)| csv_field(v))
+                    .unwrap_or_default()
+            })
+            .collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn flatten(value: &Value, prefix: String, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(v, column, cells);
+            }
+        }
+        other => {
+            let column = if prefix.is_empty() {
+                "value".to_string()
+            } else {
+                prefix
+            };
+            let cell = match other {
+                Value::Null => String::new(),
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            };
+            cells.push((column, cell));
+        }
+    }
+}
+
 /// Print a value as JSON or use the provided human formatter
 pub fn print_value<F>(value: &Value, json_mode: bool, human_formatter: F)
 where
     F: FnOnce(&Value),
 {
     if json_mode {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
-        );
+        print!("{}", render(value, "", OutputFormat::Json));
     } else {
         human_formatter(value);
     }
@@ -71,6 +406,14 @@ pub fn print_table(items: &Value, columns: &[&str]) {
     }
 }
 
+/// Print the timestamp header that separates polls in `--watch --no-clear`
+/// mode, where the screen is never cleared and each poll is appended.
+pub fn print_poll_header() {
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S %Z");
+    let bold = console::Style::new().bold();
+    println!("{}", bold.apply_to(format!("── {} ──", now)));
+}
+
 /// Format a JSON value for human-readable display
 pub fn format_value(value: &Value) -> String {
     match value {
@@ -82,6 +425,76 @@ pub fn format_value(value: &Value) -> String {
     }
 }
 
+/// Quote a field for CSV output when it contains a delimiter, quote, or newline
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Escape text for inclusion in HTML element content or attribute values
+pub fn html_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Wrap an HTML body fragment in a standalone page with the shared inline stylesheet
+pub fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        html_escape(title),
+        HTML_STYLE,
+        body
+    )
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #1f2328; margin: 2em auto; max-width: 960px; padding: 0 1em; }
+h1 { font-size: 1.5em; border-bottom: 2px solid #d0d7de; padding-bottom: .3em; }
+h2 { font-size: 1.2em; margin-top: 1.8em; }
+table { border-collapse: collapse; width: 100%; margin: .5em 0 1em; }
+th, td { text-align: left; padding: 4px 8px; border-bottom: 1px solid #d0d7de; }
+th { background: #f6f8fa; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+.muted { color: #656d76; }
+.ok { color: #1a7f37; }
+.warning { color: #9a6700; }
+.critical { color: #cf222e; font-weight: 600; }
+.bar { background: #eaeef2; border-radius: 3px; height: 10px; width: 200px; display: inline-block; vertical-align: middle; }
+.bar > span { display: block; height: 100%; border-radius: 3px; background: #2da44e; }
+.bar > span.warning { background: #d4a72c; }
+.bar > span.critical { background: #cf222e; }
+section.cluster { border: 1px solid #d0d7de; border-radius: 6px; padding: .2em 1em .8em; margin: 1em 0; }
+";
+
+/// Render an inline capacity bar; the fill turns amber at 80% and red at 90%
+pub fn html_bar(pct: f64) -> String {
+    let clamped = pct.clamp(0.0, 100.0);
+    let class = if clamped >= 90.0 {
+        "critical"
+    } else if clamped >= 80.0 {
+        "warning"
+    } else {
+        "ok"
+    };
+    format!(
+        "<span class=\"bar\"><span class=\"{}\" style=\"width: {:.1}%\"></span></span>",
+        class, clamped
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,4 +541,110 @@ mod tests {
         });
         assert!(called);
     }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_output_format_parse() {
+        assert_eq!(OutputFormat::parse("yaml").unwrap(), OutputFormat::Yaml);
+        assert!(!OutputFormat::parse("table").unwrap().is_structured());
+        let err = OutputFormat::parse("xml").unwrap_err().to_string();
+        assert!(err.contains("expected table, json, yaml, csv, ndjson"));
+    }
+
+    #[test]
+    fn test_render_yaml() {
+        let doc = json!({
+            "cluster": {"cluster_name": "prod-1", "tags": []},
+            "nodes": [{"id": 1, "node_status": "online"}, {"id": 2, "node_status": "offline"}],
+            "paths": ["/home/", "yes", "10", "a: b", " padded"],
+            "note": null
+        });
+        assert_eq!(
+            render(&doc, "", OutputFormat::Yaml),
+            "cluster:\n  cluster_name: prod-1\n  tags: []\n\
+             nodes:\n  - id: 1\n    node_status: online\n  - id: 2\n    node_status: offline\n\
+             note: null\n\
+             paths:\n  - /home/\n  - \"yes\"\n  - \"10\"\n  - \"a: b\"\n  - \" padded\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_csv_flattens_rows() {
+        let doc = json!({
+            "entries": [
+                {"id": 1, "name": "a,b", "capacity": {"used_pct": 50.5}},
+                {"id": 2, "tags": ["x"], "capacity": {"used_pct": null}}
+            ]
+        });
+        assert_eq!(
+            render(&doc, "/entries", OutputFormat::Csv),
+            "capacity.used_pct,id,name,tags\n50.5,1,\"a,b\",\n,2,,\"[\"\"x\"\"]\"\n"
+        );
+        assert_eq!(
+            render(&json!([1, 2]), "", OutputFormat::Csv),
+            "value\n1\n2\n"
+        );
+    }
+
+    #[test]
+    fn test_render_ndjson_one_line_per_row() {
+        let doc = json!({"files": [{"name": "a"}, {"name": "b"}], "paging": {"next": ""}});
+        assert_eq!(
+            render(&doc, "/files", OutputFormat::Ndjson),
+            "{\"name\":\"a\"}\n{\"name\":\"b\"}\n"
+        );
+        assert_eq!(
+            render(&json!({"a": 1}), "", OutputFormat::Ndjson),
+            "{\"a\":1}\n"
+        );
+    }
+
+    #[test]
+    fn test_template_render() {
+        let template =
+            Template::parse("{{.name}}\\t{{ .capacity.used_pct }}% {{.nodes.0}}{{.missing}}")
+                .unwrap();
+        let value = json!({"name": "prod", "capacity": {"used_pct": 71.5}, "nodes": [3, 4]});
+        assert_eq!(template.render(&value), "prod\t71.5% 3\n");
+        assert_eq!(
+            Template::parse("{{.}}").unwrap().render(&json!({"a": [1]})),
+            "{\"a\":[1]}\n"
+        );
+
+        let err = Template::parse("{{.name").unwrap_err().to_string();
+        assert!(err.contains("unclosed"), "{}", err);
+        let err = Template::parse("{{name}}").unwrap_err().to_string();
+        assert!(err.contains("'{{name}}' is not a field"), "{}", err);
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(
+            html_escape("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_html_page_is_standalone() {
+        let page = html_page("A <b> title", "<p>hi</p>\n");
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>A &lt;b&gt; title</title>"));
+        assert!(page.contains("<style>"));
+        assert!(page.contains("<p>hi</p>"));
+        assert!(!page.contains("<link"));
+    }
+
+    #[test]
+    fn test_html_bar_thresholds() {
+        assert!(html_bar(50.0).contains("class=\"ok\""));
+        assert!(html_bar(85.0).contains("class=\"warning\""));
+        assert!(html_bar(150.0).contains("class=\"critical\" style=\"width: 100.0%\""));
+    }
 }
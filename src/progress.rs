@@ -0,0 +1,156 @@
+//! How long operations report progress: spinners on a terminal by default,
+//! or with `--progress json` one event per line on stderr for wrappers and
+//! CI to read, e.g.
+//!
+//! ```text
+//! {"phase":"download","items_done":3,"items_total":10,"bytes":1048576,"item":"/data/a.bin"}
+//! ```
+//!
+//! `items_total` and `bytes` are null when unknown or not meaningful for
+//! the phase. `item` names what just finished, when there is one. Warnings
+//! and logs still go to stderr too, so readers should skip lines that
+//! aren't JSON objects.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// The global `--progress` mode, set once at startup.
+static MODE: OnceLock<ProgressMode> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Spinners and status lines when stderr is a terminal
+    #[default]
+    Auto,
+    /// JSON events on stderr, and no spinners
+    Json,
+    /// Nothing
+    None,
+}
+
+impl ProgressMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "json" => Ok(Self::Json),
+            "none" => Ok(Self::None),
+            other => anyhow::bail!(
+                "unknown --progress '{}' (expected auto, json or none)",
+                other
+            ),
+        }
+    }
+}
+
+/// Report progress in `mode` for the rest of the process.
+pub fn set_mode(mode: ProgressMode) {
+    let _ = MODE.set(mode);
+}
+
+pub fn mode() -> ProgressMode {
+    MODE.get().copied().unwrap_or_default()
+}
+
+/// Whether commands may draw spinners and status lines (on a terminal)
+pub fn interactive() -> bool {
+    mode() == ProgressMode::Auto
+}
+
+/// One `--progress json` event.
+#[derive(Debug, Serialize)]
+struct Event<'a> {
+    phase: &'a str,
+    items_done: u64,
+    items_total: Option<u64>,
+    bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<&'a str>,
+}
+
+/// Progress through one phase of an operation, shared by the threads
+/// working on it. Events are only written with `--progress json`.
+#[derive(Debug)]
+pub struct Tracker {
+    phase: &'static str,
+    total: Option<u64>,
+    counts_bytes: bool,
+    done: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl Tracker {
+    /// Start a phase of `total` items (if known), reporting that none are
+    /// done yet. With `counts_bytes`, events carry the bytes handled so far.
+    pub fn start(phase: &'static str, total: Option<u64>, counts_bytes: bool) -> Self {
+        let tracker = Self {
+            phase,
+            total,
+            counts_bytes,
+            done: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        };
+        tracker.emit(0, 0, None);
+        tracker
+    }
+
+    /// Record one more item done, which moved `bytes`
+    pub fn advance(&self, bytes: u64, item: Option<&str>) {
+        let done = self.done.fetch_add(1, Ordering::SeqCst) + 1;
+        let bytes = self.bytes.fetch_add(bytes, Ordering::SeqCst) + bytes;
+        self.emit(done, bytes, item);
+    }
+
+    /// Report `done` items when the count is kept elsewhere
+    pub fn set(&self, done: u64) {
+        self.done.store(done, Ordering::SeqCst);
+        self.emit(done, self.bytes.load(Ordering::SeqCst), None);
+    }
+
+    fn emit(&self, done: u64, bytes: u64, item: Option<&str>) {
+        if mode() != ProgressMode::Json {
+            return;
+        }
+        let event = Event {
+            phase: self.phase,
+            items_done: done,
+            items_total: self.total,
+            bytes: self.counts_bytes.then_some(bytes),
+            item,
+        };
+        if let Ok(line) = serde_json::to_string(&event) {
+            // One write per line so events from parallel workers don't interleave
+            let _ = writeln!(std::io::stderr().lock(), "{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(ProgressMode::parse("json").unwrap(), ProgressMode::Json);
+        assert_eq!(ProgressMode::parse("none").unwrap(), ProgressMode::None);
+        assert!(ProgressMode::parse("bar").is_err());
+    }
+
+    #[test]
+    fn test_event_shape() {
+        let event = Event {
+            phase: "download",
+            items_done: 3,
+            items_total: Some(10),
+            bytes: None,
+            item: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"phase":"download","items_done":3,"items_total":10,"bytes":null}"#
+        );
+    }
+}
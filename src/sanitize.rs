@@ -0,0 +1,327 @@
+//! `--sanitize`: pseudonymize customer data in captured JSON before it is
+//! shared.
+//!
+//! Hostnames, usernames, and share names are found by the keys that hold
+//! them (`node_name`, `username`, `share_name`, ...) and then replaced
+//! wherever they appear as a whole word, so a node name inside a URL or an
+//! alert message changes with it. IP addresses are replaced wherever they
+//! appear. Pseudonyms are derived from a hash of the original, so the same
+//! name maps to the same pseudonym in every capture, and captures taken on
+//! different days can still be compared. They are not a secret: a guessable
+//! name can be recovered by hashing guesses.
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde_json::Value;
+
+/// Keys whose string values are hostnames or DNS names
+const HOST_KEYS: &[&str] = &[
+    "cluster_name",
+    "node_name",
+    "hostname",
+    "host",
+    "host_name",
+    "fqdn",
+    "dns_name",
+    "domain",
+    "domain_netbios",
+];
+
+/// Keys whose string values are user or account names
+const USER_KEYS: &[&str] = &[
+    "username",
+    "user_name",
+    "user",
+    "login",
+    "account",
+    "owner_name",
+    "created_by",
+    "bind_username",
+    "sam_account_name",
+];
+
+/// Keys whose string values are share or export names
+const SHARE_KEYS: &[&str] = &["share_name", "share", "export_path", "export_name"];
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Host,
+    User,
+    Share,
+}
+
+impl Kind {
+    fn of_key(key: &str) -> Option<Self> {
+        let key = key.to_ascii_lowercase();
+        if HOST_KEYS.contains(&key.as_str()) {
+            Some(Self::Host)
+        } else if USER_KEYS.contains(&key.as_str()) {
+            Some(Self::User)
+        } else if SHARE_KEYS.contains(&key.as_str()) {
+            Some(Self::Share)
+        } else {
+            None
+        }
+    }
+
+    fn pseudonym(self, real: &str) -> String {
+        let (prefix, hash) = match self {
+            Self::Host => ("host", fnv64("host", real)),
+            Self::User => ("user", fnv64("user", real)),
+            Self::Share => ("share", fnv64("share", real)),
+        };
+        let name = format!("{}-{:06x}", prefix, hash & 0xff_ffff);
+        // Export paths stay paths
+        if real.starts_with('/') {
+            format!("/{}", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// Sanitize every document in `values` in place. Names found in any of them
+/// are replaced in all of them, so a set of responses captured together
+/// stays consistent.
+pub fn sanitize_all(values: &mut [Value]) {
+    let mut names = BTreeMap::new();
+    for value in values.iter() {
+        collect(value, None, &mut names);
+    }
+    // Longest first, so a node name is replaced before the cluster name it
+    // starts with
+    let mut names: Vec<(String, String)> = names.into_iter().collect();
+    names.sort_by_key(|(real, _)| std::cmp::Reverse(real.len()));
+    for value in values.iter_mut() {
+        rewrite(value, &names);
+    }
+}
+
+/// Sanitize a single document in place
+pub fn sanitize(value: &mut Value) {
+    sanitize_all(std::slice::from_mut(value));
+}
+
+fn collect(value: &Value, kind: Option<Kind>, names: &mut BTreeMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(kind) = kind {
+                if !s.is_empty() && s.parse::<IpAddr>().is_err() {
+                    names.insert(s.clone(), kind.pseudonym(s));
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect(v, kind, names)),
+        Value::Object(map) => {
+            for (key, v) in map {
+                collect(v, Kind::of_key(key), names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rewrite(value: &mut Value, names: &[(String, String)]) {
+    match value {
+        Value::String(s) => {
+            let mut out = replace_ips(s);
+            for (real, pseudonym) in names {
+                if out.contains(real.as_str()) {
+                    out = replace_word(&out, real, pseudonym);
+                }
+            }
+            *s = out;
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| rewrite(v, names)),
+        Value::Object(map) => map.values_mut().for_each(|v| rewrite(v, names)),
+        _ => {}
+    }
+}
+
+/// Whether `c` continues a name, so a match next to it is only part of a
+/// longer word
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Replace occurrences of `word` in `s` that are not part of a longer word
+fn replace_word(s: &str, word: &str, replacement: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(at) = rest.find(word) {
+        let before = if at > 0 {
+            rest[..at].chars().last()
+        } else {
+            out.chars().last()
+        };
+        let after = rest[at + word.len()..].chars().next();
+        let bounded = !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char);
+        out.push_str(&rest[..at]);
+        out.push_str(if bounded { replacement } else { word });
+        rest = &rest[at + word.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Replace every IPv4 and IPv6 address in `s` (including `ip:port` and
+/// `ip/prefix` forms). Loopback and unspecified addresses identify nothing
+/// and are kept.
+fn replace_ips(s: &str) -> String {
+    let is_ip_char = |c: char| c.is_ascii_hexdigit() || c == '.' || c == ':';
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find(is_ip_char) {
+        out.push_str(&rest[..start]);
+        let run_len = rest[start..]
+            .find(|c: char| !is_ip_char(c))
+            .unwrap_or(rest.len() - start);
+        let run = &rest[start..start + run_len];
+        // A run inside a word (e.g. the "ad" of "load") is not an address
+        let after = rest[start + run_len..].chars().next();
+        let mid_word = out.chars().last().is_some_and(|c| c.is_alphanumeric())
+            || after.is_some_and(|c| c.is_alphanumeric());
+        match (!mid_word).then(|| ip_in_run(run)).flatten() {
+            Some((ip, len)) => {
+                out.push_str(&pseudonym_ip(ip));
+                out.push_str(&run[len..]);
+            }
+            None => out.push_str(run),
+        }
+        rest = &rest[start + run_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The address at the start of `run` and its length: the whole run, or the
+/// part before an IPv4 `:port`
+fn ip_in_run(run: &str) -> Option<(IpAddr, usize)> {
+    if let Ok(ip) = run.parse::<IpAddr>() {
+        return Some((ip, run.len()));
+    }
+    let (host, _) = run.rsplit_once(':')?;
+    let ip = host.parse::<Ipv4Addr>().ok()?;
+    Some((IpAddr::V4(ip), host.len()))
+}
+
+fn pseudonym_ip(ip: IpAddr) -> String {
+    if ip.is_loopback() || ip.is_unspecified() {
+        return ip.to_string();
+    }
+    let hash = fnv64("ip", &ip.to_string());
+    match ip {
+        IpAddr::V4(_) => {
+            let [_, _, _, _, _, a, b, c] = hash.to_be_bytes();
+            Ipv4Addr::new(10, a, b, c).to_string()
+        }
+        IpAddr::V6(_) => {
+            let [_, _, _, _, a, b, c, d] = hash.to_be_bytes();
+            let (x, y) = (u16::from_be_bytes([a, b]), u16::from_be_bytes([c, d]));
+            Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, x, y).to_string()
+        }
+    }
+}
+
+/// 64-bit FNV-1a of `kind` and `value`, so a user and a host with the same
+/// name get unrelated pseudonyms
+fn fnv64(kind: &str, value: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in kind.bytes().chain([0]).chain(value.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_replace_ips() {
+        let v4 = pseudonym_ip("192.168.1.20".parse().unwrap());
+        assert!(v4.starts_with("10."), "got: {}", v4);
+        assert_eq!(replace_ips("192.168.1.20"), v4);
+        assert_eq!(
+            replace_ips("nfs://192.168.1.20:2049/x"),
+            format!("nfs://{}:2049/x", v4)
+        );
+        assert_eq!(
+            replace_ips("from 192.168.1.20/24"),
+            format!("from {}/24", v4)
+        );
+        assert_eq!(replace_ips("127.0.0.1 and ::1"), "127.0.0.1 and ::1");
+        assert!(replace_ips("fe80::1c2a:3bff:fe4d:5e6f").starts_with("fd00::"));
+        // Not addresses
+        for s in [
+            "7.7.2",
+            "00:22:48:b3:bf:e0",
+            "21:57:48.633837495Z",
+            "deadbeef",
+            "load 1.2.3.4x",
+        ] {
+            assert_eq!(replace_ips(s), s);
+        }
+    }
+
+    #[test]
+    fn test_replace_word() {
+        assert_eq!(
+            replace_word("admin logged in", "admin", "user-1"),
+            "user-1 logged in"
+        );
+        assert_eq!(
+            replace_word("/home/administrator", "admin", "user-1"),
+            "/home/administrator"
+        );
+        assert_eq!(
+            replace_word("iss-sg-1 and iss-sg", "iss-sg", "host-1"),
+            "iss-sg-1 and host-1"
+        );
+        assert_eq!(
+            replace_word("https://iss-sg.corp:8000", "iss-sg", "host-1"),
+            "https://host-1.corp:8000"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_stable_pseudonyms() {
+        let mut values = vec![
+            json!({"cluster_name": "iss-sg", "nodes": [{"node_name": "iss-sg-1", "ip": "10.20.30.40"}]}),
+            json!({
+                "message": "node iss-sg-1 of iss-sg unreachable from 10.20.30.40",
+                "shares": [{"share_name": "finance", "path": "/finance"}],
+                "username": "jdoe",
+                "owner": "jdoe",
+                "name": "report.txt",
+            }),
+        ];
+        sanitize_all(&mut values);
+        let host = Kind::Host.pseudonym("iss-sg");
+        let node = Kind::Host.pseudonym("iss-sg-1");
+        let ip = pseudonym_ip("10.20.30.40".parse().unwrap());
+        let share = Kind::Share.pseudonym("finance");
+        let user = Kind::User.pseudonym("jdoe");
+
+        assert_eq!(values[0]["cluster_name"], host);
+        assert_eq!(values[0]["nodes"][0]["node_name"], node);
+        assert_eq!(values[0]["nodes"][0]["ip"], ip);
+        assert_eq!(
+            values[1]["message"],
+            format!("node {} of {} unreachable from {}", node, host, ip)
+        );
+        assert_eq!(values[1]["shares"][0]["share_name"], share);
+        assert_eq!(values[1]["username"], user);
+        assert_eq!(values[1]["owner"], user);
+        assert_eq!(values[1]["name"], "report.txt");
+
+        // Same input, same pseudonyms
+        let mut again = json!({"cluster_name": "iss-sg"});
+        sanitize(&mut again);
+        assert_eq!(again["cluster_name"], host);
+        assert_ne!(Kind::Host.pseudonym("jdoe"), user);
+    }
+}
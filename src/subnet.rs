@@ -0,0 +1,143 @@
+//! Labels for client addresses by subnet, from the config's `[subnets]` table:
+//!
+//! ```toml
+//! [subnets]
+//! "10.20.0.0/16" = "render-farm"
+//! "10.20.5.0/24" = "render-leads"
+//! ```
+//!
+//! The most specific matching subnet wins, so nested ranges can carve out
+//! smaller groups. IPv4 and IPv6 ranges can be mixed; a bare address is a
+//! single-host range.
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use anyhow::Result;
+
+use crate::error::QontrolError;
+
+/// Connections from addresses outside every configured subnet.
+pub const UNLABELED: &str = "unlabeled";
+
+/// Subnet labels, most specific first.
+#[derive(Debug, Clone, Default)]
+pub struct SubnetLabels {
+    subnets: Vec<(Subnet, String)>,
+}
+
+impl SubnetLabels {
+    /// Parse a `[subnets]` table of CIDR range to label.
+    pub fn new(table: &BTreeMap<String, String>) -> Result<Self> {
+        let mut subnets = table
+            .iter()
+            .map(|(cidr, label)| {
+                let subnet = Subnet::parse(cidr).ok_or_else(|| {
+                    QontrolError::ConfigError(format!(
+                        "invalid subnet '{}' in [subnets]: expected a CIDR range like 10.20.0.0/16",
+                        cidr
+                    ))
+                })?;
+                Ok((subnet, label.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        subnets.sort_by_key(|(subnet, _)| std::cmp::Reverse(subnet.prefix));
+        Ok(SubnetLabels { subnets })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subnets.is_empty()
+    }
+
+    /// The label of the most specific subnet containing `address`. Addresses
+    /// that don't parse (hostnames, empty strings) have no label.
+    pub fn label(&self, address: &str) -> Option<&str> {
+        let address: IpAddr = address.trim().parse().ok()?;
+        self.subnets
+            .iter()
+            .find(|(subnet, _)| subnet.contains(address))
+            .map(|(_, label)| label.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Subnet {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Subnet {
+    fn parse(cidr: &str) -> Option<Self> {
+        let (address, prefix) = match cidr.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u8>().ok()?)),
+            None => (cidr.trim(), None),
+        };
+        let network: IpAddr = address.parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Subnet { network, prefix })
+    }
+
+    fn contains(&self, address: IpAddr) -> bool {
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(entries: &[(&str, &str)]) -> SubnetLabels {
+        let table = entries
+            .iter()
+            .map(|(cidr, label)| (cidr.to_string(), label.to_string()))
+            .collect();
+        SubnetLabels::new(&table).unwrap()
+    }
+
+    #[test]
+    fn test_most_specific_subnet_wins() {
+        let labels = labels(&[
+            ("10.20.0.0/16", "render-farm"),
+            ("10.20.5.0/24", "render-leads"),
+            ("0.0.0.0/0", "anywhere"),
+        ]);
+        assert_eq!(labels.label("10.20.1.9"), Some("render-farm"));
+        assert_eq!(labels.label("10.20.5.9"), Some("render-leads"));
+        assert_eq!(labels.label("192.168.1.1"), Some("anywhere"));
+        assert_eq!(labels.label("fd00::1"), None);
+        assert_eq!(labels.label("not-an-ip"), None);
+    }
+
+    #[test]
+    fn test_ipv6_and_single_hosts() {
+        let labels = labels(&[("fd00:abcd::/32", "lab"), ("10.0.0.7", "backup-host")]);
+        assert_eq!(labels.label("fd00:abcd:1::5"), Some("lab"));
+        assert_eq!(labels.label("fd00:abce::5"), None);
+        assert_eq!(labels.label("10.0.0.7"), Some("backup-host"));
+        assert_eq!(labels.label("10.0.0.8"), None);
+    }
+
+    #[test]
+    fn test_invalid_subnet_rejected() {
+        for cidr in ["10.0.0.0/33", "10.0.0/8", "render-farm", "fd00::/129"] {
+            let table = BTreeMap::from([(cidr.to_string(), "x".to_string())]);
+            let err = SubnetLabels::new(&table).unwrap_err().to_string();
+            assert!(
+                err.contains(&format!("invalid subnet '{}'", cidr)),
+                "{}",
+                err
+            );
+        }
+    }
+}
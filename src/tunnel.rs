@@ -0,0 +1,233 @@
+//! SSH local forwards for profiles with `ssh_tunnel = "user@bastion"`.
+//!
+//! Each client opens its own forward by running the system `ssh` with `-N
+//! -L`, so `~/.ssh/config` (ProxyJump, identities, ports) applies, and
+//! closes it when the client is dropped. ssh runs in batch mode: it
+//! authenticates with a key or agent and never prompts, since several
+//! clusters may be connecting at once.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+/// How often to check whether the forward is accepting connections
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many of ssh's last stderr lines to keep for the error when it exits
+const STDERR_TAIL_LINES: usize = 20;
+
+/// A running `ssh -L` forward from a local port to a cluster.
+#[derive(Debug)]
+pub struct SshTunnel {
+    child: Child,
+    local_port: u16,
+    /// Reads ssh's stderr for as long as it runs, so warnings from a
+    /// long-lived tunnel can't fill the pipe and block it, and returns the
+    /// last lines
+    stderr: Option<JoinHandle<String>>,
+}
+
+impl SshTunnel {
+    /// Forward a free local port through `destination` (anything ssh takes,
+    /// e.g. `user@bastion` or `ssh://user@bastion:2222`) to `host:port` as
+    /// the bastion sees it, waiting up to `timeout` for the forward to come
+    /// up.
+    pub fn open(destination: &str, host: &str, port: u16, timeout: Duration) -> Result<Self> {
+        Self::open_with("ssh", destination, host, port, timeout)
+    }
+
+    fn open_with(
+        program: &str,
+        destination: &str,
+        host: &str,
+        port: u16,
+        timeout: Duration,
+    ) -> Result<Self> {
+        if destination.starts_with('-') {
+            anyhow::bail!(
+                "invalid ssh_tunnel '{}': must be a host, not an ssh option",
+                destination
+            );
+        }
+        let local_port = free_port()?;
+        let mut child = Command::new(program)
+            .args(ssh_args(destination, local_port, host, port))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run {} for ssh_tunnel", program))?;
+        let stderr = child.stderr.take().map(drain_stderr);
+        let mut tunnel = SshTunnel {
+            child,
+            local_port,
+            stderr,
+        };
+        tunnel.wait_ready(destination, timeout)?;
+        tracing::debug!(%destination, %host, port, local_port, "ssh tunnel open");
+        Ok(tunnel)
+    }
+
+    /// The local address the forward listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, self.local_port))
+    }
+
+    fn wait_ready(&mut self, destination: &str, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                let stderr = self
+                    .stderr
+                    .take()
+                    .and_then(|h| h.join().ok())
+                    .unwrap_or_default();
+                anyhow::bail!(
+                    "ssh tunnel via {} exited ({}): {}",
+                    destination,
+                    status,
+                    stderr.trim()
+                );
+            }
+            if TcpStream::connect_timeout(&self.local_addr(), POLL_INTERVAL).is_ok() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "ssh tunnel via {} did not come up within {}s",
+                    destination,
+                    timeout.as_secs()
+                );
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+/// Read ssh's stderr until it exits, logging each line and keeping the last
+/// few for error messages.
+fn drain_stderr(pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+        for line in BufReader::new(pipe).lines() {
+            let Ok(line) = line else { break };
+            tracing::debug!(%line, "ssh");
+            if tail.len() == STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+        Vec::from(tail).join("\n")
+    })
+}
+
+/// Arguments for an `ssh` that only forwards `127.0.0.1:local_port` to
+/// `host:port`, and exits if the forward can't be set up. `--` ends the
+/// options so the destination is never parsed as one.
+fn ssh_args(destination: &str, local_port: u16, host: &str, port: u16) -> Vec<String> {
+    let host = if host.contains(':') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    };
+    [
+        "-N",
+        "-o",
+        "ExitOnForwardFailure=yes",
+        "-o",
+        "BatchMode=yes",
+        "-o",
+        "ServerAliveInterval=30",
+        "-L",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .chain([
+        format!("127.0.0.1:{}:{}:{}", local_port, host, port),
+        "--".to_string(),
+        destination.to_string(),
+    ])
+    .collect()
+}
+
+/// A local port nothing is listening on right now
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .context("failed to find a free local port for ssh_tunnel")?;
+    Ok(listener.local_addr()?.port())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssh_args() {
+        let args = ssh_args("ops@bastion", 40123, "qumulo.lab", 8000);
+        assert_eq!(args[0], "-N");
+        assert!(args.contains(&"ExitOnForwardFailure=yes".to_string()));
+        assert!(args.contains(&"BatchMode=yes".to_string()));
+        assert_eq!(
+            args[args.len() - 3..],
+            ["127.0.0.1:40123:qumulo.lab:8000", "--", "ops@bastion"]
+        );
+        let args = ssh_args("bastion", 40123, "fd00::5", 8000);
+        assert_eq!(args[args.len() - 3], "127.0.0.1:40123:[fd00::5]:8000");
+    }
+
+    #[test]
+    fn test_open_reports_ssh_exit() {
+        let err = SshTunnel::open_with(
+            "false",
+            "ops@bastion",
+            "qumulo.lab",
+            8000,
+            Duration::from_secs(5),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("ssh tunnel via ops@bastion exited"), "{}", err);
+    }
+
+    #[test]
+    fn test_open_rejects_option_destination() {
+        let err = SshTunnel::open_with(
+            "true",
+            "-oProxyCommand=touch /tmp/pwned",
+            "qumulo.lab",
+            8000,
+            Duration::from_secs(5),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("must be a host, not an ssh option"), "{}", err);
+    }
+
+    #[test]
+    fn test_open_reports_ssh_stderr() {
+        let err = SshTunnel::open_with(
+            "sh",
+            "ops@bastion",
+            "qumulo.lab",
+            8000,
+            Duration::from_secs(5),
+        )
+        .unwrap_err()
+        .to_string();
+        // sh rejects the -N option on stderr; the drain thread hands it back
+        assert!(err.contains("exited"), "{}", err);
+        assert!(err.contains("-N"), "{}", err);
+    }
+}
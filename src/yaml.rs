@@ -0,0 +1,326 @@
+//! A reader for the small subset of YAML that inventory files need:
+//!
+//! ```yaml
+//! defaults:
+//!   insecure: true
+//! clusters:
+//!   - name: prod
+//!     host: prod.example.com
+//!   - name: dr
+//!     host: dr.example.com
+//!     port: 8443          # comments are fine
+//!     token: "access-v1:..."
+//! ```
+//!
+//! That is block mappings and lists nested by indentation, with plain,
+//! single- or double-quoted scalars. Anchors, tags, flow collections (other
+//! than an empty `[]` or `{}`), multi-line strings and multiple documents
+//! are not supported and fail to parse rather than being misread.
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+/// One meaningful line: its indentation, its content with any comment
+/// removed, and its 1-based line number for errors
+struct Line {
+    indent: usize,
+    text: String,
+    number: usize,
+}
+
+/// Parse a YAML document into the JSON value it describes.
+pub fn parse(text: &str) -> Result<Value> {
+    let mut lines = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let number = i + 1;
+        let content = strip_comment(raw).trim_end();
+        let trimmed = content.trim_start();
+        if trimmed.is_empty() || (lines.is_empty() && trimmed == "---") {
+            continue;
+        }
+        let indent = content.len() - trimmed.len();
+        if content[..indent].contains('\t') {
+            anyhow::bail!("line {}: tabs can't be used for indentation", number);
+        }
+        lines.push(Line {
+            indent,
+            text: trimmed.to_string(),
+            number,
+        });
+    }
+    let Some(first) = lines.first() else {
+        return Ok(Value::Null);
+    };
+    let indent = first.indent;
+    let mut pos = 0;
+    let value = parse_block(&mut lines, &mut pos, indent)?;
+    if let Some(line) = lines.get(pos) {
+        anyhow::bail!("line {}: unexpected indentation", line.number);
+    }
+    Ok(value)
+}
+
+/// A mapping or list whose entries start at `indent`
+fn parse_block(lines: &mut [Line], pos: &mut usize, indent: usize) -> Result<Value> {
+    if is_item(&lines[*pos].text) {
+        parse_list(lines, pos, indent)
+    } else {
+        parse_mapping(lines, pos, indent)
+    }
+}
+
+fn parse_mapping(lines: &mut [Line], pos: &mut usize, indent: usize) -> Result<Value> {
+    let mut map = Map::new();
+    while let Some(line) = lines.get(*pos) {
+        if line.indent < indent || (line.indent == indent && is_item(&line.text)) {
+            break;
+        }
+        if line.indent > indent {
+            anyhow::bail!("line {}: unexpected indentation", line.number);
+        }
+        let number = line.number;
+        let Some((key, rest)) = split_key(&line.text) else {
+            anyhow::bail!("line {}: expected 'key: value'", number);
+        };
+        let key = match scalar(key, number)? {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        let rest = rest.to_string();
+        *pos += 1;
+        let value = if !rest.is_empty() {
+            scalar(&rest, number)?
+        } else {
+            match lines.get(*pos) {
+                // A list may sit at the same indentation as its key
+                Some(next)
+                    if next.indent > indent || (next.indent == indent && is_item(&next.text)) =>
+                {
+                    let child = next.indent;
+                    parse_block(lines, pos, child)?
+                }
+                _ => Value::Null,
+            }
+        };
+        if map.insert(key.clone(), value).is_some() {
+            anyhow::bail!("line {}: duplicate key '{}'", number, key);
+        }
+    }
+    Ok(Value::Object(map))
+}
+
+fn parse_list(lines: &mut [Line], pos: &mut usize, indent: usize) -> Result<Value> {
+    let mut items = Vec::new();
+    while let Some(line) = lines.get(*pos) {
+        if line.indent != indent || !is_item(&line.text) {
+            if line.indent > indent {
+                anyhow::bail!("line {}: unexpected indentation", line.number);
+            }
+            break;
+        }
+        let number = line.number;
+        let rest = line.text[1..].trim_start();
+        let offset = line.text.len() - rest.len();
+        if rest.is_empty() {
+            *pos += 1;
+            items.push(match lines.get(*pos) {
+                Some(next) if next.indent > indent => {
+                    let child = next.indent;
+                    parse_block(lines, pos, child)?
+                }
+                _ => Value::Null,
+            });
+        } else if is_item(rest) || split_key(rest).is_some() {
+            // `- key: value` starts a mapping (or `- - x` a list) indented
+            // to where its first entry begins
+            let child = indent + offset;
+            let rest = rest.to_string();
+            let line = &mut lines[*pos];
+            line.indent = child;
+            line.text = rest;
+            items.push(parse_block(lines, pos, child)?);
+        } else {
+            items.push(scalar(rest, number)?);
+            *pos += 1;
+        }
+    }
+    Ok(Value::Array(items))
+}
+
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Split `key: value` (or `key:`) at the first colon that ends the key,
+/// skipping a quoted key
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    let start = match text.chars().next()? {
+        quote @ ('"' | '\'') => text[1..].find(quote)? + 2,
+        _ => 0,
+    };
+    let mut from = start;
+    loop {
+        let colon = from + text[from..].find(':')?;
+        let after = &text[colon + 1..];
+        if after.is_empty() || after.starts_with(' ') {
+            return Some((text[..colon].trim_end(), after.trim()));
+        }
+        from = colon + 1;
+    }
+}
+
+/// Remove a `#` comment that starts the line or follows whitespace, outside
+/// quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && prev.is_whitespace() => return &line[..i],
+            None => {}
+        }
+        prev = c;
+    }
+    line
+}
+
+fn scalar(text: &str, number: usize) -> Result<Value> {
+    match text {
+        "[]" => return Ok(Value::Array(Vec::new())),
+        "{}" => return Ok(Value::Object(Map::new())),
+        "~" | "null" | "Null" | "NULL" => return Ok(Value::Null),
+        "true" | "True" | "TRUE" => return Ok(Value::Bool(true)),
+        "false" | "False" | "FALSE" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    if let Some(body) = text.strip_prefix('"') {
+        return double_quoted(body, number).map(Value::String);
+    }
+    if let Some(body) = text.strip_prefix('\'') {
+        let Some(body) = body.strip_suffix('\'') else {
+            anyhow::bail!("line {}: unterminated quoted string", number);
+        };
+        return Ok(Value::String(body.replace("''", "'")));
+    }
+    if text.starts_with(['[', '{', '&', '*', '!', '|', '>']) {
+        anyhow::bail!(
+            "line {}: unsupported YAML '{}' (use plain or quoted values)",
+            number,
+            text
+        );
+    }
+    if let Ok(n) = text.parse::<i64>() {
+        return Ok(Value::from(n));
+    }
+    if let Some(n) = text
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+    {
+        return Ok(Value::Number(n));
+    }
+    Ok(Value::String(text.to_string()))
+}
+
+/// The string in a double-quoted scalar, given the text after the opening
+/// quote
+fn double_quoted(body: &str, number: usize) -> Result<String> {
+    let mut out = String::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                if !chars.as_str().is_empty() {
+                    anyhow::bail!("line {}: unexpected text after quoted string", number);
+                }
+                return Ok(out);
+            }
+            '\\' => out.push(match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('"') => '"',
+                Some('\\') => '\\',
+                Some('/') => '/',
+                Some(other) => anyhow::bail!("line {}: unsupported escape '\\{}'", number, other),
+                None => break,
+            }),
+            c => out.push(c),
+        }
+    }
+    anyhow::bail!("line {}: unterminated quoted string", number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_inventory() {
+        let doc = r#"
+# fleet
+defaults:
+  insecure: true
+clusters:
+  - name: prod
+    host: prod.example.com   # primary
+  - name: 'dr # 2'
+    host: dr.example.com
+    port: 8443
+    token: "access-v1:abc\"def"
+"#;
+        assert_eq!(
+            parse(doc).unwrap(),
+            json!({
+                "defaults": {"insecure": true},
+                "clusters": [
+                    {"name": "prod", "host": "prod.example.com"},
+                    {"name": "dr # 2", "host": "dr.example.com", "port": 8443,
+                     "token": "access-v1:abc\"def"},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_list_at_key_indent() {
+        let doc = "clusters:\n- name: a\n  host: h1\n- name: b\n  host: h2\nempty: []\n";
+        assert_eq!(
+            parse(doc).unwrap(),
+            json!({
+                "clusters": [{"name": "a", "host": "h1"}, {"name": "b", "host": "h2"}],
+                "empty": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_scalars() {
+        let doc = "a: 10.0.0.1\nb: 8000\nc: ~\nd: access-v1:tok\ne: 'it''s'\nf:\n";
+        assert_eq!(
+            parse(doc).unwrap(),
+            json!({"a": "10.0.0.1", "b": 8000, "c": null, "d": "access-v1:tok",
+                   "e": "it's", "f": null})
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported() {
+        for (doc, err) in [
+            ("a: [1, 2]\n", "unsupported YAML"),
+            ("a: &x 1\n", "unsupported YAML"),
+            ("a: |\n  text\n", "unsupported YAML"),
+            ("a: \"open\n", "unterminated"),
+            ("a: 1\na: 2\n", "duplicate key 'a'"),
+            ("a: 1\n  b: 2\n", "line 2: unexpected indentation"),
+            ("a:\n\t- 1\n", "tabs"),
+            ("just text\n", "expected 'key: value'"),
+        ] {
+            let e = parse(doc).unwrap_err().to_string();
+            assert!(e.contains(err), "{:?}: {}", doc, e);
+        }
+    }
+}
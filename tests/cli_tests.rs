@@ -205,3 +205,37 @@ fn test_help_shows_fs_command() {
         .success()
         .stdout(predicate::str::contains("fs"));
 }
+
+#[test]
+fn test_completions_bash_script() {
+    Command::cargo_bin("qontrol")
+        .unwrap()
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "complete -o default -F _qontrol qontrol",
+        ))
+        .stdout(predicate::str::contains("\" fleet status\""));
+
+    Command::cargo_bin("qontrol")
+        .unwrap()
+        .args(["completions", "fish"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unsupported shell 'fish'"));
+}
+
+#[test]
+fn test_init_needs_a_terminal() {
+    let temp = std::env::temp_dir().join("qontrol-test-init");
+    Command::cargo_bin("qontrol")
+        .unwrap()
+        .env("HOME", &temp)
+        .env("XDG_CONFIG_HOME", temp.join("config"))
+        .arg("init")
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`qontrol init` is interactive"));
+}
@@ -13,6 +13,14 @@
 // ## How to refresh fixtures
 // Run the relevant API calls against a live cluster and save the JSON responses
 // to tests/fixtures/, overwriting the existing files.
+//
+// `qontrol --profile <p> dev record-fixtures --set status|cdf|fs` does this
+// for a whole set, with UUIDs and hostnames scrubbed: status fixtures land in
+// tests/fixtures/status/<p>/ (see `mount_full_status_fixtures`), cdf and fs
+// fixtures in tests/fixtures/<set>/<p>/ (see `mount_fixture_from`).
+
+// Each integration test binary uses a different subset of the harness.
+#![allow(dead_code)]
 
 use std::path::{Path, PathBuf};
 
@@ -38,7 +46,36 @@ const FIXTURE_ROUTES: &[(&str, &str, &str)] = &[
     ("network_status", "GET", "/v3/network/status"),
     ("fs_entries_root", "GET", "/v1/files/%2F/entries/"),
     ("fs_entries_home", "GET", "/v1/files/%2Fhome/entries/"),
+    ("fs_entries_data", "GET", "/v1/files/%2Fdata/entries/"),
+    ("fs_entries_media", "GET", "/v1/files/%2Fmedia/entries/"),
+    (
+        "fs_entries_data_releases",
+        "GET",
+        "/v1/files/%2Fdata%2Freleases/entries/",
+    ),
+    (
+        "fs_entries_projects",
+        "GET",
+        "/v1/files/%2Fprojects/entries/",
+    ),
+    ("quotas_list", "GET", "/v1/files/quotas/"),
     ("fs_attributes_root", "GET", "/v1/files/%2F/info/attributes"),
+    (
+        "fs_attributes_data",
+        "GET",
+        "/v1/files/%2Fdata/info/attributes",
+    ),
+    (
+        "fs_user_metadata_home",
+        "GET",
+        "/v1/files/%2Fhome/user-metadata/",
+    ),
+    (
+        "fs_user_metadata_value",
+        "GET",
+        "/v1/files/%2Fhome/user-metadata/GENERIC/project",
+    ),
+    ("fs_streams_home", "GET", "/v1/files/%2Fhome/streams/"),
     (
         "fs_attributes_home",
         "GET",
@@ -57,7 +94,15 @@ const FIXTURE_ROUTES: &[(&str, &str, &str)] = &[
         "/v1/snapshots/capacity-used-per-snapshot/",
     ),
     ("snapshots_policies", "GET", "/v2/snapshots/policies/"),
+    ("snapshot_policy_single", "GET", "/v2/snapshots/policies/1"),
+    ("snapshot_policy_create", "POST", "/v2/snapshots/policies/"),
+    (
+        "snapshot_policy_update",
+        "PATCH",
+        "/v2/snapshots/policies/1",
+    ),
     ("snapshot_single", "GET", "/v2/snapshots/1"),
+    ("snapshot_create", "POST", "/v2/snapshots/"),
     ("snapshot_status_single", "GET", "/v2/snapshots/status/1"),
     (
         "snapshots_total_capacity",
@@ -268,12 +313,23 @@ insecure = true
             .await;
     }
 
+    /// Mount a plain-text response (e.g. file or symlink data).
+    pub async fn mount_text(&self, http_method: &str, api_path: &str, body: &str) {
+        Mock::given(method(http_method))
+            .and(path(api_path))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body.to_string(), "text/plain"))
+            .mount(&self.mock_server)
+            .await;
+    }
+
     /// Build an assert_cmd Command pre-configured with the test environment.
     #[allow(deprecated)]
     pub fn command(&self) -> Command {
         let mut cmd = Command::cargo_bin("qontrol").expect("binary not found");
         let port = self.mock_server.address().port();
         cmd.env("QONTROL_CONFIG_DIR", self.temp_dir.path())
+            .env("QONTROL_CACHE_DIR", self.temp_dir.path().join("cache"))
+            .env("QONTROL_DATA_DIR", self.temp_dir.path().join("data"))
             .env("QONTROL_BASE_URL", format!("http://127.0.0.1:{}", port));
         cmd
     }
@@ -320,6 +376,15 @@ base_url = "http://127.0.0.1:{port}"
         Self { servers, temp_dir }
     }
 
+    /// Append raw TOML to the generated config (e.g. a `[profiles.<name>.collect]` table).
+    pub fn append_config(&self, toml: &str) {
+        let path = self.temp_dir.path().join("config.toml");
+        let mut contents = std::fs::read_to_string(&path).expect("failed to read config");
+        contents.push('\n');
+        contents.push_str(toml);
+        std::fs::write(&path, contents).expect("failed to write config");
+    }
+
     /// Mount a fixture on a specific profile's mock server.
     pub async fn mount_fixture(&self, profile: &str, name: &str) {
         let (_, server) = self
@@ -400,6 +465,7 @@ base_url = "http://127.0.0.1:{port}"
     }
 
     /// Mount a status fixture with a required query parameter.
+    #[allow(clippy::too_many_arguments)]
     pub async fn mount_status_fixture_with_query(
         &self,
         profile: &str,
@@ -663,7 +729,8 @@ base_url = "http://127.0.0.1:{port}"
     pub fn command(&self) -> Command {
         let mut cmd = Command::cargo_bin("qontrol").expect("binary not found");
         cmd.env("QONTROL_CONFIG_DIR", self.temp_dir.path())
-            .env("QONTROL_CACHE_DIR", self.temp_dir.path().join("cache"));
+            .env("QONTROL_CACHE_DIR", self.temp_dir.path().join("cache"))
+            .env("QONTROL_DATA_DIR", self.temp_dir.path().join("data"));
         cmd
     }
 }
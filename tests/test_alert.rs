@@ -0,0 +1,214 @@
+mod harness;
+
+use predicates::prelude::*;
+
+fn read_history(mts: &harness::MultiTestServer) -> Vec<serde_json::Value> {
+    let path = mts.temp_dir.path().join("data").join("alert-history.jsonl");
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+/// Test: fleet status logs alerts when they first fire, not on every poll.
+#[tokio::test]
+async fn test_status_records_fired_alerts_once() {
+    let mts = harness::MultiTestServer::start(&["healthy", "broken"]).await;
+    mts.mount_cluster_fixtures("healthy").await;
+
+    for _ in 0..2 {
+        mts.command()
+            .args(["fleet", "status", "--json", "--no-cache"])
+            .assert()
+            .success();
+    }
+
+    let history = read_history(&mts);
+    let broken: Vec<_> = history
+        .iter()
+        .filter(|t| t["cluster"] == "broken")
+        .collect();
+    assert_eq!(broken.len(), 1, "got: {:?}", history);
+    assert_eq!(broken[0]["transition"], "fired");
+    assert_eq!(broken[0]["category"], "connectivity");
+    assert_eq!(broken[0]["severity"], "critical");
+}
+
+/// Test: an alert that clears on a reachable cluster is logged as resolved.
+#[tokio::test]
+async fn test_status_records_resolved_alerts() {
+    let mts = harness::MultiTestServer::start(&["healthy"]).await;
+    mts.mount_cluster_fixtures("healthy").await;
+    let cluster_name = {
+        let output = mts
+            .command()
+            .args(["fleet", "status", "--json", "--no-cache"])
+            .output()
+            .expect("failed to execute");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["clusters"][0]["cluster_name"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let stale = serde_json::json!({
+        "timestamp": "2026-01-01T00:00:00Z",
+        "transition": "fired",
+        "severity": "warning",
+        "cluster": cluster_name,
+        "category": "disk_unhealthy",
+        "message": "1 disk(s) unhealthy",
+    });
+    let path = mts.temp_dir.path().join("data").join("alert-history.jsonl");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut contents = std::fs::read_to_string(&path).unwrap_or_default();
+    contents.push_str(&format!("{}\n", stale));
+    std::fs::write(&path, contents).unwrap();
+
+    mts.command()
+        .args(["fleet", "status", "--json", "--no-cache"])
+        .assert()
+        .success();
+
+    let history = read_history(&mts);
+    let last = history.last().unwrap();
+    assert_eq!(last["transition"], "resolved");
+    assert_eq!(last["category"], "disk_unhealthy");
+}
+
+#[tokio::test]
+async fn test_alert_history_filters() {
+    let mts = harness::MultiTestServer::start(&["healthy", "broken"]).await;
+    mts.mount_cluster_fixtures("healthy").await;
+    let data = mts.temp_dir.path().join("data");
+    std::fs::create_dir_all(&data).unwrap();
+    std::fs::write(
+        data.join("alert-history.jsonl"),
+        concat!(
+            r#"{"timestamp":"2020-01-01T00:00:00Z","transition":"fired","severity":"warning","cluster":"old","category":"capacity","message":"capacity at 91%"}"#,
+            "\n"
+        ),
+    )
+    .unwrap();
+    mts.command()
+        .args(["fleet", "status", "--json", "--no-cache"])
+        .assert()
+        .success();
+
+    mts.command()
+        .args(["alert", "history", "--cluster", "broken"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("fired"))
+        .stdout(predicate::str::contains("connectivity"))
+        .stdout(predicate::str::contains("old").not());
+
+    let output = mts
+        .command()
+        .args(["alert", "history", "--since", "7d", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = json.as_array().unwrap();
+    assert!(!entries.is_empty());
+    assert!(entries.iter().all(|t| t["cluster"] != "old"));
+
+    mts.command()
+        .args(["alert", "history", "--since", "soon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid duration"));
+}
+
+/// Test: an acknowledged alert moves to `acknowledged_alerts`, stays firing
+/// in the history, and no longer counts toward `--check`.
+#[tokio::test]
+async fn test_alert_ack_silences_status_check() {
+    let mts = harness::MultiTestServer::start(&["healthy", "broken"]).await;
+    mts.mount_cluster_fixtures("healthy").await;
+
+    mts.command()
+        .args(["fleet", "status", "--check", "--no-cache"])
+        .assert()
+        .code(3);
+
+    mts.command()
+        .args(["alerts", "ack", "broken", "connectivity", "--until", "2h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Acknowledged connectivity alerts for 'broken'",
+        ));
+    mts.command()
+        .args(["alert", "acks"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("broken"));
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--check", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert_eq!(output.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["alerts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|a| a["cluster"] != "broken"));
+    assert_eq!(json["acknowledged_alerts"][0]["cluster"], "broken");
+    let history = read_history(&mts);
+    assert_eq!(
+        history.len(),
+        1,
+        "ack must not resolve the alert: {:?}",
+        history
+    );
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Acknowledged:"));
+
+    mts.command()
+        .args(["alert", "unack", "broken", "connectivity"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed the ack"));
+    mts.command()
+        .args(["fleet", "status", "--check", "--no-cache"])
+        .assert()
+        .code(3);
+}
+
+/// Test: alert categories lists each category the engine emits.
+#[tokio::test]
+async fn test_alert_categories() {
+    let mts = harness::MultiTestServer::start(&["healthy"]).await;
+
+    mts.command()
+        .args(["alert", "categories"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("disk_wear"))
+        .stdout(predicate::str::contains("80% of rated endurance"));
+
+    let output = mts
+        .command()
+        .args(["alert", "categories", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let node_offline = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["category"] == "node_offline")
+        .unwrap();
+    assert_eq!(node_offline["severity"], "critical");
+}
@@ -0,0 +1,108 @@
+mod harness;
+
+use predicates::prelude::*;
+
+/// Test: adding nodes pushes days-to-full out, sized from the cluster's own nodes.
+#[tokio::test]
+async fn test_capacity_what_if_add_nodes() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+
+    let output = mts
+        .command()
+        .args(["capacity", "what-if", "--cluster", "gravytrain"])
+        .args(["--add-nodes", "2", "--model", "C192T", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let total = json["total_bytes"].as_u64().unwrap();
+    // Five nodes today, so two more add two fifths
+    assert_eq!(json["added_bytes"].as_u64().unwrap(), total / 5 * 2);
+    let before = json["days_until_full"].as_u64().unwrap();
+    let after = json["new_days_until_full"].as_u64().unwrap();
+    assert!(after > before * 10, "{} -> {}", before, after);
+    assert!(json["new_used_pct"].as_f64().unwrap() < json["used_pct"].as_f64().unwrap());
+
+    mts.command()
+        .args(["capacity", "what-if", "--cluster", "gravytrain"])
+        .args(["--add-usable", "200TB"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("What-if for gravytrain"))
+        .stdout(predicate::str::contains("Adding:   181.9 TB usable"))
+        .stdout(predicate::str::contains(" days → "));
+}
+
+/// Test: a model the cluster doesn't have can't be sized.
+#[tokio::test]
+async fn test_capacity_what_if_unknown_model() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+
+    mts.command()
+        .args(["capacity", "what-if", "--cluster", "gravytrain"])
+        .args(["--add-nodes", "1", "--model", "C432T"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no C432T nodes"));
+}
+
+/// Test: capacity history as a chart with the fill date, and as CSV and JSON rows.
+#[tokio::test]
+async fn test_analytics_capacity() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+
+    let output = mts
+        .command()
+        .args(["analytics", "capacity", "--cluster", "gravytrain", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let points = json["points"].as_array().unwrap();
+    assert!(points.len() >= 7);
+    assert!(points[0]["used_bytes"].as_u64().unwrap() > 0);
+    let fill_date = json["projection"]["fill_date"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    mts.command()
+        .args([
+            "analytics",
+            "capacity",
+            "--cluster",
+            "gravytrain",
+            "--chart",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(" │"))
+        .stdout(predicate::str::contains(format!("→ full ~{}", fill_date)))
+        .stdout(predicate::str::contains("Projected full:"));
+
+    let output = mts
+        .command()
+        .args(["analytics", "capacity", "--cluster", "gravytrain"])
+        .args(["--output", "csv"])
+        .output()
+        .expect("failed to execute");
+    let csv = String::from_utf8_lossy(&output.stdout);
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().contains("used_bytes"));
+    assert_eq!(lines.count(), points.len());
+}
@@ -28,7 +28,8 @@ async fn test_backfill_writes_uuid_to_config() {
     let json: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
     let clusters = json["clusters"].as_array().expect("clusters array");
     assert_eq!(
-        clusters[0]["cluster_uuid"], "a1b2c3d4-e5f6-7890-abcd-ef1234567890"
+        clusters[0]["cluster_uuid"],
+        "a1b2c3d4-e5f6-7890-abcd-ef1234567890"
     );
 }
 
@@ -87,8 +88,7 @@ base_url = "http://127.0.0.1:{port}"
 cluster_uuid = "pre-existing-uuid-1234"
 "#
     );
-    std::fs::write(mts.temp_dir.path().join("config.toml"), &config_content)
-        .expect("write config");
+    std::fs::write(mts.temp_dir.path().join("config.toml"), &config_content).expect("write config");
 
     // Mount cluster fixtures but NOT node_state — if backfill tried to fetch, it'd get 404
     mts.mount_cluster_fixtures("alpha").await;
@@ -0,0 +1,120 @@
+mod harness;
+
+use predicates::prelude::*;
+
+fn history_path(server: &harness::TestServer) -> std::path::PathBuf {
+    server
+        .temp_dir
+        .path()
+        .join("data")
+        .join("client-history.jsonl")
+}
+
+fn write_history(server: &harness::TestServer, samples: &[serde_json::Value]) {
+    let path = history_path(server);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let lines: String = samples.iter().map(|s| format!("{}\n", s)).collect();
+    std::fs::write(path, lines).unwrap();
+}
+
+/// Test: `connections record` appends who is connected and who is active.
+#[tokio::test]
+async fn test_connections_record_appends_sample() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let server = harness::TestServer::start().await;
+    server.mount_fixture("network_connections").await;
+    Mock::given(method("GET"))
+        .and(path("/v1/analytics/activity/current"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "entries": [
+                {"type": "file-throughput-read", "ip": "10.102.0.74", "id": "3", "rate": 8192.0},
+                {"type": "file-iops-read", "ip": "10.120.151.96", "id": "3", "rate": 0.0}
+            ]
+        })))
+        .mount(&server.mock_server)
+        .await;
+
+    for _ in 0..2 {
+        server
+            .command()
+            .args(["connections", "record"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 active, for test"));
+    }
+
+    let history = std::fs::read_to_string(history_path(&server)).unwrap();
+    let samples: Vec<serde_json::Value> = history
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0]["cluster"], "test");
+    assert_eq!(samples[0]["connected"]["10.102.0.74"], 10);
+    assert_eq!(samples[0]["active"], serde_json::json!(["10.102.0.74"]));
+}
+
+/// Test: idle-report lists clients connected without activity past the threshold.
+#[tokio::test]
+async fn test_connections_idle_report() {
+    let server = harness::TestServer::start().await;
+    let now = chrono::Utc::now();
+    let ago = |days: i64| (now - chrono::Duration::days(days)).to_rfc3339();
+    write_history(
+        &server,
+        &[
+            serde_json::json!({"timestamp": ago(10), "cluster": "test",
+                "connected": {"10.0.0.1": 2, "10.0.0.2": 1}, "active": ["10.0.0.2"]}),
+            serde_json::json!({"timestamp": ago(10), "cluster": "other",
+                "connected": {"10.9.9.9": 1}, "active": []}),
+            serde_json::json!({"timestamp": ago(1), "cluster": "test",
+                "connected": {"10.0.0.1": 2, "10.0.0.2": 1}, "active": ["10.0.0.2"]}),
+        ],
+    );
+
+    server
+        .command()
+        .args(["connections", "idle-report", "--threshold", "7d"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10.0.0.1"))
+        .stdout(predicate::str::contains("10.0.0.2").not())
+        .stdout(predicate::str::contains("10.9.9.9").not());
+
+    let output = server
+        .command()
+        .args(["connections", "idle-report", "--threshold", "12d", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json, serde_json::json!([]));
+    assert!(String::from_utf8_lossy(&output.stderr).is_empty());
+
+    server
+        .command()
+        .args(["connections", "idle-report", "--threshold", "12d"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No idle clients."))
+        .stderr(predicate::str::contains(
+            "oldest sample for test is from 10d ago",
+        ));
+}
+
+/// Test: idle-report without any recorded samples says how to build the history.
+#[tokio::test]
+async fn test_connections_idle_report_without_history() {
+    let server = harness::TestServer::start().await;
+
+    server
+        .command()
+        .args(["connections", "idle-report"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no connection history for test: run `qontrol connections record`",
+        ));
+}
@@ -0,0 +1,93 @@
+mod harness;
+
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn test_dev_record_fixtures_fs() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "cluster_settings",
+        "cluster_nodes",
+        "fs_entries_root",
+        "fs_attributes_root",
+        "quotas_list",
+    ])
+    .await;
+    let out = ts.temp_dir.path().join("fixtures");
+
+    ts.command()
+        .args([
+            "dev",
+            "record-fixtures",
+            "--set",
+            "fs",
+            "--name",
+            "lab",
+            "--out",
+        ])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Recorded 3 fixtures"))
+        .stdout(predicate::str::contains(
+            "skipped: fs_recursive_aggregates_root",
+        ));
+
+    let dir = out.join("fs").join("lab");
+    let entries = std::fs::read_to_string(dir.join("fs_entries_root.json")).unwrap();
+    let recorded: serde_json::Value = serde_json::from_str(&entries).unwrap();
+    let original: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("tests/fixtures/fs_entries_root.json").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(recorded["files"][0]["name"], original["files"][0]["name"]);
+    assert!(dir.join("quotas_list.json").exists());
+
+    // Recording over an existing set needs --force
+    ts.command()
+        .args([
+            "dev",
+            "record-fixtures",
+            "--set",
+            "fs",
+            "--name",
+            "lab",
+            "--out",
+        ])
+        .arg(&out)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+}
+
+#[tokio::test]
+async fn test_dev_record_fixtures_scrubs_names() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["cluster_settings", "cluster_nodes", "version", "node_state"])
+        .await;
+    let out = ts.temp_dir.path().join("fixtures");
+
+    ts.command()
+        .args(["dev", "record-fixtures", "--set", "status", "--out"])
+        .arg(&out)
+        .assert()
+        .success();
+
+    let dir = out.join("status").join("test");
+    let settings = std::fs::read_to_string(dir.join("cluster_settings.json")).unwrap();
+    assert!(settings.contains("fixture-cluster"), "got: {}", settings);
+    let nodes = std::fs::read_to_string(dir.join("cluster_nodes.json")).unwrap();
+    assert!(nodes.contains("00000000-0000-4000-8000-"), "got: {}", nodes);
+    assert!(!dir.join("network_status.json").exists());
+}
+
+#[tokio::test]
+async fn test_dev_record_fixtures_unknown_set() {
+    let ts = harness::TestServer::start().await;
+
+    ts.command()
+        .args(["dev", "record-fixtures", "--set", "quota"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown fixture set 'quota'"));
+}
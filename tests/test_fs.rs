@@ -96,6 +96,46 @@ async fn test_fs_ls_limit() {
         .stdout(predicate::str::contains("home"));
 }
 
+#[tokio::test]
+async fn test_fs_ls_limit_stops_paging() {
+    let ts = harness::TestServer::start().await;
+    // Page 1 has a next cursor; a limit it satisfies must not follow it
+    ts.mount_fixture_without_query(
+        "fs_entries_root_page1",
+        "GET",
+        "/v1/files/%2F/entries/",
+        "after",
+    )
+    .await;
+
+    ts.command()
+        .args(["fs", "ls", "/", "--limit", "2", "--json"])
+        .assert()
+        .success();
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url.query(), Some("limit=2"));
+}
+
+#[tokio::test]
+async fn test_fs_ls_sort_keys() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_entries_root").await;
+
+    ts.command()
+        .args(["fs", "ls", "/", "--sort", "ctime,name", "-r"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("home"));
+
+    ts.command()
+        .args(["fs", "ls", "/", "--sort", "size,colour"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown sort key 'colour'"));
+}
+
 #[tokio::test]
 async fn test_fs_ls_long() {
     let ts = harness::TestServer::start().await;
@@ -113,6 +153,41 @@ async fn test_fs_ls_long() {
         .stdout(predicate::str::contains("DIR"));
 }
 
+#[tokio::test]
+async fn test_fs_ls_long_resolves_owners() {
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_entries_root").await;
+    Mock::given(method("POST"))
+        .and(path("/v1/identity/find"))
+        .and(body_json(serde_json::json!({"auth_id": "500"})))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"auth_id": "500", "name": "alice"})),
+        )
+        .expect(1)
+        .mount(&ts.mock_server)
+        .await;
+
+    // The second listing is served from the identity cache
+    for _ in 0..2 {
+        ts.command()
+            .args(["fs", "ls", "/", "--long"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("alice"));
+    }
+
+    ts.command()
+        .args(["fs", "ls", "/", "--long", "--numeric-ids"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("500"))
+        .stdout(predicate::str::contains("alice").not());
+}
+
 #[tokio::test]
 async fn test_fs_ls_json() {
     let ts = harness::TestServer::start().await;
@@ -170,6 +245,27 @@ async fn test_fs_stat_json() {
     assert!(json.get("size").is_some());
     assert!(json.get("mode").is_some());
     assert_eq!(json["type"], "FS_FILE_TYPE_DIRECTORY");
+    assert_eq!(json["path"], "/");
+
+    // Attributes outside the curated set are only under `raw`
+    assert!(json.get("datablocks").is_none());
+    assert!(json["raw"].get("datablocks").is_some());
+    assert_eq!(json["raw"]["type"], "FS_FILE_TYPE_DIRECTORY");
+}
+
+#[tokio::test]
+async fn test_fs_stat_normalizes_path() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_attributes_root").await;
+
+    ts.command()
+        .args(["fs", "stat", "/home/../", "--debug-path"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("File: /"))
+        .stderr(predicate::str::contains(
+            "debug-path: '/home/../' -> / -> /v1/files/%2F/",
+        ));
 }
 
 #[tokio::test]
@@ -189,6 +285,79 @@ async fn test_fs_tree() {
         .stdout(predicate::str::contains("home"));
 }
 
+#[tokio::test]
+async fn test_fs_top() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_recursive_aggregates_root", "fs_attributes_home"])
+        .await;
+
+    let output = ts
+        .command()
+        .args(["--json", "fs", "top", "--limit", "3", "-n"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let dirs = json["directories"].as_array().unwrap();
+    let paths: Vec<&str> = dirs.iter().map(|d| d["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["/home", "/home/dmotles", "/home/dmotles/.nvm"]);
+    assert_eq!(dirs[0]["capacity_bytes"], 1158451200);
+    assert_eq!(dirs[0]["owner"], "500");
+    // No attributes mounted for the others
+    assert_eq!(dirs[1]["owner"], "-");
+
+    // Only the four directories within the default depth of 3 are ranked
+    ts.command()
+        .args(["fs", "top", "--by", "files", "-n"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home/dmotles/.deno"))
+        .stdout(predicate::str::contains("versions").not());
+
+    ts.command()
+        .args(["fs", "top", "--by", "owner"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown --by 'owner'"));
+}
+
+#[tokio::test]
+async fn test_fs_du() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_recursive_aggregates_root", "snapshots_list"])
+        .await;
+    ts.mount_text(
+        "GET",
+        "/v1/snapshots/capacity-used-per-snapshot/",
+        r#"{"entries": [{"id": 1, "capacity_used_bytes": "4096"}]}"#,
+    )
+    .await;
+
+    let output = ts
+        .command()
+        .args(["--json", "fs", "du", "/"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let dirs = json["directories"].as_array().unwrap();
+    let paths: Vec<&str> = dirs.iter().map(|d| d["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["/", "/home"]);
+    assert_eq!(dirs[0]["used_bytes"], 1158455296);
+    assert_eq!(dirs[0]["files"], 9988);
+    // Snapshot 1 was taken of the root
+    assert_eq!(dirs[0]["snapshot_bytes"], 4096);
+    assert_eq!(dirs[1]["snapshot_bytes"], 0);
+
+    ts.command()
+        .args(["fs", "du", "/", "--depth", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.1 GB"))
+        .stdout(predicate::str::contains("4.0 KB"))
+        .stdout(predicate::str::contains("/home/dmotles"));
+}
+
 #[tokio::test]
 async fn test_fs_tree_multi_page() {
     let ts = harness::TestServer::start().await;
@@ -220,3 +389,947 @@ async fn test_fs_tree_multi_page() {
         .stdout(predicate::str::contains("etc"))
         .stdout(predicate::str::contains("var"));
 }
+
+#[tokio::test]
+async fn test_fs_ls_recursive_full_paths() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_root", "fs_entries_home"])
+        .await;
+
+    // Depth 2 lists / and /home; /home/dmotles is printed but not descended
+    ts.command()
+        .args(["fs", "ls", "/", "--recursive", "--max-depth", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home\n"))
+        .stdout(predicate::str::contains("/home/dmotles"));
+}
+
+#[tokio::test]
+async fn test_fs_ls_recursive_depth_one() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_entries_root").await;
+
+    ts.command()
+        .args(["fs", "ls", "/", "-R", "-d", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home"))
+        .stdout(predicate::str::contains("dmotles").not());
+}
+
+#[tokio::test]
+async fn test_fs_ls_recursive_csv() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_root", "fs_entries_home"])
+        .await;
+
+    let output = ts
+        .command()
+        .args(["fs", "ls", "/", "-R", "--max-depth", "2", "--output", "csv"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "path,type,size,owner,group,modified,id");
+    assert!(lines[1].starts_with("/home,FS_FILE_TYPE_DIRECTORY,"));
+    assert!(lines[2].starts_with("/home/dmotles,FS_FILE_TYPE_DIRECTORY,22016,"));
+    assert_eq!(lines.len(), 3);
+}
+
+#[tokio::test]
+async fn test_fs_ls_recursive_json() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_root", "fs_entries_home"])
+        .await;
+
+    let output = ts
+        .command()
+        .args(["fs", "ls", "/", "-R", "--max-depth", "2", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let files = json["files"].as_array().expect("files should be array");
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[1]["path"], "/home/dmotles");
+    assert_eq!(files[1]["depth"], 2);
+}
+
+#[tokio::test]
+async fn test_fs_ls_max_depth_requires_recursive() {
+    let ts = harness::TestServer::start().await;
+
+    ts.command()
+        .args(["fs", "ls", "/", "--max-depth", "2"])
+        .assert()
+        .failure();
+}
+
+#[tokio::test]
+async fn test_fs_wait_exists() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_attributes_home").await;
+
+    ts.command()
+        .args(["fs", "wait", "/home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home: exists"));
+}
+
+#[tokio::test]
+async fn test_fs_wait_absent() {
+    let ts = harness::TestServer::start().await;
+
+    // Unmounted paths return 404 from the mock server
+    let output = ts
+        .command()
+        .args(["fs", "wait", "/incoming/done", "--for", "absent", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(json["condition"], "absent");
+    assert_eq!(json["met"], true);
+}
+
+#[tokio::test]
+async fn test_fs_wait_times_out() {
+    let ts = harness::TestServer::start().await;
+
+    ts.command()
+        .args(["fs", "wait", "/incoming/done", "--wait-timeout", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("timed out after 0s"));
+}
+
+#[tokio::test]
+async fn test_fs_wait_retries_busy_cluster() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let ts = harness::TestServer::start().await;
+    // Busy once, then the path is there
+    Mock::given(method("GET"))
+        .and(path("/v1/files/%2Fhome/info/attributes"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&ts.mock_server)
+        .await;
+    ts.mount_fixture("fs_attributes_home").await;
+
+    ts.command()
+        .args(["fs", "wait", "/home", "--interval", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home: exists"));
+}
+
+#[tokio::test]
+async fn test_fs_wait_busy_cluster_times_out() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_error("GET", "/v1/files/%2Fhome/info/attributes", 429)
+        .await;
+
+    ts.command()
+        .args(["fs", "wait", "/home", "--wait-timeout", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("timed out after 1s"));
+}
+
+#[tokio::test]
+async fn test_fs_wait_fails_on_api_error() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_error("GET", "/v1/files/%2Fhome/info/attributes", 403)
+        .await;
+
+    ts.command()
+        .args(["fs", "wait", "/home", "--wait-timeout", "30"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed to check path: /home"));
+}
+
+#[tokio::test]
+async fn test_fs_wait_size_stable() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_attributes_home").await;
+
+    ts.command()
+        .args([
+            "fs",
+            "wait",
+            "/home",
+            "--for",
+            "size-stable",
+            "--interval",
+            "1",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home: size-stable"));
+}
+
+#[tokio::test]
+async fn test_fs_ls_long_marks_special_types() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_entries_data").await;
+    ts.mount_text("GET", "/v1/files/301/data", "releases").await;
+
+    ts.command()
+        .args(["fs", "ls", "/data", "--long"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("LINK"))
+        .stdout(predicate::str::contains("FIFO"))
+        .stdout(predicate::str::contains("current -> releases"));
+}
+
+#[tokio::test]
+async fn test_fs_ls_recursive_no_follow_by_default() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_data", "fs_entries_data_releases"])
+        .await;
+    ts.mount_text("GET", "/v1/files/301/data", "releases").await;
+
+    ts.command()
+        .args(["fs", "ls", "/data", "-R", "-d", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/data/releases/notes.txt"))
+        .stdout(predicate::str::contains("/data/current/notes.txt").not());
+}
+
+#[tokio::test]
+async fn test_fs_ls_recursive_short_skips_link_targets() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_data", "fs_entries_data_releases"])
+        .await;
+    ts.mount_text("GET", "/v1/files/301/data", "releases").await;
+
+    ts.command()
+        .args(["fs", "ls", "/data", "-R", "-d", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/data/current"))
+        .stdout(predicate::str::contains("->").not());
+
+    // The short listing doesn't show link targets, so none are read
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|r| r.url.path() != "/v1/files/301/data"));
+}
+
+#[tokio::test]
+async fn test_fs_ls_recursive_follow_symlinks() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_data", "fs_entries_data_releases"])
+        .await;
+    ts.mount_text("GET", "/v1/files/301/data", "releases").await;
+
+    ts.command()
+        .args(["fs", "ls", "/data", "-R", "-d", "2", "--follow"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/data/current/notes.txt"))
+        .stdout(predicate::str::contains("/data/releases/notes.txt"));
+}
+
+#[tokio::test]
+async fn test_fs_tree_shows_link_target() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_data", "fs_entries_data_releases"])
+        .await;
+    ts.mount_text("GET", "/v1/files/301/data", "releases").await;
+
+    ts.command()
+        .args(["fs", "tree", "/data", "-d", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("current -> releases"))
+        .stdout(predicate::str::contains("notes.txt"));
+}
+
+#[tokio::test]
+async fn test_fs_xattr_list() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "fs_attributes_home",
+        "fs_user_metadata_home",
+        "fs_streams_home",
+    ])
+    .await;
+
+    ts.command()
+        .args(["fs", "xattr", "/home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("project  apollo"))
+        .stdout(predicate::str::contains("<4 bytes binary>"))
+        .stdout(predicate::str::contains("AFP_AfpInfo"));
+}
+
+#[tokio::test]
+async fn test_fs_xattr_get_value() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_user_metadata_value").await;
+
+    ts.command()
+        .args(["fs", "xattr", "/home", "project"])
+        .assert()
+        .success()
+        .stdout("apollo");
+}
+
+#[tokio::test]
+async fn test_fs_xattr_rejects_unknown_type() {
+    let ts = harness::TestServer::start().await;
+
+    ts.command()
+        .args(["fs", "xattr", "/home", "project", "--type", "nfs"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown metadata type 'nfs'"));
+}
+
+#[tokio::test]
+async fn test_fs_stat_streams() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_attributes_home", "fs_streams_home"])
+        .await;
+
+    ts.command()
+        .args(["fs", "stat", "/home", "--streams"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Streams: 2"))
+        .stdout(predicate::str::contains("Zone.Identifier"));
+
+    // -v only raises log verbosity
+    ts.command()
+        .args(["-v", "fs", "stat", "/home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Streams:").not());
+}
+
+#[tokio::test]
+async fn test_fs_mkdirs_from_file() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_error("POST", "/v1/files/%2F/entries/", 409).await;
+    ts.mount_success_empty("POST", "/v1/files/%2Fprojects/entries/")
+        .await;
+    ts.mount_success_empty("PATCH", "/v1/files/%2Fprojects%2Fa/info/attributes")
+        .await;
+    ts.mount_success_empty("PATCH", "/v1/files/%2Fprojects%2Fb/info/attributes")
+        .await;
+
+    let manifest = ts.temp_dir.path().join("dirs.txt");
+    std::fs::write(
+        &manifest,
+        "# scaffolding\n/projects/b\n/projects\n\n/projects/a/\n",
+    )
+    .unwrap();
+
+    let output = ts
+        .command()
+        .args(["fs", "mkdirs", "--from-file"])
+        .arg(&manifest)
+        .args(["--mode", "755", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(json["created"], 2);
+    assert_eq!(json["existed"], 1);
+    assert_eq!(json["failed"], 0);
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results[0]["path"], "/projects/b");
+    assert_eq!(results[0]["status"], "created");
+    assert_eq!(results[1]["path"], "/projects");
+    assert_eq!(results[1]["status"], "exists");
+
+    // The parent is created before its children, and only new dirs get the mode
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert_eq!(requests[0].url.path(), "/v1/files/%2F/entries/");
+    let patches: Vec<_> = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "PATCH")
+        .collect();
+    assert_eq!(patches.len(), 2);
+    let body: serde_json::Value = serde_json::from_slice(&patches[0].body).unwrap();
+    assert_eq!(body["mode"], "0755");
+}
+
+#[tokio::test]
+async fn test_fs_mkdirs_reports_failures() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_success_empty("POST", "/v1/files/%2F/entries/")
+        .await;
+    ts.mount_error("POST", "/v1/files/%2Fro/entries/", 403)
+        .await;
+
+    let manifest = ts.temp_dir.path().join("dirs.txt");
+    std::fs::write(&manifest, "/ro\n/ro/x\n").unwrap();
+
+    ts.command()
+        .args(["fs", "mkdirs", "--from-file"])
+        .arg(&manifest)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("created  /ro"))
+        .stdout(predicate::str::contains("failed   /ro/x"))
+        .stdout(predicate::str::contains(
+            "1 created, 0 already existed, 1 failed",
+        ))
+        .stderr(predicate::str::contains("1 of 2 directories failed"));
+}
+
+#[tokio::test]
+async fn test_fs_get_recursive() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "fs_attributes_data",
+        "fs_entries_data",
+        "fs_entries_data_releases",
+    ])
+    .await;
+    ts.mount_text("GET", "/v1/files/303/data", "release notes")
+        .await;
+    let out = ts.temp_dir.path().join("out");
+
+    ts.command()
+        .args(["fs", "get", "--recursive", "/data", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Downloaded 1 file (13 B)"))
+        .stdout(predicate::str::contains("0 failed, 2 skipped"));
+
+    let notes = out.join("releases").join("notes.txt");
+    assert_eq!(std::fs::read_to_string(&notes).unwrap(), "release notes");
+    let expected: std::time::SystemTime =
+        chrono::DateTime::parse_from_rfc3339("2026-01-08T16:51:17.886316583Z")
+            .unwrap()
+            .into();
+    let modified = std::fs::metadata(&notes).unwrap().modified().unwrap();
+    let drift = modified
+        .duration_since(expected)
+        .unwrap_or_else(|e| e.duration());
+    assert!(drift.as_millis() < 1, "mtime {:?}", modified);
+    // The symlink and pipe are not recreated
+    assert!(!out.join("current").exists());
+    assert!(!out.join("ingest.pipe").exists());
+}
+
+#[tokio::test]
+async fn test_fs_get_progress_json() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "fs_attributes_data",
+        "fs_entries_data",
+        "fs_entries_data_releases",
+    ])
+    .await;
+    ts.mount_text("GET", "/v1/files/303/data", "release notes")
+        .await;
+    let out = ts.temp_dir.path().join("out");
+
+    let output = ts
+        .command()
+        .args(["--progress", "json", "fs", "get", "--recursive", "/data"])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        // Warnings share stderr with the events
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect(line))
+        .collect();
+    assert_eq!(events.len(), 2, "{:?}", events);
+    assert_eq!(events[0]["phase"], "download");
+    assert_eq!(events[0]["items_done"], 0);
+    assert_eq!(events[1]["items_done"], 1);
+    assert_eq!(events[1]["items_total"], 1);
+    assert_eq!(events[1]["bytes"], 13);
+    assert_eq!(events[1]["item"], "/data/releases/notes.txt");
+
+    ts.command()
+        .args(["--progress", "bar", "fs", "get", "/data", "x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown --progress 'bar'"));
+}
+
+#[tokio::test]
+async fn test_fs_get_directory_requires_recursive() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_attributes_data").await;
+
+    ts.command()
+        .args(["fs", "get", "/data", "out"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --recursive"));
+}
+
+#[tokio::test]
+async fn test_fs_get_reports_failures() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "fs_attributes_data",
+        "fs_entries_data",
+        "fs_entries_data_releases",
+    ])
+    .await;
+    ts.mount_error("GET", "/v1/files/303/data", 500).await;
+    let out = ts.temp_dir.path().join("out");
+
+    let output = ts
+        .command()
+        .args(["fs", "get", "-r", "/data", out.to_str().unwrap(), "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["files"], 0);
+    assert_eq!(json["failed"], 1);
+    assert_eq!(json["failures"][0]["path"], "/data/releases/notes.txt");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 of 1 files failed to download"));
+
+    let leftovers: Vec<_> = std::fs::read_dir(out.join("releases")).unwrap().collect();
+    assert!(leftovers.is_empty(), "partial files left behind");
+}
+
+/// Mount one ranged read of file 303 (notes.txt, 1024 bytes per the
+/// fixture), answered from the version `etag` names
+async fn mount_range(ts: &harness::TestServer, offset: &str, body: Vec<u8>, etag: &str) {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, ResponseTemplate};
+
+    Mock::given(method("GET"))
+        .and(path("/v1/files/303/data"))
+        .and(query_param("offset", offset))
+        .and(query_param("length", "512"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", etag)
+                .set_body_bytes(body),
+        )
+        .mount(&ts.mock_server)
+        .await;
+}
+
+/// Mount notes.txt's attributes, with `etag` as the version's ETag header
+async fn mount_notes_attrs(ts: &harness::TestServer, etag: Option<&str>) {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mut response = ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "path": "/data/releases/notes.txt",
+        "type": "FS_FILE_TYPE_FILE",
+        "id": "303",
+        "size": "1024",
+        "modification_time": "2026-01-08T16:51:17.886316583Z",
+    }));
+    if let Some(etag) = etag {
+        response = response.insert_header("ETag", etag);
+    }
+    Mock::given(method("GET"))
+        .and(path(
+            "/v1/files/%2Fdata%2Freleases%2Fnotes.txt/info/attributes",
+        ))
+        .respond_with(response)
+        .mount(&ts.mock_server)
+        .await;
+}
+
+/// Run `fs get -r /data` into the temp dir, split into two 512-byte ranges
+fn multipart_get(ts: &harness::TestServer) -> assert_cmd::assert::Assert {
+    let out = ts.temp_dir.path().join("out");
+    ts.command()
+        .args(["fs", "get", "-r", "/data", out.to_str().unwrap()])
+        .args(["--jobs", "2", "--multipart-over", "1K"])
+        .assert()
+}
+
+async fn mount_multipart_tree(ts: &harness::TestServer) {
+    ts.mount_fixtures(&[
+        "fs_attributes_data",
+        "fs_entries_data",
+        "fs_entries_data_releases",
+    ])
+    .await;
+}
+
+fn assert_no_leftovers(ts: &harness::TestServer) {
+    let releases = ts.temp_dir.path().join("out").join("releases");
+    let leftovers: Vec<_> = std::fs::read_dir(releases).unwrap().collect();
+    assert!(leftovers.is_empty(), "partial files left behind");
+}
+
+#[tokio::test]
+async fn test_fs_get_multipart() {
+    let ts = harness::TestServer::start().await;
+    mount_multipart_tree(&ts).await;
+    mount_notes_attrs(&ts, Some("\"v1\"")).await;
+    mount_range(&ts, "0", vec![b'a'; 512], "\"v1\"").await;
+    mount_range(&ts, "512", vec![b'b'; 512], "\"v1\"").await;
+
+    multipart_get(&ts)
+        .success()
+        .stdout(predicate::str::contains("Downloaded 1 file (1.0 KiB)"));
+
+    let data = std::fs::read(ts.temp_dir.path().join("out/releases/notes.txt")).unwrap();
+    assert_eq!(data.len(), 1024);
+    assert!(data[..512].iter().all(|&b| b == b'a'));
+    assert!(data[512..].iter().all(|&b| b == b'b'));
+}
+
+#[tokio::test]
+async fn test_fs_get_multipart_short_range_fails() {
+    let ts = harness::TestServer::start().await;
+    mount_multipart_tree(&ts).await;
+    mount_notes_attrs(&ts, Some("\"v1\"")).await;
+    mount_range(&ts, "0", vec![b'a'; 512], "\"v1\"").await;
+    mount_range(&ts, "512", vec![b'b'; 100], "\"v1\"").await;
+
+    multipart_get(&ts)
+        .failure()
+        .stderr(predicate::str::contains(
+            "short read at offset 512: got 100 of 512 bytes",
+        ));
+    assert_no_leftovers(&ts);
+}
+
+/// Test: a range read from a newer version of the source fails the download.
+#[tokio::test]
+async fn test_fs_get_multipart_source_changed_fails() {
+    let ts = harness::TestServer::start().await;
+    mount_multipart_tree(&ts).await;
+    mount_notes_attrs(&ts, Some("\"v1\"")).await;
+    mount_range(&ts, "0", vec![b'a'; 512], "\"v1\"").await;
+    mount_range(&ts, "512", vec![b'b'; 512], "\"v2\"").await;
+
+    multipart_get(&ts).failure().stderr(predicate::str::contains(
+        "/data/releases/notes.txt changed during download (ETag \"v1\" became \"v2\" at offset 512)",
+    ));
+    assert_no_leftovers(&ts);
+}
+
+/// Test: without an ETag to check the ranges against, nothing is split.
+#[tokio::test]
+async fn test_fs_get_multipart_requires_etag() {
+    let ts = harness::TestServer::start().await;
+    mount_multipart_tree(&ts).await;
+    mount_notes_attrs(&ts, None).await;
+    mount_range(&ts, "0", vec![b'a'; 512], "\"v1\"").await;
+    mount_range(&ts, "512", vec![b'b'; 512], "\"v1\"").await;
+
+    multipart_get(&ts)
+        .failure()
+        .stderr(predicate::str::contains("cluster sent no ETag"));
+    assert_no_leftovers(&ts);
+}
+
+#[tokio::test]
+async fn test_fs_grep() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "fs_attributes_data",
+        "fs_entries_data",
+        "fs_entries_data_releases",
+    ])
+    .await;
+    ts.mount_text(
+        "GET",
+        "/v1/files/303/data",
+        "ok\nERROR: disk full\nfine\r\nerror: retrying\n",
+    )
+    .await;
+
+    ts.command()
+        .args(["fs", "grep", "ERROR", "/data"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "/data/releases/notes.txt:2:ERROR: disk full",
+        ))
+        .stdout(predicate::str::contains("retrying").not());
+
+    let output = ts
+        .command()
+        .args(["fs", "grep", "-i", "error", "/data", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let lines: Vec<u64> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, [2, 4]);
+    assert_eq!(json[1]["text"], "error: retrying");
+}
+
+#[tokio::test]
+async fn test_fs_grep_filters_candidates() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&[
+        "fs_attributes_data",
+        "fs_entries_data",
+        "fs_entries_data_releases",
+    ])
+    .await;
+    ts.mount_text("GET", "/v1/files/303/data", "ERROR\n").await;
+
+    // Name and size filters exclude notes.txt (1024 bytes): nothing matches
+    ts.command()
+        .args(["fs", "grep", "ERROR", "/data", "--name", "*.log"])
+        .assert()
+        .code(1)
+        .stdout("");
+    ts.command()
+        .args(["fs", "grep", "ERROR", "/data", "--max-size", "512"])
+        .assert()
+        .code(1);
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|r| r.url.path() != "/v1/files/303/data"));
+
+    ts.command()
+        .args([
+            "fs",
+            "grep",
+            "ERROR",
+            "/data",
+            "--name",
+            "*.txt",
+            "--max-size",
+            "1K",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("notes.txt:1:ERROR"));
+}
+
+#[tokio::test]
+async fn test_fs_api_errors_are_explained() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_error_with_body(
+        "GET",
+        "/v1/files/%2Fmissing/info/attributes",
+        404,
+        r#"{"module": "qfsd", "error_class": "fs_no_such_entry_error", "description": "fs_no_such_entry_error"}"#,
+    )
+    .await;
+
+    ts.command()
+        .args(["fs", "stat", "/missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "/missing: no such file or directory (HTTP 404)",
+        ))
+        .stderr(predicate::str::contains("error_class").not());
+}
+
+async fn mount_media(ts: &harness::TestServer) {
+    ts.mount_fixture("fs_entries_media").await;
+    ts.mount_text("GET", "/v1/files/401/data", "abcdef").await;
+    ts.mount_text("GET", "/v1/files/402/data", "abcdef").await;
+    ts.mount_text("GET", "/v1/files/403/data", "abcxyz").await;
+    ts.mount_text("GET", "/v1/files/404/data", "abc").await;
+    ts.mount_text("GET", "/v1/files/405/data", "").await;
+}
+
+#[tokio::test]
+async fn test_fs_dedupe_report() {
+    let ts = harness::TestServer::start().await;
+    mount_media(&ts).await;
+
+    ts.command()
+        .args(["fs", "dedupe-report", "/media"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2 copies of 6 B (6 B reclaimable)",
+        ))
+        .stdout(predicate::str::contains("  /media/a.bin\n  /media/b.bin\n"))
+        .stdout(predicate::str::contains(
+            "1 duplicate set(s) (2 files), 6 B reclaimable",
+        ));
+
+    let output = ts
+        .command()
+        .args(["fs", "dedupe-report", "/media", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    // The hard link to a.bin is the same file, not a duplicate
+    assert_eq!(json["files_scanned"], 4);
+    assert_eq!(json["reclaimable_bytes"], 6);
+    assert_eq!(json["verified"], true);
+    assert_eq!(
+        json["duplicate_sets"][0]["paths"],
+        serde_json::json!(["/media/a.bin", "/media/b.bin"])
+    );
+
+    // d.txt has a unique size, so it is never downloaded
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|r| r.url.path() != "/v1/files/404/data"));
+}
+
+#[tokio::test]
+async fn test_fs_dedupe_report_min_size() {
+    let ts = harness::TestServer::start().await;
+    mount_media(&ts).await;
+
+    ts.command()
+        .args([
+            "fs",
+            "dedupe-report",
+            "/media",
+            "--min-size",
+            "1K",
+            "--quick",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0 probable duplicate set(s) (0 files), 0 B reclaimable",
+        ));
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| !r.url.path().ends_with("/data")));
+}
+
+#[tokio::test]
+async fn test_fs_manifest_create_and_verify() {
+    let ts = harness::TestServer::start().await;
+    mount_media(&ts).await;
+    let manifest = ts.temp_dir.path().join("manifest.json");
+    let manifest_arg = manifest.to_str().unwrap();
+
+    ts.command()
+        .args(["fs", "manifest", "create", "/media", "--checksum", "--out"])
+        .arg(manifest_arg)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Wrote manifest of 6 file(s) (27 B) under /media",
+        ));
+    let recorded: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&manifest).unwrap()).unwrap();
+    assert_eq!(recorded["root"], "/media");
+    assert_eq!(recorded["checksum"], "sha256");
+    let files = recorded["files"].as_array().unwrap();
+    assert_eq!(files.len(), 6);
+    assert_eq!(files[0]["path"], "a.bin");
+    assert_eq!(files[0]["size"], 6);
+    assert_eq!(files[0]["checksum"], files[1]["checksum"]);
+    assert_eq!(
+        files[0]["checksum"],
+        "bef57ec7f53a6d40beb640a780a639c83bc29ac8a9816f1fc6c5c6dcd93c4721"
+    );
+
+    ts.command()
+        .args(["fs", "manifest", "verify"])
+        .arg(manifest_arg)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0 added, 0 removed, 0 changed, 6 unchanged",
+        ));
+
+    // Simulate a tree that drifted from the manifest
+    let mut edited = recorded.clone();
+    let files = edited["files"].as_array_mut().unwrap();
+    files.retain(|f| f["path"] != "d.txt");
+    files.push(serde_json::json!({"path": "gone.bin", "size": 9, "mtime": null}));
+    for f in files.iter_mut() {
+        if f["path"] == "c.bin" {
+            f["checksum"] = serde_json::json!("0".repeat(64));
+        }
+    }
+    std::fs::write(&manifest, edited.to_string()).unwrap();
+
+    ts.command()
+        .args(["fs", "manifest", "verify"])
+        .arg(manifest_arg)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("+ d.txt"))
+        .stdout(predicate::str::contains("- gone.bin"))
+        .stdout(predicate::str::contains("~ c.bin (checksum)"))
+        .stdout(predicate::str::contains(
+            "1 added, 1 removed, 1 changed, 4 unchanged",
+        ));
+
+    // --quick skips reading contents, so the checksum drift goes unnoticed
+    let output = ts
+        .command()
+        .args(["fs", "manifest", "verify", "--quick", "--json"])
+        .arg(manifest_arg)
+        .output()
+        .expect("failed to execute");
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["checksums_verified"], false);
+    assert_eq!(json["added"], serde_json::json!(["d.txt"]));
+    assert_eq!(json["changed"], serde_json::json!([]));
+}
+
+/// Test: --output ndjson writes one JSON object per entry.
+#[tokio::test]
+async fn test_fs_ls_ndjson() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_entries_root").await;
+
+    let output = ts
+        .command()
+        .args(["fs", "ls", "/", "--output", "ndjson"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let entries: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("each line is JSON"))
+        .collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["name"], "home");
+}
+
+/// Test: --format prints one template line per entry instead of JSON.
+#[tokio::test]
+async fn test_fs_ls_format_template() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("fs_entries_root").await;
+
+    ts.command()
+        .args(["fs", "ls", "/", "--format", "{{.name}}\\t{{.type}}"])
+        .assert()
+        .success()
+        .stdout("home\tFS_FILE_TYPE_DIRECTORY\n");
+}
@@ -0,0 +1,126 @@
+mod harness;
+
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn test_maintenance_start_records_window_in_config() {
+    let mts = harness::MultiTestServer::start(&["music"]).await;
+
+    mts.command()
+        .args([
+            "maintenance",
+            "start",
+            "--cluster",
+            "music",
+            "--duration",
+            "2h",
+            "--reason",
+            "drive swap",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Maintenance window started for 'music'",
+        ));
+
+    let config = std::fs::read_to_string(mts.temp_dir.path().join("config.toml")).unwrap();
+    assert!(
+        config.contains("[profiles.music.maintenance]"),
+        "got: {}",
+        config
+    );
+    assert!(
+        config.contains("reason = \"drive swap\""),
+        "got: {}",
+        config
+    );
+
+    let output = mts
+        .command()
+        .args(["maintenance", "list", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["profile"], "music");
+    assert_eq!(json[0]["state"], "active");
+}
+
+#[tokio::test]
+async fn test_maintenance_end_clears_window() {
+    let mts = harness::MultiTestServer::start(&["music"]).await;
+    mts.append_config(
+        "[profiles.music.maintenance]\nstart = \"2026-01-01T00:00:00Z\"\nend = \"2099-01-01T00:00:00Z\"\n",
+    );
+
+    mts.command()
+        .args(["maintenance", "end", "--cluster", "music"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Maintenance window ended"));
+
+    let config = std::fs::read_to_string(mts.temp_dir.path().join("config.toml")).unwrap();
+    assert!(!config.contains("maintenance"), "got: {}", config);
+}
+
+#[tokio::test]
+async fn test_maintenance_rejects_unknown_profile_and_duration() {
+    let mts = harness::MultiTestServer::start(&["music"]).await;
+
+    mts.command()
+        .args([
+            "maintenance",
+            "start",
+            "--cluster",
+            "nope",
+            "--duration",
+            "2h",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("profile not found: nope"));
+
+    mts.command()
+        .args([
+            "maintenance",
+            "start",
+            "--cluster",
+            "music",
+            "--duration",
+            "soon",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid duration 'soon'"));
+}
+
+/// Test: alerts for a cluster in maintenance are downgraded to info.
+#[tokio::test]
+async fn test_maintenance_downgrades_status_alerts() {
+    let mts = harness::MultiTestServer::start(&["healthy", "broken"]).await;
+    mts.mount_cluster_fixtures("healthy").await;
+    mts.append_config(
+        "[profiles.broken.maintenance]\nstart = \"2026-01-01T00:00:00Z\"\nend = \"2099-01-01T00:00:00Z\"\n",
+    );
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json", "--no-cache"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let alerts: Vec<_> = json["alerts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["cluster"] == "broken")
+        .collect();
+    assert!(!alerts.is_empty());
+    assert!(alerts.iter().all(|a| a["severity"] == "info"));
+    assert!(alerts[0]["message"]
+        .as_str()
+        .unwrap()
+        .ends_with("(maintenance)"));
+}
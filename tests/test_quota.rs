@@ -0,0 +1,99 @@
+mod harness;
+
+use predicates::prelude::*;
+
+const TIB: u64 = 1 << 40;
+
+#[tokio::test]
+async fn test_quota_apply_template_dry_run() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_projects", "quotas_list"])
+        .await;
+
+    ts.command()
+        .args([
+            "quota",
+            "apply-template",
+            "--path-glob",
+            "/projects/*",
+            "--limit",
+            "10T",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+ /projects/alpha  10.0 TB"))
+        .stdout(predicate::str::contains(
+            "~ /projects/beta  5.0 TB → 10.0 TB",
+        ))
+        .stdout(predicate::str::contains("= /projects/gamma  10.0 TB"))
+        .stdout(predicate::str::contains("README").not())
+        .stdout(predicate::str::contains(
+            "3 directories matched: 1 to create, 1 to update, 1 unchanged (dry run)",
+        ));
+
+    // A dry run never writes
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.method.as_str() == "GET"));
+}
+
+#[tokio::test]
+async fn test_quota_apply_template_creates_and_updates() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_entries_projects", "quotas_list"])
+        .await;
+    ts.mount_success_empty("POST", "/v1/files/quotas/").await;
+    ts.mount_success_empty("PUT", "/v1/files/quotas/402").await;
+
+    let output = ts
+        .command()
+        .args([
+            "quota",
+            "apply-template",
+            "--path-glob",
+            "/projects/[ab]*",
+            "--limit",
+            "10T",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["path"], "/projects/alpha");
+    assert_eq!(results[0]["action"], "create");
+    assert_eq!(results[1]["action"], "update");
+    assert_eq!(results[1]["current_limit"], (5 * TIB).to_string());
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    let create = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .expect("quota should be created");
+    let body: serde_json::Value = serde_json::from_slice(&create.body).unwrap();
+    assert_eq!(body["id"], "401");
+    assert_eq!(body["limit"], (10 * TIB).to_string());
+    assert!(requests.iter().any(|r| r.method.as_str() == "PUT"));
+}
+
+#[tokio::test]
+async fn test_quota_apply_template_rejects_bad_limit() {
+    let ts = harness::TestServer::start().await;
+
+    ts.command()
+        .args([
+            "quota",
+            "apply-template",
+            "--path-glob",
+            "/projects/*",
+            "--limit",
+            "ten",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid size 'ten'"));
+}
@@ -0,0 +1,213 @@
+mod harness;
+
+use predicates::prelude::*;
+
+/// Test: capacity forecast ranks a growing cluster ahead of one without history.
+#[tokio::test]
+async fn test_report_capacity_forecast_json_ranked() {
+    let mts = harness::MultiTestServer::start(&["gravytrain", "cluster_a"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args(["report", "capacity-forecast", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON output");
+
+    let rows = json.as_array().expect("rows");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["profile"], "gravytrain");
+    assert!(rows[0]["days_until_full"].as_u64().is_some());
+    assert!(rows[0]["growth_rate_bytes_per_day"].as_f64().unwrap() > 0.0);
+    assert_eq!(rows[1]["profile"], "cluster_a");
+    assert!(rows[1]["days_until_full"].is_null());
+}
+
+/// Test: CSV output has a header and one line per cluster.
+#[tokio::test]
+async fn test_report_capacity_forecast_csv() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+
+    mts.command()
+        .args(["report", "capacity-forecast", "--output", "csv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("profile,cluster,type,"))
+        .stdout(predicate::str::contains("\ngravytrain,"));
+}
+
+/// Test: unreachable clusters are reported rather than failing the whole report.
+#[tokio::test]
+async fn test_report_capacity_forecast_unreachable() {
+    let mts = harness::MultiTestServer::start(&["down"]).await;
+    mts.mount_error("down", "GET", "/v1/cluster/settings", 500)
+        .await;
+
+    mts.command()
+        .args(["report", "capacity-forecast"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("error:"));
+}
+
+/// Test: a --cluster naming no profile fails instead of shrinking the report.
+#[tokio::test]
+async fn test_report_capacity_forecast_unknown_profile() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+
+    mts.command()
+        .args(["report", "capacity-forecast", "--cluster", "gravytrain"])
+        .args(["--cluster", "gravytrian"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("profile not found: gravytrian"));
+
+    // Globs may match nothing
+    mts.command()
+        .args(["report", "capacity-forecast", "--cluster", "gravy*"])
+        .args(["--cluster", "prod-*"])
+        .assert()
+        .success();
+}
+
+/// Test: report generate renders a template with status data into --out.
+#[tokio::test]
+async fn test_report_generate_template_to_file() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let template = mts.temp_dir.path().join("weekly.md.tmpl");
+    std::fs::write(
+        &template,
+        "# Weekly\n{% for c in clusters %}- {{ c.profile }}: {{ c.nodes.online }}/{{ c.nodes.total }} nodes\n{% endfor %}",
+    )
+    .unwrap();
+    let out = mts.temp_dir.path().join("report.md");
+
+    mts.command()
+        .args(["report", "generate", "--template"])
+        .arg(&template)
+        .arg("--out")
+        .arg(&out)
+        .assert()
+        .success();
+
+    let rendered = std::fs::read_to_string(&out).expect("report written");
+    assert!(rendered.starts_with("# Weekly\n- cluster_a: "));
+    assert!(rendered.contains(" nodes"));
+}
+
+/// Test: a template referencing an unknown field fails with a clear error.
+#[tokio::test]
+async fn test_report_generate_unknown_field_fails() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let template = mts.temp_dir.path().join("bad.tmpl");
+    std::fs::write(&template, "{{ aggregates.bogus }}").unwrap();
+
+    mts.command()
+        .args(["report", "generate", "--template"])
+        .arg(&template)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed to render template"));
+}
+
+/// Test: chargeback prices each quota's usage at --rate, biggest first.
+#[tokio::test]
+async fn test_report_chargeback_by_quota_csv() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files/quotas/status/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "quotas": [
+                {"id": "200", "path": "/home/", "limit": "1000000000000", "capacity_usage": "250000000000"},
+                {"id": "402", "path": "/projects/beta/", "limit": "5000000000000", "capacity_usage": "1500000000000"}
+            ],
+            "paging": {"next": ""}
+        })))
+        .mount(&mts.servers[0].1)
+        .await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    mts.command()
+        .args([
+            "report",
+            "chargeback",
+            "--rate",
+            "0.02-per-GB-month",
+            "--output",
+            "csv",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "profile,cluster,path,used_bytes,limit_bytes,cost,error\n\
+             cluster_a,dmotlesai-fs,/projects/beta/,1500000000000,5000000000000,30.00,\n\
+             cluster_a,dmotlesai-fs,/home/,250000000000,1000000000000,5.00,\n",
+        ));
+}
+
+/// Test: --by toplevel-dir bills each directory under the root.
+#[tokio::test]
+async fn test_report_chargeback_by_toplevel_dir_json() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    // Mounted first so it wins over the empty root aggregates fixture
+    Mock::given(method("GET"))
+        .and(path("/v1/files/%2F/aggregates/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "path": "/",
+            "files": [
+                {"name": "home", "type": "FS_FILE_TYPE_DIRECTORY", "capacity_usage": "4096"},
+                {"name": "README", "type": "FS_FILE_TYPE_FILE", "capacity_usage": "512"},
+                {"name": "data", "type": "FS_FILE_TYPE_DIRECTORY", "capacity_usage": "819200"}
+            ]
+        })))
+        .mount(&mts.servers[0].1)
+        .await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args(["report", "chargeback", "--by", "toplevel-dir", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let lines = json.as_array().expect("lines");
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["path"], "/data/");
+    assert_eq!(lines[0]["used_bytes"], 819200);
+    assert!(lines[0]["cost"].is_null());
+    assert_eq!(lines[1]["path"], "/home/");
+}
+
+/// Test: an unknown --by is rejected before any cluster is contacted.
+#[tokio::test]
+async fn test_report_chargeback_unknown_by() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+
+    mts.command()
+        .args(["report", "chargeback", "--by", "user"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected quota, toplevel-dir"));
+}
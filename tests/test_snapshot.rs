@@ -37,6 +37,38 @@ async fn test_snapshot_list_json() {
     assert_eq!(entries[0]["name"], "Test");
 }
 
+#[tokio::test]
+async fn test_snapshot_list_follows_pages() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixture("snapshots_capacity").await;
+    ts.mount_fixture_without_query("snapshots_list_page1", "GET", "/v2/snapshots/", "after")
+        .await;
+    ts.mount_fixture_with_query(
+        "snapshots_list_page2",
+        "GET",
+        "/v2/snapshots/",
+        "after",
+        "1",
+    )
+    .await;
+
+    let output = ts
+        .command()
+        .args(["snapshot", "list", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let names: Vec<&str> = json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Test", "hourly"]);
+}
+
 #[tokio::test]
 async fn test_snapshot_show() {
     let ts = harness::TestServer::start().await;
@@ -102,3 +134,468 @@ async fn test_snapshot_policies_json() {
     assert!(!entries.is_empty());
     assert_eq!(entries[0]["policy_name"], "daily-root");
 }
+
+#[tokio::test]
+async fn test_snapshot_create() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_attributes_home", "snapshot_create"])
+        .await;
+
+    ts.command()
+        .args([
+            "snapshot",
+            "create",
+            "/home",
+            "--name",
+            "pre-upgrade",
+            "--expiration",
+            "7d",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Created snapshot 7 (7_pre-upgrade) of /home",
+        ));
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    let post = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .expect("snapshot POST");
+    let body: serde_json::Value = serde_json::from_slice(&post.body).unwrap();
+    assert_eq!(body["source_file_id"], "94127437418840285971493158915");
+    assert_eq!(body["name_suffix"], "pre-upgrade");
+    let expiration = body["expiration"].as_str().unwrap();
+    assert!(expiration.ends_with('Z'), "{}", expiration);
+}
+
+#[tokio::test]
+async fn test_snapshot_create_never_expires_by_default() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_attributes_home", "snapshot_create"])
+        .await;
+
+    ts.command()
+        .args(["snapshot", "create", "/home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Expiration:  never"));
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    let post = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&post.body).unwrap();
+    assert_eq!(body["expiration"], "");
+    assert!(body.get("name_suffix").is_none());
+}
+
+#[tokio::test]
+async fn test_snapshot_delete() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["snapshot_single", "snapshots_capacity"])
+        .await;
+    ts.mount_success_empty("DELETE", "/v2/snapshots/1").await;
+
+    // Refuses without a terminal or --yes
+    ts.command()
+        .args(["snapshot", "delete", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "refusing to delete snapshot 1 without confirmation; pass --yes to proceed",
+        ));
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.method.as_str() != "DELETE"));
+
+    let output = ts
+        .command()
+        .args(["snapshot", "delete", "1", "--yes", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["deleted"], true);
+    assert_eq!(json["name"], "Test");
+    assert_eq!(json["reclaimable_bytes"], "0");
+}
+
+#[tokio::test]
+async fn test_fleet_snapshot_policy_check_reports_drift() {
+    let mts = harness::MultiTestServer::start(&["music", "archive"]).await;
+    // music has the fixture's single daily policy, keeping 7 days
+    mts.mount_fixture("music", "snapshots_policies").await;
+    mts.mount_raw(
+        "archive",
+        "snapshots_policies",
+        &serde_json::json!({"entries": [{
+            "enabled": true,
+            "id": 1,
+            "policy_name": "daily-root",
+            "schedule": {
+                "creation_schedule": {
+                    "frequency": "SCHEDULE_DAILY_OR_WEEKLY",
+                    "on_days": ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"]
+                },
+                "expiration_time_to_live": "7days"
+            }
+        }]})
+        .to_string(),
+    )
+    .await;
+
+    mts.command()
+        .args(["fleet", "snapshot-policy", "check", "--expect", "daily=7"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ matches baseline"));
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "snapshot-policy",
+            "check",
+            "--expect",
+            "daily=30,weekly=8",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let clusters = json.as_array().unwrap();
+    assert_eq!(clusters.len(), 2);
+    for cluster in clusters {
+        let drift = cluster["drift"].as_array().unwrap();
+        assert_eq!(drift.len(), 2);
+        assert_eq!(drift[0]["schedule"], "daily");
+        assert_eq!(drift[0]["actual"], 7);
+        assert_eq!(drift[0]["problem"], "retention");
+        assert_eq!(drift[1]["problem"], "missing");
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_policy_create() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_attributes_home", "snapshot_policy_create"])
+        .await;
+
+    ts.command()
+        .args([
+            "snapshot",
+            "policy-create",
+            "--name",
+            "nightly-home",
+            "--path",
+            "/home",
+            "--every-day-at",
+            "02:30",
+            "--keep-for",
+            "14d",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Created snapshot policy 2 (nightly-home)",
+        ))
+        .stdout(predicate::str::contains("every day at 02:30 UTC"))
+        .stdout(predicate::str::contains("Keep for:    14days"));
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    let post = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&post.body).unwrap();
+    assert_eq!(body["policy_name"], "nightly-home");
+    assert_eq!(body["source_file_id"], "94127437418840285971493158915");
+    assert_eq!(body["enabled"], true);
+    assert_eq!(body["schedule"]["creation_schedule"]["hour"], 2);
+    assert_eq!(body["schedule"]["expiration_time_to_live"], "14days");
+}
+
+#[tokio::test]
+async fn test_snapshot_policy_create_validates_schedule() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["fs_attributes_home", "snapshot_policy_create"])
+        .await;
+
+    ts.command()
+        .args([
+            "snapshot",
+            "policy-create",
+            "--name",
+            "bad",
+            "--path",
+            "/home",
+            "--every-day-at",
+            "25:00",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid time '25:00'"));
+    ts.command()
+        .args([
+            "snapshot",
+            "policy-create",
+            "--name",
+            "bad",
+            "--path",
+            "/home",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass --every-day-at"));
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests.is_empty());
+}
+
+#[tokio::test]
+async fn test_snapshot_policy_update_and_delete() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["snapshot_policy_single", "snapshot_policy_update"])
+        .await;
+    ts.mount_success_empty("DELETE", "/v2/snapshots/policies/1")
+        .await;
+
+    ts.command()
+        .args([
+            "snapshot",
+            "policy-update",
+            "1",
+            "--keep-for",
+            "4w",
+            "--disable",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Updated snapshot policy 1 (daily-root)",
+        ))
+        .stdout(predicate::str::contains("Keep for:    4weeks"));
+
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    let patch = requests
+        .iter()
+        .find(|r| r.method.as_str() == "PATCH")
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&patch.body).unwrap();
+    assert_eq!(body["enabled"], false);
+    assert_eq!(body["schedule"]["expiration_time_to_live"], "4weeks");
+    // The existing time survives a TTL-only change
+    assert_eq!(body["schedule"]["creation_schedule"]["hour"], 8);
+    assert!(body.get("policy_name").is_none());
+
+    ts.command()
+        .args(["snapshot", "policy-delete", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass --yes to proceed"));
+    ts.command()
+        .args(["snapshot", "policy-delete", "1", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Deleted snapshot policy 1 (daily-root)",
+        ));
+}
+
+/// Test: --output csv writes one flattened row per snapshot.
+#[tokio::test]
+async fn test_snapshot_list_csv() {
+    let ts = harness::TestServer::start().await;
+    ts.mount_fixtures(&["snapshots_list", "snapshots_capacity"])
+        .await;
+
+    let output = ts
+        .command()
+        .args(["snapshot", "list", "--output", "csv"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let header: Vec<&str> = lines.next().expect("header").split(',').collect();
+    assert!(header.contains(&"capacity_used_bytes"));
+    let name = header
+        .iter()
+        .position(|c| *c == "name")
+        .expect("name column");
+    let first: Vec<&str> = lines.next().expect("a row").split(',').collect();
+    assert_eq!(first[name], "Test");
+}
+
+/// Test: attribution splits a snapshot's space by the share of its
+/// modified and deleted entries under the path.
+#[tokio::test]
+async fn test_snapshot_attribution_json() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let ts = harness::TestServer::start().await;
+    let mount = |api_path: &'static str, body: serde_json::Value| {
+        Mock::given(method("GET"))
+            .and(path(api_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+    };
+    mount(
+        "/v2/snapshots/",
+        serde_json::json!({"entries": [
+            {"id": 1, "name": "daily_1", "source_file_id": "2", "in_delete": false},
+            {"id": 2, "name": "daily_2", "source_file_id": "2", "in_delete": false}
+        ]}),
+    )
+    .mount(&ts.mock_server)
+    .await;
+    mount(
+        "/v1/snapshots/capacity-used-per-snapshot/",
+        serde_json::json!({"entries": [
+            {"id": 1, "capacity_used_bytes": "900"},
+            {"id": 2, "capacity_used_bytes": "500"}
+        ]}),
+    )
+    .mount(&ts.mock_server)
+    .await;
+    mount(
+        "/v2/snapshots/2/changes-since/1",
+        serde_json::json!({"entries": [
+            {"op": "MODIFY", "path": "/projects/a.bin"},
+            {"op": "DELETE", "path": "/home/old.iso"},
+            {"op": "CREATE", "path": "/projects/new.bin"},
+            {"op": "DELETE", "path": "/projects/tmp/b.bin"}
+        ], "paging": {"next": ""}}),
+    )
+    .mount(&ts.mock_server)
+    .await;
+
+    let output = ts
+        .command()
+        .args(["snapshot", "attribution", "/projects/", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let snapshots = json["snapshots"].as_array().expect("snapshots");
+    assert_eq!(snapshots[0]["compared_with"], 2);
+    assert_eq!(snapshots[0]["sampled_changes"], 3);
+    assert_eq!(snapshots[0]["changes_under_path"], 2);
+    assert_eq!(snapshots[0]["attributed_bytes"], 600);
+    assert!(snapshots[1]["attributed_bytes"].is_null());
+    assert_eq!(json["attributed_bytes"], 600);
+    assert_eq!(json["unsampled_capacity_bytes"], 500);
+}
+
+/// Test: list filters by name, age and origin, and a filtered delete
+/// previews with --dry-run before deleting every match.
+#[tokio::test]
+async fn test_snapshot_filters_and_bulk_delete() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let ts = harness::TestServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v2/snapshots/"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"entries": [
+                {"id": 1, "name": "adhoc-1", "timestamp": "2020-01-01T00:00:00Z",
+                 "created_by_policy": false, "in_delete": false},
+                {"id": 2, "name": "adhoc-2", "timestamp": "2099-01-01T00:00:00Z",
+                 "created_by_policy": false, "in_delete": false},
+                {"id": 3, "name": "daily", "timestamp": "2020-01-01T00:00:00Z",
+                 "created_by_policy": true, "in_delete": false}
+            ]})),
+        )
+        .mount(&ts.mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/snapshots/capacity-used-per-snapshot/"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"entries": [
+                {"id": 1, "capacity_used_bytes": "1000"},
+                {"id": 2, "capacity_used_bytes": "24"}
+            ]})),
+        )
+        .mount(&ts.mock_server)
+        .await;
+    ts.mount_success_empty("DELETE", "/v2/snapshots/1").await;
+    ts.mount_success_empty("DELETE", "/v2/snapshots/2").await;
+
+    let output = ts
+        .command()
+        .args([
+            "snapshot",
+            "list",
+            "--name-glob",
+            "adhoc-*",
+            "--older-than",
+            "30d",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = json["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["name"], "adhoc-1");
+
+    ts.command()
+        .args(["snapshot", "delete", "--created-by", "manual", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("adhoc-1"))
+        .stdout(predicate::str::contains("adhoc-2"))
+        .stdout(predicate::str::contains("daily").not())
+        .stdout(predicate::str::contains("2 snapshot(s) match"))
+        .stdout(predicate::str::contains("Dry run"));
+    ts.command()
+        .args(["snapshot", "delete", "--created-by", "manual"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("refusing to delete 2 snapshots"));
+    let requests = ts.mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.method.as_str() != "DELETE"));
+
+    let output = ts
+        .command()
+        .args([
+            "snapshot",
+            "delete",
+            "--created-by",
+            "manual",
+            "--yes",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["reclaimable_bytes"], "1024");
+    let deleted: Vec<_> = json["snapshots"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|s| s["deleted"] == true)
+        .map(|s| s["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(deleted, [1, 2]);
+
+    ts.command()
+        .args(["snapshot", "delete"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass a snapshot ID"));
+    ts.command()
+        .args(["snapshot", "delete", "1", "--name-glob", "x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
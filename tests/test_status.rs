@@ -40,6 +40,104 @@ async fn test_status_json_output() {
     assert_eq!(json["aggregates"]["cluster_count"], 1);
 }
 
+/// Test: --progress json reports each cluster as collection finishes.
+#[tokio::test]
+async fn test_status_progress_json() {
+    let mts = harness::MultiTestServer::start(&["cluster_a", "cluster_b"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.mount_cluster_fixtures("cluster_b").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json", "--progress", "json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect(line))
+        .collect();
+    assert_eq!(events.len(), 3, "{:?}", events);
+    assert!(events.iter().all(|e| e["phase"] == "collect"));
+    assert!(events.iter().all(|e| e["items_total"] == 2));
+    assert_eq!(events[2]["items_done"], 2);
+    let mut finished: Vec<&str> = events[1..]
+        .iter()
+        .map(|e| e["item"].as_str().unwrap())
+        .collect();
+    finished.sort();
+    assert_eq!(finished, ["cluster_a", "cluster_b"]);
+}
+
+/// Test: --json-compact prints one line without null fields.
+#[tokio::test]
+async fn test_status_json_compact() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json-compact", "--no-cache"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "got: {}", stdout);
+    assert!(!stdout.contains(":null"), "got: {}", stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON output");
+    assert_eq!(json["aggregates"]["cluster_count"], 1);
+}
+
+/// Test: --json-out --gzip writes a gzip file and nothing to stdout.
+#[tokio::test]
+async fn test_status_json_out_gzip() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    let plain = mts.temp_dir.path().join("status.json");
+    let gz = mts.temp_dir.path().join("status.json.gz");
+
+    for (path, gzip) in [(&plain, false), (&gz, true)] {
+        let mut cmd = mts.command();
+        cmd.args([
+            "fleet",
+            "status",
+            "--json-compact",
+            "--no-cache",
+            "--json-out",
+        ])
+        .arg(path);
+        if gzip {
+            cmd.arg("--gzip");
+        }
+        cmd.assert().success().stdout("");
+    }
+
+    let plain = std::fs::read(&plain).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&plain).expect("invalid JSON file");
+    assert_eq!(json["aggregates"]["cluster_count"], 1);
+
+    let gz = std::fs::read(&gz).unwrap();
+    assert_eq!(&gz[..2], &[0x1f, 0x8b], "gzip magic");
+    // The trailer records the uncompressed size; latencies can differ by a
+    // few digits between the two runs
+    let size = u32::from_le_bytes(gz[gz.len() - 4..].try_into().unwrap());
+    assert!(
+        (size as usize).abs_diff(plain.len()) < 64,
+        "{} vs {}",
+        size,
+        plain.len()
+    );
+    assert!(gz.len() < plain.len());
+
+    mts.command()
+        .args(["fleet", "status", "--gzip"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--json-out"));
+}
+
 /// Test: 1 healthy + 1 unreachable → healthy returned, unreachable flagged as alert.
 #[tokio::test]
 async fn test_status_one_healthy_one_unreachable() {
@@ -69,6 +167,87 @@ async fn test_status_one_healthy_one_unreachable() {
     );
 }
 
+/// Test: --check exits 0 for a healthy fleet and 3 when the only problem is
+/// an unreachable cluster.
+#[tokio::test]
+async fn test_status_check_exit_codes() {
+    let mts = harness::MultiTestServer::start(&["healthy", "broken"]).await;
+    mts.mount_cluster_fixtures("healthy").await;
+
+    mts.command()
+        .args([
+            "fleet",
+            "status",
+            "--check",
+            "--no-cache",
+            "--cluster",
+            "healthy",
+        ])
+        .assert()
+        .code(0)
+        .stdout(predicate::str::contains("Environment Overview"));
+
+    mts.command()
+        .args(["fleet", "status", "--check", "--no-cache", "--json"])
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("\"broken\""));
+
+    mts.command()
+        .args(["fleet", "status", "--check", "--watch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+/// Test: an `[[alert_rules]]` entry raises an alert on a cluster it matches,
+/// and a malformed rule fails the command naming the rule.
+#[tokio::test]
+async fn test_status_custom_alert_rules() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.append_config(
+        r#"[[alert_rules]]
+name = "small cluster"
+when = "nodes.total < 4 and reachable == true"
+
+[[alert_rules]]
+name = "never"
+when = "files.total_snapshots > 1000000"
+severity = "critical"
+"#,
+    );
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json", "--no-cache", "--check"])
+        .output()
+        .expect("failed to execute");
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let rule_alerts: Vec<_> = json["alerts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["category"].as_str().unwrap().starts_with("rule:"))
+        .collect();
+    assert_eq!(rule_alerts.len(), 1, "{:?}", rule_alerts);
+    assert_eq!(rule_alerts[0]["category"], "rule:small cluster");
+    assert_eq!(rule_alerts[0]["severity"], "warning");
+    assert_eq!(rule_alerts[0]["cluster"], "dmotlesai-fs");
+    assert_eq!(
+        rule_alerts[0]["message"],
+        "small cluster: nodes.total is 1 (< 4), reachable is true (== true)"
+    );
+
+    mts.append_config("[[alert_rules]]\nname = \"typo\"\nwhen = \"files.total_snapshots = 5\"\n");
+    mts.command()
+        .args(["fleet", "status", "--no-cache"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid alert rule 'typo'"));
+}
+
 /// Test: --profile filter works — only queries the specified profile.
 #[tokio::test]
 async fn test_status_profile_filter() {
@@ -187,6 +366,86 @@ async fn test_status_capacity_projection_onprem() {
     assert!(projection["growth_rate_bytes_per_day"].as_f64().unwrap() > 0.0);
 }
 
+/// Test: the projection model and window come from the flags, else the
+/// profile, and are recorded in the JSON.
+#[tokio::test]
+async fn test_status_capacity_projection_model() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+    mts.mount_status_fixture(
+        "gravytrain",
+        "gravytrain",
+        "activity_iops_read",
+        "GET",
+        "/v1/analytics/activity/current",
+    )
+    .await;
+    let projection = |args: &[&str]| {
+        let output = mts
+            .command()
+            .args(["fleet", "status", "--json", "--no-cache"])
+            .args(args)
+            .output()
+            .expect("failed to execute");
+        assert!(output.status.success());
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+        json["clusters"][0]["capacity"]["projection"].clone()
+    };
+
+    let default = projection(&[]);
+    assert_eq!(default["model"], "linear");
+    assert_eq!(default["window_days"], 30);
+
+    let smoothed = projection(&["--projection-model", "smoothing"]);
+    assert_eq!(smoothed["model"], "smoothing");
+    assert!(smoothed["growth_rate_bytes_per_day"].as_f64().is_some());
+
+    mts.append_config("[profiles.gravytrain.collect]\nprojection_window = \"7d\"\n");
+    assert_eq!(projection(&[])["window_days"], 7);
+    assert_eq!(
+        projection(&["--projection-window", "90d"])["window_days"],
+        90
+    );
+
+    mts.command()
+        .args(["fleet", "status", "--projection-window", "14d"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown projection window '14d'"));
+}
+
+/// Test: the capacity line carries a sparkline of the history.
+#[tokio::test]
+async fn test_status_capacity_sparkline() {
+    let mts = harness::MultiTestServer::start(&["gravytrain"]).await;
+    mts.mount_cluster_fixtures_with_capacity("gravytrain", "gravytrain")
+        .await;
+    mts.mount_status_fixture(
+        "gravytrain",
+        "gravytrain",
+        "activity_iops_read",
+        "GET",
+        "/v1/analytics/activity/current",
+    )
+    .await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout
+        .lines()
+        .find(|l| l.contains("Capacity:") && l.contains("snaps:"))
+        .expect("cluster capacity line");
+    assert!(line.contains("30d "), "got: {}", line);
+    assert!(line.contains('▁') && line.contains('█'), "got: {}", line);
+}
+
 /// Test: empty capacity history → no projection.
 #[tokio::test]
 async fn test_status_capacity_no_history() {
@@ -381,6 +640,11 @@ async fn test_status_offline_node_alert() {
         .find(|a| a["category"].as_str() == Some("node_offline"));
     assert!(node_alert.is_some(), "should have node_offline alert");
     assert_eq!(node_alert.unwrap()["severity"], "critical");
+
+    mts.command()
+        .args(["fleet", "status", "--check", "--no-cache"])
+        .assert()
+        .code(2);
 }
 
 /// Test: cluster with unhealthy disk → disk_unhealthy alert.
@@ -583,6 +847,277 @@ async fn test_status_partial_health_failure() {
     assert_eq!(clusters[0]["health"]["disks_unhealthy"], 0);
     // Other health data should still be present
     assert!(clusters[0]["health"]["remaining_node_failures"].is_number());
+    // ...and the missing disk data is flagged, not passed off as healthy
+    assert_eq!(clusters[0]["degraded"], serde_json::json!(["disks"]));
+}
+
+/// Test: a flaky endpoint is retried once, then marked degraded with an alert
+#[tokio::test]
+async fn test_status_flaky_endpoint_retried_then_degraded() {
+    let mts = harness::MultiTestServer::start(&["flaky"]).await;
+    for fixture in [
+        "cluster_settings",
+        "version",
+        "cluster_nodes",
+        "filesystem",
+        "analytics_activity",
+        "cluster_chassis",
+        "cluster_protection_status",
+        "cluster_restriper_status",
+    ] {
+        mts.mount_fixture("flaky", fixture).await;
+    }
+    mts.mount_error("flaky", "GET", "/v1/cluster/slots/", 500)
+        .await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json", "--no-cache"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(
+        json["clusters"][0]["degraded"],
+        serde_json::json!(["disks"])
+    );
+    let alerts = json["alerts"].as_array().expect("alerts");
+    assert!(alerts.iter().any(|a| {
+        a["category"] == "degraded_collection"
+            && a["message"] == "incomplete data: could not fetch disks after retry"
+    }));
+
+    let requests = mts.servers[0].1.received_requests().await.unwrap();
+    let slot_calls = requests
+        .iter()
+        .filter(|r| r.url.path() == "/v1/cluster/slots/")
+        .count();
+    assert_eq!(slot_calls, 2, "a 500 is retried exactly once");
+
+    // A fully successful collection reports no degraded groups
+    let healthy = harness::MultiTestServer::start(&["healthy"]).await;
+    healthy.mount_cluster_fixtures("healthy").await;
+    let output = healthy
+        .command()
+        .args(["fleet", "status", "--json", "--no-cache"])
+        .output()
+        .expect("failed to execute");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert!(json["clusters"][0].get("degraded").is_none());
+}
+
+/// Test: --sink delivers the poll's JSON to a file and a webhook while the
+/// table still goes to stdout.
+#[tokio::test]
+async fn test_status_sinks() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    let hook = wiremock::MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&hook)
+        .await;
+    let jsonl = mts.temp_dir.path().join("status.jsonl");
+
+    for _ in 0..2 {
+        mts.command()
+            .args(["fleet", "status", "--no-cache", "--sink"])
+            .arg(format!("file:{}", jsonl.display()))
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("cluster_a"));
+    }
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&jsonl)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("invalid JSON line"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["aggregates"]["cluster_count"], 1);
+
+    let heartbeat = mts.temp_dir.path().join("qontrol.prom");
+    mts.command()
+        .args(["fleet", "status", "--no-cache", "--sink"])
+        .arg(format!("heartbeat:{}", heartbeat.display()))
+        .assert()
+        .success();
+    let metric = std::fs::read_to_string(&heartbeat).unwrap();
+    assert!(
+        metric.contains("\nqontrol_last_successful_poll 1"),
+        "{}",
+        metric
+    );
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache", "--json", "--sink"])
+        .arg(format!("webhook:{}/hook", hook.uri()))
+        .assert()
+        .success();
+    let requests = hook.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["clusters"][0]["profile"], "cluster_a");
+
+    // A sink that can't be reached is only a warning
+    mts.command()
+        .args(["fleet", "status", "--no-cache", "--sink"])
+        .arg("webhook:http://127.0.0.1:9/hook")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("failed to deliver status to sink"));
+
+    mts.command()
+        .args(["fleet", "status", "--sink", "syslog:local0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid --sink 'syslog:local0'"));
+}
+
+/// Test: --notify-test posts a test payload to each [notify] webhook,
+/// retrying a 5xx, and fails when a webhook rejects it.
+#[tokio::test]
+async fn test_status_notify_test() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    let hook = wiremock::MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/flaky"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&hook)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/flaky"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&hook)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/gone"))
+        .respond_with(ResponseTemplate::new(410))
+        .mount(&hook)
+        .await;
+
+    mts.append_config(&format!(
+        "[notify]\nwebhooks = [\"{}/flaky\"]\nretries = 1\n",
+        hook.uri()
+    ));
+    mts.command()
+        .args(["fleet", "status", "--notify-test"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Delivered a test notification"));
+    let requests = hook.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(body["test"], true);
+    assert_eq!(body["events"], serde_json::json!([]));
+
+    let config = mts.temp_dir.path().join("config.toml");
+    let contents = std::fs::read_to_string(&config)
+        .unwrap()
+        .replace("/flaky", "/gone");
+    std::fs::write(&config, contents).unwrap();
+    mts.command()
+        .args(["fleet", "status", "--notify-test"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("answered HTTP 410"))
+        .stderr(predicate::str::contains("1 of 1 webhooks failed"));
+    // A 4xx is not retried
+    assert_eq!(hook.received_requests().await.unwrap().len(), 3);
+}
+
+/// Test: --notify-test sends Slack targets a Block Kit message.
+#[tokio::test]
+async fn test_status_notify_test_slack() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    let slack = wiremock::MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/services/T0/B0/x"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&slack)
+        .await;
+    mts.append_config(&format!(
+        "[notify]\nslack = [\"{}/services/T0/B0/x\"]\n",
+        slack.uri()
+    ));
+
+    mts.command()
+        .args(["fleet", "status", "--notify-test", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"delivered\": true"));
+    let requests = slack.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["text"], "qontrol test notification");
+    assert_eq!(body["blocks"][0]["type"], "section");
+}
+
+/// Test: a cluster over its --cluster-budget skips the endpoints still to
+/// come and is reported partial, without failing the poll.
+#[tokio::test]
+async fn test_status_cluster_budget_marks_partial() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["slow"]).await;
+    Mock::given(method("GET"))
+        .and(path("/v1/file-system"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({
+                    "total_size_bytes": "1000000000000",
+                    "free_size_bytes": "400000000000",
+                    "snapshot_size_bytes": "0"
+                }))
+                .set_delay(std::time::Duration::from_millis(1500)),
+        )
+        .mount(&mts.servers[0].1)
+        .await;
+    mts.mount_cluster_fixtures("slow").await;
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--json",
+            "--no-cache",
+            "--cluster-budget",
+            "1s",
+        ])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let cluster = &json["clusters"][0];
+    assert_eq!(cluster["partial"], true);
+    assert_eq!(cluster["degraded"], serde_json::json!(["capacity_history"]));
+    assert_eq!(cluster["capacity"]["used_bytes"], 600000000000u64);
+    let alerts = json["alerts"].as_array().expect("alerts");
+    assert!(alerts.iter().any(|a| {
+        a["message"] == "incomplete data: collection budget exceeded, missing capacity_history"
+    }));
+
+    let requests = mts.servers[0].1.received_requests().await.unwrap();
+    assert!(!requests
+        .iter()
+        .any(|r| r.url.path().starts_with("/v1/analytics/capacity-history")));
 }
 
 /// Test: cloud cluster (empty PSU array) → no PSU alerts.
@@ -769,36 +1304,142 @@ async fn test_status_json_includes_node_network_details() {
     }
 }
 
-/// Test: network connections 403 doesn't fail the whole cluster.
+/// Test: `[subnets]` labels group each node's connections by client subnet.
 #[tokio::test]
-async fn test_status_network_connections_403_graceful() {
-    let mts = harness::MultiTestServer::start(&["partial"]).await;
-    // Mount core fixtures but error on network connections
-    for fixture in &[
-        "cluster_settings",
-        "version",
-        "cluster_nodes",
-        "filesystem",
-        "analytics_activity",
-        "network_status",
-    ] {
-        mts.mount_fixture("partial", fixture).await;
-    }
-    mts.mount_error("partial", "GET", "/v2/network/connections/", 403)
-        .await;
+async fn test_status_subnet_labels_connections() {
+    let mts = harness::MultiTestServer::start(&["net_cluster"]).await;
+    mts.mount_cluster_fixtures("net_cluster").await;
+    mts.append_config(
+        r#"
+[subnets]
+"10.116.0.0/16" = "render-farm"
+"10.220.0.0/15" = "office"
+"#,
+    );
 
     let output = mts
         .command()
-        .args(["fleet", "status", "--json"])
+        .args(["fleet", "status", "--json", "--no-cache"])
         .output()
         .expect("failed to execute");
-
     assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON output");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
 
-    // Cluster should still be reachable despite connections 403
-    assert_eq!(json["aggregates"]["healthy_count"], 1);
+    let mut totals = std::collections::BTreeMap::new();
+    for detail in json["clusters"][0]["nodes"]["details"].as_array().unwrap() {
+        for (label, count) in detail["subnet_breakdown"].as_object().unwrap() {
+            *totals.entry(label.clone()).or_insert(0) += count.as_u64().unwrap();
+        }
+    }
+    assert_eq!(totals["render-farm"], 95);
+    assert_eq!(totals["office"], 148);
+    assert_eq!(totals["unlabeled"], 116);
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Clients:  office: 148, unlabeled: 116, render-farm: 95",
+        ));
+}
+
+/// Test: connections that report a protocol version are broken down by
+/// protocol+version in JSON details and the text summary.
+#[tokio::test]
+async fn test_status_connections_by_protocol_version() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    // Mounted first so it wins over the plain connections fixture
+    Mock::given(method("GET"))
+        .and(path("/v2/network/connections/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {
+                "id": 1,
+                "connections": [
+                    {"type": "CONNECTION_TYPE_NFS", "network_address": "10.0.0.1", "protocol_version": "NFSV3"},
+                    {"type": "CONNECTION_TYPE_NFS", "network_address": "10.0.0.2", "protocol_version": "NFSV3"},
+                    {"type": "CONNECTION_TYPE_NFS", "network_address": "10.0.0.3", "protocol_version": "NFSV4_1"},
+                    {"type": "CONNECTION_TYPE_SMB", "network_address": "10.0.0.4", "dialect": "SMB3_1_1"}
+                ]
+            }
+        ])))
+        .mount(&mts.servers[0].1)
+        .await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json", "--no-cache"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let node = &json["clusters"][0]["nodes"]["details"][0];
+    assert_eq!(node["connections"], 4);
+    assert_eq!(node["version_breakdown"]["NFSv3"], 2);
+    assert_eq!(node["version_breakdown"]["NFSv4.1"], 1);
+    assert_eq!(node["version_breakdown"]["SMB3"], 1);
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Protocols: NFSv3: 2, NFSv4.1: 1, SMB3: 1",
+        ));
+}
+
+/// Test: a malformed `[subnets]` range is a config error, not a silent miss.
+#[tokio::test]
+async fn test_status_invalid_subnet_rejected() {
+    let mts = harness::MultiTestServer::start(&["net_cluster"]).await;
+    mts.mount_cluster_fixtures("net_cluster").await;
+    mts.append_config("\n[subnets]\n\"10.116.0.0/40\" = \"render-farm\"\n");
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid subnet '10.116.0.0/40' in [subnets]",
+        ));
+}
+
+/// Test: network connections 403 doesn't fail the whole cluster.
+#[tokio::test]
+async fn test_status_network_connections_403_graceful() {
+    let mts = harness::MultiTestServer::start(&["partial"]).await;
+    // Mount core fixtures but error on network connections
+    for fixture in &[
+        "cluster_settings",
+        "version",
+        "cluster_nodes",
+        "filesystem",
+        "analytics_activity",
+        "network_status",
+    ] {
+        mts.mount_fixture("partial", fixture).await;
+    }
+    mts.mount_error("partial", "GET", "/v2/network/connections/", 403)
+        .await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON output");
+
+    // Cluster should still be reachable despite connections 403
+    assert_eq!(json["aggregates"]["healthy_count"], 1);
 }
 
 /// Test: network status 403 doesn't fail the whole cluster.
@@ -1164,6 +1805,46 @@ async fn test_status_watch_mode_shows_footer() {
     );
 }
 
+/// Test: --no-clear never clears the screen and heads each poll with a
+/// timestamp, so every poll stays in scrollback.
+#[tokio::test]
+async fn test_status_watch_no_clear_appends_polls() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--watch",
+            "--interval",
+            "1",
+            "--no-clear",
+        ])
+        .timeout(std::time::Duration::from_secs(4))
+        .output()
+        .expect("failed to execute");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("\x1B[2J"), "should not clear the screen");
+    assert!(
+        stdout.matches("Environment Overview").count() >= 2,
+        "should keep every poll: {}",
+        stdout
+    );
+    assert!(
+        stdout.matches("── 20").count() >= 2,
+        "each poll should have a timestamp header"
+    );
+
+    mts.command()
+        .args(["fleet", "status", "--no-clear"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--watch"));
+}
+
 /// Test: watch mode with JSON output produces valid JSON on each poll.
 #[tokio::test]
 async fn test_status_watch_mode_json_multiple_polls() {
@@ -1784,3 +2465,855 @@ async fn test_status_timing_unreachable_cluster() {
         "timing should include broken cluster (partial timing up to failure)"
     );
 }
+
+/// Test: --output html produces a standalone page with a section per cluster.
+#[tokio::test]
+async fn test_status_html_output() {
+    let mts = harness::MultiTestServer::start(&["cluster_a", "cluster_b"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.mount_cluster_fixtures("cluster_b").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--output", "html"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<!DOCTYPE html>"));
+    assert!(stdout.contains("<style>"));
+    assert_eq!(stdout.matches("<section class=\"cluster\">").count(), 2);
+    assert!(
+        !stdout.contains("\x1b["),
+        "HTML output must not contain ANSI codes"
+    );
+}
+
+/// Test: HTML output is rejected in watch mode.
+#[tokio::test]
+async fn test_status_html_rejects_watch() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+
+    mts.command()
+        .args(["fleet", "status", "--output", "html", "--watch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be combined with --watch"));
+}
+
+/// Test: collect.skip leaves the named endpoint groups out of collection.
+#[tokio::test]
+async fn test_status_collect_skip_omits_endpoints() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.append_config(
+        "[profiles.cluster_a.collect]\nskip = [\"capacity_history\", \"disks\", \"activity\"]\n",
+    );
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--timing", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("get_file_system"), "got: {}", stderr);
+    assert!(!stderr.contains("get_capacity_history"), "got: {}", stderr);
+    assert!(!stderr.contains("get_cluster_slots"), "got: {}", stderr);
+    assert!(!stderr.contains("get_activity"), "got: {}", stderr);
+}
+
+/// Test: unknown collect.skip entries warn but do not fail collection.
+#[tokio::test]
+async fn test_status_collect_skip_unknown_entry_warns() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.append_config("[profiles.cluster_a.collect]\nskip = [\"nodes\"]\n");
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("unknown collect.skip entry"));
+}
+
+/// Test: `--level fast` stops after settings, version, nodes, and file system.
+#[tokio::test]
+async fn test_status_level_fast_skips_optional_endpoints() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--level",
+            "fast",
+            "--timing",
+            "--no-cache",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("get_cluster_nodes"), "got: {}", stderr);
+    assert!(stderr.contains("get_file_system"), "got: {}", stderr);
+    for call in [
+        "get_activity",
+        "get_network_details",
+        "get_capacity_history",
+        "get_cluster_slots",
+        "get_restriper_status",
+    ] {
+        assert!(!stderr.contains(call), "{} in: {}", call, stderr);
+    }
+}
+
+/// Test: a profile's `collect.level` applies unless `--level` overrides it.
+#[tokio::test]
+async fn test_status_level_profile_default_and_override() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.append_config("[profiles.cluster_a.collect]\nlevel = \"fast\"\n");
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--timing", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("get_activity"), "got: {}", stderr);
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--level",
+            "standard",
+            "--timing",
+            "--no-cache",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("get_activity"), "got: {}", stderr);
+}
+
+/// Test: `--level full` adds the largest paths from capacity analytics.
+#[tokio::test]
+async fn test_status_level_full_largest_paths() {
+    use wiremock::matchers::{method, path_regex};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/v1/analytics/capacity-history/\d+/$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "AVAILABLE",
+            "threshold_for_inclusion": "1000",
+            "largest_paths": [
+                {"path": "/media/", "capacity_used": "3000"},
+                {"path": "/home/", "capacity_used": "9000"}
+            ]
+        })))
+        .mount(&mts.servers[0].1)
+        .await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--level", "full", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let paths = &json["clusters"][0]["capacity"]["largest_paths"];
+    assert_eq!(paths[0]["path"], "/home/");
+    assert_eq!(paths[0]["used_bytes"], 9000);
+    assert_eq!(paths[1]["path"], "/media/");
+}
+
+/// Test: an unknown `--level` is rejected.
+#[tokio::test]
+async fn test_status_level_unknown_rejected() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+
+    mts.command()
+        .args(["fleet", "status", "--level", "turbo"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown collection level 'turbo'"));
+}
+
+/// Test: watch polls between full refreshes only re-fetch volatile endpoints.
+#[tokio::test]
+async fn test_status_watch_incremental_polls_skip_slow_endpoints() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--watch",
+            "--interval",
+            "1",
+            "--json",
+            "--no-cache",
+        ])
+        .timeout(std::time::Duration::from_secs(4))
+        .output()
+        .expect("failed to execute");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let polls: Vec<serde_json::Value> = serde_json::Deserializer::from_str(&stdout)
+        .into_iter::<serde_json::Value>()
+        .filter_map(|v| v.ok())
+        .collect();
+    assert!(polls.len() >= 2, "got {} polls", polls.len());
+    // Carried-forward data still renders on incremental polls
+    assert_eq!(
+        polls[1]["clusters"][0]["name"],
+        polls[0]["clusters"][0]["name"]
+    );
+
+    let requests = mts.servers[0].1.received_requests().await.unwrap();
+    let count = |p: &str| requests.iter().filter(|r| r.url.path() == p).count();
+    assert_eq!(count("/v1/file-system"), 1);
+    assert_eq!(count("/v1/cluster/nodes/"), 1);
+    assert!(count("/v1/version") >= 2);
+    assert!(count("/v1/cluster/restriper/status") >= 2);
+}
+
+/// Test: `--full-refresh-every 1` re-collects everything on every poll.
+#[tokio::test]
+async fn test_status_watch_full_refresh_every_poll() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--watch",
+            "--interval",
+            "1",
+            "--json",
+            "--no-cache",
+            "--full-refresh-every",
+            "1",
+        ])
+        .timeout(std::time::Duration::from_secs(4))
+        .output()
+        .expect("failed to execute");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let polls = serde_json::Deserializer::from_str(&stdout)
+        .into_iter::<serde_json::Value>()
+        .filter_map(|v| v.ok())
+        .count();
+    assert!(polls >= 2, "got {} polls", polls);
+
+    let requests = mts.servers[0].1.received_requests().await.unwrap();
+    let fs_calls = requests
+        .iter()
+        .filter(|r| r.url.path() == "/v1/file-system")
+        .count();
+    assert!(fs_calls >= 2, "got {} file-system calls", fs_calls);
+}
+
+/// Test: `--top-activity` ranks paths and clients and resolves file IDs.
+#[tokio::test]
+async fn test_status_top_activity() {
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    let server = &mts.servers[0].1;
+    // Mounted before the cluster fixtures so it wins over the empty activity fixture
+    Mock::given(method("GET"))
+        .and(path("/v1/analytics/activity/current"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "entries": [
+                {"id": "100", "ip": "10.0.0.5", "rate": 50.0, "type": "file-iops-read"},
+                {"id": "200", "ip": "10.0.0.6", "rate": 400.0, "type": "file-iops-write"},
+                {"id": "200", "ip": "10.0.0.6", "rate": 1048576.0, "type": "file-throughput-write"}
+            ]
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/files/resolve"))
+        .and(body_json(serde_json::json!(["200", "100"])))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"id": "200", "path": "/data/render/"},
+            {"id": "100", "path": ""}
+        ])))
+        .mount(server)
+        .await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--top-activity", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let top = &json["clusters"][0]["activity"]["top"];
+    assert_eq!(top["paths"][0]["name"], "/data/render/");
+    assert_eq!(top["paths"][0]["iops"], 400.0);
+    assert_eq!(top["paths"][0]["throughput_bps"], 1048576.0);
+    assert_eq!(top["paths"][1]["name"], "file id 100");
+    assert_eq!(top["clients"][0]["name"], "10.0.0.6");
+
+    mts.command()
+        .args(["fleet", "status", "--top-activity", "--no-cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Top paths"))
+        .stdout(predicate::str::contains("/data/render/"))
+        .stdout(predicate::str::contains("Top clients"));
+}
+
+/// Test: without `--top-activity` the JSON `top` field is null.
+#[tokio::test]
+async fn test_status_top_activity_absent_by_default() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["clusters"][0]["activity"]["top"].is_null());
+}
+
+/// Test: SSDs past `collect.disk_wear_threshold` surface as disk_wear warnings.
+#[tokio::test]
+async fn test_status_disk_wear_threshold() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.append_config("[profiles.cluster_a.collect]\ndisk_wear_threshold = 70\n");
+    Mock::given(method("GET"))
+        .and(path("/v1/health/ssd-endurance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"id": "1.3", "percent_used": 75},
+            {"id": "2.3", "percent_used": 20}
+        ])))
+        .mount(&mts.servers[0].1)
+        .await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let worn = json["clusters"][0]["health"]["worn_disks"]
+        .as_array()
+        .unwrap();
+    assert_eq!(worn.len(), 1);
+    assert_eq!(worn[0]["id"], "1.3");
+    let wear_alerts: Vec<_> = json["alerts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["category"] == "disk_wear")
+        .collect();
+    assert_eq!(wear_alerts.len(), 1);
+    assert_eq!(wear_alerts[0]["severity"], "warning");
+}
+
+/// Test: `collect.aggregates_max_depth` walks recursive aggregates for the
+/// counts and the largest directories.
+#[tokio::test]
+async fn test_status_aggregates_walk() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.mount_fixture("cluster_a", "fs_recursive_aggregates_root")
+        .await;
+    mts.append_config("[profiles.cluster_a.collect]\naggregates_max_depth = 1\n");
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let files = &json["clusters"][0]["files"];
+    assert_eq!(files["total_files"], 9988);
+    assert_eq!(files["total_directories"], 2786);
+    let largest = files["largest_directories"].as_array().unwrap();
+    assert_eq!(largest.len(), 1);
+    assert_eq!(largest[0]["path"], "/home/");
+    assert_eq!(largest[0]["used_bytes"], 1158451200u64);
+
+    // One page covered the whole budget; the root's plain aggregates weren't needed
+    let requests = mts.servers[0].1.received_requests().await.unwrap();
+    let paths: Vec<&str> = requests.iter().map(|r| r.url.path()).collect();
+    assert_eq!(
+        paths
+            .iter()
+            .filter(|p| p.contains("recursive-aggregates"))
+            .count(),
+        1
+    );
+    assert!(!paths.contains(&"/v1/files/%2F/aggregates/"));
+}
+
+/// Test: a cluster whose nodes lost their LDAP connection to the DCs raises
+/// a critical directory_services alert; skipping the endpoint drops it.
+#[tokio::test]
+async fn test_status_directory_services_broken_bind() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    Mock::given(method("GET"))
+        .and(path("/v1/ad/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "JOINED",
+            "domain": "ad.example.com",
+            "dcs": [{"name": "DC1", "address": "10.0.0.5"}],
+            "ldap_connection_states": [
+                {"node_id": 1, "servers": [{"bind_uri": "ldap://10.0.0.5", "status": "DISCONNECTED"}]}
+            ]
+        })))
+        .mount(&mts.servers[0].1)
+        .await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let dir = &json["clusters"][0]["health"]["directory_services"];
+    assert_eq!(dir["domain"], "ad.example.com");
+    assert_eq!(dir["disconnected_nodes"], serde_json::json!([1]));
+    let alerts: Vec<_> = json["alerts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["category"] == "directory_services")
+        .collect();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["severity"], "critical");
+    assert_eq!(
+        alerts[0]["message"],
+        "LDAP connection to ad.example.com down on all nodes"
+    );
+
+    mts.append_config("[profiles.cluster_a.collect]\nskip = [\"directory_services\"]\n");
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--no-cache", "--json"])
+        .output()
+        .expect("failed to execute");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["clusters"][0]["health"]
+        .get("directory_services")
+        .is_none());
+}
+
+/// Test: `--view` applies a saved `[views.<name>]` and flags override it.
+#[tokio::test]
+async fn test_status_saved_view() {
+    let mts = harness::MultiTestServer::start(&["cluster_a", "cluster_b"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.mount_cluster_fixtures("cluster_b").await;
+    mts.append_config("[views.morning]\nprofiles = [\"cluster_b\"]\ncompact = true\n");
+
+    mts.command()
+        .args(["fleet", "status", "--no-cache", "--view", "morning"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("═══ Clusters"))
+        .stdout(predicate::str::contains("cluster_b"))
+        .stdout(predicate::str::contains("cluster_a").not());
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--no-cache",
+            "--json",
+            "--view",
+            "morning",
+            "--cluster",
+            "cluster_a",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let clusters = json["clusters"].as_array().expect("clusters array");
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0]["profile"], "cluster_a");
+
+    mts.command()
+        .args(["fleet", "status", "--view", "evening"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unknown view 'evening' (available: morning)",
+        ));
+}
+
+/// Test: --cluster takes glob patterns as well as profile names.
+#[tokio::test]
+async fn test_status_cluster_glob_filter() {
+    let mts = harness::MultiTestServer::start(&["prod-a", "prod-b", "dev"]).await;
+    for profile in ["prod-a", "prod-b", "dev"] {
+        mts.mount_cluster_fixtures(profile).await;
+    }
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--json",
+            "--no-cache",
+            "--cluster",
+            "prod-*",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["aggregates"]["cluster_count"], 2);
+    let profiles: Vec<&str> = json["clusters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["profile"].as_str().unwrap())
+        .collect();
+    assert!(!profiles.contains(&"dev"), "got: {:?}", profiles);
+}
+
+/// Test: --sort score puts the lowest health score first, and each cluster
+/// carries its score in compact and JSON output.
+#[tokio::test]
+async fn test_status_sort_by_score() {
+    let mts = harness::MultiTestServer::start(&["alpha", "bravo"]).await;
+    mts.mount_cluster_fixtures("alpha").await;
+    // bravo has a node offline, which costs it a critical alert
+    let nodes_with_offline = r#"[
+        {"id": 1, "node_name": "node1", "node_status": "online", "model_number": "C192T", "serial_number": "SN001"},
+        {"id": 2, "node_name": "node2", "node_status": "offline", "model_number": "C192T", "serial_number": "SN002"}
+    ]"#;
+    mts.mount_raw("bravo", "cluster_nodes", nodes_with_offline)
+        .await;
+    // Alerts name the cluster, so bravo needs a name of its own
+    mts.mount_raw("bravo", "cluster_settings", r#"{"cluster_name": "bravo"}"#)
+        .await;
+    mts.mount_cluster_fixtures("bravo").await;
+
+    let output = mts
+        .command()
+        .args([
+            "fleet",
+            "status",
+            "--no-cache",
+            "--compact",
+            "--sort",
+            "score",
+        ])
+        .output()
+        .expect("failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<&str> = stdout.lines().filter(|l| l.contains("score")).collect();
+    assert_eq!(rows.len(), 2, "got: {}", stdout);
+    assert!(rows[0].contains("bravo"), "got: {}", stdout);
+    assert!(
+        rows[1].contains("alpha") && rows[1].contains("score 100"),
+        "got: {}",
+        stdout
+    );
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--no-cache", "--json", "--sort", "score"])
+        .output()
+        .expect("failed to execute");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["clusters"][0]["profile"], "bravo");
+    assert!(json["clusters"][0]["score"].as_u64().unwrap() < 100);
+    assert_eq!(json["clusters"][1]["score"], 100);
+}
+
+/// Test: --output csv writes one row per cluster.
+#[tokio::test]
+async fn test_status_output_csv() {
+    let mts = harness::MultiTestServer::start(&["cluster_a", "cluster_b"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.mount_cluster_fixtures("cluster_b").await;
+
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--output", "csv"])
+        .output()
+        .expect("failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    let header: Vec<&str> = lines[0].split(',').collect();
+    assert!(header.contains(&"capacity.used_pct"));
+    assert!(lines[1].contains("cluster_a"));
+    assert!(lines[2].contains("cluster_b"));
+}
+
+/// Test: --format renders each cluster through the template.
+#[tokio::test]
+async fn test_status_format_template() {
+    let mts = harness::MultiTestServer::start(&["cluster_a", "cluster_b"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.mount_cluster_fixtures("cluster_b").await;
+
+    mts.command()
+        .args([
+            "fleet",
+            "status",
+            "--format",
+            "{{.profile}} reachable={{.reachable}}",
+        ])
+        .assert()
+        .success()
+        .stdout("cluster_a reachable=true\ncluster_b reachable=true\n");
+}
+
+/// Test: --format can't be combined with another --output format.
+#[tokio::test]
+async fn test_status_format_conflicts_with_output() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+
+    mts.command()
+        .args([
+            "fleet",
+            "status",
+            "--format",
+            "{{.profile}}",
+            "--output",
+            "csv",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+/// Test: `daemon --once` caches a poll that `fleet status --cached` then
+/// shows as fresh without contacting the cluster.
+#[tokio::test]
+async fn test_daemon_once_feeds_cached_status() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+
+    mts.command()
+        .args(["fleet", "status", "--cached"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no cached status yet"));
+
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.command()
+        .args(["daemon", "--once"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("polled 1/1 clusters"));
+
+    let requests = mts.servers[0].1.received_requests().await.unwrap().len();
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--cached", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(json["clusters"][0]["profile"], "cluster_a");
+    assert_eq!(json["clusters"][0]["stale"], false);
+    assert_eq!(
+        mts.servers[0].1.received_requests().await.unwrap().len(),
+        requests,
+        "--cached should not contact the cluster"
+    );
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let output = mts
+        .command()
+        .args(["fleet", "status", "--cached", "--max-age", "1s", "--json"])
+        .output()
+        .expect("failed to execute");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(json["clusters"][0]["stale"], true);
+}
+
+/// GET a path from a local `qontrol serve`, returning the status line and body.
+fn http_get(port: u16, path: &str) -> (String, serde_json::Value) {
+    use std::io::{Read, Write};
+
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status_line = head.lines().next().unwrap().to_string();
+    (status_line, serde_json::from_str(body).unwrap())
+}
+
+/// Test: `qontrol serve` answers /status, /clusters/<profile>, and /alerts
+/// from what the daemon cached.
+#[tokio::test]
+async fn test_serve_cached_status() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+    mts.command().args(["daemon", "--once"]).assert().success();
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let dir = mts.temp_dir.path();
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin!("qontrol"))
+        .args(["serve", "--listen", &format!("127.0.0.1:{}", port)])
+        .env("QONTROL_CONFIG_DIR", dir)
+        .env("QONTROL_CACHE_DIR", dir.join("cache"))
+        .env("QONTROL_DATA_DIR", dir.join("data"))
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("failed to start qontrol serve");
+    for _ in 0..50 {
+        if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let (status_line, body) = http_get(port, "/status");
+    assert!(status_line.contains("200"), "got: {}", status_line);
+    assert_eq!(body["aggregates"]["cluster_count"], 1);
+    assert_eq!(body["clusters"][0]["stale"], false);
+
+    let (status_line, body) = http_get(port, "/clusters/cluster_a");
+    assert!(status_line.contains("200"), "got: {}", status_line);
+    assert_eq!(body["profile"], "cluster_a");
+
+    let (status_line, _) = http_get(port, "/clusters/nope");
+    assert!(status_line.contains("404"), "got: {}", status_line);
+
+    let (status_line, body) = http_get(port, "/alerts");
+    assert!(status_line.contains("200"), "got: {}", status_line);
+    assert!(body["alerts"].is_array());
+
+    let (status_line, body) = http_get(port, "/healthz");
+    assert!(status_line.contains("200"), "got: {}", status_line);
+    assert_eq!(body["status"], "ok");
+    assert!(body["qontrol_last_successful_poll"].as_i64().unwrap() > 0);
+
+    server.kill().ok();
+    server.wait().ok();
+}
+
+#[tokio::test]
+async fn test_history_records_each_collection() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    for _ in 0..2 {
+        mts.command()
+            .args(["fleet", "status", "--json"])
+            .assert()
+            .success();
+    }
+
+    let output = mts
+        .command()
+        .args([
+            "history",
+            "show",
+            "--cluster",
+            "cluster_a",
+            "--since",
+            "1h",
+            "--json",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let rows: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["cluster"], "cluster_a");
+    assert!(rows[0]["total"].as_u64().unwrap() > 0);
+
+    mts.command()
+        .args(["history", "show", "--metric", "files"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DIRECTORIES"));
+    mts.command()
+        .args(["history", "show", "--cluster", "cluster_b"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No history recorded"));
+    mts.command()
+        .args(["history", "show", "--metric", "latency"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown metric"));
+}
+
+#[tokio::test]
+async fn test_status_detail() {
+    let mts = harness::MultiTestServer::start(&["cluster_a"]).await;
+    mts.mount_cluster_fixtures("cluster_a").await;
+
+    mts.command()
+        .args(["status", "cluster_a", "--detail"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("═══ Nodes"))
+        .stdout(predicate::str::contains("═══ Protection"))
+        .stdout(predicate::str::contains("═══ Replication"));
+
+    let output = mts
+        .command()
+        .args(["status", "cluster_a", "--detail", "--json"])
+        .output()
+        .expect("failed to execute");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(json["clusters"].as_array().unwrap().len(), 1);
+    assert!(!json["detail"]["nodes"].as_array().unwrap().is_empty());
+}